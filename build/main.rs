@@ -5,6 +5,7 @@ use std::io::Write;
 use std::path::Path;
 
 mod textures;
+mod trace;
 
 fn main() {
     let dest = env::var("OUT_DIR").unwrap();
@@ -12,6 +13,7 @@ fn main() {
 
     textures::build_texture_file(&mut File::create(&dest.join("textures.rs")).unwrap());
     println!("cargo:rerun-if-changed=build/main.rs");
+    println!("cargo:rerun-if-changed=build/trace.rs");
 
     let mut file_output = File::create(&dest.join("gl_bindings.rs")).unwrap();
     generate_gl_bindings(&mut file_output);
@@ -38,6 +40,7 @@ where
             "GL_ARB_depth_texture",
             "GL_ARB_direct_state_access",
             "GL_ARB_draw_buffers",
+            "GL_ARB_draw_buffers_blend",
             "GL_ARB_ES2_compatibility",
             "GL_ARB_ES3_compatibility",
             "GL_ARB_ES3_1_compatibility",
@@ -87,9 +90,12 @@ where
             "GL_EXT_texture_sRGB",
             "GL_EXT_transform_feedback",
             "GL_GREMEDY_string_marker",
+            "GL_KHR_blend_equation_advanced",
+            "GL_KHR_blend_equation_advanced_coherent",
             "GL_KHR_robustness",
             "GL_NVX_gpu_memory_info",
             "GL_NV_conditional_render",
+            "GL_NV_texture_barrier",
             "GL_NV_vertex_attrib_integer_64bit",
         ],
     );
@@ -106,18 +112,24 @@ where
             "GL_ARM_rgba8",
             "GL_EXT_buffer_storage",
             "GL_EXT_disjoint_timer_query",
+            "GL_EXT_draw_buffers_indexed",
             "GL_EXT_multi_draw_indirect",
             "GL_EXT_multisampled_render_to_texture",
             "GL_EXT_occlusion_query_boolean",
             "GL_EXT_primitive_bounding_box",
             "GL_EXT_robustness",
+            "GL_KHR_blend_equation_advanced",
+            "GL_KHR_blend_equation_advanced_coherent",
             "GL_KHR_debug",
             "GL_NV_copy_buffer",
             "GL_NV_framebuffer_multisample",
             "GL_NV_internalformat_sample_query",
             "GL_NV_pixel_buffer_object",
             "GL_OES_depth_texture",
+            "GL_OES_draw_buffers_indexed",
             "GL_OES_draw_elements_base_vertex",
+            "GL_OES_EGL_image",
+            "GL_OES_EGL_image_external",
             "GL_OES_packed_depth_stencil",
             "GL_OES_primitive_bounding_box",
             "GL_OES_rgb8_rgba8",
@@ -128,7 +140,11 @@ where
         ],
     );
 
-    (gl_registry + gles_registry)
-        .write_bindings(gl_generator::StructGenerator, dest)
-        .unwrap();
+    let registry = gl_registry + gles_registry;
+
+    if env::var_os("CARGO_FEATURE_GL_TRACE").is_some() {
+        registry.write_bindings(trace::TracingStructGenerator, dest).unwrap();
+    } else {
+        registry.write_bindings(gl_generator::StructGenerator, dest).unwrap();
+    }
 }
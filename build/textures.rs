@@ -323,6 +323,7 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
 
             use crate::backend::Facade;
             use crate::uniforms::{{UniformValue, AsUniformValue, Sampler, ImageUnit, ImageUnitError, ImageUnitFormat}};
+            use crate::uniforms::{{SamplerObject, TextureWithSampler}};
             use crate::framebuffer;
 
             use crate::Rect;
@@ -457,6 +458,15 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                                 pub fn sampled(&self) -> Sampler<{myname}> {{
                                     Sampler(self, Default::default())
                                 }}
+
+                                /// Combines the texture with a `SamplerObject`, which is used
+                                /// instead of the default sampling parameters of the texture.
+                                #[inline]
+                                pub fn with_sampler<'s>(&'s self, sampler: &'s SamplerObject)
+                                                        -> TextureWithSampler<'s>
+                                {{
+                                    TextureWithSampler::new(UniformValue::{myname}(self, None), sampler)
+                                }}
                             }}
                         ", myname = name)).unwrap();
             },
@@ -823,6 +833,12 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                     let format = TextureFormatRequest::Specific(format);
                     {name}(any::from_id(facade, format, id, owned, mipmap, ty))
                 }}
+
+                /// Gives up the ownership of the texture and returns its id, without deleting it.
+                #[allow(dead_code)]
+                pub(crate) fn into_id(self) -> gl::types::GLuint {{
+                    self.0.into_id()
+                }}
         ", format = relevant_format, name = name)).unwrap();
 
     // writing the 'new_from_fd' function
@@ -0,0 +1,102 @@
+use gl_generator::{Generator, Registry, StructGenerator};
+use std::io;
+use std::io::Write;
+
+/// Maximum number of calls kept by the generated `Gl` struct.
+const TRACE_CAPACITY: usize = 4096;
+
+/// Same bindings as `StructGenerator`, except that the `Gl` struct records each call and its
+/// arguments in a ring buffer before forwarding it.
+///
+/// The bindings generated by `StructGenerator` are put in a private `__raw` module, and
+/// everything except `Gl` is re-exported so that the rest of glium doesn't see a difference.
+pub struct TracingStructGenerator;
+
+impl Generator for TracingStructGenerator {
+    fn write<W>(&self, registry: &Registry, dest: &mut W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        writeln!(dest, "mod __raw {{")?;
+        StructGenerator.write(registry, dest)?;
+        writeln!(dest, "}}")?;
+
+        writeln!(
+            dest,
+            r#"
+            pub use self::__raw::*;
+
+            mod __gl_imports {{
+                pub use std::os::raw;
+            }}
+
+            /// Maximum number of calls kept in `Gl::calls`.
+            pub const TRACE_CAPACITY: usize = {capacity};
+
+            #[allow(non_camel_case_types, non_snake_case, dead_code)]
+            #[derive(Clone)]
+            pub struct Gl {{
+                raw: __raw::Gl,
+                /// The most recent calls, oldest first.
+                pub calls: std::cell::RefCell<std::collections::VecDeque<String>>,
+            }}
+
+            impl std::ops::Deref for Gl {{
+                type Target = __raw::Gl;
+
+                #[inline]
+                fn deref(&self) -> &__raw::Gl {{
+                    &self.raw
+                }}
+            }}
+
+            impl Gl {{
+                pub fn load_with<F>(loadfn: F) -> Gl
+                    where F: FnMut(&'static str) -> *const __gl_imports::raw::c_void
+                {{
+                    Gl {{
+                        raw: __raw::Gl::load_with(loadfn),
+                        calls: std::cell::RefCell::new(
+                            std::collections::VecDeque::with_capacity(TRACE_CAPACITY)),
+                    }}
+                }}
+
+                fn record(&self, call: String) {{
+                    let mut calls = self.calls.borrow_mut();
+                    if calls.len() >= TRACE_CAPACITY {{
+                        calls.pop_front();
+                    }}
+                    calls.push_back(call);
+                }}
+            "#,
+            capacity = TRACE_CAPACITY
+        )?;
+
+        for cmd in &registry.cmds {
+            let params = cmd
+                .params
+                .iter()
+                .map(|p| format!("{}: {}", p.ident, p.ty))
+                .collect::<Vec<_>>();
+            let idents = cmd.params.iter().map(|p| p.ident.clone()).collect::<Vec<_>>();
+            let format = vec!["{:?}"; cmd.params.len()].join(", ");
+
+            writeln!(
+                dest,
+                "#[allow(non_snake_case, unused_variables, dead_code)]
+                #[inline] pub unsafe fn {name}(&self, {params}) -> {ret} {{
+                    self.record(format!(\"gl{name}({format})\"{comma}{idents}));
+                    self.raw.{name}({idents})
+                }}",
+                name = cmd.proto.ident,
+                params = params.join(", "),
+                ret = cmd.proto.ty,
+                format = format,
+                comma = if idents.is_empty() { "" } else { ", " },
+                idents = idents.join(", "),
+            )?;
+        }
+
+        writeln!(dest, "}}")
+    }
+}
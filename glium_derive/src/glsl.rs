@@ -0,0 +1,1019 @@
+//! A small GLSL parser that checks the syntax of a shader.
+//!
+//! It only rejects sources that can't be parsed. Names aren't resolved, so calling a function
+//! that doesn't exist or using the wrong type is only detected when the shader is compiled.
+//! Sources that define macros or use conditional directives are only checked for lexical
+//! errors and unbalanced delimiters, since the macros aren't expanded.
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// Error returned when the source isn't valid GLSL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// Line of the error, starting at 1.
+    pub line: usize,
+    /// Column of the error, starting at 1.
+    pub column: usize,
+    /// Description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "GLSL syntax error at line {}, column {}: {}", self.line, self.column,
+               self.message)
+    }
+}
+
+/// Checks that `source` is syntactically valid GLSL.
+pub fn validate(source: &str) -> Result<(), SyntaxError> {
+    let (tokens, preprocessed) = tokenize(source)?;
+
+    if preprocessed {
+        check_delimiters(&tokens)
+    } else {
+        let mut parser = Parser { tokens: &tokens, position: 0, types: HashSet::new(),
+                                  subroutine: false };
+        parser.translation_unit()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Number,
+    Punct(&'static str),
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+    column: usize,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match &self.kind {
+            TokenKind::Ident(name) => format!("`{}`", name),
+            TokenKind::Number => "a number".to_owned(),
+            TokenKind::Punct(p) => format!("`{}`", p),
+            TokenKind::Eof => "the end of the source".to_owned(),
+        }
+    }
+}
+
+/// Longest punctuators first, so that the first match is the right one.
+const PUNCTUATORS: &[&str] = &[
+    "<<=", ">>=", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "^^", "+=", "-=",
+    "*=", "/=", "%=", "&=", "|=", "^=", "(", ")", "[", "]", "{", "}", ".", ",", ";", ":", "?",
+    "+", "-", "*", "/", "%", "<", ">", "!", "~", "&", "|", "^", "=",
+];
+
+const DIRECTIVES: &[&str] = &[
+    "define", "undef", "if", "ifdef", "ifndef", "else", "elif", "endif", "error", "pragma",
+    "extension", "version", "line",
+];
+
+/// Splits the source into tokens. Also returns true if the source uses macros or conditional
+/// directives.
+fn tokenize(source: &str) -> Result<(Vec<Token>, bool), SyntaxError> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut preprocessed = false;
+
+    let (mut i, mut line, mut column) = (0, 1, 1);
+    // true if only whitespace and comments precede on the current line
+    let mut line_start = true;
+
+    let error = |line, column, message: String| Err(SyntaxError { line, column, message });
+
+    while i < chars.len() {
+        let c = chars[i];
+        let (start_line, start_column) = (line, column);
+
+        // consumes `n` characters of the current line
+        macro_rules! advance {
+            ($n:expr) => {{ i += $n; column += $n; }};
+        }
+
+        if c == '\n' {
+            i += 1;
+            line += 1;
+            column = 1;
+            line_start = true;
+
+        } else if c == '\\' && chars.get(i + 1) == Some(&'\n') {
+            i += 2;
+            line += 1;
+            column = 1;
+
+        } else if c.is_whitespace() {
+            advance!(1);
+
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                advance!(1);
+            }
+
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            advance!(2);
+            loop {
+                if i >= chars.len() {
+                    return error(start_line, start_column, "unterminated comment".to_owned());
+                }
+                if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    advance!(2);
+                    break;
+                }
+                if chars[i] == '\n' {
+                    i += 1;
+                    line += 1;
+                    column = 1;
+                } else {
+                    advance!(1);
+                }
+            }
+
+        } else if c == '#' {
+            if !line_start {
+                return error(line, column, "`#` must start a preprocessor directive".to_owned());
+            }
+
+            let mut directive = String::new();
+            advance!(1);
+            while i < chars.len() && (chars[i] == ' ' || chars[i] == '\t') {
+                advance!(1);
+            }
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                directive.push(chars[i]);
+                advance!(1);
+            }
+
+            if !directive.is_empty() && !DIRECTIVES.contains(&&directive[..]) {
+                return error(start_line, start_column,
+                             format!("unknown preprocessor directive `#{}`", directive));
+            }
+            if directive == "version" && !tokens.is_empty() {
+                return error(start_line, start_column,
+                             "`#version` must come before anything else".to_owned());
+            }
+            if !matches!(&directive[..], "" | "version" | "extension" | "pragma" | "line") {
+                preprocessed = true;
+            }
+
+            // the rest of the directive, including continued lines, is skipped
+            while i < chars.len() && chars[i] != '\n' {
+                if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') {
+                    i += 2;
+                    line += 1;
+                    column = 1;
+                } else {
+                    advance!(1);
+                }
+            }
+
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                name.push(chars[i]);
+                advance!(1);
+            }
+            tokens.push(Token { kind: TokenKind::Ident(name), line: start_line,
+                                column: start_column });
+            line_start = false;
+
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) {
+            let mut number = String::new();
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.' ||
+                  ((chars[i] == '+' || chars[i] == '-') &&
+                   matches!(number.chars().last(), Some('e') | Some('E')) &&
+                   !number.starts_with("0x") && !number.starts_with("0X")))
+            {
+                number.push(chars[i]);
+                advance!(1);
+            }
+            if !is_valid_number(&number) {
+                return error(start_line, start_column, format!("invalid number `{}`", number));
+            }
+            tokens.push(Token { kind: TokenKind::Number, line: start_line, column: start_column });
+            line_start = false;
+
+        } else if let Some(p) = PUNCTUATORS.iter().find(|p| {
+            p.chars().enumerate().all(|(n, pc)| chars.get(i + n) == Some(&pc))
+        }) {
+            advance!(p.len());
+            tokens.push(Token { kind: TokenKind::Punct(p), line: start_line,
+                                column: start_column });
+            line_start = false;
+
+        } else {
+            return error(line, column, format!("unexpected character `{}`", c));
+        }
+    }
+
+    tokens.push(Token { kind: TokenKind::Eof, line, column });
+    Ok((tokens, preprocessed))
+}
+
+/// Returns true if the string is a valid integer or floating-point literal.
+fn is_valid_number(number: &str) -> bool {
+    let lower = number.to_ascii_lowercase();
+
+    if let Some(hex) = lower.strip_prefix("0x") {
+        let hex = hex.strip_suffix('u').unwrap_or(hex);
+        return !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+
+    let is_float = lower.contains('.') || lower.contains('e') || lower.ends_with('f');
+    if !is_float {
+        let digits = lower.strip_suffix('u').unwrap_or(&lower);
+        return !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) &&
+               !(digits.len() > 1 && digits.starts_with('0') && digits.contains(['8', '9']));
+    }
+
+    let body = lower.strip_suffix("lf").or_else(|| lower.strip_suffix('f')).unwrap_or(&lower);
+    let (mantissa, exponent) = match body.split_once('e') {
+        Some((m, e)) => (m, Some(e)),
+        None => (body, None),
+    };
+
+    let mantissa_valid = match mantissa.split_once('.') {
+        Some((int, frac)) => (!int.is_empty() || !frac.is_empty()) &&
+                             int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()),
+        None => !mantissa.is_empty() && mantissa.chars().all(|c| c.is_ascii_digit()),
+    };
+    let exponent_valid = exponent.map_or(true, |e| {
+        let e = e.strip_prefix(['+', '-']).unwrap_or(e);
+        !e.is_empty() && e.chars().all(|c| c.is_ascii_digit())
+    });
+
+    mantissa_valid && exponent_valid
+}
+
+/// Checks that parentheses, brackets and braces are balanced.
+fn check_delimiters(tokens: &[Token]) -> Result<(), SyntaxError> {
+    let mut stack: Vec<&Token> = Vec::new();
+
+    for token in tokens {
+        let closing = match token.kind {
+            TokenKind::Punct("(") | TokenKind::Punct("[") | TokenKind::Punct("{") => {
+                stack.push(token);
+                continue;
+            },
+            TokenKind::Punct(")") => "(",
+            TokenKind::Punct("]") => "[",
+            TokenKind::Punct("}") => "{",
+            _ => continue,
+        };
+
+        match stack.pop() {
+            Some(open) if open.kind == TokenKind::Punct(closing) => (),
+            _ => return Err(SyntaxError { line: token.line, column: token.column,
+                                          message: format!("unexpected {}", token.describe()) }),
+        }
+    }
+
+    match stack.pop() {
+        Some(open) => Err(SyntaxError { line: open.line, column: open.column,
+                                        message: format!("unclosed {}", open.describe()) }),
+        None => Ok(()),
+    }
+}
+
+/// Qualifiers that can precede a declaration.
+const QUALIFIERS: &[&str] = &[
+    "const", "in", "out", "inout", "uniform", "buffer", "shared", "attribute", "varying",
+    "centroid", "flat", "smooth", "noperspective", "patch", "sample", "invariant", "precise",
+    "highp", "mediump", "lowp", "coherent", "volatile", "restrict", "readonly", "writeonly",
+    "subroutine",
+];
+
+/// Words that can't be used as names.
+const KEYWORDS: &[&str] = &[
+    "break", "continue", "do", "for", "while", "switch", "case", "default", "if", "else",
+    "discard", "return", "struct", "true", "false", "layout", "precision",
+];
+
+fn is_builtin_type(name: &str) -> bool {
+    const SCALARS: &[&str] = &["void", "bool", "int", "uint", "float", "double", "atomic_uint",
+                               "sampler"];
+    if SCALARS.contains(&name) {
+        return true;
+    }
+
+    let vector = ["vec", "bvec", "ivec", "uvec", "dvec"].iter()
+        .any(|p| name.strip_prefix(p).is_some_and(|n| matches!(n, "2" | "3" | "4")));
+    let matrix = ["mat", "dmat"].iter().any(|p| name.strip_prefix(p).is_some_and(|n| {
+        matches!(n, "2" | "3" | "4" | "2x2" | "2x3" | "2x4" | "3x2" | "3x3" | "3x4" | "4x2" |
+                    "4x3" | "4x4")
+    }));
+    let opaque = [name, name.strip_prefix(['i', 'u']).unwrap_or(name)].iter().any(|name| {
+        ["sampler", "image", "texture", "subpassInput"].iter()
+            .any(|p| name.strip_prefix(p).is_some_and(|n| n.starts_with(char::is_uppercase) ||
+                                                            n.starts_with(char::is_numeric)))
+    });
+
+    vector || matrix || opaque
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    /// Names of the structs and subroutine types declared so far.
+    types: HashSet<String>,
+    /// True if the last qualifiers contained `subroutine` without a list of types, which
+    /// means that the function being declared is a subroutine type.
+    subroutine: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &'a Token {
+        &self.tokens[self.position]
+    }
+
+    fn peek_at(&self, offset: usize) -> &'a Token {
+        &self.tokens[(self.position + offset).min(self.tokens.len() - 1)]
+    }
+
+    fn bump(&mut self) -> &'a Token {
+        let token = self.peek();
+        if token.kind != TokenKind::Eof {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, token: &Token, message: String) -> Result<T, SyntaxError> {
+        Err(SyntaxError { line: token.line, column: token.column, message })
+    }
+
+    fn is_punct(&self, p: &str) -> bool {
+        matches!(self.peek().kind, TokenKind::Punct(q) if q == p)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Ident(name) if name == word)
+    }
+
+    fn eat_punct(&mut self, p: &str) -> bool {
+        if self.is_punct(p) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, p: &str) -> Result<(), SyntaxError> {
+        if self.eat_punct(p) {
+            Ok(())
+        } else {
+            let token = self.peek();
+            self.error(token, format!("expected `{}`, found {}", p, token.describe()))
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), SyntaxError> {
+        if self.is_word(word) {
+            self.bump();
+            Ok(())
+        } else {
+            let token = self.peek();
+            self.error(token, format!("expected `{}`, found {}", word, token.describe()))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<&'a str, SyntaxError> {
+        let token = self.peek();
+        match &token.kind {
+            TokenKind::Ident(name) if KEYWORDS.contains(&&name[..]) ||
+                                      QUALIFIERS.contains(&&name[..]) ||
+                                      is_builtin_type(name) =>
+            {
+                self.error(token, format!("expected a name, found keyword `{}`", name))
+            },
+            TokenKind::Ident(name) => {
+                self.bump();
+                Ok(name)
+            },
+            _ => self.error(token, format!("expected a name, found {}", token.describe())),
+        }
+    }
+
+    fn is_type_at(&self, offset: usize) -> bool {
+        match &self.peek_at(offset).kind {
+            TokenKind::Ident(name) => is_builtin_type(name) || self.types.contains(name),
+            _ => false,
+        }
+    }
+
+    fn is_qualifier(&self) -> bool {
+        match &self.peek().kind {
+            TokenKind::Ident(name) => QUALIFIERS.contains(&&name[..]) || name == "layout",
+            _ => false,
+        }
+    }
+
+    fn translation_unit(&mut self) -> Result<(), SyntaxError> {
+        while self.peek().kind != TokenKind::Eof {
+            self.external_declaration()?;
+        }
+        Ok(())
+    }
+
+    /// Parses `layout(...)` and the other qualifiers. Returns true if there was any.
+    fn qualifiers(&mut self) -> Result<bool, SyntaxError> {
+        let mut any = false;
+        self.subroutine = false;
+
+        while self.is_qualifier() {
+            any = true;
+
+            if self.is_word("layout") {
+                self.bump();
+                self.expect_punct("(")?;
+                loop {
+                    if !self.is_word("shared") {
+                        self.expect_ident()?;
+                    } else {
+                        self.bump();
+                    }
+                    if self.eat_punct("=") {
+                        self.conditional_expression()?;
+                    }
+                    if !self.eat_punct(",") {
+                        break;
+                    }
+                }
+                self.expect_punct(")")?;
+
+            } else if self.is_word("subroutine") {
+                self.bump();
+                self.subroutine = !self.is_punct("(");
+                // `subroutine(type1, type2)` lists the subroutine types of a function
+                if self.eat_punct("(") {
+                    loop {
+                        self.expect_ident()?;
+                        if !self.eat_punct(",") {
+                            break;
+                        }
+                    }
+                    self.expect_punct(")")?;
+                }
+
+            } else {
+                self.bump();
+            }
+        }
+
+        Ok(any)
+    }
+
+    fn external_declaration(&mut self) -> Result<(), SyntaxError> {
+        if self.eat_punct(";") {
+            return Ok(());
+        }
+
+        if self.is_word("precision") {
+            return self.precision_statement();
+        }
+
+        let qualified = self.qualifiers()?;
+
+        // `layout(local_size_x = 1) in;`
+        if qualified && self.eat_punct(";") {
+            return Ok(());
+        }
+
+        if qualified && !self.is_type_at(0) && !self.is_word("struct") {
+            let name = self.peek();
+            if let TokenKind::Ident(_) = name.kind {
+                // interface block
+                if self.peek_at(1).kind == TokenKind::Punct("{") {
+                    self.bump();
+                    self.struct_body()?;
+                    if !self.is_punct(";") {
+                        self.expect_ident()?;
+                        self.array_specifier()?;
+                    }
+                    return self.expect_punct(";");
+                }
+
+                // `invariant gl_Position;`
+                if matches!(self.peek_at(1).kind, TokenKind::Punct(";") | TokenKind::Punct(",")) {
+                    loop {
+                        self.expect_ident()?;
+                        if !self.eat_punct(",") {
+                            break;
+                        }
+                    }
+                    return self.expect_punct(";");
+                }
+            }
+        }
+
+        self.type_specifier()?;
+
+        if self.eat_punct(";") {
+            return Ok(());
+        }
+
+        // function prototype or definition
+        if self.peek_at(1).kind == TokenKind::Punct("(") {
+            let name = self.expect_ident()?;
+            if self.subroutine {
+                self.types.insert(name.to_owned());
+            }
+            self.parameters()?;
+            if self.eat_punct(";") {
+                return Ok(());
+            }
+            if !self.is_punct("{") {
+                let token = self.peek();
+                return self.error(token, format!("expected `{{` or `;` after the function \
+                                                  parameters, found {}", token.describe()));
+            }
+            return self.compound_statement();
+        }
+
+        self.declarators()?;
+        self.expect_punct(";")
+    }
+
+    fn precision_statement(&mut self) -> Result<(), SyntaxError> {
+        self.expect_word("precision")?;
+        if !(self.is_word("highp") || self.is_word("mediump") || self.is_word("lowp")) {
+            let token = self.peek();
+            return self.error(token, format!("expected a precision qualifier, found {}",
+                                             token.describe()));
+        }
+        self.bump();
+        self.type_specifier()?;
+        self.expect_punct(";")
+    }
+
+    /// Parses a type, which is either a name or a struct definition, followed by the
+    /// optional array size.
+    fn type_specifier(&mut self) -> Result<(), SyntaxError> {
+        if self.is_word("struct") {
+            self.bump();
+            if !self.is_punct("{") {
+                let name = self.expect_ident()?;
+                self.types.insert(name.to_owned());
+            }
+            self.struct_body()?;
+        } else if self.is_type_at(0) {
+            self.bump();
+        } else {
+            let token = self.peek();
+            return match (&token.kind, &self.peek_at(1).kind) {
+                (TokenKind::Ident(name), TokenKind::Ident(_)) if !KEYWORDS.contains(&&name[..]) =>
+                    self.error(token, format!("unknown type `{}`", name)),
+                _ => self.error(token, format!("expected a type, found {}", token.describe())),
+            };
+        }
+
+        self.array_specifier()
+    }
+
+    /// Parses the members of a struct or of an interface block, between braces.
+    fn struct_body(&mut self) -> Result<(), SyntaxError> {
+        self.expect_punct("{")?;
+        loop {
+            self.qualifiers()?;
+            self.type_specifier()?;
+            loop {
+                self.expect_ident()?;
+                self.array_specifier()?;
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+            self.expect_punct(";")?;
+
+            if self.eat_punct("}") {
+                return Ok(());
+            }
+        }
+    }
+
+    fn array_specifier(&mut self) -> Result<(), SyntaxError> {
+        while self.eat_punct("[") {
+            if !self.is_punct("]") {
+                self.conditional_expression()?;
+            }
+            self.expect_punct("]")?;
+        }
+        Ok(())
+    }
+
+    fn parameters(&mut self) -> Result<(), SyntaxError> {
+        self.expect_punct("(")?;
+        if self.eat_punct(")") {
+            return Ok(());
+        }
+        if self.is_word("void") && self.peek_at(1).kind == TokenKind::Punct(")") {
+            self.bump();
+            self.bump();
+            return Ok(());
+        }
+
+        loop {
+            self.qualifiers()?;
+            self.type_specifier()?;
+            if let TokenKind::Ident(_) = self.peek().kind {
+                self.expect_ident()?;
+                self.array_specifier()?;
+            }
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct(")")
+    }
+
+    /// Parses the names declared after a type, with their initializers.
+    fn declarators(&mut self) -> Result<(), SyntaxError> {
+        loop {
+            self.expect_ident()?;
+            self.array_specifier()?;
+            if self.eat_punct("=") {
+                self.initializer()?;
+            }
+            if !self.eat_punct(",") {
+                return Ok(());
+            }
+        }
+    }
+
+    fn initializer(&mut self) -> Result<(), SyntaxError> {
+        if self.eat_punct("{") {
+            loop {
+                self.initializer()?;
+                if !self.eat_punct(",") || self.is_punct("}") {
+                    break;
+                }
+            }
+            self.expect_punct("}")
+        } else {
+            self.assignment_expression()
+        }
+    }
+
+    fn compound_statement(&mut self) -> Result<(), SyntaxError> {
+        self.expect_punct("{")?;
+        while !self.eat_punct("}") {
+            if self.peek().kind == TokenKind::Eof {
+                let token = self.peek();
+                return self.error(token, "expected `}`, found the end of the source".to_owned());
+            }
+            self.statement()?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the statement that starts here is a declaration.
+    fn is_declaration(&self) -> bool {
+        if self.is_qualifier() || self.is_word("struct") || self.is_word("precision") {
+            return true;
+        }
+        if self.is_type_at(0) {
+            // a type followed by `(` is a constructor
+            return self.peek_at(1).kind != TokenKind::Punct("(");
+        }
+        // two names in a row can only be a declaration with an unknown type
+        matches!((&self.peek().kind, &self.peek_at(1).kind),
+                 (TokenKind::Ident(name), TokenKind::Ident(_)) if !KEYWORDS.contains(&&name[..]))
+    }
+
+    fn declaration_statement(&mut self) -> Result<(), SyntaxError> {
+        if self.is_word("precision") {
+            return self.precision_statement();
+        }
+        self.qualifiers()?;
+        self.type_specifier()?;
+        if self.eat_punct(";") {
+            return Ok(());
+        }
+        self.declarators()?;
+        self.expect_punct(";")
+    }
+
+    fn statement(&mut self) -> Result<(), SyntaxError> {
+        let keyword = match &self.peek().kind {
+            TokenKind::Ident(name) => &name[..],
+            TokenKind::Punct("{") => return self.compound_statement(),
+            TokenKind::Punct(";") => {
+                self.bump();
+                return Ok(());
+            },
+            _ => "",
+        };
+
+        match keyword {
+            "if" => {
+                self.bump();
+                self.expect_punct("(")?;
+                self.expression()?;
+                self.expect_punct(")")?;
+                self.statement()?;
+                if self.is_word("else") {
+                    self.bump();
+                    self.statement()?;
+                }
+                Ok(())
+            },
+            "while" => {
+                self.bump();
+                self.expect_punct("(")?;
+                self.condition()?;
+                self.expect_punct(")")?;
+                self.statement()
+            },
+            "do" => {
+                self.bump();
+                self.statement()?;
+                self.expect_word("while")?;
+                self.expect_punct("(")?;
+                self.expression()?;
+                self.expect_punct(")")?;
+                self.expect_punct(";")
+            },
+            "for" => {
+                self.bump();
+                self.expect_punct("(")?;
+                if !self.eat_punct(";") {
+                    if self.is_declaration() {
+                        self.declaration_statement()?;
+                    } else {
+                        self.expression()?;
+                        self.expect_punct(";")?;
+                    }
+                }
+                if !self.is_punct(";") {
+                    self.condition()?;
+                }
+                self.expect_punct(";")?;
+                if !self.is_punct(")") {
+                    self.expression()?;
+                }
+                self.expect_punct(")")?;
+                self.statement()
+            },
+            "switch" => {
+                self.bump();
+                self.expect_punct("(")?;
+                self.expression()?;
+                self.expect_punct(")")?;
+                self.compound_statement()
+            },
+            "case" => {
+                self.bump();
+                self.expression()?;
+                self.expect_punct(":")
+            },
+            "default" => {
+                self.bump();
+                self.expect_punct(":")
+            },
+            "break" | "continue" | "discard" => {
+                self.bump();
+                self.expect_punct(";")
+            },
+            "return" => {
+                self.bump();
+                if !self.is_punct(";") {
+                    self.expression()?;
+                }
+                self.expect_punct(";")
+            },
+            _ if self.is_declaration() => self.declaration_statement(),
+            _ => {
+                self.expression()?;
+                self.expect_punct(";")
+            },
+        }
+    }
+
+    /// The condition of a `while` or `for` loop, which can declare a variable.
+    fn condition(&mut self) -> Result<(), SyntaxError> {
+        if self.is_type_at(0) && self.peek_at(1).kind != TokenKind::Punct("(") {
+            self.type_specifier()?;
+            self.expect_ident()?;
+            self.expect_punct("=")?;
+            self.initializer()
+        } else {
+            self.expression()
+        }
+    }
+
+    fn expression(&mut self) -> Result<(), SyntaxError> {
+        self.assignment_expression()?;
+        while self.eat_punct(",") {
+            self.assignment_expression()?;
+        }
+        Ok(())
+    }
+
+    fn assignment_expression(&mut self) -> Result<(), SyntaxError> {
+        self.conditional_expression()?;
+        const ASSIGNMENTS: &[&str] = &["=", "+=", "-=", "*=", "/=", "%=", "<<=", ">>=", "&=",
+                                       "^=", "|="];
+        if ASSIGNMENTS.iter().any(|p| self.is_punct(p)) {
+            self.bump();
+            self.assignment_expression()?;
+        }
+        Ok(())
+    }
+
+    fn conditional_expression(&mut self) -> Result<(), SyntaxError> {
+        self.binary_expression(0)?;
+        if self.eat_punct("?") {
+            self.expression()?;
+            self.expect_punct(":")?;
+            self.assignment_expression()?;
+        }
+        Ok(())
+    }
+
+    /// Parses the binary operators, from the lowest precedence level `level`.
+    fn binary_expression(&mut self, level: usize) -> Result<(), SyntaxError> {
+        const LEVELS: &[&[&str]] = &[
+            &["||"], &["^^"], &["&&"], &["|"], &["^"], &["&"], &["==", "!="],
+            &["<", ">", "<=", ">="], &["<<", ">>"], &["+", "-"], &["*", "/", "%"],
+        ];
+
+        if level == LEVELS.len() {
+            return self.unary_expression();
+        }
+
+        self.binary_expression(level + 1)?;
+        while LEVELS[level].iter().any(|p| self.is_punct(p)) {
+            self.bump();
+            self.binary_expression(level + 1)?;
+        }
+        Ok(())
+    }
+
+    fn unary_expression(&mut self) -> Result<(), SyntaxError> {
+        if ["++", "--", "+", "-", "!", "~"].iter().any(|p| self.is_punct(p)) {
+            self.bump();
+            return self.unary_expression();
+        }
+
+        self.primary_expression()?;
+
+        loop {
+            if self.eat_punct("[") {
+                self.expression()?;
+                self.expect_punct("]")?;
+            } else if self.is_punct("(") {
+                self.arguments()?;
+            } else if self.eat_punct(".") {
+                // `.length()` is the only method, and `length` isn't a keyword
+                self.expect_ident()?;
+            } else if self.is_punct("++") || self.is_punct("--") {
+                self.bump();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn primary_expression(&mut self) -> Result<(), SyntaxError> {
+        let token = self.peek();
+        match &token.kind {
+            TokenKind::Number => {
+                self.bump();
+                Ok(())
+            },
+            TokenKind::Punct("(") => {
+                self.bump();
+                self.expression()?;
+                self.expect_punct(")")
+            },
+            TokenKind::Ident(name) if name == "true" || name == "false" => {
+                self.bump();
+                Ok(())
+            },
+            // constructor, possibly of an array
+            TokenKind::Ident(_) if self.is_type_at(0) => {
+                self.bump();
+                self.array_specifier()?;
+                if !self.is_punct("(") {
+                    let next = self.peek();
+                    return self.error(next, format!("expected `(` after the type, found {}",
+                                                    next.describe()));
+                }
+                Ok(())
+            },
+            TokenKind::Ident(_) => self.expect_ident().map(|_| ()),
+            _ => self.error(token, format!("expected an expression, found {}",
+                                           token.describe())),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<(), SyntaxError> {
+        self.expect_punct("(")?;
+        if self.eat_punct(")") {
+            return Ok(());
+        }
+        if self.is_word("void") && self.peek_at(1).kind == TokenKind::Punct(")") {
+            self.bump();
+            self.bump();
+            return Ok(());
+        }
+        loop {
+            self.assignment_expression()?;
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct(")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+
+    fn error_at(source: &str) -> (usize, usize) {
+        let err = validate(source).unwrap_err();
+        (err.line, err.column)
+    }
+
+    #[test]
+    fn valid_shaders() {
+        validate("
+            #version 330 core
+            #extension GL_ARB_explicit_uniform_location : enable
+
+            layout(location = 0) in vec3 position;
+            layout(std140) uniform Block {
+                mat4 matrix;
+                vec4 colors[4];
+            } block;
+            uniform sampler2D tex;
+            out vec4 color;
+
+            struct Light { vec3 position; float intensity; };
+            const float weights[3] = float[](0.25, 0.5, 0.25);
+
+            float attenuation(Light light, in vec3 point);
+
+            void main() {
+                Light light = Light(vec3(0.0), 1.0);
+                for (int i = 0; i < 3; ++i) {
+                    if (i == 1) continue; else light.intensity *= weights[i];
+                }
+                switch (int(light.intensity)) {
+                    case 0: break;
+                    default: discard;
+                }
+                color = texture(tex, position.xy) * block.colors[0].x > 0.5 ? vec4(1.0) : vec4(.5e-1);
+                gl_Position = block.matrix * vec4(position, 1.0);
+            }
+        ").unwrap();
+    }
+
+    #[test]
+    fn compute_layout() {
+        validate("
+            #version 430
+            layout(local_size_x = 8, local_size_y = 8) in;
+            layout(rgba32f, binding = 0) uniform writeonly image2D image;
+            void main() {
+                imageStore(image, ivec2(gl_GlobalInvocationID.xy), vec4(1.0));
+            }
+        ").unwrap();
+    }
+
+    #[test]
+    fn subroutine_types() {
+        validate("
+            #version 400
+            subroutine vec4 color_t();
+            subroutine(color_t) vec4 red() { return vec4(1.0, 0.0, 0.0, 1.0); }
+            subroutine uniform color_t color;
+        ").unwrap();
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(error_at("void main() {\n    vec4 x = vec4(1.0)\n}"), (3, 1));
+        assert_eq!(error_at("void main() {\n    flot x = 1.0;\n}"), (2, 5));
+        assert_eq!(error_at("void main() {\n    float x = (1.0 + 2.0;\n}"), (2, 25));
+        assert_eq!(error_at("void main() {\n    float x = 1.0.0;\n}"), (2, 15));
+        assert_eq!(error_at("void main() {\n    float x = 1.0; /*\n}"), (2, 20));
+        assert_eq!(error_at("void main() {}\n#version 330"), (2, 1));
+    }
+
+    #[test]
+    fn macros_only_check_delimiters() {
+        validate("#define SQUARE(x) ((x) * (x))\nvoid main() { float y = SQUARE(2.0); }").unwrap();
+        assert_eq!(error_at("#define ONE 1\nvoid main() { float y = ONE; "), (2, 13));
+    }
+}
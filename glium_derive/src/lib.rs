@@ -0,0 +1,286 @@
+/*!
+Derive macros for glium.
+
+Use glium's `derive` feature instead of depending on this crate directly.
+
+*/
+#![warn(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, LitStr};
+
+#[cfg(feature = "glsl")]
+mod glsl;
+
+/// Implements `UniformBlock` and `BlockMember` for a struct, and checks at compile time that
+/// the offset of each field matches the `std140` layout.
+///
+/// The struct must be `#[repr(C)]`. Add `#[uniform_block(std430)]` to the struct to check
+/// against the `std430` layout instead, which is the layout usually used by shader storage
+/// blocks.
+///
+/// Glium doesn't insert padding itself. If a field is not at the offset that OpenGL expects,
+/// compilation fails and you have to add a field marked with `#[uniform_block(padding)]`
+/// before it. Padding fields are ignored when the struct is matched against the block of a
+/// program. The total size of the struct must also match the size that OpenGL expects, which
+/// can require a padding field at the end.
+///
+/// ```ignore
+/// #[derive(Copy, Clone, glium::uniforms::UniformBlock)]
+/// #[repr(C)]
+/// struct Light {
+///     position: [f32; 3],
+///     intensity: f32,
+///     color: [f32; 3],
+///     #[uniform_block(padding)]
+///     _padding: f32,
+/// }
+/// ```
+#[proc_macro_derive(UniformBlock, attributes(uniform_block))]
+pub fn derive_uniform_block(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand_uniform_block(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Checks the syntax of a GLSL shader at compile time, and inserts the value of Rust
+/// constants into it.
+///
+/// The shader is given as a string literal. Raw strings are recommended, as they don't need
+/// any escaping and keep the lines of the preprocessor directives intact. The macro fails to
+/// compile if the source can't be parsed, with the line and column of the error within the
+/// literal. Only the syntax is checked, names and types are still checked by the driver when
+/// the program is created.
+///
+/// `${PATH}` is replaced by the value of the Rust constant or static at `PATH`, formatted with
+/// `ToString`. Without any placeholder the macro expands to a `&'static str`, otherwise it
+/// expands to a `String` that is built when the expression is evaluated.
+///
+/// ```ignore
+/// const MAX_LIGHTS: usize = 4;
+///
+/// let fragment_shader = glium::program::glsl!(r#"
+///     #version 140
+///     uniform vec3 lights[${MAX_LIGHTS}];
+///     out vec4 color;
+///     void main() {
+///         color = vec4(lights[0], 1.0);
+///     }
+/// "#);
+/// ```
+#[cfg(feature = "glsl")]
+#[proc_macro]
+pub fn glsl(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as LitStr);
+    match expand_glsl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[cfg(feature = "glsl")]
+fn expand_glsl(input: &LitStr) -> Result<TokenStream2, Error> {
+    let source = input.value();
+
+    // the placeholders are replaced by a number of the same length for the validation, so that
+    // the columns of the errors are right
+    let mut checked = String::with_capacity(source.len());
+    let mut parts = Vec::new();
+    let mut rest = &source[..];
+    while let Some(start) = rest.find("${") {
+        let end = match rest[start ..].find('}') {
+            Some(end) => start + end,
+            None => return Err(Error::new_spanned(input, "unclosed `${` in the shader")),
+        };
+
+        let path = syn::parse_str::<syn::Path>(&rest[start + 2 .. end]).map_err(|_| {
+            Error::new_spanned(input, format!("`{}` isn't a path to a Rust constant",
+                                              &rest[start .. end + 1]))
+        })?;
+
+        checked.push_str(&rest[.. start]);
+        checked.push('1');
+        checked.extend(std::iter::repeat(' ').take(end - start));
+        parts.push((rest[.. start].to_owned(), path));
+        rest = &rest[end + 1 ..];
+    }
+    checked.push_str(rest);
+
+    glsl::validate(&checked).map_err(|err| Error::new_spanned(input, err))?;
+
+    if parts.is_empty() {
+        return Ok(quote!(#input));
+    }
+
+    let pieces = parts.iter().map(|(text, path)| {
+        quote! {
+            source.push_str(#text);
+            source.push_str(&::std::string::ToString::to_string(&#path));
+        }
+    });
+
+    Ok(quote! {
+        {
+            let mut source = ::std::string::String::new();
+            #(#pieces)*
+            source.push_str(#rest);
+            source
+        }
+    })
+}
+
+fn expand_uniform_block(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics,
+                                      "`UniformBlock` can't be derived for generic structs"));
+    }
+
+    if !has_repr_c(input)? {
+        return Err(Error::new_spanned(name, "`UniformBlock` requires the struct to be `#[repr(C)]`, \
+                                             otherwise the fields may be reordered"));
+    }
+
+    let std430 = parse_struct_layout(input)?;
+    let (layout_name, align, size) = if std430 {
+        ("std430", quote!(STD430_ALIGN), quote!(STD430_SIZE))
+    } else {
+        ("std140", quote!(STD140_ALIGN), quote!(STD140_SIZE))
+    };
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(name, "`UniformBlock` requires named fields")),
+        },
+        _ => return Err(Error::new_spanned(name, "`UniformBlock` can only be derived for structs")),
+    };
+
+    let mut members = Vec::new();
+    for field in fields {
+        if !is_padding(field)? {
+            members.push((field.ident.clone().unwrap(), &field.ty));
+        }
+    }
+
+    if members.is_empty() {
+        return Err(Error::new_spanned(name, "`UniformBlock` requires at least one field \
+                                             that isn't padding"));
+    }
+
+    let member_names = members.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+    let member_types = members.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+
+    // largest alignment of the members, as a constant expression
+    let max_align = |align: TokenStream2| member_types.iter().fold(quote!(0), |acc, ty| quote! {
+        ::glium::uniforms::__max(#acc, <#ty as ::glium::uniforms::BlockMember>::#align)
+    });
+    let std140_align = max_align(quote!(STD140_ALIGN));
+    let std430_align = max_align(quote!(STD430_ALIGN));
+
+    let offset_checks = members.iter().map(|(ident, ty)| {
+        let message = LitStr::new(&format!("field `{}` of `{}` is not at its {} offset, add a \
+                                            `#[uniform_block(padding)]` field before it",
+                                           ident, name, layout_name), ident.span());
+        quote! {
+            let offset = ::glium::uniforms::__align_up(
+                offset, <#ty as ::glium::uniforms::BlockMember>::#align
+            );
+            assert!(::glium::__glium_offset_of!(#name, #ident) == offset, #message);
+            let offset = offset + <#ty as ::glium::uniforms::BlockMember>::#size;
+        }
+    });
+
+    let size_message = LitStr::new(&format!("the size of `{}` doesn't match its {} size, add a \
+                                             `#[uniform_block(padding)]` field at the end",
+                                            name, layout_name), name.span());
+
+    Ok(quote! {
+        impl ::glium::uniforms::BlockMember for #name {
+            const STD140_ALIGN: usize = ::glium::uniforms::__align_up(#std140_align, 16);
+            const STD140_SIZE: usize = ::glium::uniforms::__align_up({
+                let offset = 0usize;
+                #(
+                    let offset = ::glium::uniforms::__align_up(
+                        offset, <#member_types as ::glium::uniforms::BlockMember>::STD140_ALIGN
+                    ) + <#member_types as ::glium::uniforms::BlockMember>::STD140_SIZE;
+                )*
+                offset
+            }, Self::STD140_ALIGN);
+            const STD430_ALIGN: usize = #std430_align;
+            const STD430_SIZE: usize = ::glium::uniforms::__align_up({
+                let offset = 0usize;
+                #(
+                    let offset = ::glium::uniforms::__align_up(
+                        offset, <#member_types as ::glium::uniforms::BlockMember>::STD430_ALIGN
+                    ) + <#member_types as ::glium::uniforms::BlockMember>::STD430_SIZE;
+                )*
+                offset
+            }, Self::STD430_ALIGN);
+        }
+
+        const _: () = {
+            let offset = 0usize;
+            #(#offset_checks)*
+            assert!(::core::mem::size_of::<#name>() == ::glium::uniforms::__align_up(
+                offset, <#name as ::glium::uniforms::BlockMember>::#align
+            ), #size_message);
+        };
+
+        ::glium::implement_uniform_block!(#name, #(#member_names),*);
+    })
+}
+
+/// Returns true if the struct has a `#[repr(C)]` attribute.
+fn has_repr_c(input: &DeriveInput) -> Result<bool, Error> {
+    let mut repr_c = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(repr_c)
+}
+
+/// Parses the `#[uniform_block(...)]` attributes of the struct. Returns true for `std430`.
+fn parse_struct_layout(input: &DeriveInput) -> Result<bool, Error> {
+    let mut std430 = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("uniform_block")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("std430") {
+                std430 = true;
+                Ok(())
+            } else if meta.path.is_ident("std140") {
+                std430 = false;
+                Ok(())
+            } else {
+                Err(meta.error("expected `std140` or `std430`"))
+            }
+        })?;
+    }
+    Ok(std430)
+}
+
+/// Returns true if the field has a `#[uniform_block(padding)]` attribute.
+fn is_padding(field: &syn::Field) -> Result<bool, Error> {
+    let mut padding = false;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("uniform_block")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("padding") {
+                padding = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `padding`"))
+            }
+        })?;
+    }
+    Ok(padding)
+}
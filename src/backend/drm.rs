@@ -0,0 +1,778 @@
+#![cfg(all(feature = "drm", target_os = "linux"))]
+/*!
+
+Backend that presents directly to a screen with DRM/KMS, without any display server.
+
+The OpenGL context is created with EGL on top of a GBM device, and each frame is shown by
+flipping the CRTC of the first connected screen to the buffer that was just rendered. This is
+meant for kiosks and embedded appliances whose only application is the glium application,
+started from a virtual terminal. No compositor must be running on the same GPU, as only one
+process at a time can control the modes of the screens.
+
+# Features
+
+Only available on Linux if the 'drm' feature is enabled. `libdrm.so.2` and `libgbm.so.1` are
+loaded when the display is created, so they aren't needed to build the application. The EGL
+implementation must support `EGL_KHR_platform_gbm` or `EGL_MESA_platform_gbm`.
+
+```no_run
+use glium::Surface;
+
+let display = glium::backend::drm::DrmDisplay::new().unwrap();
+println!("{}x{}", display.mode().width, display.mode().height);
+
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 0.0, 1.0);
+frame.finish().unwrap();
+```
+
+`Frame::finish` waits for the page flip, which happens at the next vertical blank, so the
+application is naturally synchronized with the screen. The previous content of the screen is
+restored when the display is dropped.
+
+*/
+use glutin::api::egl;
+use glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+use glutin::context::{ContextApi, ContextAttributesBuilder};
+use glutin::display::GetGlDisplay;
+use glutin::error::{Error as GlutinError, ErrorKind};
+use glutin::prelude::*;
+use glutin::surface::{SurfaceAttributesBuilder, WindowSurface};
+use libloading::Library;
+use raw_window_handle::{GbmDisplayHandle, GbmWindowHandle, RawDisplayHandle, RawWindowHandle};
+
+use crate::backend;
+use crate::backend::Backend;
+use crate::backend::Context;
+use crate::debug;
+use crate::Frame;
+use crate::IncompatibleOpenGl;
+use crate::SwapBuffersError;
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::mem::ManuallyDrop;
+use std::num::NonZeroU32;
+use std::ops::Deref;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::rc::Rc;
+use std::slice;
+
+const DRM_MODE_CONNECTED: c_uint = 1;
+const DRM_MODE_TYPE_PREFERRED: u32 = 1 << 3;
+const DRM_MODE_PAGE_FLIP_EVENT: u32 = 1;
+
+const GBM_FORMAT_XRGB8888: u32 = 0x3432_5258;
+const GBM_FORMAT_ARGB8888: u32 = 0x3432_5241;
+const GBM_BO_USE_SCANOUT: u32 = 1 << 0;
+const GBM_BO_USE_RENDERING: u32 = 1 << 2;
+
+#[repr(C)]
+struct DrmModeRes {
+    count_fbs: c_int,
+    fbs: *mut u32,
+    count_crtcs: c_int,
+    crtcs: *mut u32,
+    count_connectors: c_int,
+    connectors: *mut u32,
+    count_encoders: c_int,
+    encoders: *mut u32,
+    min_width: u32,
+    max_width: u32,
+    min_height: u32,
+    max_height: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct DrmModeModeInfo {
+    clock: u32,
+    hdisplay: u16,
+    hsync_start: u16,
+    hsync_end: u16,
+    htotal: u16,
+    hskew: u16,
+    vdisplay: u16,
+    vsync_start: u16,
+    vsync_end: u16,
+    vtotal: u16,
+    vscan: u16,
+    vrefresh: u32,
+    flags: u32,
+    ty: u32,
+    name: [c_char; 32],
+}
+
+#[repr(C)]
+struct DrmModeConnector {
+    connector_id: u32,
+    encoder_id: u32,
+    connector_type: u32,
+    connector_type_id: u32,
+    connection: c_uint,
+    mm_width: u32,
+    mm_height: u32,
+    subpixel: c_uint,
+    count_modes: c_int,
+    modes: *mut DrmModeModeInfo,
+    count_props: c_int,
+    props: *mut u32,
+    prop_values: *mut u64,
+    count_encoders: c_int,
+    encoders: *mut u32,
+}
+
+#[repr(C)]
+struct DrmModeEncoder {
+    encoder_id: u32,
+    encoder_type: u32,
+    crtc_id: u32,
+    possible_crtcs: u32,
+    possible_clones: u32,
+}
+
+#[repr(C)]
+struct DrmModeCrtc {
+    crtc_id: u32,
+    buffer_id: u32,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    mode_valid: c_int,
+    mode: DrmModeModeInfo,
+    gamma_size: c_int,
+}
+
+type EventHandler = extern "C" fn(c_int, c_uint, c_uint, c_uint, *mut c_void);
+
+// version 2 of the structure
+#[repr(C)]
+struct DrmEventContext {
+    version: c_int,
+    vblank_handler: Option<EventHandler>,
+    page_flip_handler: Option<EventHandler>,
+}
+
+enum GbmDevice {}
+enum GbmSurface {}
+enum GbmBo {}
+
+/// The functions of libdrm and libgbm used by the backend.
+struct Libraries {
+    get_resources: unsafe extern "C" fn(c_int) -> *mut DrmModeRes,
+    free_resources: unsafe extern "C" fn(*mut DrmModeRes),
+    get_connector: unsafe extern "C" fn(c_int, u32) -> *mut DrmModeConnector,
+    free_connector: unsafe extern "C" fn(*mut DrmModeConnector),
+    get_encoder: unsafe extern "C" fn(c_int, u32) -> *mut DrmModeEncoder,
+    free_encoder: unsafe extern "C" fn(*mut DrmModeEncoder),
+    get_crtc: unsafe extern "C" fn(c_int, u32) -> *mut DrmModeCrtc,
+    free_crtc: unsafe extern "C" fn(*mut DrmModeCrtc),
+    set_crtc: unsafe extern "C" fn(c_int, u32, u32, u32, u32, *mut u32, c_int,
+                                   *mut DrmModeModeInfo) -> c_int,
+    add_fb: unsafe extern "C" fn(c_int, u32, u32, u8, u8, u32, u32, *mut u32) -> c_int,
+    rm_fb: unsafe extern "C" fn(c_int, u32) -> c_int,
+    page_flip: unsafe extern "C" fn(c_int, u32, u32, u32, *mut c_void) -> c_int,
+    handle_event: unsafe extern "C" fn(c_int, *mut DrmEventContext) -> c_int,
+
+    create_device: unsafe extern "C" fn(c_int) -> *mut GbmDevice,
+    destroy_device: unsafe extern "C" fn(*mut GbmDevice),
+    create_surface: unsafe extern "C" fn(*mut GbmDevice, u32, u32, u32, u32) -> *mut GbmSurface,
+    destroy_surface: unsafe extern "C" fn(*mut GbmSurface),
+    lock_front_buffer: unsafe extern "C" fn(*mut GbmSurface) -> *mut GbmBo,
+    release_buffer: unsafe extern "C" fn(*mut GbmSurface, *mut GbmBo),
+    // returns a `union gbm_bo_handle`, whose `u32` member is the GEM handle
+    bo_get_handle: unsafe extern "C" fn(*mut GbmBo) -> u64,
+    bo_get_stride: unsafe extern "C" fn(*mut GbmBo) -> u32,
+
+    // the libraries must outlive the function pointers above
+    _drm: Library,
+    _gbm: Library,
+}
+
+impl Libraries {
+    unsafe fn load() -> Result<Libraries, libloading::Error> {
+        let drm = Library::new("libdrm.so.2")?;
+        let gbm = Library::new("libgbm.so.1")?;
+
+        Ok(Libraries {
+            get_resources: *drm.get(b"drmModeGetResources\0")?,
+            free_resources: *drm.get(b"drmModeFreeResources\0")?,
+            get_connector: *drm.get(b"drmModeGetConnector\0")?,
+            free_connector: *drm.get(b"drmModeFreeConnector\0")?,
+            get_encoder: *drm.get(b"drmModeGetEncoder\0")?,
+            free_encoder: *drm.get(b"drmModeFreeEncoder\0")?,
+            get_crtc: *drm.get(b"drmModeGetCrtc\0")?,
+            free_crtc: *drm.get(b"drmModeFreeCrtc\0")?,
+            set_crtc: *drm.get(b"drmModeSetCrtc\0")?,
+            add_fb: *drm.get(b"drmModeAddFB\0")?,
+            rm_fb: *drm.get(b"drmModeRmFB\0")?,
+            page_flip: *drm.get(b"drmModePageFlip\0")?,
+            handle_event: *drm.get(b"drmHandleEvent\0")?,
+            create_device: *gbm.get(b"gbm_create_device\0")?,
+            destroy_device: *gbm.get(b"gbm_device_destroy\0")?,
+            create_surface: *gbm.get(b"gbm_surface_create\0")?,
+            destroy_surface: *gbm.get(b"gbm_surface_destroy\0")?,
+            lock_front_buffer: *gbm.get(b"gbm_surface_lock_front_buffer\0")?,
+            release_buffer: *gbm.get(b"gbm_surface_release_buffer\0")?,
+            bo_get_handle: *gbm.get(b"gbm_bo_get_handle\0")?,
+            bo_get_stride: *gbm.get(b"gbm_bo_get_stride\0")?,
+            _drm: drm,
+            _gbm: gbm,
+        })
+    }
+}
+
+/// Display mode of the screen a `DrmDisplay` presents to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrmMode {
+    /// Name of the mode, for example `1920x1080`.
+    pub name: String,
+    /// Width of the screen in pixels.
+    pub width: u32,
+    /// Height of the screen in pixels.
+    pub height: u32,
+    /// Refresh rate of the screen in millihertz, which can be passed to
+    /// `util::FramePacer::from_refresh_rate_millihertz`.
+    pub refresh_rate_millihertz: u32,
+}
+
+impl DrmMode {
+    fn from_info(info: &DrmModeModeInfo) -> DrmMode {
+        let name = unsafe { CStr::from_ptr(info.name.as_ptr()) };
+        let pixels = info.htotal as u64 * info.vtotal as u64;
+
+        // the clock is in kHz
+        let refresh_rate_millihertz = (info.clock as u64 * 1_000_000).checked_div(pixels)
+            .map_or(info.vrefresh * 1000, |rate| rate as u32);
+
+        DrmMode {
+            name: name.to_string_lossy().into_owned(),
+            width: info.hdisplay as u32,
+            height: info.vdisplay as u32,
+            refresh_rate_millihertz,
+        }
+    }
+}
+
+/// Error that can happen while creating a `DrmDisplay`.
+#[derive(Debug)]
+pub enum DrmCreationError {
+    /// The DRM device couldn't be opened.
+    IoError(io::Error),
+    /// `libdrm.so.2` or `libgbm.so.1` couldn't be loaded.
+    LibraryError(libloading::Error),
+    /// No screen is connected to the device.
+    NoConnector,
+    /// No CRTC can drive the connected screen.
+    NoCrtc,
+    /// GBM failed to create the device or the surface.
+    GbmError,
+    /// An error has happened while creating the EGL context or surface.
+    GlutinError(GlutinError),
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+impl fmt::Display for DrmCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrmCreationError::IoError(err) => write!(fmt, "{}", err),
+            DrmCreationError::LibraryError(err) => write!(fmt, "{}", err),
+            DrmCreationError::NoConnector => write!(fmt, "No screen is connected to the device"),
+            DrmCreationError::NoCrtc => write!(fmt, "No CRTC can drive the connected screen"),
+            DrmCreationError::GbmError => write!(fmt, "Failed to create the GBM device or surface"),
+            DrmCreationError::GlutinError(err) => write!(fmt, "{}", err),
+            DrmCreationError::IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for DrmCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            DrmCreationError::IoError(ref err) => Some(err),
+            DrmCreationError::LibraryError(ref err) => Some(err),
+            DrmCreationError::NoConnector => None,
+            DrmCreationError::NoCrtc => None,
+            DrmCreationError::GbmError => None,
+            DrmCreationError::GlutinError(ref err) => Some(err),
+            DrmCreationError::IncompatibleOpenGl(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for DrmCreationError {
+    #[inline]
+    fn from(err: io::Error) -> DrmCreationError {
+        DrmCreationError::IoError(err)
+    }
+}
+
+impl From<libloading::Error> for DrmCreationError {
+    #[inline]
+    fn from(err: libloading::Error) -> DrmCreationError {
+        DrmCreationError::LibraryError(err)
+    }
+}
+
+impl From<GlutinError> for DrmCreationError {
+    #[inline]
+    fn from(err: GlutinError) -> DrmCreationError {
+        DrmCreationError::GlutinError(err)
+    }
+}
+
+impl From<IncompatibleOpenGl> for DrmCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> DrmCreationError {
+        DrmCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+/// A glium context that presents to a screen with DRM/KMS.
+#[derive(Clone)]
+pub struct DrmDisplay {
+    context: Rc<Context>,
+    mode: DrmMode,
+}
+
+/// An implementation of the `Backend` trait for an EGL context on a GBM surface that is
+/// scanned out by a CRTC.
+pub struct DrmBackend {
+    // destroyed manually before the GBM objects they are created from
+    context: ManuallyDrop<egl::context::PossiblyCurrentContext>,
+    surface: ManuallyDrop<egl::surface::Surface<WindowSurface>>,
+    gbm_surface: *mut GbmSurface,
+    gbm_device: *mut GbmDevice,
+    connector: u32,
+    crtc: u32,
+    mode: DrmModeModeInfo,
+    // the CRTC configuration before the first frame, restored on drop
+    saved_crtc: *mut DrmModeCrtc,
+    // buffer currently scanned out, released once the next one is on screen
+    front: Cell<Option<*mut GbmBo>>,
+    // DRM framebuffers created for the buffers of the GBM surface
+    framebuffers: RefCell<Vec<(*mut GbmBo, u32)>>,
+    mode_set: Cell<bool>,
+    libraries: Libraries,
+    // closed last
+    file: File,
+}
+
+impl DrmDisplay {
+    /// Opens the first DRM device, in `/dev/dri`, that has a connected screen.
+    pub fn new() -> Result<DrmDisplay, DrmCreationError> {
+        let mut cards: Vec<_> = fs::read_dir("/dev/dri")?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                matches!(path.file_name().and_then(|n| n.to_str()), Some(n) if n.starts_with("card"))
+            })
+            .collect();
+        cards.sort();
+
+        let mut result = Err(DrmCreationError::NoConnector);
+        for card in cards {
+            result = DrmDisplay::open(&card);
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Opens a DRM device, for example `/dev/dri/card0`, and presents to its first connected
+    /// screen, in its preferred mode.
+    #[inline]
+    pub fn open<P>(path: P) -> Result<DrmDisplay, DrmCreationError> where P: AsRef<Path> {
+        DrmDisplay::with_debug(path, Default::default())
+    }
+
+    /// The same as the `open` constructor, but allows for specifying debug callback behaviour.
+    pub fn with_debug<P>(path: P, debug: debug::DebugCallbackBehavior)
+                         -> Result<DrmDisplay, DrmCreationError> where P: AsRef<Path>
+    {
+        let backend = unsafe { DrmBackend::open(path.as_ref()) }?;
+        let mode = DrmMode::from_info(&backend.mode);
+        let context = unsafe { Context::new(backend, true, debug) }?;
+        Ok(DrmDisplay { context, mode })
+    }
+
+    /// Returns the display mode of the screen.
+    #[inline]
+    pub fn mode(&self) -> &DrmMode {
+        &self.mode
+    }
+
+    /// Start drawing on the screen.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. When the `Frame` is
+    /// finished, it is flipped to the screen at the next vertical blank.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.get_framebuffer_dimensions())
+    }
+}
+
+impl fmt::Debug for DrmDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::drm::DrmDisplay {}]", self.mode.name)
+    }
+}
+
+impl Deref for DrmDisplay {
+    type Target = Context;
+    #[inline]
+    fn deref(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for DrmDisplay {
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+impl DrmBackend {
+    unsafe fn open(path: &Path) -> Result<DrmBackend, DrmCreationError> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let fd = file.as_raw_fd();
+        let libraries = Libraries::load()?;
+
+        let (connector, crtc, mode) = find_output(&libraries, fd)?;
+
+        let gbm_device = (libraries.create_device)(fd);
+        if gbm_device.is_null() {
+            return Err(DrmCreationError::GbmError);
+        }
+
+        let (context, surface, gbm_surface) = match create_surface(&libraries, gbm_device, &mode) {
+            Ok(objects) => objects,
+            Err(err) => {
+                (libraries.destroy_device)(gbm_device);
+                return Err(err);
+            },
+        };
+
+        let saved_crtc = (libraries.get_crtc)(fd, crtc);
+
+        Ok(DrmBackend {
+            context: ManuallyDrop::new(context),
+            surface: ManuallyDrop::new(surface),
+            gbm_surface,
+            gbm_device,
+            connector,
+            crtc,
+            mode,
+            saved_crtc,
+            front: Cell::new(None),
+            framebuffers: RefCell::new(Vec::new()),
+            mode_set: Cell::new(false),
+            libraries,
+            file,
+        })
+    }
+
+    /// Returns the DRM framebuffer of a buffer of the GBM surface, creating it the first time
+    /// the buffer is used.
+    unsafe fn framebuffer(&self, bo: *mut GbmBo) -> Option<u32> {
+        let mut framebuffers = self.framebuffers.borrow_mut();
+        if let Some(&(_, fb)) = framebuffers.iter().find(|&&(b, _)| b == bo) {
+            return Some(fb);
+        }
+
+        let handle = (self.libraries.bo_get_handle)(bo) as u32;
+        let stride = (self.libraries.bo_get_stride)(bo);
+        let mut fb = 0;
+        if (self.libraries.add_fb)(self.file.as_raw_fd(), self.mode.hdisplay as u32,
+                                   self.mode.vdisplay as u32, 24, 32, stride, handle,
+                                   &mut fb) != 0
+        {
+            return None;
+        }
+
+        framebuffers.push((bo, fb));
+        Some(fb)
+    }
+
+    /// Shows a framebuffer at the next vertical blank, and waits until it is on screen.
+    unsafe fn flip(&self, fb: u32) -> bool {
+        let fd = self.file.as_raw_fd();
+
+        if !self.mode_set.get() {
+            let mut connector = self.connector;
+            let mut mode = self.mode;
+            if (self.libraries.set_crtc)(fd, self.crtc, fb, 0, 0, &mut connector, 1,
+                                         &mut mode) != 0
+            {
+                return false;
+            }
+            self.mode_set.set(true);
+            return true;
+        }
+
+        extern "C" fn page_flipped(_: c_int, _: c_uint, _: c_uint, _: c_uint,
+                                   data: *mut c_void) {
+            unsafe { (*(data as *const Cell<bool>)).set(true) };
+        }
+
+        let flipped = Cell::new(false);
+        if (self.libraries.page_flip)(fd, self.crtc, fb, DRM_MODE_PAGE_FLIP_EVENT,
+                                      &flipped as *const Cell<bool> as *mut c_void) != 0
+        {
+            return false;
+        }
+
+        let mut events = DrmEventContext {
+            version: 2,
+            vblank_handler: None,
+            page_flip_handler: Some(page_flipped),
+        };
+        while !flipped.get() {
+            // blocks until an event is available
+            if (self.libraries.handle_event)(fd, &mut events) != 0 {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+type EglObjects = (egl::context::PossiblyCurrentContext, egl::surface::Surface<WindowSurface>,
+                  *mut GbmSurface);
+
+/// Creates a GBM surface with the dimensions of the mode, and an EGL context that is current on
+/// it.
+unsafe fn create_surface(libraries: &Libraries, gbm_device: *mut GbmDevice, mode: &DrmModeModeInfo)
+                         -> Result<EglObjects, DrmCreationError>
+{
+    let mut handle = GbmDisplayHandle::empty();
+    handle.gbm_device = gbm_device as *mut c_void;
+    let display = egl::display::Display::new(RawDisplayHandle::Gbm(handle))?;
+
+    // the format of the GBM surface must match the visual of the configuration
+    let template = ConfigTemplateBuilder::new()
+        .with_surface_type(ConfigSurfaceTypes::WINDOW)
+        .build();
+    let config = display.find_configs(template)?
+        .find(|c| {
+            c.native_visual() == GBM_FORMAT_XRGB8888 || c.native_visual() == GBM_FORMAT_ARGB8888
+        })
+        .ok_or_else(|| GlutinError::from(ErrorKind::BadConfig))?;
+
+    let (width, height) = (mode.hdisplay as u32, mode.vdisplay as u32);
+    let gbm_surface = (libraries.create_surface)(gbm_device, width, height, config.native_visual(),
+                                                 GBM_BO_USE_SCANOUT | GBM_BO_USE_RENDERING);
+    if gbm_surface.is_null() {
+        return Err(DrmCreationError::GbmError);
+    }
+
+    let egl_objects = (|| {
+        let context = display.create_context(&config, &ContextAttributesBuilder::new().build(None))
+            .or_else(|_| {
+                let attributes = ContextAttributesBuilder::new()
+                    .with_context_api(ContextApi::Gles(None))
+                    .build(None);
+                display.create_context(&config, &attributes)
+            })?;
+
+        let mut handle = GbmWindowHandle::empty();
+        handle.gbm_surface = gbm_surface as *mut c_void;
+        let attributes = SurfaceAttributesBuilder::<WindowSurface>::new().build(
+            RawWindowHandle::Gbm(handle),
+            NonZeroU32::new(width).unwrap(),
+            NonZeroU32::new(height).unwrap(),
+        );
+        let surface = display.create_window_surface(&config, &attributes)?;
+        let context = context.make_current(&surface)?;
+        Ok::<_, GlutinError>((context, surface))
+    })();
+
+    match egl_objects {
+        Ok((context, surface)) => Ok((context, surface, gbm_surface)),
+        Err(err) => {
+            (libraries.destroy_surface)(gbm_surface);
+            Err(err.into())
+        },
+    }
+}
+
+/// Finds the first connected screen, a CRTC that can drive it and its preferred mode.
+unsafe fn find_output(libraries: &Libraries, fd: c_int)
+                      -> Result<(u32, u32, DrmModeModeInfo), DrmCreationError>
+{
+    let resources = (libraries.get_resources)(fd);
+    if resources.is_null() {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let result = find_output_in(libraries, fd, &*resources);
+    (libraries.free_resources)(resources);
+    result
+}
+
+unsafe fn find_output_in(libraries: &Libraries, fd: c_int, resources: &DrmModeRes)
+                         -> Result<(u32, u32, DrmModeModeInfo), DrmCreationError>
+{
+    let ids = |ptr: *mut u32, count: c_int| {
+        if ptr.is_null() { &[][..] } else { slice::from_raw_parts(ptr, count as usize) }
+    };
+    let crtcs = ids(resources.crtcs, resources.count_crtcs);
+
+    for &id in ids(resources.connectors, resources.count_connectors) {
+        let connector = (libraries.get_connector)(fd, id);
+        if connector.is_null() {
+            continue;
+        }
+
+        let found = {
+            let c = &*connector;
+            let modes = if c.modes.is_null() || c.count_modes <= 0 {
+                &[][..]
+            } else {
+                slice::from_raw_parts(c.modes, c.count_modes as usize)
+            };
+
+            let mode = modes.iter().find(|m| m.ty & DRM_MODE_TYPE_PREFERRED != 0)
+                .or_else(|| modes.first());
+
+            match mode {
+                Some(mode) if c.connection == DRM_MODE_CONNECTED => {
+                    // the CRTC currently used by the connector, or else the first one that one
+                    // of its encoders can drive
+                    let mut encoders = vec![c.encoder_id];
+                    encoders.extend_from_slice(ids(c.encoders, c.count_encoders));
+
+                    let crtc = encoders.into_iter().filter(|&e| e != 0).find_map(|e| {
+                        let encoder = (libraries.get_encoder)(fd, e);
+                        if encoder.is_null() {
+                            return None;
+                        }
+
+                        let crtc = if (*encoder).crtc_id != 0 && e == c.encoder_id {
+                            Some((*encoder).crtc_id)
+                        } else {
+                            crtcs.iter().enumerate()
+                                .find(|&(i, _)| (*encoder).possible_crtcs & (1 << i) != 0)
+                                .map(|(_, &crtc)| crtc)
+                        };
+
+                        (libraries.free_encoder)(encoder);
+                        crtc
+                    });
+
+                    Some(crtc.map(|crtc| (c.connector_id, crtc, *mode)))
+                },
+                _ => None,
+            }
+        };
+
+        (libraries.free_connector)(connector);
+
+        match found {
+            Some(Some(output)) => return Ok(output),
+            Some(None) => return Err(DrmCreationError::NoCrtc),
+            None => (),
+        }
+    }
+
+    Err(DrmCreationError::NoConnector)
+}
+
+unsafe impl Backend for DrmBackend {
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        if self.surface.swap_buffers(&self.context).is_err() {
+            return Err(SwapBuffersError::ContextLost);
+        }
+
+        unsafe {
+            let bo = (self.libraries.lock_front_buffer)(self.gbm_surface);
+            if bo.is_null() {
+                return Err(SwapBuffersError::ContextLost);
+            }
+
+            let flipped = match self.framebuffer(bo) {
+                Some(fb) => self.flip(fb),
+                None => false,
+            };
+
+            if !flipped {
+                (self.libraries.release_buffer)(self.gbm_surface, bo);
+                return Err(SwapBuffersError::ContextLost);
+            }
+
+            // the previous buffer isn't scanned out anymore
+            if let Some(previous) = self.front.replace(Some(bo)) {
+                (self.libraries.release_buffer)(self.gbm_surface, previous);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        self.context.display().get_proc_address(&symbol) as *const _
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        (self.mode.hdisplay as u32, self.mode.vdisplay as u32)
+    }
+
+    #[inline]
+    fn resize(&self, _: (u32, u32)) {
+        // the dimensions are those of the mode of the screen
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        self.context.is_current()
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        self.context.make_current(&self.surface).unwrap();
+    }
+}
+
+impl Drop for DrmBackend {
+    fn drop(&mut self) {
+        unsafe {
+            let fd = self.file.as_raw_fd();
+
+            if !self.saved_crtc.is_null() {
+                let saved = &mut *self.saved_crtc;
+                if self.mode_set.get() && saved.mode_valid != 0 {
+                    let mut connector = self.connector;
+                    (self.libraries.set_crtc)(fd, saved.crtc_id, saved.buffer_id, saved.x,
+                                              saved.y, &mut connector, 1, &mut saved.mode);
+                }
+                (self.libraries.free_crtc)(self.saved_crtc);
+            }
+
+            for &(_, fb) in self.framebuffers.borrow().iter() {
+                (self.libraries.rm_fb)(fd, fb);
+            }
+            if let Some(bo) = self.front.take() {
+                (self.libraries.release_buffer)(self.gbm_surface, bo);
+            }
+
+            ManuallyDrop::drop(&mut self.context);
+            ManuallyDrop::drop(&mut self.surface);
+            (self.libraries.destroy_surface)(self.gbm_surface);
+            (self.libraries.destroy_device)(self.gbm_device);
+        }
+    }
+}
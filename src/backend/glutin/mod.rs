@@ -7,6 +7,8 @@ Backend implementation for the glutin library
 
 Only available if the 'glutin' feature is enabled.
 
+Contexts that render to a pbuffer instead of a window are provided by the `pbuffer` module.
+
 */
 pub use glutin;
 use glutin::surface::Surface;
@@ -16,11 +18,15 @@ use crate::backend::Backend;
 use crate::backend::Context;
 use crate::context;
 use crate::debug;
-use crate::glutin::context::PossiblyCurrentContext;
+use crate::glutin::config::GetGlConfig;
+use crate::glutin::context::{NotCurrentContext, PossiblyCurrentContext};
 use crate::glutin::display::GetGlDisplay;
 use crate::glutin::prelude::*;
-use crate::glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+use crate::glutin::surface::{PbufferSurface, ResizeableSurface, SurfaceTypeTrait};
+use crate::ContextExt;
+use crate::Rect;
 use crate::SwapBuffersError;
+use crate::framebuffer::{self, FrameMultisampling};
 use crate::{Frame, IncompatibleOpenGl};
 use std::cell::RefCell;
 use std::error::Error;
@@ -31,31 +37,68 @@ use std::ops::Deref;
 use std::os::raw::c_void;
 use std::rc::Rc;
 
+pub mod pbuffer;
+
 /// Wraps a glutin context together with the corresponding Surface.
 /// This is necessary so that we can swap buffers and determine the framebuffer size within glium.
 pub struct ContextSurfacePair<T: SurfaceTypeTrait + ResizeableSurface> {
     context: PossiblyCurrentContext,
-    surface: glutin::surface::Surface<T>,
+    // `None` between `Display::destroy_surface` and `Display::recreate_surface`
+    surface: Option<glutin::surface::Surface<T>>,
+    // 1x1 surface that keeps the context current while the window surface is destroyed
+    placeholder: Option<glutin::surface::Surface<PbufferSurface>>,
 }
 
 impl<T: SurfaceTypeTrait + ResizeableSurface> ContextSurfacePair<T> {
     fn new(context: PossiblyCurrentContext, surface: glutin::surface::Surface<T>) -> Self {
-        Self { context, surface }
+        Self { context, surface: Some(surface), placeholder: None }
     }
 
     #[inline]
     /// Return the stored framebuffer dimensions
     pub fn get_framebuffer_dimensions(&self) -> (u32, u32) {
-        (
-            self.surface.width().unwrap(),
-            self.surface.height().unwrap(),
-        )
+        match self.surface {
+            Some(ref surface) => (surface.width().unwrap(), surface.height().unwrap()),
+            None => (1, 1),
+        }
     }
 
     #[inline]
     /// Return the stored framebuffer dimensions
     pub fn swap_buffers(&self) -> Result<(), glutin::error::Error> {
-        self.surface.swap_buffers(&self.context)
+        match self.surface {
+            Some(ref surface) => surface.swap_buffers(&self.context),
+            // there is nothing to present to
+            None => Ok(()),
+        }
+    }
+
+    /// Swaps the buffers of the surface, passing the damaged regions to the compositor.
+    ///
+    /// Only EGL surfaces support this, the buffers of the other surfaces are swapped entirely.
+    pub fn swap_buffers_with_damage(&self, damage: &[glutin::surface::Rect])
+                                    -> Result<(), glutin::error::Error>
+    {
+        #[cfg(all(feature = "damage", not(any(target_os = "macos", target_os = "ios"))))]
+        if let (Some(glutin::surface::Surface::Egl(surface)),
+                PossiblyCurrentContext::Egl(context)) = (&self.surface, &self.context)
+        {
+            return surface.swap_buffers_with_damage(context, damage);
+        }
+
+        let _ = damage;
+        self.swap_buffers()
+    }
+
+    #[inline]
+    /// Set the swap interval of the associated surface
+    pub fn set_swap_interval(&self, interval: glutin::surface::SwapInterval)
+                             -> Result<(), glutin::error::Error>
+    {
+        match self.surface {
+            Some(ref surface) => surface.set_swap_interval(&self.context, interval),
+            None => Err(glutin::error::ErrorKind::BadSurface.into()),
+        }
     }
 
     #[inline]
@@ -64,7 +107,23 @@ impl<T: SurfaceTypeTrait + ResizeableSurface> ContextSurfacePair<T> {
         // Make sure that no dimension is zero, which happens when minimizing on Windows for example.
         let width = NonZeroU32::new(new_size.0).unwrap_or(NonZeroU32::new(1).unwrap());
         let height = NonZeroU32::new(new_size.1).unwrap_or(NonZeroU32::new(1).unwrap());
-        self.surface.resize(&self.context, width, height);
+        if let Some(ref surface) = self.surface {
+            surface.resize(&self.context, width, height);
+        }
+    }
+
+    /// Returns true if the window surface exists.
+    #[inline]
+    pub fn has_surface(&self) -> bool {
+        self.surface.is_some()
+    }
+
+    unsafe fn make_current(&self) -> Result<(), glutin::error::Error> {
+        match (&self.surface, &self.placeholder) {
+            (Some(surface), _) => self.context.make_current(surface),
+            (None, Some(placeholder)) => self.context.make_current(placeholder),
+            (None, None) => panic!("The surface of the display has been destroyed"),
+        }
     }
 }
 
@@ -87,6 +146,8 @@ pub struct Display<T: SurfaceTypeTrait + ResizeableSurface + 'static> {
     context: Rc<context::Context>,
     // The glutin Surface alongside its associated glutin Context.
     gl_context: Rc<RefCell<Option<ContextSurfacePair<T>>>>,
+    // The buffers that frames draw to if multisampling is enabled.
+    multisampling: Rc<RefCell<Option<Rc<FrameMultisampling>>>>,
 }
 
 /// An implementation of the `Backend` trait for glutin.
@@ -122,10 +183,53 @@ impl<T: SurfaceTypeTrait + ResizeableSurface> Display<T> {
         Self::from_context_surface(context, surface).map_err(From::from)
     }
 
-    /// Create a new glium `Display` from the given context and surface.
+    /// Create a new glium `Display` from a context and a surface that have already been created
+    /// with glutin.
+    ///
+    /// This is the way to go when `SimpleWindowBuilder` isn't flexible enough, for example to
+    /// pick the configuration with custom logic or to request context attributes that it doesn't
+    /// expose. The context must be current on the surface. This is the same as `new`, except
+    /// for the error type.
     ///
     /// Performs a compatibility check to make sure that all core elements of glium are supported
     /// by the implementation.
+    ///
+    /// ```no_run
+    /// use glium::backend::glutin::glutin;
+    /// use glutin::config::ConfigTemplateBuilder;
+    /// use glutin::context::ContextAttributesBuilder;
+    /// use glutin::display::GetGlDisplay;
+    /// use glutin::prelude::*;
+    /// use glutin::surface::{SurfaceAttributesBuilder, WindowSurface};
+    /// use raw_window_handle::HasRawWindowHandle;
+    /// use std::num::NonZeroU32;
+    ///
+    /// let event_loop = winit::event_loop::EventLoopBuilder::new().build().unwrap();
+    /// let (window, config) = glutin_winit::DisplayBuilder::new()
+    ///     .with_window_builder(Some(winit::window::WindowBuilder::new()))
+    ///     .build(&event_loop, ConfigTemplateBuilder::new(), |configs| {
+    ///         // the configuration with the most samples, among those without sRGB
+    ///         configs.filter(|c| !c.srgb_capable()).max_by_key(|c| c.num_samples()).unwrap()
+    ///     })
+    ///     .unwrap();
+    /// let window = window.unwrap();
+    ///
+    /// let (width, height): (u32, u32) = window.inner_size().into();
+    /// let attributes = SurfaceAttributesBuilder::<WindowSurface>::new().build(
+    ///     window.raw_window_handle(),
+    ///     NonZeroU32::new(width).unwrap(),
+    ///     NonZeroU32::new(height).unwrap(),
+    /// );
+    /// let gl_display = config.display();
+    /// let surface = unsafe { gl_display.create_window_surface(&config, &attributes) }.unwrap();
+    /// let attributes = ContextAttributesBuilder::new().build(Some(window.raw_window_handle()));
+    /// let context = unsafe { gl_display.create_context(&config, &attributes) }
+    ///     .unwrap()
+    ///     .make_current(&surface)
+    ///     .unwrap();
+    ///
+    /// let display = glium::Display::from_context_surface(context, surface).unwrap();
+    /// ```
     pub fn from_context_surface(
         context: PossiblyCurrentContext,
         surface: Surface<T>,
@@ -135,7 +239,7 @@ impl<T: SurfaceTypeTrait + ResizeableSurface> Display<T> {
 
     /// Create a new glium `Display` from the given context and surface.
     ///
-    /// This function does the same as `build_glium`, except that the resulting context
+    /// This function does the same as `from_context_surface`, except that the resulting context
     /// will assume that the current OpenGL context will never change.
     pub unsafe fn unchecked(
         context: PossiblyCurrentContext,
@@ -175,13 +279,33 @@ impl<T: SurfaceTypeTrait + ResizeableSurface> Display<T> {
         Ok(Display {
             gl_context: gl_window,
             context,
+            multisampling: Rc::new(RefCell::new(None)),
         })
     }
 
     /// Resize the underlying surface.
+    ///
+    /// The render targets that have been registered with `framebuffer::WindowSized` are
+    /// rebuilt with the new dimensions.
     #[inline]
     pub fn resize(&self, new_size: (u32, u32)) {
-        self.gl_context.borrow().as_ref().unwrap().resize(new_size)
+        self.context.resize(new_size)
+    }
+
+    /// Changes the swap interval of the window, for example to turn vsync on or off from a
+    /// settings menu without recreating the window.
+    ///
+    /// `SwapInterval::Wait(1)` synchronizes the buffer swaps with the refresh rate of the
+    /// monitor, while `SwapInterval::DontWait` swaps the buffers as soon as a frame is
+    /// finished. Adaptive vsync (a swap interval of `-1`) is not exposed by glutin.
+    ///
+    /// Returns an error if the platform doesn't allow changing the swap interval.
+    pub fn set_swap_interval(&self, interval: glutin::surface::SwapInterval)
+                             -> Result<(), glutin::error::Error>
+    {
+        // glutin requires the context to be current
+        let _ctxt = self.context.make_current();
+        self.gl_context.borrow().as_ref().unwrap().set_swap_interval(interval)
     }
 
     /// Start drawing on the backbuffer.
@@ -190,10 +314,317 @@ impl<T: SurfaceTypeTrait + ResizeableSurface> Display<T> {
     /// destroyed, the buffers are swapped.
     ///
     /// Note that destroying a `Frame` is immediate, even if vsync is enabled.
-    #[inline]
     pub fn draw(&self) -> Frame {
         let dimensions = self.get_framebuffer_dimensions();
-        Frame::new(self.context.clone(), dimensions)
+
+        let mut multisampling = self.multisampling.borrow_mut();
+        let multisampling = match *multisampling {
+            Some(ref mut m) => {
+                if m.get_dimensions() != (dimensions.0.max(1), dimensions.1.max(1)) {
+                    // the number of samples has already been checked
+                    *m = Rc::new(FrameMultisampling::new(&self.context, dimensions,
+                                                         m.get_samples()).unwrap());
+                }
+                m.clone()
+            },
+            None => return Frame::new(self.context.clone(), dimensions),
+        };
+
+        Frame::with_multisampling(self.context.clone(), multisampling)
+    }
+
+    /// Makes the frames returned by `draw` render to multisampled buffers with the given number
+    /// of samples, or to the window directly if `None`.
+    ///
+    /// The buffers have a color, a depth and a stencil attachment, and follow the dimensions of
+    /// the window. Their color attachment is resolved to the back buffer when a frame is
+    /// finished. This is an alternative to choosing a multisampled surface configuration,
+    /// which isn't available everywhere. `SimpleWindowBuilder::with_multisampling` does this
+    /// automatically.
+    ///
+    /// While multisampling is enabled, the display can't be moved to another thread with
+    /// `SendContext`.
+    pub fn set_multisampling(&self, samples: Option<u32>)
+                             -> Result<(), framebuffer::FrameMultisamplingError>
+    {
+        let multisampling = match samples {
+            Some(samples) => {
+                let dimensions = self.get_framebuffer_dimensions();
+                Some(Rc::new(FrameMultisampling::new(&self.context, dimensions, samples)?))
+            },
+            None => None,
+        };
+
+        *self.multisampling.borrow_mut() = multisampling;
+        Ok(())
+    }
+
+    /// Returns the number of samples of the buffers that frames render to, or `None` if they
+    /// render to the window directly. See `set_multisampling`.
+    #[inline]
+    pub fn get_multisampling(&self) -> Option<u32> {
+        self.multisampling.borrow().as_ref().map(|m| m.get_samples())
+    }
+
+    /// Destroys the window surface while keeping the OpenGL context and all the objects created
+    /// with it.
+    ///
+    /// This must be called when the native window is about to be destroyed, for example from
+    /// `ApplicationHandler::suspended` on Android. Call `recreate_surface` once a new window is
+    /// available.
+    ///
+    /// If the configuration supports pbuffers, the context stays current on a 1x1 surface and
+    /// glium can still be used normally, although frames aren't presented anywhere. Otherwise
+    /// the context is released, and any use of the display, including dropping the objects
+    /// created with it, panics until the surface is recreated.
+    ///
+    /// Does nothing if the surface has already been destroyed.
+    pub fn destroy_surface(&self) {
+        if !self.has_surface() {
+            return;
+        }
+
+        // the commands that draw to the window must be submitted before it disappears
+        self.context.flush();
+
+        let mut pair = self.gl_context.borrow_mut();
+        let ContextSurfacePair { context, surface, .. } = pair.take().unwrap();
+
+        let config = context.config();
+        let attributes = glutin::surface::SurfaceAttributesBuilder::<PbufferSurface>::new()
+            .build(NonZeroU32::new(1).unwrap(), NonZeroU32::new(1).unwrap());
+        let placeholder = unsafe { config.display().create_pbuffer_surface(&config, &attributes) }
+            .ok()
+            .filter(|placeholder| context.make_current(placeholder).is_ok());
+
+        let context = match placeholder {
+            Some(_) => context,
+            None => context.make_not_current()
+                .expect("failed to release the OpenGL context")
+                .treat_as_possibly_current(),
+        };
+
+        // the window surface is destroyed once it isn't current anymore
+        drop(surface);
+
+        *pair = Some(ContextSurfacePair { context, surface: None, placeholder });
+    }
+
+    /// Returns false if the window surface has been destroyed with `destroy_surface` and not
+    /// recreated yet.
+    #[inline]
+    pub fn has_surface(&self) -> bool {
+        self.gl_context.borrow().as_ref().unwrap().has_surface()
+    }
+}
+
+impl Display<glutin::surface::WindowSurface> {
+    /// Creates a new surface for the window after `destroy_surface`, for example from
+    /// `ApplicationHandler::resumed` on Android, and makes the context current on it.
+    ///
+    /// The window doesn't need to be the one the display was created with, but must be
+    /// compatible with the configuration of the context.
+    #[cfg(feature = "simple_window_builder")]
+    pub fn recreate_surface(&self, window: &winit::window::Window)
+                            -> Result<(), glutin::error::Error>
+    {
+        use glutin::surface::{SurfaceAttributesBuilder, WindowSurface};
+        use raw_window_handle::HasRawWindowHandle;
+
+        let (width, height): (u32, u32) = window.inner_size().into();
+        let attributes = SurfaceAttributesBuilder::<WindowSurface>::new()
+            .build(
+                window.raw_window_handle(),
+                NonZeroU32::new(width.max(1)).unwrap(),
+                NonZeroU32::new(height.max(1)).unwrap(),
+            );
+        unsafe { self.recreate_surface_with_attributes(&attributes) }
+    }
+
+    /// The same as `recreate_surface`, for windows that haven't been created with winit.
+    ///
+    /// # Safety
+    ///
+    /// The window of the attributes must be valid, and must outlive the display or the next
+    /// call to `destroy_surface`.
+    pub unsafe fn recreate_surface_with_attributes(
+        &self,
+        attributes: &glutin::surface::SurfaceAttributes<glutin::surface::WindowSurface>,
+    ) -> Result<(), glutin::error::Error> {
+        let dimensions = {
+            let mut pair = self.gl_context.borrow_mut();
+            let pair = pair.as_mut().unwrap();
+
+            let config = pair.context.config();
+            let surface = config.display().create_window_surface(&config, attributes)?;
+            pair.context.make_current(&surface)?;
+
+            pair.surface = Some(surface);
+            pair.placeholder = None;
+            pair.get_framebuffer_dimensions()
+        };
+
+        // the render targets that follow the dimensions of the window are rebuilt
+        self.context.resize(dimensions);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "raw_window_handle_display")]
+impl Display<glutin::surface::WindowSurface> {
+    /// Creates the glutin display, configuration, context and surface for a window created by
+    /// any windowing library that supports `raw-window-handle`, then builds a glium `Display`.
+    ///
+    /// The first configuration that matches `template` and is compatible with the window is
+    /// used. `dimensions` is the current size of the window in pixels. The display uses EGL on
+    /// Linux and other Unix systems, WGL on Windows and CGL on macOS.
+    ///
+    /// ```no_run
+    /// # fn example(window_handle: raw_window_handle::RawWindowHandle,
+    /// #            display_handle: raw_window_handle::RawDisplayHandle) {
+    /// let template = glium::glutin::config::ConfigTemplateBuilder::new();
+    /// let display = unsafe {
+    ///     glium::Display::from_raw_window_handle(window_handle, display_handle, template,
+    ///                                            (800, 600))
+    /// }.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The handles must be valid, and the window must outlive the returned display.
+    pub unsafe fn from_raw_window_handle(
+        window_handle: raw_window_handle::RawWindowHandle,
+        display_handle: raw_window_handle::RawDisplayHandle,
+        template: glutin::config::ConfigTemplateBuilder,
+        dimensions: (u32, u32),
+    ) -> Result<Self, DisplayCreationError> {
+        use glutin::context::{ContextApi, ContextAttributesBuilder};
+        use glutin::display::DisplayApiPreference;
+        use glutin::error::{Error as GlutinError, ErrorKind};
+        use glutin::surface::{SurfaceAttributesBuilder, WindowSurface};
+
+        #[cfg(target_vendor = "apple")]
+        let preference = DisplayApiPreference::Cgl;
+        #[cfg(windows)]
+        let preference = DisplayApiPreference::Wgl(Some(window_handle));
+        #[cfg(not(any(windows, target_vendor = "apple")))]
+        let preference = DisplayApiPreference::Egl;
+
+        let gl_display = glutin::display::Display::new(display_handle, preference)?;
+
+        let template = template.compatible_with_native_window(window_handle).build();
+        let config = gl_display.find_configs(template)?
+            .next()
+            .ok_or_else(|| GlutinError::from(ErrorKind::BadConfig))?;
+
+        // some drivers only support OpenGL ES
+        let context = gl_display
+            .create_context(&config, &ContextAttributesBuilder::new().build(Some(window_handle)))
+            .or_else(|_| {
+                let attributes = ContextAttributesBuilder::new()
+                    .with_context_api(ContextApi::Gles(None))
+                    .build(Some(window_handle));
+                gl_display.create_context(&config, &attributes)
+            })?;
+
+        let attributes = SurfaceAttributesBuilder::<WindowSurface>::new().build(
+            window_handle,
+            NonZeroU32::new(dimensions.0.max(1)).unwrap(),
+            NonZeroU32::new(dimensions.1.max(1)).unwrap(),
+        );
+        let surface = gl_display.create_window_surface(&config, &attributes)?;
+        let context = context.make_current(&surface)?;
+
+        Display::new(context, surface)
+    }
+}
+
+/// A `Display` whose OpenGL context has been released, so that it can be sent to another thread.
+///
+/// OpenGL contexts can only be current on one thread at a time, and glium panics in debug builds
+/// when a context is used from a thread other than the one it was made current on. Wrapping the
+/// display in a `SendContext` makes the glutin context not current, and `into_display` makes it
+/// current again on the receiving thread.
+///
+/// ```no_run
+/// # fn example(display: glium::Display<glium::glutin::surface::WindowSurface>) {
+/// let context = glium::backend::glutin::SendContext::new(display)
+///     .unwrap_or_else(|_| panic!("the display is still in use"));
+///
+/// std::thread::spawn(move || {
+///     let display = context.into_display();
+///     let frame = display.draw();
+///     frame.finish().unwrap();
+/// });
+/// # }
+/// ```
+pub struct SendContext<T: SurfaceTypeTrait + ResizeableSurface + 'static> {
+    display: Display<T>,
+    // the released glutin context, always `Some` except while being dropped
+    released: Option<(NotCurrentContext, Surface<T>)>,
+}
+
+// Safety: `SendContext::new` checks that nothing else references the glium context, and the
+// glutin context and surface are `Send` once the context isn't current anymore.
+unsafe impl<T: SurfaceTypeTrait + ResizeableSurface + 'static> Send for SendContext<T>
+    where Surface<T>: Send {}
+
+impl<T: SurfaceTypeTrait + ResizeableSurface> SendContext<T> {
+    /// Releases the OpenGL context of the display from the current thread.
+    ///
+    /// Returns the display back if it can't be moved to another thread. This is the case if
+    /// the display has been cloned, if an object created from it (a buffer, a texture, a frame,
+    /// etc.) is still alive, if it was built with a `DebugCallbackBehavior::Custom` callback, or
+    /// if its surface has been destroyed with `Display::destroy_surface`.
+    pub fn new(display: Display<T>) -> Result<SendContext<T>, Display<T>> {
+        if !display.context.is_movable() || Rc::strong_count(&display.gl_context) != 2 ||
+           !display.has_surface()
+        {
+            return Err(display);
+        }
+
+        display.context.flush();
+
+        let ContextSurfacePair { context, surface, .. } = display.gl_context.borrow_mut()
+            .take().unwrap();
+        let surface = surface.unwrap();
+        let context = context.make_not_current()
+            .expect("failed to release the OpenGL context");
+
+        Ok(SendContext { display, released: Some((context, surface)) })
+    }
+
+    /// Makes the OpenGL context current on the calling thread and returns the display.
+    pub fn into_display(mut self) -> Display<T> {
+        self.restore();
+        Display {
+            context: self.display.context.clone(),
+            gl_context: self.display.gl_context.clone(),
+            multisampling: self.display.multisampling.clone(),
+        }
+    }
+
+    fn restore(&mut self) {
+        if let Some((context, surface)) = self.released.take() {
+            let context = context.make_current(&surface)
+                .expect("failed to make the OpenGL context current");
+            *self.display.gl_context.borrow_mut() = Some(ContextSurfacePair::new(context, surface));
+            self.display.context.set_owner_thread();
+        }
+    }
+}
+
+impl<T: SurfaceTypeTrait + ResizeableSurface> Drop for SendContext<T> {
+    fn drop(&mut self) {
+        // the glium context needs a current OpenGL context to destroy its objects
+        self.restore();
+    }
+}
+
+impl<T: SurfaceTypeTrait + ResizeableSurface> std::fmt::Debug for SendContext<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[glium::backend::glutin::SendContext]")
     }
 }
 
@@ -262,6 +693,21 @@ unsafe impl<T: SurfaceTypeTrait + ResizeableSurface> Backend for GlutinBackend<T
         }
     }
 
+    #[inline]
+    fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        let damage: Vec<_> = damage.iter()
+            .map(|r| {
+                glutin::surface::Rect::new(r.left as i32, r.bottom as i32,
+                                           r.width as i32, r.height as i32)
+            })
+            .collect();
+
+        match self.borrow().as_ref().unwrap().swap_buffers_with_damage(&damage) {
+            Ok(()) => Ok(()),
+            _ => Err(SwapBuffersError::ContextLost),
+        }
+    }
+
     #[inline]
     unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
         let symbol = CString::new(symbol).unwrap();
@@ -293,12 +739,7 @@ unsafe impl<T: SurfaceTypeTrait + ResizeableSurface> Backend for GlutinBackend<T
 
     #[inline]
     unsafe fn make_current(&self) {
-        let pair = self.borrow();
-        pair.as_ref()
-            .unwrap()
-            .context
-            .make_current(&pair.as_ref().unwrap().surface)
-            .unwrap();
+        self.borrow().as_ref().unwrap().make_current().unwrap();
     }
 }
 
@@ -306,6 +747,49 @@ unsafe impl<T: SurfaceTypeTrait + ResizeableSurface> Backend for GlutinBackend<T
 /// Builder to simplify glium/glutin context creation.
 pub struct SimpleWindowBuilder {
     builder: winit::window::WindowBuilder,
+    multisampling: Option<u8>,
+    robustness: glutin::context::Robustness,
+    depth_bits: Option<u8>,
+    stencil_bits: Option<u8>,
+    srgb: Option<bool>,
+    transparent: bool,
+    color_buffer: Option<ColorBufferFormat>,
+    swap_interval: Option<glutin::surface::SwapInterval>,
+}
+
+#[cfg(feature = "simple_window_builder")]
+/// Format of the color buffer requested with [`SimpleWindowBuilder::with_color_buffer`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorBufferFormat {
+    /// 8 bits per component, with an alpha channel.
+    Rgba8,
+    /// 10 bits per color component and 2 bits of alpha, for displays with a higher bit depth.
+    Rgb10A2,
+    /// 16 bits floating-point components, for HDR output.
+    Float16,
+}
+
+#[cfg(feature = "simple_window_builder")]
+impl ColorBufferFormat {
+    fn matches(&self, config: &glutin::config::Config) -> bool {
+        use glutin::config::ColorBufferType;
+
+        let rgb = |size| config.color_buffer_type() == Some(ColorBufferType::Rgb {
+            r_size: size,
+            g_size: size,
+            b_size: size,
+        });
+
+        match *self {
+            ColorBufferFormat::Rgba8 => {
+                !config.float_pixels() && rgb(8) && config.alpha_size() == 8
+            },
+            ColorBufferFormat::Rgb10A2 => {
+                !config.float_pixels() && rgb(10) && config.alpha_size() == 2
+            },
+            ColorBufferFormat::Float16 => config.float_pixels() && rgb(16),
+        }
+    }
 }
 
 #[cfg(feature = "simple_window_builder")]
@@ -316,6 +800,14 @@ impl SimpleWindowBuilder {
             builder: winit::window::WindowBuilder::new()
                 .with_title("Simple Glium Window")
                 .with_inner_size(winit::dpi::PhysicalSize::new(800, 480)),
+            multisampling: None,
+            robustness: Default::default(),
+            depth_bits: None,
+            stencil_bits: None,
+            srgb: None,
+            transparent: false,
+            color_buffer: None,
+            swap_interval: None,
         }
     }
 
@@ -334,6 +826,98 @@ impl SimpleWindowBuilder {
         self
     }
 
+    /// Requests multisampling with the given number of samples.
+    ///
+    /// A surface configuration with at least this many samples is used if there is one.
+    /// Otherwise the frames render to multisampled buffers that are resolved to the window when
+    /// they are finished, see [`Display::set_multisampling`]. If neither is supported, the
+    /// window is not multisampled.
+    pub fn with_multisampling(mut self, samples: u8) -> Self {
+        self.multisampling = Some(samples);
+        self
+    }
+
+    /// Requests a depth buffer with at least the given number of bits.
+    pub fn with_depth_buffer(mut self, bits: u8) -> Self {
+        self.depth_bits = Some(bits);
+        self
+    }
+
+    /// Requests a stencil buffer with at least the given number of bits.
+    pub fn with_stencil_buffer(mut self, bits: u8) -> Self {
+        self.stencil_bits = Some(bits);
+        self
+    }
+
+    /// Requests a surface that is, or isn't, capable of converting the output of the fragment
+    /// shaders to sRGB.
+    pub fn with_srgb(mut self, srgb: bool) -> Self {
+        self.srgb = Some(srgb);
+        self
+    }
+
+    /// Requests a window whose alpha channel is used to blend it with what is behind it.
+    pub fn with_transparency(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Requests a specific format for the color buffer.
+    ///
+    /// If no configuration supports it, the closest one is used. The format that was actually
+    /// obtained is described by the `color_bits`, `float_color` and `srgb` fields of the
+    /// capabilities of the display:
+    ///
+    /// ```no_run
+    /// use glium::CapabilitiesSource;
+    /// use glium::backend::glutin::{ColorBufferFormat, SimpleWindowBuilder};
+    ///
+    /// let event_loop = winit::event_loop::EventLoopBuilder::new().build().unwrap();
+    /// let (_window, display) = SimpleWindowBuilder::new()
+    ///     .with_color_buffer(ColorBufferFormat::Float16)
+    ///     .build(&event_loop);
+    ///
+    /// let hdr = display.get_capabilities().float_color;
+    /// ```
+    pub fn with_color_buffer(mut self, format: ColorBufferFormat) -> Self {
+        self.color_buffer = Some(format);
+        self
+    }
+
+    /// Sets the swap interval of the surface, which controls vsync.
+    ///
+    /// Some platforms ignore it. If this is not set, the default of the platform is used.
+    pub fn with_swap_interval(mut self, interval: glutin::surface::SwapInterval) -> Self {
+        self.swap_interval = Some(interval);
+        self
+    }
+
+    /// Requests a context with the given robustness.
+    ///
+    /// With [`Robustness::RobustLoseContextOnReset`](glutin::context::Robustness), out-of-bounds
+    /// accesses from shaders can't crash the process, and a context that the driver had to reset
+    /// reports it through [`Context::get_reset_status`](crate::backend::Context::get_reset_status).
+    /// Use [`Context::is_robust`](crate::backend::Context::is_robust) to check what has been
+    /// obtained.
+    ///
+    /// [`Robustness::NoError`](glutin::context::Robustness) requests a `GL_KHR_no_error`
+    /// context, which skips error checking in the driver and in glium:
+    ///
+    /// ```no_run
+    /// use glium::backend::glutin::glutin::context::Robustness;
+    ///
+    /// let robustness = if cfg!(debug_assertions) {
+    ///     Robustness::NotRobust
+    /// } else {
+    ///     Robustness::NoError
+    /// };
+    /// let builder = glium::backend::glutin::SimpleWindowBuilder::new().with_robustness(robustness);
+    /// ```
+    pub fn with_robustness(mut self, robustness: glutin::context::Robustness) -> Self {
+        self.robustness = robustness;
+        self
+    }
+
     /// Replace the used [`WindowBuilder`](winit::window::WindowBuilder),
     /// do this before you set other parameters or you'll overwrite the parameters.
     pub fn set_window_builder(mut self, window_builder: winit::window::WindowBuilder) -> Self {
@@ -354,18 +938,75 @@ impl SimpleWindowBuilder {
     ) -> (
         winit::window::Window,
         Display<glutin::surface::WindowSurface>,
+    ) {
+        let (window, display, _) = self.build_with_config(event_loop);
+        (window, display)
+    }
+
+    /// The same as `build`, but also returns the surface configuration that has been selected.
+    ///
+    /// The requested properties are preferences: the first configuration that satisfies the
+    /// most of them is used. The returned configuration tells which ones have been obtained.
+    ///
+    /// ```no_run
+    /// use glium::backend::glutin::glutin::config::GlConfig;
+    /// use glium::backend::glutin::{ColorBufferFormat, SimpleWindowBuilder};
+    ///
+    /// let event_loop = winit::event_loop::EventLoopBuilder::new().build().unwrap();
+    /// let (window, display, config) = SimpleWindowBuilder::new()
+    ///     .with_depth_buffer(24)
+    ///     .with_color_buffer(ColorBufferFormat::Rgb10A2)
+    ///     .build_with_config(&event_loop);
+    ///
+    /// if config.depth_size() < 24 {
+    ///     println!("only {} bits of depth", config.depth_size());
+    /// }
+    /// ```
+    pub fn build_with_config<T>(
+        self,
+        event_loop: &winit::event_loop::EventLoop<T>,
+    ) -> (
+        winit::window::Window,
+        Display<glutin::surface::WindowSurface>,
+        glutin::config::Config,
     ) {
         use glutin::prelude::*;
         use raw_window_handle::HasRawWindowHandle;
 
         // First we start by opening a new Window
+        let window_builder = self.builder.with_transparent(self.transparent);
         let display_builder =
-            glutin_winit::DisplayBuilder::new().with_window_builder(Some(self.builder));
+            glutin_winit::DisplayBuilder::new().with_window_builder(Some(window_builder));
         let config_template_builder = glutin::config::ConfigTemplateBuilder::new();
+
+        let multisampling = self.multisampling;
+        let depth_bits = self.depth_bits;
+        let stencil_bits = self.stencil_bits;
+        let srgb = self.srgb;
+        let transparent = self.transparent;
+        let color_buffer = self.color_buffer;
         let (window, gl_config) = display_builder
-            .build(&event_loop, config_template_builder, |mut configs| {
-                // Just use the first configuration since we don't have any special preferences here
-                configs.next().unwrap()
+            .build(&event_loop, config_template_builder, |configs| {
+                // Use the first configuration that satisfies the most of the requested
+                // properties, no configuration being guaranteed to satisfy them all
+                let score = |config: &glutin::config::Config| {
+                    [
+                        multisampling.map(|samples| config.num_samples() >= samples),
+                        depth_bits.map(|bits| config.depth_size() >= bits),
+                        stencil_bits.map(|bits| config.stencil_size() >= bits),
+                        srgb.map(|srgb| config.srgb_capable() == srgb),
+                        Some(!transparent || config.supports_transparency() == Some(true)),
+                        color_buffer.map(|format| format.matches(config)),
+                    ].iter().filter(|&&satisfied| satisfied == Some(true)).count()
+                };
+
+                configs.fold(None, |best: Option<(glutin::config::Config, usize)>, config| {
+                    let config_score = score(&config);
+                    match best {
+                        Some((_, best_score)) if best_score >= config_score => best,
+                        _ => Some((config, config_score)),
+                    }
+                }).unwrap().0
             })
             .unwrap();
         let window = window.unwrap();
@@ -374,6 +1015,7 @@ impl SimpleWindowBuilder {
         let (width, height): (u32, u32) = window.inner_size().into();
         let attrs =
             glutin::surface::SurfaceAttributesBuilder::<glutin::surface::WindowSurface>::new()
+                .with_srgb(self.srgb)
                 .build(
                     window.raw_window_handle(),
                     NonZeroU32::new(width).unwrap(),
@@ -388,6 +1030,7 @@ impl SimpleWindowBuilder {
                 .unwrap()
         };
         let context_attributes = glutin::context::ContextAttributesBuilder::new()
+            .with_robustness(self.robustness)
             .build(Some(window.raw_window_handle()));
         let current_context = Some(unsafe {
             gl_config
@@ -398,8 +1041,21 @@ impl SimpleWindowBuilder {
         .unwrap()
         .make_current(&surface)
         .unwrap();
+
+        if let Some(interval) = self.swap_interval {
+            // not supported everywhere, in which case the default of the platform is kept
+            let _ = surface.set_swap_interval(&current_context, interval);
+        }
+
         let display = Display::from_context_surface(current_context, surface).unwrap();
 
-        (window, display)
+        if let Some(samples) = self.multisampling {
+            if gl_config.num_samples() < samples {
+                // not supported by the surface, falling back to multisampled buffers
+                let _ = display.set_multisampling(Some(samples as u32));
+            }
+        }
+
+        (window, display, gl_config)
     }
 }
@@ -0,0 +1,180 @@
+/*!
+
+Offscreen displays rendering to a pbuffer.
+
+A pbuffer is a surface that isn't attached to any window. This is useful for generating
+thumbnails or baking textures in a background job that shouldn't open a window. Unlike the
+`headless` backend, it works with every glutin API that supports pbuffers, including GLX and
+WGL.
+
+```no_run
+# fn example(gl_display: glium::glutin::display::Display) {
+use glium::Surface;
+
+let display = glium::backend::glutin::pbuffer::PbufferDisplay::from_display(&gl_display, 256, 256)
+    .unwrap();
+
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 0.0, 1.0);
+frame.finish().unwrap();
+# }
+```
+
+Nothing is ever presented, and pbuffers can't be resized. To get the result, render to a texture
+with a `SimpleFrameBuffer` and read the texture, which is more portable than reading from the
+pbuffer itself.
+
+*/
+use glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+use glutin::context::{ContextApi, ContextAttributesBuilder, PossiblyCurrentContext};
+use glutin::display::GetGlDisplay;
+use glutin::error::{Error as GlutinError, ErrorKind};
+use glutin::prelude::*;
+use glutin::surface::{PbufferSurface, Surface, SurfaceAttributesBuilder};
+
+use crate::backend;
+use crate::backend::Backend;
+use crate::backend::Context;
+use crate::debug;
+use crate::Frame;
+use crate::IncompatibleOpenGl;
+use crate::SwapBuffersError;
+use super::DisplayCreationError;
+use std::ffi::CString;
+use std::fmt;
+use std::num::NonZeroU32;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::rc::Rc;
+
+/// A glium context whose default framebuffer is a pbuffer.
+#[derive(Clone)]
+pub struct PbufferDisplay {
+    context: Rc<Context>,
+}
+
+/// An implementation of the `Backend` trait for a glutin context and a pbuffer.
+pub struct PbufferBackend {
+    context: PossiblyCurrentContext,
+    surface: Surface<PbufferSurface>,
+}
+
+impl PbufferDisplay {
+    /// Builds a glium context from a glutin context that is current on a pbuffer.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn new(context: PossiblyCurrentContext, surface: Surface<PbufferSurface>)
+               -> Result<PbufferDisplay, IncompatibleOpenGl>
+    {
+        PbufferDisplay::with_debug(context, surface, Default::default())
+    }
+
+    /// The same as the `new` constructor, but allows for specifying debug callback behaviour.
+    pub fn with_debug(context: PossiblyCurrentContext, surface: Surface<PbufferSurface>,
+                      debug: debug::DebugCallbackBehavior)
+                      -> Result<PbufferDisplay, IncompatibleOpenGl>
+    {
+        let backend = PbufferBackend { context, surface };
+        let context = unsafe { Context::new(backend, true, debug) }?;
+        Ok(PbufferDisplay { context })
+    }
+
+    /// Creates an OpenGL context and a pbuffer of the given dimensions on a glutin display, and
+    /// builds a glium context from them.
+    ///
+    /// The first configuration that supports pbuffers is used. If it doesn't support desktop
+    /// OpenGL, an OpenGL ES context is created instead.
+    pub fn from_display(display: &glutin::display::Display, width: u32, height: u32)
+                        -> Result<PbufferDisplay, DisplayCreationError>
+    {
+        let template = ConfigTemplateBuilder::new()
+            .with_surface_type(ConfigSurfaceTypes::PBUFFER)
+            .build();
+        let config = unsafe { display.find_configs(template) }?
+            .next()
+            .ok_or_else(|| GlutinError::from(ErrorKind::BadConfig))?;
+
+        let context = unsafe {
+            display.create_context(&config, &ContextAttributesBuilder::new().build(None))
+        }.or_else(|_| {
+            let attributes = ContextAttributesBuilder::new()
+                .with_context_api(ContextApi::Gles(None))
+                .build(None);
+            unsafe { display.create_context(&config, &attributes) }
+        })?;
+
+        let attributes = SurfaceAttributesBuilder::<PbufferSurface>::new().build(
+            NonZeroU32::new(width.max(1)).unwrap(),
+            NonZeroU32::new(height.max(1)).unwrap(),
+        );
+        let surface = unsafe { display.create_pbuffer_surface(&config, &attributes) }?;
+        let context = context.make_current(&surface)?;
+
+        PbufferDisplay::new(context, surface).map_err(From::from)
+    }
+
+    /// Start drawing on the pbuffer.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. Finishing the frame
+    /// doesn't present anything.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.get_framebuffer_dimensions())
+    }
+}
+
+impl fmt::Debug for PbufferDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::glutin::pbuffer::PbufferDisplay]")
+    }
+}
+
+impl Deref for PbufferDisplay {
+    type Target = Context;
+    #[inline]
+    fn deref(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for PbufferDisplay {
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+unsafe impl Backend for PbufferBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // pbuffers have no front buffer to present
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        self.context.display().get_proc_address(&symbol) as *const _
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        (self.surface.width().unwrap(), self.surface.height().unwrap())
+    }
+
+    #[inline]
+    fn resize(&self, _: (u32, u32)) {
+        // the dimensions of a pbuffer are fixed at creation
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        self.context.is_current()
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        self.context.make_current(&self.surface).unwrap();
+    }
+}
@@ -0,0 +1,580 @@
+#![cfg(all(feature = "headless", not(any(target_os = "macos", target_os = "ios"))))]
+/*!
+
+Headless backend that doesn't need any window, display server or pbuffer.
+
+The OpenGL context is created with EGL on top of an `EGLDevice` and is made current without
+any surface. Since there is no default framebuffer, a `HeadlessRenderer` draws to a texture
+that it owns. This is useful for tests running on CI machines, for server-side rendering and
+for compute shaders.
+
+# Features
+
+Only available if the 'headless' feature is enabled. Requires the `EGL_EXT_device_base` and
+`EGL_EXT_platform_device` extensions, which are supported by Mesa and by the proprietary
+NVidia driver.
+
+Machines with several GPUs expose one EGL device per GPU. Use `devices` to list them and
+`HeadlessRenderer::with_device` to pick one.
+
+# Software rendering
+
+Mesa exposes its llvmpipe software rasterizer as an additional EGL device, which makes it
+possible to render on machines without any GPU. It is used by `HeadlessRenderer::software`, and
+by `HeadlessRenderer::new` when the `GLIUM_SOFTWARE_RENDERING` environment variable is set to
+anything other than `0`. This is useful for golden-image comparisons, whose results shouldn't
+depend on the GPU of the machine running the tests.
+
+# OpenGL ES 2.0
+
+`HeadlessRenderer::with_feature_level` with `FeatureLevel::Gles2` creates an OpenGL ES context
+on which glium restricts itself to OpenGL ES 2.0, which allows running the tests of an
+application meant for older embedded devices on a development machine.
+
+```no_run
+use glium::Surface;
+
+let renderer = glium::backend::headless::HeadlessRenderer::new(256, 256).unwrap();
+renderer.draw().clear_color(1.0, 0.0, 0.0, 1.0);
+let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+```
+
+*/
+use glutin::api::egl;
+use glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder, GetGlConfig};
+use glutin::context::{ContextApi, ContextAttributesBuilder, Version as GlutinVersion};
+use glutin::display::GetGlDisplay;
+use glutin::prelude::*;
+
+use crate::backend::Backend;
+use crate::backend::Context;
+use crate::backend::Facade;
+use crate::backend::FeatureLevel;
+use crate::backend::upload_thread::UploadThread;
+use crate::debug;
+use crate::framebuffer::{DepthRenderBuffer, RenderBufferCreationError, SimpleFrameBuffer};
+use crate::texture::{DepthFormat, MipmapsOption, Texture2d, TextureCreationError};
+use crate::texture::UncompressedFloatFormat;
+use crate::IncompatibleOpenGl;
+use crate::{Api, Version};
+use crate::SwapBuffersError;
+use std::cell::Cell;
+use std::env;
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::mem;
+use std::ops::Deref;
+use std::os::raw::{c_char, c_void};
+use std::rc::Rc;
+
+/// An implementation of the `Backend` trait for an EGL context without any surface.
+pub struct HeadlessBackend {
+    context: Rc<egl::context::PossiblyCurrentContext>,
+    // there is no surface, so these are the dimensions of the render target of the renderer
+    dimensions: Cell<(u32, u32)>,
+}
+
+/// An EGL device that a headless context can be created on. Usually corresponds to a GPU, but
+/// Mesa also exposes a software rasterizer as a device.
+#[derive(Debug, Clone)]
+pub struct EglDevice {
+    device: egl::device::Device,
+    drm_device_file: Option<String>,
+    drm_render_node_file: Option<String>,
+}
+
+impl EglDevice {
+    /// Returns the name of the device, if the `EGL_EXT_device_query_name` extension is
+    /// supported.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.device.name()
+    }
+
+    /// Returns the vendor of the device, if the `EGL_EXT_device_query_name` extension is
+    /// supported.
+    #[inline]
+    pub fn vendor(&self) -> Option<&str> {
+        self.device.vendor()
+    }
+
+    /// Returns the path of the DRM primary node of the device, for example `/dev/dri/card0`.
+    ///
+    /// Requires the `EGL_EXT_device_drm` extension.
+    #[inline]
+    pub fn drm_device_file(&self) -> Option<&str> {
+        self.drm_device_file.as_deref()
+    }
+
+    /// Returns the path of the DRM render node of the device, for example
+    /// `/dev/dri/renderD128`.
+    ///
+    /// Requires the `EGL_EXT_device_drm_render_node` extension.
+    #[inline]
+    pub fn drm_render_node_file(&self) -> Option<&str> {
+        self.drm_render_node_file.as_deref()
+    }
+
+    /// Returns true if this is a software rasterizer instead of a GPU.
+    #[inline]
+    pub fn is_software(&self) -> bool {
+        self.device.extensions().contains("EGL_MESA_device_software")
+    }
+
+    /// Returns the device extensions supported by this device.
+    #[inline]
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.device.extensions().iter().copied()
+    }
+}
+
+/// Name of the environment variable that makes `HeadlessRenderer::new` use the software
+/// rasterizer.
+pub const SOFTWARE_RENDERING_ENV: &str = "GLIUM_SOFTWARE_RENDERING";
+
+/// Returns true if the `GLIUM_SOFTWARE_RENDERING` environment variable asks for the software
+/// rasterizer.
+fn software_rendering_requested() -> bool {
+    match env::var_os(SOFTWARE_RENDERING_ENV) {
+        Some(value) => !value.is_empty() && value != "0",
+        None => false,
+    }
+}
+
+/// Returns the first device that is a software rasterizer, or `NoDevice` if there is none.
+pub fn software_device() -> Result<EglDevice, HeadlessCreationError> {
+    devices()?.into_iter().find(|d| d.is_software()).ok_or(HeadlessCreationError::NoDevice)
+}
+
+/// Returns the list of EGL devices of the system, in the order reported by
+/// `eglQueryDevicesEXT`.
+///
+/// Requires the `EGL_EXT_device_enumeration` and `EGL_EXT_device_query` extensions.
+pub fn devices() -> Result<Vec<EglDevice>, HeadlessCreationError> {
+    const DRM_DEVICE_FILE: i32 = 0x3233;
+    const DRM_RENDER_NODE_FILE: i32 = 0x3377;
+
+    type QueryDeviceString = unsafe extern "system" fn(*const c_void, i32) -> *const c_char;
+
+    let devices = egl::device::Device::query_devices()?.collect::<Vec<_>>();
+    if devices.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // glutin doesn't expose `eglQueryDeviceStringEXT`. Loading it requires a display, but the
+    // function itself doesn't depend on it.
+    let query_device_string: Option<QueryDeviceString> = {
+        let display = unsafe { egl::display::Display::with_device(&devices[0], None) }?;
+        let symbol = CString::new("eglQueryDeviceStringEXT").unwrap();
+        let ptr = display.get_proc_address(&symbol);
+        if ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { mem::transmute::<*const c_void, QueryDeviceString>(ptr) })
+        }
+    };
+
+    let query = |device: &egl::device::Device, extension: &str, name: i32| -> Option<String> {
+        let query_device_string = query_device_string?;
+        if !device.extensions().contains(extension) {
+            return None;
+        }
+
+        let value = unsafe { query_device_string(device.raw_device(), name) };
+        if value.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(value) }.to_string_lossy().into_owned())
+    };
+
+    Ok(devices.into_iter().map(|device| {
+        EglDevice {
+            drm_device_file: query(&device, "EGL_EXT_device_drm", DRM_DEVICE_FILE),
+            drm_render_node_file: query(&device, "EGL_EXT_device_drm_render_node",
+                                        DRM_RENDER_NODE_FILE),
+            device,
+        }
+    }).collect())
+}
+
+impl HeadlessBackend {
+    /// Creates an OpenGL context on the first EGL device and makes it current. If the
+    /// `GLIUM_SOFTWARE_RENDERING` environment variable is set, the software rasterizer is used
+    /// instead.
+    ///
+    /// If the device doesn't support desktop OpenGL, an OpenGL ES context is created instead.
+    #[inline]
+    pub fn new(dimensions: (u32, u32)) -> Result<HeadlessBackend, HeadlessCreationError> {
+        HeadlessBackend::with_api(dimensions, None)
+    }
+
+    /// The same as `new`, but only creates a context of the given API if it is `Some`.
+    fn with_api(dimensions: (u32, u32), api: Option<ContextApi>)
+                -> Result<HeadlessBackend, HeadlessCreationError>
+    {
+        if software_rendering_requested() {
+            return HeadlessBackend::from_egl_device(&software_device()?.device, dimensions, api);
+        }
+
+        let device = egl::device::Device::query_devices()?
+            .next()
+            .ok_or(HeadlessCreationError::NoDevice)?;
+
+        HeadlessBackend::from_egl_device(&device, dimensions, api)
+    }
+
+    /// Creates an OpenGL context on the given device and makes it current.
+    #[inline]
+    pub fn with_device(device: &EglDevice, dimensions: (u32, u32))
+                       -> Result<HeadlessBackend, HeadlessCreationError>
+    {
+        HeadlessBackend::from_egl_device(&device.device, dimensions, None)
+    }
+
+    fn from_egl_device(device: &egl::device::Device, dimensions: (u32, u32),
+                       api: Option<ContextApi>)
+                       -> Result<HeadlessBackend, HeadlessCreationError>
+    {
+        let display = unsafe { egl::display::Display::with_device(device, None) }?;
+
+        let template = ConfigTemplateBuilder::new()
+            .with_surface_type(ConfigSurfaceTypes::empty())
+            .build();
+        let config = unsafe { display.find_configs(template) }?
+            .next()
+            .ok_or(HeadlessCreationError::NoDevice)?;
+
+        HeadlessBackend::from_config(&config, None, dimensions, api)
+    }
+
+    /// Creates an OpenGL context that shares its objects with another headless context.
+    fn shared(other: &egl::context::PossiblyCurrentContext, dimensions: (u32, u32))
+              -> Result<HeadlessBackend, HeadlessCreationError>
+    {
+        HeadlessBackend::from_config(&other.config(), Some(other), dimensions, None)
+    }
+
+    fn from_config(config: &egl::config::Config,
+                   shared: Option<&egl::context::PossiblyCurrentContext>, dimensions: (u32, u32),
+                   api: Option<ContextApi>)
+                   -> Result<HeadlessBackend, HeadlessCreationError>
+    {
+        let context = HeadlessBackend::create_context(config, shared, api)?;
+        HeadlessBackend::from_context(context, dimensions)
+    }
+
+    /// Creates an OpenGL context without making it current, so that it can be sent to another
+    /// thread.
+    ///
+    /// If `api` is `None`, desktop OpenGL is preferred, unless the context shares its objects
+    /// with another one, in which case it uses the same API.
+    fn create_context(config: &egl::config::Config,
+                      shared: Option<&egl::context::PossiblyCurrentContext>,
+                      api: Option<ContextApi>)
+                      -> Result<egl::context::NotCurrentContext, HeadlessCreationError>
+    {
+        let builder = || match shared {
+            Some(shared) => ContextAttributesBuilder::new().with_sharing(shared),
+            None => ContextAttributesBuilder::new(),
+        };
+
+        let display = config.display();
+        let api = api.or_else(|| shared.map(|shared| shared.context_api()));
+        if let Some(api) = api {
+            let attributes = builder().with_context_api(api).build(None);
+            return Ok(unsafe { display.create_context(config, &attributes) }?);
+        }
+
+        let attributes = builder().build(None);
+        let context = unsafe { display.create_context(config, &attributes) }.or_else(|_| {
+            let attributes = builder().with_context_api(ContextApi::Gles(None)).build(None);
+            unsafe { display.create_context(config, &attributes) }
+        })?;
+
+        Ok(context)
+    }
+
+    fn from_context(context: egl::context::NotCurrentContext, dimensions: (u32, u32))
+                    -> Result<HeadlessBackend, HeadlessCreationError>
+    {
+        Ok(HeadlessBackend {
+            context: Rc::new(context.make_current_surfaceless()?),
+            dimensions: Cell::new(dimensions),
+        })
+    }
+}
+
+unsafe impl Backend for HeadlessBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // nothing is presented
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        self.context.display().get_proc_address(&symbol) as *const _
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.dimensions.get()
+    }
+
+    #[inline]
+    fn resize(&self, new_size: (u32, u32)) {
+        self.dimensions.set(new_size);
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        self.context.is_current()
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        self.context.make_current_surfaceless().unwrap();
+    }
+}
+
+/// A glium context that draws to a texture instead of a window.
+///
+/// The color buffer is an RGBA8 texture and the depth buffer is a 24 bits renderbuffer, both
+/// with the dimensions passed to `new`.
+pub struct HeadlessRenderer {
+    // the render targets must be destroyed before the context
+    color: Texture2d,
+    depth: DepthRenderBuffer,
+    context: Rc<Context>,
+    // the EGL context, kept to create contexts that share objects with this one
+    egl_context: Rc<egl::context::PossiblyCurrentContext>,
+}
+
+/// Error that can happen while creating a `HeadlessRenderer`.
+#[derive(Debug)]
+pub enum HeadlessCreationError {
+    /// An error has happened while creating the EGL context.
+    GlutinError(glutin::error::Error),
+    /// EGL didn't return any device or configuration.
+    NoDevice,
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+    /// Error while creating the color texture.
+    TextureCreationError(TextureCreationError),
+    /// Error while creating the depth buffer.
+    RenderBufferCreationError(RenderBufferCreationError),
+}
+
+impl fmt::Display for HeadlessCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeadlessCreationError::GlutinError(err) => write!(fmt, "{}", err),
+            HeadlessCreationError::NoDevice => write!(fmt, "No EGL device is available"),
+            HeadlessCreationError::IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+            HeadlessCreationError::TextureCreationError(err) => write!(fmt, "{}", err),
+            HeadlessCreationError::RenderBufferCreationError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for HeadlessCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            HeadlessCreationError::GlutinError(ref err) => Some(err),
+            HeadlessCreationError::NoDevice => None,
+            HeadlessCreationError::IncompatibleOpenGl(ref err) => Some(err),
+            HeadlessCreationError::TextureCreationError(ref err) => Some(err),
+            HeadlessCreationError::RenderBufferCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<glutin::error::Error> for HeadlessCreationError {
+    #[inline]
+    fn from(err: glutin::error::Error) -> HeadlessCreationError {
+        HeadlessCreationError::GlutinError(err)
+    }
+}
+
+impl From<IncompatibleOpenGl> for HeadlessCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> HeadlessCreationError {
+        HeadlessCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+impl From<TextureCreationError> for HeadlessCreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> HeadlessCreationError {
+        HeadlessCreationError::TextureCreationError(err)
+    }
+}
+
+impl From<RenderBufferCreationError> for HeadlessCreationError {
+    #[inline]
+    fn from(err: RenderBufferCreationError) -> HeadlessCreationError {
+        HeadlessCreationError::RenderBufferCreationError(err)
+    }
+}
+
+impl HeadlessRenderer {
+    /// Creates a headless context and its render target.
+    #[inline]
+    pub fn new(width: u32, height: u32) -> Result<HeadlessRenderer, HeadlessCreationError> {
+        HeadlessRenderer::with_debug(width, height, Default::default())
+    }
+
+    /// The same as `new`, but allows for specifying debug callback behaviour.
+    pub fn with_debug(width: u32, height: u32, debug: debug::DebugCallbackBehavior)
+                      -> Result<HeadlessRenderer, HeadlessCreationError>
+    {
+        let backend = HeadlessBackend::new((width, height))?;
+        HeadlessRenderer::from_backend(backend, debug, FeatureLevel::Full)
+    }
+
+    /// Creates a headless context whose OpenGL features are restricted, and its render target.
+    ///
+    /// With `FeatureLevel::Gles2`, an OpenGL ES context is created even if the device supports
+    /// desktop OpenGL. See `Context::with_feature_level`.
+    ///
+    /// ```no_run
+    /// use glium::backend::FeatureLevel;
+    /// use glium::backend::headless::HeadlessRenderer;
+    ///
+    /// let renderer = HeadlessRenderer::with_feature_level(256, 256, FeatureLevel::Gles2).unwrap();
+    /// assert!(glium::uniforms::UniformBuffer::new(&renderer, 0u32).is_err());
+    /// ```
+    pub fn with_feature_level(width: u32, height: u32, feature_level: FeatureLevel)
+                              -> Result<HeadlessRenderer, HeadlessCreationError>
+    {
+        let api = match feature_level {
+            FeatureLevel::Full => None,
+            FeatureLevel::Gles2 => Some(ContextApi::Gles(Some(GlutinVersion::new(2, 0)))),
+        };
+
+        let backend = HeadlessBackend::with_api((width, height), api)?;
+        HeadlessRenderer::from_backend(backend, Default::default(), feature_level)
+    }
+
+    /// Creates a headless context and its render target on the software rasterizer, no matter
+    /// which GPUs are available.
+    ///
+    /// Returns `NoDevice` if the EGL implementation doesn't provide a software device.
+    #[inline]
+    pub fn software(width: u32, height: u32) -> Result<HeadlessRenderer, HeadlessCreationError> {
+        HeadlessRenderer::with_device(&software_device()?, width, height, Default::default())
+    }
+
+    /// Creates a headless context on a specific device and its render target.
+    ///
+    /// ```no_run
+    /// use glium::backend::headless::{self, HeadlessRenderer};
+    ///
+    /// let device = headless::devices().unwrap().into_iter()
+    ///     .find(|d| d.drm_render_node_file() == Some("/dev/dri/renderD129"))
+    ///     .unwrap();
+    /// let renderer = HeadlessRenderer::with_device(&device, 256, 256, Default::default());
+    /// ```
+    pub fn with_device(device: &EglDevice, width: u32, height: u32,
+                       debug: debug::DebugCallbackBehavior)
+                       -> Result<HeadlessRenderer, HeadlessCreationError>
+    {
+        let backend = HeadlessBackend::with_device(device, (width, height))?;
+        HeadlessRenderer::from_backend(backend, debug, FeatureLevel::Full)
+    }
+
+    fn from_backend(backend: HeadlessBackend, debug: debug::DebugCallbackBehavior,
+                    feature_level: FeatureLevel)
+                    -> Result<HeadlessRenderer, HeadlessCreationError>
+    {
+        let (width, height) = backend.get_framebuffer_dimensions();
+        let egl_context = backend.context.clone();
+        let context = unsafe { Context::with_feature_level(backend, true, debug, feature_level) }?;
+
+        // OpenGL ES 2.0 has neither sized texture formats nor 24 bits depth renderbuffers
+        let (color, depth) = if *context.get_opengl_version() == Version(Api::GlEs, 2, 0) {
+            (Texture2d::empty(&context, width, height)?,
+             DepthRenderBuffer::new(&context, DepthFormat::I16, width, height)?)
+        } else {
+            (Texture2d::empty_with_format(&context, UncompressedFloatFormat::U8U8U8U8,
+                                          MipmapsOption::NoMipmap, width, height)?,
+             DepthRenderBuffer::new(&context, DepthFormat::I24, width, height)?)
+        };
+
+        Ok(HeadlessRenderer { color, depth, context, egl_context })
+    }
+
+    /// Creates another headless context, on the same device, that shares its buffers, textures
+    /// and programs with this one. It has its own render target.
+    pub fn shared(&self, width: u32, height: u32)
+                  -> Result<HeadlessRenderer, HeadlessCreationError>
+    {
+        let backend = HeadlessBackend::shared(&self.egl_context, (width, height))?;
+        let renderer = HeadlessRenderer::from_backend(backend, Default::default(),
+                                                      self.context.get_feature_level())?;
+        unsafe { renderer.context.share_objects_with(&self.context) };
+        Ok(renderer)
+    }
+
+    /// Spawns an `UploadThread` whose context shares its objects with this one.
+    ///
+    /// The context of the worker is created on the current thread, and made current on the
+    /// worker thread.
+    pub fn upload_thread(&self) -> Result<UploadThread, HeadlessCreationError> {
+        let context = HeadlessBackend::create_context(&self.egl_context.config(),
+                                                      Some(&self.egl_context), None)?;
+
+        Ok(unsafe {
+            UploadThread::new(&self.context, move || {
+                // the worker never draws, so the dimensions don't matter
+                HeadlessBackend::from_context(context, (1, 1)).unwrap()
+            })
+        })
+    }
+
+    /// Returns a framebuffer that draws to the color texture and the depth buffer.
+    ///
+    /// Contrary to `Display::draw`, the content of the previous frame isn't discarded and there
+    /// is nothing to swap. Use `texture` to read the result.
+    #[inline]
+    pub fn draw(&self) -> SimpleFrameBuffer<'_> {
+        SimpleFrameBuffer::with_depth_buffer(&self.context, &self.color, &self.depth).unwrap()
+    }
+
+    /// Returns the texture that `draw` renders to.
+    #[inline]
+    pub fn texture(&self) -> &Texture2d {
+        &self.color
+    }
+
+    /// Returns the dimensions of the render target.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        self.context.get_framebuffer_dimensions()
+    }
+}
+
+impl fmt::Debug for HeadlessRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::headless::HeadlessRenderer]")
+    }
+}
+
+impl Deref for HeadlessRenderer {
+    type Target = Context;
+    #[inline]
+    fn deref(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl Facade for HeadlessRenderer {
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
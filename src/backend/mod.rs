@@ -17,6 +17,7 @@ use std::ops::Deref;
 use std::os::raw::c_void;
 
 use crate::CapabilitiesSource;
+use crate::Rect;
 use crate::SwapBuffersError;
 
 use crate::context::Capabilities;
@@ -24,10 +25,20 @@ use crate::context::ExtensionsList;
 use crate::version::Version;
 
 pub use crate::context::Context;
+pub use crate::context::FeatureLevel;
 pub use crate::context::ReleaseBehavior;
+pub use crate::context::ResetStatus;
+pub use crate::context::ScopedForeignFrame;
 
+#[cfg(all(feature = "drm", target_os = "linux"))]
+pub mod drm;
 #[cfg(feature = "glutin")]
 pub mod glutin;
+#[cfg(feature = "headless")]
+pub mod headless;
+#[cfg(feature = "sdl2")]
+pub mod sdl2;
+pub mod upload_thread;
 
 /// Trait for types that can be used as a backend for a glium context.
 ///
@@ -37,6 +48,16 @@ pub unsafe trait Backend {
     /// Swaps buffers at the end of a frame.
     fn swap_buffers(&self) -> Result<(), SwapBuffersError>;
 
+    /// Swaps buffers at the end of a frame, telling the compositor that only the given regions
+    /// of the surface have changed.
+    ///
+    /// The rectangles are in pixels, with the origin at the bottom left of the surface. The
+    /// default implementation ignores them and calls `swap_buffers`, which is always correct.
+    fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        let _ = damage;
+        self.swap_buffers()
+    }
+
     /// Returns the address of an OpenGL function.
     ///
     /// Supposes that the context has been made current before this function is called.
@@ -60,6 +81,10 @@ unsafe impl<T> Backend for Rc<T> where T: Backend {
         self.deref().swap_buffers()
     }
 
+    fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.deref().swap_buffers_with_damage(damage)
+    }
+
     unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
         self.deref().get_proc_address(symbol)
     }
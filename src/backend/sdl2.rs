@@ -0,0 +1,201 @@
+#![cfg(feature = "sdl2")]
+/*!
+
+Backend implementation for the SDL2 library
+
+This allows engines that already create their windows with SDL2 to use glium without switching
+to another windowing library.
+
+# Features
+
+Only available if the 'sdl2' feature is enabled.
+
+```no_run
+let sdl = glium::backend::sdl2::sdl2::init().unwrap();
+let video = sdl.video().unwrap();
+let window = video.window("glium", 800, 600).opengl().build().unwrap();
+
+let display = glium::backend::sdl2::Display::new(window).unwrap();
+let frame = display.draw();
+frame.finish().unwrap();
+```
+
+*/
+pub use sdl2;
+
+use sdl2::video::{GLContext, SwapInterval, Window};
+
+use crate::backend;
+use crate::backend::Backend;
+use crate::backend::Context;
+use crate::context;
+use crate::debug;
+use crate::ContextExt;
+use crate::SwapBuffersError;
+use crate::{Frame, IncompatibleOpenGl};
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::rc::Rc;
+
+/// A glium context on top of an SDL2 window and its OpenGL context.
+#[derive(Clone)]
+pub struct Display {
+    // contains everything related to the current glium context and its state
+    context: Rc<context::Context>,
+    // the SDL2 window alongside its OpenGL context
+    backend: Rc<Sdl2Backend>,
+}
+
+/// An implementation of the `Backend` trait for SDL2.
+pub struct Sdl2Backend {
+    // the OpenGL context must be destroyed before the window
+    gl_context: GLContext,
+    window: Window,
+}
+
+/// Error that can happen while creating a glium display.
+#[derive(Debug)]
+pub enum DisplayCreationError {
+    /// An error has happened while creating the OpenGL context. SDL2 only reports errors as
+    /// strings.
+    SdlError(String),
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+impl fmt::Debug for Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::sdl2::Display]")
+    }
+}
+
+impl Display {
+    /// Creates an OpenGL context for the window and builds a glium `Display` from it.
+    ///
+    /// The window must have been built with `WindowBuilder::opengl`. The attributes of the
+    /// context, like its version or profile, are taken from `VideoSubsystem::gl_attr`.
+    pub fn new(window: Window) -> Result<Display, DisplayCreationError> {
+        Display::with_debug(window, Default::default())
+    }
+
+    /// The same as the `new` constructor, but allows for specifying debug callback behaviour.
+    pub fn with_debug(window: Window, debug: debug::DebugCallbackBehavior)
+                      -> Result<Display, DisplayCreationError>
+    {
+        let gl_context = window.gl_create_context().map_err(DisplayCreationError::SdlError)?;
+        Display::from_window_context(window, gl_context, debug).map_err(From::from)
+    }
+
+    /// Builds a glium `Display` from a window and an OpenGL context that has already been
+    /// created for it.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn from_window_context(window: Window, gl_context: GLContext,
+                               debug: debug::DebugCallbackBehavior)
+                               -> Result<Display, IncompatibleOpenGl>
+    {
+        let backend = Rc::new(Sdl2Backend { gl_context, window });
+        let context = unsafe { context::Context::new(backend.clone(), true, debug) }?;
+        Ok(Display { context, backend })
+    }
+
+    /// Returns the SDL2 window.
+    #[inline]
+    pub fn window(&self) -> &Window {
+        &self.backend.window
+    }
+
+    /// Sets the swap interval of the OpenGL context.
+    pub fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), String> {
+        let _ctxt = self.context.make_current();
+        self.backend.window.subsystem().gl_set_swap_interval(interval)
+    }
+
+    /// Start drawing on the backbuffer.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. When the `Frame` is
+    /// destroyed, the buffers are swapped.
+    ///
+    /// Note that destroying a `Frame` is immediate, even if vsync is enabled.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.get_framebuffer_dimensions())
+    }
+}
+
+impl fmt::Display for DisplayCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            DisplayCreationError::SdlError(err) => write!(fmt, "{}", err),
+            DisplayCreationError::IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for DisplayCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            DisplayCreationError::SdlError(_) => None,
+            DisplayCreationError::IncompatibleOpenGl(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for DisplayCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> DisplayCreationError {
+        DisplayCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+impl Deref for Display {
+    type Target = Context;
+    #[inline]
+    fn deref(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for Display {
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+unsafe impl Backend for Sdl2Backend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        self.window.gl_swap_window();
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        self.window.subsystem().gl_get_proc_address(symbol) as *const _
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.window.drawable_size()
+    }
+
+    #[inline]
+    fn resize(&self, _: (u32, u32)) {
+        // SDL2 resizes the drawable along with the window
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        self.gl_context.is_current()
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        self.window.gl_make_current(&self.gl_context).unwrap();
+    }
+}
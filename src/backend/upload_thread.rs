@@ -0,0 +1,427 @@
+/*!
+
+Creating resources on a background thread.
+
+Uploading a large texture or compiling a program can stall the rendering thread for several
+frames. An `UploadThread` owns a hidden OpenGL context that shares its objects with the main
+one, and that is current on a worker thread. Textures and buffers are created and filled by
+this thread, and programs are compiled and linked there.
+
+Each request returns a future that must be polled on the rendering thread. Once the worker has
+finished, it places a fence after its commands and hands the objects over to the main context.
+The future resolves when the fence has been signaled, so the objects can be used right away.
+
+```no_run
+# fn example(uploads: glium::backend::upload_thread::UploadThread) {
+# fn block_on<F: std::future::Future>(_: F) -> F::Output { unimplemented!() }
+let image = glium::texture::RawImage2d::from_raw_rgba(vec![0u8; 4 * 512 * 512], (512, 512));
+let texture = uploads.texture_2d(image, glium::texture::UncompressedFloatFormat::U8U8U8U8, true);
+let texture = block_on(texture).unwrap();
+# }
+```
+
+Fences can't notify a waker, so a future whose commands are still being executed by the GPU asks
+to be polled again immediately.
+
+Programs are compiled by the worker, then rebuilt from their binary on the rendering thread,
+which is much faster than compiling them. If the implementation doesn't support program
+binaries, the program is compiled again on the rendering thread.
+
+*/
+use crate::backend::Backend;
+use crate::backend::Context;
+use crate::buffer::{Buffer, BufferCreationError, BufferMode, BufferType, Content, RawAlloc};
+use crate::debug::DebugCallbackBehavior;
+use crate::program::{Program, ProgramCreationError, ProgramCreationInput};
+use crate::sync::{self, LinearSyncFence, SyncFence};
+use crate::texture::{MipmapsOption, RawImage2d, Texture2d, TextureCreationError};
+use crate::texture::{Dimensions, UncompressedFloatFormat};
+use crate::ContextExt;
+use crate::IncompatibleOpenGl;
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, Waker};
+use std::thread;
+
+/// Error that can happen while creating a resource on the upload thread.
+#[derive(Debug)]
+pub enum UploadError {
+    /// The context of the upload thread couldn't be initialized.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+
+    /// The texture couldn't be created.
+    TextureCreation(TextureCreationError),
+
+    /// The buffer couldn't be created.
+    BufferCreation(BufferCreationError),
+
+    /// The program couldn't be compiled or linked.
+    ProgramCreation(ProgramCreationError),
+
+    /// The upload thread has panicked.
+    WorkerPanicked,
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            UploadError::IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+            UploadError::TextureCreation(err) => write!(fmt, "{}", err),
+            UploadError::BufferCreation(err) => write!(fmt, "{}", err),
+            UploadError::ProgramCreation(err) => write!(fmt, "{}", err),
+            UploadError::WorkerPanicked => write!(fmt, "The upload thread has panicked"),
+        }
+    }
+}
+
+impl Error for UploadError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            UploadError::IncompatibleOpenGl(ref err) => Some(err),
+            UploadError::TextureCreation(ref err) => Some(err),
+            UploadError::BufferCreation(ref err) => Some(err),
+            UploadError::ProgramCreation(ref err) => Some(err),
+            UploadError::WorkerPanicked => None,
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for UploadError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> UploadError {
+        UploadError::IncompatibleOpenGl(err)
+    }
+}
+
+impl From<TextureCreationError> for UploadError {
+    #[inline]
+    fn from(err: TextureCreationError) -> UploadError {
+        UploadError::TextureCreation(err)
+    }
+}
+
+impl From<BufferCreationError> for UploadError {
+    #[inline]
+    fn from(err: BufferCreationError) -> UploadError {
+        UploadError::BufferCreation(err)
+    }
+}
+
+impl From<ProgramCreationError> for UploadError {
+    #[inline]
+    fn from(err: ProgramCreationError) -> UploadError {
+        UploadError::ProgramCreation(err)
+    }
+}
+
+/// Builds the final object in a context of the share group. Called with the main context once
+/// the worker is done, or with the context of the worker to destroy the objects if the future
+/// has been dropped.
+type Finish<T> = Box<dyn FnOnce(&Rc<Context>) -> Result<T, UploadError> + Send>;
+
+type Job = Box<dyn FnOnce(Result<&Rc<Context>, &IncompatibleOpenGl>) + Send>;
+
+/// What the worker sends back once its commands have been submitted.
+struct Uploaded<T> {
+    // `None` if fences aren't supported, in which case the worker has called `glFinish`
+    fence: Option<LinearSyncFence>,
+    finish: Finish<T>,
+}
+
+/// A worker thread with an OpenGL context that shares its objects with the main context.
+///
+/// Dropping the `UploadThread` doesn't cancel the pending requests. The thread exits once all
+/// of them have been processed, and destroys its context.
+pub struct UploadThread {
+    context: Rc<Context>,
+    jobs: Sender<Job>,
+}
+
+impl UploadThread {
+    /// Spawns the worker thread.
+    ///
+    /// `create_backend` is called on the worker thread, and must return a backend whose OpenGL
+    /// context shares its objects with the one of `context`. The context is usually created on
+    /// the rendering thread without being made current, moved into the closure, and made
+    /// current by the closure.
+    ///
+    /// # Safety
+    ///
+    /// The OpenGL context of the backend must share its objects with the one of `context`.
+    pub unsafe fn new<C, B>(context: &Rc<Context>, create_backend: C) -> UploadThread
+        where C: FnOnce() -> B + Send + 'static, B: Backend + 'static
+    {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+
+        thread::Builder::new()
+            .name("glium-upload".to_owned())
+            .spawn(move || {
+                let backend = create_backend();
+                let context = Context::new(backend, true, DebugCallbackBehavior::Ignore);
+
+                // the jobs that are still queued are processed before exiting
+                for job in receiver {
+                    job(context.as_ref());
+                }
+            })
+            .expect("Failed to spawn the upload thread");
+
+        UploadThread {
+            context: context.clone(),
+            jobs,
+        }
+    }
+
+    /// Creates a two-dimensional texture from an image.
+    ///
+    /// If `mipmaps` is true, the mipmaps are generated by the worker as well.
+    pub fn texture_2d(&self, image: RawImage2d<'static, u8>, format: UncompressedFloatFormat,
+                      mipmaps: bool) -> Upload<Texture2d>
+    {
+        self.queue(move |context| {
+            let (width, height) = (image.width, image.height);
+            let option = if mipmaps {
+                MipmapsOption::AutoGeneratedMipmaps
+            } else {
+                MipmapsOption::NoMipmap
+            };
+
+            let id = Texture2d::with_format(context, image, format, option)?.into_id();
+
+            Ok(Box::new(move |context: &Rc<Context>| {
+                // the mipmaps already contain the generated data
+                let option = if mipmaps { MipmapsOption::EmptyMipmaps } else { option };
+                let ty = Dimensions::Texture2d { width, height };
+                Ok(unsafe { Texture2d::from_id(context, format, id, true, option, ty) })
+            }) as Finish<_>)
+        })
+    }
+
+    /// Creates a buffer containing the given data.
+    pub fn buffer<T>(&self, data: Vec<T>, ty: BufferType, mode: BufferMode) -> Upload<Buffer<[T]>>
+        where T: Copy + Send + 'static, [T]: Content
+    {
+        self.queue(move |context| {
+            let raw: RawAlloc = Buffer::new(context, &data[..], ty, mode)?.into_raw();
+
+            Ok(Box::new(move |context: &Rc<Context>| {
+                Ok(unsafe { Buffer::from_raw(context, raw) })
+            }) as Finish<_>)
+        })
+    }
+
+    /// Compiles and links a program made of a vertex shader, a fragment shader and an optional
+    /// geometry shader, like `Program::from_source`.
+    pub fn program(&self, vertex_shader: String, fragment_shader: String,
+                   geometry_shader: Option<String>) -> Upload<Program>
+    {
+        self.queue(move |context| {
+            let program = Program::from_source(context, &vertex_shader, &fragment_shader,
+                                               geometry_shader.as_deref())?;
+            let binary = program.get_binary().ok();
+
+            Ok(Box::new(move |context: &Rc<Context>| {
+                if let Some(binary) = binary {
+                    let program = Program::new(context, ProgramCreationInput::Binary {
+                        data: binary,
+                        outputs_srgb: true,
+                        uses_point_size: false,
+                    });
+
+                    if let Ok(program) = program {
+                        return Ok(program);
+                    }
+                }
+
+                // the binary isn't available or has been rejected
+                Ok(Program::from_source(context, &vertex_shader, &fragment_shader,
+                                        geometry_shader.as_deref())?)
+            }) as Finish<_>)
+        })
+    }
+
+    /// Sends a job to the worker, and returns the future that receives its result.
+    fn queue<T, W>(&self, work: W) -> Upload<T>
+        where W: FnOnce(&Rc<Context>) -> Result<Finish<T>, UploadError> + Send + 'static,
+              T: 'static
+    {
+        let (sender, receiver) = mpsc::channel();
+        let waker = Arc::new(Mutex::new(None::<Waker>));
+
+        let job_waker = waker.clone();
+        let job: Job = Box::new(move |context| {
+            let context = match context {
+                Ok(context) => context,
+                Err(err) => {
+                    let _ = sender.send(Err(UploadError::from(err.clone())));
+                    wake(&job_waker);
+                    return;
+                },
+            };
+
+            let result = work(context).map(|finish| {
+                let fence = unsafe { sync::new_linear_sync_fence(&mut context.make_current()) };
+                let fence = fence.ok();
+
+                // the commands must reach the server before the main context waits for them
+                if fence.is_some() {
+                    context.flush();
+                } else {
+                    context.finish();
+                }
+
+                Uploaded { fence, finish }
+            });
+
+            // the future can't be dropped while the lock is held, so the result is either
+            // received by the future or returned here
+            let (sent, waker) = {
+                let mut waker = job_waker.lock().unwrap();
+                (sender.send(result), waker.take())
+            };
+
+            if let Err(mpsc::SendError(Ok(uploaded))) = sent {
+                // the future has been dropped, the objects are destroyed by the worker
+                if let Some(fence) = uploaded.fence {
+                    unsafe { sync::destroy_linear_sync_fence(&mut context.make_current(), fence) };
+                }
+                drop((uploaded.finish)(context));
+            }
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+
+        // the worker only exits once this sender has been dropped
+        self.jobs.send(job).unwrap();
+
+        Upload {
+            context: self.context.clone(),
+            state: UploadState::Working { receiver, waker },
+        }
+    }
+}
+
+impl fmt::Debug for UploadThread {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "UploadThread")
+    }
+}
+
+fn wake(waker: &Mutex<Option<Waker>>) {
+    if let Some(waker) = waker.lock().unwrap().take() {
+        waker.wake();
+    }
+}
+
+/// A resource being created by an `UploadThread`.
+///
+/// Resolves to the resource once the commands of the worker have been executed.
+#[must_use = "futures do nothing unless polled"]
+pub struct Upload<T> {
+    context: Rc<Context>,
+    state: UploadState<T>,
+}
+
+enum UploadState<T> {
+    Working {
+        receiver: Receiver<Result<Uploaded<T>, UploadError>>,
+        waker: Arc<Mutex<Option<Waker>>>,
+    },
+    Waiting {
+        object: T,
+        fence: Option<SyncFence>,
+    },
+    Done,
+}
+
+// the state is never pinned
+impl<T> Unpin for Upload<T> {}
+
+impl<T> Future for Upload<T> {
+    type Output = Result<T, UploadError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        loop {
+            match self.state {
+                UploadState::Working { ref receiver, ref waker } => {
+                    // the waker is stored before checking, so that a result sent in between
+                    // isn't missed
+                    *waker.lock().unwrap() = Some(cx.waker().clone());
+
+                    let uploaded = match receiver.try_recv() {
+                        Ok(result) => result,
+                        Err(TryRecvError::Empty) => return Poll::Pending,
+                        Err(TryRecvError::Disconnected) => Err(UploadError::WorkerPanicked),
+                    };
+
+                    let result = uploaded.and_then(|uploaded| {
+                        let fence = uploaded.fence.map(|f| f.into_sync_fence(&self.context));
+                        (uploaded.finish)(&self.context).map(|object| (object, fence))
+                    });
+
+                    match result {
+                        Ok((object, fence)) => self.state = UploadState::Waiting { object, fence },
+                        Err(err) => {
+                            self.state = UploadState::Done;
+                            return Poll::Ready(Err(err));
+                        },
+                    }
+                },
+
+                UploadState::Waiting { ref fence, .. } => {
+                    if matches!(*fence, Some(ref fence) if !fence.is_signaled()) {
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+
+                    match mem::replace(&mut self.state, UploadState::Done) {
+                        UploadState::Waiting { object, .. } => return Poll::Ready(Ok(object)),
+                        _ => unreachable!(),
+                    }
+                },
+
+                UploadState::Done => panic!("Upload polled after completion"),
+            }
+        }
+    }
+}
+
+impl<T> Drop for Upload<T> {
+    fn drop(&mut self) {
+        if let UploadState::Working { receiver, waker } = mem::replace(&mut self.state,
+                                                                        UploadState::Done)
+        {
+            let _lock = waker.lock().unwrap();
+
+            // the objects may have been sent before the future was dropped
+            if let Ok(Ok(uploaded)) = receiver.try_recv() {
+                drop(uploaded.fence.map(|fence| fence.into_sync_fence(&self.context)));
+                drop((uploaded.finish)(&self.context));
+            }
+
+            // nothing can be sent once the receiver is destroyed
+            drop(receiver);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Upload<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let state = match self.state {
+            UploadState::Working { .. } => "working",
+            UploadState::Waiting { .. } => "waiting",
+            UploadState::Done => "done",
+        };
+        write!(fmt, "Upload({})", state)
+    }
+}
@@ -1,6 +1,8 @@
 use crate::backend::Facade;
 use crate::context::CommandContext;
 use crate::context::Context;
+use crate::context::GarbageObject;
+use crate::context::SharedObject;
 use crate::version::Version;
 use crate::CapabilitiesSource;
 use crate::ContextExt;
@@ -10,13 +12,16 @@ use std::error::Error;
 use std::{fmt, mem, ptr};
 use std::cell::Cell;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use std::ops::{Deref, DerefMut, Range};
 use crate::GlObject;
 use crate::TransformFeedbackSessionExt;
 
-use crate::buffer::{Content, BufferType, BufferMode, BufferCreationError};
+use crate::buffer::{Content, BufferType, BufferMode, BufferCreationError, UploadPath};
 use crate::vertex::TransformFeedbackSession;
 use crate::vertex_array_object::VertexAttributesSystem;
+use crate::debug::StateAudit;
+use crate::memory_object::MemoryObject;
 
 use crate::version::Api;
 
@@ -97,6 +102,19 @@ pub struct Alloc {
     latest_shader_write: Cell<u64>,
 }
 
+/// A buffer that has been detached from its context, in order to be handed over to another
+/// context of the same share group. Can be sent to another thread.
+pub(crate) struct RawAlloc {
+    id: gl::types::GLuint,
+    ty: BufferType,
+    size: usize,
+    // the address of the persistent mapping, which stays valid in the other contexts
+    persistent_mapping: Option<usize>,
+    immutable: bool,
+    creation_mode: BufferMode,
+    created_with_buffer_storage: bool,
+}
+
 impl Alloc {
     /// Builds a new buffer containing the given data. The size of the buffer is equal to the
     /// size of the data.
@@ -150,6 +168,98 @@ impl Alloc {
         })
     }
 
+    /// Builds a buffer whose storage is a range of a memory object allocated by an external API.
+    ///
+    /// The buffer is immutable and can't be mapped persistently.
+    pub unsafe fn from_memory_object<F: Facade + ?Sized>(facade: &F, memory: &MemoryObject,
+                                                         offset: u64, size: usize, ty: BufferType)
+                                                         -> Result<Alloc, BufferCreationError>
+    {
+        let mut ctxt = facade.get_context().make_current();
+
+        if !is_buffer_type_supported(&mut ctxt, ty) {
+            return Err(BufferCreationError::BufferTypeNotSupported);
+        }
+
+        let mut id: gl::types::GLuint = 0;
+        let mut obtained_size: gl::types::GLint = 0;
+
+        if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+            ctxt.gl.CreateBuffers(1, &mut id);
+            ctxt.gl.NamedBufferStorageMemEXT(id, size as gl::types::GLsizeiptr, memory.get_id(),
+                                             offset);
+            ctxt.gl.GetNamedBufferParameteriv(id, gl::BUFFER_SIZE, &mut obtained_size);
+        } else {
+            ctxt.gl.GenBuffers(1, &mut id);
+            let bind = bind_buffer(&mut ctxt, id, ty);
+            ctxt.gl.BufferStorageMemEXT(bind, size as gl::types::GLsizeiptr, memory.get_id(),
+                                        offset);
+            ctxt.gl.GetBufferParameteriv(bind, gl::BUFFER_SIZE, &mut obtained_size);
+        }
+
+        // the storage isn't allocated if the range is outside of the memory object
+        if size != obtained_size as usize {
+            destroy_buffer(&mut ctxt, id);
+            return Err(BufferCreationError::OutOfMemory);
+        }
+
+        Ok(Alloc {
+            context: facade.get_context().clone(),
+            id,
+            ty,
+            size,
+            persistent_mapping: None,
+            immutable: true,
+            created_with_buffer_storage: true,
+            creation_mode: BufferMode::Immutable,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+        })
+    }
+
+    /// Removes the buffer from the caches of its context and returns it without deleting it.
+    pub(crate) fn into_raw(self) -> RawAlloc {
+        let this = mem::ManuallyDrop::new(self);
+        // the context is the only field that needs to be dropped
+        let context = unsafe { ptr::read(&this.context) };
+
+        let mut ctxt = context.make_current();
+        this.assert_unmapped(&mut ctxt);
+        this.assert_not_transform_feedback(&mut ctxt);
+        VertexAttributesSystem::purge_buffer(&mut ctxt, this.id);
+        forget_buffer(&mut ctxt, this.id);
+
+        RawAlloc {
+            id: this.id,
+            ty: this.ty,
+            size: this.size,
+            persistent_mapping: this.persistent_mapping.map(|ptr| ptr as usize),
+            immutable: this.immutable,
+            creation_mode: this.creation_mode,
+            created_with_buffer_storage: this.created_with_buffer_storage,
+        }
+    }
+
+    /// Takes ownership of a buffer returned by `into_raw`.
+    ///
+    /// # Safety
+    ///
+    /// The context must share its objects with the one the buffer has been created with.
+    pub(crate) unsafe fn from_raw(context: &Rc<Context>, raw: RawAlloc) -> Alloc {
+        Alloc {
+            context: context.clone(),
+            id: raw.id,
+            ty: raw.ty,
+            size: raw.size,
+            persistent_mapping: raw.persistent_mapping.map(|ptr| ptr as *mut raw::c_void),
+            immutable: raw.immutable,
+            created_with_buffer_storage: raw.created_with_buffer_storage,
+            creation_mode: raw.creation_mode,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+        }
+    }
+
     /// Returns the context corresponding to this buffer.
     #[inline]
     pub fn get_context(&self) -> &Rc<Context> {
@@ -190,6 +300,13 @@ impl Alloc {
         TransformFeedbackSession::ensure_buffer_out_of_transform_feedback(ctxt, self.id);
     }
 
+    /// Makes the buffer available to an API that accesses it behind glium's back.
+    #[cfg(feature = "cuda")]
+    pub(crate) fn prepare_for_external_access(&self, ctxt: &mut CommandContext<'_>) {
+        self.assert_unmapped(ctxt);
+        self.assert_not_transform_feedback(ctxt);
+    }
+
     /// Calls `glMemoryBarrier(GL_BUFFER_UPDATE_BARRIER_BIT)` if necessary.
     fn barrier_for_buffer_update(&self, ctxt: &mut CommandContext<'_>) {
         if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_buffer_update {
@@ -223,6 +340,10 @@ impl Alloc {
 
     /// Binds the buffer to `GL_ELEMENT_ARRAY_BUFFER` regardless of the current vertex array object.
     pub fn bind_to_element_array(&self, ctxt: &mut CommandContext<'_>) {
+        let vao = ctxt.state.vertex_array;
+        ctxt.state.audit("glBindBuffer", (gl::ELEMENT_ARRAY_BUFFER as u64) << 32 | vao as u64,
+                         self.id as u64);
+
         if ctxt.version >= &Version(Api::Gl, 1, 5) ||
            ctxt.version >= &Version(Api::GlEs, 2, 0)
         {
@@ -447,11 +568,8 @@ impl Alloc {
             self.assert_unmapped(&mut ctxt);
             self.assert_not_transform_feedback(&mut ctxt);
 
-            let (tmp_buffer, _, _, _) = create_buffer(&mut ctxt, mem::size_of_val(data), Some(data),
-                                                      BufferType::CopyReadBuffer,
-                                                      BufferMode::Dynamic).unwrap();
-            copy_buffer(&mut ctxt, tmp_buffer, 0, self.id, offset_bytes, mem::size_of_val(data)).unwrap();
-            destroy_buffer(&mut ctxt, tmp_buffer);
+            let persistent = self.context.get_upload_path() == UploadPath::PersistentStaging;
+            upload_staged(&mut ctxt, self.id, offset_bytes, data, persistent);
 
         } else {
             assert!(offset_bytes < self.size);
@@ -470,32 +588,12 @@ impl Alloc {
                 ctxt.gl.InvalidateBufferData(self.id);
             }
 
-            if ctxt.version >= &Version(Api::Gl, 4, 5) {
-                ctxt.gl.NamedBufferSubData(self.id, offset_bytes as gl::types::GLintptr,
-                                           mem::size_of_val(data) as gl::types::GLsizeiptr,
-                                           data.to_void_ptr() as *const _)
-
-            } else if ctxt.extensions.gl_ext_direct_state_access {
-                ctxt.gl.NamedBufferSubDataEXT(self.id, offset_bytes as gl::types::GLintptr,
-                                              mem::size_of_val(data) as gl::types::GLsizeiptr,
-                                              data.to_void_ptr() as *const _)
-
-            } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-                ctxt.version >= &Version(Api::GlEs, 2, 0)
-            {
-                let bind = bind_buffer(&mut ctxt, self.id, self.ty);
-                ctxt.gl.BufferSubData(bind, offset_bytes as gl::types::GLintptr,
-                                      mem::size_of_val(data) as gl::types::GLsizeiptr,
-                                      data.to_void_ptr() as *const _);
-
-            } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-                let bind = bind_buffer(&mut ctxt, self.id, self.ty);
-                ctxt.gl.BufferSubDataARB(bind, offset_bytes as gl::types::GLintptr,
-                                         mem::size_of_val(data) as gl::types::GLsizeiptr,
-                                         data.to_void_ptr() as *const _);
-
-            } else {
-                unreachable!();
+            match self.context.get_upload_path() {
+                UploadPath::Direct => upload_direct(&mut ctxt, self.id, self.ty, offset_bytes, data),
+                UploadPath::Staging => upload_staged(&mut ctxt, self.id, offset_bytes, data, false),
+                UploadPath::PersistentStaging => {
+                    upload_staged(&mut ctxt, self.id, offset_bytes, data, true)
+                },
             }
         }
     }
@@ -875,6 +973,8 @@ impl Drop for Alloc {
             self.assert_not_transform_feedback(&mut ctxt);
             VertexAttributesSystem::purge_buffer(&mut ctxt, self.id);
             destroy_buffer(&mut ctxt, self.id);
+            drop(ctxt);
+            self.context.forget_in_shared_contexts(SharedObject::Buffer(self.id));
         }
     }
 }
@@ -1113,6 +1213,143 @@ pub fn is_buffer_read_supported<C: ?Sized>(ctxt: &C) -> bool where C: Capabiliti
     }
 }
 
+/// Uploads data to a buffer with `glBufferSubData`.
+unsafe fn upload_direct<D>(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint,
+                           ty: BufferType, offset_bytes: usize, data: &D)
+                           where D: ?Sized + Content
+{
+    if ctxt.version >= &Version(Api::Gl, 4, 5) {
+        ctxt.gl.NamedBufferSubData(id, offset_bytes as gl::types::GLintptr,
+                                   mem::size_of_val(data) as gl::types::GLsizeiptr,
+                                   data.to_void_ptr() as *const _)
+
+    } else if ctxt.extensions.gl_ext_direct_state_access {
+        ctxt.gl.NamedBufferSubDataEXT(id, offset_bytes as gl::types::GLintptr,
+                                      mem::size_of_val(data) as gl::types::GLsizeiptr,
+                                      data.to_void_ptr() as *const _)
+
+    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+        ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        let bind = bind_buffer(ctxt, id, ty);
+        ctxt.gl.BufferSubData(bind, offset_bytes as gl::types::GLintptr,
+                              mem::size_of_val(data) as gl::types::GLsizeiptr,
+                              data.to_void_ptr() as *const _);
+
+    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+        let bind = bind_buffer(ctxt, id, ty);
+        ctxt.gl.BufferSubDataARB(bind, offset_bytes as gl::types::GLintptr,
+                                 mem::size_of_val(data) as gl::types::GLsizeiptr,
+                                 data.to_void_ptr() as *const _);
+
+    } else {
+        unreachable!();
+    }
+}
+
+/// Uploads data to a buffer by creating a temporary buffer that contains the data, then copying
+/// from it.
+///
+/// If `persistent` is true and the backend supports it, the data is written to the temporary
+/// buffer through a persistent mapping instead of being passed when creating it.
+unsafe fn upload_staged<D>(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint,
+                           offset_bytes: usize, data: &D, persistent: bool)
+                           where D: ?Sized + Content
+{
+    let size = mem::size_of_val(data);
+
+    let tmp_buffer = if persistent {
+        let (tmp_buffer, _, _, mapping) = create_buffer::<D>(ctxt, size, None,
+                                                             BufferType::CopyReadBuffer,
+                                                             BufferMode::Persistent).unwrap();
+        match mapping {
+            Some(mapping) => {
+                ptr::copy_nonoverlapping(data.to_void_ptr() as *const u8, mapping as *mut u8,
+                                         size);
+                flush_range(ctxt, tmp_buffer, BufferType::CopyReadBuffer, 0 .. size);
+                tmp_buffer
+            },
+            None => {
+                destroy_buffer(ctxt, tmp_buffer);
+                return upload_staged(ctxt, id, offset_bytes, data, false);
+            },
+        }
+
+    } else {
+        let (tmp_buffer, _, _, _) = create_buffer(ctxt, size, Some(data),
+                                                  BufferType::CopyReadBuffer,
+                                                  BufferMode::Dynamic).unwrap();
+        tmp_buffer
+    };
+
+    copy_buffer(ctxt, tmp_buffer, 0, id, offset_bytes, size).unwrap();
+    destroy_buffer(ctxt, tmp_buffer);
+}
+
+/// Returns true if the backend supports uploading data with the given path.
+pub fn is_upload_path_supported<C>(ctxt: &C, path: UploadPath) -> bool
+    where C: ?Sized + CapabilitiesSource
+{
+    let version = ctxt.get_version();
+    let extensions = ctxt.get_extensions();
+
+    let copy = version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_copy_buffer || extensions.gl_nv_copy_buffer;
+
+    // `create_buffer` only maps buffers with `glMapBufferRange` or `glMapNamedBufferRange`
+    let persistent = (version >= &Version(Api::Gl, 4, 4) || extensions.gl_arb_buffer_storage) &&
+                     (version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_map_buffer_range);
+
+    match path {
+        UploadPath::Direct => true,
+        UploadPath::Staging => copy,
+        UploadPath::PersistentStaging => copy && persistent,
+    }
+}
+
+/// Measures the average time it takes to upload a few megabytes with each upload path that the
+/// backend supports.
+pub fn probe_upload_paths(ctxt: &mut CommandContext<'_>) -> Vec<(UploadPath, Duration)> {
+    const SIZE: usize = 4 * 1024 * 1024;
+    const ITERATIONS: u32 = 16;
+
+    let data = vec![0u8; SIZE];
+    let data = &data[..];
+
+    unsafe {
+        let (id, _, _, _) = create_buffer::<[u8]>(ctxt, SIZE, None, BufferType::ArrayBuffer,
+                                                  BufferMode::Default).unwrap();
+
+        let mut results = Vec::with_capacity(3);
+
+        for path in [UploadPath::Direct, UploadPath::Staging, UploadPath::PersistentStaging] {
+            if !is_upload_path_supported(ctxt, path) {
+                continue;
+            }
+
+            let upload = |ctxt: &mut CommandContext<'_>| match path {
+                UploadPath::Direct => upload_direct(ctxt, id, BufferType::ArrayBuffer, 0, data),
+                UploadPath::Staging => upload_staged(ctxt, id, 0, data, false),
+                UploadPath::PersistentStaging => upload_staged(ctxt, id, 0, data, true),
+            };
+
+            // the first upload isn't measured, as the driver may allocate memory during it
+            upload(ctxt);
+            ctxt.gl.Finish();
+
+            let start = Instant::now();
+            for _ in 0 .. ITERATIONS {
+                upload(ctxt);
+            }
+            ctxt.gl.Finish();
+            results.push((path, start.elapsed() / ITERATIONS));
+        }
+
+        destroy_buffer(ctxt, id);
+        results
+    }
+}
+
 /// Creates a new buffer.
 ///
 /// # Panic
@@ -1395,6 +1632,7 @@ unsafe fn bind_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, ty:
 
                 if ctxt.state.$state_var != $input_id {
                     ctxt.state.$state_var = $input_id;
+                    ctxt.state.audit("glBindBuffer", en as u64, id as u64);
 
                     if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                        ctxt.version >= &Version(Api::GlEs, 2, 0)
@@ -1429,6 +1667,10 @@ unsafe fn bind_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, ty:
         // TODO: the state if the current buffer is not cached
         VertexAttributesSystem::hijack_current_element_array_buffer(ctxt);
 
+        let vao = ctxt.state.vertex_array;
+        ctxt.state.audit("glBindBuffer", (gl::ELEMENT_ARRAY_BUFFER as u64) << 32 | vao as u64,
+                         id as u64);
+
         if ctxt.version >= &Version(Api::Gl, 1, 5) ||
            ctxt.version >= &Version(Api::GlEs, 2, 0)
         {
@@ -1449,6 +1691,7 @@ unsafe fn bind_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, ty:
 
         if ctxt.state.indexed_transform_feedback_buffer_bindings[0].buffer != id {
             ctxt.state.indexed_transform_feedback_buffer_bindings[0].buffer = id;
+            ctxt.state.audit("glBindBuffer", gl::TRANSFORM_FEEDBACK_BUFFER as u64, id as u64);
 
             if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                ctxt.version >= &Version(Api::GlEs, 2, 0)
@@ -1505,6 +1748,9 @@ unsafe fn indexed_bind_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLui
                     unit.offset = offset;
                     unit.size = size;
 
+                    ctxt.state.audit("glBindBufferRange", (en as u64) << 32 | $input_index as u64,
+                                     StateAudit::hash((id, offset, size)));
+
                     if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                        ctxt.version >= &Version(Api::GlEs, 3, 0)
                     {
@@ -1619,9 +1865,31 @@ unsafe fn copy_buffer(ctxt: &mut CommandContext<'_>, source: gl::types::GLuint,
 
 /// Destroys a buffer.
 unsafe fn destroy_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    if !ctxt.garbage.defer(GarbageObject::Buffer(id)) {
+        delete_buffer(ctxt, id);
+    }
+}
+
+/// Deletes a buffer and removes it from the state cache.
+pub(crate) unsafe fn delete_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
     // FIXME: uncomment this and move it from Buffer's destructor
     //self.context.vertex_array_objects.purge_buffer(&mut ctxt, id);
 
+    forget_buffer(ctxt, id);
+
+    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+        ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        ctxt.gl.DeleteBuffers(1, [id].as_ptr());
+    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+        ctxt.gl.DeleteBuffersARB(1, [id].as_ptr());
+    } else {
+        unreachable!();
+    }
+}
+
+/// Removes a buffer from the state cache.
+pub(crate) fn forget_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
     if ctxt.state.array_buffer_binding == id {
         ctxt.state.array_buffer_binding = 0;
     }
@@ -1694,16 +1962,6 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
             point.buffer = 0;
         }
     }
-
-    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-        ctxt.version >= &Version(Api::GlEs, 2, 0)
-    {
-        ctxt.gl.DeleteBuffers(1, [id].as_ptr());
-    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-        ctxt.gl.DeleteBuffersARB(1, [id].as_ptr());
-    } else {
-        unreachable!();
-    }
 }
 
 /// Flushes a range of a mapped buffer.
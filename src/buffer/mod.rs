@@ -49,6 +49,8 @@ pub use self::view::{Buffer, BufferAny, BufferMutSlice};
 pub use self::view::{BufferSlice, BufferAnySlice};
 pub use self::alloc::{Mapping, WriteMapping, ReadMapping, ReadError, CopyError};
 pub use self::alloc::{is_buffer_read_supported};
+pub(crate) use self::alloc::{is_upload_path_supported, probe_upload_paths};
+pub(crate) use self::alloc::{delete_buffer, forget_buffer, RawAlloc};
 pub use self::fences::Inserter;
 
 /// DEPRECATED. Only here for backwards compatibility.
@@ -284,6 +286,29 @@ impl Default for BufferMode {
     }
 }
 
+/// How glium uploads data to buffers that aren't persistently mapped.
+///
+/// The fastest path depends on the driver. Use `Context::probe_upload_paths` to measure them
+/// and select the fastest one, or `Context::set_upload_path` to select one manually.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum UploadPath {
+    /// The data is passed to `glBufferSubData`. This is the default.
+    #[default]
+    Direct,
+
+    /// The data is passed to a temporary buffer, then copied by the GPU with
+    /// `glCopyBufferSubData`.
+    ///
+    /// Requires OpenGL 3.1, OpenGL ES 3.0, `GL_ARB_copy_buffer` or `GL_NV_copy_buffer`.
+    Staging,
+
+    /// The data is written in a temporary buffer that is persistently mapped in memory, then
+    /// copied by the GPU with `glCopyBufferSubData`.
+    ///
+    /// Requires the same as `Staging`, plus `glBufferStorage` and `glMapBufferRange`.
+    PersistentStaging,
+}
+
 /// Type of a buffer.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[allow(missing_docs)]
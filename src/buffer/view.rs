@@ -24,12 +24,14 @@ use crate::buffer::Content;
 use crate::buffer::fences::Fences;
 use crate::buffer::fences::Inserter;
 use crate::buffer::alloc::Alloc;
+use crate::buffer::alloc::RawAlloc;
 use crate::buffer::alloc::Mapping;
 use crate::buffer::alloc::ReadMapping;
 use crate::buffer::alloc::WriteMapping;
 use crate::buffer::alloc::ReadError;
 use crate::buffer::alloc::CopyError;
 use crate::field::Field;
+use crate::memory_object::MemoryObject;
 
 /// Represents a view of a buffer.
 pub struct Buffer<T: ?Sized> where T: Content {
@@ -82,12 +84,90 @@ impl<T: ?Sized> Buffer<T> where T: Content {
             })
     }
 
+    /// Builds a buffer whose storage is `size` bytes of memory allocated by an external API like
+    /// Vulkan, starting at `offset` bytes in the memory object.
+    ///
+    /// The buffer is immutable: it can only be modified by the external API, by the GPU, or with
+    /// `write`, which goes through a temporary buffer. Use a `Semaphore` to synchronize the
+    /// accesses with the external API.
+    ///
+    /// ```no_run
+    /// # fn example(display: &glium::Display<glium::glutin::surface::WindowSurface>,
+    /// #            fd: std::fs::File) {
+    /// use glium::buffer::{Buffer, BufferType};
+    /// use glium::memory_object::MemoryObject;
+    ///
+    /// // 4096 bytes allocated and exported by Vulkan
+    /// let memory = unsafe { MemoryObject::new_from_fd(display, false, fd, 4096) }.unwrap();
+    /// let buffer: Buffer<[[f32; 4]]> = unsafe {
+    ///     Buffer::from_memory_object(display, &memory, 1024, 2048, BufferType::ArrayBuffer)
+    /// }.unwrap();
+    /// assert_eq!(buffer.len(), 128);
+    /// # }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The memory object must outlive the buffer, and the range must contain a valid `T`.
+    pub unsafe fn from_memory_object<F: Facade + ?Sized>(facade: &F, memory: &MemoryObject,
+                                                         offset: u64, size: usize, ty: BufferType)
+                                                         -> Result<Buffer<T>, BufferCreationError>
+    {
+        assert!(<T as Content>::is_size_suitable(size));
+
+        Alloc::from_memory_object(facade, memory, offset, size, ty)
+            .map(|buffer| {
+                Buffer {
+                    alloc: Some(buffer),
+                    fence: Some(Fences::new()),
+                    marker: PhantomData,
+                }
+            })
+    }
+
+    /// Unmaps the buffer and takes it out of the transform feedback process, so that an API that
+    /// accesses it behind glium's back sees it as an ordinary buffer.
+    #[cfg(feature = "cuda")]
+    pub(crate) fn prepare_for_external_access(&mut self, ctxt: &mut CommandContext<'_>) {
+        self.alloc.as_ref().unwrap().prepare_for_external_access(ctxt);
+    }
+
+    /// Detaches the buffer from its context without deleting it. See `Alloc::into_raw`.
+    pub(crate) fn into_raw(mut self) -> RawAlloc {
+        let alloc = self.alloc.take().unwrap();
+        let mut fence = self.fence.take().unwrap();
+        fence.clean(&mut alloc.get_context().make_current());
+        alloc.into_raw()
+    }
+
+    /// Takes ownership of a buffer returned by `into_raw`.
+    ///
+    /// # Safety
+    ///
+    /// The context must share its objects with the one the buffer has been created with, and
+    /// `T` must be the content of the original buffer.
+    pub(crate) unsafe fn from_raw(context: &Rc<Context>, raw: RawAlloc) -> Buffer<T> {
+        Buffer {
+            alloc: Some(Alloc::from_raw(context, raw)),
+            fence: Some(Fences::new()),
+            marker: PhantomData,
+        }
+    }
+
     /// Returns the context corresponding to this buffer.
     #[inline]
     pub fn get_context(&self) -> &Rc<Context> {
         self.alloc.as_ref().unwrap().get_context()
     }
 
+    /// Attaches a name to this buffer, which OpenGL debuggers display instead of its id.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`.
+    #[inline]
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        self.get_context().set_object_label(gl::BUFFER, self.get_id(), label)
+    }
+
     /// Returns the size in bytes of this buffer.
     #[inline]
     pub fn get_size(&self) -> usize {
@@ -665,6 +745,27 @@ impl<'a, T> BufferSlice<'a, [T]> where [T]: Content + 'a {
             marker: PhantomData,
         })
     }
+
+    /// Builds a slice that contains only the element at `index`. Returns `None` if out of range.
+    ///
+    /// This method builds an object that represents a slice of the buffer. No actual operation
+    /// OpenGL is performed.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<BufferSlice<'a, T>> where T: Content {
+        if index >= self.len() {
+            return None;
+        }
+
+        let bytes_start = self.bytes_start + index * mem::size_of::<T>();
+
+        Some(BufferSlice {
+            alloc: self.alloc,
+            bytes_start,
+            bytes_end: bytes_start + mem::size_of::<T>(),
+            fence: self.fence,
+            marker: PhantomData,
+        })
+    }
 }
 
 impl<'a, T> BufferSlice<'a, [T]> where T: PixelValue + 'a {
@@ -1117,12 +1218,28 @@ impl BufferAny {
         self.size / self.elements_size
     }
 
+    /// Changes the size of each element, for buffers whose layout is only known at runtime.
+    #[inline]
+    pub(crate) fn set_elements_size(&mut self, elements_size: usize) {
+        assert!(elements_size != 0);
+        assert_eq!(self.size % elements_size, 0);
+        self.elements_size = elements_size;
+    }
+
     /// Returns the context corresponding to this buffer.
     #[inline]
     pub fn get_context(&self) -> &Rc<Context> {
         self.alloc.get_context()
     }
 
+    /// Attaches a name to this buffer, which OpenGL debuggers display instead of its id.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`.
+    #[inline]
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        self.alloc.get_context().set_object_label(gl::BUFFER, self.alloc.get_id(), label)
+    }
+
     /// Returns the number of bytes in this subbuffer.
     #[inline]
     pub fn get_size(&self) -> usize {
@@ -1417,6 +1534,6 @@ impl<'a> BufferExt for BufferAnySlice<'a> {
 
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext<'_>, index: gl::types::GLuint) {
-        self.alloc.bind_to_transform_feedback(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.bind_to_transform_feedback(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 }
@@ -0,0 +1,366 @@
+/*!
+Asynchronous capture of the frames rendered to a window.
+
+Reading back the content of the screen with `glReadPixels` normally blocks until the GPU has
+finished drawing. The `FrameRecorder` avoids this stall by reading each frame into one of
+several pixel buffers and only accessing the data once a fence tells that the transfer is over.
+The frames are then handed in order to a user callback, for example to feed a video encoder.
+
+## Example
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::capture::{CaptureFormat, FrameRecorder};
+
+let mut recorder = FrameRecorder::new(&display, 3, CaptureFormat::Nv12, |frame| {
+    // `frame.data` contains the Y plane followed by the interleaved UV plane
+    println!("frame {} captured ({}x{})", frame.index, frame.width, frame.height);
+}).unwrap();
+
+loop {
+    let mut target = display.draw();
+    // drawing...
+    recorder.capture(&target).unwrap();
+    target.finish().unwrap();
+}
+# }
+```
+
+*/
+use std::collections::VecDeque;
+use std::fmt;
+use std::error::Error;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::pixel_buffer::PixelBuffer;
+use crate::sync::SyncFence;
+use crate::ops;
+use crate::gl;
+
+use crate::CapabilitiesSource;
+use crate::ContextExt;
+use crate::Frame;
+use crate::Rect;
+use crate::Surface;
+
+use crate::version::Api;
+use crate::version::Version;
+
+/// Layout of the data passed to the callback of a `FrameRecorder`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CaptureFormat {
+    /// Four bytes per pixel, in the R, G, B, A order.
+    Rgba8,
+
+    /// A full-resolution plane of luma (Y) bytes, followed by a half-resolution plane of
+    /// interleaved chroma (U, V) bytes. Uses the BT.601 limited range coefficients.
+    ///
+    /// If the width or height of the frame is odd, the chroma plane is rounded up.
+    Nv12,
+}
+
+/// A frame that has been read back by a `FrameRecorder`.
+///
+/// Rows are stored from top to bottom, which is the order that encoders usually expect.
+#[derive(Debug, Copy, Clone)]
+pub struct CapturedFrame<'a> {
+    /// Number of the frame, starting at 0 for the first call to `capture`.
+    pub index: u64,
+    /// Width of the frame in pixels.
+    pub width: u32,
+    /// Height of the frame in pixels.
+    pub height: u32,
+    /// Layout of `data`.
+    pub format: CaptureFormat,
+    /// The content of the frame.
+    pub data: &'a [u8],
+}
+
+/// Error that can happen when creating a `FrameRecorder` or capturing a frame.
+#[derive(Debug)]
+pub enum CaptureError {
+    /// The backend doesn't support pixel buffer objects or fences.
+    NotSupported,
+
+    /// The ring must contain at least one buffer.
+    EmptyRing,
+
+    /// The frame doesn't belong to the same context as the recorder.
+    WrongContext,
+
+    /// Reading the frame failed.
+    Read(ops::ReadError),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::CaptureError::*;
+        match *self {
+            NotSupported =>
+                fmt.write_str("The backend doesn't support pixel buffer objects or fences"),
+            EmptyRing => fmt.write_str("The ring must contain at least one buffer"),
+            WrongContext =>
+                fmt.write_str("The frame doesn't belong to the same context as the recorder"),
+            Read(ref err) => write!(fmt, "Reading the frame failed: {}", err),
+        }
+    }
+}
+
+impl Error for CaptureError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            CaptureError::Read(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ops::ReadError> for CaptureError {
+    #[inline]
+    fn from(err: ops::ReadError) -> CaptureError {
+        CaptureError::Read(err)
+    }
+}
+
+/// One slot of the ring.
+struct Slot {
+    buffer: Option<PixelBuffer<(u8, u8, u8, u8)>>,
+    pending: Option<PendingRead>,
+}
+
+/// A transfer that has been submitted but not handed to the callback yet.
+struct PendingRead {
+    fence: SyncFence,
+    index: u64,
+    dimensions: (u32, u32),
+}
+
+/// Reads back frames asynchronously through a ring of pixel buffers.
+///
+/// Each call to `capture` submits a read of the back buffer and returns immediately. Frames are
+/// handed to the callback, in order, once the GPU has finished transferring them. The callback
+/// is only ever called from within `capture`, `poll` and `flush`.
+///
+/// If all the buffers of the ring are in use when a new frame is captured, the oldest one is
+/// waited upon. Using three buffers is usually enough to never block.
+pub struct FrameRecorder<F> where F: FnMut(CapturedFrame<'_>) {
+    context: Rc<Context>,
+    format: CaptureFormat,
+    slots: Vec<Slot>,
+    // indices of the slots with a pending read, from the oldest to the newest
+    queue: VecDeque<usize>,
+    next_slot: usize,
+    next_index: u64,
+    scratch: Vec<u8>,
+    callback: F,
+}
+
+impl<F> FrameRecorder<F> where F: FnMut(CapturedFrame<'_>) {
+    /// Builds a new recorder with `ring_size` pixel buffers.
+    ///
+    /// The buffers are allocated lazily, when the first frame is captured.
+    pub fn new<Fa>(facade: &Fa, ring_size: usize, format: CaptureFormat, callback: F)
+                   -> Result<FrameRecorder<F>, CaptureError> where Fa: ?Sized + Facade
+    {
+        if ring_size == 0 {
+            return Err(CaptureError::EmptyRing);
+        }
+
+        if !is_supported(facade) {
+            return Err(CaptureError::NotSupported);
+        }
+
+        Ok(FrameRecorder {
+            context: facade.get_context().clone(),
+            format,
+            slots: (0 .. ring_size).map(|_| Slot { buffer: None, pending: None }).collect(),
+            queue: VecDeque::with_capacity(ring_size),
+            next_slot: 0,
+            next_index: 0,
+            scratch: Vec::new(),
+            callback,
+        })
+    }
+
+    /// Returns the layout of the data passed to the callback.
+    #[inline]
+    pub fn get_format(&self) -> CaptureFormat {
+        self.format
+    }
+
+    /// Returns the number of frames that have been captured but not handed to the callback yet.
+    #[inline]
+    pub fn get_pending_frames(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Submits a read of the content of the frame.
+    ///
+    /// This should be called after drawing and before calling `finish` on the frame. Frames that
+    /// have finished transferring are then handed to the callback.
+    pub fn capture(&mut self, frame: &Frame) -> Result<(), CaptureError> {
+        if !Rc::ptr_eq(&frame.context, &self.context) {
+            return Err(CaptureError::WrongContext);
+        }
+
+        // if the slot is still in use, the ring is full and we have no choice but to wait
+        if self.slots[self.next_slot].pending.is_some() {
+            let slot = self.queue.pop_front().unwrap();
+            debug_assert_eq!(slot, self.next_slot);
+            self.deliver(slot, true);
+        }
+
+        let (width, height) = frame.get_dimensions();
+        let num_pixels = width as usize * height as usize;
+        if num_pixels == 0 {
+            return Ok(());
+        }
+
+        let slot = &mut self.slots[self.next_slot];
+        let too_small = slot.buffer.as_ref().map(|b| b.len() < num_pixels).unwrap_or(true);
+        if too_small {
+            slot.buffer = Some(PixelBuffer::new_empty(&self.context, num_pixels));
+        }
+
+        let rect = Rect { left: 0, bottom: 0, width, height };
+        {
+            let mut ctxt = self.context.make_current();
+            ops::read(&mut ctxt, ops::Source::DefaultFramebuffer(gl::BACK_LEFT), &rect,
+                      slot.buffer.as_ref().unwrap(), false)?;
+        }
+
+        let fence = SyncFence::new(&self.context).map_err(|_| CaptureError::NotSupported)?;
+        slot.pending = Some(PendingRead {
+            fence,
+            index: self.next_index,
+            dimensions: (width, height),
+        });
+
+        self.queue.push_back(self.next_slot);
+        self.next_slot = (self.next_slot + 1) % self.slots.len();
+        self.next_index += 1;
+
+        self.poll();
+        Ok(())
+    }
+
+    /// Hands to the callback all the frames that have finished transferring, without blocking.
+    pub fn poll(&mut self) {
+        while let Some(&slot) = self.queue.front() {
+            let signaled = self.slots[slot].pending.as_ref().unwrap().fence.is_signaled();
+            if !signaled {
+                break;
+            }
+
+            self.queue.pop_front();
+            self.deliver(slot, false);
+        }
+    }
+
+    /// Blocks until all the captured frames have been handed to the callback.
+    pub fn flush(&mut self) {
+        while let Some(slot) = self.queue.pop_front() {
+            self.deliver(slot, true);
+        }
+    }
+
+    /// Reads the content of a slot and passes it to the callback.
+    fn deliver(&mut self, slot: usize, wait: bool) {
+        let slot = &mut self.slots[slot];
+        let PendingRead { fence, index, dimensions: (width, height) } = slot.pending.take().unwrap();
+
+        if wait {
+            fence.wait();
+        }
+
+        let buffer = slot.buffer.as_mut().unwrap();
+        let pixels = buffer.map_read();
+        let pixels = &pixels[.. width as usize * height as usize];
+
+        match self.format {
+            CaptureFormat::Rgba8 => rgba_top_down(pixels, width, height, &mut self.scratch),
+            CaptureFormat::Nv12 => rgba_to_nv12(pixels, width, height, &mut self.scratch),
+        }
+
+        (self.callback)(CapturedFrame {
+            index,
+            width,
+            height,
+            format: self.format,
+            data: &self.scratch,
+        });
+    }
+}
+
+/// Returns true if the backend supports the `FrameRecorder`.
+pub fn is_supported<C>(caps: &C) -> bool where C: ?Sized + CapabilitiesSource {
+    let version = caps.get_version();
+    let extensions = caps.get_extensions();
+
+    let pbo = version >= &Version(Api::Gl, 2, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+              extensions.gl_arb_pixel_buffer_object || extensions.gl_nv_pixel_buffer_object;
+    let sync = version >= &Version(Api::Gl, 3, 2) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_sync || extensions.gl_apple_sync;
+
+    pbo && sync
+}
+
+/// Copies the bottom-up pixels returned by OpenGL into `out`, from top to bottom.
+fn rgba_top_down(pixels: &[(u8, u8, u8, u8)], width: u32, height: u32, out: &mut Vec<u8>) {
+    out.clear();
+    out.reserve(pixels.len() * 4);
+
+    for row in (0 .. height as usize).rev() {
+        let row = &pixels[row * width as usize .. (row + 1) * width as usize];
+        for &(r, g, b, a) in row {
+            out.extend_from_slice(&[r, g, b, a]);
+        }
+    }
+}
+
+/// Converts the bottom-up pixels returned by OpenGL to NV12, from top to bottom.
+fn rgba_to_nv12(pixels: &[(u8, u8, u8, u8)], width: u32, height: u32, out: &mut Vec<u8>) {
+    let (width, height) = (width as usize, height as usize);
+    let (chroma_width, chroma_height) = ((width + 1) / 2, (height + 1) / 2);
+
+    out.clear();
+    out.resize(width * height + chroma_width * chroma_height * 2, 0);
+    let (luma, chroma) = out.split_at_mut(width * height);
+
+    // the pixel at `(x, y)` with `y` counted from the top
+    let pixel = |x: usize, y: usize| {
+        let (r, g, b, _) = pixels[(height - 1 - y) * width + x];
+        (r as i32, g as i32, b as i32)
+    };
+
+    for y in 0 .. height {
+        for x in 0 .. width {
+            let (r, g, b) = pixel(x, y);
+            luma[y * width + x] = (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8;
+        }
+    }
+
+    for cy in 0 .. chroma_height {
+        for cx in 0 .. chroma_width {
+            // averaging the 2x2 block, clamped to the edges of the frame
+            let (mut r, mut g, mut b) = (0, 0, 0);
+            for &(dx, dy) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let x = (cx * 2 + dx).min(width - 1);
+                let y = (cy * 2 + dy).min(height - 1);
+                let p = pixel(x, y);
+                r += p.0;
+                g += p.1;
+                b += p.2;
+            }
+            let (r, g, b) = (r / 4, g / 4, b / 4);
+
+            let offset = (cy * chroma_width + cx) * 2;
+            chroma[offset] = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+            chroma[offset + 1] = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+        }
+    }
+}
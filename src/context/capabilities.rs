@@ -58,6 +58,10 @@ pub struct Capabilities {
     /// will be supported.
     pub forward_compatible: bool,
 
+    /// The context has been created with `GL_KHR_no_error`. Errors are not reported and
+    /// result in undefined behavior instead.
+    pub no_error: bool,
+
     /// True if out-of-bound access on the GPU side can't result in crashes.
     pub robustness: bool,
 
@@ -73,6 +77,14 @@ pub struct Capabilities {
     /// True if the default framebuffer is in sRGB.
     pub srgb: bool,
 
+    /// Number of bits of the red, green, blue and alpha components of the default framebuffer's
+    /// color buffer. All zero if the context doesn't have a default framebuffer.
+    pub color_bits: (u16, u16, u16, u16),
+
+    /// True if the default framebuffer's color buffer stores floating-point values, which aren't
+    /// clamped to `[0.0, 1.0]`. This is the case of the FP16 surfaces used for HDR output.
+    pub float_color: bool,
+
     /// Number of bits in the default framebuffer's depth buffer
     pub depth_bits: Option<u16>,
 
@@ -104,9 +116,17 @@ pub struct Capabilities {
     /// Maximum width and height of `glViewport`.
     pub max_viewport_dims: (gl::types::GLint, gl::types::GLint),
 
+    /// Maximum number of viewports and scissor boxes that can be used at once. `1` if
+    /// viewport arrays are not supported.
+    pub max_viewports: gl::types::GLint,
+
     /// Maximum number of elements that can be passed with `glDrawBuffers`.
     pub max_draw_buffers: gl::types::GLint,
 
+    /// Maximum number of user-defined clip distances that can be enabled at once.
+    /// `0` if the backend doesn't support them.
+    pub max_clip_distances: gl::types::GLint,
+
     /// Maximum number of vertices per patch. `None` if tessellation is not supported.
     pub max_patch_vertices: Option<gl::types::GLint>,
 
@@ -122,6 +142,10 @@ pub struct Capabilities {
     /// Number of available buffer bind points for `GL_UNIFORM_BUFFER`.
     pub max_indexed_uniform_buffer: gl::types::GLint,
 
+    /// Required alignment in bytes of the offset of a range of buffer that is bound to a uniform
+    /// block. `0` if uniform buffers are not supported.
+    pub uniform_buffer_offset_alignment: gl::types::GLint,
+
     /// Number of work groups for compute shaders.
     pub max_compute_work_group_count: (gl::types::GLint, gl::types::GLint, gl::types::GLint),
 
@@ -170,15 +194,20 @@ pub enum ReleaseBehavior {
 pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList)
                                -> Capabilities
 {
-    // GL_CONTEXT_FLAGS are only available from GL 3.0 onwards
-    let (debug, forward_compatible) = if version >= &Version(Api::Gl, 3, 0) {
+    // GL_CONTEXT_FLAGS are only available from GL 3.0 and GLES 3.2 onwards, or with
+    // `GL_KHR_no_error` on older GLES versions
+    let (debug, forward_compatible, no_error) = if version >= &Version(Api::Gl, 3, 0) ||
+        version >= &Version(Api::GlEs, 3, 2) ||
+        (version.0 == Api::GlEs && extensions.gl_khr_no_error)
+    {
         let mut val = 0;
         gl.GetIntegerv(gl::CONTEXT_FLAGS, &mut val);
         let val = val as gl::types::GLenum;
         ((val & gl::CONTEXT_FLAG_DEBUG_BIT) != 0,
-         (val & gl::CONTEXT_FLAG_FORWARD_COMPATIBLE_BIT) != 0)
+         (val & gl::CONTEXT_FLAG_FORWARD_COMPATIBLE_BIT) != 0,
+         (val & gl::CONTEXT_FLAG_NO_ERROR_BIT) != 0)
     } else {
-        (false, false)
+        (false, false, false)
     };
 
     // getting the value of `GL_RENDERER`
@@ -189,6 +218,8 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
                                     .expect("glGetString(GL_RENDERER) returned a non-UTF8 string")
     };
 
+    let (color_bits, float_color) = get_color_buffer_format(gl, version, extensions);
+
     Capabilities {
         supported_glsl_versions: {
             get_supported_glsl(gl, version, extensions)
@@ -229,6 +260,8 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
 
         forward_compatible,
 
+        no_error,
+
         robustness: if version >= &Version(Api::Gl, 4, 5) || version >= &Version(Api::GlEs, 3, 2) ||
                        (version >= &Version(Api::Gl, 3, 0) && extensions.gl_arb_robustness)
         {
@@ -320,6 +353,9 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        color_bits,
+        float_color,
+
         depth_bits: {
             let mut value = 0;
 
@@ -447,6 +483,16 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             (val[0], val[1])
         },
 
+        max_viewports: {
+            if version >= &Version(Api::Gl, 4, 1) || extensions.gl_arb_viewport_array {
+                let mut val = 1;
+                gl.GetIntegerv(gl::MAX_VIEWPORTS, &mut val);
+                val
+            } else {
+                1
+            }
+        },
+
         max_draw_buffers: {
             if version >= &Version(Api::Gl, 2, 0) ||
                 version >= &Version(Api::GlEs, 3, 0) ||
@@ -460,6 +506,19 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        max_clip_distances: {
+            // `GL_MAX_CLIP_PLANES` and `GL_MAX_CLIP_DISTANCES` share the same value
+            if version >= &Version(Api::Gl, 1, 0) || version >= &Version(Api::GlEs, 3, 2) ||
+                extensions.gl_ext_clip_cull_distance
+            {
+                let mut val = 0;
+                gl.GetIntegerv(gl::MAX_CLIP_DISTANCES, &mut val);
+                val
+            } else {
+                0
+            }
+        },
+
         max_patch_vertices: if version >= &Version(Api::Gl, 4, 0) ||
             extensions.gl_arb_tessellation_shader
         {
@@ -482,7 +541,9 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         },
 
         max_indexed_shader_storage_buffer: {
-            if version >= &Version(Api::Gl, 4, 3) || extensions.gl_arb_shader_storage_buffer_object {      // TODO: GLES
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_storage_buffer_object
+            {
                 let mut val = 0;
                 gl.GetIntegerv(gl::MAX_SHADER_STORAGE_BUFFER_BINDINGS, &mut val);
                 val
@@ -515,6 +576,18 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        uniform_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_uniform_buffer_object
+            {
+                let mut val = 0;
+                gl.GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                val
+            } else {
+                0
+            }
+        },
+
         max_compute_work_group_count: if version >= &Version(Api::Gl, 4, 3) ||
                                          version >= &Version(Api::GlEs, 3, 1) ||
                                          extensions.gl_arb_compute_shader
@@ -612,6 +685,58 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
 /// Can panic if the version number or extensions list don't match the backend, leading to
 /// unloaded functions being called.
 ///
+/// Returns the number of bits of each component of the default framebuffer's color buffer, and
+/// whether it stores floating-point values.
+unsafe fn get_color_buffer_format(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList)
+                                  -> ((u16, u16, u16, u16), bool)
+{
+    // same as the depth bits, `GL_RED_BITS` and friends are removed from core contexts
+    if (version >= &Version(Api::Gl, 3, 0) && !extensions.gl_arb_compatibility) ||
+       version >= &Version(Api::GlEs, 3, 0)
+    {
+        let mut fb = 0;
+        gl.GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut fb);
+        let attachment = if fb != 0 {
+            gl::COLOR_ATTACHMENT0
+        } else if version.0 == Api::GlEs {
+            gl::BACK
+        } else {
+            gl::FRONT_LEFT
+        };
+
+        let mut ty = 0;
+        gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment,
+                                               gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE, &mut ty);
+        if ty as gl::types::GLenum == gl::NONE {
+            return ((0, 0, 0, 0), false);
+        }
+
+        let get = |pname| {
+            let mut value = 0;
+            gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment, pname,
+                                                   &mut value);
+            value
+        };
+
+        let bits = (get(gl::FRAMEBUFFER_ATTACHMENT_RED_SIZE) as u16,
+                    get(gl::FRAMEBUFFER_ATTACHMENT_GREEN_SIZE) as u16,
+                    get(gl::FRAMEBUFFER_ATTACHMENT_BLUE_SIZE) as u16,
+                    get(gl::FRAMEBUFFER_ATTACHMENT_ALPHA_SIZE) as u16);
+        let float = get(gl::FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE) as gl::types::GLenum ==
+                    gl::FLOAT;
+        (bits, float)
+
+    } else {
+        let get = |pname| {
+            let mut value = 0;
+            gl.GetIntegerv(pname, &mut value);
+            value as u16
+        };
+
+        ((get(gl::RED_BITS), get(gl::GREEN_BITS), get(gl::BLUE_BITS), get(gl::ALPHA_BITS)), false)
+    }
+}
+
 pub unsafe fn get_supported_glsl(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList)
                                  -> Vec<Version>
 {
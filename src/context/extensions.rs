@@ -14,6 +14,24 @@ macro_rules! extensions {
             )+
         }
 
+        impl ExtensionsList {
+            /// Returns the names of all the extensions that glium knows about, and whether each
+            /// of them is supported by the backend.
+            pub fn iter(&self) -> impl Iterator<Item = (&'static str, bool)> {
+                [$(($string, self.$field),)+].into_iter()
+            }
+
+            /// Returns a copy of the list where the extensions that aren't in `names` are
+            /// marked as unsupported.
+            pub(crate) fn retain(&self, names: &[&str]) -> ExtensionsList {
+                ExtensionsList {
+                    $(
+                        $field: self.$field && names.contains(&$string),
+                    )+
+                }
+            }
+        }
+
         /// Returns the list of extensions supported by the backend.
         ///
         /// The version must match the one of the backend.
@@ -48,6 +66,22 @@ macro_rules! extensions {
     }
 }
 
+/// Extensions that glium keeps using with `FeatureLevel::Gles2`. They are supported by nearly
+/// every OpenGL ES 2.0 implementation, including the Raspberry Pi and Mali-400 class GPUs.
+pub const GLES2_COMMON_EXTENSIONS: &[&str] = &[
+    "GL_EXT_blend_minmax",
+    "GL_EXT_debug_marker",
+    "GL_KHR_debug",
+    "GL_OES_depth_texture",
+    "GL_OES_EGL_image",
+    "GL_OES_EGL_image_external",
+    "GL_OES_element_index_uint",
+    "GL_OES_packed_depth_stencil",
+    "GL_OES_rgb8_rgba8",
+    "GL_OES_vertex_array_object",
+    "GL_OES_vertex_half_float",
+];
+
 extensions! {
     "GL_AMD_depth_clamp_separate" => gl_amd_depth_clamp_separate,
     "GL_AMD_query_buffer_object" => gl_amd_query_buffer_object,
@@ -65,6 +99,7 @@ extensions! {
     "GL_ARB_depth_texture" => gl_arb_depth_texture,
     "GL_ARB_direct_state_access" => gl_arb_direct_state_access,
     "GL_ARB_draw_buffers" => gl_arb_draw_buffers,
+    "GL_ARB_draw_buffers_blend" => gl_arb_draw_buffers_blend,
     "GL_ARB_draw_elements_base_vertex" => gl_arb_draw_elements_base_vertex,
     "GL_ARB_compatibility" => gl_arb_compatibility,
     "GL_ARB_ES2_compatibility" => gl_arb_es2_compatibility,
@@ -86,6 +121,7 @@ extensions! {
     "GL_ARB_occlusion_query" => gl_arb_occlusion_query,
     "GL_ARB_occlusion_query2" => gl_arb_occlusion_query2,
     "GL_ARB_pixel_buffer_object" => gl_arb_pixel_buffer_object,
+    "GL_ARB_polygon_offset_clamp" => gl_arb_polygon_offset_clamp,
     "GL_ARB_program_interface_query" => gl_arb_program_interface_query,
     "GL_ARB_query_buffer_object" => gl_arb_query_buffer_object,
     "GL_ARB_map_buffer_range" => gl_arb_map_buffer_range,
@@ -102,6 +138,7 @@ extensions! {
     "GL_ARB_shader_subroutine" => gl_arb_shader_subroutine,
     "GL_ARB_sync" => gl_arb_sync,
     "GL_ARB_tessellation_shader" => gl_arb_tessellation_shader,
+    "GL_ARB_texture_barrier" => gl_arb_texture_barrier,
     "GL_ARB_texture_buffer_object" => gl_arb_texture_buffer_object,
     "GL_ARB_texture_buffer_object_rgb32" => gl_arb_texture_buffer_object_rgb32,
     "GL_ARB_texture_compression_bptc" => gl_arb_texture_compression_bptc,
@@ -115,6 +152,7 @@ extensions! {
     "GL_ARB_texture_stencil8" => gl_arb_texture_stencil8,
     "GL_ARB_texture_storage" => gl_arb_texture_storage,
     "GL_ARB_timer_query" => gl_arb_timer_query,
+    "GL_ARB_transform_feedback2" => gl_arb_transform_feedback2,
     "GL_ARB_transform_feedback3" => gl_arb_transform_feedback3,
     "GL_ARB_uniform_buffer_object" => gl_arb_uniform_buffer_object,
     "GL_ARB_vertex_array_object" => gl_arb_vertex_array_object,
@@ -123,17 +161,21 @@ extensions! {
     "GL_ARB_vertex_shader" => gl_arb_vertex_shader,
     "GL_ARB_vertex_type_10f_11f_11f_rev" => gl_arb_vertex_type_10f_11f_11f_rev,
     "GL_ARB_vertex_type_2_10_10_10_rev" => gl_arb_vertex_type_2_10_10_10_rev,
+    "GL_ARB_viewport_array" => gl_arb_viewport_array,
     "GL_ARM_rgba8" => gl_arm_rgba8,
     "GL_ATI_meminfo" => gl_ati_meminfo,
     "GL_ATI_draw_buffers" => gl_ati_draw_buffers,
     "GL_ATI_texture_float" => gl_ati_texture_float,
     "GL_EXT_blend_minmax" => gl_ext_blend_minmax,
     "GL_EXT_buffer_storage" => gl_ext_buffer_storage,
+    "GL_EXT_clip_cull_distance" => gl_ext_clip_cull_distance,
     "GL_EXT_debug_marker" => gl_ext_debug_marker,
+    "GL_EXT_depth_clamp" => gl_ext_depth_clamp,
     "GL_EXT_direct_state_access" => gl_ext_direct_state_access,
     "GL_EXT_memory_object" => gl_ext_memory_object,
     "GL_EXT_memory_object_fd" => gl_ext_memory_object_fd,
     "GL_EXT_disjoint_timer_query" => gl_ext_disjoint_timer_query,
+    "GL_EXT_draw_buffers_indexed" => gl_ext_draw_buffers_indexed,
     "GL_EXT_framebuffer_blit" => gl_ext_framebuffer_blit,
     "GL_EXT_framebuffer_object" => gl_ext_framebuffer_object,
     "GL_EXT_framebuffer_multisample" => gl_ext_framebuffer_multisample,
@@ -166,8 +208,11 @@ extensions! {
     "GL_EXT_texture_sRGB" => gl_ext_texture_srgb,
     "GL_EXT_transform_feedback" => gl_ext_transform_feedback,
     "GL_GREMEDY_string_marker" => gl_gremedy_string_marker,
+    "GL_KHR_blend_equation_advanced" => gl_khr_blend_equation_advanced,
+    "GL_KHR_blend_equation_advanced_coherent" => gl_khr_blend_equation_advanced_coherent,
     "GL_KHR_debug" => gl_khr_debug,
     "GL_KHR_context_flush_control" => gl_khr_context_flush_control,
+    "GL_KHR_no_error" => gl_khr_no_error,
     "GL_KHR_robustness" => gl_khr_robustness,
     "GL_KHR_robust_buffer_access_behavior" => gl_khr_robust_buffer_access_behavior,
     "GL_NV_fbo_color_attachments" => gl_nv_fbo_color_attachments,
@@ -182,13 +227,17 @@ extensions! {
     "GL_NV_read_stencil" => gl_nv_read_stencil,
     "GL_NV_read_depth_stencil" => gl_nv_read_depth_stencil,
     "GL_NV_shader_atomic_counters" => gl_nv_shader_atomic_counters,
+    "GL_NV_texture_barrier" => gl_nv_texture_barrier,
     "GL_NV_shader_storage_buffer_object" => gl_nv_shader_storage_buffer_object,
     "GL_NV_texture_array" => gl_nv_texture_array,
     "GL_NV_transform_feedback" => gl_nv_transform_feedback,
     "GL_NV_vertex_attrib_integer_64bit" => gl_nv_vertex_attrib_integer_64bit,
     "GL_NVX_gpu_memory_info" => gl_nvx_gpu_memory_info,
     "GL_OES_depth_texture" => gl_oes_depth_texture,
+    "GL_OES_draw_buffers_indexed" => gl_oes_draw_buffers_indexed,
     "GL_OES_draw_elements_base_vertex" => gl_oes_draw_elements_base_vertex,
+    "GL_OES_EGL_image" => gl_oes_egl_image,
+    "GL_OES_EGL_image_external" => gl_oes_egl_image_external,
     "GL_OES_element_index_uint" => gl_oes_element_index_uint,
     "GL_OES_fixed_point" => gl_oes_fixed_point,
     "GL_OES_geometry_shader" => gl_oes_geometry_shader,
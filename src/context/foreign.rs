@@ -0,0 +1,454 @@
+//! Interoperability with code that uses the same OpenGL context as glium.
+//!
+//! Glium caches the state of the context to avoid redundant calls. When another renderer (a UI
+//! toolkit, a game engine, etc.) changes the state behind its back, the cache must be
+//! invalidated. Bindings are marked as unknown, so that glium binds its objects again the next
+//! time it needs them. Capabilities are read back from the context, since glium can't represent
+//! an unknown boolean, and the other values are replaced with values that never match.
+//!
+//! `ScopedForeignFrame` also saves the part of the state that renderers usually depend on, and
+//! restores it once glium is done.
+
+use crate::context::{CommandContext, Context};
+use crate::gl;
+use crate::version::{Api, Version};
+use crate::ContextExt;
+use crate::Handle;
+
+/// Value used for object names and enums whose current value is unknown. OpenGL never returns
+/// this name for a new object, and no enum has this value.
+const UNKNOWN: gl::types::GLuint = gl::types::GLuint::MAX;
+
+/// Number of texture units whose bindings are saved by `ScopedForeignFrame`.
+const SAVED_UNITS: gl::types::GLint = 32;
+
+/// Synchronizes glium's state cache with the actual state of the context.
+pub unsafe fn invalidate(ctxt: &mut CommandContext<'_>) {
+    let desktop = ctxt.version >= &Version(Api::Gl, 1, 0);
+    let gl3 = ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0);
+    let srgb = ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+               ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control;
+    let vaos = gl3 || ctxt.extensions.gl_arb_vertex_array_object ||
+               ctxt.extensions.gl_oes_vertex_array_object ||
+               ctxt.extensions.gl_apple_vertex_array_object;
+    let samplers = ctxt.version >= &Version(Api::Gl, 3, 3) ||
+                   ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                   ctxt.extensions.gl_arb_sampler_objects;
+
+    let gl = ctxt.gl;
+    let is_enabled = |cap| gl.IsEnabled(cap) != gl::FALSE;
+    let get_integer = |name| {
+        let mut value = 0;
+        gl.GetIntegerv(name, &mut value);
+        value
+    };
+
+    let state = &mut *ctxt.state;
+
+    // capabilities
+    state.enabled_blend = is_enabled(gl::BLEND);
+    state.enabled_cull_face = is_enabled(gl::CULL_FACE);
+    state.enabled_depth_test = is_enabled(gl::DEPTH_TEST);
+    state.enabled_dither = is_enabled(gl::DITHER);
+    state.enabled_polygon_offset_fill = is_enabled(gl::POLYGON_OFFSET_FILL);
+    state.enabled_sample_alpha_to_coverage = is_enabled(gl::SAMPLE_ALPHA_TO_COVERAGE);
+    state.enabled_sample_coverage = is_enabled(gl::SAMPLE_COVERAGE);
+    state.enabled_scissor_test = is_enabled(gl::SCISSOR_TEST);
+    state.enabled_stencil_test = is_enabled(gl::STENCIL_TEST);
+
+    if desktop {
+        state.enabled_color_logic_op = is_enabled(gl::COLOR_LOGIC_OP);
+        state.enabled_multisample = is_enabled(gl::MULTISAMPLE);
+        state.enabled_polygon_offset_line = is_enabled(gl::POLYGON_OFFSET_LINE);
+        state.enabled_polygon_offset_point = is_enabled(gl::POLYGON_OFFSET_POINT);
+        state.enabled_sample_alpha_to_one = is_enabled(gl::SAMPLE_ALPHA_TO_ONE);
+        state.enabled_line_smooth = is_enabled(gl::LINE_SMOOTH);
+        state.enabled_polygon_smooth = is_enabled(gl::POLYGON_SMOOTH);
+    }
+
+    if srgb {
+        state.enabled_framebuffer_srgb = is_enabled(gl::FRAMEBUFFER_SRGB);
+    }
+
+    if gl3 {
+        state.enabled_rasterizer_discard = is_enabled(gl::RASTERIZER_DISCARD);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 2) {
+        state.enabled_program_point_size = is_enabled(gl::PROGRAM_POINT_SIZE);
+        state.enabled_depth_clamp_near = is_enabled(gl::DEPTH_CLAMP);
+        state.enabled_depth_clamp_far = state.enabled_depth_clamp_near;
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+        state.enabled_primitive_fixed_restart = is_enabled(gl::PRIMITIVE_RESTART_FIXED_INDEX);
+    }
+
+    if desktop && gl3 {
+        let planes = ctxt.capabilities.max_clip_distances.clamp(0, 32) as gl::types::GLuint;
+        state.enabled_clip_planes = (0 .. planes)
+            .filter(|&i| is_enabled(gl::CLIP_DISTANCE0 + i))
+            .fold(0, |planes, i| planes | (1 << i));
+    }
+
+    // write masks, which can't be represented as unknown either
+    let mut depth_mask = gl::TRUE;
+    gl.GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+    state.depth_mask = depth_mask != gl::FALSE;
+    state.stencil_mask_front = get_integer(gl::STENCIL_WRITEMASK) as gl::types::GLuint;
+    state.stencil_mask_back = get_integer(gl::STENCIL_BACK_WRITEMASK) as gl::types::GLuint;
+
+    // the active texture unit is used as an index by glium
+    state.active_texture = get_integer(gl::ACTIVE_TEXTURE) as gl::types::GLenum - gl::TEXTURE0;
+
+    // binding an element array buffer modifies the current vertex array object, so it must be
+    // known exactly
+    if vaos {
+        state.vertex_array = get_integer(gl::VERTEX_ARRAY_BINDING) as gl::types::GLuint;
+    }
+
+    // bindings
+    state.program = Handle::Id(UNKNOWN);
+    state.array_buffer_binding = UNKNOWN;
+    state.pixel_pack_buffer_binding = UNKNOWN;
+    state.pixel_unpack_buffer_binding = UNKNOWN;
+    state.uniform_buffer_binding = UNKNOWN;
+    state.copy_read_buffer_binding = UNKNOWN;
+    state.copy_write_buffer_binding = UNKNOWN;
+    state.dispatch_indirect_buffer_binding = UNKNOWN;
+    state.draw_indirect_buffer_binding = UNKNOWN;
+    state.query_buffer_binding = UNKNOWN;
+    state.texture_buffer_binding = UNKNOWN;
+    state.atomic_counter_buffer_binding = UNKNOWN;
+    state.shader_storage_buffer_binding = UNKNOWN;
+    for binding in state.indexed_uniform_buffer_bindings.iter_mut()
+        .chain(state.indexed_atomic_counter_buffer_bindings.iter_mut())
+        .chain(state.indexed_shader_storage_buffer_bindings.iter_mut())
+        .chain(state.indexed_transform_feedback_buffer_bindings.iter_mut())
+    {
+        binding.buffer = UNKNOWN;
+    }
+    state.read_framebuffer = UNKNOWN;
+    state.draw_framebuffer = UNKNOWN;
+    state.default_framebuffer_read = None;
+    state.renderbuffer = UNKNOWN;
+
+    // every texture unit is marked, since the units that glium hasn't used yet are assumed to
+    // be empty
+    let units = ctxt.capabilities.max_combined_texture_image_units.max(1) as usize;
+    state.texture_units.resize(units, Default::default());
+    for unit in state.texture_units.iter_mut() {
+        unit.texture = UNKNOWN;
+        unit.sampler = if samplers { UNKNOWN } else { 0 };
+    }
+
+    // values
+    let unknown_float = f32::NAN;
+    state.clear_color = (unknown_float, unknown_float, unknown_float, unknown_float);
+    state.clear_depth = unknown_float;
+    state.clear_stencil = gl::types::GLint::MIN;
+    state.color_mask = (2, 2, 2, 2);
+    // glium sets the blending of every draw buffer again, since blending can be enabled for
+    // some of them only
+    state.attachment_blend = true;
+    state.blend_equation = (UNKNOWN, UNKNOWN);
+    state.blend_func = (UNKNOWN, UNKNOWN, UNKNOWN, UNKNOWN);
+    state.blend_color = state.clear_color;
+    state.logic_op = UNKNOWN;
+    state.depth_func = UNKNOWN;
+    state.depth_range = (unknown_float, unknown_float);
+    state.stencil_func_front = (UNKNOWN, 0, 0);
+    state.stencil_func_back = (UNKNOWN, 0, 0);
+    state.stencil_op_front = (UNKNOWN, UNKNOWN, UNKNOWN);
+    state.stencil_op_back = (UNKNOWN, UNKNOWN, UNKNOWN);
+    state.viewport = None;
+    state.scissor = None;
+    state.viewports.clear();
+    state.scissors.clear();
+    state.line_width = unknown_float;
+    state.point_size = unknown_float;
+    state.cull_face = UNKNOWN;
+    state.polygon_mode = UNKNOWN;
+    state.polygon_offset = (unknown_float, unknown_float, unknown_float);
+    state.smooth = (UNKNOWN, UNKNOWN);
+    state.provoking_vertex = UNKNOWN;
+    state.clip_control = (UNKNOWN, UNKNOWN);
+    state.pixel_store_unpack_alignment = 0;
+    state.pixel_store_pack_alignment = 0;
+    state.clamp_color = UNKNOWN;
+    state.patch_patch_vertices = 0;
+    state.patch_default_outer_level = [unknown_float; 4];
+    state.patch_default_inner_level = [unknown_float; 2];
+    state.primitive_bounding_box = (unknown_float, unknown_float, unknown_float, unknown_float,
+                                    unknown_float, unknown_float, unknown_float, unknown_float);
+}
+
+/// The part of the state of the context that is saved and restored by `ScopedForeignFrame`.
+struct SavedState {
+    capabilities: Vec<(gl::types::GLenum, bool)>,
+    program: gl::types::GLint,
+    vertex_array: Option<gl::types::GLint>,
+    array_buffer: gl::types::GLint,
+    element_array_buffer: gl::types::GLint,
+    // draw and read framebuffers, or the only framebuffer binding of OpenGL ES 2
+    framebuffers: (gl::types::GLint, Option<gl::types::GLint>),
+    renderbuffer: gl::types::GLint,
+    active_texture: gl::types::GLint,
+    textures_2d: Vec<gl::types::GLint>,
+    viewport: [gl::types::GLint; 4],
+    scissor: [gl::types::GLint; 4],
+    blend_equation: [gl::types::GLint; 2],
+    blend_func: [gl::types::GLint; 4],
+    blend_color: [gl::types::GLfloat; 4],
+    color_mask: [gl::types::GLboolean; 4],
+    depth_func: gl::types::GLint,
+    depth_mask: gl::types::GLboolean,
+    cull_face: gl::types::GLint,
+    line_width: gl::types::GLfloat,
+    clear_color: [gl::types::GLfloat; 4],
+    clear_depth: gl::types::GLfloat,
+    clear_stencil: gl::types::GLint,
+    // function, reference, value mask, fail, depth fail, pass, write mask
+    stencil_front: [gl::types::GLint; 7],
+    stencil_back: [gl::types::GLint; 7],
+    unpack_alignment: gl::types::GLint,
+    pack_alignment: gl::types::GLint,
+}
+
+unsafe fn save(ctxt: &mut CommandContext<'_>) -> SavedState {
+    let gl3 = ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0);
+    let gl = ctxt.gl;
+
+    let get_integer = |name| {
+        let mut value = 0;
+        gl.GetIntegerv(name, &mut value);
+        value
+    };
+    let get_integers = |name, values: &mut [gl::types::GLint]| {
+        gl.GetIntegerv(name, values.as_mut_ptr());
+    };
+    let get_floats = |name, values: &mut [gl::types::GLfloat]| {
+        gl.GetFloatv(name, values.as_mut_ptr());
+    };
+
+    let mut capabilities = vec![gl::BLEND, gl::CULL_FACE, gl::DEPTH_TEST, gl::DITHER,
+                                gl::POLYGON_OFFSET_FILL, gl::SAMPLE_ALPHA_TO_COVERAGE,
+                                gl::SCISSOR_TEST, gl::STENCIL_TEST];
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+       ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
+    {
+        capabilities.push(gl::FRAMEBUFFER_SRGB);
+    }
+    if gl3 {
+        capabilities.push(gl::RASTERIZER_DISCARD);
+    }
+    if ctxt.version >= &Version(Api::Gl, 1, 0) {
+        capabilities.push(gl::MULTISAMPLE);
+    }
+
+    let vaos = gl3 || ctxt.extensions.gl_arb_vertex_array_object ||
+               ctxt.extensions.gl_oes_vertex_array_object ||
+               ctxt.extensions.gl_apple_vertex_array_object;
+
+    let framebuffers = if gl3 {
+        (get_integer(gl::DRAW_FRAMEBUFFER_BINDING), Some(get_integer(gl::READ_FRAMEBUFFER_BINDING)))
+    } else {
+        (get_integer(gl::FRAMEBUFFER_BINDING), None)
+    };
+
+    // glium uses the first units after its cache has been invalidated
+    let active_texture = get_integer(gl::ACTIVE_TEXTURE);
+    let units = ctxt.capabilities.max_combined_texture_image_units.clamp(0, SAVED_UNITS);
+    let textures_2d = (0 .. units as gl::types::GLenum).map(|unit| {
+        gl.ActiveTexture(gl::TEXTURE0 + unit);
+        get_integer(gl::TEXTURE_BINDING_2D)
+    }).collect();
+    gl.ActiveTexture(active_texture as gl::types::GLenum);
+
+    let mut saved = SavedState {
+        capabilities: capabilities.into_iter().map(|cap| (cap, gl.IsEnabled(cap) != gl::FALSE))
+                                  .collect(),
+        program: get_integer(gl::CURRENT_PROGRAM),
+        vertex_array: if vaos { Some(get_integer(gl::VERTEX_ARRAY_BINDING)) } else { None },
+        array_buffer: get_integer(gl::ARRAY_BUFFER_BINDING),
+        element_array_buffer: get_integer(gl::ELEMENT_ARRAY_BUFFER_BINDING),
+        framebuffers,
+        renderbuffer: get_integer(gl::RENDERBUFFER_BINDING),
+        active_texture,
+        textures_2d,
+        viewport: [0; 4],
+        scissor: [0; 4],
+        blend_equation: [get_integer(gl::BLEND_EQUATION_RGB),
+                         get_integer(gl::BLEND_EQUATION_ALPHA)],
+        blend_func: [get_integer(gl::BLEND_SRC_RGB), get_integer(gl::BLEND_DST_RGB),
+                     get_integer(gl::BLEND_SRC_ALPHA), get_integer(gl::BLEND_DST_ALPHA)],
+        blend_color: [0.0; 4],
+        color_mask: [gl::TRUE; 4],
+        depth_func: get_integer(gl::DEPTH_FUNC),
+        depth_mask: gl::TRUE,
+        cull_face: get_integer(gl::CULL_FACE_MODE),
+        line_width: 1.0,
+        clear_color: [0.0; 4],
+        clear_depth: 1.0,
+        clear_stencil: get_integer(gl::STENCIL_CLEAR_VALUE),
+        stencil_front: [
+            get_integer(gl::STENCIL_FUNC), get_integer(gl::STENCIL_REF),
+            get_integer(gl::STENCIL_VALUE_MASK), get_integer(gl::STENCIL_FAIL),
+            get_integer(gl::STENCIL_PASS_DEPTH_FAIL), get_integer(gl::STENCIL_PASS_DEPTH_PASS),
+            get_integer(gl::STENCIL_WRITEMASK),
+        ],
+        stencil_back: [
+            get_integer(gl::STENCIL_BACK_FUNC), get_integer(gl::STENCIL_BACK_REF),
+            get_integer(gl::STENCIL_BACK_VALUE_MASK), get_integer(gl::STENCIL_BACK_FAIL),
+            get_integer(gl::STENCIL_BACK_PASS_DEPTH_FAIL),
+            get_integer(gl::STENCIL_BACK_PASS_DEPTH_PASS), get_integer(gl::STENCIL_BACK_WRITEMASK),
+        ],
+        unpack_alignment: get_integer(gl::UNPACK_ALIGNMENT),
+        pack_alignment: get_integer(gl::PACK_ALIGNMENT),
+    };
+
+    get_integers(gl::VIEWPORT, &mut saved.viewport);
+    get_integers(gl::SCISSOR_BOX, &mut saved.scissor);
+    get_floats(gl::BLEND_COLOR, &mut saved.blend_color);
+    get_floats(gl::COLOR_CLEAR_VALUE, &mut saved.clear_color);
+    get_floats(gl::DEPTH_CLEAR_VALUE, std::slice::from_mut(&mut saved.clear_depth));
+    get_floats(gl::LINE_WIDTH, std::slice::from_mut(&mut saved.line_width));
+    gl.GetBooleanv(gl::COLOR_WRITEMASK, saved.color_mask.as_mut_ptr());
+    gl.GetBooleanv(gl::DEPTH_WRITEMASK, &mut saved.depth_mask);
+
+    saved
+}
+
+unsafe fn restore(ctxt: &mut CommandContext<'_>, saved: &SavedState) {
+    let gl = ctxt.gl;
+
+    for &(cap, enabled) in &saved.capabilities {
+        if enabled { gl.Enable(cap) } else { gl.Disable(cap) }
+    }
+
+    // the vertex array object must be restored before the element array buffer, which is part
+    // of it
+    gl.UseProgram(saved.program as gl::types::GLuint);
+    if let Some(vertex_array) = saved.vertex_array {
+        let vertex_array = vertex_array as gl::types::GLuint;
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+           ctxt.extensions.gl_arb_vertex_array_object
+        {
+            gl.BindVertexArray(vertex_array);
+        } else if ctxt.extensions.gl_oes_vertex_array_object {
+            gl.BindVertexArrayOES(vertex_array);
+        } else {
+            gl.BindVertexArrayAPPLE(vertex_array);
+        }
+    }
+    gl.BindBuffer(gl::ARRAY_BUFFER, saved.array_buffer as gl::types::GLuint);
+    gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, saved.element_array_buffer as gl::types::GLuint);
+
+    match saved.framebuffers {
+        (draw, Some(read)) => {
+            gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, draw as gl::types::GLuint);
+            gl.BindFramebuffer(gl::READ_FRAMEBUFFER, read as gl::types::GLuint);
+        },
+        (framebuffer, None) => {
+            gl.BindFramebuffer(gl::FRAMEBUFFER, framebuffer as gl::types::GLuint);
+        },
+    }
+    gl.BindRenderbuffer(gl::RENDERBUFFER, saved.renderbuffer as gl::types::GLuint);
+
+    for (unit, &texture) in saved.textures_2d.iter().enumerate() {
+        gl.ActiveTexture(gl::TEXTURE0 + unit as gl::types::GLenum);
+        gl.BindTexture(gl::TEXTURE_2D, texture as gl::types::GLuint);
+    }
+    gl.ActiveTexture(saved.active_texture as gl::types::GLenum);
+
+    let [x, y, width, height] = saved.viewport;
+    gl.Viewport(x, y, width, height);
+    let [x, y, width, height] = saved.scissor;
+    gl.Scissor(x, y, width, height);
+
+    let [rgb, alpha] = saved.blend_equation;
+    gl.BlendEquationSeparate(rgb as gl::types::GLenum, alpha as gl::types::GLenum);
+    let [src_rgb, dst_rgb, src_alpha, dst_alpha] = saved.blend_func;
+    gl.BlendFuncSeparate(src_rgb as gl::types::GLenum, dst_rgb as gl::types::GLenum,
+                         src_alpha as gl::types::GLenum, dst_alpha as gl::types::GLenum);
+    let [r, g, b, a] = saved.blend_color;
+    gl.BlendColor(r, g, b, a);
+    let [r, g, b, a] = saved.color_mask;
+    gl.ColorMask(r, g, b, a);
+
+    gl.DepthFunc(saved.depth_func as gl::types::GLenum);
+    gl.DepthMask(saved.depth_mask);
+    gl.CullFace(saved.cull_face as gl::types::GLenum);
+    gl.LineWidth(saved.line_width);
+
+    let [r, g, b, a] = saved.clear_color;
+    gl.ClearColor(r, g, b, a);
+    if ctxt.version >= &Version(Api::Gl, 1, 0) {
+        gl.ClearDepth(saved.clear_depth as gl::types::GLclampd);
+    } else {
+        gl.ClearDepthf(saved.clear_depth);
+    }
+    gl.ClearStencil(saved.clear_stencil);
+
+    for (face, stencil) in [(gl::FRONT, saved.stencil_front), (gl::BACK, saved.stencil_back)] {
+        let [func, reference, value_mask, fail, depth_fail, pass, write_mask] = stencil;
+        gl.StencilFuncSeparate(face, func as gl::types::GLenum, reference,
+                               value_mask as gl::types::GLuint);
+        gl.StencilOpSeparate(face, fail as gl::types::GLenum, depth_fail as gl::types::GLenum,
+                             pass as gl::types::GLenum);
+        gl.StencilMaskSeparate(face, write_mask as gl::types::GLuint);
+    }
+
+    gl.PixelStorei(gl::UNPACK_ALIGNMENT, saved.unpack_alignment);
+    gl.PixelStorei(gl::PACK_ALIGNMENT, saved.pack_alignment);
+}
+
+/// Guard that lets glium render with an OpenGL context owned by another renderer.
+///
+/// Creating the guard saves the state that renderers usually depend on and invalidates glium's
+/// state cache. Dropping it restores the saved state, and invalidates the cache again. Glium
+/// objects can be used normally while the guard is alive.
+///
+/// ```no_run
+/// # fn example(context: &glium::backend::Context) {
+/// // in the paint callback of the toolkit, with its OpenGL context current
+/// let _guard = glium::backend::ScopedForeignFrame::new(context);
+/// // draw with glium
+/// # }
+/// ```
+///
+/// The saved state includes the enabled capabilities, the bound program, vertex array object,
+/// array and element array buffers, framebuffers and renderbuffer, the 2D textures bound to the
+/// first 32 texture units, the viewport, the scissor box, the blending, depth and
+/// stencil parameters, the color mask, the clear values, the line width and the pixel store
+/// alignments. The rest of the state is left as glium set it.
+pub struct ScopedForeignFrame<'a> {
+    context: &'a Context,
+    saved: Option<SavedState>,
+}
+
+impl<'a> ScopedForeignFrame<'a> {
+    /// Saves the state of the context and invalidates glium's state cache.
+    pub fn new(context: &'a Context) -> ScopedForeignFrame<'a> {
+        let mut ctxt = context.make_current();
+
+        let saved = unsafe {
+            let saved = save(&mut ctxt);
+            invalidate(&mut ctxt);
+            saved
+        };
+
+        ScopedForeignFrame { context, saved: Some(saved) }
+    }
+}
+
+impl Drop for ScopedForeignFrame<'_> {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        unsafe {
+            restore(&mut ctxt, &self.saved.take().unwrap());
+            invalidate(&mut ctxt);
+        }
+    }
+}
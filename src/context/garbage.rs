@@ -0,0 +1,177 @@
+//! Deferred deletion of OpenGL objects.
+//!
+//! When enabled with `Context::set_deferred_deletion`, dropping a buffer, a texture or a
+//! renderbuffer doesn't call `glDelete*` immediately. Instead the object is put in a queue, and
+//! is destroyed once enough frames have been swapped and the GPU has reached a fence inserted
+//! at the end of the frame the object was dropped in.
+
+use std::collections::VecDeque;
+use std::mem;
+
+use crate::buffer;
+use crate::context::CommandContext;
+use crate::framebuffer;
+use crate::gl;
+use crate::sync::{self, LinearSyncFence};
+use crate::texture;
+
+/// An object whose deletion has been deferred.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GarbageObject {
+    Buffer(gl::types::GLuint),
+    Texture(gl::types::GLuint),
+    Renderbuffer(gl::types::GLuint),
+}
+
+/// The objects dropped during a frame that has been swapped.
+struct RetiredFrame {
+    /// Value of `GarbageQueue::frame` when the objects were dropped.
+    frame: u64,
+    /// Fence inserted after the frame. `None` if the backend doesn't support sync objects.
+    fence: Option<LinearSyncFence>,
+    objects: Vec<GarbageObject>,
+}
+
+/// Queue of objects waiting to be deleted.
+#[derive(Default)]
+pub struct GarbageQueue {
+    /// Number of frames an object is kept alive after being dropped. 0 means that objects are
+    /// deleted immediately.
+    delay: u32,
+
+    /// Number of frames that have been swapped since the creation of the context.
+    frame: u64,
+
+    /// Objects dropped during the current frame.
+    pending: Vec<GarbageObject>,
+
+    /// Objects dropped during previous frames, oldest first.
+    retired: VecDeque<RetiredFrame>,
+}
+
+impl GarbageQueue {
+    /// Changes the number of frames objects are kept alive after being dropped.
+    #[inline]
+    pub fn set_delay(&mut self, frames: u32) {
+        self.delay = frames;
+    }
+
+    /// Puts the object in the queue if deletion is deferred.
+    ///
+    /// Returns `false` if the caller must destroy the object itself.
+    #[inline]
+    pub fn defer(&mut self, object: GarbageObject) -> bool {
+        if self.delay == 0 {
+            return false;
+        }
+
+        self.pending.push(object);
+        true
+    }
+
+    /// Returns the number of objects waiting to be deleted.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pending.len() + self.retired.iter().map(|f| f.objects.len()).sum::<usize>()
+    }
+}
+
+/// Must be called after the buffers have been swapped. Retires the objects dropped during the
+/// frame and deletes the ones whose delay has elapsed.
+///
+/// Returns the number of deleted objects.
+pub unsafe fn end_frame(ctxt: &mut CommandContext<'_>) -> usize {
+    retire_pending(ctxt);
+    ctxt.garbage.frame += 1;
+
+    let mut destroyed = 0;
+
+    while let Some(retired) = ctxt.garbage.retired.pop_front() {
+        if ctxt.garbage.frame - retired.frame <= ctxt.garbage.delay as u64 ||
+           !is_signaled(ctxt, &retired) {
+            ctxt.garbage.retired.push_front(retired);
+            break;
+        }
+
+        destroyed += destroy_frame(ctxt, retired);
+    }
+
+    destroyed
+}
+
+/// Deletes every object whose frame has been finished by the GPU, no matter how many frames
+/// have been swapped since then. Objects dropped during the current frame are retired first.
+///
+/// Without sync objects support, everything is deleted.
+///
+/// Returns the number of deleted objects.
+pub unsafe fn collect(ctxt: &mut CommandContext<'_>) -> usize {
+    retire_pending(ctxt);
+
+    let mut destroyed = 0;
+
+    while let Some(retired) = ctxt.garbage.retired.pop_front() {
+        if !is_signaled(ctxt, &retired) {
+            ctxt.garbage.retired.push_front(retired);
+            break;
+        }
+
+        destroyed += destroy_frame(ctxt, retired);
+    }
+
+    destroyed
+}
+
+/// Deletes all the objects of the queue without waiting. Used when the context is destroyed.
+pub unsafe fn destroy_all(ctxt: &mut CommandContext<'_>) {
+    let pending = mem::take(&mut ctxt.garbage.pending);
+    for object in pending {
+        destroy_object(ctxt, object);
+    }
+
+    while let Some(retired) = ctxt.garbage.retired.pop_front() {
+        destroy_frame(ctxt, retired);
+    }
+}
+
+/// Moves the objects dropped during the current frame to the list of retired frames, along
+/// with a fence.
+unsafe fn retire_pending(ctxt: &mut CommandContext<'_>) {
+    if ctxt.garbage.pending.is_empty() {
+        return;
+    }
+
+    let objects = mem::take(&mut ctxt.garbage.pending);
+    let fence = sync::new_linear_sync_fence(ctxt).ok();
+    let frame = ctxt.garbage.frame;
+    ctxt.garbage.retired.push_back(RetiredFrame { frame, fence, objects });
+}
+
+unsafe fn is_signaled(ctxt: &mut CommandContext<'_>, retired: &RetiredFrame) -> bool {
+    match retired.fence {
+        Some(ref fence) => sync::is_linear_sync_fence_signaled(ctxt, fence),
+        None => true,
+    }
+}
+
+unsafe fn destroy_frame(ctxt: &mut CommandContext<'_>, retired: RetiredFrame) -> usize {
+    if let Some(fence) = retired.fence {
+        sync::destroy_linear_sync_fence(ctxt, fence);
+    }
+
+    let count = retired.objects.len();
+    for object in retired.objects {
+        destroy_object(ctxt, object);
+    }
+    count
+}
+
+/// Deletes the object and removes it from glium's state cache. The object stays bound while it
+/// is in the queue, so the cache is only updated here.
+unsafe fn destroy_object(ctxt: &mut CommandContext<'_>, object: GarbageObject) {
+    match object {
+        GarbageObject::Buffer(id) => buffer::delete_buffer(ctxt, id),
+        GarbageObject::Texture(id) => texture::delete_texture(ctxt, id),
+        GarbageObject::Renderbuffer(id) => framebuffer::delete_renderbuffer(ctxt, id),
+    }
+}
@@ -2,21 +2,26 @@
 
 use crate::gl;
 
+use std::any::Any;
 use std::collections::HashMap;
 use std::mem;
+use std::panic;
 use std::ptr;
 use std::str;
 use std::borrow::Cow;
 use std::cell::{Cell, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::ffi::CStr;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::os::raw;
 use std::hash::BuildHasherDefault;
+use std::thread::{self, ThreadId};
+use std::time::Duration;
 
 use fnv::FnvHasher;
 
 use crate::IncompatibleOpenGl;
+use crate::Rect;
 use crate::SwapBuffersError;
 use crate::CapabilitiesSource;
 use crate::ContextExt;
@@ -25,8 +30,10 @@ use crate::version;
 use crate::version::Api;
 use crate::version::Version;
 
+use crate::buffer;
 use crate::debug;
 use crate::fbo;
+use crate::framebuffer::WindowSizedTarget;
 use crate::ops;
 use crate::sampler_object;
 use crate::texture;
@@ -35,11 +42,17 @@ use crate::vertex_array_object;
 
 pub use self::capabilities::{ReleaseBehavior, Capabilities, Profile};
 pub use self::extensions::ExtensionsList;
+pub use self::foreign::ScopedForeignFrame;
+pub use self::garbage::GarbageObject;
+pub use self::share::SharedObject;
 pub use self::state::GlState;
 pub use self::uuid::UuidError;
 
 mod capabilities;
 mod extensions;
+mod foreign;
+mod garbage;
+mod share;
 mod state;
 mod uuid;
 
@@ -74,11 +87,27 @@ pub struct Context {
     /// The callback that is used by the debug output feature.
     debug_callback: Option<debug::DebugCallback>,
 
+    /// True if `debug_callback` was provided by the user. Such a callback isn't required to be
+    /// `Send`, which prevents the context from being moved to another thread.
+    custom_debug_callback: bool,
+
+    /// A panic that happened inside `debug_callback`. Unwinding through the OpenGL driver isn't
+    /// possible, so the panic is caught and resumed when the current `CommandContext` is
+    /// dropped.
+    debug_callback_panic: Cell<Option<Box<dyn Any + Send>>>,
+
+    /// The thread the OpenGL context is current on. In debug builds, using the context from
+    /// another thread panics.
+    owner_thread: Cell<ThreadId>,
+
     /// Whether or not errors triggered by ARB_debug_output (and similar extensions) should be
     /// reported to the user when `DebugCallbackBehavior::DebugMessageOnError` is used. This must
     /// be set to `false` in some situations, like compiling/linking shaders.
     report_debug_output_errors: Cell<bool>,
 
+    /// How data is uploaded to buffers. Selected by the user or by `probe_upload_paths`.
+    upload_path: Cell<buffer::UploadPath>,
+
     /// We maintain a cache of FBOs.
     /// The `Option` is here in order to destroy the container. It must be filled at all time
     /// is a normal situation.
@@ -97,8 +126,48 @@ pub struct Context {
     /// List of images handles that are resident. We need to call `MakeImageHandleResidentARB`
     /// when rebuilding the context.
     resident_image_handles: RefCell<Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
+
+    /// Objects whose deletion has been deferred by `set_deferred_deletion`.
+    garbage: RefCell<garbage::GarbageQueue>,
+
+    /// List of the render targets that must be rebuilt when the context is resized. Dead
+    /// entries are removed during the next resize.
+    window_sized_targets: RefCell<Vec<Weak<dyn WindowSizedTarget>>>,
+
+    /// Functions that recreate resources after a context loss. See `register_recreation_hook`.
+    recreation_hooks: RefCell<Vec<RecreationHook>>,
+
+    /// The other contexts that share objects with this one. See `share_objects_with`.
+    shared_contexts: RefCell<Vec<Weak<Context>>>,
+
+    /// Shared objects that have been destroyed by another context and that must be removed from
+    /// the caches of this one. Processed by `make_current`.
+    foreign_deletions: RefCell<Vec<SharedObject>>,
+
+    /// The features of OpenGL that glium is allowed to use.
+    feature_level: FeatureLevel,
+}
+
+/// Status returned by `Context::get_reset_status`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResetStatus {
+    /// The context hasn't been reset.
+    NoError,
+
+    /// The context has been reset because of a command of this context, for example a shader
+    /// that ran for too long.
+    GuiltyContextReset,
+
+    /// The context has been reset because of another context.
+    InnocentContextReset,
+
+    /// The context has been reset for an unknown reason.
+    UnknownContextReset,
 }
 
+/// A function registered with `Context::register_recreation_hook`.
+type RecreationHook = Box<dyn FnMut(&Rc<Context>)>;
+
 /// This struct is a guard that is returned when you want to access the OpenGL backend.
 pub struct CommandContext<'a> {
     /// Source of OpenGL function pointers.
@@ -121,6 +190,9 @@ pub struct CommandContext<'a> {
     /// reported to the user (by panicking).
     pub report_debug_output_errors: &'a Cell<bool>,
 
+    /// Panic caught in the debug callback, resumed when this struct is dropped.
+    debug_callback_panic: &'a Cell<Option<Box<dyn Any + Send>>>,
+
     /// The list of vertex array objects.
     pub vertex_array_objects: &'a vertex_array_object::VertexAttributesSystem,
 
@@ -136,6 +208,9 @@ pub struct CommandContext<'a> {
     /// List of image handles and their access that need to be made resident.
     pub resident_image_handles: RefMut<'a, Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
 
+    /// Queue of objects waiting to be deleted.
+    pub garbage: RefMut<'a, garbage::GarbageQueue>,
+
     /// This marker is here to prevent `CommandContext` from implementing `Send`
     // TODO: use this when possible
     //impl<'a, 'b> !Send for CommandContext<'a, 'b> {}
@@ -155,12 +230,37 @@ impl Context {
     /// The OpenGL context must be newly-created. If you make modifications to the context before
     /// passing it to this function, glium's state cache may mismatch the actual one.
     ///
+    #[inline]
     pub unsafe fn new<B>(
         backend: B,
         check_current_context: bool,
         callback_behavior: DebugCallbackBehavior,
     ) -> Result<Rc<Context>, IncompatibleOpenGl>
         where B: Backend + 'static
+    {
+        Context::with_feature_level(backend, check_current_context, callback_behavior,
+                                    FeatureLevel::Full)
+    }
+
+    /// The same as `new`, but restricts the OpenGL features that glium uses.
+    ///
+    /// With `FeatureLevel::Gles2`, the context must be an OpenGL ES context, and glium behaves
+    /// as if it was an OpenGL ES 2.0 context that only supports the extensions that are common
+    /// on this class of hardware, even if the implementation supports more. Objects that need
+    /// more, like uniform buffers or integral textures, fail to be created with the same errors
+    /// as on an actual OpenGL ES 2.0 implementation. This allows testing an application meant
+    /// for older embedded devices on a recent GPU.
+    ///
+    /// # Safety
+    ///
+    /// The same as `new`.
+    pub unsafe fn with_feature_level<B>(
+        backend: B,
+        check_current_context: bool,
+        callback_behavior: DebugCallbackBehavior,
+        feature_level: FeatureLevel,
+    ) -> Result<Rc<Context>, IncompatibleOpenGl>
+        where B: Backend + 'static
     {
         backend.make_current();
 
@@ -171,6 +271,19 @@ impl Context {
         let extensions = extensions::get_extensions(&gl, &version);
         check_gl_compatibility(&version, &extensions)?;
 
+        let (version, extensions) = match feature_level {
+            FeatureLevel::Full => (version, extensions),
+            FeatureLevel::Gles2 => {
+                if version.0 != Api::GlEs {
+                    return Err(IncompatibleOpenGl("The OpenGL ES 2.0 feature level requires an \
+                                                   OpenGL ES context".to_owned()));
+                }
+
+                let extensions = extensions.retain(extensions::GLES2_COMMON_EXTENSIONS);
+                (Version(Api::GlEs, 2, 0), extensions)
+            },
+        };
+
         let capabilities = capabilities::get_capabilities(&gl, &version, &extensions);
         let report_debug_output_errors = Cell::new(true);
 
@@ -183,7 +296,9 @@ impl Context {
         });
         let resident_texture_handles = RefCell::new(Vec::new());
         let resident_image_handles = RefCell::new(Vec::new());
+        let window_sized_targets = RefCell::new(Vec::new());
 
+        let custom_debug_callback = matches!(callback_behavior, DebugCallbackBehavior::Custom { .. });
         let (debug_callback, synchronous) = match callback_behavior {
             DebugCallbackBehavior::Ignore => (None, false),
             DebugCallbackBehavior::DebugMessageOnError => {
@@ -204,7 +319,11 @@ impl Context {
             extensions,
             capabilities,
             debug_callback,
+            custom_debug_callback,
+            debug_callback_panic: Cell::new(None),
+            owner_thread: Cell::new(thread::current().id()),
             report_debug_output_errors,
+            upload_path: Cell::new(Default::default()),
             backend: RefCell::new(Box::new(backend)),
             check_current_context,
             framebuffer_objects: Some(framebuffer_objects),
@@ -212,9 +331,16 @@ impl Context {
             samplers,
             resident_texture_handles,
             resident_image_handles,
+            garbage: RefCell::new(Default::default()),
+            window_sized_targets,
+            recreation_hooks: RefCell::new(Vec::new()),
+            shared_contexts: RefCell::new(Vec::new()),
+            foreign_deletions: RefCell::new(Vec::new()),
+            feature_level,
         });
 
-        if context.debug_callback.is_some() {
+        // debug output isn't available in `GL_KHR_no_error` contexts
+        if context.debug_callback.is_some() && !context.capabilities.no_error {
             init_debug_callback(&context, synchronous);
         }
 
@@ -242,6 +368,32 @@ impl Context {
         self.backend.borrow().get_framebuffer_dimensions()
     }
 
+    /// Resizes the surface of the backend, then rebuilds the render targets that have been
+    /// registered with `framebuffer::WindowSized`.
+    ///
+    /// Dimensions of zero are replaced with one.
+    pub fn resize(&self, new_size: (u32, u32)) {
+        self.backend.borrow().resize(new_size);
+
+        let dimensions = (new_size.0.max(1), new_size.1.max(1));
+
+        // the list isn't borrowed during the callbacks, as they can register new targets
+        let targets = {
+            let mut targets = self.window_sized_targets.borrow_mut();
+            targets.retain(|target| target.strong_count() != 0);
+            targets.iter().filter_map(|target| target.upgrade()).collect::<Vec<_>>()
+        };
+
+        for target in targets {
+            target.resize(dimensions);
+        }
+    }
+
+    /// Adds a render target to the list of targets to rebuild in `resize`.
+    pub(crate) fn register_window_sized_target(&self, target: Weak<dyn WindowSizedTarget>) {
+        self.window_sized_targets.borrow_mut().push(target);
+    }
+
     /// Changes the OpenGL context associated with this context.
     ///
     /// The new context **must** have lists shared with the old one.
@@ -258,7 +410,14 @@ impl Context {
 
         new_backend.make_current();
 
-        *self.state.borrow_mut() = Default::default();
+        {
+            let mut state = self.state.borrow_mut();
+            let state_audit = state.state_audit.take();
+            *state = Default::default();
+
+            // the bindings of the new context are unknown
+            state.state_audit = state_audit.map(|mut audit| { audit.forget_bindings(); audit });
+        }
         // FIXME: verify version, capabilities and extensions
         *self.backend.borrow_mut() = Box::new(new_backend);
 
@@ -277,9 +436,103 @@ impl Context {
         Ok(())
     }
 
+    /// Tells glium that the OpenGL contexts of `self` and `other` share their objects, and that
+    /// buffers, textures, renderbuffers and programs created with one of them can be used with
+    /// the other.
+    ///
+    /// Glium keeps a separate state cache, and separate vertex array objects and framebuffer
+    /// objects, for each context. When a shared object is dropped, it is removed from the caches
+    /// of all the contexts of the group. If `other` already shares objects with other contexts,
+    /// `self` joins this group.
+    ///
+    /// Commands of a context are flushed when another context is made current, which is the
+    /// default release behavior, so objects modified by one context are up to date when used
+    /// by another one on the same thread.
+    ///
+    /// # Safety
+    ///
+    /// The OpenGL contexts must have been created to share their objects, for example by
+    /// passing one of them to glutin's `ContextAttributesBuilder::with_sharing` when creating
+    /// the other.
+    pub unsafe fn share_objects_with(self: &Rc<Self>, other: &Rc<Context>) {
+        let mut group = other.shared_contexts.borrow().iter()
+            .filter_map(|context| context.upgrade())
+            .collect::<Vec<_>>();
+        group.push(other.clone());
+
+        for context in group {
+            if Rc::ptr_eq(&context, self) {
+                continue;
+            }
+
+            let mut shared = context.shared_contexts.borrow_mut();
+            shared.retain(|c| c.strong_count() != 0);
+            shared.push(Rc::downgrade(self));
+
+            self.shared_contexts.borrow_mut().push(Rc::downgrade(&context));
+        }
+    }
+
+    /// Returns true if `other` is this context, or a context of its share group.
+    pub(crate) fn shares_objects_with(&self, other: &Context) -> bool {
+        ptr::eq(self, other) || self.shared_contexts.borrow().iter()
+            .any(|context| ptr::eq(context.as_ptr(), other))
+    }
+
+    /// Asks the other contexts of the share group to remove a destroyed object from their caches.
+    pub(crate) fn forget_in_shared_contexts(&self, object: SharedObject) {
+        for context in self.shared_contexts.borrow().iter() {
+            if let Some(context) = context.upgrade() {
+                context.foreign_deletions.borrow_mut().push(object);
+            }
+        }
+    }
+
+    /// Returns true if the context can be moved to another thread, which is the case if glium
+    /// holds the only reference to it and no custom debug callback or recreation hook has been
+    /// registered.
+    pub(crate) fn is_movable(self: &Rc<Self>) -> bool {
+        Rc::strong_count(self) == 1 && Rc::weak_count(self) == 0 && !self.custom_debug_callback &&
+            self.recreation_hooks.borrow().is_empty()
+    }
+
+    /// Tells the context that the OpenGL context is now current on the calling thread.
+    pub(crate) fn set_owner_thread(&self) {
+        self.owner_thread.set(thread::current().id());
+    }
+
+    /// Panics if the calling thread isn't the one the OpenGL context belongs to.
+    fn assert_owner_thread(&self) {
+        let current = thread::current();
+        if current.id() != self.owner_thread.get() {
+            panic!("glium context used from thread {:?} ({:?}) but it is current on thread {:?}; \
+                    use `SendContext` to move a context between threads",
+                   current.name().unwrap_or("<unnamed>"), current.id(), self.owner_thread.get());
+        }
+    }
+
     /// Swaps the buffers in the backend.
+    #[inline]
     pub fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
-        if self.state.borrow().lost_context {
+        self.present(None)
+    }
+
+    /// Swaps the buffers in the backend, telling the compositor that only the given regions of
+    /// the default framebuffer have changed since the previous frame.
+    ///
+    /// Backends that can't pass the damage to the compositor swap the whole surface instead.
+    /// An empty slice means that the whole surface has changed.
+    #[inline]
+    pub fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.present(Some(damage))
+    }
+
+    fn present(&self, damage: Option<&[Rect]>) -> Result<(), SwapBuffersError> {
+        if cfg!(debug_assertions) {
+            self.assert_owner_thread();
+        }
+
+        if self.capabilities.can_lose_context && self.is_context_lost() {
             return Err(SwapBuffersError::ContextLost);
         }
 
@@ -289,6 +542,7 @@ impl Context {
         //       Therefore we need to bind the default framebuffer before swapping.
         if self.state.borrow().draw_framebuffer != 0 || self.state.borrow().read_framebuffer != 0 {
             let mut ctxt = self.make_current();
+            ctxt.state.audit("glBindFramebuffer", 0, debug::StateAudit::hash((0, 0)));
 
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                ctxt.extensions.gl_arb_framebuffer_object
@@ -315,10 +569,24 @@ impl Context {
         }
 
         // swapping
-        let err = backend.swap_buffers();
+        let err = match damage {
+            Some(damage) if !damage.is_empty() => backend.swap_buffers_with_damage(damage),
+            _ => backend.swap_buffers(),
+        };
         if let Err(SwapBuffersError::ContextLost) = err {
             self.state.borrow_mut().lost_context = true;
         }
+        drop(backend);
+
+        if err.is_ok() {
+            let mut ctxt = self.make_current();
+            unsafe { garbage::end_frame(&mut ctxt) };
+
+            // the textures rendered to during this frame aren't considered for feedback loops
+            // anymore, so that the list doesn't grow forever
+            ctxt.state.textures_written_since_barrier.clear();
+        }
+
         err
     }
 
@@ -330,11 +598,19 @@ impl Context {
     }
 
     /// Returns the OpenGL version detected by this context.
+    ///
+    /// With `FeatureLevel::Gles2`, this is always OpenGL ES 2.0.
     #[inline]
     pub fn get_opengl_version(&self) -> &Version {
         &self.version
     }
 
+    /// Returns the feature level that was passed when creating the context.
+    #[inline]
+    pub fn get_feature_level(&self) -> FeatureLevel {
+        self.feature_level
+    }
+
     /// Returns the GLSL version guaranteed to be supported.
     #[inline]
     pub fn get_supported_glsl_version(&self) -> Version {
@@ -392,6 +668,18 @@ impl Context {
         self.capabilities().profile
     }
 
+    /// Returns true if the context has been created with `GL_KHR_no_error`.
+    ///
+    /// In this mode the driver doesn't check the commands, and glium skips its own calls to
+    /// `glGetError` and doesn't register a debug callback. `assert_no_error` never panics.
+    ///
+    /// Such a context can be requested by passing `Robustness::NoError` to glutin, for example
+    /// only in release builds so that errors are still reported during development.
+    #[inline]
+    pub fn is_no_error(&self) -> bool {
+        self.capabilities().no_error
+    }
+
     /// Returns true if out-of-bound buffer access from the GPU side (inside a program) cannot
     /// result in a crash.
     ///
@@ -412,30 +700,98 @@ impl Context {
     /// # Implementation
     ///
     /// If it has been determined that the context has been lost before, then the function
-    /// immediately returns true. Otherwise, calls `glGetGraphicsResetStatus`. If this function
-    /// is not available, returns false.
+    /// immediately returns true. Otherwise, calls `get_reset_status`.
     pub fn is_context_lost(&self) -> bool {
         if self.state.borrow().lost_context {
             return true;
         }
 
+        self.get_reset_status() != ResetStatus::NoError
+    }
+
+    /// Calls `glGetGraphicsResetStatus` to know whether the context has been reset since the
+    /// last call, and whether this context caused it.
+    ///
+    /// Returns `NoError` if the function is not available. Resets are only reported by contexts
+    /// created with a robustness that loses the context on reset, see
+    /// `glutin::context::Robustness::RobustLoseContextOnReset`.
+    pub fn get_reset_status(&self) -> ResetStatus {
         let mut ctxt = self.make_current();
 
-        let lost = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
-                      ctxt.version >= &Version(Api::GlEs, 3, 2) ||
-                      ctxt.extensions.gl_khr_robustness
+        let status = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+                        ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                        ctxt.extensions.gl_khr_robustness
         {
-            unsafe { ctxt.gl.GetGraphicsResetStatus() != gl::NO_ERROR }
+            unsafe { ctxt.gl.GetGraphicsResetStatus() }
         } else if ctxt.extensions.gl_ext_robustness {
-            unsafe { ctxt.gl.GetGraphicsResetStatusEXT() != gl::NO_ERROR }
+            unsafe { ctxt.gl.GetGraphicsResetStatusEXT() }
         } else if ctxt.extensions.gl_arb_robustness {
-            unsafe { ctxt.gl.GetGraphicsResetStatusARB() != gl::NO_ERROR }
+            unsafe { ctxt.gl.GetGraphicsResetStatusARB() }
         } else {
-            false
+            gl::NO_ERROR
+        };
+
+        let status = match status {
+            gl::GUILTY_CONTEXT_RESET => ResetStatus::GuiltyContextReset,
+            gl::INNOCENT_CONTEXT_RESET => ResetStatus::InnocentContextReset,
+            gl::UNKNOWN_CONTEXT_RESET => ResetStatus::UnknownContextReset,
+            _ => ResetStatus::NoError,
         };
 
-        if lost { ctxt.state.lost_context = true; }
-        lost
+        if status != ResetStatus::NoError { ctxt.state.lost_context = true; }
+        status
+    }
+
+    /// Registers a function that recreates a resource after the context has been lost.
+    ///
+    /// The hooks aren't called by this context. Once a context loss has been detected, build a
+    /// new context and call `recreate_resources` on it. The hooks then receive the new context,
+    /// and are usually used to upload the content of a texture or a buffer again and to replace
+    /// the object held by the application.
+    ///
+    /// ```no_run
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// use glium::backend::Facade;
+    /// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+    /// let pixels = vec![0u8; 4 * 16 * 16];
+    /// let load = move |facade: &Rc<glium::backend::Context>| {
+    ///     let image = glium::texture::RawImage2d::from_raw_rgba(pixels.clone(), (16, 16));
+    ///     glium::Texture2d::new(facade, image).unwrap()
+    /// };
+    ///
+    /// let texture = Rc::new(RefCell::new(load(display.get_context())));
+    /// let slot = Rc::downgrade(&texture);
+    /// display.register_recreation_hook(move |new_context| {
+    ///     if let Some(slot) = slot.upgrade() {
+    ///         *slot.borrow_mut() = load(new_context);
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn register_recreation_hook<F>(&self, hook: F) where F: FnMut(&Rc<Context>) + 'static {
+        self.recreation_hooks.borrow_mut().push(Box::new(hook));
+    }
+
+    /// Moves the recreation hooks of a lost context to this context, then calls them in the
+    /// order in which they have been registered.
+    ///
+    /// The hooks stay registered on this context, so that resources can be recreated again if
+    /// this context is lost as well. Returns the number of hooks that have been called.
+    pub fn recreate_resources(self: &Rc<Self>, lost: &Context) -> usize {
+        let mut hooks = mem::take(&mut *lost.recreation_hooks.borrow_mut());
+
+        // the list isn't borrowed during the calls, as the hooks can register new hooks
+        for hook in hooks.iter_mut() {
+            hook(self);
+        }
+
+        let count = hooks.len();
+        let mut registered = self.recreation_hooks.borrow_mut();
+        hooks.append(&mut registered);
+        *registered = hooks;
+        count
     }
 
     /// Returns the behavior when the current OpenGL context is changed.
@@ -477,6 +833,178 @@ impl Context {
         }
     }
 
+    /// Samples the GPU clock and the CPU clock at the same time, in order to convert GPU
+    /// timestamps to `Instant`s.
+    ///
+    /// The GPU timestamp is retrieved several times and the sample with the smallest round-trip
+    /// is kept. This doesn't wait for the commands queue to be executed.
+    ///
+    /// Returns `None` if the backend doesn't support retrieving the GPU timestamp.
+    pub fn calibrate_gpu_clock(&self) -> Option<debug::GpuClockCalibration> {
+        // number of times the timestamp is retrieved
+        const SAMPLES: u32 = 8;
+
+        let ctxt = self.make_current();
+
+        if !(ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query ||
+             (ctxt.version >= &Version(Api::GlEs, 3, 0) &&
+              ctxt.extensions.gl_ext_disjoint_timer_query))
+        {
+            return None;
+        }
+
+        let mut best: Option<debug::GpuClockCalibration> = None;
+
+        for _ in 0 .. SAMPLES {
+            let before = std::time::Instant::now();
+            let mut gpu_timestamp = 0;
+            unsafe { ctxt.gl.GetInteger64v(gl::TIMESTAMP, &mut gpu_timestamp) };
+            let after = std::time::Instant::now();
+
+            let uncertainty = (after - before) / 2;
+            if !matches!(best, Some(b) if b.get_uncertainty() <= uncertainty) {
+                best = Some(debug::GpuClockCalibration::new(before + uncertainty,
+                                                            gpu_timestamp as u64, uncertainty));
+            }
+        }
+
+        best
+    }
+
+    /// Measures the bandwidth of the different ways glium can upload data to buffers, then uses
+    /// the fastest one for the rest of the lifetime of the context.
+    ///
+    /// The fastest path varies a lot between drivers, and it isn't possible to know which one to
+    /// use without trying them. This function uploads a few megabytes several times with each
+    /// path that the backend supports and waits for the uploads to finish, so it should be called
+    /// once at startup, not during rendering.
+    ///
+    /// Returns the average time each supported path took for one upload.
+    pub fn probe_upload_paths(&self) -> Vec<(buffer::UploadPath, Duration)> {
+        let results = {
+            let mut ctxt = self.make_current();
+            buffer::probe_upload_paths(&mut ctxt)
+        };
+
+        if let Some(&(fastest, _)) = results.iter().min_by_key(|(_, duration)| *duration) {
+            self.upload_path.set(fastest);
+        }
+
+        results
+    }
+
+    /// Returns the way glium uploads data to buffers.
+    #[inline]
+    pub fn get_upload_path(&self) -> buffer::UploadPath {
+        self.upload_path.get()
+    }
+
+    /// Changes the way glium uploads data to buffers.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the backend doesn't support this path. The supported paths are the ones
+    /// returned by `probe_upload_paths`.
+    pub fn set_upload_path(&self, path: buffer::UploadPath) {
+        assert!(buffer::is_upload_path_supported(self, path),
+                "The backend doesn't support uploading with {:?}", path);
+        self.upload_path.set(path);
+    }
+
+    /// Returns the number of draw calls, compute dispatches and state changes submitted to this
+    /// context since its creation.
+    ///
+    /// The counters are reset if the context is rebuilt. See also `debug::Profiler`, which
+    /// reports them per frame.
+    #[inline]
+    pub fn get_statistics(&self) -> debug::Statistics {
+        self.state.borrow().statistics
+    }
+
+    /// Defers the deletion of buffers, textures and renderbuffers by the given number of frames.
+    ///
+    /// Dropping one of these objects normally deletes it right away. The driver keeps it alive
+    /// for as long as it is used by commands that haven't been executed yet, but some drivers
+    /// synchronize with the GPU to do so. When a delay is set, the object is instead queued and
+    /// deleted by `swap_buffers` once `frames` more frames have been swapped and the GPU has
+    /// finished the frame the object was dropped in.
+    ///
+    /// Passing `0`, which is the default, deletes objects immediately again. Objects that are
+    /// already queued stay queued until they are collected.
+    pub fn set_deferred_deletion(&self, frames: u32) {
+        self.garbage.borrow_mut().set_delay(frames);
+    }
+
+    /// Deletes the queued objects whose frame has been finished by the GPU, without waiting for
+    /// the delay set by `set_deferred_deletion` to elapse. Returns the number of deleted
+    /// objects.
+    ///
+    /// `swap_buffers` already collects the queue, so this is only useful when rendering
+    /// without swapping buffers, or to free memory early.
+    pub fn collect_garbage(&self) -> usize {
+        let mut ctxt = self.make_current();
+        unsafe { garbage::collect(&mut ctxt) }
+    }
+
+    /// Returns the number of objects waiting to be deleted because of `set_deferred_deletion`.
+    pub fn pending_garbage(&self) -> usize {
+        self.garbage.borrow().len()
+    }
+
+    /// Enables or disables the state audit.
+    ///
+    /// While the audit is enabled, glium counts its calls to the OpenGL functions that bind
+    /// objects or set uniforms, and detects the calls that bind a value that was already bound.
+    /// These calls are supposed to be avoided by glium's state cache, so they are worth
+    /// reporting. Calls made by the user through `exec_in_context` aren't counted.
+    ///
+    /// The audit slows down every state change, so it shouldn't be left enabled in release
+    /// builds. Disabling it discards the calls that haven't been reported.
+    pub fn set_state_audit(&self, enabled: bool) {
+        let mut state = self.state.borrow_mut();
+
+        if !enabled {
+            state.state_audit = None;
+        } else if state.state_audit.is_none() {
+            state.state_audit = Some(Default::default());
+        }
+    }
+
+    /// Returns the calls recorded by the state audit since the last call to this function,
+    /// and starts a new recording. Returns `None` if the audit is disabled.
+    ///
+    /// Call this once per frame to get per-frame numbers.
+    ///
+    /// ```no_run
+    /// # fn example(context: &glium::backend::Context) {
+    /// context.set_state_audit(true);
+    /// // draw the frame
+    /// if let Some(report) = context.take_state_audit_report() {
+    ///     for entry in report.top_offenders(5) {
+    ///         println!("{}: {} redundant calls out of {}", entry.function, entry.redundant,
+    ///                  entry.calls);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn take_state_audit_report(&self) -> Option<debug::StateAuditReport> {
+        self.state.borrow_mut().state_audit.as_mut().map(|audit| audit.take_report())
+    }
+
+    /// Returns the most recent OpenGL calls made by glium with their arguments, oldest first,
+    /// for example `glBindBuffer(34962, 3)`.
+    ///
+    /// Up to 4096 calls are kept. Calls made by the user through `exec_in_context` aren't
+    /// included. The list is meant to be attached to bug reports, especially for bugs that only
+    /// happen with some drivers.
+    ///
+    /// Only available with the `gl_trace` feature, which formats every call and is therefore
+    /// slow.
+    #[cfg(feature = "gl_trace")]
+    pub fn dump_recent_calls(&self) -> Vec<String> {
+        self.gl.calls.borrow().iter().cloned().collect()
+    }
+
     /// Returns an estimate of the amount of video memory available in bytes.
     ///
     /// Returns `None` if no estimate is available.
@@ -542,6 +1070,20 @@ impl Context {
         action()
     }
 
+    /// Tells glium that the state of the OpenGL context has been modified by something else,
+    /// for example by another renderer that uses the same context.
+    ///
+    /// Glium keeps a cache of the state of the context and skips the calls that wouldn't change
+    /// anything. After this function, the objects are bound again and the state is set again
+    /// the next time glium needs them. Calls made through `exec_in_context` that restore the
+    /// state don't need this.
+    ///
+    /// See also `ScopedForeignFrame`, which also restores the state of the other renderer.
+    pub fn invalidate_cached_state(&self) {
+        let mut ctxt = self.make_current();
+        unsafe { foreign::invalidate(&mut ctxt) };
+    }
+
     /// Asserts that there are no OpenGL errors pending.
     ///
     /// This function should be used in tests.
@@ -627,6 +1169,100 @@ impl Context {
             Ok(())
         }
     }
+
+    /// Opens a named group of commands. OpenGL debuggers such as RenderDoc or apitrace display
+    /// all the commands issued until the matching `pop_debug_group` as children of this group.
+    ///
+    /// Groups can be nested. Each call to `push_debug_group` that returns `Ok` must be matched
+    /// by a call to `pop_debug_group`.
+    ///
+    /// Returns `Err` if the backend supports neither `GL_KHR_debug` nor `GL_EXT_debug_marker`.
+    pub fn push_debug_group(&self, name: &str) -> Result<(), ()> {
+        let ctxt = self.make_current();
+        let name = name.as_bytes();
+
+        if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+        {
+            unsafe { ctxt.gl.PushDebugGroup(gl::DEBUG_SOURCE_APPLICATION, 0,
+                                            name.len() as gl::types::GLsizei,
+                                            name.as_ptr() as *const _) };
+            Ok(())
+
+        } else if ctxt.extensions.gl_khr_debug {
+            unsafe { ctxt.gl.PushDebugGroupKHR(gl::DEBUG_SOURCE_APPLICATION, 0,
+                                               name.len() as gl::types::GLsizei,
+                                               name.as_ptr() as *const _) };
+            Ok(())
+
+        } else if ctxt.extensions.gl_ext_debug_marker {
+            unsafe { ctxt.gl.PushGroupMarkerEXT(name.len() as gl::types::GLsizei,
+                                                name.as_ptr() as *const _) };
+            Ok(())
+
+        } else {
+            Err(())
+        }
+    }
+
+    /// Closes the group opened by the last call to `push_debug_group`.
+    ///
+    /// Returns `Err` if the backend doesn't support debug groups.
+    pub fn pop_debug_group(&self) -> Result<(), ()> {
+        let ctxt = self.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+        {
+            unsafe { ctxt.gl.PopDebugGroup() };
+            Ok(())
+
+        } else if ctxt.extensions.gl_khr_debug {
+            unsafe { ctxt.gl.PopDebugGroupKHR() };
+            Ok(())
+
+        } else if ctxt.extensions.gl_ext_debug_marker {
+            unsafe { ctxt.gl.PopGroupMarkerEXT() };
+            Ok(())
+
+        } else {
+            Err(())
+        }
+    }
+
+    /// Returns the address of a function of the windowing API, as reported by the backend.
+    ///
+    /// The context must be current.
+    pub(crate) unsafe fn get_proc_address(&self, symbol: &str) -> *const raw::c_void {
+        self.backend.borrow().get_proc_address(symbol)
+    }
+
+    /// Attaches a human-readable label to an OpenGL object. Used by the `set_label` methods
+    /// of the various objects.
+    ///
+    /// `identifier` is the namespace of the object, for example `gl::BUFFER` or `gl::TEXTURE`.
+    pub(crate) fn set_object_label(&self, identifier: gl::types::GLenum, id: gl::types::GLuint,
+                                   label: &str) -> Result<(), ()>
+    {
+        let ctxt = self.make_current();
+        let label = label.as_bytes();
+
+        if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+        {
+            unsafe { ctxt.gl.ObjectLabel(identifier, id, label.len() as gl::types::GLsizei,
+                                         label.as_ptr() as *const _) };
+            Ok(())
+
+        } else if ctxt.extensions.gl_khr_debug {
+            unsafe { ctxt.gl.ObjectLabelKHR(identifier, id, label.len() as gl::types::GLsizei,
+                                            label.as_ptr() as *const _) };
+            Ok(())
+
+        } else {
+            Err(())
+        }
+    }
 }
 
 impl ContextExt for Context {
@@ -636,6 +1272,10 @@ impl ContextExt for Context {
     }
 
     fn make_current(&self) -> CommandContext<'_> {
+        if cfg!(debug_assertions) {
+            self.assert_owner_thread();
+        }
+
         if self.check_current_context {
             let backend = self.backend.borrow();
             if !backend.is_current() {
@@ -644,20 +1284,29 @@ impl ContextExt for Context {
             }
         }
 
-        CommandContext {
+        let mut ctxt = CommandContext {
             gl: &self.gl,
             state: self.state.borrow_mut(),
             version: &self.version,
             extensions: &self.extensions,
             capabilities: &self.capabilities,
             report_debug_output_errors: &self.report_debug_output_errors,
+            debug_callback_panic: &self.debug_callback_panic,
             vertex_array_objects: &self.vertex_array_objects,
             framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
             samplers: self.samplers.borrow_mut(),
             resident_texture_handles: self.resident_texture_handles.borrow_mut(),
             resident_image_handles: self.resident_image_handles.borrow_mut(),
+            garbage: self.garbage.borrow_mut(),
             marker: PhantomData,
+        };
+
+        if !self.foreign_deletions.borrow().is_empty() {
+            let objects = mem::take(&mut *self.foreign_deletions.borrow_mut());
+            unsafe { share::forget_objects(&mut ctxt, objects) };
         }
+
+        ctxt
     }
 
     #[inline]
@@ -683,6 +1332,16 @@ impl CapabilitiesSource for Context {
     }
 }
 
+impl<'a> Drop for CommandContext<'a> {
+    fn drop(&mut self) {
+        if let Some(payload) = self.debug_callback_panic.take() {
+            if !thread::panicking() {
+                panic::resume_unwind(payload);
+            }
+        }
+    }
+}
+
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
@@ -702,16 +1361,19 @@ impl Drop for Context {
                 extensions: &self.extensions,
                 capabilities: &self.capabilities,
                 report_debug_output_errors: &self.report_debug_output_errors,
+                debug_callback_panic: &self.debug_callback_panic,
                 vertex_array_objects: &self.vertex_array_objects,
                 framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
                 samplers: self.samplers.borrow_mut(),
                 resident_texture_handles: self.resident_texture_handles.borrow_mut(),
                 resident_image_handles: self.resident_image_handles.borrow_mut(),
+                garbage: self.garbage.borrow_mut(),
                 marker: PhantomData,
             };
 
             fbo::FramebuffersContainer::cleanup(&mut ctxt);
             vertex_array_object::VertexAttributesSystem::cleanup(&mut ctxt);
+            garbage::destroy_all(&mut ctxt);
 
             for (_, s) in mem::replace(&mut *ctxt.samplers, HashMap::with_hasher(Default::default())) {
                 s.destroy(&mut ctxt);
@@ -803,6 +1465,10 @@ pub enum DebugCallbackBehavior {
     PrintAll,
 
     /// Use a custom callback.
+    ///
+    /// `debug::DebugOutputFilter` can be used to drop some of the messages before they reach
+    /// the callback, and `debug::log_callback` builds a callback that writes to the `log`
+    /// crate.
     Custom {
         /// The function to be called.
         callback: debug::DebugCallback,
@@ -811,6 +1477,19 @@ pub enum DebugCallbackBehavior {
     },
 }
 
+/// Restricts the OpenGL features that glium uses, regardless of what the backend supports.
+///
+/// See `Context::with_feature_level`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum FeatureLevel {
+    /// Everything that the backend supports is used. This is the default.
+    #[default]
+    Full,
+
+    /// Only OpenGL ES 2.0 and the extensions that are common on OpenGL ES 2.0 hardware are used.
+    Gles2,
+}
+
 impl Default for DebugCallbackBehavior {
     #[inline]
     fn default() -> DebugCallbackBehavior {
@@ -933,9 +1612,17 @@ fn init_debug_callback(context: &Rc<Context>, synchronous: bool) {
         };
 
         if let Some(callback) = user_param.debug_callback.as_mut() {
-            // FIXME: catch_panic here once it's stable
-            callback(source, ty, severity, id, user_param.report_debug_output_errors.get(),
-                     &message);
+            let report_debug_output_errors = user_param.report_debug_output_errors.get();
+
+            // unwinding through the driver is undefined behavior, so the panic is stored and
+            // resumed by the `CommandContext` that issued the faulty command
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                callback(source, ty, severity, id, report_debug_output_errors, &message)
+            }));
+
+            if let Err(payload) = result {
+                user_param.debug_callback_panic.set(Some(payload));
+            }
         }
     }
 
@@ -0,0 +1,59 @@
+//! Objects shared between contexts.
+//!
+//! Buffers, textures, renderbuffers and programs can be shared between OpenGL contexts, but
+//! glium's caches aren't: each context has its own state cache, and its own vertex array objects
+//! and framebuffer objects. When a shared object is destroyed, the other contexts of the group
+//! must forget about it before they reuse its name.
+//!
+//! The other contexts aren't made current when the object is destroyed. Instead the object is
+//! put in their list of foreign deletions, which is processed the next time they are used.
+
+use crate::buffer;
+use crate::context::CommandContext;
+use crate::fbo::FramebuffersContainer;
+use crate::framebuffer;
+use crate::gl;
+use crate::texture;
+use crate::vertex_array_object::VertexAttributesSystem;
+use crate::Handle;
+
+/// An object that has been destroyed by another context of the share group.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SharedObject {
+    Buffer(gl::types::GLuint),
+    Texture(gl::types::GLuint),
+    Renderbuffer(gl::types::GLuint),
+    Program(Handle),
+}
+
+/// Removes objects destroyed by another context from the caches of the current context.
+///
+/// Vertex array objects and framebuffer objects that use them are destroyed, and the bindings
+/// of the state cache are reset. The objects themselves may stay bound in this context until
+/// something else is bound, which keeps them alive but is harmless.
+pub unsafe fn forget_objects(ctxt: &mut CommandContext<'_>, objects: Vec<SharedObject>) {
+    for object in objects {
+        match object {
+            SharedObject::Buffer(id) => {
+                VertexAttributesSystem::purge_buffer(ctxt, id);
+                buffer::forget_buffer(ctxt, id);
+            },
+            SharedObject::Texture(id) => {
+                FramebuffersContainer::purge_texture(ctxt, id);
+                texture::forget_texture(ctxt, id);
+            },
+            SharedObject::Renderbuffer(id) => {
+                FramebuffersContainer::purge_renderbuffer(ctxt, id);
+                framebuffer::forget_renderbuffer(ctxt, id);
+            },
+            SharedObject::Program(handle) => {
+                VertexAttributesSystem::purge_program(ctxt, handle);
+
+                // the next program will be bound even if it reuses the same name
+                if ctxt.state.program == handle {
+                    ctxt.state.program = Handle::Id(0);
+                }
+            },
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::Handle;
+use crate::debug::{StateAudit, Statistics};
 use crate::gl;
 use smallvec::SmallVec;
 
@@ -15,6 +16,9 @@ pub struct GlState {
     /// Whether GL_BLEND is enabled
     pub enabled_blend: bool,
 
+    /// Whether GL_COLOR_LOGIC_OP is enabled
+    pub enabled_color_logic_op: bool,
+
     /// Whether GL_CULL_FACE is enabled
     pub enabled_cull_face: bool,
 
@@ -60,6 +64,9 @@ pub struct GlState {
     /// Whether GL_SAMPLE_ALPHA_TO_COVERAGE is enabled
     pub enabled_sample_alpha_to_coverage: bool,
 
+    /// Whether GL_SAMPLE_ALPHA_TO_ONE is enabled
+    pub enabled_sample_alpha_to_one: bool,
+
     /// Whether GL_SAMPLE_COVERAGE is enabled
     pub enabled_sample_coverage: bool,
 
@@ -173,6 +180,17 @@ pub struct GlState {
     pub blend_color: (gl::types::GLclampf, gl::types::GLclampf,
                       gl::types::GLclampf, gl::types::GLclampf),
 
+    /// True if the blending state of some draw buffers has been changed with `glEnablei`,
+    /// `glBlendFuncSeparatei`, etc. since the last time it was set for all the draw buffers.
+    /// The cached blending values are then only valid for the other buffers.
+    pub attachment_blend: bool,
+
+    /// True if `glColorMaski` has been called since the last call to `glColorMask`.
+    pub attachment_color_mask: bool,
+
+    /// The latest value passed to `glLogicOp`.
+    pub logic_op: gl::types::GLenum,
+
     /// The latest value passed to `glDepthFunc`.
     pub depth_func: gl::types::GLenum,
 
@@ -208,6 +226,14 @@ pub struct GlState {
     pub scissor: Option<(gl::types::GLint, gl::types::GLint,
                          gl::types::GLsizei, gl::types::GLsizei)>,
 
+    /// The latest values passed to `glViewportArrayv`, starting at index 0. Empty if
+    /// `glViewport` has been called since.
+    pub viewports: Vec<[gl::types::GLfloat; 4]>,
+
+    /// The latest values passed to `glScissorArrayv`, starting at index 0. Empty if
+    /// `glScissor` has been called since.
+    pub scissors: Vec<[gl::types::GLint; 4]>,
+
     /// The latest value passed to `glLineWidth`.
     pub line_width: gl::types::GLfloat,
 
@@ -220,8 +246,9 @@ pub struct GlState {
     /// The latest value passed to `glPolygonMode`.
     pub polygon_mode: gl::types::GLenum,
 
-    /// The latest values passed to `glPolygonOffset`.
-    pub polygon_offset: (gl::types::GLfloat, gl::types::GLfloat),
+    /// The latest factor, units and clamp passed to `glPolygonOffsetClamp`, or to
+    /// `glPolygonOffset` with a clamp of `0.0`.
+    pub polygon_offset: (gl::types::GLfloat, gl::types::GLfloat, gl::types::GLfloat),
 
     /// The latest value passed to `glHint` for smoothing.
     pub smooth: (gl::types::GLenum, gl::types::GLenum),
@@ -244,6 +271,12 @@ pub struct GlState {
     /// The latest value passed to `glPatchParameter` with `GL_PATCH_VERTICES`.
     pub patch_patch_vertices: gl::types::GLint,
 
+    /// The latest value passed to `glPatchParameterfv` with `GL_PATCH_DEFAULT_OUTER_LEVEL`.
+    pub patch_default_outer_level: [gl::types::GLfloat; 4],
+
+    /// The latest value passed to `glPatchParameterfv` with `GL_PATCH_DEFAULT_INNER_LEVEL`.
+    pub patch_default_inner_level: [gl::types::GLfloat; 2],
+
     /// The id of the active texture unit.
     /// IMPORTANT: this is a raw number (0, 1, 2, ...), not an
     ///            enumeration (GL_TEXTURE0, GL_TEXTURE1, ...).
@@ -261,6 +294,10 @@ pub struct GlState {
     /// Current query being used for GL_ANY_SAMPLES_PASSED​_CONSERVATIVE.
     pub any_samples_passed_conservative_query: gl::types::GLuint,
 
+    /// Query whose `scope` function is currently running, or 0 if none. Draw commands that don't
+    /// specify a samples query keep this query active instead of ending it.
+    pub samples_query_scope: gl::types::GLuint,
+
     /// Current query being used for GL_PRIMITIVES_GENERATED​.
     pub primitives_generated_query: gl::types::GLuint,
 
@@ -288,6 +325,14 @@ pub struct GlState {
     /// We maintain a counter that is incremented at each draw call.
     pub next_draw_call_id: u64,
 
+    /// Number of operations submitted since the creation of the context, reported by
+    /// `Context::get_statistics`.
+    pub statistics: Statistics,
+
+    /// Calls to the state-changing functions, recorded if `Context::set_state_audit` has
+    /// enabled the audit.
+    pub(crate) state_audit: Option<StateAudit>,
+
     /// The draw call ID of the latest call to `glMemoryBarrier` with
     /// `GL_VERTEX_ATTRIB_ARRAY_BARRIER_BIT`.
     pub latest_memory_barrier_vertex_attrib_array: u64,
@@ -343,6 +388,11 @@ pub struct GlState {
     /// The draw call ID of the latest call to `glMemoryBarrier` with
     /// `GL_QUERY_BUFFER_BARRIER_BIT`.
     pub latest_memory_barrier_query_buffer: u64,
+
+    /// List of textures that have been attached to a framebuffer during a draw call with
+    /// `check_hazards` or `auto_texture_barrier`, since the latest barrier covering texture
+    /// fetches or the latest swap. Textures are removed when they are deleted.
+    pub textures_written_since_barrier: SmallVec<[gl::types::GLuint; 4]>,
 }
 
 /// State of a texture unit (the one designated by `glActiveTexture`).
@@ -369,6 +419,19 @@ pub struct IndexedBufferState {
 }
 
 /// Builds the `GlState` corresponding to a newly-created OpenGL context.
+impl GlState {
+    /// Records a call to a state-changing function for the state audit. Does nothing if the
+    /// audit is disabled.
+    ///
+    /// Extension variants must be recorded under the name of the core function.
+    #[inline]
+    pub(crate) fn audit(&mut self, function: &'static str, slot: u64, value: u64) {
+        if let Some(audit) = self.state_audit.as_mut() {
+            audit.record(function, slot, value);
+        }
+    }
+}
+
 impl Default for GlState {
     fn default() -> GlState {
         fn small_vec_one<T>() -> SmallVec<T> where T: ::smallvec::Array, T::Item: Default {
@@ -381,6 +444,7 @@ impl Default for GlState {
             lost_context: false,
 
             enabled_blend: false,
+            enabled_color_logic_op: false,
             enabled_cull_face: false,
             enabled_debug_output: None,
             enabled_debug_output_synchronous: false,
@@ -395,6 +459,7 @@ impl Default for GlState {
             enabled_polygon_offset_point: false,
             enabled_rasterizer_discard: false,
             enabled_sample_alpha_to_coverage: false,
+            enabled_sample_alpha_to_one: false,
             enabled_sample_coverage: false,
             enabled_scissor_test: false,
             enabled_stencil_test: false,
@@ -442,8 +507,13 @@ impl Default for GlState {
             blend_equation: (gl::FUNC_ADD, gl::FUNC_ADD),
             blend_func: (gl::ONE, gl::ZERO, gl::ONE, gl::ZERO),
             blend_color: (0.0, 0.0, 0.0, 0.0),
+            attachment_blend: false,
+            attachment_color_mask: false,
+            logic_op: gl::COPY,
             viewport: None,
             scissor: None,
+            viewports: Vec::new(),
+            scissors: Vec::new(),
             line_width: 1.0,
             point_size: 1.0,
             cull_face: gl::BACK,
@@ -454,11 +524,14 @@ impl Default for GlState {
             pixel_store_pack_alignment: 4,
             clamp_color: gl::FIXED_ONLY,
             patch_patch_vertices: 3,
+            patch_default_outer_level: [1.0; 4],
+            patch_default_inner_level: [1.0; 2],
             active_texture: 0,
             texture_units: small_vec_one(),
             samples_passed_query: 0,
             any_samples_passed_query: 0,
             any_samples_passed_conservative_query: 0,
+            samples_query_scope: 0,
             primitives_generated_query: 0,
             transform_feedback_primitives_written_query: 0,
             time_elapsed_query: 0,
@@ -466,10 +539,12 @@ impl Default for GlState {
             transform_feedback_enabled: None,
             transform_feedback_paused: false,
             primitive_bounding_box: (-1.0, -1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0),
-            polygon_offset: (0.0, 0.0),
+            polygon_offset: (0.0, 0.0, 0.0),
             clip_control: (gl::LOWER_LEFT, gl::NEGATIVE_ONE_TO_ONE),
 
             next_draw_call_id: 1,
+            statistics: Default::default(),
+            state_audit: None,
             latest_memory_barrier_vertex_attrib_array: 1,
             latest_memory_barrier_element_array: 1,
             latest_memory_barrier_uniform: 1,
@@ -484,6 +559,7 @@ impl Default for GlState {
             latest_memory_barrier_atomic_counter: 1,
             latest_memory_barrier_shader_storage: 1,
             latest_memory_barrier_query_buffer: 1,
+            textures_written_since_barrier: SmallVec::new(),
         }
     }
 }
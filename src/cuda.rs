@@ -0,0 +1,113 @@
+/*!
+Sharing buffers and textures with CUDA kernels.
+
+CUDA registers OpenGL objects by name with `cudaGraphicsGLRegisterBuffer` and
+`cudaGraphicsGLRegisterImage`, then maps them with `cudaGraphicsMapResources` each time a kernel
+needs them. Mapping synchronizes with the OpenGL commands issued before, and unmapping with the
+ones issued after, but only if glium doesn't touch the object in the meantime: OpenGL commands
+that use an object mapped by CUDA have undefined results.
+
+An `Access` borrows a buffer or a texture mutably for as long as CUDA may use it, which prevents
+glium from drawing with it, and makes the OpenGL context current so that the CUDA calls apply to
+it. Register, map, unmap and unregister the object while an `Access` is alive.
+
+```no_run
+# use std::os::raw::{c_int, c_uint, c_void};
+# extern "C" {
+#     fn cudaGraphicsGLRegisterBuffer(resource: *mut *mut c_void, buffer: c_uint, flags: c_uint)
+#                                     -> c_int;
+#     fn cudaGraphicsMapResources(count: c_int, resources: *mut *mut c_void, stream: *mut c_void)
+#                                 -> c_int;
+#     fn cudaGraphicsUnmapResources(count: c_int, resources: *mut *mut c_void,
+#                                   stream: *mut c_void) -> c_int;
+# }
+# fn example(mut vertex_buffer: glium::VertexBuffer<[f32; 4]>) {
+use glium::cuda::Access;
+
+let mut resource = std::ptr::null_mut();
+{
+    let access = Access::buffer(&mut vertex_buffer);
+    unsafe { cudaGraphicsGLRegisterBuffer(&mut resource, access.id(), 0) };
+}
+
+// every frame
+{
+    let _access = Access::buffer(&mut vertex_buffer);
+    unsafe { cudaGraphicsMapResources(1, &mut resource, std::ptr::null_mut()) };
+    // ... launch the kernel that writes the vertices ...
+    unsafe { cudaGraphicsUnmapResources(1, &mut resource, std::ptr::null_mut()) };
+}
+// draw with `vertex_buffer` as usual
+# }
+```
+
+The object must be unregistered with `cudaGraphicsUnregisterResource` before it is dropped.
+
+*/
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::buffer::{Buffer, Content};
+use crate::gl;
+use crate::texture::TextureAny;
+use crate::ContextExt;
+use crate::GlObject;
+use crate::TextureExt;
+
+/// Exclusive access to a buffer or a texture for CUDA.
+///
+/// Must be alive between `cudaGraphicsMapResources` and `cudaGraphicsUnmapResources`.
+pub struct Access<'a> {
+    id: gl::types::GLuint,
+    target: gl::types::GLenum,
+    marker: PhantomData<&'a mut ()>,
+}
+
+impl<'a> Access<'a> {
+    /// Borrows a buffer, for example the `Buffer` inside a `VertexBuffer`.
+    ///
+    /// Unmaps the buffer if it is mapped and makes sure that it isn't being used for transform
+    /// feedback.
+    pub fn buffer<T: Content + ?Sized>(buffer: &'a mut Buffer<T>) -> Access<'a> {
+        let context = buffer.get_context().clone();
+        let mut ctxt = context.make_current();
+        buffer.prepare_for_external_access(&mut ctxt);
+        // `cudaGraphicsMapResources` only waits for the commands that have been submitted
+        unsafe { ctxt.gl.Flush(); }
+        drop(ctxt);
+
+        Access {
+            id: buffer.get_id(),
+            target: gl::ARRAY_BUFFER,
+            marker: PhantomData,
+        }
+    }
+
+    /// Borrows a texture, for example a `Texture2d`.
+    pub fn texture<T>(texture: &'a mut T) -> Access<'a> where T: Deref<Target = TextureAny> {
+        let texture: &TextureAny = texture;
+        let ctxt = texture.get_context().make_current();
+        unsafe { ctxt.gl.Flush(); }
+        drop(ctxt);
+
+        Access {
+            id: texture.get_id(),
+            target: texture.get_bind_point(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the name of the OpenGL object, to pass to `cudaGraphicsGLRegisterBuffer` or
+    /// `cudaGraphicsGLRegisterImage`.
+    #[inline]
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    /// Returns the target of a texture, to pass to `cudaGraphicsGLRegisterImage`.
+    /// `GL_ARRAY_BUFFER` for buffers.
+    #[inline]
+    pub fn target(&self) -> gl::types::GLenum {
+        self.target
+    }
+}
@@ -9,7 +9,15 @@ use crate::ContextExt;
 use crate::version::Api;
 use crate::version::Version;
 use crate::gl;
+use crate::CapabilitiesSource;
+use fnv::FnvHasher;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::mem;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 pub use crate::context::DebugCallbackBehavior;
 
@@ -22,7 +30,7 @@ pub use crate::context::DebugCallbackBehavior;
 pub type DebugCallback = Box<dyn FnMut(Source, MessageType, Severity, u32, bool, &str)>;
 
 /// Severity of a debug message.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(u32)]
 pub enum Severity {
     /// Anything that isn't an error or performance issue.
@@ -40,8 +48,28 @@ pub enum Severity {
     High = gl::DEBUG_SEVERITY_HIGH,
 }
 
+impl Severity {
+    /// Returns true if `self` is at least as severe as `other`.
+    ///
+    /// The OpenGL constants aren't sorted by severity, which is why `Severity` doesn't
+    /// implement `Ord`.
+    #[inline]
+    pub fn is_at_least(self, other: Severity) -> bool {
+        fn rank(severity: Severity) -> u8 {
+            match severity {
+                Severity::Notification => 0,
+                Severity::Low => 1,
+                Severity::Medium => 2,
+                Severity::High => 3,
+            }
+        }
+
+        rank(self) >= rank(other)
+    }
+}
+
 /// Source of a debug message.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(u32)]
 pub enum Source {
     /// Calls to the OpenGL API.
@@ -66,7 +94,7 @@ pub enum Source {
 }
 
 /// Type of a debug message.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(u32)]
 pub enum MessageType {
     /// An error, typically from the API
@@ -89,6 +117,129 @@ pub enum MessageType {
     Other = gl::DEBUG_TYPE_OTHER,
 }
 
+/// Selects which messages of the debug output reach a callback.
+///
+/// The filter is applied by wrapping a callback with `wrap`, or by `log_callback` when the
+/// `log` feature is enabled. The wrapped callback can then be passed to
+/// `DebugCallbackBehavior::Custom`.
+///
+/// ```no_run
+/// use glium::debug::{DebugCallbackBehavior, DebugOutputFilter, MessageType, Severity};
+///
+/// let filter = DebugOutputFilter {
+///     min_severity: Severity::Low,
+///     ignored_types: vec![MessageType::Performance],
+///     // "buffer detailed info" spam from some drivers
+///     ignored_ids: vec![131185],
+///     panic_on_high_severity: true,
+///     .. Default::default()
+/// };
+///
+/// let behavior = DebugCallbackBehavior::Custom {
+///     callback: filter.wrap(Box::new(|source, ty, severity, id, _, message| {
+///         eprintln!("{:?} {:?} {:?} {}: {}", source, ty, severity, id, message);
+///     })),
+///     synchronous: true,
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DebugOutputFilter {
+    /// Messages less severe than this are dropped.
+    ///
+    /// The default value is `Severity::Notification`, which keeps every message.
+    pub min_severity: Severity,
+
+    /// Messages coming from one of these sources are dropped.
+    ///
+    /// The default value is an empty list.
+    pub ignored_sources: Vec<Source>,
+
+    /// Messages of one of these types are dropped.
+    ///
+    /// The default value is an empty list.
+    pub ignored_types: Vec<MessageType>,
+
+    /// Messages with one of these ids are dropped. The ids are implementation-defined, which
+    /// makes this the only way to silence one specific message that a driver keeps sending.
+    ///
+    /// The default value is an empty list.
+    pub ignored_ids: Vec<u32>,
+
+    /// If true and glium is compiled with `debug_assertions`, messages of `Severity::High`
+    /// that pass the filter cause a panic after the callback has been called.
+    ///
+    /// The panic can't unwind through the OpenGL driver. It is instead raised when the glium
+    /// function that issued the faulty command returns, which requires the debug output to be
+    /// synchronous. Messages that glium triggers on purpose, for example while compiling
+    /// shaders, never panic.
+    ///
+    /// The default value is `false`.
+    pub panic_on_high_severity: bool,
+}
+
+impl Default for DebugOutputFilter {
+    #[inline]
+    fn default() -> DebugOutputFilter {
+        DebugOutputFilter {
+            min_severity: Severity::Notification,
+            ignored_sources: Vec::new(),
+            ignored_types: Vec::new(),
+            ignored_ids: Vec::new(),
+            panic_on_high_severity: false,
+        }
+    }
+}
+
+impl DebugOutputFilter {
+    /// Returns true if a message with these properties passes the filter.
+    pub fn accepts(&self, source: Source, ty: MessageType, severity: Severity, id: u32) -> bool {
+        severity.is_at_least(self.min_severity) &&
+        !self.ignored_sources.contains(&source) &&
+        !self.ignored_types.contains(&ty) &&
+        !self.ignored_ids.contains(&id)
+    }
+
+    /// Builds a callback that only calls `callback` for the messages that pass the filter.
+    pub fn wrap(self, mut callback: DebugCallback) -> DebugCallback {
+        Box::new(move |source, ty, severity, id, report_debug_output_errors, message| {
+            if !self.accepts(source, ty, severity, id) {
+                return;
+            }
+
+            callback(source, ty, severity, id, report_debug_output_errors, message);
+
+            if cfg!(debug_assertions) && self.panic_on_high_severity &&
+               report_debug_output_errors && severity == Severity::High
+            {
+                panic!("OpenGL debug output with high severity: {:?} {:?} ({}): {}",
+                       source, ty, id, message);
+            }
+        })
+    }
+}
+
+/// Builds a callback that forwards the messages that pass `filter` to the `log` crate.
+///
+/// Messages are logged with the `glium::debug` target. `Severity::High` maps to
+/// `Level::Error`, `Medium` to `Warn`, `Low` to `Info` and `Notification` to `Debug`.
+/// Applications using `tracing` receive these records through `tracing-log`.
+///
+/// Pass the result to `DebugCallbackBehavior::Custom`, preferably with `synchronous` set to
+/// `true` so that the messages are logged right after the command that triggered them.
+#[cfg(feature = "log")]
+pub fn log_callback(filter: DebugOutputFilter) -> DebugCallback {
+    filter.wrap(Box::new(|source, ty, severity, id, _, message| {
+        let level = match severity {
+            Severity::High => log::Level::Error,
+            Severity::Medium => log::Level::Warn,
+            Severity::Low => log::Level::Info,
+            Severity::Notification => log::Level::Debug,
+        };
+
+        log::log!(target: "glium::debug", level, "{:?} {:?} ({}): {}", source, ty, id, message);
+    }))
+}
+
 /// Allows you to obtain the timestamp inside the OpenGL commands queue.
 ///
 /// When you call functions in glium, they are not instantly executed. Instead they are
@@ -193,7 +344,6 @@ impl TimestampQuery {
             unsafe {
                 let mut value = 0;
                 ctxt.gl.GetQueryObjectui64v(self.id, gl::QUERY_RESULT, &mut value);
-                ctxt.gl.DeleteQueries(1, [self.id].as_ptr());
                 value
             }
 
@@ -201,7 +351,6 @@ impl TimestampQuery {
             unsafe {
                 let mut value = 0;
                 ctxt.gl.GetQueryObjectui64vEXT(self.id, gl::QUERY_RESULT_EXT, &mut value);
-                ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr());
                 value
             }
 
@@ -210,3 +359,637 @@ impl TimestampQuery {
         }
     }
 }
+
+impl Drop for TimestampQuery {
+    fn drop(&mut self) {
+        let ctxt = self.context.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
+            unsafe { ctxt.gl.DeleteQueries(1, [self.id].as_ptr()); }
+        } else if ctxt.extensions.gl_ext_disjoint_timer_query {
+            unsafe { ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr()); }
+        } else {
+            unreachable!();
+        }
+    }
+}
+
+/// Measures how much time the GPU spends in named sections of each frame.
+///
+/// Each call to `scope` surrounds a section of commands with two `TimestampQuery`s. Once per
+/// frame, `end_frame` collects the results of the previous frames whose timestamps are
+/// available. The results lag one or two frames behind, but retrieving them never blocks.
+///
+/// If the GPU is more than `MAX_PENDING_FRAMES` frames behind, the oldest measurements are
+/// dropped instead of waiting for them.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+/// let mut timer = glium::debug::GpuTimer::new(&display).unwrap();
+///
+/// loop {
+///     {
+///         let _scope = timer.scope("shadow pass");
+///         // draw the shadow maps here
+///     }
+///     {
+///         let _scope = timer.scope("main pass");
+///         // draw the scene here
+///     }
+///
+///     timer.end_frame();
+///     for timing in timer.get_results() {
+///         println!("{}: {:?}", timing.name, timing.elapsed());
+///     }
+/// }
+/// # }
+/// ```
+pub struct GpuTimer {
+    context: Rc<Context>,
+
+    // scopes of the frame being recorded, in the order they were opened
+    current: RefCell<Vec<PendingScope>>,
+
+    // frames whose results are not available yet, the oldest first
+    pending: VecDeque<Vec<PendingScope>>,
+
+    // results of the most recent frame whose results are available
+    results: Vec<GpuTiming>,
+}
+
+struct PendingScope {
+    name: String,
+    begin: TimestampQuery,
+    end: Option<TimestampQuery>,
+}
+
+impl PendingScope {
+    #[inline]
+    fn is_ready(&self) -> bool {
+        self.begin.is_ready() && self.end.as_ref().map(|e| e.is_ready()).unwrap_or(true)
+    }
+}
+
+/// Time spent by the GPU in a scope of a `GpuTimer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuTiming {
+    /// Name passed to `GpuTimer::scope`.
+    pub name: String,
+
+    /// GPU timestamp, in nanoseconds, at which the GPU entered the scope.
+    pub begin: u64,
+
+    /// GPU timestamp, in nanoseconds, at which the GPU left the scope.
+    pub end: u64,
+}
+
+impl GpuTiming {
+    /// Returns the time spent by the GPU in the scope.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.end.saturating_sub(self.begin))
+    }
+}
+
+/// Guard returned by `GpuTimer::scope`. The scope ends when this object is destroyed.
+pub struct GpuTimerScope<'a> {
+    timer: &'a GpuTimer,
+    index: usize,
+}
+
+impl<'a> Drop for GpuTimerScope<'a> {
+    fn drop(&mut self) {
+        let end = TimestampQuery::new(&self.timer.context);
+        self.timer.current.borrow_mut()[self.index].end = end;
+    }
+}
+
+impl GpuTimer {
+    /// Maximum number of frames whose results can be waited for at the same time.
+    pub const MAX_PENDING_FRAMES: usize = 3;
+
+    /// Creates a new `GpuTimer`. Returns `None` if the backend doesn't support timestamp
+    /// queries.
+    pub fn new<F>(facade: &F) -> Option<GpuTimer> where F: ?Sized + Facade {
+        let context = facade.get_context();
+
+        if !(context.get_version() >= &Version(Api::Gl, 3, 2) ||
+             context.get_extensions().gl_ext_disjoint_timer_query)
+        {
+            return None;
+        }
+
+        Some(GpuTimer {
+            context: context.clone(),
+            current: RefCell::new(Vec::new()),
+            pending: VecDeque::with_capacity(GpuTimer::MAX_PENDING_FRAMES + 1),
+            results: Vec::new(),
+        })
+    }
+
+    /// Starts measuring the commands submitted until the returned object is destroyed.
+    ///
+    /// Scopes can be nested. Each scope is measured independently, so the time of a scope
+    /// includes the time of the scopes nested inside of it.
+    pub fn scope<S>(&self, name: S) -> GpuTimerScope<'_> where S: Into<String> {
+        // `new` has already checked that timestamp queries are supported
+        let begin = TimestampQuery::new(&self.context).unwrap();
+
+        let mut current = self.current.borrow_mut();
+        current.push(PendingScope {
+            name: name.into(),
+            begin,
+            end: None,
+        });
+
+        GpuTimerScope {
+            timer: self,
+            index: current.len() - 1,
+        }
+    }
+
+    /// Marks the end of the current frame, and updates the results with the most recent frame
+    /// whose timestamps are available.
+    ///
+    /// Returns `true` if the results have been updated.
+    pub fn end_frame(&mut self) -> bool {
+        let frame = mem::take(self.current.get_mut());
+        self.pending.push_back(frame);
+
+        while self.pending.len() > GpuTimer::MAX_PENDING_FRAMES {
+            self.pending.pop_front();
+        }
+
+        let mut updated = false;
+
+        while self.pending.front().map(|f| f.iter().all(|s| s.is_ready())).unwrap_or(false) {
+            let frame = self.pending.pop_front().unwrap();
+
+            self.results = frame.into_iter().filter_map(|scope| {
+                let PendingScope { name, begin, end } = scope;
+                end.map(|end| GpuTiming { name, begin: begin.get(), end: end.get() })
+            }).collect();
+
+            updated = true;
+        }
+
+        updated
+    }
+
+    /// Returns the timings of the most recent frame whose results are available, in the order
+    /// in which the scopes were opened.
+    ///
+    /// Returns an empty list until the results of the first frame are available.
+    #[inline]
+    pub fn get_results(&self) -> &[GpuTiming] {
+        &self.results
+    }
+}
+
+/// Correspondence between the GPU clock and the CPU clock, obtained with
+/// `Context::calibrate_gpu_clock`.
+///
+/// GPU timestamps, such as the ones returned by `TimestampQuery`, are expressed in nanoseconds
+/// relative to an arbitrary origin. This object allows converting them to `Instant`s, so that
+/// GPU and CPU events can be put on the same timeline.
+///
+/// The two clocks may drift apart over time, so it is a good idea to calibrate again regularly,
+/// for example once per second.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+/// let calibration = display.calibrate_gpu_clock().unwrap();
+///
+/// let query = glium::debug::TimestampQuery::new(&display).unwrap();
+/// // do some stuff here
+/// let reached_at = calibration.to_instant(query.get());
+/// println!("The GPU reached the query {:?} after the calibration",
+///          reached_at.saturating_duration_since(calibration.get_cpu_time()));
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GpuClockCalibration {
+    cpu_time: Instant,
+    gpu_timestamp: u64,
+    uncertainty: Duration,
+}
+
+impl GpuClockCalibration {
+    #[inline]
+    pub(crate) fn new(cpu_time: Instant, gpu_timestamp: u64, uncertainty: Duration)
+                      -> GpuClockCalibration
+    {
+        GpuClockCalibration {
+            cpu_time,
+            gpu_timestamp,
+            uncertainty,
+        }
+    }
+
+    /// Returns the CPU time at which the GPU timestamp was sampled.
+    #[inline]
+    pub fn get_cpu_time(&self) -> Instant {
+        self.cpu_time
+    }
+
+    /// Returns the GPU timestamp, in nanoseconds, that corresponds to `get_cpu_time`.
+    #[inline]
+    pub fn get_gpu_timestamp(&self) -> u64 {
+        self.gpu_timestamp
+    }
+
+    /// Returns the maximum error of the correspondence between the two clocks.
+    ///
+    /// This is half of the CPU time it took to retrieve the GPU timestamp.
+    #[inline]
+    pub fn get_uncertainty(&self) -> Duration {
+        self.uncertainty
+    }
+
+    /// Converts a GPU timestamp to the corresponding CPU time.
+    ///
+    /// If the timestamp corresponds to a moment that can't be represented by an `Instant`,
+    /// the time of the calibration is returned instead.
+    pub fn to_instant(&self, gpu_timestamp: u64) -> Instant {
+        if gpu_timestamp >= self.gpu_timestamp {
+            let elapsed = Duration::from_nanos(gpu_timestamp - self.gpu_timestamp);
+            self.cpu_time.checked_add(elapsed).unwrap_or(self.cpu_time)
+        } else {
+            let elapsed = Duration::from_nanos(self.gpu_timestamp - gpu_timestamp);
+            self.cpu_time.checked_sub(elapsed).unwrap_or(self.cpu_time)
+        }
+    }
+
+    /// Converts a CPU time to the corresponding GPU timestamp, in nanoseconds.
+    pub fn to_gpu_timestamp(&self, instant: Instant) -> u64 {
+        if instant >= self.cpu_time {
+            let elapsed = (instant - self.cpu_time).as_nanos() as u64;
+            self.gpu_timestamp.saturating_add(elapsed)
+        } else {
+            let elapsed = (self.cpu_time - instant).as_nanos() as u64;
+            self.gpu_timestamp.saturating_sub(elapsed)
+        }
+    }
+}
+
+/// Number of operations submitted to a context, returned by `Context::get_statistics`.
+///
+/// The program and framebuffer changes only count the operations that actually reached
+/// OpenGL, and not the ones that glium skipped because the state was already correct.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Statistics {
+    /// Number of draw commands. A multidraw command counts as one.
+    pub draw_calls: u64,
+
+    /// Number of compute shader dispatches.
+    pub compute_dispatches: u64,
+
+    /// Number of times the current program has been changed.
+    pub program_changes: u64,
+
+    /// Number of times the framebuffer used for drawing has been changed.
+    pub framebuffer_changes: u64,
+}
+
+impl Statistics {
+    /// Returns the number of operations submitted since `earlier` was obtained.
+    #[inline]
+    pub fn since(&self, earlier: &Statistics) -> Statistics {
+        Statistics {
+            draw_calls: self.draw_calls.saturating_sub(earlier.draw_calls),
+            compute_dispatches: self.compute_dispatches.saturating_sub(earlier.compute_dispatches),
+            program_changes: self.program_changes.saturating_sub(earlier.program_changes),
+            framebuffer_changes: self.framebuffer_changes
+                                     .saturating_sub(earlier.framebuffer_changes),
+        }
+    }
+}
+
+/// Collects the CPU time, GPU time and statistics of named sections of each frame.
+///
+/// The results of each frame are gathered in a `ProfilerFrame`, which is meant to be displayed
+/// by a UI overlay. The GPU timings are obtained with a `GpuTimer` and lag a few frames behind
+/// the CPU timings, and are always empty if the backend doesn't support timestamp queries.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+/// let mut profiler = glium::debug::Profiler::new(&display);
+///
+/// loop {
+///     {
+///         let _scope = profiler.scope("shadow pass");
+///         // draw the shadow maps here
+///     }
+///
+///     let frame = profiler.end_frame();
+///     println!("{} draw calls in {:?}", frame.statistics.draw_calls, frame.cpu_duration);
+/// }
+/// # }
+/// ```
+pub struct Profiler {
+    context: Rc<Context>,
+    gpu_timer: Option<GpuTimer>,
+
+    // start of the frame being recorded
+    frame_start: Instant,
+    statistics_at_frame_start: Statistics,
+
+    // spans of the frame being recorded, in the order they were opened
+    cpu_spans: RefCell<Vec<CpuSpan>>,
+
+    last_frame: ProfilerFrame,
+}
+
+/// Time spent by the CPU in a scope of a `Profiler`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuSpan {
+    /// Name passed to `Profiler::scope`.
+    pub name: String,
+
+    /// Time between the start of the frame and the start of the scope.
+    pub start: Duration,
+
+    /// Time spent in the scope.
+    pub duration: Duration,
+}
+
+/// Results of a frame recorded by a `Profiler`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfilerFrame {
+    /// Time between the start and the end of the frame on the CPU.
+    pub cpu_duration: Duration,
+
+    /// Operations submitted during the frame.
+    pub statistics: Statistics,
+
+    /// CPU time of the scopes of the frame, in the order they were opened.
+    pub cpu_spans: Vec<CpuSpan>,
+
+    /// GPU time of the scopes of the most recent frame whose results are available.
+    pub gpu_timings: Vec<GpuTiming>,
+}
+
+/// Guard returned by `Profiler::scope`. The scope ends when this object is destroyed.
+pub struct ProfilerScope<'a> {
+    profiler: &'a Profiler,
+    index: usize,
+    start: Instant,
+    // dropped after the CPU time has been measured
+    _gpu_scope: Option<GpuTimerScope<'a>>,
+}
+
+impl<'a> Drop for ProfilerScope<'a> {
+    fn drop(&mut self) {
+        self.profiler.cpu_spans.borrow_mut()[self.index].duration = self.start.elapsed();
+    }
+}
+
+impl Profiler {
+    /// Creates a new `Profiler`.
+    pub fn new<F>(facade: &F) -> Profiler where F: ?Sized + Facade {
+        let context = facade.get_context().clone();
+        let statistics_at_frame_start = context.get_statistics();
+
+        Profiler {
+            gpu_timer: GpuTimer::new(&context),
+            context,
+            frame_start: Instant::now(),
+            statistics_at_frame_start,
+            cpu_spans: RefCell::new(Vec::new()),
+            last_frame: Default::default(),
+        }
+    }
+
+    /// Starts measuring a section of the frame, until the returned object is destroyed.
+    ///
+    /// Scopes can be nested.
+    pub fn scope<S>(&self, name: S) -> ProfilerScope<'_> where S: Into<String> {
+        let name = name.into();
+        let start = Instant::now();
+
+        let index = {
+            let mut cpu_spans = self.cpu_spans.borrow_mut();
+            cpu_spans.push(CpuSpan {
+                name: name.clone(),
+                start: start - self.frame_start,
+                duration: Duration::new(0, 0),
+            });
+            cpu_spans.len() - 1
+        };
+
+        ProfilerScope {
+            profiler: self,
+            index,
+            start,
+            _gpu_scope: self.gpu_timer.as_ref().map(|timer| timer.scope(name)),
+        }
+    }
+
+    /// Marks the end of the current frame and the start of the next one, and returns the
+    /// results of the frame.
+    pub fn end_frame(&mut self) -> &ProfilerFrame {
+        let now = Instant::now();
+        let statistics = self.context.get_statistics();
+
+        let gpu_timings = match self.gpu_timer {
+            Some(ref mut timer) => {
+                timer.end_frame();
+                timer.get_results().to_vec()
+            },
+            None => Vec::new(),
+        };
+
+        self.last_frame = ProfilerFrame {
+            cpu_duration: now - self.frame_start,
+            statistics: statistics.since(&self.statistics_at_frame_start),
+            cpu_spans: mem::take(self.cpu_spans.get_mut()),
+            gpu_timings,
+        };
+
+        self.frame_start = now;
+        self.statistics_at_frame_start = statistics;
+        &self.last_frame
+    }
+
+    /// Returns the results of the latest frame passed to `end_frame`.
+    #[inline]
+    pub fn get_last_frame(&self) -> &ProfilerFrame {
+        &self.last_frame
+    }
+}
+
+/// Records the calls to the state-changing OpenGL functions while the state audit of a
+/// `Context` is enabled.
+///
+/// A call is redundant if the same value was already bound to the same slot by the previous
+/// call of this function. glium's state cache is supposed to skip these calls, so each
+/// redundant call points either to a gap in the cache or to a place where glium forgets what
+/// is bound.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StateAudit {
+    // number of calls and of redundant calls of each function since the last report
+    counters: HashMap<&'static str, (u64, u64), BuildHasherDefault<FnvHasher>>,
+    // last value passed to each function for each slot
+    bindings: HashMap<(&'static str, u64), u64, BuildHasherDefault<FnvHasher>>,
+}
+
+impl StateAudit {
+    /// Records a call to `function`, which binds `value` to `slot`.
+    ///
+    /// What a slot is depends on the function, for example the target of `glBindBuffer` or the
+    /// location of `glUniform`.
+    pub fn record(&mut self, function: &'static str, slot: u64, value: u64) {
+        let redundant = self.bindings.insert((function, slot), value) == Some(value);
+
+        let counter = self.counters.entry(function).or_insert((0, 0));
+        counter.0 += 1;
+        if redundant {
+            counter.1 += 1;
+        }
+    }
+
+    /// Turns a value that doesn't fit in a `u64`, like a buffer range, into something that can
+    /// be passed to `record`.
+    pub fn hash<T>(value: T) -> u64 where T: Hash {
+        let mut hasher = FnvHasher::default();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Forgets the values that are bound. Must be called when the state of the OpenGL context
+    /// is no longer known.
+    pub fn forget_bindings(&mut self) {
+        self.bindings.clear();
+    }
+
+    /// Builds a report of the calls recorded since the last report.
+    pub fn take_report(&mut self) -> StateAuditReport {
+        let mut entries: Vec<_> = self.counters.drain().map(|(function, (calls, redundant))| {
+            StateAuditEntry { function, calls, redundant }
+        }).collect();
+
+        entries.sort_by(|a, b| {
+            b.redundant.cmp(&a.redundant).then(b.calls.cmp(&a.calls))
+                       .then(a.function.cmp(b.function))
+        });
+
+        StateAuditReport { entries }
+    }
+}
+
+/// Calls to the state-changing OpenGL functions, returned by `Context::take_state_audit_report`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateAuditReport {
+    /// One entry per function that has been called, the functions with the most redundant calls
+    /// first.
+    pub entries: Vec<StateAuditEntry>,
+}
+
+/// Number of calls to an OpenGL function in a `StateAuditReport`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateAuditEntry {
+    /// Name of the function, for example `glBindBuffer`. Extension variants, like
+    /// `glBindBufferARB`, are counted with the core function.
+    pub function: &'static str,
+
+    /// Number of calls.
+    pub calls: u64,
+
+    /// Number of calls that didn't change anything.
+    pub redundant: u64,
+}
+
+impl StateAuditReport {
+    /// Returns the total number of calls.
+    #[inline]
+    pub fn total_calls(&self) -> u64 {
+        self.entries.iter().map(|e| e.calls).sum()
+    }
+
+    /// Returns the total number of redundant calls.
+    #[inline]
+    pub fn total_redundant(&self) -> u64 {
+        self.entries.iter().map(|e| e.redundant).sum()
+    }
+
+    /// Returns up to `count` functions with redundant calls, the worst offender first.
+    pub fn top_offenders(&self, count: usize) -> &[StateAuditEntry] {
+        let with_redundant = self.entries.iter().take_while(|e| e.redundant != 0).count();
+        &self.entries[.. with_redundant.min(count)]
+    }
+}
+
+impl fmt::Display for StateAuditReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "{:<32} {:>10} {:>10}", "function", "calls", "redundant")?;
+        for entry in &self.entries {
+            writeln!(fmt, "{:<32} {:>10} {:>10}", entry.function, entry.calls, entry.redundant)?;
+        }
+        write!(fmt, "{:<32} {:>10} {:>10}", "total", self.total_calls(), self.total_redundant())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DebugOutputFilter, MessageType, Severity, Source, StateAudit};
+
+    #[test]
+    fn severity_order() {
+        assert!(Severity::High.is_at_least(Severity::Medium));
+        assert!(Severity::Medium.is_at_least(Severity::Low));
+        assert!(Severity::Low.is_at_least(Severity::Notification));
+        assert!(Severity::Low.is_at_least(Severity::Low));
+        assert!(!Severity::Notification.is_at_least(Severity::High));
+    }
+
+    #[test]
+    fn filter() {
+        let filter = DebugOutputFilter {
+            min_severity: Severity::Low,
+            ignored_sources: vec![Source::ThirdParty],
+            ignored_types: vec![MessageType::Performance],
+            ignored_ids: vec![131185],
+            .. Default::default()
+        };
+
+        assert!(filter.accepts(Source::Api, MessageType::Error, Severity::High, 1));
+        assert!(!filter.accepts(Source::Api, MessageType::Other, Severity::Notification, 1));
+        assert!(!filter.accepts(Source::ThirdParty, MessageType::Error, Severity::High, 1));
+        assert!(!filter.accepts(Source::Api, MessageType::Performance, Severity::High, 1));
+        assert!(!filter.accepts(Source::Api, MessageType::Other, Severity::Low, 131185));
+    }
+
+    #[test]
+    fn state_audit() {
+        let mut audit = StateAudit::default();
+        audit.record("glBindBuffer", 1, 5);
+        audit.record("glBindBuffer", 1, 5);
+        audit.record("glBindBuffer", 2, 5);
+        audit.record("glUseProgram", 0, 3);
+        audit.record("glUseProgram", 0, 4);
+
+        let report = audit.take_report();
+        assert_eq!(report.total_calls(), 5);
+        assert_eq!(report.total_redundant(), 1);
+        assert_eq!(report.top_offenders(10).len(), 1);
+        assert_eq!(report.top_offenders(10)[0].function, "glBindBuffer");
+        assert_eq!(report.entries[1].function, "glUseProgram");
+
+        // the bindings are remembered across reports
+        audit.record("glUseProgram", 0, 4);
+        assert_eq!(audit.take_report().total_redundant(), 1);
+
+        audit.forget_bindings();
+        audit.record("glUseProgram", 0, 4);
+        assert_eq!(audit.take_report().total_redundant(), 0);
+    }
+}
@@ -2,9 +2,14 @@ use crate::context::CommandContext;
 use crate::version::Api;
 use crate::version::Version;
 
+use crate::CapabilitiesSource;
 use crate::DrawError;
 use crate::gl;
 
+use std::hash::{Hash, Hasher};
+
+use super::hash_f32;
+
 /// Blend effect that the GPU will use for blending.
 ///
 /// Blending happens at the end of the rendering process, when the GPU wants to write the
@@ -38,6 +43,17 @@ impl Blend {
             constant_value: (0.0, 0.0, 0.0, 0.0)
         }
     }
+
+    /// Returns a blend effect that uses an advanced blend equation.
+    ///
+    /// See `BlendingFunction::Advanced` for the requirements of these equations.
+    pub fn advanced(equation: AdvancedBlendEquation) -> Blend {
+        Blend {
+            color: BlendingFunction::Advanced(equation),
+            alpha: BlendingFunction::Advanced(equation),
+            constant_value: (0.0, 0.0, 0.0, 0.0)
+        }
+    }
 }
 
 impl Default for Blend {
@@ -50,8 +66,22 @@ impl Default for Blend {
     }
 }
 
+impl Eq for Blend {}
+
+impl Hash for Blend {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.color.hash(state);
+        self.alpha.hash(state);
+
+        let (r, g, b, a) = self.constant_value;
+        for value in [r, g, b, a] {
+            hash_f32(value, state);
+        }
+    }
+}
+
 /// Function that the GPU will use for blending.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BlendingFunction {
     /// Simply overwrite the destination pixel with the source pixel.
     ///
@@ -116,10 +146,132 @@ pub enum BlendingFunction {
         /// The factor to apply to the destination pixel.
         destination: LinearBlendingFactor,
     },
+
+    /// One of the advanced blend equations, similar to the blend modes of image editors.
+    ///
+    /// The source and the destination are treated as premultiplied colors, and the same
+    /// equation is used for all four components. Therefore the `color` and the `alpha` of the
+    /// `Blend` must both be this same value, otherwise drawing returns
+    /// `DrawError::AdvancedBlendingMismatch`.
+    ///
+    /// This requires OpenGL ES 3.2 or the `GL_KHR_blend_equation_advanced` extension, and the
+    /// fragment shader must declare the equations it is used with, for example
+    /// `layout(blend_support_multiply) out;`. Only one color attachment can be written to.
+    ///
+    /// Unless `GL_KHR_blend_equation_advanced_coherent` is supported, glium inserts a blend
+    /// barrier before each draw command that uses an advanced equation. Note that a single draw
+    /// command still must not touch the same pixel twice in that situation.
+    Advanced(AdvancedBlendEquation),
+}
+
+/// Advanced blend equation. See `BlendingFunction::Advanced`.
+///
+/// In the descriptions below, `Cs` is the source color and `Cd` is the destination color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AdvancedBlendEquation {
+    /// `Cs * Cd`
+    Multiply,
+    /// `Cs + Cd - Cs * Cd`
+    Screen,
+    /// `Multiply` or `Screen` depending on the destination color.
+    Overlay,
+    /// `min(Cs, Cd)`
+    Darken,
+    /// `max(Cs, Cd)`
+    Lighten,
+    /// Brightens the destination color to reflect the source color.
+    ColorDodge,
+    /// Darkens the destination color to reflect the source color.
+    ColorBurn,
+    /// `Multiply` or `Screen` depending on the source color.
+    HardLight,
+    /// Darkens or lightens the destination color depending on the source color.
+    SoftLight,
+    /// `abs(Cd - Cs)`
+    Difference,
+    /// `Cs + Cd - 2 * Cs * Cd`
+    Exclusion,
+    /// Hue of the source color, with the saturation and luminosity of the destination color.
+    HslHue,
+    /// Saturation of the source color, with the hue and luminosity of the destination color.
+    HslSaturation,
+    /// Hue and saturation of the source color, with the luminosity of the destination color.
+    HslColor,
+    /// Luminosity of the source color, with the hue and saturation of the destination color.
+    HslLuminosity,
+}
+
+impl AdvancedBlendEquation {
+    fn to_glenum(self) -> gl::types::GLenum {
+        match self {
+            AdvancedBlendEquation::Multiply => gl::MULTIPLY_KHR,
+            AdvancedBlendEquation::Screen => gl::SCREEN_KHR,
+            AdvancedBlendEquation::Overlay => gl::OVERLAY_KHR,
+            AdvancedBlendEquation::Darken => gl::DARKEN_KHR,
+            AdvancedBlendEquation::Lighten => gl::LIGHTEN_KHR,
+            AdvancedBlendEquation::ColorDodge => gl::COLORDODGE_KHR,
+            AdvancedBlendEquation::ColorBurn => gl::COLORBURN_KHR,
+            AdvancedBlendEquation::HardLight => gl::HARDLIGHT_KHR,
+            AdvancedBlendEquation::SoftLight => gl::SOFTLIGHT_KHR,
+            AdvancedBlendEquation::Difference => gl::DIFFERENCE_KHR,
+            AdvancedBlendEquation::Exclusion => gl::EXCLUSION_KHR,
+            AdvancedBlendEquation::HslHue => gl::HSL_HUE_KHR,
+            AdvancedBlendEquation::HslSaturation => gl::HSL_SATURATION_KHR,
+            AdvancedBlendEquation::HslColor => gl::HSL_COLOR_KHR,
+            AdvancedBlendEquation::HslLuminosity => gl::HSL_LUMINOSITY_KHR,
+        }
+    }
+}
+
+/// Returns true if the backend supports `BlendingFunction::Advanced`.
+pub fn is_advanced_blending_supported<C>(ctxt: &C) -> bool where C: ?Sized + CapabilitiesSource {
+    ctxt.get_version() >= &Version(Api::GlEs, 3, 2) ||
+        ctxt.get_extensions().gl_khr_blend_equation_advanced
+}
+
+/// Returns true if the blending state can be set separately for each draw buffer, with
+/// `DrawParameters::attachment_blend`.
+pub fn is_attachment_blending_supported<C>(ctxt: &C) -> bool
+    where C: ?Sized + CapabilitiesSource
+{
+    indexed_blending(ctxt).is_some()
+}
+
+/// Which set of entry points sets the blending state of a single draw buffer.
+#[derive(Copy, Clone)]
+enum IndexedBlending {
+    /// `glBlendFuncSeparatei` and co. of OpenGL 4.0 and OpenGL ES 3.2.
+    Core,
+    /// `GL_ARB_draw_buffers_blend`, with `glEnablei` from OpenGL 3.0.
+    Arb,
+    /// `GL_EXT_draw_buffers_indexed`.
+    Ext,
+    /// `GL_OES_draw_buffers_indexed`.
+    Oes,
+}
+
+fn indexed_blending<C>(ctxt: &C) -> Option<IndexedBlending>
+    where C: ?Sized + CapabilitiesSource
+{
+    if ctxt.get_version() >= &Version(Api::Gl, 4, 0) ||
+       ctxt.get_version() >= &Version(Api::GlEs, 3, 2)
+    {
+        Some(IndexedBlending::Core)
+    } else if ctxt.get_version() >= &Version(Api::Gl, 3, 0) &&
+              ctxt.get_extensions().gl_arb_draw_buffers_blend
+    {
+        Some(IndexedBlending::Arb)
+    } else if ctxt.get_extensions().gl_ext_draw_buffers_indexed {
+        Some(IndexedBlending::Ext)
+    } else if ctxt.get_extensions().gl_oes_draw_buffers_indexed {
+        Some(IndexedBlending::Oes)
+    } else {
+        None
+    }
 }
 
 /// Indicates which value to multiply each component with.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum LinearBlendingFactor {
     /// Multiply the source or destination component by zero, which always
     /// gives `0.0`.
@@ -223,56 +375,65 @@ impl LinearBlendingFactor {
     }
 }
 
-pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend) -> Result<(), DrawError> {
-    #[inline(always)]
-    fn blend_eq(ctxt: &mut CommandContext<'_>, blending_function: BlendingFunction)
-                -> Result<gl::types::GLenum, DrawError>
-    {
-        match blending_function {
-            BlendingFunction::AlwaysReplace |
-            BlendingFunction::Addition { .. } => Ok(gl::FUNC_ADD),
-            BlendingFunction::Subtraction { .. } => Ok(gl::FUNC_SUBTRACT),
-            BlendingFunction::ReverseSubtraction { .. } => Ok(gl::FUNC_REVERSE_SUBTRACT),
-
-            BlendingFunction::Min => {
-                if ctxt.version <= &Version(Api::GlEs, 2, 0) &&
-                   !ctxt.extensions.gl_ext_blend_minmax
-                {
-                    Err(DrawError::BlendingParameterNotSupported)
-                } else {
-                    Ok(gl::MIN)
-                }
-            },
+#[inline(always)]
+fn blend_eq(ctxt: &mut CommandContext<'_>, blending_function: BlendingFunction)
+            -> Result<gl::types::GLenum, DrawError>
+{
+    match blending_function {
+        BlendingFunction::AlwaysReplace |
+        BlendingFunction::Addition { .. } => Ok(gl::FUNC_ADD),
+        BlendingFunction::Subtraction { .. } => Ok(gl::FUNC_SUBTRACT),
+        BlendingFunction::ReverseSubtraction { .. } => Ok(gl::FUNC_REVERSE_SUBTRACT),
+
+        BlendingFunction::Min => {
+            if ctxt.version <= &Version(Api::GlEs, 2, 0) &&
+               !ctxt.extensions.gl_ext_blend_minmax
+            {
+                Err(DrawError::BlendingParameterNotSupported)
+            } else {
+                Ok(gl::MIN)
+            }
+        },
+
+        BlendingFunction::Max => {
+            if ctxt.version <= &Version(Api::GlEs, 2, 0) &&
+               !ctxt.extensions.gl_ext_blend_minmax
+            {
+                Err(DrawError::BlendingParameterNotSupported)
+            } else {
+                Ok(gl::MAX)
+            }
+        },
 
-            BlendingFunction::Max => {
-                if ctxt.version <= &Version(Api::GlEs, 2, 0) &&
-                   !ctxt.extensions.gl_ext_blend_minmax
-                {
-                    Err(DrawError::BlendingParameterNotSupported)
-                } else {
-                    Ok(gl::MAX)
-                }
-            },
-        }
+        BlendingFunction::Advanced(equation) => {
+            if !is_advanced_blending_supported(ctxt) {
+                Err(DrawError::BlendingParameterNotSupported)
+            } else {
+                Ok(equation.to_glenum())
+            }
+        },
     }
+}
 
-    #[inline(always)]
-    fn blending_factors(blending_function: BlendingFunction)
-                        -> Option<(LinearBlendingFactor, LinearBlendingFactor)>
-    {
-        match blending_function {
-            BlendingFunction::AlwaysReplace |
-            BlendingFunction::Min |
-            BlendingFunction::Max => None,
-            BlendingFunction::Addition { source, destination } =>
-                Some((source, destination)),
-            BlendingFunction::Subtraction { source, destination } =>
-                Some((source, destination)),
-            BlendingFunction::ReverseSubtraction { source, destination } =>
-                Some((source, destination)),
-        }
+#[inline(always)]
+fn blending_factors(blending_function: BlendingFunction)
+                    -> Option<(LinearBlendingFactor, LinearBlendingFactor)>
+{
+    match blending_function {
+        BlendingFunction::AlwaysReplace |
+        BlendingFunction::Min |
+        BlendingFunction::Max |
+        BlendingFunction::Advanced(_) => None,
+        BlendingFunction::Addition { source, destination } =>
+            Some((source, destination)),
+        BlendingFunction::Subtraction { source, destination } =>
+            Some((source, destination)),
+        BlendingFunction::ReverseSubtraction { source, destination } =>
+            Some((source, destination)),
     }
+}
 
+pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend) -> Result<(), DrawError> {
     if let (BlendingFunction::AlwaysReplace, BlendingFunction::AlwaysReplace) =
            (blend.color, blend.alpha)
     {
@@ -282,6 +443,39 @@ pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend) -> Result<(),
             ctxt.state.enabled_blend = false;
         }
 
+    } else if let BlendingFunction::Advanced(_) = blend.color {
+        if blend.alpha != blend.color {
+            return Err(DrawError::AdvancedBlendingMismatch);
+        }
+
+        let equation = blend_eq(ctxt, blend.color)?;
+
+        if !ctxt.state.enabled_blend {
+            unsafe { ctxt.gl.Enable(gl::BLEND); }
+            ctxt.state.enabled_blend = true;
+        }
+
+        // advanced equations can't be passed to `glBlendEquationSeparate`
+        if ctxt.state.blend_equation != (equation, equation) {
+            unsafe { ctxt.gl.BlendEquation(equation); }
+            ctxt.state.blend_equation = (equation, equation);
+        }
+
+        // without coherent blending, a barrier is needed so that this command reads what the
+        // previous ones wrote
+        if !ctxt.extensions.gl_khr_blend_equation_advanced_coherent {
+            unsafe {
+                if ctxt.version >= &Version(Api::GlEs, 3, 2) {
+                    ctxt.gl.BlendBarrier();
+                } else {
+                    ctxt.gl.BlendBarrierKHR();
+                }
+            }
+        }
+
+    } else if let BlendingFunction::Advanced(_) = blend.alpha {
+        return Err(DrawError::AdvancedBlendingMismatch);
+
     } else {
         if !ctxt.state.enabled_blend {
             unsafe { ctxt.gl.Enable(gl::BLEND); }
@@ -302,22 +496,10 @@ pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend) -> Result<(),
             .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
 
         // Updating the blending color if necessary.
-        if (color_factor_src == LinearBlendingFactor::ConstantColor ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_dst == LinearBlendingFactor::ConstantColor ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha) && ctxt.state.blend_color != blend.constant_value {
+        if uses_constant_value(&[color_factor_src, color_factor_dst, alpha_factor_src,
+                                 alpha_factor_dst]) &&
+           ctxt.state.blend_color != blend.constant_value
+        {
             let (r, g, b, a) = blend.constant_value;
             unsafe { ctxt.gl.BlendColor(r, g, b, a); }
             ctxt.state.blend_color = blend.constant_value;
@@ -343,3 +525,104 @@ pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend) -> Result<(),
 
     Ok(())
 }
+
+/// Returns true if one of the factors reads the constant blending color.
+fn uses_constant_value(factors: &[LinearBlendingFactor]) -> bool {
+    factors.iter().any(|factor| matches!(factor, LinearBlendingFactor::ConstantColor |
+                                                 LinearBlendingFactor::OneMinusConstantColor |
+                                                 LinearBlendingFactor::ConstantAlpha |
+                                                 LinearBlendingFactor::OneMinusConstantAlpha))
+}
+
+/// Sets the blending state of a single draw buffer, after `sync_blending` has set the state
+/// of all of them.
+///
+/// The per-buffer state isn't cached, and the caller must call `invalidate_blending` before
+/// the next call to `sync_blending`.
+pub fn sync_attachment_blending(ctxt: &mut CommandContext<'_>, index: u32, blend: Blend)
+                                -> Result<(), DrawError>
+{
+    let entry_points = match indexed_blending(ctxt) {
+        Some(entry_points) => entry_points,
+        None => return Err(DrawError::AttachmentBlendingNotSupported),
+    };
+
+    match (blend.color, blend.alpha) {
+        (BlendingFunction::AlwaysReplace, BlendingFunction::AlwaysReplace) => unsafe {
+            match entry_points {
+                IndexedBlending::Core | IndexedBlending::Arb => ctxt.gl.Disablei(gl::BLEND, index),
+                IndexedBlending::Ext => ctxt.gl.DisableiEXT(gl::BLEND, index),
+                IndexedBlending::Oes => ctxt.gl.DisableiOES(gl::BLEND, index),
+            }
+        },
+
+        // advanced equations apply to all the draw buffers at once
+        (BlendingFunction::Advanced(_), _) | (_, BlendingFunction::Advanced(_)) => {
+            return Err(DrawError::BlendingParameterNotSupported);
+        },
+
+        _ => {
+            let (color_eq, alpha_eq) = (blend_eq(ctxt, blend.color)?,
+                                        blend_eq(ctxt, blend.alpha)?);
+            let (color_factor_src, color_factor_dst) = blending_factors(blend.color)
+                .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
+            let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha)
+                .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
+
+            // the blending color is shared by all the draw buffers
+            if uses_constant_value(&[color_factor_src, color_factor_dst, alpha_factor_src,
+                                     alpha_factor_dst]) &&
+               ctxt.state.blend_color != blend.constant_value
+            {
+                let (r, g, b, a) = blend.constant_value;
+                unsafe { ctxt.gl.BlendColor(r, g, b, a); }
+                ctxt.state.blend_color = blend.constant_value;
+            }
+
+            let (color_factor_src, color_factor_dst) = (color_factor_src.to_glenum(),
+                                                        color_factor_dst.to_glenum());
+            let (alpha_factor_src, alpha_factor_dst) = (alpha_factor_src.to_glenum(),
+                                                        alpha_factor_dst.to_glenum());
+
+            unsafe {
+                match entry_points {
+                    IndexedBlending::Core => {
+                        ctxt.gl.Enablei(gl::BLEND, index);
+                        ctxt.gl.BlendEquationSeparatei(index, color_eq, alpha_eq);
+                        ctxt.gl.BlendFuncSeparatei(index, color_factor_src, color_factor_dst,
+                                                   alpha_factor_src, alpha_factor_dst);
+                    },
+                    IndexedBlending::Arb => {
+                        ctxt.gl.Enablei(gl::BLEND, index);
+                        ctxt.gl.BlendEquationSeparateiARB(index, color_eq, alpha_eq);
+                        ctxt.gl.BlendFuncSeparateiARB(index, color_factor_src, color_factor_dst,
+                                                      alpha_factor_src, alpha_factor_dst);
+                    },
+                    IndexedBlending::Ext => {
+                        ctxt.gl.EnableiEXT(gl::BLEND, index);
+                        ctxt.gl.BlendEquationSeparateiEXT(index, color_eq, alpha_eq);
+                        ctxt.gl.BlendFuncSeparateiEXT(index, color_factor_src, color_factor_dst,
+                                                      alpha_factor_src, alpha_factor_dst);
+                    },
+                    IndexedBlending::Oes => {
+                        ctxt.gl.EnableiOES(gl::BLEND, index);
+                        ctxt.gl.BlendEquationSeparateiOES(index, color_eq, alpha_eq);
+                        ctxt.gl.BlendFuncSeparateiOES(index, color_factor_src, color_factor_dst,
+                                                      alpha_factor_src, alpha_factor_dst);
+                    },
+                }
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Forgets the cached blending state, so that the next call to `sync_blending` sets it again
+/// for all the draw buffers.
+pub fn invalidate_blending(ctxt: &mut CommandContext<'_>) {
+    unsafe { ctxt.gl.Disable(gl::BLEND); }
+    ctxt.state.enabled_blend = false;
+    ctxt.state.blend_equation = (gl::NONE, gl::NONE);
+    ctxt.state.blend_func = (gl::NONE, gl::NONE, gl::NONE, gl::NONE);
+}
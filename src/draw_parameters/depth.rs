@@ -1,3 +1,4 @@
+use crate::CapabilitiesSource;
 use crate::context::CommandContext;
 use crate::version::Api;
 use crate::version::Version;
@@ -5,8 +6,12 @@ use crate::version::Version;
 use crate::DrawError;
 use crate::gl;
 
+use std::hash::{Hash, Hasher};
+
+use super::hash_f32;
+
 /// Represents the depth parameters of a draw command.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Depth {
     /// The function that the GPU will use to determine whether to write over an existing pixel
     /// on the target. Don't forget to set `depth_write` appropriately if you use a depth test.
@@ -41,7 +46,8 @@ pub struct Depth {
 
     /// Sets whether the depth values of samples should be clamped to `0.0` and `1.0`.
     ///
-    /// The default value is `NoClamp`.
+    /// The default value is `NoClamp`. See `is_depth_clamp_supported` for the values that
+    /// the backend supports.
     pub clamp: DepthClamp,
 }
 
@@ -57,6 +63,18 @@ impl Default for Depth {
     }
 }
 
+impl Eq for Depth {}
+
+impl Hash for Depth {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.test.hash(state);
+        self.write.hash(state);
+        hash_f32(self.range.0, state);
+        hash_f32(self.range.1, state);
+        self.clamp.hash(state);
+    }
+}
+
 /// The function that the GPU will use to determine whether to write over an existing pixel
 /// on the target.
 ///
@@ -77,7 +95,7 @@ impl Default for Depth {
 ///
 /// If you don't have a depth buffer available, you can only pass `Overwrite`. Glium detects if
 /// you pass any other value and reports an error.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum DepthTest {
     /// Never replace the target pixel.
     ///
@@ -140,7 +158,7 @@ impl DepthTest {
 }
 
 /// Specifies whether the depth value of samples should be clamped to `0.0` or `1.0`.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum DepthClamp {
     /// Do not clamp. Samples with values outside of the `[0.0, 1.0]` range will be discarded.
     ///
@@ -149,23 +167,51 @@ pub enum DepthClamp {
 
     /// Clamp the depth values. All samples will always be drawn.
     ///
-    /// This value is only supported on OpenGL.
+    /// When rendering a shadow map, this flattens the shadow casters that are in front of the
+    /// near plane onto it instead of clipping them, which lets the near plane be moved as close
+    /// as possible to the receivers.
+    ///
+    /// Requires OpenGL 3.2, `GL_ARB_depth_clamp` or `GL_NV_depth_clamp`, or
+    /// `GL_EXT_depth_clamp` on OpenGL ES.
     Clamp,
 
     /// Depth values inferior to `0.0` will be clamped to `0.0`.
     ///
-    /// **This option is supported only by very few OpenGL devices**.
+    /// **This option is supported only by very few OpenGL devices**, as it requires
+    /// `GL_AMD_depth_clamp_separate`.
     ClampNear,
 
     /// Depth values superior to `1.0` will be clamped to `1.0`.
     ///
-    /// **This option is supported only by very few OpenGL devices**.
+    /// **This option is supported only by very few OpenGL devices**, as it requires
+    /// `GL_AMD_depth_clamp_separate`.
     ClampFar,
 }
 
+/// Returns true if the backend supports the given `DepthClamp` value.
+pub fn is_depth_clamp_supported<C>(ctxt: &C, clamp: DepthClamp) -> bool
+    where C: ?Sized + CapabilitiesSource
+{
+    match clamp {
+        DepthClamp::NoClamp => true,
+        DepthClamp::Clamp => is_full_depth_clamp_supported(ctxt),
+        DepthClamp::ClampNear | DepthClamp::ClampFar =>
+            ctxt.get_extensions().gl_amd_depth_clamp_separate,
+    }
+}
+
+#[inline]
+fn is_full_depth_clamp_supported<C>(ctxt: &C) -> bool where C: ?Sized + CapabilitiesSource {
+    ctxt.get_version() >= &Version(Api::Gl, 3, 2) || ctxt.get_extensions().gl_arb_depth_clamp ||
+        ctxt.get_extensions().gl_nv_depth_clamp || ctxt.get_extensions().gl_ext_depth_clamp
+}
+
 pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), DrawError> {
     // depth clamp
     {
+        let full_supported = is_full_depth_clamp_supported(ctxt);
+        let separate_supported = ctxt.extensions.gl_amd_depth_clamp_separate;
+
         let state = &mut *ctxt.state;
         match (depth.clamp, &mut state.enabled_depth_clamp_near,
                &mut state.enabled_depth_clamp_far)
@@ -174,9 +220,7 @@ pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), Dr
             (DepthClamp::Clamp, &mut true, &mut true) => (),
 
             (DepthClamp::NoClamp, near, far) => {
-                if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
-                   ctxt.extensions.gl_nv_depth_clamp
-                {
+                if full_supported {
                     unsafe { ctxt.gl.Disable(gl::DEPTH_CLAMP) };
                     *near = false;
                     *far = false;
@@ -186,9 +230,7 @@ pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), Dr
             },
 
             (DepthClamp::Clamp, near, far) => {
-                if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
-                   ctxt.extensions.gl_nv_depth_clamp
-                {
+                if full_supported {
                     unsafe { ctxt.gl.Enable(gl::DEPTH_CLAMP) };
                     *near = true;
                     *far = true;
@@ -201,7 +243,7 @@ pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), Dr
             (DepthClamp::ClampFar, &mut false, &mut true) => (),
 
             (DepthClamp::ClampNear, &mut true, far) => {
-                if ctxt.extensions.gl_amd_depth_clamp_separate {
+                if separate_supported {
                     unsafe { ctxt.gl.Disable(gl::DEPTH_CLAMP_FAR_AMD) };
                     *far = false;
                 } else {
@@ -211,7 +253,7 @@ pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), Dr
             },
 
             (DepthClamp::ClampNear, near @ &mut false, far) => {
-                if ctxt.extensions.gl_amd_depth_clamp_separate {
+                if separate_supported {
                     unsafe { ctxt.gl.Enable(gl::DEPTH_CLAMP_NEAR_AMD) };
                     if *far { unsafe { ctxt.gl.Disable(gl::DEPTH_CLAMP_FAR_AMD); } }
                     *near = true;
@@ -222,7 +264,7 @@ pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), Dr
             },
 
             (DepthClamp::ClampFar, near, &mut true) => {
-                if ctxt.extensions.gl_amd_depth_clamp_separate {
+                if separate_supported {
                     unsafe { ctxt.gl.Disable(gl::DEPTH_CLAMP_NEAR_AMD) };
                     *near = false;
                 } else {
@@ -231,7 +273,7 @@ pub fn sync_depth(ctxt: &mut CommandContext<'_>, depth: &Depth) -> Result<(), Dr
             },
 
             (DepthClamp::ClampFar, near, far @ &mut false) => {
-                if ctxt.extensions.gl_amd_depth_clamp_separate {
+                if separate_supported {
                     unsafe { ctxt.gl.Enable(gl::DEPTH_CLAMP_FAR_AMD) };
                     if *near { unsafe { ctxt.gl.Disable(gl::DEPTH_CLAMP_NEAR_AMD); } }
                     *near = false;
@@ -14,6 +14,41 @@
 //! };
 //! ```
 //!
+//! # Reversed-Z
+//!
+//! With the default depth mode, most of the precision of a floating-point depth buffer is
+//! wasted near the camera and distant objects start to flicker. Mapping the far plane to a
+//! depth of `0.0` and the near plane to `1.0` instead spreads the precision evenly. This
+//! requires three changes that must be made together:
+//!
+//! - Use `ClipControlDepth::ZeroToOne`, otherwise the mapping from `[-1, 1]` to `[0, 1]`
+//!   throws the extra precision away. See `is_clip_control_supported`.
+//! - Clear the depth buffer to `0.0` instead of `1.0`.
+//! - Use a `IfMoreOrEqual` depth test (or `IfMore`) instead of `IfLess`.
+//!
+//! The projection matrix must also map the near plane to `1.0` and the far plane to `0.0`.
+//! This works best with a `F32` depth buffer.
+//!
+//! ```no_run
+//! # use glium::Surface;
+//! # fn example(mut frame: glium::Frame) {
+//! frame.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 0.0);
+//!
+//! let params = glium::DrawParameters {
+//!     depth: glium::Depth {
+//!         test: glium::draw_parameters::DepthTest::IfMoreOrEqual,
+//!         write: true,
+//!         .. Default::default()
+//!     },
+//!     clip_control_depth: glium::draw_parameters::ClipControlDepth::ZeroToOne,
+//!     .. Default::default()
+//! };
+//! # }
+//! ```
+//!
+//! Glium keeps track of the clip control state, so there is no need to call `glClipControl`
+//! manually.
+//!
 //! # Queries
 //!
 //! Query objects allow you to obtain information about the rendering process. For example, a
@@ -57,11 +92,26 @@
 //! If you use conditional rendering, glium will submit the draw command but the GPU will execute
 //! it only if the query contains a value different from 0.
 //!
+//! Instead of attaching the query to each draw call, you can also make it active for everything
+//! that is drawn inside of a closure:
+//!
+//! ```no_run
+//! # use glium::Surface;
+//! # fn example(query: glium::draw_parameters::SamplesPassedQuery, mut frame: glium::Frame) {
+//! query.scope(|| {
+//!     frame.clear_color(0.0, 0.0, 0.0, 1.0);
+//!     // frame.draw(...) calls that don't specify a `samples_passed_query`
+//! }).unwrap();
+//! # }
+//! ```
+//!
 //! ## WrongQueryOperation errors
 //!
 //! OpenGL puts some restrictions about the usage of queries. If you draw one or several times
 //! with a query, then draw *without* that query, then the query cannot be used again. Trying
 //! to draw with it results in a `WrongQueryOperation` error returned by the `draw` function.
+//! Inside of a `scope`, drawing with a different samples query or starting another scope also
+//! results in a `WrongQueryOperation` error.
 //!
 //! For the same reasons, as soon as you call `is_ready` on a query it will stop being usable.
 
@@ -76,14 +126,18 @@ use crate::index::PrimitiveType;
 use crate::QueryExt;
 use crate::CapabilitiesSource;
 use crate::DrawError;
+use crate::GlObject;
 use crate::Rect;
 use crate::ToGlEnum;
 use crate::vertex::TransformFeedbackSession;
 
+use std::hash::{Hash, Hasher};
 use std::ops::Range;
+use std::ptr;
 
-pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor};
-pub use self::depth::{Depth, DepthTest, DepthClamp};
+pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor, AdvancedBlendEquation};
+pub use self::blend::{is_advanced_blending_supported, is_attachment_blending_supported};
+pub use self::depth::{Depth, DepthTest, DepthClamp, is_depth_clamp_supported};
 pub use self::query::{QueryCreationError};
 pub use self::query::{SamplesPassedQuery, TimeElapsedQuery, PrimitivesGeneratedQuery};
 pub use self::query::{AnySamplesPassedQuery, TransformFeedbackPrimitivesWrittenQuery};
@@ -135,7 +189,7 @@ mod stencil;
 /// By doing so you can use backface culling to discard all the triangles that are not
 /// facing the screen, and increase your framerate.
 ///
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BackfaceCullingMode {
     /// All triangles are always drawn.
     CullingDisabled,
@@ -166,7 +220,7 @@ pub enum BackfaceCullingMode {
 ///  </g>
 /// </svg>
 ///
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PolygonMode {
     /// Only draw a single point at each vertex.
     ///
@@ -193,10 +247,75 @@ impl ToGlEnum for PolygonMode {
     }
 }
 
+/// Bitwise operation applied between the color written by the fragment shader (the source) and
+/// the color already in the framebuffer (the destination).
+///
+/// Only applies to attachments with a normalized integer or integral format. Floating-point
+/// attachments are written as if the operation was `Copy`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LogicOp {
+    /// `0`
+    Clear,
+    /// `source & destination`
+    And,
+    /// `source & !destination`
+    AndReverse,
+    /// `source`
+    Copy,
+    /// `!source & destination`
+    AndInverted,
+    /// `destination`
+    Noop,
+    /// `source ^ destination`
+    Xor,
+    /// `source | destination`
+    Or,
+    /// `!(source | destination)`
+    Nor,
+    /// `!(source ^ destination)`
+    Equiv,
+    /// `!destination`
+    Invert,
+    /// `source | !destination`
+    OrReverse,
+    /// `!source`
+    CopyInverted,
+    /// `!source | destination`
+    OrInverted,
+    /// `!(source & destination)`
+    Nand,
+    /// All the bits set to `1`.
+    Set,
+}
+
+impl ToGlEnum for LogicOp {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            LogicOp::Clear => gl::CLEAR,
+            LogicOp::And => gl::AND,
+            LogicOp::AndReverse => gl::AND_REVERSE,
+            LogicOp::Copy => gl::COPY,
+            LogicOp::AndInverted => gl::AND_INVERTED,
+            LogicOp::Noop => gl::NOOP,
+            LogicOp::Xor => gl::XOR,
+            LogicOp::Or => gl::OR,
+            LogicOp::Nor => gl::NOR,
+            LogicOp::Equiv => gl::EQUIV,
+            LogicOp::Invert => gl::INVERT,
+            LogicOp::OrReverse => gl::OR_REVERSE,
+            LogicOp::CopyInverted => gl::COPY_INVERTED,
+            LogicOp::OrInverted => gl::OR_INVERTED,
+            LogicOp::Nand => gl::NAND,
+            LogicOp::Set => gl::SET,
+        }
+    }
+}
+
 /// Specifies a hint for the smoothing.
 ///
 /// Note that this is just a hint and the driver may disregard it.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Smooth {
     /// The most efficient option should be chosen.
     Fastest,
@@ -220,7 +339,7 @@ impl ToGlEnum for Smooth {
 }
 
 /// The vertex to use for flat shading.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ProvokingVertex {
     /// Use the last vertex of each primitive.
     LastVertex,
@@ -246,6 +365,13 @@ pub enum ProvokingVertex {
 /// };
 /// ```
 ///
+/// # Comparison and hashing
+///
+/// `DrawParameters` implements `Eq` and `Hash` so that it can be used as the key of a cache of
+/// render states. The values of the parameters are compared, except for the queries and the
+/// transform feedback session which are compared by identity. Floating-point parameters must
+/// not be NaN, otherwise the parameters are not equal to themselves.
+///
 #[derive(Clone, Debug)]
 pub struct DrawParameters<'a> {
     /// How the fragment will interact with the depth buffer.
@@ -268,6 +394,35 @@ pub struct DrawParameters<'a> {
     /// is `(true, true, true, true)`.
     pub color_mask: (bool, bool, bool, bool),
 
+    /// Blend effects for the individual draw buffers of the framebuffer, in the order of the
+    /// fragment shader outputs. The effect at index `i` replaces `blend` for draw buffer `i`,
+    /// and the draw buffers past the end of the list use `blend`.
+    ///
+    /// This lets a single pass of a deferred renderer for example overwrite its albedo target
+    /// while adding to its emissive target. All the draw buffers share the same
+    /// `constant_value`, and advanced blend equations can only be used with `blend`.
+    ///
+    /// Requires OpenGL 4.0, OpenGL ES 3.2 or one of the `GL_ARB_draw_buffers_blend`,
+    /// `GL_EXT_draw_buffers_indexed` and `GL_OES_draw_buffers_indexed` extensions, see
+    /// `is_attachment_blending_supported`. The default value is an empty list.
+    pub attachment_blend: Vec<Blend>,
+
+    /// Bitwise operation between the output of the fragment shader and the content of the
+    /// framebuffer, for example `Xor` to draw an overlay that disappears when it is drawn a
+    /// second time. `None` writes the output as it is. The default value is `None`.
+    ///
+    /// Blending is replaced by the operation, so `blend` and `attachment_blend` must not blend
+    /// when this is `Some`, otherwise drawing returns a `LogicOpWithBlending` error. Returns a
+    /// `LogicOpNotSupported` error on OpenGL ES, which doesn't have logic operations.
+    pub color_logic_op: Option<LogicOp>,
+
+    /// Color masks for the individual draw buffers of the framebuffer. Like
+    /// `attachment_blend`, the mask at index `i` replaces `color_mask` for draw buffer `i`.
+    ///
+    /// Requires OpenGL 3.0, OpenGL ES 3.2 or one of the `GL_EXT_draw_buffers_indexed` and
+    /// `GL_OES_draw_buffers_indexed` extensions. The default value is an empty list.
+    pub attachment_color_mask: Vec<(bool, bool, bool, bool)>,
+
     /// Width in pixels of the lines to draw when drawing lines.
     ///
     /// `None` means "don't care". Use this when you don't draw lines.
@@ -280,7 +435,16 @@ pub struct DrawParameters<'a> {
 
     /// If the bit corresponding to 2^i is 1 in the bitmask, then GL_CLIP_DISTANCEi is enabled.
     ///
-    /// The most common value for GL_MAX_CLIP_DISTANCES is 8, so 32 bits in the mask is plenty.
+    /// Writing to `gl_ClipDistance[i]` in a shader has no effect unless the corresponding bit
+    /// is set here. For example a shader that clips against a water plane by writing
+    /// `gl_ClipDistance[0]` must be drawn with a bitmask of `1 << 0`.
+    ///
+    /// The value is checked against `Capabilities::max_clip_distances` (the most common value
+    /// being 8). Setting a bit that is out of range makes the draw fail with
+    /// `DrawError::ClipPlaneIndexOutOfBounds`. On OpenGL ES, clip distances require ES 3.2
+    /// or `GL_EXT_clip_cull_distance`.
+    ///
+    /// The default value is `0`.
     ///
     /// See `https://www.khronos.org/registry/OpenGL-Refpages/gl4/html/gl_ClipDistance.xhtml`.
     pub clip_planes_bitmask: u32,
@@ -305,6 +469,20 @@ pub struct DrawParameters<'a> {
     /// creating the window.
     pub multisampling: bool,
 
+    /// Whether the alpha value written by the fragment shader is converted to a coverage mask
+    /// when drawing to a multisampled target. The default value is `false`.
+    ///
+    /// This is how cutouts like foliage are anti-aliased under MSAA: a fragment with an alpha
+    /// of `0.5` only covers about half of the samples of its pixel. Has no effect on targets
+    /// that are not multisampled or when `multisampling` is `false`.
+    pub multisampling_alpha_to_coverage: bool,
+
+    /// Whether the alpha value of the fragments is replaced with `1.0` after it has been used
+    /// by `multisampling_alpha_to_coverage`. The default value is `false`.
+    ///
+    /// Returns an `AlphaToOneNotSupported` error if `true` on OpenGL ES.
+    pub multisampling_alpha_to_one: bool,
+
     /// Whether dithering is activated. Default value is `true`.
     ///
     /// Dithering will smoothen the transition between colors in your color buffer.
@@ -327,16 +505,39 @@ pub struct DrawParameters<'a> {
     /// not the scissor box.
     pub scissor: Option<Rect>,
 
+    /// The viewports to use when drawing, for geometry shaders that write `gl_ViewportIndex`.
+    ///
+    /// When not empty, the viewport at index `i` of the list is used for the primitives
+    /// whose `gl_ViewportIndex` is `i` and `viewport` is ignored. This allows for example
+    /// rendering all the cascades of a shadow map into an atlas in a single pass. Primitives
+    /// that don't write `gl_ViewportIndex` use the first viewport. Shaders must not write an
+    /// index past the end of the list.
+    ///
+    /// Using more than one viewport requires OpenGL 4.1 or `GL_ARB_viewport_array`, see
+    /// `is_viewport_array_supported`. The list can't be longer than
+    /// `Capabilities::max_viewports`, otherwise drawing returns a `TooManyViewports` error.
+    /// The default value is an empty list.
+    pub viewports: Vec<Rect>,
+
+    /// The scissor boxes to use for each viewport index. Like `viewports`, the box at index `i`
+    /// applies to the primitives whose `gl_ViewportIndex` is `i`, and `scissor` is ignored when
+    /// the list is not empty. Viewport indices past the end of the list are not scissored.
+    ///
+    /// Has the same requirements as `viewports`. The default value is an empty list.
+    pub scissors: Vec<Rect>,
+
     /// If `false`, the pipeline will stop after the primitives generation stage. The default
     /// value is `true`.
     ///
     /// If `false`, the fragment shader of your program won't be executed.
     ///
-    /// If `false`, drawing may return `TransformFeedbackNotSupported` if the backend doesn't
-    /// support this feature.
+    /// If `false`, drawing returns `RasterizerDiscardNotSupported` if the backend doesn't
+    /// support this feature, see `is_rasterizer_discard_supported`.
     ///
     /// This parameter may seem pointless, but it can be useful when you use transform
-    /// feedback or if you just use your shaders to write to a buffer.
+    /// feedback or if you just use your shaders to write to a buffer. It doesn't require a
+    /// transform feedback session, so it can also be used to measure the cost of the vertex
+    /// stages alone.
     pub draw_primitives: bool,
 
     /// If set, each sample (ie. usually each pixel) written to the output adds one to the
@@ -385,9 +586,11 @@ pub struct DrawParameters<'a> {
     /// drawn is implementation-specific.
     ///
     /// This field is useless if you're not using a geometry shader or tessellation shader.
+    /// The coordinates are in clip space, and the default value covers the whole viewport.
     ///
     /// Since this is purely an optimization, this parameter is ignored if the backend doesn't
-    /// support it.
+    /// support it. Use `is_primitive_bounding_box_supported` to know whether it has an effect.
+    /// It is mostly supported by the tiled GPUs of mobile devices.
     pub primitive_bounding_box: (Range<f32>, Range<f32>, Range<f32>, Range<f32>),
 
     /// If enabled, will split the index buffer (if any is used in the draw call)
@@ -402,14 +605,65 @@ pub struct DrawParameters<'a> {
     pub polygon_offset: PolygonOffset,
 
     /// Clip control origin. The default value is `LowerLeft`.
+    ///
+    /// Values other than the default require OpenGL 4.5 or `GL_ARB_clip_control`, see
+    /// `is_clip_control_supported`. Drawing returns a `ClipControlNotSupported` error otherwise.
     pub clip_control_origin: ClipControlOrigin,
 
     /// Clip control depth mode. The default value is `NegativeOneToOne`.
+    ///
+    /// Set it to `ZeroToOne` when using a reversed depth buffer, see the module-level
+    /// documentation. Has the same requirements as `clip_control_origin`.
     pub clip_control_depth: ClipControlDepth,
+
+    /// Tessellation levels to use when drawing patches with a program that doesn't have a
+    /// tessellation control shader.
+    ///
+    /// This is only relevant when drawing `Patches`. The default value is `1.0` for every level,
+    /// which is also the default in OpenGL.
+    ///
+    /// Any other value can trigger a `DefaultTessellationLevelsNotSupported` error. Most
+    /// notably OpenGL ES doesn't support default tessellation levels.
+    pub default_tessellation_levels: TessellationLevels,
+
+    /// If true, the draw command checks that no resource is read and written at the same time
+    /// before being submitted, which gives undefined results in OpenGL. The default value is
+    /// `false`.
+    ///
+    /// The draw command returns:
+    ///
+    /// - `TransformFeedbackBufferAliasing` if the range written by the transform feedback
+    ///   overlaps a vertex source or the index buffer. Disjoint slices of the same buffer are
+    ///   allowed.
+    /// - `ImageWrittenWhileSampled` if a sampled texture is also bound to an image unit with
+    ///   write access.
+    /// - `TextureFeedbackLoop` if a sampled texture is attached to the target and has been
+    ///   rendered to since the latest texture barrier, unless `auto_texture_barrier` is enabled.
+    pub check_hazards: bool,
+
+    /// If true, a texture barrier is automatically inserted before the draw command when it
+    /// samples a texture that is attached to the target and that has been rendered to since the
+    /// latest barrier. The default value is `false`.
+    ///
+    /// Returns a `TextureBarrierNotSupported` error if a barrier is required but not supported.
+    /// See `Surface::texture_barrier` for the rules that apply to feedback loops.
+    pub auto_texture_barrier: bool,
+
+    /// Whether the colors written to sRGB attachments are converted from linear RGB, which is
+    /// what `GL_FRAMEBUFFER_SRGB` controls. Attachments that aren't sRGB are never affected.
+    ///
+    /// `None` lets the program decide: the conversion is done unless it has been created with
+    /// `outputs_srgb` set to `true`. `Some(false)` writes the output of the fragment shader as
+    /// it is, which is useful for example to draw a user interface whose colors are already
+    /// sRGB on an sRGB swapchain, while the 3D scene is drawn with `Some(true)`.
+    ///
+    /// Returns a `FramebufferSrgbNotSupported` error if the value is `Some` and the backend
+    /// doesn't support changing it. The default value is `None`.
+    pub framebuffer_srgb: Option<bool>,
 }
 
 /// Condition whether to render or not.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct ConditionalRendering<'a> {
     /// The query to use.
     pub query: SamplesQueryParam<'a>,
@@ -432,6 +686,37 @@ pub enum SamplesQueryParam<'a> {
     AnySamplesPassedQuery(&'a AnySamplesPassedQuery),
 }
 
+// queries are compared by identity
+impl<'a> PartialEq for SamplesQueryParam<'a> {
+    #[inline]
+    fn eq(&self, other: &SamplesQueryParam<'_>) -> bool {
+        match (*self, *other) {
+            (SamplesQueryParam::SamplesPassedQuery(a),
+             SamplesQueryParam::SamplesPassedQuery(b)) => ptr::eq(a, b),
+            (SamplesQueryParam::AnySamplesPassedQuery(a),
+             SamplesQueryParam::AnySamplesPassedQuery(b)) => ptr::eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl<'a> Eq for SamplesQueryParam<'a> {}
+
+impl<'a> Hash for SamplesQueryParam<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            SamplesQueryParam::SamplesPassedQuery(q) => {
+                0u8.hash(state);
+                ptr::hash(q, state);
+            },
+            SamplesQueryParam::AnySamplesPassedQuery(q) => {
+                1u8.hash(state);
+                ptr::hash(q, state);
+            },
+        }
+    }
+}
+
 impl<'a> From<&'a SamplesPassedQuery> for SamplesQueryParam<'a> {
     #[inline]
     fn from(r: &'a SamplesPassedQuery) -> SamplesQueryParam<'a> {
@@ -447,12 +732,26 @@ impl<'a> From<&'a AnySamplesPassedQuery> for SamplesQueryParam<'a> {
 }
 
 /// Specifies the depth offset applied to rendered geometry
-#[derive(Debug, Copy, Clone)]
+///
+/// The `point`, `line` and `fill` flags enable the offset separately for each `PolygonMode`.
+/// Only `fill` is supported on OpenGL ES, enabling the others returns a
+/// `PolygonOffsetNotSupported` error.
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct PolygonOffset {
     /// Scale polygon depth with a factor
     pub factor: f32,
     /// Add a constant value to polygon depth
     pub units: f32,
+    /// Maximum absolute value of the offset, or `0.0` to not clamp it.
+    ///
+    /// Steep polygons get a large offset from `factor`, which makes shadows detach from their
+    /// casters ("peter-panning"). Clamping the offset lets `factor` be large enough to remove
+    /// the shadow acne on the other polygons.
+    ///
+    /// Requires OpenGL 4.6 or `GL_ARB_polygon_offset_clamp`, see
+    /// `is_polygon_offset_clamp_supported`. Using a value other than `0.0` otherwise returns a
+    /// `PolygonOffsetNotSupported` error.
+    pub clamp: f32,
     /// If true, the depth offset is enabled for points
     pub point: bool,
     /// If true, the depth offset is enabled for lines
@@ -466,6 +765,7 @@ impl Default for PolygonOffset {
         PolygonOffset{
             factor: 0.0,
             units: 0.0,
+            clamp: 0.0,
             point: false,
             line: false,
             fill: false
@@ -473,8 +773,70 @@ impl Default for PolygonOffset {
     }
 }
 
+impl Eq for PolygonOffset {}
+
+impl Hash for PolygonOffset {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.factor, state);
+        hash_f32(self.units, state);
+        hash_f32(self.clamp, state);
+        self.point.hash(state);
+        self.line.hash(state);
+        self.fill.hash(state);
+    }
+}
+
+/// Returns true if the backend supports `PolygonOffset::clamp`.
+#[inline]
+pub fn is_polygon_offset_clamp_supported<C>(ctxt: &C) -> bool
+    where C: ?Sized + CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 4, 6) ||
+        ctxt.get_extensions().gl_arb_polygon_offset_clamp
+}
+
+/// Returns true if the backend supports drawing with more than one entry in
+/// `DrawParameters::viewports` or `DrawParameters::scissors`.
+#[inline]
+pub fn is_viewport_array_supported<C>(ctxt: &C) -> bool
+    where C: ?Sized + CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 4, 1) ||
+        ctxt.get_extensions().gl_arb_viewport_array
+}
+
+/// Returns true if the backend takes `DrawParameters::primitive_bounding_box` into account.
+#[inline]
+pub fn is_primitive_bounding_box_supported<C>(ctxt: &C) -> bool
+    where C: ?Sized + CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::GlEs, 3, 2) ||
+        ctxt.get_extensions().gl_arb_es3_2_compatibility ||
+        ctxt.get_extensions().gl_oes_primitive_bounding_box ||
+        ctxt.get_extensions().gl_ext_primitive_bounding_box
+}
+
+/// Returns true if the backend supports setting `DrawParameters::draw_primitives` to `false`.
+#[inline]
+pub fn is_rasterizer_discard_supported<C>(ctxt: &C) -> bool
+    where C: ?Sized + CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 3, 0) ||
+        ctxt.get_version() >= &Version(Api::GlEs, 3, 0) ||
+        ctxt.get_extensions().gl_ext_transform_feedback
+}
+
+/// Returns true if the backend supports values other than the defaults for
+/// `DrawParameters::clip_control_origin` and `DrawParameters::clip_control_depth`.
+#[inline]
+pub fn is_clip_control_supported<C>(ctxt: &C) -> bool
+    where C: ?Sized + CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 4, 5) || ctxt.get_extensions().gl_arb_clip_control
+}
+
 /// Specifies the clip control origin.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ClipControlOrigin {
     /// The clip space origin is at the lower-left corner of the viewport. This is the default state.
     LowerLeft,
@@ -484,15 +846,50 @@ pub enum ClipControlOrigin {
 }
 
 /// Specifies the clip control depth mode.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ClipControlDepth {
     /// The near and far clipping planes correspond to Z coordinates of -1 and +1. This is the default state.
     NegativeOneToOne,
 
     /// The near and far clipping planes correspond to Z coordinates of 0 and +1. This may improve numerical precision of depth mapping.
+    ///
+    /// This is the mode to use for reversed-Z rendering.
     ZeroToOne,
 }
 
+/// Default tessellation levels used by the tessellation primitive generator when there is no
+/// tessellation control shader.
+///
+/// See `https://www.khronos.org/opengl/wiki/Tessellation#Tessellation_levels`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TessellationLevels {
+    /// The outer tessellation levels, corresponding to `gl_TessLevelOuter`.
+    pub outer: [f32; 4],
+
+    /// The inner tessellation levels, corresponding to `gl_TessLevelInner`.
+    pub inner: [f32; 2],
+}
+
+impl Default for TessellationLevels {
+    #[inline]
+    fn default() -> TessellationLevels {
+        TessellationLevels {
+            outer: [1.0; 4],
+            inner: [1.0; 2],
+        }
+    }
+}
+
+impl Eq for TessellationLevels {}
+
+impl Hash for TessellationLevels {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for &value in self.outer.iter().chain(self.inner.iter()) {
+            hash_f32(value, state);
+        }
+    }
+}
+
 impl<'a> Default for DrawParameters<'a> {
     fn default() -> DrawParameters<'a> {
         DrawParameters {
@@ -500,15 +897,22 @@ impl<'a> Default for DrawParameters<'a> {
             stencil: Default::default(),
             blend: Default::default(),
             color_mask: (true, true, true, true),
+            attachment_blend: Vec::new(),
+            color_logic_op: None,
+            attachment_color_mask: Vec::new(),
             line_width: None,
             point_size: None,
             backface_culling: BackfaceCullingMode::CullingDisabled,
             polygon_mode: PolygonMode::Fill,
             clip_planes_bitmask: 0,
             multisampling: true,
+            multisampling_alpha_to_coverage: false,
+            multisampling_alpha_to_one: false,
             dithering: true,
             viewport: None,
             scissor: None,
+            viewports: Vec::new(),
+            scissors: Vec::new(),
             draw_primitives: true,
             samples_passed_query: None,
             time_elapsed_query: None,
@@ -523,7 +927,149 @@ impl<'a> Default for DrawParameters<'a> {
             polygon_offset: Default::default(),
             clip_control_origin: ClipControlOrigin::LowerLeft,
             clip_control_depth: ClipControlDepth::NegativeOneToOne,
+            default_tessellation_levels: Default::default(),
+            check_hazards: false,
+            auto_texture_barrier: false,
+            framebuffer_srgb: None,
+        }
+    }
+}
+
+impl<'a> PartialEq for DrawParameters<'a> {
+    fn eq(&self, other: &DrawParameters<'_>) -> bool {
+        // destructuring makes sure that new fields are not forgotten
+        let DrawParameters {
+            depth, stencil, blend, color_mask, attachment_blend, color_logic_op, attachment_color_mask, line_width, point_size, clip_planes_bitmask,
+            backface_culling, polygon_mode, multisampling, multisampling_alpha_to_coverage,
+            multisampling_alpha_to_one, dithering, viewport, scissor, viewports, scissors,
+            draw_primitives, samples_passed_query, time_elapsed_query,
+            primitives_generated_query, transform_feedback_primitives_written_query, condition,
+            transform_feedback, smooth, provoking_vertex, primitive_bounding_box,
+            primitive_restart_index, polygon_offset, clip_control_origin, clip_control_depth,
+            default_tessellation_levels, check_hazards, auto_texture_barrier, framebuffer_srgb,
+        } = self;
+
+        *depth == other.depth && *stencil == other.stencil && *blend == other.blend &&
+        *color_mask == other.color_mask && *attachment_blend == other.attachment_blend &&
+        *color_logic_op == other.color_logic_op &&
+        *attachment_color_mask == other.attachment_color_mask &&
+        *line_width == other.line_width &&
+        *point_size == other.point_size && *clip_planes_bitmask == other.clip_planes_bitmask &&
+        *backface_culling == other.backface_culling && *polygon_mode == other.polygon_mode &&
+        *multisampling == other.multisampling &&
+        *multisampling_alpha_to_coverage == other.multisampling_alpha_to_coverage &&
+        *multisampling_alpha_to_one == other.multisampling_alpha_to_one &&
+        *dithering == other.dithering &&
+        *viewport == other.viewport && *scissor == other.scissor &&
+        *viewports == other.viewports && *scissors == other.scissors &&
+        *draw_primitives == other.draw_primitives &&
+        *samples_passed_query == other.samples_passed_query &&
+        same_object(*time_elapsed_query, other.time_elapsed_query) &&
+        same_object(*primitives_generated_query, other.primitives_generated_query) &&
+        same_object(*transform_feedback_primitives_written_query,
+                    other.transform_feedback_primitives_written_query) &&
+        *condition == other.condition &&
+        same_object(*transform_feedback, other.transform_feedback) &&
+        *smooth == other.smooth && *provoking_vertex == other.provoking_vertex &&
+        *primitive_bounding_box == other.primitive_bounding_box &&
+        *primitive_restart_index == other.primitive_restart_index &&
+        *polygon_offset == other.polygon_offset &&
+        *clip_control_origin == other.clip_control_origin &&
+        *clip_control_depth == other.clip_control_depth &&
+        *default_tessellation_levels == other.default_tessellation_levels &&
+        *check_hazards == other.check_hazards &&
+        *auto_texture_barrier == other.auto_texture_barrier &&
+        *framebuffer_srgb == other.framebuffer_srgb
+    }
+}
+
+impl<'a> Eq for DrawParameters<'a> {}
+
+impl<'a> Hash for DrawParameters<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let DrawParameters {
+            depth, stencil, blend, color_mask, attachment_blend, color_logic_op, attachment_color_mask, line_width, point_size, clip_planes_bitmask,
+            backface_culling, polygon_mode, multisampling, multisampling_alpha_to_coverage,
+            multisampling_alpha_to_one, dithering, viewport, scissor, viewports, scissors,
+            draw_primitives, samples_passed_query, time_elapsed_query,
+            primitives_generated_query, transform_feedback_primitives_written_query, condition,
+            transform_feedback, smooth, provoking_vertex, primitive_bounding_box,
+            primitive_restart_index, polygon_offset, clip_control_origin, clip_control_depth,
+            default_tessellation_levels, check_hazards, auto_texture_barrier, framebuffer_srgb,
+        } = self;
+
+        depth.hash(state);
+        stencil.hash(state);
+        blend.hash(state);
+        color_mask.hash(state);
+        attachment_blend.hash(state);
+        color_logic_op.hash(state);
+        attachment_color_mask.hash(state);
+        hash_option_f32(*line_width, state);
+        hash_option_f32(*point_size, state);
+        clip_planes_bitmask.hash(state);
+        backface_culling.hash(state);
+        polygon_mode.hash(state);
+        multisampling.hash(state);
+        multisampling_alpha_to_coverage.hash(state);
+        multisampling_alpha_to_one.hash(state);
+        dithering.hash(state);
+        viewport.hash(state);
+        scissor.hash(state);
+        viewports.hash(state);
+        scissors.hash(state);
+        draw_primitives.hash(state);
+        samples_passed_query.hash(state);
+        time_elapsed_query.map(|q| q as *const TimeElapsedQuery).hash(state);
+        primitives_generated_query.map(|q| q as *const PrimitivesGeneratedQuery).hash(state);
+        transform_feedback_primitives_written_query
+            .map(|q| q as *const TransformFeedbackPrimitivesWrittenQuery).hash(state);
+        condition.hash(state);
+        transform_feedback.map(|s| s as *const TransformFeedbackSession<'_>).hash(state);
+        smooth.hash(state);
+        provoking_vertex.hash(state);
+        let (x, y, z, w) = primitive_bounding_box;
+        for range in [x, y, z, w] {
+            hash_f32(range.start, state);
+            hash_f32(range.end, state);
         }
+        primitive_restart_index.hash(state);
+        polygon_offset.hash(state);
+        clip_control_origin.hash(state);
+        clip_control_depth.hash(state);
+        default_tessellation_levels.hash(state);
+        check_hazards.hash(state);
+        auto_texture_barrier.hash(state);
+        framebuffer_srgb.hash(state);
+    }
+}
+
+/// Returns true if both options are `None` or point to the same object.
+#[inline]
+fn same_object<T: ?Sized>(a: Option<&T>, b: Option<&T>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => ptr::eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Hashes a float so that two values that compare equal produce the same hash.
+#[inline]
+fn hash_f32<H: Hasher>(value: f32, state: &mut H) {
+    // `0.0 == -0.0`, but their bits are different
+    if value == 0.0 {
+        0u32.hash(state);
+    } else {
+        value.to_bits().hash(state);
+    }
+}
+
+#[inline]
+fn hash_option_f32<H: Hasher>(value: Option<f32>, state: &mut H) {
+    value.is_some().hash(state);
+    if let Some(value) = value {
+        hash_f32(value, state);
     }
 }
 
@@ -550,16 +1096,28 @@ pub fn sync(ctxt: &mut context::CommandContext<'_>, draw_parameters: &DrawParame
 {
     depth::sync_depth(ctxt, &draw_parameters.depth)?;
     stencil::sync_stencil(ctxt, &draw_parameters.stencil);
-    blend::sync_blending(ctxt, draw_parameters.blend)?;
-    sync_color_mask(ctxt, draw_parameters.color_mask);
+    sync_blending_and_color_mask(ctxt, draw_parameters)?;
+    sync_logic_op(ctxt, draw_parameters)?;
     sync_line_width(ctxt, draw_parameters.line_width);
     sync_point_size(ctxt, draw_parameters.point_size);
     sync_polygon_mode(ctxt, draw_parameters.backface_culling, draw_parameters.polygon_mode);
     sync_clip_planes_bitmask(ctxt, draw_parameters.clip_planes_bitmask)?;
-    sync_multisampling(ctxt, draw_parameters.multisampling);
+    sync_multisampling(ctxt, draw_parameters.multisampling,
+                       draw_parameters.multisampling_alpha_to_coverage,
+                       draw_parameters.multisampling_alpha_to_one)?;
     sync_dithering(ctxt, draw_parameters.dithering);
-    sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.scissor,
-                          dimensions);
+    if draw_parameters.viewports.len() <= 1 && draw_parameters.scissors.len() <= 1 {
+        sync_viewport_scissor(ctxt,
+                              draw_parameters.viewports.first().cloned()
+                                             .or(draw_parameters.viewport),
+                              draw_parameters.scissors.first().cloned()
+                                             .or(draw_parameters.scissor),
+                              dimensions);
+    } else {
+        sync_viewport_scissor_arrays(ctxt, &draw_parameters.viewports,
+                                     &draw_parameters.scissors, draw_parameters.viewport,
+                                     draw_parameters.scissor, dimensions)?;
+    }
     sync_rasterizer_discard(ctxt, draw_parameters.draw_primitives)?;
     sync_queries(ctxt, draw_parameters.samples_passed_query,
                       draw_parameters.time_elapsed_query,
@@ -570,9 +1128,106 @@ pub fn sync(ctxt: &mut context::CommandContext<'_>, draw_parameters: &DrawParame
     sync_provoking_vertex(ctxt, draw_parameters.provoking_vertex)?;
     sync_primitive_bounding_box(ctxt, &draw_parameters.primitive_bounding_box);
     sync_primitive_restart_index(ctxt, draw_parameters.primitive_restart_index)?;
-    sync_polygon_offset(ctxt, draw_parameters.polygon_offset);
+    sync_polygon_offset(ctxt, draw_parameters.polygon_offset)?;
     sync_clip_control(ctxt, draw_parameters.clip_control_origin,
                       draw_parameters.clip_control_depth)?;
+    sync_default_tessellation_levels(ctxt, draw_parameters.default_tessellation_levels,
+                                     primitives_types)?;
+    sync_framebuffer_srgb(ctxt, draw_parameters.framebuffer_srgb)?;
+
+    Ok(())
+}
+
+fn sync_blending_and_color_mask(ctxt: &mut context::CommandContext<'_>,
+                                draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError>
+{
+    // the state of all the draw buffers is set again if some of them were set individually
+    if ctxt.state.attachment_blend {
+        blend::invalidate_blending(ctxt);
+        ctxt.state.attachment_blend = false;
+    }
+    if ctxt.state.attachment_color_mask {
+        let (r, g, b, a) = ctxt.state.color_mask;
+        unsafe { ctxt.gl.ColorMask(r, g, b, a); }
+        ctxt.state.attachment_color_mask = false;
+    }
+
+    blend::sync_blending(ctxt, draw_parameters.blend)?;
+    sync_color_mask(ctxt, draw_parameters.color_mask);
+
+    let max_draw_buffers = ctxt.capabilities.max_draw_buffers.max(1) as usize;
+
+    for (index, &attachment_blend) in draw_parameters.attachment_blend.iter()
+                                                     .enumerate().take(max_draw_buffers)
+    {
+        if attachment_blend != draw_parameters.blend {
+            blend::sync_attachment_blending(ctxt, index as u32, attachment_blend)?;
+            ctxt.state.attachment_blend = true;
+        }
+    }
+
+    for (index, &mask) in draw_parameters.attachment_color_mask.iter()
+                                         .enumerate().take(max_draw_buffers)
+    {
+        if mask != draw_parameters.color_mask {
+            let index = index as gl::types::GLuint;
+            let (r, g, b, a) = (mask.0 as gl::types::GLboolean, mask.1 as gl::types::GLboolean,
+                                mask.2 as gl::types::GLboolean, mask.3 as gl::types::GLboolean);
+
+            unsafe {
+                if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                   ctxt.version >= &Version(Api::GlEs, 3, 2)
+                {
+                    ctxt.gl.ColorMaski(index, r, g, b, a);
+                } else if ctxt.extensions.gl_ext_draw_buffers_indexed {
+                    ctxt.gl.ColorMaskiEXT(index, r, g, b, a);
+                } else if ctxt.extensions.gl_oes_draw_buffers_indexed {
+                    ctxt.gl.ColorMaskiOES(index, r, g, b, a);
+                } else {
+                    return Err(DrawError::AttachmentBlendingNotSupported);
+                }
+            }
+            ctxt.state.attachment_color_mask = true;
+        }
+    }
+
+    Ok(())
+}
+
+/// Must be called after the blending has been synchronized.
+fn sync_logic_op(ctxt: &mut context::CommandContext<'_>, draw_parameters: &DrawParameters<'_>)
+                 -> Result<(), DrawError>
+{
+    let logic_op = match draw_parameters.color_logic_op {
+        Some(logic_op) => logic_op,
+        None => {
+            if ctxt.state.enabled_color_logic_op {
+                unsafe { ctxt.gl.Disable(gl::COLOR_LOGIC_OP); }
+                ctxt.state.enabled_color_logic_op = false;
+            }
+            return Ok(());
+        },
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 1, 1)) {
+        return Err(DrawError::LogicOpNotSupported);
+    }
+
+    // the logic operation silently replaces blending
+    if ctxt.state.enabled_blend || ctxt.state.attachment_blend {
+        return Err(DrawError::LogicOpWithBlending);
+    }
+
+    if !ctxt.state.enabled_color_logic_op {
+        unsafe { ctxt.gl.Enable(gl::COLOR_LOGIC_OP); }
+        ctxt.state.enabled_color_logic_op = true;
+    }
+
+    let logic_op = logic_op.to_glenum();
+    if ctxt.state.logic_op != logic_op {
+        unsafe { ctxt.gl.LogicOp(logic_op); }
+        ctxt.state.logic_op = logic_op;
+    }
 
     Ok(())
 }
@@ -663,33 +1318,38 @@ fn sync_polygon_mode(ctxt: &mut context::CommandContext<'_>, backface_culling: B
 
 fn sync_clip_planes_bitmask(ctxt: &mut context::CommandContext<'_>, clip_planes_bitmask: u32)
                             -> Result<(), DrawError> {
-    // Not supported on GLES
-    if !(ctxt.version >= &Version(Api::Gl, 1, 0)) {
+    let max_clip_planes = ctxt.capabilities.max_clip_distances.max(0) as u32;
+
+    if max_clip_planes < 32 && clip_planes_bitmask >> max_clip_planes != 0 {
+        return Err(DrawError::ClipPlaneIndexOutOfBounds);
+    }
+
+    let changed = clip_planes_bitmask ^ ctxt.state.enabled_clip_planes;
+    if changed == 0 {
         return Ok(());
     }
+
     unsafe {
-        let mut max_clip_planes: gl::types::GLint = 0;
-        ctxt.gl.GetIntegerv(gl::MAX_CLIP_DISTANCES, &mut max_clip_planes);
-        for i in 0..32 {
-            if clip_planes_bitmask & (1 << i) != ctxt.state.enabled_clip_planes & (1 << i) {
-                if clip_planes_bitmask & (1 << i) != 0 {
-                    if i < max_clip_planes {
-                        ctxt.gl.Enable(gl::CLIP_DISTANCE0 + i as u32);
-                        ctxt.state.enabled_clip_planes |= 1 << i;
-                    } else {
-                        return Err(DrawError::ClipPlaneIndexOutOfBounds);
-                    }
-                } else if i < max_clip_planes {
-                    ctxt.gl.Disable(gl::CLIP_DISTANCE0 + i as u32);
-                    ctxt.state.enabled_clip_planes &= !(1 << i);
-                }
+        for i in 0..max_clip_planes.min(32) {
+            if changed & (1 << i) == 0 {
+                continue;
+            }
+
+            if clip_planes_bitmask & (1 << i) != 0 {
+                ctxt.gl.Enable(gl::CLIP_DISTANCE0 + i);
+            } else {
+                ctxt.gl.Disable(gl::CLIP_DISTANCE0 + i);
             }
         }
-        Ok(())
     }
+
+    ctxt.state.enabled_clip_planes = clip_planes_bitmask;
+    Ok(())
 }
 
-fn sync_multisampling(ctxt: &mut context::CommandContext<'_>, multisampling: bool) {
+fn sync_multisampling(ctxt: &mut context::CommandContext<'_>, multisampling: bool,
+                      alpha_to_coverage: bool, alpha_to_one: bool) -> Result<(), DrawError>
+{
     if ctxt.state.enabled_multisample != multisampling {
         unsafe {
             if multisampling {
@@ -701,6 +1361,35 @@ fn sync_multisampling(ctxt: &mut context::CommandContext<'_>, multisampling: boo
             }
         }
     }
+
+    if ctxt.state.enabled_sample_alpha_to_coverage != alpha_to_coverage {
+        unsafe {
+            if alpha_to_coverage {
+                ctxt.gl.Enable(gl::SAMPLE_ALPHA_TO_COVERAGE);
+            } else {
+                ctxt.gl.Disable(gl::SAMPLE_ALPHA_TO_COVERAGE);
+            }
+        }
+        ctxt.state.enabled_sample_alpha_to_coverage = alpha_to_coverage;
+    }
+
+    if ctxt.state.enabled_sample_alpha_to_one != alpha_to_one {
+        // OpenGL ES doesn't have `GL_SAMPLE_ALPHA_TO_ONE`
+        if !(ctxt.version >= &Version(Api::Gl, 1, 3)) {
+            return Err(DrawError::AlphaToOneNotSupported);
+        }
+
+        unsafe {
+            if alpha_to_one {
+                ctxt.gl.Enable(gl::SAMPLE_ALPHA_TO_ONE);
+            } else {
+                ctxt.gl.Disable(gl::SAMPLE_ALPHA_TO_ONE);
+            }
+        }
+        ctxt.state.enabled_sample_alpha_to_one = alpha_to_one;
+    }
+
+    Ok(())
 }
 
 fn sync_dithering(ctxt: &mut context::CommandContext<'_>, dithering: bool) {
@@ -734,6 +1423,7 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Optio
         if ctxt.state.viewport != Some(viewport) {
             unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
             ctxt.state.viewport = Some(viewport);
+            ctxt.state.viewports.clear();
         }
 
     } else {
@@ -748,6 +1438,7 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Optio
         if ctxt.state.viewport != Some(viewport) {
             unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
             ctxt.state.viewport = Some(viewport);
+            ctxt.state.viewports.clear();
         }
     }
 
@@ -761,6 +1452,7 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Optio
             if ctxt.state.scissor != Some(scissor) {
                 ctxt.gl.Scissor(scissor.0, scissor.1, scissor.2, scissor.3);
                 ctxt.state.scissor = Some(scissor);
+                ctxt.state.scissors.clear();
             }
 
             if !ctxt.state.enabled_scissor_test {
@@ -778,11 +1470,96 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Optio
     }
 }
 
+fn sync_viewport_scissor_arrays(ctxt: &mut context::CommandContext<'_>, viewports: &[Rect],
+                                scissors: &[Rect], viewport: Option<Rect>,
+                                scissor: Option<Rect>, surface_dimensions: (u32, u32))
+                                -> Result<(), DrawError>
+{
+    let max_viewports = ctxt.capabilities.max_viewports as usize;
+    if !is_viewport_array_supported(ctxt) || viewports.len() > max_viewports ||
+        scissors.len() > max_viewports
+    {
+        return Err(DrawError::TooManyViewports);
+    }
+
+    let full_surface = Rect {
+        left: 0,
+        bottom: 0,
+        width: surface_dimensions.0,
+        height: surface_dimensions.1,
+    };
+
+    // viewports
+    let viewports: Vec<[gl::types::GLfloat; 4]> = if viewports.is_empty() {
+        vec![viewport.unwrap_or(full_surface)]
+    } else {
+        viewports.to_vec()
+    }.into_iter().map(|rect| {
+        if rect.width > ctxt.capabilities.max_viewport_dims.0 as u32 ||
+            rect.height > ctxt.capabilities.max_viewport_dims.1 as u32
+        {
+            return Err(DrawError::ViewportTooLarge);
+        }
+
+        Ok([rect.left as gl::types::GLfloat, rect.bottom as gl::types::GLfloat,
+            rect.width as gl::types::GLfloat, rect.height as gl::types::GLfloat])
+    }).collect::<Result<_, _>>()?;
+
+    if ctxt.state.viewports != viewports {
+        unsafe {
+            ctxt.gl.ViewportArrayv(0, viewports.len() as gl::types::GLsizei,
+                                   viewports.as_ptr() as *const _);
+        }
+        ctxt.state.viewport = None;
+        ctxt.state.viewports = viewports;
+    }
+
+    // scissor boxes
+    let scissors: Vec<Rect> = if !scissors.is_empty() {
+        scissors.to_vec()
+    } else if let Some(scissor) = scissor {
+        vec![scissor]
+    } else {
+        unsafe {
+            if ctxt.state.enabled_scissor_test {
+                ctxt.gl.Disable(gl::SCISSOR_TEST);
+                ctxt.state.enabled_scissor_test = false;
+            }
+        }
+        return Ok(());
+    };
+
+    // viewport indices without a scissor box get one that covers the whole surface
+    let num_boxes = scissors.len().max(ctxt.state.viewports.len());
+    let scissors: Vec<[gl::types::GLint; 4]> = scissors.into_iter()
+        .chain(std::iter::repeat(full_surface))
+        .take(num_boxes)
+        .map(|rect| [rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
+                     rect.width as gl::types::GLint, rect.height as gl::types::GLint])
+        .collect();
+
+    unsafe {
+        if ctxt.state.scissors != scissors {
+            ctxt.gl.ScissorArrayv(0, scissors.len() as gl::types::GLsizei,
+                                  scissors.as_ptr() as *const _);
+            ctxt.state.scissor = None;
+            ctxt.state.scissors = scissors;
+        }
+
+        if !ctxt.state.enabled_scissor_test {
+            ctxt.gl.Enable(gl::SCISSOR_TEST);
+            ctxt.state.enabled_scissor_test = true;
+        }
+    }
+
+    Ok(())
+}
+
 fn sync_rasterizer_discard(ctxt: &mut context::CommandContext<'_>, draw_primitives: bool)
                            -> Result<(), DrawError>
 {
     if ctxt.state.enabled_rasterizer_discard == draw_primitives {
-        if ctxt.version >= &Version(Api::Gl, 3, 0) {
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
             if draw_primitives {
                 unsafe { ctxt.gl.Disable(gl::RASTERIZER_DISCARD); }
                 ctxt.state.enabled_rasterizer_discard = false;
@@ -816,11 +1593,20 @@ fn sync_queries(ctxt: &mut context::CommandContext<'_>,
                                             Option<&TransformFeedbackPrimitivesWrittenQuery>)
                 -> Result<(), DrawError>
 {
+    // inside a `scope`, the scoped query is the only samples query that can be active
+    let scope = ctxt.state.samples_query_scope;
+
     if let Some(SamplesQueryParam::SamplesPassedQuery(q)) = samples_passed_query {
+        if scope != 0 && q.get_id() != scope {
+            return Err(DrawError::WrongQueryOperation);
+        }
         q.begin_query(ctxt)?;
     } else if let Some(SamplesQueryParam::AnySamplesPassedQuery(q)) = samples_passed_query {
+        if scope != 0 && q.get_id() != scope {
+            return Err(DrawError::WrongQueryOperation);
+        }
         q.begin_query(ctxt)?;
-    } else {
+    } else if scope == 0 {
         TimeElapsedQuery::end_samples_passed_query(ctxt);
     }
 
@@ -1030,14 +1816,28 @@ fn set_flag_enabled(ctxt: &mut context::CommandContext<'_>, cap: gl::types::GLen
     }
 }
 
-fn sync_polygon_offset(ctxt: &mut context::CommandContext<'_>, offset: PolygonOffset) {
-    let (factor, units) = ctxt.state.polygon_offset;
+fn sync_polygon_offset(ctxt: &mut context::CommandContext<'_>, offset: PolygonOffset)
+                       -> Result<(), DrawError>
+{
+    if (offset.point || offset.line) && !(ctxt.version >= &Version(Api::Gl, 1, 1)) {
+        return Err(DrawError::PolygonOffsetNotSupported);
+    }
 
-    if ctxt.state.polygon_offset != (offset.factor, offset.units) {
-        unsafe {
-            ctxt.gl.PolygonOffset(offset.factor, offset.units);
+    if ctxt.state.polygon_offset != (offset.factor, offset.units, offset.clamp) {
+        if offset.clamp != 0.0 {
+            if !is_polygon_offset_clamp_supported(ctxt) {
+                return Err(DrawError::PolygonOffsetNotSupported);
+            }
+
+            unsafe {
+                ctxt.gl.PolygonOffsetClamp(offset.factor, offset.units, offset.clamp);
+            }
+        } else {
+            unsafe {
+                ctxt.gl.PolygonOffset(offset.factor, offset.units);
+            }
         }
-        ctxt.state.polygon_offset = (offset.factor, offset.units);
+        ctxt.state.polygon_offset = (offset.factor, offset.units, offset.clamp);
     }
 
     if offset.point != ctxt.state.enabled_polygon_offset_point {
@@ -1054,6 +1854,8 @@ fn sync_polygon_offset(ctxt: &mut context::CommandContext<'_>, offset: PolygonOf
         ctxt.state.enabled_polygon_offset_fill = offset.fill;
         set_flag_enabled(ctxt, gl::POLYGON_OFFSET_FILL, offset.fill);
     }
+
+    Ok(())
 }
 
 fn sync_clip_control(ctxt: &mut context::CommandContext<'_>,
@@ -1073,7 +1875,7 @@ fn sync_clip_control(ctxt: &mut context::CommandContext<'_>,
         return Ok(());
     }
 
-    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_clip_control {
+    if is_clip_control_supported(ctxt) {
         unsafe { ctxt.gl.ClipControl(origin, depth); }
         ctxt.state.clip_control = (origin, depth);
     } else {
@@ -1082,3 +1884,69 @@ fn sync_clip_control(ctxt: &mut context::CommandContext<'_>,
 
     Ok(())
 }
+
+/// Must be called after the program has been bound, as it overrides the state set by the program.
+fn sync_framebuffer_srgb(ctxt: &mut context::CommandContext<'_>, enable: Option<bool>)
+                         -> Result<(), DrawError>
+{
+    let enable = match enable {
+        Some(enable) => enable,
+        None => return Ok(()),
+    };
+
+    if ctxt.state.enabled_framebuffer_srgb == enable {
+        return Ok(());
+    }
+
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+         ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control)
+    {
+        return Err(DrawError::FramebufferSrgbNotSupported);
+    }
+
+    unsafe {
+        if enable {
+            ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
+        } else {
+            ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
+        }
+    }
+    ctxt.state.enabled_framebuffer_srgb = enable;
+
+    Ok(())
+}
+
+fn sync_default_tessellation_levels(ctxt: &mut context::CommandContext<'_>,
+                                    levels: TessellationLevels,
+                                    primitive_type: PrimitiveType)
+                                    -> Result<(), DrawError>
+{
+    // the default levels are only used by the primitive generator
+    if !matches!(primitive_type, PrimitiveType::Patches { .. }) {
+        return Ok(());
+    }
+
+    if ctxt.state.patch_default_outer_level == levels.outer &&
+       ctxt.state.patch_default_inner_level == levels.inner
+    {
+        return Ok(());
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.extensions.gl_arb_tessellation_shader {
+        unsafe {
+            if ctxt.state.patch_default_outer_level != levels.outer {
+                ctxt.gl.PatchParameterfv(gl::PATCH_DEFAULT_OUTER_LEVEL, levels.outer.as_ptr());
+                ctxt.state.patch_default_outer_level = levels.outer;
+            }
+
+            if ctxt.state.patch_default_inner_level != levels.inner {
+                ctxt.gl.PatchParameterfv(gl::PATCH_DEFAULT_INNER_LEVEL, levels.inner.as_ptr());
+                ctxt.state.patch_default_inner_level = levels.inner;
+            }
+        }
+    } else {
+        return Err(DrawError::DefaultTessellationLevelsNotSupported);
+    }
+
+    Ok(())
+}
@@ -192,6 +192,25 @@ impl RawQuery {
         })
     }
 
+    /// Attaches a name to the query object, which OpenGL debuggers display instead of its id.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`, or if the query object
+    /// doesn't exist yet. Unless direct state access is available, names returned by
+    /// `glGenQueries` only become objects the first time the query is used.
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        let created = {
+            let ctxt = self.context.make_current();
+            ctxt.version >= &Version(Api::Gl, 4, 5) ||
+            (ctxt.version >= &Version(Api::Gl, 3, 3) && ctxt.extensions.gl_arb_direct_state_access)
+        };
+
+        if !created && !self.has_been_used.get() {
+            return Err(());
+        }
+
+        self.context.set_object_label(gl::QUERY, self.id, label)
+    }
+
     /// Queries the counter to see if the result is already available.
     pub fn is_ready(&self) -> bool {
         let mut ctxt = self.context.make_current();
@@ -251,16 +270,14 @@ impl RawQuery {
     pub fn write_u32_to_buffer(&self, target: BufferSlice<'_, u32>) -> Result<(), ToBufferError> {
         let mut ctxt = self.context.make_current();
 
-        if !(ctxt.version >= &Version(Api::Gl, 4, 4) || ctxt.extensions.gl_arb_query_buffer_object ||
-             ctxt.extensions.gl_amd_query_buffer_object)
-        {
+        if !is_query_buffer_supported(&ctxt) {
             return Err(ToBufferError::NotSupported);
         }
 
         self.deactivate(&mut ctxt);
 
         if !self.has_been_used.get() {
-            panic!();
+            panic!("The query must have been used before writing its result to a buffer");
         }
 
         assert!(target.get_offset_bytes() % 4 == 0);
@@ -275,6 +292,35 @@ impl RawQuery {
         Ok(())
     }
 
+    /// Writes the value of the query to a buffer as a 64-bit integer.
+    pub fn write_u64_to_buffer(&self, target: BufferSlice<'_, u64>) -> Result<(), ToBufferError> {
+        let mut ctxt = self.context.make_current();
+
+        // `glGetQueryObjectui64v` is only available starting from OpenGL 3.3
+        if !is_query_buffer_supported(&ctxt) || !(ctxt.version >= &Version(Api::Gl, 3, 3)) {
+            return Err(ToBufferError::NotSupported);
+        }
+
+        self.deactivate(&mut ctxt);
+
+        if !self.has_been_used.get() {
+            panic!("The query must have been used before writing its result to a buffer");
+        }
+
+        assert_eq!(target.get_offset_bytes() % 8, 0);
+
+        target.prepare_and_bind_for_query(&mut ctxt);
+        unsafe {
+            self.raw_get_u64(&mut ctxt, target.get_offset_bytes() as *mut _).unwrap();
+        }
+
+        if let Some(fence) = target.add_fence() {
+            fence.insert(&mut ctxt);
+        }
+
+        Ok(())
+    }
+
     unsafe fn raw_get_u32(&self, ctxt: &mut CommandContext<'_>, target: *mut gl::types::GLuint) {
         if ctxt.version >= &Version(Api::Gl, 1, 5) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
             ctxt.gl.GetQueryObjectuiv(self.id, gl::QUERY_RESULT, target);
@@ -337,6 +383,34 @@ impl RawQuery {
         self.get_u32() != 0
     }
 
+    /// Makes this samples query active, calls `f`, then ends the query. Draw commands executed
+    /// by `f` that don't specify a samples query of their own are counted by this query.
+    fn samples_scope<R, F>(&self, f: F) -> Result<R, DrawError> where F: FnOnce() -> R {
+        // ends the query even if `f` panics
+        struct ScopeGuard<'a>(&'a Context);
+
+        impl Drop for ScopeGuard<'_> {
+            fn drop(&mut self) {
+                let mut ctxt = self.0.make_current();
+                ctxt.state.samples_query_scope = 0;
+                RawQuery::end_samples_passed_query(&mut ctxt);
+            }
+        }
+
+        {
+            let mut ctxt = self.context.make_current();
+            if ctxt.state.samples_query_scope != 0 {
+                return Err(DrawError::WrongQueryOperation);
+            }
+
+            self.begin_query(&mut ctxt)?;
+            ctxt.state.samples_query_scope = self.id;
+        }
+
+        let _guard = ScopeGuard(&self.context);
+        Ok(f())
+    }
+
     /// If the query is active, unactivates it.
     fn deactivate(&self, ctxt: &mut CommandContext<'_>) {
         if ctxt.state.samples_passed_query == self.id {
@@ -714,6 +788,13 @@ unsafe fn raw_end_query(ctxt: &mut CommandContext<'_>, ty: gl::types::GLenum) {
     }
 }
 
+/// Returns true if the results of queries can be written to a buffer.
+#[inline]
+fn is_query_buffer_supported(ctxt: &CommandContext<'_>) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 4) || ctxt.extensions.gl_arb_query_buffer_object ||
+        ctxt.extensions.gl_amd_query_buffer_object
+}
+
 macro_rules! impl_helper {
     ($name:ident, $ret:ty, $get_fn:ident) => {
         impl $name {
@@ -742,13 +823,42 @@ macro_rules! impl_helper {
             /// This function doesn't block. Instead it submits a commands to the GPU's commands
             /// queue and orders the GPU to write the result of the query to a buffer.
             ///
-            /// This operation is not necessarily supported everywhere.
+            /// The target can be a field of a larger structure obtained with `slice_custom`.
+            /// For example writing to the `instance_count` of a `DrawCommandNoIndices` makes a
+            /// later indirect draw depend on the result without reading it back on the CPU.
+            ///
+            /// Requires OpenGL 4.4, `GL_ARB_query_buffer_object` or
+            /// `GL_AMD_query_buffer_object`. Returns `ToBufferError::NotSupported` otherwise.
+            ///
+            /// # Panic
+            ///
+            /// Panics if the query has never been used, or if the slice is not aligned on
+            /// four bytes.
             #[inline]
             pub fn to_buffer_u32(&self, target: BufferSlice<'_, u32>)
                                  -> Result<(), ToBufferError>
             {
                 self.query.write_u32_to_buffer(target)
             }
+
+            /// Same as `to_buffer_u32`, but writes the result as a 64-bit integer.
+            ///
+            /// Also requires OpenGL 3.3. The slice must be aligned on eight bytes.
+            #[inline]
+            pub fn to_buffer_u64(&self, target: BufferSlice<'_, u64>)
+                                 -> Result<(), ToBufferError>
+            {
+                self.query.write_u64_to_buffer(target)
+            }
+
+            /// Attaches a name to this query, which OpenGL debuggers display instead of its id.
+            ///
+            /// Returns `Err` if the backend doesn't support `GL_KHR_debug`. Without direct
+            /// state access this also fails until the query has been used once.
+            #[inline]
+            pub fn set_label(&self, label: &str) -> Result<(), ()> {
+                self.query.set_label(label)
+            }
         }
 
         impl GlObject for $name {
@@ -820,6 +930,19 @@ impl SamplesPassedQuery {
     pub fn new<F: ?Sized>(facade: &F) -> Result<SamplesPassedQuery, QueryCreationError> where F: Facade {
         RawQuery::new(facade, QueryType::SamplesPassed).map(|q| SamplesPassedQuery { query: q })
     }
+
+    /// Makes the query active while `f` runs.
+    ///
+    /// Every draw command executed inside of `f` whose `samples_passed_query` is `None` is
+    /// counted by this query, which lets you measure a group of draw calls without passing the
+    /// query to each of them.
+    ///
+    /// Returns a `WrongQueryOperation` error if the query has already been used, or if another
+    /// samples query scope is already running.
+    #[inline]
+    pub fn scope<R, F>(&self, f: F) -> Result<R, DrawError> where F: FnOnce() -> R {
+        self.query.samples_scope(f)
+    }
 }
 
 impl_helper!(SamplesPassedQuery, u32, get_u32);
@@ -882,6 +1005,24 @@ impl AnySamplesPassedQuery {
             Err(QueryCreationError::NotSupported)
         }
     }
+
+    /// Returns true if the query uses `GL_ANY_SAMPLES_PASSED_CONSERVATIVE`.
+    ///
+    /// This can be false even if you asked for a conservative query, if the backend doesn't
+    /// support them.
+    #[inline]
+    pub fn is_conservative(&self) -> bool {
+        matches!(self.query.ty, QueryType::AnySamplesPassedConservative)
+    }
+
+    /// Makes the query active while `f` runs.
+    ///
+    /// Every draw command executed inside of `f` whose `samples_passed_query` is `None` is
+    /// counted by this query. See `SamplesPassedQuery::scope`.
+    #[inline]
+    pub fn scope<R, F>(&self, f: F) -> Result<R, DrawError> where F: FnOnce() -> R {
+        self.query.samples_scope(f)
+    }
 }
 
 impl_helper!(AnySamplesPassedQuery, bool, get_bool);
@@ -0,0 +1,545 @@
+/*!
+Painting the output of egui.
+
+The `Painter` owns the textures that egui asks for, including its font atlas, and draws the
+tessellated shapes of a frame on any `Surface`. It is the glium counterpart of `egui_glow` and
+doesn't handle windows or input, which are left to an integration such as `egui-winit`.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::Surface;
+
+let egui_ctx = egui::Context::default();
+let mut painter = glium::egui::Painter::new(&display).unwrap();
+
+// every frame
+# let raw_input = egui::RawInput::default();
+let output = egui_ctx.run(raw_input, |ctx| {
+    egui::CentralPanel::default().show(ctx, |ui| ui.label("Hello world"));
+});
+let primitives = egui_ctx.tessellate(output.shapes, output.pixels_per_point);
+
+let mut target = display.draw();
+target.clear_color(0.1, 0.1, 0.1, 1.0);
+painter.paint_and_update_textures(&mut target, output.pixels_per_point, &primitives,
+                                  &output.textures_delta).unwrap();
+target.finish().unwrap();
+# }
+```
+
+egui blends in gamma space. Its textures are uploaded as `Texture2d`s whose texels are sRGB
+encoded, and the program is created with `outputs_srgb` so that glium doesn't enable
+`GL_FRAMEBUFFER_SRGB`: the colors written to the surface are the ones egui computed, whatever
+its format. `SrgbTexture2d`s registered with `register_native_texture` are decoded to linear when
+sampled, and encoded back before blending.
+
+Paint callbacks are ignored.
+
+Only available if the `egui` feature is enabled.
+
+*/
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use egui::epaint::{ClippedPrimitive, ImageData, ImageDelta, Mesh, Primitive};
+use egui::{TextureFilter, TextureId, TextureOptions, TextureWrapMode, TexturesDelta};
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::draw_parameters::{Blend, BlendingFunction, LinearBlendingFactor};
+use crate::index::{self, IndexBuffer, PrimitiveType};
+use crate::program::{Program, ProgramChooserCreationError};
+use crate::texture::{MipmapsOption, RawImage2d, SrgbTexture2d, Texture2d};
+use crate::texture::TextureCreationError;
+use crate::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, Sampler, SamplerBehavior};
+use crate::uniforms::SamplerWrapFunction;
+use crate::vertex::{self, VertexBuffer};
+use crate::{DrawError, DrawParameters, Rect, Surface};
+use crate::ContextExt;
+
+/// Error that can happen while painting.
+#[derive(Debug)]
+pub enum PaintError {
+    /// A vertex buffer couldn't be created.
+    VertexBufferCreation(vertex::BufferCreationError),
+
+    /// An index buffer couldn't be created.
+    IndexBufferCreation(index::BufferCreationError),
+
+    /// A texture couldn't be created.
+    TextureCreation(TextureCreationError),
+
+    /// A mesh couldn't be drawn.
+    Draw(DrawError),
+}
+
+impl fmt::Display for PaintError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        use self::PaintError::*;
+        match *self {
+            VertexBufferCreation(ref err) => write!(fmt, "Error creating a vertex buffer: {}", err),
+            IndexBufferCreation(ref err) => write!(fmt, "Error creating an index buffer: {}", err),
+            TextureCreation(ref err) => write!(fmt, "Error creating a texture: {}", err),
+            Draw(ref err) => write!(fmt, "Error drawing a mesh: {}", err),
+        }
+    }
+}
+
+impl Error for PaintError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::PaintError::*;
+        match *self {
+            VertexBufferCreation(ref err) => Some(err),
+            IndexBufferCreation(ref err) => Some(err),
+            TextureCreation(ref err) => Some(err),
+            Draw(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<vertex::BufferCreationError> for PaintError {
+    #[inline]
+    fn from(err: vertex::BufferCreationError) -> PaintError {
+        PaintError::VertexBufferCreation(err)
+    }
+}
+
+impl From<index::BufferCreationError> for PaintError {
+    #[inline]
+    fn from(err: index::BufferCreationError) -> PaintError {
+        PaintError::IndexBufferCreation(err)
+    }
+}
+
+impl From<TextureCreationError> for PaintError {
+    #[inline]
+    fn from(err: TextureCreationError) -> PaintError {
+        PaintError::TextureCreation(err)
+    }
+}
+
+impl From<DrawError> for PaintError {
+    #[inline]
+    fn from(err: DrawError) -> PaintError {
+        PaintError::Draw(err)
+    }
+}
+
+/// A texture created by the application, that egui can paint with.
+#[derive(Debug, Clone)]
+pub enum NativeTexture {
+    /// A texture whose texels are sRGB encoded, like the ones of egui. Sampled as it is.
+    Texture2d(Rc<Texture2d>),
+
+    /// An sRGB texture, whose texels are decoded to linear when sampled.
+    SrgbTexture2d(Rc<SrgbTexture2d>),
+}
+
+impl From<Rc<Texture2d>> for NativeTexture {
+    #[inline]
+    fn from(texture: Rc<Texture2d>) -> NativeTexture {
+        NativeTexture::Texture2d(texture)
+    }
+}
+
+impl From<Rc<SrgbTexture2d>> for NativeTexture {
+    #[inline]
+    fn from(texture: Rc<SrgbTexture2d>) -> NativeTexture {
+        NativeTexture::SrgbTexture2d(texture)
+    }
+}
+
+/// Paints the meshes produced by egui and keeps its textures up to date.
+pub struct Painter {
+    context: Rc<Context>,
+    program: Program,
+    textures: HashMap<TextureId, (PainterTexture, SamplerBehavior)>,
+    next_native_id: u64,
+}
+
+enum PainterTexture {
+    Managed(Rc<Texture2d>),
+    Native(NativeTexture),
+}
+
+#[derive(Copy, Clone)]
+struct Vertex {
+    a_pos: [f32; 2],
+    a_tc: [f32; 2],
+    a_srgba: [u8; 4],
+}
+
+implement_vertex!(Vertex, a_pos normalize(false), a_tc normalize(false), a_srgba normalize(true));
+
+impl Painter {
+    /// Builds the program used to paint.
+    pub fn new<F: Facade + ?Sized>(facade: &F) -> Result<Painter, ProgramChooserCreationError> {
+        let program = program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform vec2 u_screen_size;
+                    in vec2 a_pos;
+                    in vec2 a_tc;
+                    in vec4 a_srgba;
+                    out vec2 v_tc;
+                    out vec4 v_rgba;
+
+                    void main() {
+                        gl_Position = vec4(2.0 * a_pos.x / u_screen_size.x - 1.0,
+                                           1.0 - 2.0 * a_pos.y / u_screen_size.y, 0.0, 1.0);
+                        v_tc = a_tc;
+                        v_rgba = a_srgba;
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    uniform sampler2D u_sampler;
+                    uniform bool u_linear;
+                    in vec2 v_tc;
+                    in vec4 v_rgba;
+                    out vec4 f_color;
+
+                    vec3 srgb_from_linear(vec3 rgb) {
+                        vec3 lower = rgb * 12.92;
+                        vec3 higher = 1.055 * pow(rgb, vec3(1.0 / 2.4)) - 0.055;
+                        return mix(higher, lower, vec3(lessThan(rgb, vec3(0.0031308))));
+                    }
+
+                    void main() {
+                        vec4 texel = texture(u_sampler, v_tc);
+                        if (u_linear) {
+                            texel.rgb = srgb_from_linear(texel.rgb);
+                        }
+                        f_color = v_rgba * texel;
+                    }
+                "
+            },
+
+            110 => {
+                vertex: "
+                    #version 110
+
+                    uniform vec2 u_screen_size;
+                    attribute vec2 a_pos;
+                    attribute vec2 a_tc;
+                    attribute vec4 a_srgba;
+                    varying vec2 v_tc;
+                    varying vec4 v_rgba;
+
+                    void main() {
+                        gl_Position = vec4(2.0 * a_pos.x / u_screen_size.x - 1.0,
+                                           1.0 - 2.0 * a_pos.y / u_screen_size.y, 0.0, 1.0);
+                        v_tc = a_tc;
+                        v_rgba = a_srgba;
+                    }
+                ",
+                fragment: "
+                    #version 110
+
+                    uniform sampler2D u_sampler;
+                    uniform bool u_linear;
+                    varying vec2 v_tc;
+                    varying vec4 v_rgba;
+
+                    vec3 srgb_from_linear(vec3 rgb) {
+                        vec3 lower = rgb * 12.92;
+                        vec3 higher = 1.055 * pow(rgb, vec3(1.0 / 2.4)) - 0.055;
+                        return mix(higher, lower, vec3(lessThan(rgb, vec3(0.0031308))));
+                    }
+
+                    void main() {
+                        vec4 texel = texture2D(u_sampler, v_tc);
+                        if (u_linear) {
+                            texel.rgb = srgb_from_linear(texel.rgb);
+                        }
+                        gl_FragColor = v_rgba * texel;
+                    }
+                "
+            },
+
+            100 es => {
+                vertex: "
+                    #version 100
+
+                    uniform mediump vec2 u_screen_size;
+                    attribute mediump vec2 a_pos;
+                    attribute mediump vec2 a_tc;
+                    attribute lowp vec4 a_srgba;
+                    varying mediump vec2 v_tc;
+                    varying lowp vec4 v_rgba;
+
+                    void main() {
+                        gl_Position = vec4(2.0 * a_pos.x / u_screen_size.x - 1.0,
+                                           1.0 - 2.0 * a_pos.y / u_screen_size.y, 0.0, 1.0);
+                        v_tc = a_tc;
+                        v_rgba = a_srgba;
+                    }
+                ",
+                fragment: "
+                    #version 100
+                    precision mediump float;
+
+                    uniform sampler2D u_sampler;
+                    uniform bool u_linear;
+                    varying vec2 v_tc;
+                    varying vec4 v_rgba;
+
+                    vec3 srgb_from_linear(vec3 rgb) {
+                        vec3 lower = rgb * 12.92;
+                        vec3 higher = 1.055 * pow(rgb, vec3(1.0 / 2.4)) - 0.055;
+                        return mix(higher, lower, vec3(lessThan(rgb, vec3(0.0031308))));
+                    }
+
+                    void main() {
+                        vec4 texel = texture2D(u_sampler, v_tc);
+                        if (u_linear) {
+                            texel.rgb = srgb_from_linear(texel.rgb);
+                        }
+                        gl_FragColor = v_rgba * texel;
+                    }
+                "
+            },
+        )?;
+
+        Ok(Painter {
+            context: facade.get_context().clone(),
+            program,
+            textures: HashMap::new(),
+            next_native_id: 0,
+        })
+    }
+
+    /// Returns the maximum width and height of the textures, to give to egui through
+    /// `RawInput::max_texture_side`.
+    #[inline]
+    pub fn max_texture_side(&self) -> usize {
+        self.context.capabilities().max_texture_size as usize
+    }
+
+    /// Applies the texture changes of a frame, paints its meshes, then frees the textures that
+    /// egui doesn't need anymore.
+    pub fn paint_and_update_textures<S>(&mut self, target: &mut S, pixels_per_point: f32,
+                                        clipped_primitives: &[ClippedPrimitive],
+                                        textures_delta: &TexturesDelta) -> Result<(), PaintError>
+        where S: Surface + ?Sized
+    {
+        for (id, delta) in &textures_delta.set {
+            self.set_texture(*id, delta)?;
+        }
+
+        self.paint_primitives(target, pixels_per_point, clipped_primitives)?;
+
+        for id in &textures_delta.free {
+            self.free_texture(*id);
+        }
+
+        Ok(())
+    }
+
+    /// Paints meshes with the textures that are currently set.
+    ///
+    /// `pixels_per_point` must be the one given to `Context::tessellate`.
+    pub fn paint_primitives<S>(&mut self, target: &mut S, pixels_per_point: f32,
+                               clipped_primitives: &[ClippedPrimitive]) -> Result<(), PaintError>
+        where S: Surface + ?Sized
+    {
+        let (width, height) = target.get_dimensions();
+
+        for clipped in clipped_primitives {
+            let mesh = match clipped.primitive {
+                Primitive::Mesh(ref mesh) => mesh,
+                Primitive::Callback(_) => continue,
+            };
+
+            // the clip rectangle is in points, with the origin at the top
+            let clip = clipped.clip_rect;
+            let to_pixels = |v: f32, max: u32| {
+                (v * pixels_per_point).round().clamp(0.0, max as f32) as u32
+            };
+            let (left, right) = (to_pixels(clip.min.x, width), to_pixels(clip.max.x, width));
+            let (top, bottom) = (to_pixels(clip.min.y, height), to_pixels(clip.max.y, height));
+            if left >= right || top >= bottom {
+                continue;
+            }
+
+            let scissor = Rect { left, bottom: height - bottom, width: right - left,
+                                 height: bottom - top };
+            let screen_size = [width as f32 / pixels_per_point, height as f32 / pixels_per_point];
+            self.paint_mesh(target, mesh, scissor, screen_size)?;
+        }
+
+        Ok(())
+    }
+
+    fn paint_mesh<S>(&self, target: &mut S, mesh: &Mesh, scissor: Rect, screen_size: [f32; 2])
+                     -> Result<(), PaintError>
+        where S: Surface + ?Sized
+    {
+        let (texture, sampler) = match self.textures.get(&mesh.texture_id) {
+            Some(&(ref texture, sampler)) => (texture, sampler),
+            None => return Ok(()),
+        };
+
+        let vertices: Vec<Vertex> = mesh.vertices.iter().map(|v| Vertex {
+            a_pos: [v.pos.x, v.pos.y],
+            a_tc: [v.uv.x, v.uv.y],
+            a_srgba: v.color.to_array(),
+        }).collect();
+        let vertices = VertexBuffer::new(&self.context, &vertices)?;
+        let indices = IndexBuffer::new(&self.context, PrimitiveType::TrianglesList, &mesh.indices)?;
+
+        // egui outputs premultiplied colors, and doesn't use a consistent winding order
+        let parameters = DrawParameters {
+            blend: Blend {
+                color: BlendingFunction::Addition {
+                    source: LinearBlendingFactor::One,
+                    destination: LinearBlendingFactor::OneMinusSourceAlpha,
+                },
+                alpha: BlendingFunction::Addition {
+                    source: LinearBlendingFactor::OneMinusDestinationAlpha,
+                    destination: LinearBlendingFactor::One,
+                },
+                constant_value: (0.0, 0.0, 0.0, 0.0),
+            },
+            scissor: Some(scissor),
+            .. Default::default()
+        };
+
+        match *texture {
+            PainterTexture::Managed(ref texture) |
+            PainterTexture::Native(NativeTexture::Texture2d(ref texture)) => {
+                let uniforms = uniform! {
+                    u_screen_size: screen_size,
+                    u_sampler: Sampler(&**texture, sampler),
+                    u_linear: false,
+                };
+                target.draw(&vertices, &indices, &self.program, &uniforms, &parameters)?;
+            },
+            PainterTexture::Native(NativeTexture::SrgbTexture2d(ref texture)) => {
+                let uniforms = uniform! {
+                    u_screen_size: screen_size,
+                    u_sampler: Sampler(&**texture, sampler),
+                    u_linear: true,
+                };
+                target.draw(&vertices, &indices, &self.program, &uniforms, &parameters)?;
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Creates or updates a texture of egui.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the delta updates a part of a texture that doesn't exist.
+    pub fn set_texture(&mut self, id: TextureId, delta: &ImageDelta) -> Result<(), PaintError> {
+        let ImageData::Color(ref image) = delta.image;
+        let data: Vec<u8> = image.pixels.iter().flat_map(|color| color.to_array()).collect();
+        let [width, height] = image.size;
+        // egui's rows go from top to bottom, and so do its texture coordinates
+        let data = RawImage2d::from_raw_rgba(data, (width as u32, height as u32));
+        let sampler = sampler_behavior(&delta.options);
+
+        if let Some([x, y]) = delta.pos {
+            let texture = match self.textures.get_mut(&id) {
+                Some(&mut (PainterTexture::Managed(ref texture), ref mut behavior)) => {
+                    *behavior = sampler;
+                    texture
+                },
+                _ => panic!("Partial update of the missing egui texture {:?}", id),
+            };
+
+            let rect = Rect { left: x as u32, bottom: y as u32, width: width as u32,
+                              height: height as u32 };
+            // also regenerates the mipmaps of the textures created with `AutoGeneratedMipmaps`
+            texture.write(rect, data);
+            return Ok(());
+        }
+
+        let mipmaps = if delta.options.mipmap_mode.is_some() {
+            MipmapsOption::AutoGeneratedMipmaps
+        } else {
+            MipmapsOption::NoMipmap
+        };
+        let texture = Texture2d::with_mipmaps(&self.context, data, mipmaps)?;
+        self.textures.insert(id, (PainterTexture::Managed(Rc::new(texture)), sampler));
+        Ok(())
+    }
+
+    /// Frees a texture of egui or a native texture.
+    #[inline]
+    pub fn free_texture(&mut self, id: TextureId) {
+        self.textures.remove(&id);
+    }
+
+    /// Makes a texture of the application available to egui, for example to show the content of
+    /// a viewport in an `egui::Image`.
+    pub fn register_native_texture<T>(&mut self, texture: T, options: TextureOptions) -> TextureId
+        where T: Into<NativeTexture>
+    {
+        let id = TextureId::User(self.next_native_id);
+        self.next_native_id += 1;
+        self.replace_native_texture(id, texture, options);
+        id
+    }
+
+    /// Replaces the texture behind an id returned by `register_native_texture`, for example
+    /// after the viewport has been resized.
+    pub fn replace_native_texture<T>(&mut self, id: TextureId, texture: T,
+                                     options: TextureOptions)
+        where T: Into<NativeTexture>
+    {
+        let texture = PainterTexture::Native(texture.into());
+        self.textures.insert(id, (texture, sampler_behavior(&options)));
+    }
+}
+
+impl fmt::Debug for Painter {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Painter({} textures)", self.textures.len())
+    }
+}
+
+fn sampler_behavior(options: &TextureOptions) -> SamplerBehavior {
+    let wrap = match options.wrap_mode {
+        TextureWrapMode::ClampToEdge => SamplerWrapFunction::Clamp,
+        TextureWrapMode::Repeat => SamplerWrapFunction::Repeat,
+        TextureWrapMode::MirroredRepeat => SamplerWrapFunction::Mirror,
+    };
+
+    let magnify_filter = match options.magnification {
+        TextureFilter::Nearest => MagnifySamplerFilter::Nearest,
+        TextureFilter::Linear => MagnifySamplerFilter::Linear,
+    };
+
+    let minify_filter = match (options.minification, options.mipmap_mode) {
+        (TextureFilter::Nearest, None) => MinifySamplerFilter::Nearest,
+        (TextureFilter::Linear, None) => MinifySamplerFilter::Linear,
+        (TextureFilter::Nearest, Some(TextureFilter::Nearest)) => {
+            MinifySamplerFilter::NearestMipmapNearest
+        },
+        (TextureFilter::Nearest, Some(TextureFilter::Linear)) => {
+            MinifySamplerFilter::NearestMipmapLinear
+        },
+        (TextureFilter::Linear, Some(TextureFilter::Nearest)) => {
+            MinifySamplerFilter::LinearMipmapNearest
+        },
+        (TextureFilter::Linear, Some(TextureFilter::Linear)) => {
+            MinifySamplerFilter::LinearMipmapLinear
+        },
+    };
+
+    SamplerBehavior {
+        wrap_function: (wrap, wrap, wrap),
+        minify_filter,
+        magnify_filter,
+        .. Default::default()
+    }
+}
@@ -71,6 +71,7 @@ use crate::framebuffer::RenderBufferAny;
 
 use crate::gl;
 use crate::context::CommandContext;
+use crate::debug::StateAudit;
 use crate::version::Version;
 use crate::version::Api;
 
@@ -205,6 +206,7 @@ impl<'a> FramebufferAttachments<'a> {
                         layers,
                         depth_buffer_bits: None,
                         stencil_buffer_bits: None,
+                        samples: samples.unwrap_or(0),
                         marker: PhantomData,
                     })
 
@@ -346,6 +348,7 @@ impl<'a> FramebufferAttachments<'a> {
             layers: None,       // FIXME: count layers
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
+            samples: samples.unwrap_or(0),
             marker: PhantomData,
         })
     }
@@ -408,7 +411,9 @@ impl<'a> FramebufferAttachments<'a> {
 
         macro_rules! handle_rb {
             ($rb:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
-                $num_bits = Some(24);       // FIXME: totally arbitrary
+                // a depth renderbuffer has no stencil bits and vice versa
+                let (depth_bits, stencil_bits) = $rb.get_depth_stencil_bits();
+                $num_bits = Some(cmp::max(depth_bits, stencil_bits));
                 handle_rb!($rb, $dim, $samples)
             });
 
@@ -535,6 +540,7 @@ impl<'a> FramebufferAttachments<'a> {
             layers: None,
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
+            samples: samples.unwrap_or(0),
             marker: PhantomData,
         })
     }
@@ -548,6 +554,7 @@ pub struct ValidatedAttachments<'a> {
     layers: Option<u32>,
     depth_buffer_bits: Option<u16>,
     stencil_buffer_bits: Option<u16>,
+    samples: u32,
     marker: PhantomData<&'a ()>,
 }
 
@@ -577,10 +584,46 @@ impl<'a> ValidatedAttachments<'a> {
     pub fn get_stencil_buffer_bits(&self) -> Option<u16> {
         self.stencil_buffer_bits
     }
+
+    /// Returns the number of samples of the attachments, or `0` if they are not multisampled.
+    #[inline]
+    pub fn get_samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Extends the lifetime of the attachments to `'static`.
+    ///
+    /// # Safety
+    ///
+    /// The attached textures and render buffers must outlive the returned object.
+    #[inline]
+    pub(crate) unsafe fn into_static(self) -> ValidatedAttachments<'static> {
+        ValidatedAttachments {
+            raw: self.raw,
+            dimensions: self.dimensions,
+            layers: self.layers,
+            depth_buffer_bits: self.depth_buffer_bits,
+            stencil_buffer_bits: self.stencil_buffer_bits,
+            samples: self.samples,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the ids of the textures that are attached.
+    pub(crate) fn get_texture_ids(&self) -> impl Iterator<Item = gl::types::GLuint> + '_ {
+        self.raw.color.iter().map(|(_, a)| a)
+            .chain(self.raw.depth.iter())
+            .chain(self.raw.stencil.iter())
+            .chain(self.raw.depth_stencil.iter())
+            .filter_map(|a| match *a {
+                RawAttachment::Texture { texture, .. } => Some(texture),
+                RawAttachment::RenderBuffer(_) => None,
+            })
+    }
 }
 
 /// An error that can happen while validating attachments.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ValidationError {
     /// You requested an empty framebuffer object, but they are not supported.
     EmptyFramebufferObjectsNotSupported,
@@ -604,6 +647,14 @@ pub enum ValidationError {
         /// Number of attachments that were given.
         obtained: usize,
     },
+
+    /// The OpenGL implementation reported that the framebuffer is incomplete.
+    Incomplete {
+        /// The rule of framebuffer completeness that is violated.
+        reason: IncompletenessReason,
+        /// Description of each attachment of the framebuffer.
+        attachments: Vec<AttachmentDiagnostic>,
+    },
 }
 
 impl fmt::Display for ValidationError {
@@ -620,10 +671,19 @@ impl fmt::Display for ValidationError {
                 "All attachments must have the same number of samples",
             TooManyColorAttachments {..} =>
                 "Backends only support a certain number of color attachments",
+            Incomplete {..} =>
+                "The OpenGL implementation reported that the framebuffer is incomplete",
         };
         match self {
             TooManyColorAttachments{ ref maximum, ref obtained } =>
                 write!(fmt, "{}: found {}, maximum: {}", desc, obtained, maximum),
+            Incomplete { ref reason, ref attachments } => {
+                write!(fmt, "{}: {}", desc, reason)?;
+                for attachment in attachments {
+                    write!(fmt, "\n  {}", attachment)?;
+                }
+                Ok(())
+            },
             _ =>
                 fmt.write_str(desc),
         }
@@ -632,6 +692,149 @@ impl fmt::Display for ValidationError {
 
 impl Error for ValidationError {}
 
+/// Rule of framebuffer completeness that is violated, as reported by `glCheckFramebufferStatus`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IncompletenessReason {
+    /// One of the attachments is incomplete. Its image has a width or height of zero, or its
+    /// format can't be rendered to at this attachment point.
+    IncompleteAttachment,
+
+    /// The framebuffer doesn't have any attachment.
+    MissingAttachment,
+
+    /// A draw buffer refers to an attachment point that doesn't have any image.
+    IncompleteDrawBuffer,
+
+    /// The read buffer refers to an attachment point that doesn't have any image.
+    IncompleteReadBuffer,
+
+    /// The attachments don't all have the same number of samples, or don't all use fixed
+    /// sample locations.
+    IncompleteMultisample,
+
+    /// Some attachments are layered and others are not.
+    IncompleteLayerTargets,
+
+    /// The attachments don't all have the same dimensions. Only reported by OpenGL ES 2.
+    IncompleteDimensions,
+
+    /// The combination of formats of the attachments isn't supported by the implementation.
+    Unsupported,
+
+    /// A status that glium doesn't know about, with its OpenGL enum.
+    Unknown(u32),
+}
+
+impl IncompletenessReason {
+    fn from_glenum(status: gl::types::GLenum) -> IncompletenessReason {
+        match status {
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => IncompletenessReason::IncompleteAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => IncompletenessReason::MissingAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => IncompletenessReason::IncompleteDrawBuffer,
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => IncompletenessReason::IncompleteReadBuffer,
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => IncompletenessReason::IncompleteMultisample,
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => IncompletenessReason::IncompleteLayerTargets,
+            gl::FRAMEBUFFER_INCOMPLETE_DIMENSIONS => IncompletenessReason::IncompleteDimensions,
+            gl::FRAMEBUFFER_UNSUPPORTED => IncompletenessReason::Unsupported,
+            status => IncompletenessReason::Unknown(status),
+        }
+    }
+}
+
+impl fmt::Display for IncompletenessReason {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::IncompletenessReason::*;
+        match self {
+            IncompleteAttachment =>
+                fmt.write_str("an attachment has a size of zero or a format that can't be \
+                               rendered to at its attachment point"),
+            MissingAttachment =>
+                fmt.write_str("the framebuffer doesn't have any attachment"),
+            IncompleteDrawBuffer =>
+                fmt.write_str("a draw buffer refers to an attachment point without any image"),
+            IncompleteReadBuffer =>
+                fmt.write_str("the read buffer refers to an attachment point without any image"),
+            IncompleteMultisample =>
+                fmt.write_str("the attachments don't all have the same number of samples"),
+            IncompleteLayerTargets =>
+                fmt.write_str("some attachments are layered and others are not"),
+            IncompleteDimensions =>
+                fmt.write_str("the attachments don't all have the same dimensions"),
+            Unsupported =>
+                fmt.write_str("the combination of formats isn't supported by the implementation"),
+            Unknown(status) =>
+                write!(fmt, "unknown status 0x{:04x}", status),
+        }
+    }
+}
+
+/// Attachment point of a framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttachmentPoint {
+    /// `GL_COLOR_ATTACHMENTi`.
+    Color(u32),
+    /// `GL_DEPTH_ATTACHMENT`.
+    Depth,
+    /// `GL_STENCIL_ATTACHMENT`.
+    Stencil,
+    /// `GL_DEPTH_STENCIL_ATTACHMENT`.
+    DepthStencil,
+}
+
+impl fmt::Display for AttachmentPoint {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttachmentPoint::Color(index) => write!(fmt, "color attachment {}", index),
+            AttachmentPoint::Depth => fmt.write_str("depth attachment"),
+            AttachmentPoint::Stencil => fmt.write_str("stencil attachment"),
+            AttachmentPoint::DepthStencil => fmt.write_str("depth-stencil attachment"),
+        }
+    }
+}
+
+/// Description of an attachment of a framebuffer that OpenGL reported as incomplete.
+///
+/// The values are queried from OpenGL. They are `None` if the backend doesn't support
+/// retrieving them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AttachmentDiagnostic {
+    /// The attachment point.
+    pub point: AttachmentPoint,
+    /// `true` if the attachment is a render buffer, `false` if it is a texture.
+    pub render_buffer: bool,
+    /// The mipmap level of the texture. Always `0` for render buffers.
+    pub level: u32,
+    /// The OpenGL enum of the internal format, for example `0x8058` for `GL_RGBA8`.
+    pub internal_format: Option<u32>,
+    /// The width and height of the image.
+    pub dimensions: Option<(u32, u32)>,
+    /// The number of samples, or `0` if the image is not multisampled.
+    pub samples: Option<u32>,
+}
+
+impl fmt::Display for AttachmentDiagnostic {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}: ", self.point)?;
+        if self.render_buffer {
+            fmt.write_str("render buffer")?;
+        } else {
+            write!(fmt, "texture level {}", self.level)?;
+        }
+        match self.internal_format {
+            Some(format) => write!(fmt, ", format 0x{:04x}", format)?,
+            None => fmt.write_str(", format unknown")?,
+        }
+        match self.dimensions {
+            Some((width, height)) => write!(fmt, ", {}x{}", width, height)?,
+            None => fmt.write_str(", dimensions unknown")?,
+        }
+        match self.samples {
+            Some(samples) => write!(fmt, ", {} samples", samples),
+            None => fmt.write_str(", samples unknown"),
+        }
+    }
+}
+
 /// Data structure stored in the hashmap.
 ///
 /// These attachments are guaranteed to be valid.
@@ -821,6 +1024,50 @@ impl FramebuffersContainer {
         }
     }
 
+    /// Builds the framebuffer object of the attachments if it doesn't exist yet, and returns an
+    /// error if OpenGL reports that it is incomplete.
+    ///
+    /// The status is only retrieved when the framebuffer object is created. The attachments are
+    /// only queried if the framebuffer is incomplete.
+    ///
+    /// # Unsafety
+    ///
+    /// After calling this function, you **must** make sure to call `purge_texture`
+    /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
+    pub unsafe fn check_completeness(ctxt: &mut CommandContext<'_>,
+                                     attachments: &ValidatedAttachments<'_>)
+                                     -> Result<(), ValidationError>
+    {
+        FramebuffersContainer::get_framebuffer(ctxt, attachments);
+
+        let status = ctxt.framebuffer_objects.framebuffers.borrow()[&attachments.raw].status;
+        if status == gl::FRAMEBUFFER_COMPLETE {
+            return Ok(());
+        }
+
+        let raw = &attachments.raw;
+        let mut diagnostics = Vec::with_capacity(raw.color.len() + 1);
+        for (index, &(_, attachment)) in raw.color.iter().enumerate() {
+            diagnostics.push(describe_attachment(ctxt, AttachmentPoint::Color(index as u32),
+                                                 attachment));
+        }
+        if let Some(depth) = raw.depth {
+            diagnostics.push(describe_attachment(ctxt, AttachmentPoint::Depth, depth));
+        }
+        if let Some(stencil) = raw.stencil {
+            diagnostics.push(describe_attachment(ctxt, AttachmentPoint::Stencil, stencil));
+        }
+        if let Some(depth_stencil) = raw.depth_stencil {
+            diagnostics.push(describe_attachment(ctxt, AttachmentPoint::DepthStencil,
+                                                 depth_stencil));
+        }
+
+        Err(ValidationError::Incomplete {
+            reason: IncompletenessReason::from_glenum(status),
+            attachments: diagnostics,
+        })
+    }
+
     /// Binds the default framebuffer to `GL_READ_FRAMEBUFFER` or `GL_FRAMEBUFFER` so that it
     /// becomes the target of `glReadPixels`, `glCopyTexImage2D`, etc.
     // TODO: use an enum for the read buffer instead
@@ -947,6 +1194,8 @@ impl Drop for FramebuffersContainer {
 struct FrameBufferObject {
     id: gl::types::GLuint,
     current_read_buffer: gl::types::GLenum,
+    // value returned by `glCheckFramebufferStatus` after the attachments have been attached
+    status: gl::types::GLenum,
 }
 
 impl FrameBufferObject {
@@ -1081,7 +1330,35 @@ impl FrameBufferObject {
         }
 
         // calling `glDrawBuffers` if necessary
-        if raw_attachments != &[gl::COLOR_ATTACHMENT0] {
+        if raw_attachments.is_empty() {
+            // on some implementations a framebuffer without any color attachment is only
+            // complete if both its draw buffer and its read buffer are `GL_NONE`
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                unsafe {
+                    ctxt.gl.NamedFramebufferDrawBuffer(id, gl::NONE);
+                    ctxt.gl.NamedFramebufferReadBuffer(id, gl::NONE);
+                }
+
+            } else if ctxt.version >= &Version(Api::Gl, 1, 0) {
+                unsafe {
+                    bind_framebuffer(ctxt, id, true, true);
+                    ctxt.gl.DrawBuffer(gl::NONE);
+                    ctxt.gl.ReadBuffer(gl::NONE);
+                }
+
+            } else if ctxt.version >= &Version(Api::GlEs, 3, 0) {
+                unsafe {
+                    bind_framebuffer(ctxt, id, true, true);
+                    ctxt.gl.DrawBuffers(1, [gl::NONE].as_ptr());
+                    ctxt.gl.ReadBuffer(gl::NONE);
+                }
+            }
+
+            // OpenGL ES 2 doesn't have draw and read buffers
+
+        } else if raw_attachments != &[gl::COLOR_ATTACHMENT0] {
             if ctxt.version >= &Version(Api::Gl, 4, 5) ||
                ctxt.extensions.gl_arb_direct_state_access
             {
@@ -1120,10 +1397,12 @@ impl FrameBufferObject {
             }
         }
 
+        let status = unsafe { check_status(ctxt, id) };
 
         FrameBufferObject {
             id,
             current_read_buffer: gl::BACK,
+            status,
         }
     }
 
@@ -1161,6 +1440,162 @@ impl GlObject for FrameBufferObject {
     }
 }
 
+/// Calls `glCheckFramebufferStatus` on a framebuffer object.
+///
+/// # Safety
+///
+/// The id of the FBO must be valid.
+///
+unsafe fn check_status(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) -> gl::types::GLenum {
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+        ctxt.gl.CheckNamedFramebufferStatus(id, gl::DRAW_FRAMEBUFFER)
+
+    } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+              ctxt.extensions.gl_arb_framebuffer_object
+    {
+        bind_framebuffer(ctxt, id, true, false);
+        ctxt.gl.CheckFramebufferStatus(gl::DRAW_FRAMEBUFFER)
+
+    } else if ctxt.version >= &Version(Api::GlEs, 2, 0) {
+        bind_framebuffer(ctxt, id, true, false);
+        ctxt.gl.CheckFramebufferStatus(gl::FRAMEBUFFER)
+
+    } else if ctxt.extensions.gl_ext_framebuffer_object {
+        bind_framebuffer(ctxt, id, true, false);
+        ctxt.gl.CheckFramebufferStatusEXT(gl::FRAMEBUFFER_EXT)
+
+    } else {
+        unreachable!();
+    }
+}
+
+/// Queries the format, dimensions and number of samples of an attachment.
+///
+/// # Safety
+///
+/// The texture or render buffer of the attachment must be alive.
+///
+unsafe fn describe_attachment(ctxt: &mut CommandContext<'_>, point: AttachmentPoint,
+                              attachment: RawAttachment) -> AttachmentDiagnostic
+{
+    match attachment {
+        RawAttachment::Texture { texture, bind_point, level, cubemap_layer, .. } => {
+            let mut format = None;
+            let mut width = None;
+            let mut height = None;
+            let mut samples = None;
+
+            // retrieving the number of samples of a texture requires OpenGL 3.2 or OpenGL ES 3.1
+            let samples_supported = ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                                    ctxt.version >= &Version(Api::GlEs, 3, 1);
+
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                let query = |pname| {
+                    let mut value = 0;
+                    ctxt.gl.GetTextureLevelParameteriv(texture, level as gl::types::GLint,
+                                                       pname, &mut value);
+                    Some(value as u32)
+                };
+
+                format = query(gl::TEXTURE_INTERNAL_FORMAT);
+                width = query(gl::TEXTURE_WIDTH);
+                height = query(gl::TEXTURE_HEIGHT);
+                if samples_supported {
+                    samples = query(gl::TEXTURE_SAMPLES);
+                }
+
+            } else if ctxt.extensions.gl_ext_direct_state_access {
+                let target = match cubemap_layer {
+                    Some(layer) => gl::TEXTURE_CUBE_MAP_POSITIVE_X +
+                                   layer.get_layer_index() as gl::types::GLenum,
+                    None => bind_point,
+                };
+
+                let query = |pname| {
+                    let mut value = 0;
+                    ctxt.gl.GetTextureLevelParameterivEXT(texture, target,
+                                                          level as gl::types::GLint,
+                                                          pname, &mut value);
+                    Some(value as u32)
+                };
+
+                format = query(gl::TEXTURE_INTERNAL_FORMAT);
+                width = query(gl::TEXTURE_WIDTH);
+                height = query(gl::TEXTURE_HEIGHT);
+                if samples_supported {
+                    samples = query(gl::TEXTURE_SAMPLES);
+                }
+            }
+
+            // TODO: bind the texture when direct state access isn't supported
+
+            AttachmentDiagnostic {
+                point,
+                render_buffer: false,
+                level,
+                internal_format: format,
+                dimensions: width.and_then(|w| height.map(|h| (w, h))),
+                samples,
+            }
+        },
+
+        RawAttachment::RenderBuffer(id) => {
+            let samples_supported = ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                                    ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                                    ctxt.extensions.gl_arb_framebuffer_object;
+
+            let mut query = |pname| {
+                let mut value = 0;
+
+                if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+                   ctxt.extensions.gl_arb_direct_state_access
+                {
+                    ctxt.gl.GetNamedRenderbufferParameteriv(id, pname, &mut value);
+
+                } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                          ctxt.version >= &Version(Api::GlEs, 2, 0) ||
+                          ctxt.extensions.gl_arb_framebuffer_object
+                {
+                    if ctxt.state.renderbuffer != id {
+                        ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, id);
+                        ctxt.state.renderbuffer = id;
+                        ctxt.state.audit("glBindRenderbuffer", 0, id as u64);
+                    }
+                    ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, pname, &mut value);
+
+                } else if ctxt.extensions.gl_ext_framebuffer_object {
+                    if ctxt.state.renderbuffer != id {
+                        ctxt.gl.BindRenderbufferEXT(gl::RENDERBUFFER_EXT, id);
+                        ctxt.state.renderbuffer = id;
+                        ctxt.state.audit("glBindRenderbuffer", 0, id as u64);
+                    }
+                    ctxt.gl.GetRenderbufferParameterivEXT(gl::RENDERBUFFER_EXT, pname, &mut value);
+
+                } else {
+                    unreachable!();
+                }
+
+                value as u32
+            };
+
+            let format = query(gl::RENDERBUFFER_INTERNAL_FORMAT);
+            let dimensions = (query(gl::RENDERBUFFER_WIDTH), query(gl::RENDERBUFFER_HEIGHT));
+            let samples = if samples_supported { Some(query(gl::RENDERBUFFER_SAMPLES)) } else { None };
+
+            AttachmentDiagnostic {
+                point,
+                render_buffer: true,
+                level: 0,
+                internal_format: Some(format),
+                dimensions: Some(dimensions),
+                samples,
+            }
+        },
+    }
+}
+
 /// Binds a framebuffer object, either for drawing, reading, or both.
 ///
 /// # Safety
@@ -1170,6 +1605,12 @@ impl GlObject for FrameBufferObject {
 pub unsafe fn bind_framebuffer(ctxt: &mut CommandContext<'_>, fbo_id: gl::types::GLuint,
                                draw: bool, read: bool)
 {
+    if draw && ctxt.state.draw_framebuffer != fbo_id {
+        ctxt.state.statistics.framebuffer_changes += 1;
+    }
+
+    let previous = (ctxt.state.draw_framebuffer, ctxt.state.read_framebuffer);
+
     if draw && read {
         if ctxt.state.draw_framebuffer != fbo_id || ctxt.state.read_framebuffer != fbo_id {
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
@@ -1230,6 +1671,11 @@ pub unsafe fn bind_framebuffer(ctxt: &mut CommandContext<'_>, fbo_id: gl::types:
         }
 
     }
+
+    let current = (ctxt.state.draw_framebuffer, ctxt.state.read_framebuffer);
+    if current != previous {
+        ctxt.state.audit("glBindFramebuffer", 0, StateAudit::hash(current));
+    }
 }
 
 /// Attaches something to a framebuffer object.
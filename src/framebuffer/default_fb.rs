@@ -5,9 +5,10 @@ use std::rc::Rc;
 use crate::backend::Facade;
 use crate::context::Context;
 
-use crate::{DrawParameters, BlitMask};
+use crate::{DrawParameters, BlitMask, Attachment};
 use crate::FboAttachments;
 use crate::Rect;
+use crate::ClearValues;
 use crate::BlitTarget;
 use crate::ContextExt;
 use crate::ToGlEnum;
@@ -16,6 +17,11 @@ use crate::uniforms;
 
 use crate::{Program, Surface};
 use crate::DrawError;
+use crate::{BlitError, ReadError};
+use crate::gl;
+use crate::pixel_buffer::PixelBuffer;
+use crate::texture::{ClientFormat, PixelValue};
+use crate::TextureBarrierNotSupportedError;
 
 use crate::fbo;
 use crate::index;
@@ -56,11 +62,29 @@ impl DefaultFramebuffer {
 
 impl Surface for DefaultFramebuffer {
     #[inline]
-    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
-             depth: Option<f32>, stencil: Option<i32>)
-    {
+    fn clear(&mut self, rect: Option<&Rect>, values: &ClearValues) {
         // TODO: wrong attachment
-        ops::clear(&self.context, None, None, color, color_srgb, depth, stencil);
+        ops::clear(&self.context, None, None, values);
+    }
+
+    #[inline]
+    fn invalidate_rect(&mut self, rect: &Rect, attachments: &[Attachment]) {
+        // TODO: wrong attachment
+        ops::invalidate(&self.context, None, self.get_dimensions(), rect, attachments);
+    }
+
+    fn read_to_pixel_buffer<P>(&self, rect: &Rect, dest: &PixelBuffer<P>, format: ClientFormat)
+                               -> Result<(), ReadError> where P: PixelValue
+    {
+        let read_buffer = match self.attachment {
+            DefaultFramebufferAttachment::BackLeft => gl::BACK_LEFT,
+            DefaultFramebufferAttachment::BackRight => gl::BACK_RIGHT,
+            DefaultFramebufferAttachment::FrontLeft => gl::FRONT_LEFT,
+            DefaultFramebufferAttachment::FrontRight => gl::FRONT_RIGHT,
+        };
+
+        ops::read_surface(&self.context, ops::Source::DefaultFramebuffer(read_buffer),
+                          self.get_dimensions(), rect, dest, format)
     }
 
     fn get_dimensions(&self) -> (u32, u32) {
@@ -105,6 +129,11 @@ impl Surface for DefaultFramebuffer {
                   uniforms, draw_parameters, self.get_dimensions())
     }
 
+    fn texture_barrier(&self) -> Result<(), TextureBarrierNotSupportedError> {
+        let mut ctxt = self.context.make_current();
+        ops::texture_barrier(&mut ctxt)
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface
@@ -113,19 +142,52 @@ impl Surface for DefaultFramebuffer {
     }
 
     #[inline]
-    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface
+    {
+        target.try_blit_buffers_from_frame(source_rect, target_rect, filter, mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_frame(source_rect, target_rect, filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter,
+                                                      mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect,
+                                                           filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn try_blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, None, self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
     #[inline]
-    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn try_blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
     #[inline]
-    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn try_blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
@@ -0,0 +1,109 @@
+use crate::TextureExt;
+use crate::backend::Facade;
+use crate::framebuffer::SimpleFrameBuffer;
+use crate::texture::{DepthFormat, DepthTexture2d, MipmapsOption, TextureCreationError};
+use crate::uniforms::{DepthTextureComparison, MagnifySamplerFilter, MinifySamplerFilter};
+use crate::uniforms::{Sampler, SamplerWrapFunction};
+
+/// A depth texture that is rendered to without any color attachment and sampled afterwards,
+/// for example a shadow map.
+///
+/// A correct depth-only pass needs a depth texture without mipmaps, a framebuffer that doesn't
+/// have any draw buffer, and a sampler that doesn't use mipmaps and compares the depth values.
+/// Forgetting any of these usually results in a black screen. This struct takes care of all of
+/// them.
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # use glium::uniform;
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>, vertex_buffer: glium::VertexBuffer<[f32; 3]>,
+/// #               depth_program: glium::Program, program: glium::Program)
+/// #     where T: SurfaceTypeTrait + ResizeableSurface {
+/// # let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+/// # let light_matrix = [[0.0f32; 4]; 4];
+/// use glium::framebuffer::DepthOnlyTarget;
+///
+/// let shadow_map = DepthOnlyTarget::new(&display, 1024, 1024).unwrap();
+///
+/// // rendering the scene from the point of view of the light
+/// let mut target = shadow_map.as_surface();
+/// target.clear_depth(1.0);
+/// target.draw(&vertex_buffer, &indices, &depth_program, &uniform! { matrix: light_matrix },
+///             &glium::DrawParameters {
+///                 depth: glium::Depth {
+///                     test: glium::DepthTest::IfLess,
+///                     write: true,
+///                     .. Default::default()
+///                 },
+///                 .. Default::default()
+///             }).unwrap();
+///
+/// // rendering the scene with the shadow map, which is a `sampler2DShadow` in the shader
+/// let uniforms = uniform! { light_matrix: light_matrix, shadow_map: shadow_map.sampled() };
+/// # }
+/// ```
+pub struct DepthOnlyTarget {
+    texture: DepthTexture2d,
+}
+
+impl DepthOnlyTarget {
+    /// Creates a target with a depth texture of the given dimensions.
+    #[inline]
+    pub fn new<F>(facade: &F, width: u32, height: u32)
+                  -> Result<DepthOnlyTarget, TextureCreationError> where F: ?Sized + Facade
+    {
+        DepthOnlyTarget::with_format(facade, DepthFormat::I24, width, height)
+    }
+
+    /// Creates a target with a depth texture of the given format and dimensions.
+    pub fn with_format<F>(facade: &F, format: DepthFormat, width: u32, height: u32)
+                          -> Result<DepthOnlyTarget, TextureCreationError>
+                          where F: ?Sized + Facade
+    {
+        let texture = DepthTexture2d::empty_with_format(facade, format,
+                                                        MipmapsOption::NoMipmap, width, height)?;
+
+        Ok(DepthOnlyTarget {
+            texture,
+        })
+    }
+
+    /// Returns a surface that draws to the depth texture. Its fragment shaders don't need any
+    /// output.
+    #[inline]
+    pub fn as_surface(&self) -> SimpleFrameBuffer<'_> {
+        SimpleFrameBuffer::depth_only(self.texture.get_context(), &self.texture).unwrap()
+    }
+
+    /// Returns the depth texture.
+    #[inline]
+    pub fn texture(&self) -> &DepthTexture2d {
+        &self.texture
+    }
+
+    /// Destroys the target and returns the depth texture.
+    #[inline]
+    pub fn into_texture(self) -> DepthTexture2d {
+        self.texture
+    }
+
+    /// Returns a sampler for a `sampler2DShadow` uniform.
+    ///
+    /// The sampler compares the depth with `LessOrEqual` and filters linearly between the results
+    /// of the comparisons. Coordinates outside of the texture are clamped to the edge.
+    #[inline]
+    pub fn sampled(&self) -> Sampler<'_, DepthTexture2d> {
+        self.texture.sampled()
+            .wrap_function(SamplerWrapFunction::Clamp)
+            .minify_filter(MinifySamplerFilter::Linear)
+            .magnify_filter(MagnifySamplerFilter::Linear)
+            .depth_texture_comparison(Some(DepthTextureComparison::LessOrEqual))
+    }
+
+    /// Returns the dimensions of the depth texture.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        self.texture.dimensions()
+    }
+}
@@ -0,0 +1,142 @@
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use smallvec::SmallVec;
+
+use crate::BlitTarget;
+use crate::CapabilitiesSource;
+use crate::Rect;
+use crate::context::Context;
+use crate::fbo;
+use crate::framebuffer::{DepthStencilRenderBuffer, RenderBuffer};
+use crate::gl;
+use crate::image_format::TextureFormat;
+use crate::ops;
+use crate::texture::{DepthStencilFormat, UncompressedFloatFormat};
+use crate::version::{Api, Version};
+
+/// Error that can happen when enabling the multisampling of the frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameMultisamplingError {
+    /// The backend doesn't support multisampled render buffers or resolving them.
+    NotSupported,
+
+    /// The backend doesn't support that many samples.
+    TooManySamples {
+        /// Maximum number of samples.
+        maximum: u32,
+    },
+}
+
+impl fmt::Display for FrameMultisamplingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::FrameMultisamplingError::*;
+        match *self {
+            NotSupported =>
+                fmt.write_str("The backend doesn't support multisampled render buffers or resolving them"),
+            TooManySamples { maximum } =>
+                write!(fmt, "The backend supports at most {} samples", maximum),
+        }
+    }
+}
+
+impl Error for FrameMultisamplingError {}
+
+/// Multisampled buffers that a `Frame` draws to instead of the default framebuffer, and that
+/// are resolved to the default framebuffer before the buffers are swapped.
+pub(crate) struct FrameMultisampling {
+    // refers to the buffers below
+    attachments: fbo::ValidatedAttachments<'static>,
+    samples: u32,
+    color: RenderBuffer,
+    depth_stencil: DepthStencilRenderBuffer,
+}
+
+impl FrameMultisampling {
+    const COLOR_FORMAT: UncompressedFloatFormat = UncompressedFloatFormat::U8U8U8U8;
+    const DEPTH_STENCIL_FORMAT: DepthStencilFormat = DepthStencilFormat::I24I8;
+
+    /// Builds the buffers with the given dimensions and number of samples.
+    ///
+    /// Dimensions of zero, for example of a minimized window, are replaced with one.
+    pub fn new(context: &Rc<Context>, dimensions: (u32, u32), samples: u32)
+               -> Result<FrameMultisampling, FrameMultisamplingError>
+    {
+        let dimensions = (dimensions.0.max(1), dimensions.1.max(1));
+
+        if !(context.get_version() >= &Version(Api::Gl, 3, 0) ||
+             context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+             (context.get_extensions().gl_ext_framebuffer_multisample &&
+              context.get_extensions().gl_ext_framebuffer_blit))
+        {
+            return Err(FrameMultisamplingError::NotSupported);
+        }
+
+        // the sample counts are sorted in descending order
+        let format = TextureFormat::UncompressedFloat(FrameMultisampling::COLOR_FORMAT);
+        let maximum = context.get_capabilities().internal_formats_renderbuffers.get(&format)
+                             .and_then(|infos| infos.multisamples.as_ref())
+                             .and_then(|samples| samples.first().cloned());
+        if let Some(maximum) = maximum {
+            if samples > maximum as u32 {
+                return Err(FrameMultisamplingError::TooManySamples { maximum: maximum as u32 });
+            }
+        }
+
+        let color = RenderBuffer::new_multisample(context, FrameMultisampling::COLOR_FORMAT,
+                                                  dimensions.0, dimensions.1, samples)
+                                 .map_err(|_| FrameMultisamplingError::NotSupported)?;
+        let depth_stencil = DepthStencilRenderBuffer::new_multisample(context,
+                                  FrameMultisampling::DEPTH_STENCIL_FORMAT, dimensions.0,
+                                  dimensions.1, samples)
+                                 .map_err(|_| FrameMultisamplingError::NotSupported)?;
+
+        let attachments = {
+            let mut colors = SmallVec::new();
+            colors.push((0, fbo::RegularAttachment::RenderBuffer(&color)));
+
+            fbo::FramebufferAttachments::Regular(fbo::FramebufferSpecificAttachments {
+                colors,
+                depth_stencil: fbo::DepthStencilAttachments::DepthStencilAttachment(
+                    fbo::RegularAttachment::RenderBuffer(&depth_stencil)),
+            }).validate(context).map_err(|_| FrameMultisamplingError::NotSupported)?
+        };
+
+        // the buffers are stored alongside the attachments
+        let attachments = unsafe { attachments.into_static() };
+
+        Ok(FrameMultisampling {
+            attachments,
+            samples,
+            color,
+            depth_stencil,
+        })
+    }
+
+    /// Returns the attachments to draw to.
+    #[inline]
+    pub fn get_attachments(&self) -> &fbo::ValidatedAttachments<'_> {
+        &self.attachments
+    }
+
+    /// Returns the dimensions of the buffers.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        self.color.get_dimensions()
+    }
+
+    /// Returns the number of samples of the buffers.
+    #[inline]
+    pub fn get_samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Copies the color buffer to the back buffer of the default framebuffer.
+    pub fn resolve(&self, context: &Context) {
+        let (width, height) = self.get_dimensions();
+        let rect = Rect { left: 0, bottom: 0, width, height };
+        ops::blit(context, Some(&self.attachments), None, gl::COLOR_BUFFER_BIT, &rect,
+                  &BlitTarget::from(rect), gl::NEAREST).unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+use crate::backend::Facade;
+use crate::index::{NoIndices, PrimitiveType};
+use crate::program::{Program, ProgramCreationError, ProgramCreationInput};
+use crate::uniforms::AsUniformValue;
+use crate::vertex::EmptyVertexAttributes;
+use crate::version::Api;
+
+use crate::BlitTarget;
+use crate::CapabilitiesSource;
+use crate::DrawError;
+use crate::DrawParameters;
+use crate::Rect;
+use crate::Surface;
+
+/// How the color values of a texture or of a framebuffer are encoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorEncoding {
+    /// The values are linear RGB.
+    Linear,
+    /// The values are sRGB, which is the case of most image files.
+    Srgb,
+}
+
+/// How the source is resampled when the source and target rectangles don't have the same
+/// dimensions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlitScaling {
+    /// Takes the texel that is the closest to the center of each target pixel.
+    Nearest,
+    /// Interpolates between the four texels that are the closest to the center of each target
+    /// pixel. This is what `MagnifySamplerFilter::Linear` does with a regular blit.
+    Bilinear,
+    /// Averages all the texels covered by each target pixel, up to 16x16 texels. This gives
+    /// much better results than `Bilinear` when the image is shrunk by a large factor, for
+    /// example when building thumbnails.
+    Area,
+}
+
+/// Parameters of `LinearBlitter::blit`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LinearBlitParameters {
+    /// Encoding of the values that are read from the source.
+    ///
+    /// Sampling an `SrgbTexture2d` already turns its content into linear RGB, so this should
+    /// be `Linear` for sRGB textures. Use `Srgb` if the source is a regular texture that
+    /// contains sRGB data.
+    pub source_encoding: ColorEncoding,
+
+    /// Encoding of the values that are written to the target.
+    ///
+    /// `GL_FRAMEBUFFER_SRGB` is disabled during the blit, so this should be `Srgb` if the target
+    /// is an sRGB texture or the default framebuffer of an sRGB-capable window.
+    pub target_encoding: ColorEncoding,
+
+    /// How the source is resampled.
+    pub scaling: BlitScaling,
+}
+
+impl Default for LinearBlitParameters {
+    #[inline]
+    fn default() -> LinearBlitParameters {
+        LinearBlitParameters {
+            source_encoding: ColorEncoding::Linear,
+            target_encoding: ColorEncoding::Linear,
+            scaling: BlitScaling::Bilinear,
+        }
+    }
+}
+
+/// Copies and resizes textures with a draw command, filtering the colors in linear space.
+///
+/// When the source and the target of `Surface::blit_color` have different dimensions, many
+/// drivers interpolate the raw values of sRGB surfaces, which darkens the result. The
+/// `LinearBlitter` instead samples the source in a fragment shader, turns the texels into
+/// linear RGB before filtering them, and encodes the result again before writing it.
+///
+/// Contrary to a blit, this goes through the regular draw pipeline and is therefore a bit
+/// slower.
+pub struct LinearBlitter {
+    program: Program,
+}
+
+impl LinearBlitter {
+    /// Builds a new `LinearBlitter`.
+    pub fn new<F>(facade: &F) -> Result<LinearBlitter, ProgramCreationError>
+                  where F: ?Sized + Facade
+    {
+        let header = if facade.get_version().0 == Api::GlEs {
+            "#version 300 es\nprecision highp float;\nprecision highp int;\n"
+        } else {
+            "#version 140\n"
+        };
+
+        let vertex_shader = format!("{}{}", header, VERTEX_SHADER);
+        let fragment_shader = format!("{}{}", header, FRAGMENT_SHADER);
+
+        // the shader encodes the colors itself
+        let program = Program::new(facade, ProgramCreationInput::SourceCode {
+            vertex_shader: &vertex_shader,
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: None,
+            geometry_shader: None,
+            fragment_shader: &fragment_shader,
+            transform_feedback_varyings: None,
+            outputs_srgb: true,
+            uses_point_size: false,
+        })?;
+
+        Ok(LinearBlitter {
+            program,
+        })
+    }
+
+    /// Copies the `source_rect` area of `source` into the `target_rect` area of `target`,
+    /// resizing it if necessary.
+    ///
+    /// The source must be a two-dimensional texture, for example a `Texture2d` or an
+    /// `SrgbTexture2d`. Like with `Surface::blit_color`, the target rectangle can have a
+    /// negative width or height in order to flip the image.
+    pub fn blit<'t, T: ?Sized, S>(&self, source: &'t T, source_rect: &Rect, target: &mut S,
+                                  target_rect: &BlitTarget, parameters: &LinearBlitParameters)
+                                  -> Result<(), DrawError>
+                                  where &'t T: AsUniformValue, S: ?Sized + Surface
+    {
+        // only the pixels of the target rectangle are written
+        let scissor = Rect {
+            left: (target_rect.left as i32 + target_rect.width.min(0)).max(0) as u32,
+            bottom: (target_rect.bottom as i32 + target_rect.height.min(0)).max(0) as u32,
+            width: target_rect.width.unsigned_abs(),
+            height: target_rect.height.unsigned_abs(),
+        };
+
+        let scaling = match parameters.scaling {
+            BlitScaling::Nearest => 0i32,
+            BlitScaling::Bilinear => 1,
+            BlitScaling::Area => 2,
+        };
+
+        let uniforms = uniform! {
+            source: source,
+            source_rect: [source_rect.left as i32, source_rect.bottom as i32,
+                          source_rect.width as i32, source_rect.height as i32],
+            target_rect: [target_rect.left as f32, target_rect.bottom as f32,
+                          target_rect.width as f32, target_rect.height as f32],
+            scaling: scaling,
+            decode_source: parameters.source_encoding == ColorEncoding::Srgb,
+            encode_target: parameters.target_encoding == ColorEncoding::Srgb,
+        };
+
+        target.draw(EmptyVertexAttributes { len: 3 }, NoIndices(PrimitiveType::TrianglesList),
+                    &self.program, &uniforms, &DrawParameters {
+                        scissor: Some(scissor),
+                        .. Default::default()
+                    })
+    }
+}
+
+// the shaders below don't have a `#version` directive, see `LinearBlitter::new`
+
+const VERTEX_SHADER: &str = "
+    void main() {
+        vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+        gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+    }
+";
+
+const FRAGMENT_SHADER: &str = "
+    uniform sampler2D source;
+    uniform ivec4 source_rect;
+    uniform vec4 target_rect;
+    uniform int scaling;
+    uniform bool decode_source;
+    uniform bool encode_target;
+
+    out vec4 f_color;
+
+    vec3 to_linear(vec3 c) {
+        return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c));
+    }
+
+    vec3 to_srgb(vec3 c) {
+        return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, c));
+    }
+
+    vec4 fetch(ivec2 coords) {
+        coords = clamp(coords, source_rect.xy, source_rect.xy + source_rect.zw - 1);
+        vec4 color = texelFetch(source, coords, 0);
+        if (decode_source) {
+            color.rgb = to_linear(color.rgb);
+        }
+        return color;
+    }
+
+    // converts a position in the target into a position in the source
+    vec2 to_source(vec2 position) {
+        vec2 scale = vec2(source_rect.zw) / target_rect.zw;
+        return (position - target_rect.xy) * scale + vec2(source_rect.xy);
+    }
+
+    void main() {
+        vec4 color;
+
+        if (scaling == 0) {
+            color = fetch(ivec2(floor(to_source(gl_FragCoord.xy))));
+
+        } else if (scaling == 1) {
+            vec2 position = to_source(gl_FragCoord.xy) - 0.5;
+            ivec2 base = ivec2(floor(position));
+            vec2 t = fract(position);
+            color = mix(mix(fetch(base), fetch(base + ivec2(1, 0)), t.x),
+                        mix(fetch(base + ivec2(0, 1)), fetch(base + ivec2(1, 1)), t.x), t.y);
+
+        } else {
+            vec2 corner1 = to_source(gl_FragCoord.xy - 0.5);
+            vec2 corner2 = to_source(gl_FragCoord.xy + 0.5);
+            ivec2 start = ivec2(floor(min(corner1, corner2)));
+            ivec2 end = max(ivec2(ceil(max(corner1, corner2))), start + 1);
+            ivec2 count = min(end - start, ivec2(16));
+            vec2 stride = vec2(end - start) / vec2(count);
+
+            color = vec4(0.0);
+            for (int y = 0; y < count.y; ++y) {
+                for (int x = 0; x < count.x; ++x) {
+                    color += fetch(start + ivec2(vec2(x, y) * stride));
+                }
+            }
+            color /= float(count.x * count.y);
+        }
+
+        if (encode_target) {
+            color.rgb = to_srgb(clamp(color.rgb, 0.0, 1.0));
+        }
+
+        f_color = color;
+    }
+";
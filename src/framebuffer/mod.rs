@@ -59,6 +59,31 @@ Some restrictions apply when you use framebuffers:
    framebuffer that you are using. This is not enforced by glium as it depends on your shader's
    source code.
 
+# Mipmap levels
+
+Any mipmap level of a texture can be attached instead of the whole texture, which is what
+hierarchical depth buffers and bloom downsample chains need. For array textures and cubemaps,
+a single layer or face of a level is attached.
+
+```no_run
+# use glium::texture::{Texture2d, Texture2dArray};
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>, texture: Texture2d, array: Texture2dArray)
+#     where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::framebuffer::SimpleFrameBuffer;
+
+// one framebuffer per level of the chain
+let chain = (1 .. texture.get_mipmap_levels())
+    .map(|level| SimpleFrameBuffer::new(&display, texture.mipmap(level).unwrap()))
+    .collect::<Result<Vec<_>, _>>().unwrap();
+
+// the third layer of the second level of an array texture
+let layer = SimpleFrameBuffer::new(&display, array.mipmap(1).unwrap().layer(2).unwrap());
+# }
+```
+
+The dimensions of the framebuffer are the dimensions of the level.
+
 # Empty framebuffers
 
 Modern OpenGL implementations support empty framebuffers. This is handled by glium with the
@@ -66,6 +91,23 @@ Modern OpenGL implementations support empty framebuffers. This is handled by gli
 
 You can check whether they are supported by calling `EmptyFrameBuffer::is_supported(&display)`.
 
+# Shadow maps
+
+`DepthOnlyTarget` wraps a depth texture that is rendered to without any color attachment, then
+sampled with depth comparison, which is what a shadow map needs.
+
+# Resizing
+
+Textures that must have the same dimensions as the window, for example the attachments of a
+G-buffer, can be wrapped in a `WindowSized`. They are then rebuilt automatically when the
+display is resized.
+
+# Sub-regions
+
+`Surface::sub_region` returns a `SubSurface` that covers a rectangle of a surface, for example
+a viewport of an editor inside of the window. Draws and clears made on it are confined to the
+rectangle, and the viewport and scissor of the draw parameters are relative to it.
+
 # Layered framebuffers
 
 Not yet supported
@@ -84,6 +126,8 @@ use crate::version::Api;
 
 use crate::FboAttachments;
 use crate::Rect;
+use crate::Attachment;
+use crate::ClearValues;
 use crate::BlitTarget;
 use crate::ContextExt;
 use crate::ToGlEnum;
@@ -92,24 +136,44 @@ use crate::uniforms;
 
 use crate::{Program, Surface};
 use crate::DrawError;
+use crate::{BlitError, ReadError};
+use crate::TextureBarrierNotSupportedError;
+use crate::pixel_buffer::PixelBuffer;
+use crate::texture::{ClientFormat, PixelValue};
 
 use crate::fbo;
+use crate::gl;
 
 pub use self::default_fb::{DefaultFramebufferAttachment, DefaultFramebuffer};
 pub use self::render_buffer::{RenderBuffer, RenderBufferAny, DepthRenderBuffer};
 pub use self::render_buffer::{StencilRenderBuffer, DepthStencilRenderBuffer};
 pub use self::render_buffer::CreationError as RenderBufferCreationError;
+pub(crate) use self::render_buffer::{delete_renderbuffer, forget_renderbuffer};
 pub use crate::fbo::is_dimensions_mismatch_supported;
-pub use crate::fbo::ValidationError;
+pub use crate::fbo::{AttachmentDiagnostic, AttachmentPoint, IncompletenessReason, ValidationError};
+pub use crate::ops::is_texture_barrier_supported;
+pub use self::linear_blit::{LinearBlitter, LinearBlitParameters, ColorEncoding, BlitScaling};
+pub use self::sub_surface::SubSurface;
+pub use self::window_sized::WindowSized;
+pub use self::depth_only::DepthOnlyTarget;
+pub use self::frame_multisampling::FrameMultisamplingError;
+pub(crate) use self::window_sized::WindowSizedTarget;
+pub(crate) use self::frame_multisampling::FrameMultisampling;
 use crate::uniforms::MagnifySamplerFilter;
 
 mod default_fb;
+mod depth_only;
+mod frame_multisampling;
+mod linear_blit;
 mod render_buffer;
+mod sub_surface;
+mod window_sized;
 
 /// A framebuffer which has only one color attachment.
 pub struct SimpleFrameBuffer<'a> {
     context: Rc<Context>,
     attachments: fbo::ValidatedAttachments<'a>,
+    color: Option<fbo::RegularAttachment<'a>>,
 }
 
 impl<'a> SimpleFrameBuffer<'a> {
@@ -262,20 +326,45 @@ impl<'a> SimpleFrameBuffer<'a> {
         });
 
         let attachments = attachments.validate(facade)?;
+        check_completeness(facade.get_context(), &attachments)?;
 
         Ok(SimpleFrameBuffer {
             context: facade.get_context().clone(),
             attachments,
+            color,
         })
     }
+
+    /// Attaches a name to the framebuffer object, which OpenGL debuggers display instead of
+    /// its id.
+    ///
+    /// Glium caches framebuffer objects by attachments, so the label is shared with any other
+    /// framebuffer created with the same attachments.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`.
+    #[inline]
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        set_label(&self.context, &self.attachments, label)
+    }
 }
 
 impl<'a> Surface for SimpleFrameBuffer<'a> {
     #[inline]
-    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
-             depth: Option<f32>, stencil: Option<i32>)
+    fn clear(&mut self, rect: Option<&Rect>, values: &ClearValues) {
+        ops::clear(&self.context, Some(&self.attachments), rect, values);
+    }
+
+    fn read_to_pixel_buffer<P>(&self, rect: &Rect, dest: &PixelBuffer<P>, format: ClientFormat)
+                               -> Result<(), ReadError> where P: PixelValue
     {
-        ops::clear(&self.context, Some(&self.attachments), rect, color, color_srgb, depth, stencil);
+        let color = self.color.as_ref().ok_or(ReadError::NoColorAttachment)?;
+        ops::read_surface(&self.context, color.into(), self.get_dimensions(), rect, dest, format)
+    }
+
+    #[inline]
+    fn invalidate_rect(&mut self, rect: &Rect, attachments: &[Attachment]) {
+        ops::invalidate(&self.context, Some(&self.attachments), self.get_dimensions(), rect,
+                        attachments);
     }
 
     #[inline]
@@ -321,6 +410,11 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
 
+    fn texture_barrier(&self) -> Result<(), TextureBarrierNotSupportedError> {
+        let mut ctxt = self.context.make_current();
+        ops::texture_barrier(&mut ctxt)
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface
@@ -329,19 +423,52 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
     }
 
     #[inline]
-    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface
+    {
+        target.try_blit_buffers_from_simple_framebuffer(self, source_rect, target_rect, filter, mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_frame(source_rect, target_rect, filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter,
+                                                      mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect,
+                                                           filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn try_blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, None, self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
     #[inline]
-    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn try_blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
     #[inline]
-    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn try_blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
@@ -365,14 +492,16 @@ pub struct MultiOutputFrameBuffer<'a> {
 impl<'a> MultiOutputFrameBuffer<'a> {
     /// Creates a new `MultiOutputFrameBuffer`.
     ///
+    /// The names of the outputs are copied, so they don't need to outlive the framebuffer.
+    ///
     /// # Panic
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn new<F: ?Sized, I, A>(facade: &F, color_attachments: I)
+    pub fn new<'n, F: ?Sized, I, A>(facade: &F, color_attachments: I)
                         -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where F: Facade,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (&'n str, A)>,
               A: ToColorAttachment<'a>,
     {
         MultiOutputFrameBuffer::new_impl(facade, color_attachments, None, None, None)
@@ -384,11 +513,11 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn with_depth_buffer<F: ?Sized, D, I, A>(facade: &F, color_attachments: I, depth: D)
+    pub fn with_depth_buffer<'n, F: ?Sized, D, I, A>(facade: &F, color_attachments: I, depth: D)
                                          -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where F: Facade,
               D: ToDepthAttachment<'a>,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (&'n str, A)>,
               A: ToColorAttachment<'a>,
     {
         MultiOutputFrameBuffer::new_impl(facade, color_attachments,
@@ -401,11 +530,11 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn with_depth_and_stencil_buffer<A, F: ?Sized, I, D, S>(facade: &F, color: I, depth: D, stencil: S)
+    pub fn with_depth_and_stencil_buffer<'n, A, F: ?Sized, I, D, S>(facade: &F, color: I, depth: D, stencil: S)
                                                         -> Result<MultiOutputFrameBuffer<'a>,
                                                                   ValidationError>
         where D: ToDepthAttachment<'a>,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (&'n str, A)>,
               S: ToStencilAttachment<'a>,
               A: ToColorAttachment<'a>,
               F: Facade
@@ -421,11 +550,11 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn with_stencil_buffer<A, F: ?Sized, I, S>(facade: &F, color: I, stencil: S)
+    pub fn with_stencil_buffer<'n, A, F: ?Sized, I, S>(facade: &F, color: I, stencil: S)
                                            -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where S: ToStencilAttachment<'a>,
               F: Facade,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (&'n str, A)>,
               A: ToColorAttachment<'a>,
     {
         MultiOutputFrameBuffer::new_impl(facade, color, None,
@@ -438,22 +567,22 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn with_depth_stencil_buffer<A, F: ?Sized, I, D>(facade: &F, color: I, depthstencil: D)
+    pub fn with_depth_stencil_buffer<'n, A, F: ?Sized, I, D>(facade: &F, color: I, depthstencil: D)
                                                  -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where D: ToDepthStencilAttachment<'a>, F: Facade,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (&'n str, A)>,
               A: ToColorAttachment<'a>,
     {
         MultiOutputFrameBuffer::new_impl(facade, color, None, None,
                                     Some(depthstencil.to_depth_stencil_attachment()))
     }
 
-    fn new_impl<F: ?Sized, I, A>(facade: &F, color: I, depth: Option<DepthAttachment<'a>>,
+    fn new_impl<'n, F: ?Sized, I, A>(facade: &F, color: I, depth: Option<DepthAttachment<'a>>,
                          stencil: Option<StencilAttachment<'a>>,
                          depthstencil: Option<DepthStencilAttachment<'a>>)
                          -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where F: Facade,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (&'n str, A)>,
               A: ToColorAttachment<'a>,
     {
         let color = color.into_iter().map(|(name, tex)| {
@@ -501,6 +630,7 @@ impl<'a> MultiOutputFrameBuffer<'a> {
             colors: example_color,
             depth_stencil: depth_stencil_attachments,
         }).validate(facade)?;
+        check_completeness(facade.get_context(), &example_attachments)?;
 
         Ok(MultiOutputFrameBuffer {
             context: facade.get_context().clone(),
@@ -510,6 +640,20 @@ impl<'a> MultiOutputFrameBuffer<'a> {
         })
     }
 
+    /// Attaches a name to the framebuffer object, which OpenGL debuggers display instead of
+    /// its id.
+    ///
+    /// The color attachments are bound to the locations of the fragment outputs of the program
+    /// used when drawing, and each layout uses its own framebuffer object. This labels the
+    /// object used for clearing and for programs whose outputs are in the same order as the
+    /// attachments.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`.
+    #[inline]
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        set_label(&self.context, &self.example_attachments, label)
+    }
+
     fn build_attachments(&self, program: &Program) -> fbo::ValidatedAttachments<'_> {
         let mut colors = SmallVec::new();
 
@@ -531,11 +675,21 @@ impl<'a> MultiOutputFrameBuffer<'a> {
 
 impl<'a> Surface for MultiOutputFrameBuffer<'a> {
     #[inline]
-    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
-             depth: Option<f32>, stencil: Option<i32>)
+    fn clear(&mut self, rect: Option<&Rect>, values: &ClearValues) {
+        ops::clear(&self.context, Some(&self.example_attachments), rect, values);
+    }
+
+    fn read_to_pixel_buffer<P>(&self, rect: &Rect, dest: &PixelBuffer<P>, format: ClientFormat)
+                               -> Result<(), ReadError> where P: PixelValue
     {
-        ops::clear(&self.context, Some(&self.example_attachments), rect,
-                   color, color_srgb, depth, stencil);
+        let (_, color) = self.color_attachments.first().ok_or(ReadError::NoColorAttachment)?;
+        ops::read_surface(&self.context, color.into(), self.get_dimensions(), rect, dest, format)
+    }
+
+    #[inline]
+    fn invalidate_rect(&mut self, rect: &Rect, attachments: &[Attachment]) {
+        ops::invalidate(&self.context, Some(&self.example_attachments), self.get_dimensions(), rect,
+                        attachments);
     }
 
     #[inline]
@@ -581,6 +735,11 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
 
+    fn texture_barrier(&self) -> Result<(), TextureBarrierNotSupportedError> {
+        let mut ctxt = self.context.make_current();
+        ops::texture_barrier(&mut ctxt)
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface
@@ -589,19 +748,53 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
     }
 
     #[inline]
-    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface
+    {
+        target.try_blit_buffers_from_multioutput_framebuffer(self, source_rect, target_rect, filter,
+                                                            mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_frame(source_rect, target_rect, filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter,
+                                                      mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect,
+                                                           filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn try_blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, None, self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
     #[inline]
-    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn try_blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
     #[inline]
-    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
+    fn try_blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
@@ -610,13 +803,31 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
 impl<'a> FboAttachments for MultiOutputFrameBuffer<'a> {
     #[inline]
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments<'_>> {
-        unimplemented!();
+        Some(&self.example_attachments)
     }
 }
 
 /// A framebuffer with no attachment at all.
 ///
-/// Note that this is only supported on recent hardware.
+/// Draw commands on an empty framebuffer run the fragment shader for every covered pixel of a
+/// virtual surface of the given dimensions, but nothing is written except what the shader
+/// itself stores in images or buffers. This is useful for passes that only use image
+/// load/store or atomic counters, like voxelization or light binning, as they don't need a
+/// dummy color attachment.
+///
+/// ```no_run
+/// # fn example<F>(facade: &F) where F: glium::backend::Facade {
+/// use glium::framebuffer::EmptyFrameBuffer;
+///
+/// if EmptyFrameBuffer::is_supported(facade) {
+///     let framebuffer = EmptyFrameBuffer::new(facade, 128, 128, None, None, true).unwrap();
+///     // draw with a program whose fragment shader writes to an image unit
+/// }
+/// # }
+/// ```
+///
+/// Note that this is only supported on recent hardware (OpenGL 4.3, OpenGL ES 3.1 or
+/// `GL_ARB_framebuffer_no_attachments`).
 pub struct EmptyFrameBuffer {
     context: Rc<Context>,
     attachments: fbo::ValidatedAttachments<'static>,
@@ -663,6 +874,13 @@ impl<'a> EmptyFrameBuffer {
 
     /// Creates a `EmptyFrameBuffer`.
     ///
+    /// The framebuffer behaves like a surface of `width` by `height` pixels with `layers`
+    /// layers and `samples` samples per pixel. `fixed_samples` indicates whether the sample
+    /// locations are the same for all the pixels.
+    ///
+    /// Returns an error if empty framebuffers or the dimensions are not supported. See
+    /// `is_supported`, `is_layered_supported` and the `get_max_supported_*` functions.
+    ///
     /// # Panic
     ///
     /// Panics if `layers` or `samples` is equal to `Some(0)`.
@@ -683,20 +901,44 @@ impl<'a> EmptyFrameBuffer {
         };
 
         let attachments = attachments.validate(context)?;
+        check_completeness(context, &attachments)?;
 
         Ok(EmptyFrameBuffer {
             context: context.clone(),
             attachments,
         })
     }
+
+    /// Attaches a name to the framebuffer object, which OpenGL debuggers display instead of
+    /// its id.
+    ///
+    /// Glium caches framebuffer objects by attachments, so the label is shared with any other
+    /// framebuffer created with the same attachments.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`.
+    #[inline]
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        set_label(&self.context, &self.attachments, label)
+    }
 }
 
 impl Surface for EmptyFrameBuffer {
     #[inline]
-    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
-             depth: Option<f32>, stencil: Option<i32>)
+    fn clear(&mut self, rect: Option<&Rect>, values: &ClearValues) {
+        ops::clear(&self.context, Some(&self.attachments), rect, values);
+    }
+
+    #[inline]
+    fn read_to_pixel_buffer<P>(&self, _: &Rect, _: &PixelBuffer<P>, _: ClientFormat)
+                               -> Result<(), ReadError> where P: PixelValue
     {
-        ops::clear(&self.context, Some(&self.attachments), rect, color, color_srgb, depth, stencil);
+        Err(ReadError::NoColorAttachment)
+    }
+
+    #[inline]
+    fn invalidate_rect(&mut self, rect: &Rect, attachments: &[Attachment]) {
+        ops::invalidate(&self.context, Some(&self.attachments), self.get_dimensions(), rect,
+                        attachments);
     }
 
     #[inline]
@@ -742,6 +984,11 @@ impl Surface for EmptyFrameBuffer {
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
 
+    fn texture_barrier(&self) -> Result<(), TextureBarrierNotSupportedError> {
+        let mut ctxt = self.context.make_current();
+        ops::texture_barrier(&mut ctxt)
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface
@@ -750,27 +997,63 @@ impl Surface for EmptyFrameBuffer {
     }
 
     #[inline]
-    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                        filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface
+    {
+        Err(BlitError::NotSupported)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_frame(source_rect, target_rect, filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter,
+                                                      mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect,
+                                                           filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn try_blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                           filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                           -> Result<(), BlitError>
     {
         ops::blit(&self.context, None, self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
     #[inline]
-    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
-                                    source_rect: &Rect, target_rect: &BlitTarget,
-                                    filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    fn try_blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                        source_rect: &Rect, target_rect: &BlitTarget,
+                                        filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                                        -> Result<(), BlitError>
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
     #[inline]
-    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
-                                         source_rect: &Rect, target_rect: &BlitTarget,
-                                         filter: uniforms::MagnifySamplerFilter,
-                                         mask: BlitMask)
+    fn try_blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                             source_rect: &Rect, target_rect: &BlitTarget,
+                                             filter: uniforms::MagnifySamplerFilter,
+                                             mask: BlitMask)
+                                             -> Result<(), BlitError>
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
@@ -784,6 +1067,26 @@ impl FboAttachments for EmptyFrameBuffer {
     }
 }
 
+/// Returns an error if OpenGL reports that the framebuffer made of these attachments is
+/// incomplete.
+fn check_completeness(context: &Rc<Context>, attachments: &fbo::ValidatedAttachments<'_>)
+                      -> Result<(), ValidationError>
+{
+    let mut ctxt = context.make_current();
+    unsafe { fbo::FramebuffersContainer::check_completeness(&mut ctxt, attachments) }
+}
+
+fn set_label(context: &Rc<Context>, attachments: &fbo::ValidatedAttachments<'_>, label: &str)
+             -> Result<(), ()>
+{
+    let id = {
+        let mut ctxt = context.make_current();
+        fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, Some(attachments))
+    };
+
+    context.set_object_label(gl::FRAMEBUFFER, id, label)
+}
+
 /// Describes an attachment for a color buffer.
 #[derive(Copy, Clone)]
 pub enum ColorAttachment<'a> {
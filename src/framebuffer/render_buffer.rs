@@ -25,7 +25,11 @@ use crate::GlObject;
 use crate::fbo::FramebuffersContainer;
 use crate::backend::Facade;
 use crate::context::Context;
+use crate::context::CommandContext;
+use crate::context::GarbageObject;
+use crate::context::SharedObject;
 use crate::ContextExt;
+use crate::CapabilitiesSource;
 use crate::version::Version;
 use crate::version::Api;
 
@@ -84,6 +88,54 @@ impl RenderBuffer {
             buffer: RenderBufferAny::new(facade, format, TextureKind::Float, width, height, Some(samples))
         })
     }
+
+    /// Builds a new render buffer with the supported number of samples that is the closest to
+    /// `requested_samples`, and returns it with the number of samples that has been chosen.
+    ///
+    /// The largest supported number of samples that isn't above `requested_samples` is chosen.
+    /// If the format doesn't support multisampling at all, or if `requested_samples` is `0`, the
+    /// render buffer isn't multisampled and the returned number is `0`. If the backend can't
+    /// list the supported numbers of samples, `requested_samples` is used as is.
+    pub fn new_multisample_best_effort<F>(facade: &F, format: UncompressedFloatFormat,
+                                          width: u32, height: u32, requested_samples: u32)
+                                          -> Result<(RenderBuffer, u32), CreationError>
+                                          where F: ?Sized + Facade
+    {
+        let samples = choose_samples(facade.get_context(),
+                                     image_format::TextureFormat::UncompressedFloat(format),
+                                     requested_samples);
+
+        let buffer = if samples == 0 {
+            RenderBuffer::new(facade, format, width, height)?
+        } else {
+            RenderBuffer::new_multisample(facade, format, width, height, samples)?
+        };
+
+        Ok((buffer, samples))
+    }
+}
+
+/// Returns the number of samples that is supported by a render buffer format and that is
+/// the closest to the requested one without being above, or `0` if multisampling isn't
+/// supported.
+fn choose_samples(context: &Context, format: image_format::TextureFormat, requested: u32) -> u32 {
+    if requested == 0 {
+        return 0;
+    }
+
+    let supported = match context.get_capabilities().internal_formats_renderbuffers.get(&format)
+                                 .and_then(|infos| infos.multisamples.as_ref())
+    {
+        Some(supported) => supported,
+        None => return requested,
+    };
+
+    // the list is supposed to be sorted in descending order, but this isn't relied upon
+    supported.iter()
+             .map(|&samples| samples as u32)
+             .filter(|&samples| samples <= requested)
+             .max()
+             .unwrap_or(0)
 }
 
 impl<'a> ToColorAttachment<'a> for &'a RenderBuffer {
@@ -354,6 +406,7 @@ impl RenderBufferAny {
                 ctxt.gl.GenRenderbuffers(1, &mut id);
                 ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, id);
                 ctxt.state.renderbuffer = id;
+                ctxt.state.audit("glBindRenderbuffer", 0, id as u64);
 
                 let samples = samples.unwrap();
 
@@ -404,6 +457,7 @@ impl RenderBufferAny {
                 ctxt.gl.GenRenderbuffers(1, &mut id);
                 ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, id);
                 ctxt.state.renderbuffer = id;
+                ctxt.state.audit("glBindRenderbuffer", 0, id as u64);
                 ctxt.gl.RenderbufferStorage(gl::RENDERBUFFER, format,
                                             width as gl::types::GLsizei,
                                             height as gl::types::GLsizei);
@@ -414,6 +468,7 @@ impl RenderBufferAny {
                 ctxt.gl.GenRenderbuffersEXT(1, &mut id);
                 ctxt.gl.BindRenderbufferEXT(gl::RENDERBUFFER_EXT, id);
                 ctxt.state.renderbuffer = id;
+                ctxt.state.audit("glBindRenderbuffer", 0, id as u64);
 
                 let samples = samples.unwrap();
                 ctxt.gl.RenderbufferStorageMultisampleEXT(gl::RENDERBUFFER_EXT,
@@ -426,6 +481,7 @@ impl RenderBufferAny {
                 ctxt.gl.GenRenderbuffersEXT(1, &mut id);
                 ctxt.gl.BindRenderbufferEXT(gl::RENDERBUFFER_EXT, id);
                 ctxt.state.renderbuffer = id;
+                ctxt.state.audit("glBindRenderbuffer", 0, id as u64);
                 ctxt.gl.RenderbufferStorageEXT(gl::RENDERBUFFER_EXT, format,
                                                width as gl::types::GLsizei,
                                                height as gl::types::GLsizei);
@@ -473,14 +529,17 @@ impl RenderBufferAny {
     /// Determines the number of depth and stencil bits in the format of this render buffer.
     pub fn get_depth_stencil_bits(&self) -> (u16, u16) {
         unsafe {
-            let ctxt = self.context.make_current();
+            let mut ctxt = self.context.make_current();
             let mut depth_bits: gl::types::GLint = 0;
             let mut stencil_bits: gl::types::GLint = 0;
             ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, self.id);
+            ctxt.state.audit("glBindRenderbuffer", 0, self.id as u64);
             // FIXME: GL version considerations
             ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_DEPTH_SIZE, &mut depth_bits);
             ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_STENCIL_SIZE, &mut stencil_bits);
             ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, 0);
+            ctxt.state.renderbuffer = 0;
+            ctxt.state.audit("glBindRenderbuffer", 0, 0);
             (depth_bits as u16, stencil_bits as u16)
         }
     }
@@ -494,26 +553,34 @@ impl Drop for RenderBufferAny {
             // removing FBOs which contain this buffer
             FramebuffersContainer::purge_renderbuffer(&mut ctxt, self.id);
 
-            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-               ctxt.version >= &Version(Api::GlEs, 2, 0)
-            {
-                if ctxt.state.renderbuffer == self.id {
-                    ctxt.state.renderbuffer = 0;
-                }
+            if !ctxt.garbage.defer(GarbageObject::Renderbuffer(self.id)) {
+                delete_renderbuffer(&mut ctxt, self.id);
+            }
+        }
 
-                ctxt.gl.DeleteRenderbuffers(1, [ self.id ].as_ptr());
+        self.context.forget_in_shared_contexts(SharedObject::Renderbuffer(self.id));
+    }
+}
 
-            } else if ctxt.extensions.gl_ext_framebuffer_object {
-                if ctxt.state.renderbuffer == self.id {
-                    ctxt.state.renderbuffer = 0;
-                }
+/// Deletes a renderbuffer and removes it from the state cache.
+pub(crate) unsafe fn delete_renderbuffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    forget_renderbuffer(ctxt, id);
 
-                ctxt.gl.DeleteRenderbuffersEXT(1, [ self.id ].as_ptr());
+    if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+       ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        ctxt.gl.DeleteRenderbuffers(1, [ id ].as_ptr());
+    } else if ctxt.extensions.gl_ext_framebuffer_object {
+        ctxt.gl.DeleteRenderbuffersEXT(1, [ id ].as_ptr());
+    } else {
+        unreachable!();
+    }
+}
 
-            } else {
-                unreachable!();
-            }
-        }
+/// Removes a renderbuffer from the state cache.
+pub(crate) fn forget_renderbuffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    if ctxt.state.renderbuffer == id {
+        ctxt.state.renderbuffer = 0;
     }
 }
 
@@ -0,0 +1,237 @@
+use crate::{DrawParameters, BlitMask, Attachment};
+use crate::Rect;
+use crate::ClearValues;
+use crate::BlitTarget;
+
+use crate::{Program, Surface};
+use crate::DrawError;
+use crate::{BlitError, ReadError};
+use crate::pixel_buffer::PixelBuffer;
+use crate::texture::{ClientFormat, PixelValue};
+use crate::TextureBarrierNotSupportedError;
+
+use crate::index;
+use crate::vertex;
+use crate::uniforms;
+use crate::framebuffer::{SimpleFrameBuffer, MultiOutputFrameBuffer};
+use crate::uniforms::MagnifySamplerFilter;
+
+/// A rectangular area of another surface. Built with `Surface::sub_region`.
+///
+/// The area behaves like a surface of its own: its dimensions are the dimensions of the
+/// rectangle and its (0,0) coordinate is the bottom-left corner of the rectangle. The viewport
+/// and the scissor box of the draw parameters are relative to the area, and nothing is drawn or
+/// cleared outside of it.
+///
+/// ```no_run
+/// # fn example(mut target: glium::Frame) {
+/// use glium::{Rect, Surface};
+///
+/// // an editor with a 3D view on the left half of the window
+/// let (width, height) = target.get_dimensions();
+/// let mut view = target.sub_region(Rect { left: 0, bottom: 0, width: width / 2, height });
+/// view.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+/// # }
+/// ```
+///
+/// The destination of a blit is not clipped to the area.
+pub struct SubSurface<'s, S: ?Sized + 's> {
+    surface: &'s mut S,
+    rect: Rect,
+}
+
+impl<'s, S: ?Sized> SubSurface<'s, S> where S: Surface {
+    /// Builds a view of the area `rect` of `surface`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `rect` is not inside of the surface.
+    pub fn new(surface: &'s mut S, rect: Rect) -> SubSurface<'s, S> {
+        let (width, height) = surface.get_dimensions();
+        assert!(matches!(rect.left.checked_add(rect.width), Some(right) if right <= width) &&
+                matches!(rect.bottom.checked_add(rect.height), Some(top) if top <= height),
+                "The sub-region is outside of the surface");
+
+        SubSurface {
+            surface,
+            rect,
+        }
+    }
+
+    /// Returns the area of the parent surface that this object covers.
+    #[inline]
+    pub fn get_rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Converts a rectangle relative to the area into a rectangle of the parent surface, and
+    /// removes the part that is outside of the area.
+    fn to_parent(&self, rect: &Rect) -> Rect {
+        rect.translate(self.rect.left, self.rect.bottom).intersection(&self.rect)
+            .unwrap_or(Rect { left: self.rect.left, bottom: self.rect.bottom, width: 0, height: 0 })
+    }
+
+    #[inline]
+    fn target_to_parent(&self, target: &BlitTarget) -> BlitTarget {
+        BlitTarget {
+            left: target.left + self.rect.left,
+            bottom: target.bottom + self.rect.bottom,
+            .. *target
+        }
+    }
+}
+
+impl<'s, S: ?Sized> Surface for SubSurface<'s, S> where S: Surface {
+    fn clear(&mut self, rect: Option<&Rect>, values: &ClearValues) {
+        let rect = match rect {
+            Some(rect) => self.to_parent(rect),
+            None => self.rect,
+        };
+
+        if rect.width == 0 || rect.height == 0 {
+            return;
+        }
+
+        self.surface.clear(Some(&rect), values);
+    }
+
+    fn invalidate_rect(&mut self, rect: &Rect, attachments: &[Attachment]) {
+        let rect = self.to_parent(rect);
+        if rect.width == 0 || rect.height == 0 {
+            return;
+        }
+
+        self.surface.invalidate_rect(&rect, attachments);
+    }
+
+    fn read_to_pixel_buffer<P>(&self, rect: &Rect, dest: &PixelBuffer<P>, format: ClientFormat)
+                               -> Result<(), ReadError> where P: PixelValue
+    {
+        assert!(matches!(rect.left.checked_add(rect.width),
+                         Some(right) if right <= self.rect.width) &&
+                matches!(rect.bottom.checked_add(rect.height),
+                         Some(top) if top <= self.rect.height),
+                "The rectangle to read is outside of the surface");
+
+        let rect = rect.translate(self.rect.left, self.rect.bottom);
+        self.surface.read_to_pixel_buffer(&rect, dest, format)
+    }
+
+    #[inline]
+    fn get_dimensions(&self) -> (u32, u32) {
+        (self.rect.width, self.rect.height)
+    }
+
+    #[inline]
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        self.surface.get_depth_buffer_bits()
+    }
+
+    #[inline]
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        self.surface.get_stencil_buffer_bits()
+    }
+
+    fn draw<'a, 'b, V, I, U>(&mut self, vb: V, ib: I, program: &Program, uniforms: &U,
+                             draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError>
+                             where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
+                             V: vertex::MultiVerticesSource<'b>
+    {
+        // the viewport can go beyond the area, in which case the scissor box clips the result
+        let viewport = match draw_parameters.viewport {
+            Some(viewport) => viewport.translate(self.rect.left, self.rect.bottom),
+            None => self.rect,
+        };
+
+        let scissor = match draw_parameters.scissor {
+            Some(ref scissor) => self.to_parent(scissor),
+            None => self.rect,
+        };
+
+        let draw_parameters = DrawParameters {
+            viewport: Some(viewport),
+            scissor: Some(scissor),
+            .. draw_parameters.clone()
+        };
+
+        self.surface.draw(vb, ib, program, uniforms, &draw_parameters)
+    }
+
+    #[inline]
+    fn texture_barrier(&self) -> Result<(), TextureBarrierNotSupportedError> {
+        self.surface.texture_barrier()
+    }
+
+    #[inline]
+    fn blit_color<T>(&self, source_rect: &Rect, target: &T, target_rect: &BlitTarget,
+                     filter: MagnifySamplerFilter) where T: Surface
+    {
+        let source_rect = source_rect.translate(self.rect.left, self.rect.bottom);
+        self.surface.blit_color(&source_rect, target, target_rect, filter)
+    }
+
+    #[inline]
+    fn blit_buffers<T>(&self, source_rect: &Rect, target: &T, target_rect: &BlitTarget,
+                       filter: MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where T: Surface
+    {
+        let source_rect = source_rect.translate(self.rect.left, self.rect.bottom);
+        self.surface.blit_buffers(&source_rect, target, target_rect, filter, mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_frame(source_rect, target_rect, filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter,
+                                                      mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect,
+                                                           filter, mask).unwrap()
+    }
+
+    #[inline]
+    fn try_blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                                   filter: MagnifySamplerFilter, mask: BlitMask)
+                                   -> Result<(), BlitError>
+    {
+        self.surface.try_blit_buffers_from_frame(source_rect, &self.target_to_parent(target_rect),
+                                                 filter, mask)
+    }
+
+    #[inline]
+    fn try_blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                                source_rect: &Rect, target_rect: &BlitTarget,
+                                                filter: MagnifySamplerFilter, mask: BlitMask)
+                                                -> Result<(), BlitError>
+    {
+        self.surface.try_blit_buffers_from_simple_framebuffer(source, source_rect,
+                                                              &self.target_to_parent(target_rect),
+                                                              filter, mask)
+    }
+
+    #[inline]
+    fn try_blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                     source_rect: &Rect, target_rect: &BlitTarget,
+                                                     filter: MagnifySamplerFilter, mask: BlitMask)
+                                                     -> Result<(), BlitError>
+    {
+        self.surface.try_blit_buffers_from_multioutput_framebuffer(source, source_rect,
+                                                                   &self.target_to_parent(target_rect),
+                                                                   filter, mask)
+    }
+}
@@ -0,0 +1,107 @@
+use std::cell::{Cell, Ref, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+
+/// Render target whose dimensions follow the dimensions of the window.
+///
+/// The content, for example the textures of a G-buffer, is built by a user callback that
+/// receives the dimensions of the default framebuffer. Each time the context is resized with
+/// `Display::resize` or `Context::resize`, the callback is called again with the new dimensions
+/// and the old content is replaced.
+///
+/// ```no_run
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+/// use glium::framebuffer::WindowSized;
+/// use glium::texture::{DepthTexture2d, Texture2d};
+///
+/// let gbuffer = WindowSized::new(&display, |facade, (width, height)| {
+///     (Texture2d::empty(facade, width, height).unwrap(),
+///      DepthTexture2d::empty(facade, width, height).unwrap())
+/// });
+///
+/// // later, when drawing
+/// let (ref color, ref depth) = *gbuffer.borrow();
+/// # }
+/// ```
+pub struct WindowSized<T> {
+    inner: Rc<WindowSizedImpl<T>>,
+}
+
+struct WindowSizedImpl<T> {
+    context: Rc<Context>,
+    content: RefCell<T>,
+    dimensions: Cell<(u32, u32)>,
+    build: RefCell<Box<dyn FnMut(&Rc<Context>, (u32, u32)) -> T>>,
+}
+
+/// Object that is stored in the context and notified when the context is resized.
+pub(crate) trait WindowSizedTarget {
+    /// Rebuilds the content with the given dimensions.
+    fn resize(&self, dimensions: (u32, u32));
+}
+
+impl<T> WindowSized<T> where T: 'static {
+    /// Builds the content with the current dimensions of the default framebuffer, and registers
+    /// it in the context.
+    pub fn new<F, B>(facade: &F, mut build: B) -> WindowSized<T>
+                     where F: ?Sized + Facade, B: FnMut(&Rc<Context>, (u32, u32)) -> T + 'static
+    {
+        let context = facade.get_context().clone();
+        let dimensions = context.get_framebuffer_dimensions();
+        let content = build(&context, dimensions);
+
+        let inner = Rc::new(WindowSizedImpl {
+            context: context.clone(),
+            content: RefCell::new(content),
+            dimensions: Cell::new(dimensions),
+            build: RefCell::new(Box::new(build)),
+        });
+
+        let target: Rc<dyn WindowSizedTarget> = inner.clone();
+        context.register_window_sized_target(Rc::downgrade(&target));
+
+        WindowSized {
+            inner,
+        }
+    }
+}
+
+impl<T> WindowSized<T> {
+    /// Returns the content.
+    ///
+    /// # Panic
+    ///
+    /// The context panics if it is resized while the returned `Ref` is alive.
+    #[inline]
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.inner.content.borrow()
+    }
+
+    /// Returns the dimensions that were passed to the callback the last time it was called.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        self.inner.dimensions.get()
+    }
+}
+
+impl<T> fmt::Debug for WindowSized<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "WindowSized {{ dimensions: {:?} }}", self.inner.dimensions.get())
+    }
+}
+
+impl<T> WindowSizedTarget for WindowSizedImpl<T> {
+    fn resize(&self, dimensions: (u32, u32)) {
+        if self.dimensions.get() == dimensions {
+            return;
+        }
+
+        let content = (self.build.borrow_mut())(&self.context, dimensions);
+        *self.content.borrow_mut() = content;
+        self.dimensions.set(dimensions);
+    }
+}
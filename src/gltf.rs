@@ -0,0 +1,682 @@
+/*!
+Uploading the meshes, textures and materials of glTF documents.
+
+This module doesn't render anything. It turns a document parsed by the `gltf` crate into vertex
+buffers, index buffers and textures, and gathers the parameters of each material, so that they
+can be used with any shader.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::Surface;
+use glium::uniforms::Sampler;
+
+let (document, buffers, images) = gltf::import("model.gltf").unwrap();
+let model = glium::gltf::Model::load(&display, &document, &buffers, &images).unwrap();
+
+# let program: glium::Program = unimplemented!();
+let mut target = display.draw();
+for primitive in model.meshes.iter().flat_map(|mesh| &mesh.primitives) {
+    let material = model.material(primitive);
+    let uniforms = glium::uniform! {
+        base_color_factor: material.base_color_factor,
+        base_color: match material.base_color_texture {
+            Some(texture) => Sampler(&model.srgb_textures[texture.index], texture.sampler),
+            None => unimplemented!(),
+        },
+    };
+    target.draw(&primitive.vertices, primitive.indices(), &program, &uniforms,
+                &Default::default()).unwrap();
+}
+target.finish().unwrap();
+# }
+```
+
+Vertices are interleaved, with one attribute for each one of the primitive. Their names are
+`position`, `normal`, `tangent`, `tex_coords_0`, `tex_coords_1`, `color_0`, `joints_0` and
+`weights_0`. All of them are floats, except the joints which are four `u16`s. Morph targets
+are ignored.
+
+The images used as base color or emissive textures are uploaded as `SrgbTexture2d`s, and the
+others as `Texture2d`s. An image used both ways is uploaded twice. The rows are uploaded top
+first, so that the texture coordinates of the document can be used as they are.
+
+Only available if the `gltf` feature is enabled.
+
+*/
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::backend::Facade;
+use crate::index::{self, IndexBuffer, IndexBufferAny, IndicesSource, NoIndices, PrimitiveType};
+use crate::texture::{MipmapsOption, RawImage2d, SrgbTexture2d, Texture2d};
+use crate::texture::TextureCreationError;
+use crate::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior};
+use crate::uniforms::SamplerWrapFunction;
+use crate::vertex::{self, AttributeType, VertexBufferAny, VertexFormat};
+
+/// Error that can happen while uploading a glTF document.
+#[derive(Debug)]
+pub enum GltfError {
+    /// A primitive doesn't have positions.
+    MissingPositions {
+        /// Index of the mesh.
+        mesh: usize,
+        /// Index of the primitive in the mesh.
+        primitive: usize,
+    },
+
+    /// The content of a buffer of the document wasn't provided. Contains its index.
+    MissingBuffer(usize),
+
+    /// The content of an image of the document wasn't provided. Contains its index.
+    MissingImage(usize),
+
+    /// A vertex buffer couldn't be created.
+    VertexBufferCreation(vertex::BufferCreationError),
+
+    /// An index buffer couldn't be created.
+    IndexBufferCreation(index::BufferCreationError),
+
+    /// A texture couldn't be created.
+    TextureCreation(TextureCreationError),
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            GltfError::MissingPositions { mesh, primitive } => {
+                write!(fmt, "Primitive {} of mesh {} has no positions", primitive, mesh)
+            },
+            GltfError::MissingBuffer(index) => write!(fmt, "Missing data of buffer {}", index),
+            GltfError::MissingImage(index) => write!(fmt, "Missing data of image {}", index),
+            GltfError::VertexBufferCreation(err) => write!(fmt, "{}", err),
+            GltfError::IndexBufferCreation(err) => write!(fmt, "{}", err),
+            GltfError::TextureCreation(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for GltfError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            GltfError::VertexBufferCreation(ref err) => Some(err),
+            GltfError::IndexBufferCreation(ref err) => Some(err),
+            GltfError::TextureCreation(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<vertex::BufferCreationError> for GltfError {
+    #[inline]
+    fn from(err: vertex::BufferCreationError) -> GltfError {
+        GltfError::VertexBufferCreation(err)
+    }
+}
+
+impl From<index::BufferCreationError> for GltfError {
+    #[inline]
+    fn from(err: index::BufferCreationError) -> GltfError {
+        GltfError::IndexBufferCreation(err)
+    }
+}
+
+impl From<TextureCreationError> for GltfError {
+    #[inline]
+    fn from(err: TextureCreationError) -> GltfError {
+        GltfError::TextureCreation(err)
+    }
+}
+
+/// The meshes, materials and textures of a glTF document.
+///
+/// The meshes and materials are in the same order as in the document.
+#[derive(Debug)]
+pub struct Model {
+    /// The meshes of the document.
+    pub meshes: Vec<Mesh>,
+    /// The materials of the document.
+    pub materials: Vec<Material>,
+    /// The base color and emissive textures.
+    pub srgb_textures: Vec<SrgbTexture2d>,
+    /// The other textures, whose content isn't a color.
+    pub textures: Vec<Texture2d>,
+}
+
+/// A mesh, made of primitives that are drawn separately.
+#[derive(Debug)]
+pub struct Mesh {
+    /// The name of the mesh.
+    pub name: Option<String>,
+    /// The primitives of the mesh.
+    pub primitives: Vec<Primitive>,
+}
+
+/// Geometry drawn with a single material.
+#[derive(Debug)]
+pub struct Primitive {
+    /// The interleaved attributes of the vertices.
+    pub vertices: VertexBufferAny,
+    /// The indices, or `None` if the vertices are drawn in order.
+    pub index_buffer: Option<IndexBufferAny>,
+    /// How the vertices are assembled.
+    pub primitive_type: PrimitiveType,
+    /// Index of the material in `Model::materials`, or `None` for the default material.
+    pub material: Option<usize>,
+}
+
+impl Primitive {
+    /// Returns the indices to pass to `Surface::draw`.
+    #[inline]
+    pub fn indices(&self) -> IndicesSource<'_> {
+        match self.index_buffer {
+            Some(ref buffer) => buffer.into(),
+            None => NoIndices(self.primitive_type).into(),
+        }
+    }
+}
+
+/// The parameters of the metallic-roughness model of a material.
+///
+/// The factors multiply the content of the corresponding textures.
+#[derive(Debug, Clone)]
+pub struct Material {
+    /// The name of the material.
+    pub name: Option<String>,
+    /// The linear base color.
+    pub base_color_factor: [f32; 4],
+    /// The base color, in `Model::srgb_textures`.
+    pub base_color_texture: Option<TextureRef>,
+    /// The metalness.
+    pub metallic_factor: f32,
+    /// The roughness.
+    pub roughness_factor: f32,
+    /// The roughness in the green channel and the metalness in the blue channel, in
+    /// `Model::textures`.
+    pub metallic_roughness_texture: Option<TextureRef>,
+    /// The tangent space normal map, in `Model::textures`.
+    pub normal_texture: Option<TextureRef>,
+    /// Multiplies the X and Y components of the normals of the normal map.
+    pub normal_scale: f32,
+    /// The ambient occlusion in the red channel, in `Model::textures`.
+    pub occlusion_texture: Option<TextureRef>,
+    /// How much the occlusion is applied, between 0 and 1.
+    pub occlusion_strength: f32,
+    /// The linear emitted color.
+    pub emissive_factor: [f32; 3],
+    /// The emitted color, in `Model::srgb_textures`.
+    pub emissive_texture: Option<TextureRef>,
+    /// How the alpha of the base color is interpreted.
+    pub alpha_mode: AlphaMode,
+    /// True if the back faces must be drawn too.
+    pub double_sided: bool,
+}
+
+impl Default for Material {
+    /// The material of the primitives that don't have one.
+    fn default() -> Material {
+        Material {
+            name: None,
+            base_color_factor: [1.0; 4],
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive_factor: [0.0; 3],
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+        }
+    }
+}
+
+/// How the alpha of the base color of a material is interpreted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AlphaMode {
+    /// The alpha is ignored.
+    Opaque,
+    /// The fragments whose alpha is lower than the cutoff are discarded, the others are opaque.
+    Mask(f32),
+    /// The fragments are blended with the background.
+    Blend,
+}
+
+/// A texture used by a material.
+#[derive(Debug, Copy, Clone)]
+pub struct TextureRef {
+    /// Index of the texture in `Model::srgb_textures` or `Model::textures`, depending on how
+    /// it is used.
+    pub index: usize,
+    /// The set of texture coordinates, `0` for `tex_coords_0` and `1` for `tex_coords_1`.
+    pub tex_coord: u32,
+    /// The filters and the wrap functions of the texture.
+    pub sampler: SamplerBehavior,
+}
+
+impl Model {
+    /// Uploads the content of a document, as returned by `gltf::import`.
+    ///
+    /// `buffers` and `images` must contain the data of all the buffers and images of the
+    /// document, in the same order.
+    pub fn load<F: Facade + ?Sized>(facade: &F, document: &::gltf::Document,
+                                    buffers: &[::gltf::buffer::Data],
+                                    images: &[::gltf::image::Data]) -> Result<Model, GltfError>
+    {
+        if buffers.len() < document.buffers().len() {
+            return Err(GltfError::MissingBuffer(buffers.len()));
+        }
+        if images.len() < document.images().len() {
+            return Err(GltfError::MissingImage(images.len()));
+        }
+
+        let mut textures = Textures::default();
+        let materials = document.materials()
+                                .map(|material| load_material(facade, &material, images,
+                                                              &mut textures))
+                                .collect::<Result<_, _>>()?;
+
+        let meshes = document.meshes()
+                             .map(|mesh| load_mesh(facade, &mesh, buffers))
+                             .collect::<Result<_, _>>()?;
+
+        Ok(Model {
+            meshes,
+            materials,
+            srgb_textures: textures.srgb,
+            textures: textures.linear,
+        })
+    }
+
+    /// Returns the material of a primitive, or the default one.
+    pub fn material(&self, primitive: &Primitive) -> Cow<'_, Material> {
+        match primitive.material.and_then(|index| self.materials.get(index)) {
+            Some(material) => Cow::Borrowed(material),
+            None => Cow::Owned(Material::default()),
+        }
+    }
+}
+
+/// The textures uploaded so far, and the index of each image in them.
+#[derive(Default)]
+struct Textures {
+    srgb: Vec<SrgbTexture2d>,
+    linear: Vec<Texture2d>,
+    srgb_images: HashMap<usize, usize>,
+    linear_images: HashMap<usize, usize>,
+}
+
+fn load_material<F: Facade + ?Sized>(facade: &F, material: &::gltf::Material<'_>,
+                                     images: &[::gltf::image::Data], textures: &mut Textures)
+                                     -> Result<Material, GltfError>
+{
+    let pbr = material.pbr_metallic_roughness();
+    let normal = material.normal_texture();
+    let occlusion = material.occlusion_texture();
+
+    let mut texture = |texture: ::gltf::Texture<'_>, tex_coord: u32, srgb: bool| {
+        let image = texture.source().index();
+        let data = images.get(image).ok_or(GltfError::MissingImage(image))?;
+
+        let index = if srgb {
+            match textures.srgb_images.get(&image) {
+                Some(&index) => index,
+                None => {
+                    let pixels = RawImage2d::from_raw_rgba(rgba8(data), (data.width, data.height));
+                    textures.srgb.push(SrgbTexture2d::with_mipmaps(facade, pixels,
+                                                   MipmapsOption::AutoGeneratedMipmaps)?);
+                    textures.srgb_images.insert(image, textures.srgb.len() - 1);
+                    textures.srgb.len() - 1
+                },
+            }
+        } else {
+            match textures.linear_images.get(&image) {
+                Some(&index) => index,
+                None => {
+                    textures.linear.push(upload_linear(facade, data)?);
+                    textures.linear_images.insert(image, textures.linear.len() - 1);
+                    textures.linear.len() - 1
+                },
+            }
+        };
+
+        Ok::<_, GltfError>(TextureRef {
+            index,
+            tex_coord,
+            sampler: sampler_behavior(&texture.sampler()),
+        })
+    };
+
+    Ok(Material {
+        name: material.name().map(|name| name.to_owned()),
+        base_color_factor: pbr.base_color_factor(),
+        base_color_texture: pbr.base_color_texture()
+                               .map(|info| texture(info.texture(), info.tex_coord(), true))
+                               .transpose()?,
+        metallic_factor: pbr.metallic_factor(),
+        roughness_factor: pbr.roughness_factor(),
+        metallic_roughness_texture: pbr.metallic_roughness_texture()
+                                       .map(|info| texture(info.texture(), info.tex_coord(),
+                                                           false))
+                                       .transpose()?,
+        normal_texture: normal.as_ref()
+                              .map(|info| texture(info.texture(), info.tex_coord(), false))
+                              .transpose()?,
+        normal_scale: normal.as_ref().map_or(1.0, |info| info.scale()),
+        occlusion_texture: occlusion.as_ref()
+                                    .map(|info| texture(info.texture(), info.tex_coord(), false))
+                                    .transpose()?,
+        occlusion_strength: occlusion.as_ref().map_or(1.0, |info| info.strength()),
+        emissive_factor: material.emissive_factor(),
+        emissive_texture: material.emissive_texture()
+                                  .map(|info| texture(info.texture(), info.tex_coord(), true))
+                                  .transpose()?,
+        alpha_mode: match material.alpha_mode() {
+            ::gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
+            ::gltf::material::AlphaMode::Mask => {
+                AlphaMode::Mask(material.alpha_cutoff().unwrap_or(0.5))
+            },
+            ::gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+        },
+        double_sided: material.double_sided(),
+    })
+}
+
+fn sampler_behavior(sampler: &::gltf::texture::Sampler<'_>) -> SamplerBehavior {
+    use ::gltf::texture::{MagFilter, MinFilter, WrappingMode};
+
+    let wrap = |mode| match mode {
+        WrappingMode::ClampToEdge => SamplerWrapFunction::Clamp,
+        WrappingMode::MirroredRepeat => SamplerWrapFunction::Mirror,
+        WrappingMode::Repeat => SamplerWrapFunction::Repeat,
+    };
+
+    SamplerBehavior {
+        wrap_function: (wrap(sampler.wrap_s()), wrap(sampler.wrap_t()),
+                        SamplerWrapFunction::Repeat),
+        minify_filter: match sampler.min_filter() {
+            Some(MinFilter::Nearest) => MinifySamplerFilter::Nearest,
+            Some(MinFilter::Linear) => MinifySamplerFilter::Linear,
+            Some(MinFilter::NearestMipmapNearest) => MinifySamplerFilter::NearestMipmapNearest,
+            Some(MinFilter::LinearMipmapNearest) => MinifySamplerFilter::LinearMipmapNearest,
+            Some(MinFilter::NearestMipmapLinear) => MinifySamplerFilter::NearestMipmapLinear,
+            Some(MinFilter::LinearMipmapLinear) | None => MinifySamplerFilter::LinearMipmapLinear,
+        },
+        magnify_filter: match sampler.mag_filter() {
+            Some(MagFilter::Nearest) => MagnifySamplerFilter::Nearest,
+            Some(MagFilter::Linear) | None => MagnifySamplerFilter::Linear,
+        },
+        ..Default::default()
+    }
+}
+
+/// Returns the pixels of an image as RGBA with 8 bits per channel, for the sRGB textures.
+fn rgba8(image: &::gltf::image::Data) -> Vec<u8> {
+    use ::gltf::image::Format;
+
+    let pixels = &image.pixels;
+    match image.format {
+        Format::R8 => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        Format::R8G8 => pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        Format::R8G8B8 => pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        Format::R8G8B8A8 => pixels.clone(),
+        _ => {
+            texels_f32(image).chunks_exact(channels(image.format))
+                             .flat_map(|p| expand(p, 1.0))
+                             .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+                             .collect()
+        },
+    }
+}
+
+/// Uploads an image that isn't a color, keeping the precision of 16 bits and float images.
+fn upload_linear<F: Facade + ?Sized>(facade: &F, image: &::gltf::image::Data)
+                                     -> Result<Texture2d, TextureCreationError>
+{
+    use ::gltf::image::Format;
+
+    let dimensions = (image.width, image.height);
+    let mipmaps = MipmapsOption::AutoGeneratedMipmaps;
+    match image.format {
+        Format::R8 | Format::R8G8 | Format::R8G8B8 | Format::R8G8B8A8 => {
+            Texture2d::with_mipmaps(facade, RawImage2d::from_raw_rgba(rgba8(image), dimensions),
+                                    mipmaps)
+        },
+        Format::R16 | Format::R16G16 | Format::R16G16B16 | Format::R16G16B16A16 => {
+            let texels: Vec<u16> = image.pixels.chunks_exact(2)
+                                        .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+                                        .collect();
+            let pixels = texels.chunks_exact(channels(image.format))
+                               .flat_map(|p| expand(p, u16::MAX))
+                               .collect();
+            Texture2d::with_mipmaps(facade, RawImage2d::from_raw_rgba(pixels, dimensions),
+                                    mipmaps)
+        },
+        Format::R32G32B32FLOAT | Format::R32G32B32A32FLOAT => {
+            let pixels = texels_f32(image).chunks_exact(channels(image.format))
+                                          .flat_map(|p| expand(p, 1.0))
+                                          .collect();
+            Texture2d::with_mipmaps(facade, RawImage2d::from_raw_rgba(pixels, dimensions),
+                                    mipmaps)
+        },
+    }
+}
+
+/// Returns the channels of an image that isn't 8 bits as floats, between 0 and 1 for the
+/// integer formats.
+fn texels_f32(image: &::gltf::image::Data) -> Vec<f32> {
+    use ::gltf::image::Format;
+
+    match image.format {
+        Format::R32G32B32FLOAT | Format::R32G32B32A32FLOAT => {
+            image.pixels.chunks_exact(4)
+                        .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+                        .collect()
+        },
+        _ => {
+            image.pixels.chunks_exact(2)
+                        .map(|b| u16::from_ne_bytes([b[0], b[1]]) as f32 / 65535.0)
+                        .collect()
+        },
+    }
+}
+
+/// Returns the number of channels of a format.
+fn channels(format: ::gltf::image::Format) -> usize {
+    use ::gltf::image::Format;
+
+    match format {
+        Format::R8 | Format::R16 => 1,
+        Format::R8G8 | Format::R16G16 => 2,
+        Format::R8G8B8 | Format::R16G16B16 | Format::R32G32B32FLOAT => 3,
+        Format::R8G8B8A8 | Format::R16G16B16A16 | Format::R32G32B32A32FLOAT => 4,
+    }
+}
+
+/// Turns a pixel of one to four channels into RGBA. Grayscale images are replicated in the
+/// color channels, and the alpha is `one` when missing.
+fn expand<T: Copy>(pixel: &[T], one: T) -> [T; 4] {
+    match *pixel {
+        [l] => [l, l, l, one],
+        [l, a] => [l, l, l, a],
+        [r, g, b] => [r, g, b, one],
+        [r, g, b, a, ..] => [r, g, b, a],
+        [] => [one; 4],
+    }
+}
+
+fn load_mesh<F: Facade + ?Sized>(facade: &F, mesh: &::gltf::Mesh<'_>,
+                                 buffers: &[::gltf::buffer::Data]) -> Result<Mesh, GltfError>
+{
+    let primitives = mesh.primitives().map(|primitive| {
+        let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &data[..]));
+
+        let positions = reader.read_positions().ok_or(GltfError::MissingPositions {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+        })?;
+
+        let mut attributes = Attributes::default();
+        attributes.push(ATTRIBUTE_POSITION, bytes(positions.flatten(), f32::to_ne_bytes));
+        if let Some(normals) = reader.read_normals() {
+            attributes.push(ATTRIBUTE_NORMAL, bytes(normals.flatten(), f32::to_ne_bytes));
+        }
+        if let Some(tangents) = reader.read_tangents() {
+            attributes.push(ATTRIBUTE_TANGENT, bytes(tangents.flatten(), f32::to_ne_bytes));
+        }
+        if let Some(tex_coords) = reader.read_tex_coords(0) {
+            let tex_coords = tex_coords.into_f32().flatten();
+            attributes.push(ATTRIBUTE_TEX_COORDS_0, bytes(tex_coords, f32::to_ne_bytes));
+        }
+        if let Some(tex_coords) = reader.read_tex_coords(1) {
+            let tex_coords = tex_coords.into_f32().flatten();
+            attributes.push(ATTRIBUTE_TEX_COORDS_1, bytes(tex_coords, f32::to_ne_bytes));
+        }
+        if let Some(colors) = reader.read_colors(0) {
+            let colors = colors.into_rgba_f32().flatten();
+            attributes.push(ATTRIBUTE_COLOR_0, bytes(colors, f32::to_ne_bytes));
+        }
+        if let Some(joints) = reader.read_joints(0) {
+            let joints = joints.into_u16().flatten();
+            attributes.push(ATTRIBUTE_JOINTS_0, bytes(joints, u16::to_ne_bytes));
+        }
+        if let Some(weights) = reader.read_weights(0) {
+            let weights = weights.into_f32().flatten();
+            attributes.push(ATTRIBUTE_WEIGHTS_0, bytes(weights, f32::to_ne_bytes));
+        }
+
+        let vertices = attributes.upload(facade)?;
+
+        let primitive_type = match primitive.mode() {
+            ::gltf::mesh::Mode::Points => PrimitiveType::Points,
+            ::gltf::mesh::Mode::Lines => PrimitiveType::LinesList,
+            ::gltf::mesh::Mode::LineLoop => PrimitiveType::LineLoop,
+            ::gltf::mesh::Mode::LineStrip => PrimitiveType::LineStrip,
+            ::gltf::mesh::Mode::Triangles => PrimitiveType::TrianglesList,
+            ::gltf::mesh::Mode::TriangleStrip => PrimitiveType::TriangleStrip,
+            ::gltf::mesh::Mode::TriangleFan => PrimitiveType::TriangleFan,
+        };
+
+        let index_buffer = match reader.read_indices() {
+            Some(indices) => {
+                let indices: Vec<u32> = indices.into_u32().collect();
+                // 16 bits indices are supported everywhere
+                Some(if indices.iter().all(|&index| index <= u16::MAX as u32) {
+                    let indices: Vec<u16> = indices.iter().map(|&index| index as u16).collect();
+                    IndexBuffer::new(facade, primitive_type, &indices)?.into()
+                } else {
+                    IndexBuffer::new(facade, primitive_type, &indices)?.into()
+                })
+            },
+            None => None,
+        };
+
+        Ok(Primitive {
+            vertices,
+            index_buffer,
+            primitive_type,
+            material: primitive.material().index(),
+        })
+    }).collect::<Result<_, GltfError>>()?;
+
+    Ok(Mesh {
+        name: mesh.name().map(|name| name.to_owned()),
+        primitives,
+    })
+}
+
+/// Concatenates the bytes of the components of an attribute.
+#[inline]
+fn bytes<I, T, const N: usize>(values: I, to_bytes: fn(T) -> [u8; N]) -> Vec<u8>
+    where I: Iterator<Item = T>
+{
+    values.flat_map(to_bytes).collect()
+}
+
+/// An attribute that a primitive can have.
+struct Attribute {
+    bit: u8,
+    name: &'static str,
+    ty: AttributeType,
+}
+
+const ATTRIBUTE_POSITION: Attribute =
+    Attribute { bit: 1 << 0, name: "position", ty: AttributeType::F32F32F32 };
+const ATTRIBUTE_NORMAL: Attribute =
+    Attribute { bit: 1 << 1, name: "normal", ty: AttributeType::F32F32F32 };
+const ATTRIBUTE_TANGENT: Attribute =
+    Attribute { bit: 1 << 2, name: "tangent", ty: AttributeType::F32F32F32F32 };
+const ATTRIBUTE_TEX_COORDS_0: Attribute =
+    Attribute { bit: 1 << 3, name: "tex_coords_0", ty: AttributeType::F32F32 };
+const ATTRIBUTE_TEX_COORDS_1: Attribute =
+    Attribute { bit: 1 << 4, name: "tex_coords_1", ty: AttributeType::F32F32 };
+const ATTRIBUTE_COLOR_0: Attribute =
+    Attribute { bit: 1 << 5, name: "color_0", ty: AttributeType::F32F32F32F32 };
+const ATTRIBUTE_JOINTS_0: Attribute =
+    Attribute { bit: 1 << 6, name: "joints_0", ty: AttributeType::U16U16U16U16 };
+const ATTRIBUTE_WEIGHTS_0: Attribute =
+    Attribute { bit: 1 << 7, name: "weights_0", ty: AttributeType::F32F32F32F32 };
+
+/// The vertex formats built so far, for each combination of attributes.
+///
+/// A `VertexFormat` must be `'static`. Leaking one format for each of the 256 possible
+/// combinations is cheaper than leaking one for each primitive.
+static FORMATS: Mutex<Vec<(u8, VertexFormat)>> = Mutex::new(Vec::new());
+
+/// The attributes of the vertices of a primitive, before they are interleaved.
+#[derive(Default)]
+struct Attributes {
+    mask: u8,
+    layout: Vec<(&'static str, usize, AttributeType)>,
+    stride: usize,
+    /// The bytes of each attribute, for all the vertices.
+    columns: Vec<Vec<u8>>,
+}
+
+impl Attributes {
+    fn push(&mut self, attribute: Attribute, bytes: Vec<u8>) {
+        self.mask |= attribute.bit;
+        self.layout.push((attribute.name, self.stride, attribute.ty));
+        self.stride += attribute.ty.get_size_bytes();
+        self.columns.push(bytes);
+    }
+
+    fn upload<F: Facade + ?Sized>(self, facade: &F) -> Result<VertexBufferAny, GltfError> {
+        let sizes: Vec<_> = self.layout.iter().map(|&(_, _, ty)| ty.get_size_bytes()).collect();
+        let count = self.columns.iter().zip(&sizes)
+                                .map(|(column, size)| column.len() / size)
+                                .min().unwrap_or(0);
+
+        let mut data = Vec::with_capacity(self.stride * count);
+        for vertex in 0 .. count {
+            for (column, &size) in self.columns.iter().zip(&sizes) {
+                data.extend_from_slice(&column[vertex * size .. (vertex + 1) * size]);
+            }
+        }
+
+        let format = {
+            let mut formats = FORMATS.lock().unwrap();
+            match formats.iter().find(|&&(mask, _)| mask == self.mask) {
+                Some(&(_, format)) => format,
+                None => {
+                    let format: Vec<_> = self.layout.iter().map(|&(name, offset, ty)| {
+                        (Cow::Borrowed(name), offset, -1, ty, false)
+                    }).collect();
+                    let format: VertexFormat = Box::leak(format.into_boxed_slice());
+                    formats.push((self.mask, format));
+                    format
+                },
+            }
+        };
+
+        Ok(unsafe { VertexBufferAny::new_raw(facade, &data, format, self.stride) }?)
+    }
+}
@@ -1986,7 +1986,14 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         },
 
         TextureFormatRequest::Specific(TextureFormat::DepthFormat(format)) => {
-            if format.is_supported(context) {
+            // OpenGL ES 2.0 only has 16 bits depth renderbuffers, OpenGL ES 3.0 adds the others
+            let gles_renderbuffer = rq_ty == RequestType::Renderbuffer && match format {
+                DepthFormat::I16 => version >= &Version(Api::GlEs, 2, 0),
+                DepthFormat::I24 | DepthFormat::F32 => version >= &Version(Api::GlEs, 3, 0),
+                DepthFormat::I32 => false,
+            };
+
+            if format.is_supported(context) || gles_renderbuffer {
                 format.to_glenum()
             } else {
                 return Err(FormatNotSupportedError);
@@ -28,6 +28,23 @@ In addition to indices, you can also use **multidraw indirect** rendering.
 
 The idea is to put a list of things to render in a buffer, and pass that buffer to OpenGL.
 
+When several meshes share the same vertex and index buffers, the commands can be built from the
+location of each mesh with `MeshRange`:
+
+```no_run
+# fn example<F: glium::backend::Facade>(display: &F, indices: glium::IndexBuffer<u16>) {
+use glium::index::{DrawCommandsIndicesBuffer, MeshRange};
+
+let visible_meshes = [
+    MeshRange { first_index: 0, count: 36, base_vertex: 0, instances: 0 .. 1 },
+    MeshRange { first_index: 36, count: 12, base_vertex: 24, instances: 1 .. 5 },
+];
+
+let commands = DrawCommandsIndicesBuffer::from_ranges(display, &visible_meshes).unwrap();
+let source = commands.with_index_buffer(&indices);
+# }
+```
+
 */
 use crate::gl;
 use crate::ToGlEnum;
@@ -42,7 +59,7 @@ use crate::buffer::BufferAnySlice;
 pub use self::buffer::{IndexBuffer, IndexBufferSlice, IndexBufferAny};
 pub use self::buffer::CreationError as BufferCreationError;
 pub use self::multidraw::{DrawCommandsNoIndicesBuffer, DrawCommandNoIndices};
-pub use self::multidraw::{DrawCommandsIndicesBuffer, DrawCommandIndices};
+pub use self::multidraw::{DrawCommandsIndicesBuffer, DrawCommandIndices, MeshRange};
 
 mod buffer;
 mod multidraw;
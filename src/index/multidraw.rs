@@ -2,6 +2,7 @@
 //!
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::ops::Range;
 use std::os::raw;
 
 use crate::backend::Facade;
@@ -45,6 +46,55 @@ pub struct DrawCommandIndices {
 implement_uniform_block!(DrawCommandIndices, count, instance_count, first_index,
                          base_vertex, base_instance);
 
+impl DrawCommandIndices {
+    /// A command that doesn't draw anything.
+    const EMPTY: DrawCommandIndices = DrawCommandIndices {
+        count: 0,
+        instance_count: 0,
+        first_index: 0,
+        base_vertex: 0,
+        base_instance: 0,
+    };
+}
+
+impl<'a> From<&'a MeshRange> for DrawCommandIndices {
+    #[inline]
+    fn from(range: &'a MeshRange) -> DrawCommandIndices {
+        DrawCommandIndices {
+            count: range.count,
+            instance_count: range.instances.end.saturating_sub(range.instances.start),
+            first_index: range.first_index,
+            base_vertex: range.base_vertex,
+            base_instance: range.instances.start,
+        }
+    }
+}
+
+impl From<MeshRange> for DrawCommandIndices {
+    #[inline]
+    fn from(range: MeshRange) -> DrawCommandIndices {
+        DrawCommandIndices::from(&range)
+    }
+}
+
+/// Location of a mesh whose vertices and indices are stored in buffers shared with other meshes.
+///
+/// Converts into a `DrawCommandIndices`, and a list of ranges can be uploaded at once with
+/// `DrawCommandsIndicesBuffer::from_ranges` or `DrawCommandsIndicesBuffer::write_ranges`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MeshRange {
+    /// Position of the first index of the mesh in the index buffer.
+    pub first_index: raw::c_uint,
+    /// Number of indices of the mesh.
+    pub count: raw::c_uint,
+    /// Value to add to each index, in other words the position of the first vertex of the mesh
+    /// in the vertex buffer if the indices of the mesh start at `0`.
+    pub base_vertex: raw::c_uint,
+    /// The instances to draw, which are the elements of the per-instance vertex buffers. An empty
+    /// range draws nothing.
+    pub instances: Range<raw::c_uint>,
+}
+
 /// A buffer containing a list of draw commands.
 pub struct DrawCommandsNoIndicesBuffer {
     buffer: Buffer<[DrawCommandNoIndices]>,
@@ -204,6 +254,39 @@ impl DrawCommandsIndicesBuffer {
         Ok(DrawCommandsIndicesBuffer { buffer: buf })
     }
 
+    /// Builds a buffer that contains one command for each mesh range.
+    pub fn from_ranges<F>(facade: &F, ranges: &[MeshRange])
+                          -> Result<DrawCommandsIndicesBuffer, BufferCreationError>
+                          where F: ?Sized + Facade
+    {
+        let commands = ranges.iter().map(DrawCommandIndices::from).collect::<Vec<_>>();
+        let buf = Buffer::new(facade, &commands[..], BufferType::DrawIndirectBuffer,
+                              BufferMode::Default)?;
+        Ok(DrawCommandsIndicesBuffer { buffer: buf })
+    }
+
+    /// Replaces the commands of the buffer with one command for each mesh range, with a single
+    /// upload.
+    ///
+    /// If there are less ranges than commands in the buffer, the remaining commands are replaced
+    /// with commands that don't draw anything. This allows reusing the same buffer every frame
+    /// for a list of visible meshes whose length changes.
+    ///
+    /// # Panic
+    ///
+    /// Panics if there are more ranges than commands in the buffer.
+    pub fn write_ranges(&self, ranges: &[MeshRange]) {
+        assert!(ranges.len() <= self.buffer.len(),
+                "{} mesh ranges don't fit in a buffer of {} commands",
+                ranges.len(), self.buffer.len());
+
+        let commands = ranges.iter().map(DrawCommandIndices::from)
+                             .chain((ranges.len() .. self.buffer.len())
+                                    .map(|_| DrawCommandIndices::EMPTY))
+                             .collect::<Vec<_>>();
+        self.buffer.write(&commands[..]);
+    }
+
     /// Builds an indices source from this buffer and a primitives type. This indices source can
     /// be passed to the `draw()` function.
     #[inline]
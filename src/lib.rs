@@ -104,6 +104,7 @@ result to the user.
     // Maybe we'll fix these later, but not right now
     // (Would require API change)
     clippy::wrong_self_convention,
+    clippy::result_unit_err,
 )]
 
 #[cfg(feature = "glutin")]
@@ -116,10 +117,10 @@ pub use crate::index::IndexBuffer;
 pub use crate::vertex::{VertexBuffer, Vertex, VertexFormat};
 pub use crate::program::{Program, ProgramCreationError};
 pub use crate::program::ProgramCreationError::{CompilationError, LinkingError, ShaderTypeNotSupported};
-pub use crate::sync::{LinearSyncFence, SyncFence};
+pub use crate::sync::{ContextNotSharedError, LinearSyncFence, SyncFence};
 pub use crate::texture::Texture2d;
 pub use crate::version::{Api, Version, get_supported_glsl_version};
-pub use crate::ops::ReadError;
+pub use crate::ops::{BlitError, ReadError};
 
 use std::rc::Rc;
 use std::thread;
@@ -138,11 +139,23 @@ mod macros;
 
 pub mod backend;
 pub mod buffer;
+pub mod capture;
+#[cfg(feature = "cuda")]
+pub mod cuda;
 pub mod debug;
 pub mod draw_parameters;
+#[cfg(feature = "egui")]
+pub mod egui;
 pub mod framebuffer;
+#[cfg(feature = "gltf")]
+pub mod gltf;
 pub mod index;
+#[cfg(feature = "image")]
+pub mod loader;
+#[cfg(feature = "luminance")]
+pub mod luminance;
 pub mod memory_object;
+pub mod occlusion;
 pub mod pixel_buffer;
 pub mod program;
 pub mod uniforms;
@@ -150,6 +163,7 @@ pub mod vertex;
 pub mod semaphore;
 pub mod texture;
 pub mod field;
+pub mod util;
 
 mod context;
 mod fbo;
@@ -331,6 +345,10 @@ trait ProgramExt {
     fn get_atomic_counters(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
 
     fn get_subroutine_data(&self) -> &program::SubroutineData;
+
+    /// Returns the texture unit of the sampler uniform at this location, if the program has a
+    /// fixed assignment of its samplers to texture units.
+    fn get_fixed_texture_unit(&self, location: gl::types::GLint) -> Option<u16>;
 }
 
 /// Internal trait for queries.
@@ -408,14 +426,19 @@ trait TransformFeedbackSessionExt {
 
     /// Ensures that a buffer isn't used by transform feedback.
     fn ensure_buffer_out_of_transform_feedback(_: &mut CommandContext<'_>, _: gl::types::GLuint);
+
+    /// Returns true if one of the ranges written by the session overlaps this slice.
+    fn writes_to_buffer(&self, _: &buffer::BufferAnySlice<'_>) -> bool;
 }
 
 /// Internal trait for uniforms handling.
 trait UniformsExt {
     /// Binds the uniforms to a given program.
     ///
-    /// Will replace texture and buffer bind points.
-    fn bind_uniforms<'a, P>(&'a self, _: &mut CommandContext<'_>, _: &P, _: &mut Vec<buffer::Inserter<'a>>)
+    /// Will replace texture and buffer bind points. The textures that are accessed are
+    /// recorded in the `TextureAccesses`.
+    fn bind_uniforms<'a, P>(&'a self, _: &mut CommandContext<'_>, _: &P, _: &mut Vec<buffer::Inserter<'a>>,
+                            _: &mut uniforms::TextureAccesses)
                             -> Result<(), DrawError> where P: ProgramExt;
 }
 
@@ -436,6 +459,24 @@ pub enum RawUniformValue {
     Mat3([[gl::types::GLfloat; 3]; 3]),
     /// 4x4 column-major matrix.
     Mat4([[gl::types::GLfloat; 4]; 4]),
+    /// 2x3 column-major matrix, with 2 columns of 3 rows.
+    Mat2x3([[gl::types::GLfloat; 3]; 2]),
+    /// 2x4 column-major matrix, with 2 columns of 4 rows.
+    Mat2x4([[gl::types::GLfloat; 4]; 2]),
+    /// 3x2 column-major matrix, with 3 columns of 2 rows.
+    Mat3x2([[gl::types::GLfloat; 2]; 3]),
+    /// 3x4 column-major matrix, with 3 columns of 4 rows.
+    Mat3x4([[gl::types::GLfloat; 4]; 3]),
+    /// 4x2 column-major matrix, with 4 columns of 2 rows.
+    Mat4x2([[gl::types::GLfloat; 2]; 4]),
+    /// 4x3 column-major matrix, with 4 columns of 3 rows.
+    Mat4x3([[gl::types::GLfloat; 3]; 4]),
+    /// 2x2 row-major matrix, passed with `transpose` set to true.
+    Mat2Transposed([[gl::types::GLfloat; 2]; 2]),
+    /// 3x3 row-major matrix, passed with `transpose` set to true.
+    Mat3Transposed([[gl::types::GLfloat; 3]; 3]),
+    /// 4x4 row-major matrix, passed with `transpose` set to true.
+    Mat4Transposed([[gl::types::GLfloat; 4]; 4]),
     Vec2([gl::types::GLfloat; 2]),
     Vec3([gl::types::GLfloat; 3]),
     Vec4([gl::types::GLfloat; 4]),
@@ -451,6 +492,12 @@ pub enum RawUniformValue {
     DoubleMat2([[gl::types::GLdouble; 2]; 2]),
     DoubleMat3([[gl::types::GLdouble; 3]; 3]),
     DoubleMat4([[gl::types::GLdouble; 4]; 4]),
+    DoubleMat2x3([[gl::types::GLdouble; 3]; 2]),
+    DoubleMat2x4([[gl::types::GLdouble; 4]; 2]),
+    DoubleMat3x2([[gl::types::GLdouble; 2]; 3]),
+    DoubleMat3x4([[gl::types::GLdouble; 4]; 3]),
+    DoubleMat4x2([[gl::types::GLdouble; 2]; 4]),
+    DoubleMat4x3([[gl::types::GLdouble; 3]; 4]),
     DoubleVec2([gl::types::GLdouble; 2]),
     DoubleVec3([gl::types::GLdouble; 3]),
     DoubleVec4([gl::types::GLdouble; 4]),
@@ -467,7 +514,7 @@ pub enum RawUniformValue {
 /// Area of a surface in pixels.
 ///
 /// In the OpenGL ecosystem, the (0,0) coordinate is at the bottom-left hand corner of the images.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct Rect {
     /// Number of pixels between the left border of the surface and the left border of
     /// the rectangle.
@@ -481,6 +528,50 @@ pub struct Rect {
     pub height: u32,
 }
 
+impl Rect {
+    /// Returns the same rectangle moved by `left` pixels to the right and `bottom` pixels to
+    /// the top.
+    #[inline]
+    pub fn translate(&self, left: u32, bottom: u32) -> Rect {
+        Rect {
+            left: self.left + left,
+            bottom: self.bottom + bottom,
+            .. *self
+        }
+    }
+
+    /// Returns the area that is covered by both rectangles, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.left.max(other.left);
+        let bottom = self.bottom.max(other.bottom);
+        let right = (self.left + self.width).min(other.left + other.width);
+        let top = (self.bottom + self.height).min(other.bottom + other.height);
+
+        if right <= left || top <= bottom {
+            return None;
+        }
+
+        Some(Rect {
+            left,
+            bottom,
+            width: right - left,
+            height: top - bottom,
+        })
+    }
+}
+
+impl From<Rect> for BlitTarget {
+    #[inline]
+    fn from(rect: Rect) -> BlitTarget {
+        BlitTarget {
+            left: rect.left,
+            bottom: rect.bottom,
+            width: rect.width as i32,
+            height: rect.height as i32,
+        }
+    }
+}
+
 /// Area of a surface in pixels. Similar to a `Rect` except that dimensions can be negative.
 ///
 /// In the OpenGL ecosystem, the (0,0) coordinate is at the bottom-left hand corner of the images.
@@ -567,6 +658,119 @@ impl ToGlEnum for BlitMask {
     }
 }
 
+/// An attachment of a surface whose content can be discarded with `Surface::invalidate`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Attachment {
+    /// The color attachment with this index, in the order in which the attachments were passed
+    /// when creating the framebuffer. The default framebuffer only has the color attachment `0`.
+    Color(u32),
+    /// The depth attachment, or the depth part of a depth-stencil attachment.
+    Depth,
+    /// The stencil attachment, or the stencil part of a depth-stencil attachment.
+    Stencil,
+}
+
+/// Values to write in the attachments of a surface with `Surface::clear`.
+///
+/// The attachments whose value is `None` are left untouched.
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # fn example(frame: &mut glium::Frame) {
+/// use glium::ClearValues;
+///
+/// frame.clear(None, &ClearValues::new().color((0.0, 0.0, 1.0, 1.0)).depth(1.0));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClearValues {
+    /// Color to write in all the color attachments.
+    pub color: Option<(f32, f32, f32, f32)>,
+
+    /// If false, the color is in linear RGB and is converted to sRGB when written to an
+    /// attachment that has an sRGB format. If true, the color is written as it is.
+    ///
+    /// This also applies to the floating-point colors of `attachments`.
+    pub color_srgb: bool,
+
+    /// Value to write in the depth attachment.
+    pub depth: Option<f32>,
+
+    /// Value to write in the stencil attachment.
+    pub stencil: Option<i32>,
+
+    /// Colors to write in individual color attachments. The first element of each entry is the
+    /// index of the attachment. For a `MultiOutputFrameBuffer`, this is the position of the
+    /// attachment in the list that was passed when building it. Other surfaces only have the
+    /// attachment `0`.
+    ///
+    /// These are written after `color`. Clearing an attachment other than the first one, or
+    /// with an integral color, requires OpenGL 3.0 or OpenGL ES 3.0.
+    pub attachments: Vec<(u32, ClearColor)>,
+}
+
+impl ClearValues {
+    /// Builds a `ClearValues` that doesn't clear anything.
+    #[inline]
+    pub fn new() -> ClearValues {
+        Default::default()
+    }
+
+    /// Clears all the color attachments with a color in linear RGB.
+    #[inline]
+    pub fn color(mut self, color: (f32, f32, f32, f32)) -> ClearValues {
+        self.color = Some(color);
+        self.color_srgb = false;
+        self
+    }
+
+    /// Clears all the color attachments with a color that is already in sRGB.
+    #[inline]
+    pub fn color_srgb(mut self, color: (f32, f32, f32, f32)) -> ClearValues {
+        self.color = Some(color);
+        self.color_srgb = true;
+        self
+    }
+
+    /// Clears the depth attachment.
+    #[inline]
+    pub fn depth(mut self, depth: f32) -> ClearValues {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Clears the stencil attachment.
+    #[inline]
+    pub fn stencil(mut self, stencil: i32) -> ClearValues {
+        self.stencil = Some(stencil);
+        self
+    }
+
+    /// Clears one of the color attachments.
+    ///
+    /// Use this with an `Int` or `UnsignedInt` color to clear an integral texture, as `color`
+    /// only works with floating-point and normalized formats.
+    #[inline]
+    pub fn attachment_color(mut self, index: u32, color: ClearColor) -> ClearValues {
+        self.attachments.push((index, color));
+        self
+    }
+}
+
+/// Color to write in a single color attachment. See `ClearValues::attachment_color`.
+///
+/// The variant must match the format of the attachment, otherwise the content of the attachment
+/// is undefined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClearColor {
+    /// For floating-point and normalized formats.
+    Float([f32; 4]),
+    /// For signed integral formats.
+    Int([i32; 4]),
+    /// For unsigned integral formats.
+    UnsignedInt([u32; 4]),
+}
+
 /// Object that can be drawn upon.
 ///
 /// # What does the GPU do when you draw?
@@ -778,62 +982,98 @@ impl ToGlEnum for BlitMask {
 ///
 pub trait Surface {
     /// Clears some attachments of the target.
-    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
-             depth: Option<f32>, stencil: Option<i32>);
+    ///
+    /// If `rect` is `Some`, only the pixels inside of this area are modified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` clears a color attachment other than the first one, or clears a color
+    /// attachment with an integral color, and the backend doesn't support it.
+    fn clear(&mut self, rect: Option<&Rect>, values: &ClearValues);
 
     /// Clears the color attachment of the target. The color is converted to sRGB when the target has sRGB format.
     fn clear_color(&mut self, red: f32, green: f32, blue: f32, alpha: f32) {
-        self.clear(None, Some((red, green, blue, alpha)), false, None, None);
+        self.clear(None, &ClearValues::new().color((red, green, blue, alpha)));
     }
 
     /// Clears the color attachment of the target. The color is in sRGB format and is not converted in the target.
     fn clear_color_srgb(&mut self, red: f32, green: f32, blue: f32, alpha: f32) {
-        self.clear(None, Some((red, green, blue, alpha)), true, None, None);
+        self.clear(None, &ClearValues::new().color_srgb((red, green, blue, alpha)));
     }
 
     /// Clears the depth attachment of the target.
     fn clear_depth(&mut self, value: f32) {
-        self.clear(None, None, false, Some(value), None);
+        self.clear(None, &ClearValues::new().depth(value));
     }
 
     /// Clears the stencil attachment of the target.
     fn clear_stencil(&mut self, value: i32) {
-        self.clear(None, None, false, None, Some(value));
+        self.clear(None, &ClearValues::new().stencil(value));
     }
 
     /// Clears the color and depth attachments of the target.
     fn clear_color_and_depth(&mut self, color: (f32, f32, f32, f32), depth: f32) {
-        self.clear(None, Some(color), false, Some(depth), None);
+        self.clear(None, &ClearValues::new().color(color).depth(depth));
     }
 
     /// Clears the color and depth attachments of the target. The color is in sRGB format.
     fn clear_color_srgb_and_depth(&mut self, color: (f32, f32, f32, f32), depth: f32) {
-        self.clear(None, Some(color), true, Some(depth), None);
+        self.clear(None, &ClearValues::new().color_srgb(color).depth(depth));
     }
 
     /// Clears the color and stencil attachments of the target.
     fn clear_color_and_stencil(&mut self, color: (f32, f32, f32, f32), stencil: i32) {
-        self.clear(None, Some(color), false, None, Some(stencil));
+        self.clear(None, &ClearValues::new().color(color).stencil(stencil));
     }
 
     /// Clears the color and stencil attachments of the target. The color is in sRGB format.
     fn clear_color_srgb_and_stencil(&mut self, color: (f32, f32, f32, f32), stencil: i32) {
-        self.clear(None, Some(color), true, None, Some(stencil));
+        self.clear(None, &ClearValues::new().color_srgb(color).stencil(stencil));
     }
 
     /// Clears the depth and stencil attachments of the target.
     fn clear_depth_and_stencil(&mut self, depth: f32, stencil: i32) {
-        self.clear(None, None, false, Some(depth), Some(stencil));
+        self.clear(None, &ClearValues::new().depth(depth).stencil(stencil));
     }
 
     /// Clears the color, depth and stencil attachments of the target.
     fn clear_all(&mut self, color: (f32, f32, f32, f32), depth: f32, stencil: i32) {
-        self.clear(None, Some(color), false, Some(depth), Some(stencil));
+        self.clear(None, &ClearValues::new().color(color).depth(depth).stencil(stencil));
     }
 
     /// Clears the color, depth and stencil attachments of the target. The color is in sRGB format.
     fn clear_all_srgb(&mut self, color: (f32, f32, f32, f32), depth: f32, stencil: i32) {
-        self.clear(None, Some(color), true, Some(depth), Some(stencil));
+        self.clear(None, &ClearValues::new().color_srgb(color).depth(depth).stencil(stencil));
+    }
+
+    /// Tells the backend that the content of some attachments of the target is no longer needed.
+    ///
+    /// The content of the attachments becomes undefined. This is a hint which avoids writing the
+    /// attachments back to memory on tile-based GPUs, for example for a depth buffer that is only
+    /// used during a frame, or reading them before the next draw commands if they are going to be
+    /// entirely overwritten. It does nothing if the backend doesn't support it.
+    ///
+    /// Attachments that don't exist in the target are ignored.
+    fn invalidate(&mut self, attachments: &[Attachment]) {
+        let (width, height) = self.get_dimensions();
+        self.invalidate_rect(&Rect { left: 0, bottom: 0, width, height }, attachments);
+    }
+
+    /// Same as `invalidate`, but only for the pixels inside of `rect`.
+    fn invalidate_rect(&mut self, rect: &Rect, attachments: &[Attachment]);
+
+    /// Returns a view of the area `rect` of the target.
+    ///
+    /// Draws and clears on the returned object only modify the pixels inside of `rect`, and the
+    /// viewport and the scissor box of the draw parameters are relative to this area. See
+    /// `framebuffer::SubSurface`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `rect` is not inside of the target.
+    #[inline]
+    fn sub_region(&mut self, rect: Rect) -> framebuffer::SubSurface<'_, Self> {
+        framebuffer::SubSurface::new(self, rect)
     }
 
     /// Returns the dimensions in pixels of the target.
@@ -870,6 +1110,40 @@ pub trait Surface {
         V: vertex::MultiVerticesSource<'b>, I: Into<index::IndicesSource<'a>>,
         U: uniforms::Uniforms;
 
+    /// Makes the result of the previous draw commands visible to the next draw commands that
+    /// sample a texture which is attached to this surface.
+    ///
+    /// Sampling a texture while rendering to it is normally undefined. After a texture barrier,
+    /// a draw command can read the texels that were written before the barrier, as long as
+    /// each texel is written at most once between two barriers. This is typically used to
+    /// implement custom blending in the fragment shader.
+    ///
+    /// Also see the `auto_texture_barrier` member of the draw parameters.
+    fn texture_barrier(&self) -> Result<(), TextureBarrierNotSupportedError>;
+
+    /// Reads the pixels inside of `rect` into a pixel buffer, in the client format `format`.
+    ///
+    /// This reads the back buffer of the default framebuffer, the color attachment of a
+    /// `SimpleFrameBuffer`, or the first color attachment of a `MultiOutputFrameBuffer`.
+    ///
+    /// The format doesn't have to be the format of `P`, only its size must match. For example
+    /// an HDR target can be read with `ClientFormat::F16F16F16F16` into a
+    /// `PixelBuffer<(u16, u16, u16, u16)>`, or with `ClientFormat::F32F32F32F32` into a
+    /// `PixelBuffer<(f32, f32, f32, f32)>`. The values are not clamped. Integral attachments,
+    /// like the object ids of GPU picking, are read as integers without any conversion.
+    ///
+    /// OpenGL ES only supports `U8U8U8U8` for normalized attachments and `I32I32I32I32` or
+    /// `U32U32U32U32` for integral attachments, plus an implementation-defined format.
+    /// `ReadError::OutputFormatNotSupported` is returned for the other formats.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `rect` is outside of the surface, if `dest` is smaller than `rect`, or if the
+    /// size of a pixel of `format` is not the size of `P`.
+    fn read_to_pixel_buffer<P>(&self, rect: &Rect, dest: &pixel_buffer::PixelBuffer<P>,
+                               format: texture::ClientFormat) -> Result<(), ReadError>
+                               where P: texture::PixelValue;
+
     /// Blits from the default framebuffer.
     #[inline]
     fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
@@ -899,21 +1173,77 @@ pub trait Surface {
     }
 
     /// Blits from the default framebuffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the blit is invalid. See `try_blit_buffers_from_frame`.
     fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
                                filter: uniforms::MagnifySamplerFilter, mask: BlitMask);
 
     /// Blits from a simple framebuffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the blit is invalid. See `try_blit_buffers_from_simple_framebuffer`.
     fn blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer<'_>,
                                             source_rect: &Rect, target_rect: &BlitTarget,
                                             filter: uniforms::MagnifySamplerFilter,
                                             mask: BlitMask);
 
     /// Blits from a multi-output framebuffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the blit is invalid. See `try_blit_buffers_from_multioutput_framebuffer`.
     fn blit_buffers_from_multioutput_framebuffer(&self, source: &framebuffer::MultiOutputFrameBuffer<'_>,
                                                  source_rect: &Rect, target_rect: &BlitTarget,
                                                  filter: uniforms::MagnifySamplerFilter,
                                                  mask: BlitMask);
 
+    /// Blits from the default framebuffer. Returns an error instead of producing an OpenGL
+    /// error if the parameters are invalid.
+    ///
+    /// The default implementation calls `blit_buffers_from_frame` without checking anything.
+    #[inline]
+    fn try_blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                                   filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                                   -> Result<(), BlitError>
+    {
+        self.blit_buffers_from_frame(source_rect, target_rect, filter, mask);
+        Ok(())
+    }
+
+    /// Blits from a simple framebuffer. Returns an error instead of producing an OpenGL
+    /// error if the parameters are invalid.
+    ///
+    /// The default implementation calls `blit_buffers_from_simple_framebuffer` without checking
+    /// anything.
+    #[inline]
+    fn try_blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer<'_>,
+                                                source_rect: &Rect, target_rect: &BlitTarget,
+                                                filter: uniforms::MagnifySamplerFilter,
+                                                mask: BlitMask) -> Result<(), BlitError>
+    {
+        self.blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter, mask);
+        Ok(())
+    }
+
+    /// Blits from a multi-output framebuffer. Returns an error instead of producing an OpenGL
+    /// error if the parameters are invalid.
+    ///
+    /// The default implementation calls `blit_buffers_from_multioutput_framebuffer` without
+    /// checking anything.
+    #[inline]
+    fn try_blit_buffers_from_multioutput_framebuffer(&self, source: &framebuffer::MultiOutputFrameBuffer<'_>,
+                                                     source_rect: &Rect, target_rect: &BlitTarget,
+                                                     filter: uniforms::MagnifySamplerFilter,
+                                                     mask: BlitMask) -> Result<(), BlitError>
+    {
+        self.blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect, filter,
+                                                       mask);
+        Ok(())
+    }
+
 
     /// Copies a rectangle of pixels from this surface to another surface.
     ///
@@ -927,10 +1257,70 @@ pub trait Surface {
     ///
     /// Note that there is no alpha blending, depth/stencil checking, etc. This function just
     /// copies pixels.
+    ///
+    /// When resizing sRGB surfaces, some drivers interpolate the sRGB values directly, which
+    /// makes the result too dark. See `framebuffer::LinearBlitter` for a slower alternative
+    /// that filters in linear space.
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface;
 
 
+    /// Copies the buffers of `mask` from a rectangle of this surface to another surface.
+    ///
+    /// This is the same as `blit_color`, except that the depth and stencil buffers can be
+    /// copied as well. The parameters are checked before anything is sent to OpenGL:
+    ///
+    /// - The depth and stencil buffers can only be copied with `MagnifySamplerFilter::Nearest`.
+    /// - The depth and stencil buffers of the source and of the target must have the same
+    ///   number of bits. Buffers that don't exist on either side are not copied.
+    /// - A multisampled source can't be resized, and must have the same number of samples as
+    ///   the target if the target is multisampled too.
+    ///
+    /// The default implementation only supports copying the color buffer, with `blit_color`,
+    /// and returns `BlitError::NotSupported` otherwise.
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface
+    {
+        if mask != BlitMask::color() {
+            return Err(BlitError::NotSupported);
+        }
+
+        self.blit_color(source_rect, target, target_rect, filter);
+        Ok(())
+    }
+
+    /// Copies the depth buffer of a rectangle of this surface to another surface.
+    /// See `blit_buffers`.
+    #[inline]
+    fn blit_depth<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget)
+                     -> Result<(), BlitError> where S: Surface
+    {
+        self.blit_buffers(source_rect, target, target_rect, uniforms::MagnifySamplerFilter::Nearest,
+                          BlitMask::depth())
+    }
+
+    /// Copies the stencil buffer of a rectangle of this surface to another surface.
+    /// See `blit_buffers`.
+    #[inline]
+    fn blit_stencil<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget)
+                       -> Result<(), BlitError> where S: Surface
+    {
+        self.blit_buffers(source_rect, target, target_rect, uniforms::MagnifySamplerFilter::Nearest,
+                          BlitMask::stencil())
+    }
+
+    /// Copies the depth and stencil buffers of a rectangle of this surface to another surface.
+    /// See `blit_buffers`.
+    #[inline]
+    fn blit_depth_and_stencil<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget)
+                                 -> Result<(), BlitError> where S: Surface
+    {
+        self.blit_buffers(source_rect, target, target_rect, uniforms::MagnifySamplerFilter::Nearest,
+                          BlitMask::depth_and_stencil())
+    }
+
     /// Copies the entire surface to a target surface. See `blit_color`.
     #[inline]
     fn blit_whole_color_to<S>(&self, target: &S, target_rect: &BlitTarget,
@@ -1072,6 +1462,10 @@ pub enum DrawError {
     /// One of the blending parameters is not supported by the backend.
     BlendingParameterNotSupported,
 
+    /// Setting the blending state or the color mask separately for each attachment is not
+    /// supported by the backend.
+    AttachmentBlendingNotSupported,
+
     /// Restarting indices (multiple objects per draw call) is not supported by the backend.
     FixedIndexRestartingNotSupported,
 
@@ -1081,8 +1475,82 @@ pub enum DrawError {
     /// Tried to enable a clip plane that does not exist.
     ClipPlaneIndexOutOfBounds,
 
+    /// Tried to use more viewports or scissor boxes than the backend supports.
+    TooManyViewports,
+
     /// Tried to use too many image units simultaneously
     InsufficientImageUnits,
+
+    /// Setting the default tessellation levels is not supported by the backend.
+    DefaultTessellationLevelsNotSupported,
+
+    /// Enabling or disabling the sRGB conversion of the framebuffer is not supported by the
+    /// backend.
+    FramebufferSrgbNotSupported,
+
+    /// The range of a buffer that is the destination of the transform feedback session is also
+    /// read by the draw command, either as a vertex source or as the index buffer.
+    ///
+    /// Only returned if `check_hazards` is enabled in the draw parameters.
+    TransformFeedbackBufferAliasing,
+
+    /// A texture attached to the framebuffer is sampled by the draw command after having been
+    /// rendered to, and there was no texture barrier in between.
+    ///
+    /// Only returned if `check_hazards` is enabled in the draw parameters.
+    TextureFeedbackLoop {
+        /// Name of the sampler uniform that reads from the texture.
+        sampler: String,
+    },
+
+    /// A texture barrier was required, but texture barriers are not supported by the backend.
+    TextureBarrierNotSupported,
+
+    /// A texture is written through an image unit and sampled by the same command.
+    ///
+    /// Only returned if `check_hazards` is enabled in the draw parameters.
+    ImageWrittenWhileSampled {
+        /// Name of the image uniform that writes to the texture.
+        image: String,
+        /// Name of the sampler uniform that reads from the texture.
+        sampler: String,
+    },
+
+    /// An advanced blend equation was used for the color or the alpha channels, but not the
+    /// same one for both.
+    AdvancedBlendingMismatch,
+
+    /// A logic operation was requested, but the backend doesn't support logic operations.
+    LogicOpNotSupported,
+
+    /// A logic operation was requested while blending is enabled. OpenGL would silently ignore
+    /// the blending.
+    LogicOpWithBlending,
+
+    /// `multisampling_alpha_to_one` was enabled, but the backend doesn't support it.
+    AlphaToOneNotSupported,
+
+    /// The polygon offset clamp, or the polygon offset of points or lines, is not supported by
+    /// the backend.
+    PolygonOffsetNotSupported,
+
+    /// Tried to bind a buffer to an explicit binding point that is not supported by the backend.
+    BlockBindingOutOfRange {
+        /// The binding point that was requested.
+        binding: u32,
+    },
+
+    /// A slice of a buffer was bound to a uniform block, but its offset is not a multiple of
+    /// `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`.
+    UniformBufferOffsetNotAligned {
+        /// Offset in bytes of the slice within its buffer.
+        offset: usize,
+        /// Required alignment in bytes.
+        alignment: usize,
+    },
+
+    /// The OpenGL context has been lost. See `SwapBuffersError::ContextLost`.
+    ContextLost,
 }
 
 impl Error for DrawError {
@@ -1150,14 +1618,46 @@ impl fmt::Display for DrawError {
                 "The depth clamp mode is not supported by the backend",
             BlendingParameterNotSupported =>
                 "One the blending parameters is not supported by the backend",
+            AttachmentBlendingNotSupported =>
+                "Per-attachment blending or color masks are not supported by the backend",
             FixedIndexRestartingNotSupported =>
                 "Restarting indices (multiple objects per draw call) is not supported by the backend",
             ClipControlNotSupported =>
                 "Changing the clip volume definition (origin and depth mode) is not supported by the backend",
             ClipPlaneIndexOutOfBounds =>
                 "Tried to enable a clip plane that does not exist.",
+            TooManyViewports =>
+                "Tried to use more viewports or scissor boxes than the backend supports",
             InsufficientImageUnits =>
                 "Tried to use more image uniforms that the implementation has support for",
+            DefaultTessellationLevelsNotSupported =>
+                "Setting the default tessellation levels is not supported by the backend",
+            FramebufferSrgbNotSupported =>
+                "Enabling or disabling the sRGB conversion of the framebuffer is not supported by the backend",
+            TransformFeedbackBufferAliasing =>
+                "A transform feedback buffer is also used as a vertex source or index buffer by the same draw command",
+            TextureFeedbackLoop { .. } =>
+                "A texture attached to the framebuffer is sampled without a texture barrier after being rendered to",
+            TextureBarrierNotSupported =>
+                "Texture barriers are not supported by the backend",
+            ImageWrittenWhileSampled { .. } =>
+                "A texture is written through an image unit while being sampled by the same command",
+            AdvancedBlendingMismatch =>
+                "Advanced blend equations must be used for both the color and the alpha channels",
+            LogicOpNotSupported =>
+                "Logic operations are not supported by the backend",
+            LogicOpWithBlending =>
+                "A logic operation can't be used at the same time as blending",
+            AlphaToOneNotSupported =>
+                "Replacing the alpha of the fragments with one is not supported by the backend",
+            PolygonOffsetNotSupported =>
+                "The requested polygon offset is not supported by the backend",
+            BlockBindingOutOfRange { .. } =>
+                "Tried to bind a buffer to a binding point that is not supported by the backend",
+            UniformBufferOffsetNotAligned { .. } =>
+                "The offset of the buffer slice bound to a uniform block is not properly aligned",
+            ContextLost =>
+                "The OpenGL context has been lost and needs to be recreated",
         };
         match self {
             UniformTypeMismatch { ref name, ref expected } =>
@@ -1190,12 +1690,55 @@ impl fmt::Display for DrawError {
                     name,
                     err,
                 ),
+            TextureFeedbackLoop { sampler } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    desc,
+                    sampler,
+                ),
+            ImageWrittenWhileSampled { image, sampler } =>
+                write!(
+                    fmt,
+                    "{}: image {} aliases sampler {}",
+                    desc,
+                    image,
+                    sampler,
+                ),
+            BlockBindingOutOfRange { binding } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    desc,
+                    binding,
+                ),
+            UniformBufferOffsetNotAligned { offset, alignment } =>
+                write!(
+                    fmt,
+                    "{}: offset {} is not a multiple of {}",
+                    desc,
+                    offset,
+                    alignment,
+                ),
             _ =>
                 fmt.write_str(desc),
         }
     }
 }
 
+/// Error that happens when texture barriers are not supported by the backend.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TextureBarrierNotSupportedError;
+
+impl fmt::Display for TextureBarrierNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("Texture barriers are not supported by the OpenGL implementation")
+    }
+}
+
+impl Error for TextureBarrierNotSupportedError {}
+
 /// Error that can happen when swapping buffers.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SwapBuffersError {
@@ -1209,6 +1752,9 @@ pub enum SwapBuffersError {
     /// A context loss usually happens on mobile devices when the user puts the application on
     /// sleep and wakes it up later. However any OpenGL implementation can theoretically lose the
     /// context at any time. Can only happen if calling `is_context_loss_possible()` returns true.
+    ///
+    /// Resources registered with `Context::register_recreation_hook` can be recreated with
+    /// `Context::recreate_resources` once a new context has been built.
     ContextLost,
     /// The buffers have already been swapped.
     ///
@@ -1236,11 +1782,17 @@ impl fmt::Display for SwapBuffersError {
 ///
 /// The back- and front-buffers are swapped when you call `finish`.
 ///
+/// If multisampling has been enabled on the `Display` with `set_multisampling`, the frame
+/// draws to multisampled buffers instead, which are resolved to the back buffer when you call
+/// `finish`.
+///
 /// You **must** call either `finish` or `set_finish` or else the destructor will panic.
 pub struct Frame {
     context: Rc<Context>,
     dimensions: (u32, u32),
     destroyed: bool,        // TODO: use a linear type instead.
+    invalidate_on_finish: Vec<Attachment>,
+    multisampling: Option<Rc<framebuffer::FrameMultisampling>>,
 }
 
 impl Frame {
@@ -1251,9 +1803,53 @@ impl Frame {
             context,
             dimensions,
             destroyed: false,
+            invalidate_on_finish: Vec::new(),
+            multisampling: None,
+        }
+    }
+
+    /// Builds a `Frame` that draws to multisampled buffers.
+    #[inline]
+    pub(crate) fn with_multisampling(context: Rc<Context>,
+                                     multisampling: Rc<framebuffer::FrameMultisampling>) -> Frame
+    {
+        Frame {
+            context,
+            dimensions: multisampling.get_dimensions(),
+            destroyed: false,
+            invalidate_on_finish: Vec::new(),
+            multisampling: Some(multisampling),
+        }
+    }
+
+    /// Copies the content of the multisampled buffers, if any, to the back buffer.
+    #[inline]
+    fn resolve(&self) {
+        if let Some(ref multisampling) = self.multisampling {
+            multisampling.resolve(&self.context);
         }
     }
 
+    /// Sets the attachments that are invalidated right before the buffers are swapped, in
+    /// addition to the ones that were previously set.
+    ///
+    /// On tile-based GPUs, this avoids writing the depth and stencil buffers back to memory at
+    /// the end of each frame. See `Surface::invalidate`.
+    ///
+    /// ```no_run
+    /// # fn example(mut frame: glium::Frame) {
+    /// use glium::Attachment;
+    ///
+    /// frame.invalidate_on_finish(&[Attachment::Depth, Attachment::Stencil]);
+    /// // drawing
+    /// frame.finish().unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn invalidate_on_finish(&mut self, attachments: &[Attachment]) {
+        self.invalidate_on_finish.extend_from_slice(attachments);
+    }
+
     /// Stop drawing, swap the buffers, and consume the Frame.
     ///
     /// See the documentation of `SwapBuffersError` about what is being returned.
@@ -1268,21 +1864,78 @@ impl Frame {
     /// cause `Err(SwapBuffersError::AlreadySwapped)` to be returned.
     #[inline]
     pub fn set_finish(&mut self) -> Result<(), SwapBuffersError> {
+        self.finish_impl(None)
+    }
+
+    /// Stop drawing, swap the buffers while telling the compositor which regions of the frame
+    /// have changed, and consume the Frame.
+    ///
+    /// Applications that only redraw a small part of the window, like a blinking cursor, can use
+    /// this to let the compositor skip the rest, which saves power. The rectangles use the same
+    /// coordinates as the other methods of `Surface`, with the origin at the bottom left. The
+    /// content outside of them must still be correct, as the compositor is free to ignore the
+    /// hint; with glutin, the damage is only passed to EGL when the `damage` feature is enabled,
+    /// through `EGL_KHR_swap_buffers_with_damage` or `EGL_EXT_swap_buffers_with_damage`.
+    ///
+    /// An empty slice means that the whole frame has changed, like `finish`.
+    ///
+    /// ```no_run
+    /// # use glium::Surface;
+    /// # fn example(display: glium::Display<glium::glutin::surface::WindowSurface>) {
+    /// let cursor = glium::Rect { left: 120, bottom: 300, width: 2, height: 16 };
+    ///
+    /// let mut frame = display.draw();
+    /// frame.clear(Some(&cursor), &glium::ClearValues::new().color((1.0, 1.0, 1.0, 1.0)));
+    /// frame.finish_with_damage(&[cursor]).unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn finish_with_damage(mut self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.finish_impl(Some(damage))
+    }
+
+    fn finish_impl(&mut self, damage: Option<&[Rect]>) -> Result<(), SwapBuffersError> {
         if self.destroyed {
             return Err(SwapBuffersError::AlreadySwapped);
         }
 
         self.destroyed = true;
-        self.context.swap_buffers()
+        self.resolve();
+
+        if !self.invalidate_on_finish.is_empty() {
+            let (width, height) = self.dimensions;
+            ops::invalidate(&self.context, self.get_attachments(), self.dimensions,
+                            &Rect { left: 0, bottom: 0, width, height },
+                            &self.invalidate_on_finish);
+        }
+
+        match damage {
+            Some(damage) => self.context.swap_buffers_with_damage(damage),
+            None => self.context.swap_buffers(),
+        }
     }
 }
 
 impl Surface for Frame {
     #[inline]
-    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
-             depth: Option<f32>, stencil: Option<i32>)
+    fn clear(&mut self, rect: Option<&Rect>, values: &ClearValues) {
+        ops::clear(&self.context, self.get_attachments(), rect, values);
+    }
+
+    #[inline]
+    fn read_to_pixel_buffer<P>(&self, rect: &Rect, dest: &pixel_buffer::PixelBuffer<P>,
+                               format: texture::ClientFormat) -> Result<(), ReadError>
+                               where P: texture::PixelValue
     {
-        ops::clear(&self.context, None, rect, color, color_srgb, depth, stencil);
+        // multisampled buffers can't be read directly
+        self.resolve();
+        ops::read_surface(&self.context, ops::Source::DefaultFramebuffer(gl::BACK_LEFT),
+                          self.dimensions, rect, dest, format)
+    }
+
+    #[inline]
+    fn invalidate_rect(&mut self, rect: &Rect, attachments: &[Attachment]) {
+        ops::invalidate(&self.context, self.get_attachments(), self.dimensions, rect, attachments);
     }
 
     fn get_dimensions(&self) -> (u32, u32) {
@@ -1290,11 +1943,17 @@ impl Surface for Frame {
     }
 
     fn get_depth_buffer_bits(&self) -> Option<u16> {
-        self.context.capabilities().depth_bits
+        match self.get_attachments() {
+            Some(attachments) => attachments.get_depth_buffer_bits(),
+            None => self.context.capabilities().depth_bits,
+        }
     }
 
     fn get_stencil_buffer_bits(&self) -> Option<u16> {
-        self.context.capabilities().stencil_bits
+        match self.get_attachments() {
+            Some(attachments) => attachments.get_stencil_buffer_bits(),
+            None => self.context.capabilities().stencil_bits,
+        }
     }
 
     fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V,
@@ -1322,35 +1981,80 @@ impl Surface for Frame {
             }
         }
 
-        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
-                  uniforms, draw_parameters, self.dimensions)
+        ops::draw(&self.context, self.get_attachments(), vertex_buffer, index_buffer.into(),
+                  program, uniforms, draw_parameters, self.dimensions)
+    }
+
+    fn texture_barrier(&self) -> Result<(), TextureBarrierNotSupportedError> {
+        let mut ctxt = self.context.make_current();
+        ops::texture_barrier(&mut ctxt)
     }
 
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface
     {
+        self.resolve();
         target.blit_from_frame(source_rect, target_rect, filter)
     }
 
-    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
-        ops::blit(&self.context, None, self.get_attachments(),
-                  mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface
+    {
+        // only the color buffer is resolved
+        self.resolve();
+        target.try_blit_buffers_from_frame(source_rect, target_rect, filter, mask)
     }
 
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_frame(source_rect, target_rect, filter, mask).unwrap()
+    }
+
+    #[inline]
     fn blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer<'_>,
                                             source_rect: &Rect, target_rect: &BlitTarget,
-                                            filter: uniforms::MagnifySamplerFilter,
-                                            mask: BlitMask) {
+                                            filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter,
+                                                      mask).unwrap()
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &framebuffer::MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.try_blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect,
+                                                           filter, mask).unwrap()
+    }
+
+    fn try_blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) -> Result<(), BlitError> {
+        ops::blit(&self.context, None, self.get_attachments(),
+                  mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
+    }
+
+    fn try_blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer<'_>,
+                                                source_rect: &Rect, target_rect: &BlitTarget,
+                                                filter: uniforms::MagnifySamplerFilter,
+                                                mask: BlitMask)
+                                                -> Result<(), BlitError>
+    {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
 
-    fn blit_buffers_from_multioutput_framebuffer(&self,
-                                                 source: &framebuffer::MultiOutputFrameBuffer<'_>,
-                                                 source_rect: &Rect, target_rect: &BlitTarget,
-                                                 filter: uniforms::MagnifySamplerFilter,
-                                                 mask: BlitMask) {
+    fn try_blit_buffers_from_multioutput_framebuffer(&self,
+                                                     source: &framebuffer::MultiOutputFrameBuffer<'_>,
+                                                     source_rect: &Rect, target_rect: &BlitTarget,
+                                                     filter: uniforms::MagnifySamplerFilter,
+                                                     mask: BlitMask)
+                                                     -> Result<(), BlitError>
+    {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
     }
@@ -1359,7 +2063,7 @@ impl Surface for Frame {
 impl FboAttachments for Frame {
     #[inline]
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments<'_>> {
-        None
+        self.multisampling.as_ref().map(|m| m.get_attachments())
     }
 }
 
@@ -1388,6 +2092,11 @@ impl Error for IncompatibleOpenGl {}
 #[allow(dead_code)]
 #[inline]
 fn get_gl_error(ctxt: &mut context::CommandContext<'_>) -> Option<&'static str> {
+    // errors are not recorded by `GL_KHR_no_error` contexts
+    if ctxt.capabilities.no_error {
+        return None;
+    }
+
     match unsafe { ctxt.gl.GetError() } {
         gl::NO_ERROR => None,
         gl::INVALID_ENUM => Some("GL_INVALID_ENUM"),
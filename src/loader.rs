@@ -0,0 +1,299 @@
+/*!
+Loading textures from files without blocking the rendering thread.
+
+Decoding an image is usually much slower than uploading it, and uploading a large texture
+synchronously can stall a frame. The `AsyncLoader` decodes the files on a pool of worker
+threads. The decoded pixels are then copied into a pixel unpack buffer, the texture is filled
+from that buffer by the GPU, and the returned future resolves once a fence placed after the
+upload has been signaled.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+# fn block_on<F: std::future::Future>(_: F) -> F::Output { unimplemented!() }
+let loader = glium::loader::AsyncLoader::new(&display, 2);
+let texture = block_on(loader.load_texture("texture.png")).unwrap();
+# }
+```
+
+The futures must be polled on the thread that owns the OpenGL context, which means a
+single-threaded executor driven from the rendering loop. Fences can't notify a waker, so a
+future whose upload is still in progress asks to be polled again immediately.
+
+Only available if the `image` feature is enabled.
+
+*/
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferMode, BufferType};
+use crate::context::Context;
+use crate::sync::SyncFence;
+use crate::texture::{MipmapsOption, RawImage2d, Texture2d, TextureCreationError};
+use crate::texture::UncompressedFloatFormat;
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, Waker};
+use std::thread;
+
+/// Error that can happen while loading a texture.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file couldn't be read or decoded.
+    Decode(image::ImageError),
+
+    /// The texture couldn't be created.
+    TextureCreation(TextureCreationError),
+
+    /// The worker thread decoding the file has panicked.
+    WorkerPanicked,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            LoadError::Decode(err) => write!(fmt, "Error while decoding the image: {}", err),
+            LoadError::TextureCreation(err) => write!(fmt, "{}", err),
+            LoadError::WorkerPanicked => write!(fmt, "The thread decoding the image has panicked"),
+        }
+    }
+}
+
+impl Error for LoadError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LoadError::Decode(ref err) => Some(err),
+            LoadError::TextureCreation(ref err) => Some(err),
+            LoadError::WorkerPanicked => None,
+        }
+    }
+}
+
+impl From<image::ImageError> for LoadError {
+    #[inline]
+    fn from(err: image::ImageError) -> LoadError {
+        LoadError::Decode(err)
+    }
+}
+
+impl From<TextureCreationError> for LoadError {
+    #[inline]
+    fn from(err: TextureCreationError) -> LoadError {
+        LoadError::TextureCreation(err)
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Pixels of a decoded image, top row first.
+struct DecodedImage {
+    data: Vec<(u8, u8, u8, u8)>,
+    dimensions: (u32, u32),
+}
+
+/// Loads textures from files, decoding them on a pool of worker threads.
+///
+/// Dropping the loader doesn't cancel the pending loads. The worker threads exit once all the
+/// files that have been queued are decoded.
+pub struct AsyncLoader {
+    context: Rc<Context>,
+    jobs: Sender<Job>,
+}
+
+impl AsyncLoader {
+    /// Builds a loader that decodes images with `threads` worker threads.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `threads` is 0.
+    pub fn new<F>(facade: &F, threads: usize) -> AsyncLoader where F: ?Sized + Facade {
+        assert!(threads > 0, "An AsyncLoader needs at least one worker thread");
+
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0 .. threads {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("glium-loader-{}", index))
+                .spawn(move || loop {
+                    // the lock is released before running the job
+                    let job = match receiver.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => return,
+                    };
+                    job();
+                })
+                .expect("Failed to spawn a loader thread");
+        }
+
+        AsyncLoader {
+            context: facade.get_context().clone(),
+            jobs,
+        }
+    }
+
+    /// Starts loading an RGBA texture from a file.
+    ///
+    /// The format of the file is determined from its extension and content, like
+    /// `image::open` does.
+    pub fn load_texture<P>(&self, path: P) -> TextureLoad where P: AsRef<Path> {
+        let path: PathBuf = path.as_ref().to_owned();
+        let (sender, receiver) = mpsc::channel();
+        let waker = Arc::new(Mutex::new(None::<Waker>));
+
+        let job_waker = waker.clone();
+        let job: Job = Box::new(move || {
+            let image = image::open(&path).map(|image| {
+                let image = image.to_rgba8();
+                let dimensions = image.dimensions();
+                let data = image.into_raw().chunks_exact(4)
+                                .map(|p| (p[0], p[1], p[2], p[3])).collect();
+                DecodedImage { data, dimensions }
+            });
+
+            // the future may have been dropped in the meantime
+            let _ = sender.send(image);
+            if let Some(waker) = job_waker.lock().unwrap().take() {
+                waker.wake();
+            }
+        });
+
+        // the workers only exit once this sender has been dropped
+        self.jobs.send(job).unwrap();
+
+        TextureLoad {
+            context: self.context.clone(),
+            state: LoadState::Decoding { receiver, waker },
+        }
+    }
+}
+
+impl fmt::Debug for AsyncLoader {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "AsyncLoader")
+    }
+}
+
+/// A texture being loaded by an `AsyncLoader`.
+///
+/// Resolves to the texture once it has been decoded and uploaded.
+#[must_use = "futures do nothing unless polled"]
+pub struct TextureLoad {
+    context: Rc<Context>,
+    state: LoadState,
+}
+
+enum LoadState {
+    Decoding {
+        receiver: Receiver<Result<DecodedImage, image::ImageError>>,
+        waker: Arc<Mutex<Option<Waker>>>,
+    },
+    Uploading(Box<PendingUpload>),
+    Done,
+}
+
+/// A texture whose content may not have been uploaded yet.
+struct PendingUpload {
+    texture: Texture2d,
+    // `None` if fences aren't supported, as commands are executed in order anyway
+    fence: Option<SyncFence>,
+    // kept alive until the GPU has read it
+    _buffer: Option<Buffer<[(u8, u8, u8, u8)]>>,
+}
+
+impl TextureLoad {
+    /// Creates the texture from the decoded pixels, through a pixel unpack buffer if possible.
+    fn upload(&self, image: DecodedImage) -> Result<PendingUpload, LoadError> {
+        let (width, height) = image.dimensions;
+
+        let buffer = Buffer::new(&self.context, &image.data[..], BufferType::PixelUnpackBuffer,
+                                 BufferMode::Immutable);
+        let buffer = match buffer {
+            Ok(buffer) if width != 0 && height != 0 => buffer,
+            _ => {
+                // pixel buffers aren't supported, the upload is synchronous
+                let data = image.data.iter().flat_map(|&(r, g, b, a)| [r, g, b, a])
+                                .collect::<Vec<_>>();
+                let image = RawImage2d::from_raw_rgba_reversed(&data, image.dimensions);
+                let texture = Texture2d::new(&self.context, image)?;
+                return Ok(PendingUpload { texture, fence: None, _buffer: None });
+            },
+        };
+
+        let texture = Texture2d::empty_with_format(&self.context,
+                                                   UncompressedFloatFormat::U8U8U8U8,
+                                                   MipmapsOption::NoMipmap, width, height)?;
+        texture.main_level().raw_upload_from_pixel_buffer_inverted(buffer.as_slice(),
+                                                                   0 .. width, 0 .. height,
+                                                                   0 .. 1);
+
+        Ok(PendingUpload {
+            texture,
+            fence: SyncFence::new(&self.context).ok(),
+            _buffer: Some(buffer),
+        })
+    }
+}
+
+impl Future for TextureLoad {
+    type Output = Result<Texture2d, LoadError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        loop {
+            match self.state {
+                LoadState::Decoding { ref receiver, ref waker } => {
+                    // the waker is stored before checking, so that a result sent in between
+                    // isn't missed
+                    *waker.lock().unwrap() = Some(cx.waker().clone());
+
+                    let result = match receiver.try_recv() {
+                        Ok(result) => result.map_err(LoadError::from)
+                                            .and_then(|image| self.upload(image)),
+                        Err(TryRecvError::Empty) => return Poll::Pending,
+                        Err(TryRecvError::Disconnected) => Err(LoadError::WorkerPanicked),
+                    };
+
+                    match result {
+                        Ok(upload) => self.state = LoadState::Uploading(Box::new(upload)),
+                        Err(err) => {
+                            self.state = LoadState::Done;
+                            return Poll::Ready(Err(err));
+                        },
+                    }
+                },
+
+                LoadState::Uploading(ref upload) => {
+                    if matches!(upload.fence, Some(ref fence) if !fence.is_signaled()) {
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+
+                    match std::mem::replace(&mut self.state, LoadState::Done) {
+                        LoadState::Uploading(upload) => return Poll::Ready(Ok(upload.texture)),
+                        _ => unreachable!(),
+                    }
+                },
+
+                LoadState::Done => panic!("TextureLoad polled after completion"),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TextureLoad {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let state = match self.state {
+            LoadState::Decoding { .. } => "decoding",
+            LoadState::Uploading(_) => "uploading",
+            LoadState::Done => "done",
+        };
+        write!(fmt, "TextureLoad({})", state)
+    }
+}
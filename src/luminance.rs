@@ -0,0 +1,513 @@
+/*!
+Reduction of a texture to its average luminance, for auto-exposure.
+
+The `LuminanceReduction` computes the logarithmic average of the luminance of a texture
+entirely on the GPU. Two implementations exist:
+
+ - If compute shaders are supported, a histogram of the log-luminance of every texel is built
+   in a buffer, then reduced to the average. Both the histogram and the average can be read
+   back or bound to other shaders as shader storage blocks.
+
+ - Otherwise, the log-luminance is rendered into a `256x256` floating-point texture which is
+   then downsampled by successive draws until it reaches `1x1`. Since the source is
+   sampled with bilinear filtering, this path is less precise. No histogram is available.
+
+This module is only available if the `luminance` feature is enabled.
+
+## Example
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>, hdr_texture: glium::Texture2d)
+#     where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::luminance::{LuminanceReduction, LuminanceSettings};
+
+let reduction = LuminanceReduction::new(&display, LuminanceSettings::default()).unwrap();
+reduction.reduce(&hdr_texture).unwrap();
+
+let exposure = 0.18 / reduction.read_average().average;
+# }
+```
+
+*/
+use std::fmt;
+use std::error::Error;
+
+use crate::backend::Facade;
+use crate::buffer::BufferCreationError;
+use crate::index::{NoIndices, PrimitiveType};
+use crate::program::{ComputeShader, Program, ProgramCreationError};
+use crate::texture::{MipmapsOption, Texture2d, TextureCreationError, UncompressedFloatFormat};
+use crate::uniforms::UniformBuffer;
+use crate::vertex::EmptyVertexAttributes;
+use crate::version::Api;
+
+use crate::CapabilitiesSource;
+use crate::DrawError;
+use crate::Rect;
+use crate::Surface;
+
+/// Number of bins of the histogram built by the compute implementation.
+///
+/// The first bin contains the texels that are considered black. The other bins evenly divide
+/// the range between `min_log_luminance` and `max_log_luminance`. Like with the fragment
+/// implementation, black texels are counted as `min_log_luminance` in the average.
+pub const HISTOGRAM_BINS: usize = 256;
+
+/// Dimensions of the first texture of the chain used by the fragment implementation.
+const FRAGMENT_CHAIN_SIZE: u32 = 256;
+
+/// Parameters of a `LuminanceReduction`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LuminanceSettings {
+    /// Base-2 logarithm of the lowest luminance that is taken into account. Darker texels
+    /// are clamped to this value.
+    pub min_log_luminance: f32,
+
+    /// Base-2 logarithm of the highest luminance that is taken into account. Brighter texels
+    /// are clamped to this value.
+    pub max_log_luminance: f32,
+}
+
+impl Default for LuminanceSettings {
+    #[inline]
+    fn default() -> LuminanceSettings {
+        LuminanceSettings {
+            min_log_luminance: -10.0,
+            max_log_luminance: 2.0,
+        }
+    }
+}
+
+/// Result of the reduction.
+///
+/// This is also the layout of the buffer returned by `LuminanceReduction::get_average_buffer`,
+/// which corresponds to `buffer { float average; float log_average; }` in GLSL.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AverageLuminance {
+    /// The geometric mean of the luminance of the texels.
+    pub average: f32,
+    /// Base-2 logarithm of `average`.
+    pub log_average: f32,
+}
+
+implement_uniform_block!(AverageLuminance, average, log_average);
+
+/// Error that can happen when creating a `LuminanceReduction`.
+#[derive(Debug)]
+pub enum ReductionCreationError {
+    /// Error while compiling the shaders.
+    ProgramCreationError(ProgramCreationError),
+    /// Error while creating the histogram buffers.
+    BufferCreationError(BufferCreationError),
+    /// Error while creating the floating-point textures of the fragment implementation.
+    TextureCreationError(TextureCreationError),
+}
+
+impl fmt::Display for ReductionCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::ReductionCreationError::*;
+        match *self {
+            ProgramCreationError(ref err) => write!(fmt, "Error while compiling the shaders: {}", err),
+            BufferCreationError(ref err) => write!(fmt, "Error while creating the buffers: {}", err),
+            TextureCreationError(ref err) => write!(fmt, "Error while creating the textures: {}", err),
+        }
+    }
+}
+
+impl Error for ReductionCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::ReductionCreationError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            BufferCreationError(ref err) => Some(err),
+            TextureCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramCreationError> for ReductionCreationError {
+    #[inline]
+    fn from(err: ProgramCreationError) -> ReductionCreationError {
+        ReductionCreationError::ProgramCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for ReductionCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> ReductionCreationError {
+        ReductionCreationError::BufferCreationError(err)
+    }
+}
+
+impl From<TextureCreationError> for ReductionCreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> ReductionCreationError {
+        ReductionCreationError::TextureCreationError(err)
+    }
+}
+
+/// Computes the average luminance of textures.
+///
+/// See the documentation of the module for more information.
+pub struct LuminanceReduction {
+    settings: LuminanceSettings,
+    implementation: Implementation,
+}
+
+enum Implementation {
+    Compute(Box<ComputeImplementation>),
+    Fragment(Box<FragmentImplementation>),
+}
+
+struct ComputeImplementation {
+    histogram_pass: ComputeShader,
+    average_pass: ComputeShader,
+    histogram: UniformBuffer<[u32; HISTOGRAM_BINS]>,
+    average: UniformBuffer<AverageLuminance>,
+}
+
+struct FragmentImplementation {
+    log_luminance_pass: Program,
+    downsample_pass: Program,
+    // textures from `FRAGMENT_CHAIN_SIZE` down to `1x1`
+    chain: Vec<Texture2d>,
+}
+
+impl LuminanceReduction {
+    /// Builds a new `LuminanceReduction`.
+    ///
+    /// Uses compute shaders if they are supported, and the fragment implementation otherwise.
+    pub fn new<F>(facade: &F, settings: LuminanceSettings)
+                  -> Result<LuminanceReduction, ReductionCreationError> where F: ?Sized + Facade
+    {
+        let implementation = if ComputeShader::is_supported(facade) {
+            Implementation::Compute(Box::new(ComputeImplementation {
+                histogram_pass: build_compute_shader(facade, HISTOGRAM_SHADER)?,
+                average_pass: build_compute_shader(facade, AVERAGE_SHADER)?,
+                histogram: UniformBuffer::new(facade, [0; HISTOGRAM_BINS])?,
+                average: UniformBuffer::new(facade, AverageLuminance {
+                    average: 0.0,
+                    log_average: 0.0,
+                })?,
+            }))
+
+        } else {
+            let log_luminance_pass = build_fragment_program(facade, LOG_LUMINANCE_SHADER)?;
+            let downsample_pass = build_fragment_program(facade, DOWNSAMPLE_SHADER)?;
+
+            let mut chain = Vec::new();
+            let mut size = FRAGMENT_CHAIN_SIZE;
+            loop {
+                chain.push(Texture2d::empty_with_format(facade, UncompressedFloatFormat::F32,
+                                                        MipmapsOption::NoMipmap, size, size)?);
+                if size == 1 {
+                    break;
+                }
+                size /= 2;
+            }
+
+            Implementation::Fragment(Box::new(FragmentImplementation {
+                log_luminance_pass,
+                downsample_pass,
+                chain,
+            }))
+        };
+
+        Ok(LuminanceReduction {
+            settings,
+            implementation,
+        })
+    }
+
+    /// Returns the settings that were passed when creating the reduction.
+    #[inline]
+    pub fn get_settings(&self) -> &LuminanceSettings {
+        &self.settings
+    }
+
+    /// Returns true if the reduction is implemented with compute shaders.
+    #[inline]
+    pub fn uses_compute_shaders(&self) -> bool {
+        matches!(self.implementation, Implementation::Compute(_))
+    }
+
+    /// Computes the average luminance of the texture.
+    ///
+    /// The result is not read back. Use `read_average`, `get_average_buffer` or
+    /// `get_average_texture` to access it.
+    pub fn reduce(&self, source: &Texture2d) -> Result<(), DrawError> {
+        let min_log_luminance = self.settings.min_log_luminance;
+        let log_luminance_range = self.settings.max_log_luminance - min_log_luminance;
+
+        match self.implementation {
+            Implementation::Compute(ref compute) => {
+                let ComputeImplementation { ref histogram_pass, ref average_pass, ref histogram,
+                                            ref average } = **compute;
+
+                histogram.write(&[0; HISTOGRAM_BINS]);
+
+                let (width, height) = source.dimensions();
+                histogram_pass.execute(uniform! {
+                    source: source,
+                    min_log_luminance: min_log_luminance,
+                    inv_log_luminance_range: 1.0 / log_luminance_range,
+                    Histogram: &**histogram,
+                }, (width + 15) / 16, (height + 15) / 16, 1);
+
+                average_pass.execute(uniform! {
+                    pixel_count: width * height,
+                    min_log_luminance: min_log_luminance,
+                    log_luminance_range: log_luminance_range,
+                    Histogram: &**histogram,
+                    Average: &**average,
+                }, 1, 1, 1);
+            },
+
+            Implementation::Fragment(ref fragment) => {
+                let FragmentImplementation { ref log_luminance_pass, ref downsample_pass,
+                                             ref chain } = **fragment;
+
+                chain[0].as_surface().draw(EmptyVertexAttributes { len: 3 },
+                                           NoIndices(PrimitiveType::TrianglesList),
+                                           log_luminance_pass, &uniform! {
+                                               source: source,
+                                               min_log_luminance: min_log_luminance,
+                                               max_log_luminance: self.settings.max_log_luminance,
+                                           }, &Default::default())?;
+
+                for pair in chain.windows(2) {
+                    pair[1].as_surface().draw(EmptyVertexAttributes { len: 3 },
+                                              NoIndices(PrimitiveType::TrianglesList),
+                                              downsample_pass, &uniform! {
+                                                  previous: &pair[0],
+                                              }, &Default::default())?;
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Reads back the result of the latest call to `reduce`.
+    ///
+    /// This blocks until the reduction is over.
+    ///
+    /// # Panic
+    ///
+    /// Panics with the compute implementation on OpenGL ES, as buffers can't be read back there.
+    /// Use `get_average_buffer` from a shader instead.
+    pub fn read_average(&self) -> AverageLuminance {
+        match self.implementation {
+            Implementation::Compute(ref compute) => {
+                compute.average.read().expect("the backend doesn't support reading buffers")
+            },
+
+            Implementation::Fragment(ref fragment) => {
+                let image = fragment.chain.last().unwrap().main_level().first_layer()
+                                 .into_image(None).unwrap();
+                let rect = Rect { left: 0, bottom: 0, width: 1, height: 1 };
+                let data: Vec<Vec<(f32, f32, f32, f32)>> = image.raw_read(&rect);
+                let log_average = data[0][0].0;
+
+                AverageLuminance {
+                    average: log_average.exp2(),
+                    log_average,
+                }
+            },
+        }
+    }
+
+    /// Returns the buffer that contains the result of the compute implementation.
+    ///
+    /// Returns `None` if the fragment implementation is used.
+    #[inline]
+    pub fn get_average_buffer(&self) -> Option<&UniformBuffer<AverageLuminance>> {
+        match self.implementation {
+            Implementation::Compute(ref compute) => Some(&compute.average),
+            Implementation::Fragment(_) => None,
+        }
+    }
+
+    /// Returns the histogram built by the compute implementation.
+    ///
+    /// It corresponds to `buffer { uint bins[256]; }` in GLSL. Returns `None` if the fragment
+    /// implementation is used.
+    #[inline]
+    pub fn get_histogram_buffer(&self) -> Option<&UniformBuffer<[u32; HISTOGRAM_BINS]>> {
+        match self.implementation {
+            Implementation::Compute(ref compute) => Some(&compute.histogram),
+            Implementation::Fragment(_) => None,
+        }
+    }
+
+    /// Returns the `1x1` texture that contains the result of the fragment implementation.
+    ///
+    /// Its red channel contains the base-2 logarithm of the average luminance. Returns `None`
+    /// if the compute implementation is used.
+    #[inline]
+    pub fn get_average_texture(&self) -> Option<&Texture2d> {
+        match self.implementation {
+            Implementation::Compute(_) => None,
+            Implementation::Fragment(ref fragment) => fragment.chain.last(),
+        }
+    }
+}
+
+/// Builds a compute shader, with the `#version` directive that matches the API of the context.
+fn build_compute_shader<F>(facade: &F, source: &str)
+                           -> Result<ComputeShader, ProgramCreationError> where F: ?Sized + Facade
+{
+    let header = if facade.get_version().0 == Api::GlEs {
+        "#version 310 es\nprecision highp float;\nprecision highp int;\n\
+         precision highp sampler2D;\n"
+    } else {
+        "#version 430\n"
+    };
+
+    ComputeShader::from_source(facade, &format!("{}{}", header, source))
+}
+
+// the compute shaders don't have a `#version` directive, see `build_compute_shader`
+
+const HISTOGRAM_SHADER: &str = "
+    layout(local_size_x = 16, local_size_y = 16, local_size_z = 1) in;
+
+    uniform sampler2D source;
+    uniform float min_log_luminance;
+    uniform float inv_log_luminance_range;
+
+    layout(std430) buffer Histogram {
+        uint bins[256];
+    };
+
+    shared uint local_bins[256];
+
+    uint bin_index(vec3 color) {
+        float luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+        if (luminance < 0.0001) {
+            return 0u;
+        }
+
+        float t = clamp((log2(luminance) - min_log_luminance) * inv_log_luminance_range, 0.0, 1.0);
+        return uint(t * 254.0 + 1.0);
+    }
+
+    void main() {
+        local_bins[gl_LocalInvocationIndex] = 0u;
+        memoryBarrierShared();
+        barrier();
+
+        ivec2 coords = ivec2(gl_GlobalInvocationID.xy);
+        ivec2 size = textureSize(source, 0);
+        if (coords.x < size.x && coords.y < size.y) {
+            atomicAdd(local_bins[bin_index(texelFetch(source, coords, 0).rgb)], 1u);
+        }
+
+        memoryBarrierShared();
+        barrier();
+        atomicAdd(bins[gl_LocalInvocationIndex], local_bins[gl_LocalInvocationIndex]);
+    }
+";
+
+const AVERAGE_SHADER: &str = "
+    layout(local_size_x = 256, local_size_y = 1, local_size_z = 1) in;
+
+    uniform uint pixel_count;
+    uniform float min_log_luminance;
+    uniform float log_luminance_range;
+
+    layout(std430) buffer Histogram {
+        uint bins[256];
+    };
+
+    layout(std430) buffer Average {
+        float average;
+        float log_average;
+    };
+
+    shared float weighted[256];
+
+    void main() {
+        uint i = gl_LocalInvocationIndex;
+        // black texels are clamped to the lowest bin, like the other dark texels
+        weighted[i] = float(bins[i]) * float(max(i, 1u));
+        memoryBarrierShared();
+        barrier();
+
+        for (uint stride = 128u; stride > 0u; stride >>= 1u) {
+            if (i < stride) {
+                weighted[i] += weighted[i + stride];
+            }
+            memoryBarrierShared();
+            barrier();
+        }
+
+        if (i == 0u) {
+            float mean_bin = max(weighted[0] / max(float(pixel_count), 1.0) - 1.0, 0.0);
+            log_average = mean_bin / 254.0 * log_luminance_range + min_log_luminance;
+            average = exp2(log_average);
+        }
+    }
+";
+
+/// Builds a program of the fragment implementation, with the `#version` directive that matches
+/// the API of the context.
+fn build_fragment_program<F>(facade: &F, fragment_shader: &str)
+                             -> Result<Program, ProgramCreationError> where F: ?Sized + Facade
+{
+    let header = if facade.get_version().0 == Api::GlEs {
+        "#version 300 es\nprecision highp float;\n"
+    } else {
+        "#version 140\n"
+    };
+
+    let vertex_shader = format!("{}{}", header, FULLSCREEN_VERTEX_SHADER);
+    let fragment_shader = format!("{}{}", header, fragment_shader);
+    Program::from_source(facade, &vertex_shader, &fragment_shader, None)
+}
+
+// the shaders below don't have a `#version` directive, see `build_fragment_program`
+
+const FULLSCREEN_VERTEX_SHADER: &str = "
+    out vec2 v_tex_coords;
+
+    void main() {
+        vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+        v_tex_coords = position;
+        gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+    }
+";
+
+const LOG_LUMINANCE_SHADER: &str = "
+    uniform sampler2D source;
+    uniform float min_log_luminance;
+    uniform float max_log_luminance;
+
+    in vec2 v_tex_coords;
+    out vec4 f_log_luminance;
+
+    void main() {
+        float luminance = dot(texture(source, v_tex_coords).rgb, vec3(0.2126, 0.7152, 0.0722));
+        float log_luminance = clamp(log2(max(luminance, 0.0001)), min_log_luminance,
+                                    max_log_luminance);
+        f_log_luminance = vec4(log_luminance);
+    }
+";
+
+const DOWNSAMPLE_SHADER: &str = "
+    uniform sampler2D previous;
+
+    out vec4 f_log_luminance;
+
+    void main() {
+        ivec2 base = ivec2(gl_FragCoord.xy) * 2;
+        float sum = texelFetch(previous, base, 0).r +
+                    texelFetch(previous, base + ivec2(1, 0), 0).r +
+                    texelFetch(previous, base + ivec2(0, 1), 0).r +
+                    texelFetch(previous, base + ivec2(1, 1), 0).r;
+        f_log_luminance = vec4(sum * 0.25);
+    }
+";
@@ -388,6 +388,48 @@ macro_rules! implement_buffer_content {
     );
 }
 
+/// Implements the `glium::uniforms::AsUniformValues` trait for the given type, so that it can
+/// be used as the value of a GLSL struct uniform or as the element of a `UniformArray`.
+///
+/// The parameters must be the name of the struct and the names of its fields. Each field is
+/// bound to the uniform named `name.field`.
+///
+/// ## Example
+///
+/// ```
+/// # use glium::implement_uniform_struct;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Light {
+///     position: [f32; 3],
+///     intensity: f32,
+/// }
+///
+/// implement_uniform_struct!(Light, position, intensity);
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_uniform_struct {
+    ($struct_name:ident, $($field_name:ident),+) => (
+        impl $crate::uniforms::AsUniformValues for $struct_name {
+            fn visit_uniform_values<'a>(&'a self, name: &str,
+                                        output: &mut dyn FnMut(&str, $crate::uniforms::UniformValue<'a>))
+            {
+                $(
+                    $crate::uniforms::AsUniformValues::visit_uniform_values(
+                        &self.$field_name, &format!("{}.{}", name, stringify!($field_name)), output
+                    );
+                )+
+            }
+        }
+    );
+
+    ($struct_name:ident, $($field_name:ident),+,) => (
+        $crate::implement_uniform_struct!($struct_name, $($field_name),+);
+    );
+}
+
 /// Implements the `glium::uniforms::UniformBlock` trait for the given type.
 ///
 /// The parameters must be the name of the struct and the names of its fields.
@@ -412,7 +454,7 @@ macro_rules! implement_uniform_block {
     (__as_item $i:item) => {$i};
 
     (__impl $struct_name:ident [$($gs:tt)*], $($field_name:ident),+) => (
-        implement_uniform_block! { __as_item
+        $crate::implement_uniform_block! { __as_item
             impl<$($gs)*> $crate::uniforms::UniformBlock for $struct_name<$($gs)*> {
                 fn matches(layout: &$crate::program::BlockLayout, base_offset: usize)
                            -> ::std::result::Result<(), $crate::uniforms::LayoutMismatchError>
@@ -1,5 +1,9 @@
 /*!
 Contains everything related to external API memory objects.
+
+A memory object is imported with `MemoryObject::new_from_fd`, and buffers are then built on
+ranges of it with `Buffer::from_memory_object`. Textures are imported directly with the
+`new_from_fd` constructors of the texture types.
 */
 
 // TODO: Add Windows support via EXT_external_objects_win32
@@ -60,7 +64,7 @@ impl MemoryObject {
         fd: std::fs::File,
         size: u64,
     ) -> Result<Self, MemoryObjectCreationError> {
-        use std::os::unix::io::AsRawFd;
+        use std::os::unix::io::IntoRawFd;
         let ctxt = facade.get_context().make_current();
         let mem_obj: Self = Self::new(facade, &ctxt)?;
 
@@ -79,11 +83,12 @@ impl MemoryObject {
                 &dedicated as *const i32,
             );
 
+            // the implementation takes ownership of the file descriptor
             ctxt.gl.ImportMemoryFdEXT(
                 mem_obj.id,
                 size,
                 gl::HANDLE_TYPE_OPAQUE_FD_EXT,
-                fd.as_raw_fd(),
+                fd.into_raw_fd(),
             );
 
             Ok(mem_obj)
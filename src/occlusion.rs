@@ -0,0 +1,248 @@
+/*!
+Visibility tests of points with occlusion queries, for example for lens flares.
+
+The `OcclusionProbes` draws each probe point as an invisible point with its own
+`AnySamplesPassedQuery`, against the depth buffer of the target. Reading the result of a query
+right after drawing would stall until the GPU has caught up, so the queries of a frame are only
+read during one of the next frames, when they are ready.
+
+## Example
+
+```no_run
+# fn example<F: glium::backend::Facade>(display: &F, mut target: glium::Frame, sun: [f32; 3]) {
+use glium::occlusion::OcclusionProbes;
+
+let mut probes = OcclusionProbes::new(display).unwrap();
+
+// every frame, after drawing the scene
+probes.test(&mut target, &[sun]).unwrap();
+
+// the results of a previous frame
+let sun_visible = probes.visibility().first().cloned().unwrap_or(false);
+# }
+```
+
+*/
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::draw_parameters::{AnySamplesPassedQuery, QueryCreationError};
+use crate::draw_parameters::{Depth, DepthTest};
+use crate::index::{NoIndices, PrimitiveType};
+use crate::program::{Program, ProgramCreationError, ProgramCreationInput};
+use crate::vertex::EmptyVertexAttributes;
+use crate::version::Api;
+
+use crate::CapabilitiesSource;
+use crate::DrawError;
+use crate::DrawParameters;
+use crate::Surface;
+
+/// Number of frames whose queries can be pending before `test` waits for the oldest one.
+const MAX_FRAMES_IN_FLIGHT: usize = 3;
+
+/// Error that can happen when creating an `OcclusionProbes`.
+#[derive(Debug)]
+pub enum OcclusionProbesCreationError {
+    /// Error while compiling the shaders.
+    ProgramCreationError(ProgramCreationError),
+    /// Occlusion queries are not supported by the backend.
+    QueryCreationError(QueryCreationError),
+}
+
+impl fmt::Display for OcclusionProbesCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::OcclusionProbesCreationError::*;
+        match *self {
+            ProgramCreationError(ref err) => write!(fmt, "Error while compiling the shaders: {}", err),
+            QueryCreationError(ref err) => write!(fmt, "Error while creating the queries: {}", err),
+        }
+    }
+}
+
+impl Error for OcclusionProbesCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use self::OcclusionProbesCreationError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            QueryCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramCreationError> for OcclusionProbesCreationError {
+    #[inline]
+    fn from(err: ProgramCreationError) -> OcclusionProbesCreationError {
+        OcclusionProbesCreationError::ProgramCreationError(err)
+    }
+}
+
+impl From<QueryCreationError> for OcclusionProbesCreationError {
+    #[inline]
+    fn from(err: QueryCreationError) -> OcclusionProbesCreationError {
+        OcclusionProbesCreationError::QueryCreationError(err)
+    }
+}
+
+/// Tests whether points of the screen are hidden by what has been drawn.
+///
+/// See the module-level documentation.
+pub struct OcclusionProbes {
+    context: Rc<Context>,
+    program: Program,
+    probe_size: f32,
+    pending: VecDeque<Vec<AnySamplesPassedQuery>>,
+    visibility: Vec<bool>,
+}
+
+impl OcclusionProbes {
+    /// Builds a new `OcclusionProbes`.
+    pub fn new<F>(facade: &F) -> Result<OcclusionProbes, OcclusionProbesCreationError>
+                  where F: ?Sized + Facade
+    {
+        // checks that occlusion queries are supported, so that `test` can't fail to create them
+        AnySamplesPassedQuery::new(facade, false)?;
+
+        let header = if facade.get_version().0 == Api::GlEs {
+            "#version 300 es\nprecision highp float;\n"
+        } else {
+            "#version 140\n"
+        };
+
+        let vertex_shader = format!("{}{}", header, VERTEX_SHADER);
+        let fragment_shader = format!("{}{}", header, FRAGMENT_SHADER);
+
+        let program = Program::new(facade, ProgramCreationInput::SourceCode {
+            vertex_shader: &vertex_shader,
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: None,
+            geometry_shader: None,
+            fragment_shader: &fragment_shader,
+            transform_feedback_varyings: None,
+            outputs_srgb: true,
+            uses_point_size: true,
+        })?;
+
+        Ok(OcclusionProbes {
+            context: facade.get_context().clone(),
+            program,
+            probe_size: 1.0,
+            pending: VecDeque::with_capacity(MAX_FRAMES_IN_FLIGHT),
+            visibility: Vec::new(),
+        })
+    }
+
+    /// Sets the diameter in pixels of the point that is drawn for each probe. The default is
+    /// `1.0`.
+    ///
+    /// A probe is visible if at least one of its pixels is visible.
+    #[inline]
+    pub fn set_probe_size(&mut self, size: f32) {
+        self.probe_size = size;
+    }
+
+    /// Draws the probes on `target` and starts their queries.
+    ///
+    /// Each probe is a position in normalized device coordinates, in other words the position
+    /// returned by the vertex shader divided by its `w` component. The probe is hidden if the
+    /// depth buffer of the target contains a smaller depth at this position. If the target
+    /// doesn't have a depth buffer, all the probes are visible. Nothing is written to the target.
+    ///
+    /// The results are available from `visibility` during one of the next frames. If the
+    /// queries of several previous frames are still pending, this function waits for the oldest
+    /// ones.
+    pub fn test<S>(&mut self, target: &mut S, probes: &[[f32; 3]]) -> Result<(), DrawError>
+                   where S: ?Sized + Surface
+    {
+        self.poll();
+
+        if self.pending.len() >= MAX_FRAMES_IN_FLIGHT {
+            let oldest = self.pending.pop_front().unwrap();
+            self.visibility = oldest.into_iter().map(|query| query.get()).collect();
+        }
+
+        let depth_test = if target.has_depth_buffer() {
+            DepthTest::IfLessOrEqual
+        } else {
+            DepthTest::Overwrite
+        };
+
+        let mut queries = Vec::with_capacity(probes.len());
+
+        for &probe in probes {
+            let query = AnySamplesPassedQuery::new(&self.context, false)
+                                              .expect("Occlusion queries are supported");
+
+            let uniforms = uniform! {
+                position: probe,
+                size: self.probe_size,
+            };
+
+            target.draw(EmptyVertexAttributes { len: 1 }, NoIndices(PrimitiveType::Points),
+                        &self.program, &uniforms, &DrawParameters {
+                            depth: Depth {
+                                test: depth_test,
+                                write: false,
+                                .. Default::default()
+                            },
+                            color_mask: (false, false, false, false),
+                            samples_passed_query: Some((&query).into()),
+                            .. Default::default()
+                        })?;
+
+            queries.push(query);
+        }
+
+        self.pending.push_back(queries);
+        Ok(())
+    }
+
+    /// Returns the visibility of each probe, in the order in which they were passed to `test`,
+    /// for the most recent frame whose results are available.
+    ///
+    /// Returns an empty slice if no result is available yet.
+    #[inline]
+    pub fn visibility(&self) -> &[bool] {
+        &self.visibility
+    }
+
+    /// Reads the results of the frames whose queries are all ready, without blocking.
+    fn poll(&mut self) {
+        while matches!(self.pending.front(), Some(queries) if queries.iter().all(|q| q.is_ready())) {
+            let queries = self.pending.pop_front().unwrap();
+            self.visibility = queries.into_iter().map(|query| query.get()).collect();
+        }
+    }
+}
+
+impl fmt::Debug for OcclusionProbes {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "OcclusionProbes {{ pending_frames: {}, visibility: {:?} }}",
+               self.pending.len(), self.visibility)
+    }
+}
+
+// the shaders below don't have a `#version` directive, see `OcclusionProbes::new`
+
+const VERTEX_SHADER: &str = "
+    uniform vec3 position;
+    uniform float size;
+
+    void main() {
+        gl_Position = vec4(position, 1.0);
+        gl_PointSize = size;
+    }
+";
+
+const FRAGMENT_SHADER: &str = "
+    out vec4 f_color;
+
+    void main() {
+        f_color = vec4(0.0);
+    }
+";
@@ -0,0 +1,29 @@
+use crate::context::CommandContext;
+use crate::version::Api;
+use crate::version::Version;
+use crate::CapabilitiesSource;
+use crate::TextureBarrierNotSupportedError;
+
+/// Returns true if texture barriers are supported by the backend.
+pub fn is_texture_barrier_supported<C>(context: &C) -> bool where C: ?Sized + CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 4, 5) ||
+    context.get_extensions().gl_arb_texture_barrier ||
+    context.get_extensions().gl_nv_texture_barrier
+}
+
+/// Calls `glTextureBarrier`, so that the texels written by the previous draw commands can be
+/// safely read by the next ones even if the texture is still attached to the framebuffer.
+pub fn texture_barrier(ctxt: &mut CommandContext<'_>) -> Result<(), TextureBarrierNotSupportedError> {
+    unsafe {
+        if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_texture_barrier {
+            ctxt.gl.TextureBarrier();
+        } else if ctxt.extensions.gl_nv_texture_barrier {
+            ctxt.gl.TextureBarrierNV();
+        } else {
+            return Err(TextureBarrierNotSupportedError);
+        }
+    }
+
+    ctxt.state.textures_written_since_barrier.clear();
+    Ok(())
+}
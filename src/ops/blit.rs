@@ -1,4 +1,8 @@
+use std::error::Error;
+use std::fmt;
+
 use crate::BlitTarget;
+use crate::CapabilitiesSource;
 use crate::Rect;
 
 use crate::context::Context;
@@ -7,18 +11,82 @@ use crate::ContextExt;
 use crate::fbo::FramebuffersContainer;
 use crate::fbo::ValidatedAttachments;
 
+use crate::debug::StateAudit;
 use crate::gl;
 use crate::version::Version;
 use crate::version::Api;
 
+/// Error that can happen while blitting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlitError {
+    /// The depth or stencil buffer is copied with a filter other than `Nearest`.
+    InvalidFilter,
+
+    /// The depth buffers of the source and the target don't have the same format.
+    DepthFormatMismatch {
+        /// Number of bits of the depth buffer of the source.
+        source: u16,
+        /// Number of bits of the depth buffer of the target.
+        target: u16,
+    },
+
+    /// The stencil buffers of the source and the target don't have the same format.
+    StencilFormatMismatch {
+        /// Number of bits of the stencil buffer of the source.
+        source: u16,
+        /// Number of bits of the stencil buffer of the target.
+        target: u16,
+    },
+
+    /// The source is multisampled but the source and target areas don't have the same
+    /// dimensions. Multisampled surfaces can only be resolved, not resized.
+    MultisampledScaling,
+
+    /// The source and the target are both multisampled but don't have the same number of
+    /// samples.
+    SamplesCountMismatch {
+        /// Number of samples of the source.
+        source: u32,
+        /// Number of samples of the target.
+        target: u32,
+    },
+
+    /// The source can't copy these buffers. This is the case of an `EmptyFrameBuffer`, which
+    /// has none, and of surfaces that don't override `Surface::blit_buffers`.
+    NotSupported,
+}
+
+impl fmt::Display for BlitError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::BlitError::*;
+        match *self {
+            InvalidFilter =>
+                fmt.write_str("The depth or stencil buffer can only be copied with the `Nearest` filter"),
+            DepthFormatMismatch { source, target } =>
+                write!(fmt, "The source has a {}-bit depth buffer but the target has a {}-bit one",
+                       source, target),
+            StencilFormatMismatch { source, target } =>
+                write!(fmt, "The source has a {}-bit stencil buffer but the target has a {}-bit one",
+                       source, target),
+            MultisampledScaling =>
+                fmt.write_str("A multisampled source can only be copied to an area of the same dimensions"),
+            SamplesCountMismatch { source, target } =>
+                write!(fmt, "The source has {} samples but the target has {}", source, target),
+            NotSupported =>
+                fmt.write_str("The source doesn't support copying these buffers"),
+        }
+    }
+}
+
+impl Error for BlitError {}
+
 pub fn blit(context: &Context, source: Option<&ValidatedAttachments<'_>>,
             target: Option<&ValidatedAttachments<'_>>, mask: gl::types::GLbitfield,
             src_rect: &Rect, target_rect: &BlitTarget, filter: gl::types::GLenum)
+            -> Result<(), BlitError>
 {
-    assert!(
-        (mask & gl::DEPTH_BUFFER_BIT == 0 && mask & gl::STENCIL_BUFFER_BIT == 0) || filter == gl::NEAREST,
-        "Blitting the depth and/or stencil buffer with filter being anything other than GL_NEAREST is an invalid operation."
-    );
+    validate(context, source, target, mask, src_rect, target_rect, filter)?;
+
     unsafe {
         let mut ctxt = context.make_current();
 
@@ -43,9 +111,11 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments<'_>>,
                 (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
                 (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
 
-            return;
+            return Ok(());
         }
 
+        let previous = (ctxt.state.draw_framebuffer, ctxt.state.read_framebuffer);
+
         // binding source framebuffer
         if ctxt.state.read_framebuffer != source {
             if ctxt.version >= &Version(Api::Gl, 3, 0) {
@@ -70,6 +140,11 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments<'_>>,
             }
         }
 
+        let current = (ctxt.state.draw_framebuffer, ctxt.state.read_framebuffer);
+        if current != previous {
+            ctxt.state.audit("glBindFramebuffer", 0, StateAudit::hash(current));
+        }
+
         // doing the blit
         if ctxt.version >= &Version(Api::Gl, 3, 0) {
             ctxt.gl.BlitFramebuffer(src_rect.left as gl::types::GLint,
@@ -90,4 +165,68 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments<'_>>,
                 (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
         }
     }
+
+    Ok(())
+}
+
+/// Checks the parameters of a blit that OpenGL would reject with `GL_INVALID_OPERATION`.
+///
+/// The default framebuffer is checked with the depth and stencil bits reported by the context,
+/// and its number of samples is unknown.
+fn validate(context: &Context, source: Option<&ValidatedAttachments<'_>>,
+            target: Option<&ValidatedAttachments<'_>>, mask: gl::types::GLbitfield,
+            src_rect: &Rect, target_rect: &BlitTarget, filter: gl::types::GLenum)
+            -> Result<(), BlitError>
+{
+    let depth = mask & gl::DEPTH_BUFFER_BIT != 0;
+    let stencil = mask & gl::STENCIL_BUFFER_BIT != 0;
+
+    if (depth || stencil) && filter != gl::NEAREST {
+        return Err(BlitError::InvalidFilter);
+    }
+
+    let capabilities = context.get_capabilities();
+    let depth_bits = |a: Option<&ValidatedAttachments<'_>>| match a {
+        Some(a) => a.get_depth_buffer_bits(),
+        None => capabilities.depth_bits,
+    };
+    let stencil_bits = |a: Option<&ValidatedAttachments<'_>>| match a {
+        Some(a) => a.get_stencil_buffer_bits(),
+        None => capabilities.stencil_bits,
+    };
+
+    // buffers that are missing on either side are ignored by OpenGL
+    if depth {
+        if let (Some(s), Some(t)) = (depth_bits(source), depth_bits(target)) {
+            if s != 0 && t != 0 && s != t {
+                return Err(BlitError::DepthFormatMismatch { source: s, target: t });
+            }
+        }
+    }
+
+    if stencil {
+        if let (Some(s), Some(t)) = (stencil_bits(source), stencil_bits(target)) {
+            if s != 0 && t != 0 && s != t {
+                return Err(BlitError::StencilFormatMismatch { source: s, target: t });
+            }
+        }
+    }
+
+    let source_samples = source.map(|a| a.get_samples()).unwrap_or(0);
+    let target_samples = target.map(|a| a.get_samples()).unwrap_or(0);
+
+    if source_samples != 0 {
+        if src_rect.width as i32 != target_rect.width || src_rect.height as i32 != target_rect.height {
+            return Err(BlitError::MultisampledScaling);
+        }
+
+        if target_samples != 0 && source_samples != target_samples {
+            return Err(BlitError::SamplesCountMismatch {
+                source: source_samples,
+                target: target_samples,
+            });
+        }
+    }
+
+    Ok(())
 }
@@ -3,6 +3,7 @@ use crate::fbo::{self, ValidatedAttachments};
 use crate::context::Context;
 use crate::ContextExt;
 use crate::Rect;
+use crate::{ClearColor, ClearValues};
 
 use crate::QueryExt;
 use crate::draw_parameters::TimeElapsedQuery;
@@ -13,8 +14,7 @@ use crate::gl;
 
 
 pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
-             rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
-             depth: Option<f32>, stencil: Option<i32>)
+             rect: Option<&Rect>, values: &ClearValues)
 {
     unsafe {
         let mut ctxt = context.make_current();
@@ -27,19 +27,20 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
             ctxt.state.enabled_rasterizer_discard = false;
         }
 
-        if ctxt.state.color_mask != (1, 1, 1, 1) {
+        if ctxt.state.color_mask != (1, 1, 1, 1) || ctxt.state.attachment_color_mask {
             ctxt.state.color_mask = (1, 1, 1, 1);
+            ctxt.state.attachment_color_mask = false;
             ctxt.gl.ColorMask(1, 1, 1, 1);
         }
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
            ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
         {
-            if !color_srgb && !ctxt.state.enabled_framebuffer_srgb {
+            if !values.color_srgb && !ctxt.state.enabled_framebuffer_srgb {
                 ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
                 ctxt.state.enabled_framebuffer_srgb = true;
 
-            } else if color_srgb && ctxt.state.enabled_framebuffer_srgb {
+            } else if values.color_srgb && ctxt.state.enabled_framebuffer_srgb {
                 ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
                 ctxt.state.enabled_framebuffer_srgb = false;
             }
@@ -54,6 +55,7 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
             if ctxt.state.scissor != Some(rect) {
                 ctxt.gl.Scissor(rect.0, rect.1, rect.2, rect.3);
                 ctxt.state.scissor = Some(rect);
+                ctxt.state.scissors.clear();
             }
 
             if !ctxt.state.enabled_scissor_test {
@@ -66,6 +68,20 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
             ctxt.state.enabled_scissor_test = false;
         }
 
+        let supports_clear_buffer = ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                                    ctxt.version >= &Version(Api::GlEs, 3, 0);
+
+        // without `glClearBuffer`, only the first attachment can be cleared with a float color
+        let mut color = values.color;
+        if !supports_clear_buffer {
+            for &(index, attachment_color) in &values.attachments {
+                match (index, attachment_color) {
+                    (0, ClearColor::Float(c)) => color = Some((c[0], c[1], c[2], c[3])),
+                    _ => panic!("Clearing individual color attachments is not supported by the backend"),
+                }
+            }
+        }
+
         let mut flags = 0;
 
         if let Some(color) = color {
@@ -80,7 +96,7 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
             }
         }
 
-        if let Some(depth) = depth {
+        if let Some(depth) = values.depth {
             let depth = depth as gl::types::GLclampf;
 
             flags |= gl::DEPTH_BUFFER_BIT;
@@ -103,7 +119,7 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
             }
         }
 
-        if let Some(stencil) = stencil {
+        if let Some(stencil) = values.stencil {
             let stencil = stencil as gl::types::GLint;
 
             flags |= gl::STENCIL_BUFFER_BIT;
@@ -114,6 +130,20 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
             }
         }
 
-        ctxt.gl.Clear(flags);
+        if flags != 0 {
+            ctxt.gl.Clear(flags);
+        }
+
+        if supports_clear_buffer {
+            for &(index, attachment_color) in &values.attachments {
+                let index = index as gl::types::GLint;
+
+                match attachment_color {
+                    ClearColor::Float(c) => ctxt.gl.ClearBufferfv(gl::COLOR, index, c.as_ptr()),
+                    ClearColor::Int(c) => ctxt.gl.ClearBufferiv(gl::COLOR, index, c.as_ptr()),
+                    ClearColor::UnsignedInt(c) => ctxt.gl.ClearBufferuiv(gl::COLOR, index, c.as_ptr()),
+                }
+            }
+        }
     }
 }
@@ -1,5 +1,7 @@
 use std::ptr;
 
+use smallvec::SmallVec;
+
 use crate::BufferExt;
 use crate::BufferSliceExt;
 use crate::ProgramExt;
@@ -12,7 +14,7 @@ use crate::TransformFeedbackSessionExt;
 
 use crate::fbo::{self, ValidatedAttachments};
 
-use crate::uniforms::Uniforms;
+use crate::uniforms::{Uniforms, TextureAccesses};
 use crate::{Program, ToGlEnum};
 use crate::index::{self, IndicesSource};
 use crate::vertex::{MultiVerticesSource, VerticesSource, TransformFeedbackSession};
@@ -65,18 +67,37 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         },
     };
 
+    let index_buffer = match indices {
+        IndicesSource::IndexBuffer { buffer, .. } => Some(buffer),
+        IndicesSource::MultidrawArray { .. } => None,
+        IndicesSource::MultidrawElement { indices, .. } => Some(indices),
+        IndicesSource::NoIndices { .. } => None,
+    };
+    let vertex_buffers: SmallVec<[VerticesSource<'a>; 2]> = vertex_buffers.iter().collect();
+
+    // checking that the transform feedback doesn't write the vertex sources, before any state
+    // is changed
+    if let (true, Some(tf)) = (draw_parameters.check_hazards, draw_parameters.transform_feedback) {
+        let vertex_buffers = vertex_buffers.iter().filter_map(|src| match *src {
+            VerticesSource::VertexBuffer(ref buffer, _, _) => Some(buffer),
+            _ => None,
+        });
+
+        if index_buffer.iter().chain(vertex_buffers).any(|buffer| tf.writes_to_buffer(buffer)) {
+            return Err(DrawError::TransformFeedbackBufferAliasing);
+        }
+    }
+
     // starting the state changes
     let mut ctxt = context.make_current();
 
+    // the loss is detected when swapping buffers or by `Context::is_context_lost`
+    if ctxt.state.lost_context {
+        return Err(DrawError::ContextLost);
+    }
+
     // handling vertices source
     let (vertices_count, instances_count, base_vertex) = {
-        let index_buffer = match indices {
-            IndicesSource::IndexBuffer { buffer, .. } => Some(buffer),
-            IndicesSource::MultidrawArray { .. } => None,
-            IndicesSource::MultidrawElement { indices, .. } => Some(indices),
-            IndicesSource::NoIndices { .. } => None,
-        };
-
         // determining whether we can use the `base_vertex` variants for drawing
         let use_base_vertex = match indices {
             IndicesSource::MultidrawArray { .. } => false,
@@ -96,7 +117,7 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         // number of instances to draw
         let mut instances_count: Option<usize> = None;
 
-        for src in vertex_buffers.iter() {
+        for src in vertex_buffers {
             // Allow single match for consistency with the match below.
             // Integrating the two matches wouldn't improve the code either.
             #[allow(clippy::single_match)]
@@ -167,7 +188,40 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
 
     // binding the program and uniforms
     program.use_program(&mut ctxt);
-    uniforms.bind_uniforms(&mut ctxt, program, &mut fences)?;
+    let mut accesses = TextureAccesses::new(draw_parameters.check_hazards ||
+                                            draw_parameters.auto_texture_barrier);
+    uniforms.bind_uniforms(&mut ctxt, program, &mut fences, &mut accesses)?;
+
+    if draw_parameters.check_hazards {
+        accesses.check_images()?;
+    }
+
+    // handling feedback loops, the attachments are only tracked when they are checked
+    let track_feedback_loops = draw_parameters.check_hazards ||
+                               draw_parameters.auto_texture_barrier;
+    if let Some(framebuffer) = framebuffer.filter(|_| track_feedback_loops) {
+        for (texture, name) in accesses.get_sampled() {
+            if !ctxt.state.textures_written_since_barrier.contains(texture) ||
+               !framebuffer.get_texture_ids().any(|t| t == *texture)
+            {
+                continue;
+            }
+
+            if draw_parameters.auto_texture_barrier {
+                super::texture_barrier(&mut ctxt)
+                    .map_err(|_| DrawError::TextureBarrierNotSupported)?;
+                break;
+            }
+
+            return Err(DrawError::TextureFeedbackLoop { sampler: name.clone() });
+        }
+
+        for texture in framebuffer.get_texture_ids() {
+            if !ctxt.state.textures_written_since_barrier.contains(&texture) {
+                ctxt.state.textures_written_since_barrier.push(texture);
+            }
+        }
+    }
 
     // sync-ing draw_parameters
     unsafe {
@@ -178,7 +232,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         // TODO: changing the current transform feedback requires pausing/unbinding before changing the program
         if let Some(ref tf) = draw_parameters.transform_feedback {
             tf.bind(&mut ctxt, indices.get_primitives_type());
-        } else {
+        } else if !ctxt.state.transform_feedback_paused {
+            // a paused session doesn't capture anything and can be resumed later
             TransformFeedbackSession::unbind(&mut ctxt);
         }
     }
@@ -326,6 +381,7 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
     };
 
     ctxt.state.next_draw_call_id += 1;
+    ctxt.state.statistics.draw_calls += 1;
 
     // fulfilling the fences
     for fence in fences.into_iter() {
@@ -0,0 +1,68 @@
+use smallvec::SmallVec;
+
+use crate::fbo::{self, ValidatedAttachments};
+
+use crate::context::Context;
+use crate::ContextExt;
+use crate::Attachment;
+use crate::Rect;
+
+use crate::Api;
+use crate::version::Version;
+use crate::gl;
+
+/// Calls `glInvalidateFramebuffer` or `glInvalidateSubFramebuffer` on some attachments of a
+/// framebuffer, or does nothing if the backend doesn't support it.
+pub fn invalidate(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
+                  dimensions: (u32, u32), rect: &Rect, attachments: &[Attachment])
+{
+    let mut ctxt = context.make_current();
+
+    if !(ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+         ctxt.extensions.gl_arb_invalidate_subdata)
+    {
+        return;
+    }
+
+    // the default framebuffer has its own enums, and only one color buffer
+    let max_color_attachments = if framebuffer.is_some() {
+        ctxt.capabilities.max_color_attachments as u32
+    } else {
+        1
+    };
+
+    let attachments = attachments.iter().filter_map(|attachment| {
+        match (*attachment, framebuffer.is_some()) {
+            (Attachment::Color(index), _) if index >= max_color_attachments => None,
+            (Attachment::Color(index), true) => Some(gl::COLOR_ATTACHMENT0 + index),
+            (Attachment::Color(_), false) => Some(gl::COLOR),
+            (Attachment::Depth, true) => Some(gl::DEPTH_ATTACHMENT),
+            (Attachment::Depth, false) => Some(gl::DEPTH),
+            (Attachment::Stencil, true) => Some(gl::STENCIL_ATTACHMENT),
+            (Attachment::Stencil, false) => Some(gl::STENCIL),
+        }
+    }).collect::<SmallVec<[gl::types::GLenum; 4]>>();
+
+    if attachments.is_empty() || rect.width == 0 || rect.height == 0 {
+        return;
+    }
+
+    unsafe {
+        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, framebuffer);
+        fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false);
+
+        if rect.left == 0 && rect.bottom == 0 && (rect.width, rect.height) == dimensions {
+            ctxt.gl.InvalidateFramebuffer(gl::DRAW_FRAMEBUFFER,
+                                          attachments.len() as gl::types::GLsizei,
+                                          attachments.as_ptr());
+        } else {
+            ctxt.gl.InvalidateSubFramebuffer(gl::DRAW_FRAMEBUFFER,
+                                             attachments.len() as gl::types::GLsizei,
+                                             attachments.as_ptr(),
+                                             rect.left as gl::types::GLint,
+                                             rect.bottom as gl::types::GLint,
+                                             rect.width as gl::types::GLsizei,
+                                             rect.height as gl::types::GLsizei);
+        }
+    }
+}
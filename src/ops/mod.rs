@@ -1,9 +1,13 @@
-pub use self::blit::blit;
+pub use self::barrier::{texture_barrier, is_texture_barrier_supported};
+pub use self::blit::{blit, BlitError};
 pub use self::clear::clear;
 pub use self::draw::draw;
-pub use self::read::{read, ReadError, Source};
+pub use self::invalidate::invalidate;
+pub use self::read::{read, read_surface, ReadError, Source};
 
+mod barrier;
 mod blit;
 mod clear;
 mod draw;
+mod invalidate;
 mod read;
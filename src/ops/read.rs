@@ -5,7 +5,7 @@ use std::error::Error;
 use crate::pixel_buffer::PixelBuffer;
 use crate::texture::ClientFormat;
 use crate::texture::PixelValue;
-use crate::image_format::{TextureFormatRequest, TextureFormat};
+use crate::texture::TextureKind;
 
 use crate::fbo;
 use crate::fbo::FramebuffersContainer;
@@ -13,7 +13,8 @@ use crate::fbo::FramebuffersContainer;
 use crate::buffer::BufferAny;
 use crate::BufferExt;
 use crate::Rect;
-use crate::context::CommandContext;
+use crate::ContextExt;
+use crate::context::{CommandContext, Context};
 use crate::gl;
 
 use crate::version::Version;
@@ -73,6 +74,9 @@ pub enum ReadError {
     /// Clamping the values is not supported by the implementation.
     ClampingNotSupported,
 
+    /// The surface doesn't have a color attachment to read from.
+    NoColorAttachment,
+
     // TODO: context lost
 }
 
@@ -86,6 +90,8 @@ impl fmt::Display for ReadError {
                 "The implementation doesn't support reading a depth, depth-stencil or stencil attachment",
             ClampingNotSupported =>
                 "Clamping the values is not supported by the implementation",
+            NoColorAttachment =>
+                "The surface doesn't have a color attachment to read from",
         };
         fmt.write_str(desc)
     }
@@ -98,27 +104,48 @@ impl Error for ReadError {}
 /// Panics if the destination is not large enough.
 ///
 /// The `(u8, u8, u8, u8)` format is guaranteed to be supported.
-// TODO: differentiate between GL_* and GL_*_INTEGER
 #[inline]
-pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Rect, dest: D,
+pub fn read<'a, S, D, T>(ctxt: &mut CommandContext<'_>, source: S, rect: &Rect, dest: D,
                          clamp: bool) -> Result<(), ReadError>
                          where S: Into<Source<'a>>, D: Into<Destination<'a, T>>,
                                T: PixelValue
+{
+    read_with_format(ctxt, source, rect, dest, <T as PixelValue>::get_format(), clamp)
+}
+
+/// Reads the pixels of `rect` of a surface of dimensions `dimensions` into a pixel buffer,
+/// without clamping them.
+///
+/// Panics if `rect` is outside of the surface.
+pub fn read_surface<'a, P>(context: &Context, source: Source<'a>, dimensions: (u32, u32),
+                           rect: &Rect, dest: &'a PixelBuffer<P>, format: ClientFormat)
+                           -> Result<(), ReadError> where P: PixelValue
+{
+    assert!(matches!(rect.left.checked_add(rect.width), Some(right) if right <= dimensions.0) &&
+            matches!(rect.bottom.checked_add(rect.height), Some(top) if top <= dimensions.1),
+            "The rectangle to read is outside of the surface");
+
+    let mut ctxt = context.make_current();
+    read_with_format(&mut ctxt, source, rect, dest, format, false)
+}
+
+/// Same as `read`, but the pixels are written in `output_pixel_format` instead of the format
+/// of `T`.
+///
+/// Panics if the size of a pixel of `output_pixel_format` is not the size of `T`.
+pub fn read_with_format<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Rect,
+                                     dest: D, output_pixel_format: ClientFormat, clamp: bool)
+                                     -> Result<(), ReadError>
+                                     where S: Into<Source<'a>>, D: Into<Destination<'a, T>>,
+                                           T: PixelValue
 {
     let source = source.into();
     let dest = dest.into();
-    let output_pixel_format = <T as PixelValue>::get_format();
 
-    let pixels_to_read = rect.width * rect.height;
+    assert_eq!(output_pixel_format.get_size(), std::mem::size_of::<T>(),
+               "The size of a pixel of the format doesn't match the size of the pixel type");
 
-    // checking that the output format is supported
-    // OpenGL supported everything, while OpenGL ES only supports U8U8U8U8 plus an additional
-    // implementation-defined format
-    if ctxt.version >= &Version(Api::GlEs, 2, 0) && output_pixel_format != ClientFormat::U8U8U8U8 {
-        // TODO: GLES is guaranteed to support GL_RGBA and an implementation-defined format
-        //       queried with GL_IMPLEMENTATION_COLOR_READ_FORMAT. We only handle GL_RGBA.
-        return Err(ReadError::OutputFormatNotSupported);
-    }
+    let pixels_to_read = rect.width * rect.height;
 
     // handling clamping
     if ctxt.version >= &Version(Api::Gl, 3, 0) {
@@ -152,22 +179,8 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Re
     enum ReadSourceType { Color, Depth, Stencil, DepthStencil }
     let (integer, read_src_type) = match source {
         Source::Attachment(attachment) => {
-            match attachment {
-                fbo::RegularAttachment::Texture(ref tex) => {
-                    let integer = match tex.get_texture().get_requested_format() {
-                        TextureFormatRequest::Specific(TextureFormat::UncompressedIntegral(_)) => true,
-                        TextureFormatRequest::Specific(TextureFormat::UncompressedUnsigned(_)) => true,
-                        TextureFormatRequest::AnyIntegral => true,
-                        TextureFormatRequest::AnyUnsigned => true,
-                        _ => false,
-                    };
-
-                    (integer, ReadSourceType::Color)       // FIXME: wrong
-                },
-                fbo::RegularAttachment::RenderBuffer(ref rb) => {
-                    (false, ReadSourceType::Color)       // FIXME: wrong
-                },
-            }
+            let integer = matches!(attachment.kind(), TextureKind::Integral | TextureKind::Unsigned);
+            (integer, ReadSourceType::Color)       // FIXME: wrong
         },
         Source::DefaultFramebuffer(read_buffer) => {
             (false, ReadSourceType::Color)       // FIXME: wrong
@@ -207,6 +220,35 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Re
         },
     };
 
+    // checking that the output format is supported
+    // OpenGL supports everything, while OpenGL ES only supports RGBA with unsigned bytes for
+    // normalized buffers and with 32 bits integers for integral buffers, plus an additional
+    // implementation-defined format that depends on the read buffer
+    if ctxt.version >= &Version(Api::GlEs, 2, 0) {
+        let guaranteed = if integer {
+            ctxt.version >= &Version(Api::GlEs, 3, 0) && format == gl::RGBA_INTEGER &&
+                (gltype == gl::INT || gltype == gl::UNSIGNED_INT)
+        } else {
+            format == gl::RGBA && gltype == gl::UNSIGNED_BYTE
+        };
+
+        if !guaranteed {
+            let (implementation_format, implementation_type) = unsafe {
+                let mut implementation_format = 0;
+                let mut implementation_type = 0;
+                ctxt.gl.GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_FORMAT,
+                                    &mut implementation_format);
+                ctxt.gl.GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_TYPE, &mut implementation_type);
+                (implementation_format as gl::types::GLenum,
+                 implementation_type as gl::types::GLenum)
+            };
+
+            if format != implementation_format || gltype != implementation_type {
+                return Err(ReadError::OutputFormatNotSupported);
+            }
+        }
+    }
+
     // reading
     unsafe {
         match dest {
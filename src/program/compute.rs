@@ -114,6 +114,14 @@ impl ComputeShader {
         self.raw.get_uniform(name)
     }
 
+    /// Returns the value that a uniform had right after the program was linked.
+    ///
+    /// See `Program::uniform_default`.
+    #[inline]
+    pub fn uniform_default(&self, name: &str) -> Option<RawUniformValue> {
+        self.raw.uniform_default(name)
+    }
+
     /// Returns an iterator to the list of uniforms.
     ///
     /// ## Example
@@ -243,6 +251,11 @@ impl ProgramExt for ComputeShader {
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
     }
+
+    #[inline]
+    fn get_fixed_texture_unit(&self, _: gl::types::GLint) -> Option<u16> {
+        None
+    }
 }
 
 /// Represents a compute shader command waiting to be dispatched.
@@ -0,0 +1,454 @@
+//! Comparison of the outputs of each stage of a program with the inputs of the next stage, from
+//! the GLSL source code.
+
+use crate::program::{ProgramValidationError, ShaderStage};
+
+/// A global `in` or `out` variable of a stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Variable {
+    name: String,
+    /// The type as written in the source code, including the array dimensions.
+    ty: String,
+    location: Option<u32>,
+}
+
+/// Checks that every input of each stage is written by the previous stage with the same type
+/// and location.
+///
+/// Interface blocks and built-in variables aren't compared. A pair of stages is skipped if one
+/// of them uses the preprocessor beyond `#version`, `#extension` and `#pragma`, as its
+/// declarations can then depend on macros.
+pub fn check_interfaces(sources: &[(ShaderStage, String)]) -> Result<(), ProgramValidationError> {
+    const ORDER: [ShaderStage; 5] = [
+        ShaderStage::Vertex,
+        ShaderStage::TessellationControl,
+        ShaderStage::TessellationEvaluation,
+        ShaderStage::Geometry,
+        ShaderStage::Fragment,
+    ];
+
+    let stages: Vec<(ShaderStage, &str)> = ORDER.iter()
+        .filter_map(|&stage| {
+            sources.iter().find(|&&(s, _)| s == stage).map(|(_, source)| (stage, &source[..]))
+        })
+        .collect();
+
+    for pair in stages.windows(2) {
+        let (_, previous) = pair[0];
+        let (stage, source) = pair[1];
+
+        if uses_preprocessor(previous) || uses_preprocessor(source) {
+            continue;
+        }
+
+        let (_, outputs) = parse_interface(pair[0].0, previous);
+        let (inputs, _) = parse_interface(stage, source);
+
+        for input in inputs.iter() {
+            check_input(stage, input, &outputs)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn check_input(stage: ShaderStage, input: &Variable, outputs: &[Variable])
+               -> Result<(), ProgramValidationError>
+{
+    let by_name = outputs.iter().find(|o| o.name == input.name);
+    let by_location = input.location
+        .and_then(|location| outputs.iter().find(|o| o.location == Some(location)));
+
+    let output = match (by_location, by_name) {
+        (Some(output), _) => output,
+        (None, Some(output)) => match (output.location, input.location) {
+            (Some(output_location), Some(input_location)) => {
+                return Err(ProgramValidationError::LocationMismatch {
+                    stage,
+                    name: input.name.clone(),
+                    output_location,
+                    input_location,
+                });
+            },
+            _ => output,
+        },
+        (None, None) => {
+            return Err(ProgramValidationError::MissingInput {
+                stage,
+                name: input.name.clone(),
+            });
+        },
+    };
+
+    if output.ty != input.ty {
+        return Err(ProgramValidationError::TypeMismatch {
+            stage,
+            name: input.name.clone(),
+            output_type: output.ty.clone(),
+            input_type: input.ty.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the inputs and the outputs declared by the source code of a stage.
+///
+/// The inputs of the tessellation and geometry stages, and the outputs of the tessellation
+/// control stage, are arrays with one element per vertex. This dimension is removed from their
+/// type so that they can be compared with the other stages.
+fn parse_interface(stage: ShaderStage, source: &str) -> (Vec<Variable>, Vec<Variable>) {
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+
+    for statement in global_statements(&strip_comments(source, false)) {
+        let tokens = tokenize(&statement);
+        let mut iter = tokens.iter().map(|t| &t[..]).peekable();
+
+        let mut location = None;
+        let mut storage = None;
+        let mut patch = false;
+
+        // qualifiers
+        while let Some(&token) = iter.peek() {
+            match token {
+                "layout" => {
+                    iter.next();
+                    location = parse_layout(&mut iter);
+                },
+                "in" | "out" | "varying" => {
+                    storage = Some(token);
+                    iter.next();
+                },
+                "patch" => {
+                    patch = true;
+                    iter.next();
+                },
+                "flat" | "smooth" | "noperspective" | "centroid" | "sample" | "invariant" |
+                "precise" | "highp" | "mediump" | "lowp" => {
+                    iter.next();
+                },
+                _ => break,
+            }
+        }
+
+        let is_input = match (storage, stage) {
+            (Some("in"), _) => true,
+            (Some("varying"), ShaderStage::Fragment) => true,
+            (Some("out"), _) | (Some("varying"), _) => false,
+            _ => continue,
+        };
+
+        // `layout(triangles) in;` and the likes don't declare variables
+        let base_ty = match iter.next() {
+            Some(ty) if is_identifier(ty) => ty.to_owned(),
+            _ => continue,
+        };
+        let ty_arrays = parse_arrays(&mut iter);
+
+        let per_vertex = !patch && match stage {
+            ShaderStage::TessellationControl => true,
+            ShaderStage::TessellationEvaluation | ShaderStage::Geometry => is_input,
+            _ => false,
+        };
+
+        let mut first = true;
+        while let Some(name) = iter.next() {
+            if !is_identifier(name) {
+                break;
+            }
+
+            let mut arrays = ty_arrays.clone();
+            arrays.extend(parse_arrays(&mut iter));
+            if per_vertex && !arrays.is_empty() {
+                arrays.remove(0);
+            }
+
+            if !name.starts_with("gl_") {
+                let variable = Variable {
+                    name: name.to_owned(),
+                    ty: arrays.iter().fold(base_ty.clone(), |ty, dim| format!("{}[{}]", ty, dim)),
+                    // the next declarators use the following locations, which depend on the
+                    // size of the type, and are matched by name instead
+                    location: if first { location } else { None },
+                };
+
+                if is_input { inputs.push(variable) } else { outputs.push(variable) }
+            }
+
+            first = false;
+            if iter.next() != Some(",") {
+                break;
+            }
+        }
+    }
+
+    (inputs, outputs)
+}
+
+/// Parses `(location = N, ...)` after the `layout` keyword, and returns the location if any.
+fn parse_layout<'a, I>(iter: &mut std::iter::Peekable<I>) -> Option<u32>
+    where I: Iterator<Item = &'a str>
+{
+    let mut location = None;
+    if iter.next() != Some("(") {
+        return None;
+    }
+
+    while let Some(token) = iter.next() {
+        match token {
+            ")" => break,
+            "location" if iter.peek() == Some(&"=") => {
+                iter.next();
+                location = iter.next().and_then(parse_integer);
+            },
+            _ => (),
+        }
+    }
+
+    location
+}
+
+/// Parses the `[N]` that follow a type or a name, and returns the content of each pair of
+/// brackets.
+fn parse_arrays<'a, I>(iter: &mut std::iter::Peekable<I>) -> Vec<String>
+    where I: Iterator<Item = &'a str>
+{
+    let mut arrays = Vec::new();
+    while iter.peek() == Some(&"[") {
+        iter.next();
+        let mut dim = String::new();
+        for token in iter.by_ref() {
+            if token == "]" {
+                break;
+            }
+            dim.push_str(token);
+        }
+        arrays.push(dim);
+    }
+    arrays
+}
+
+fn parse_integer(token: &str) -> Option<u32> {
+    let token = token.trim_end_matches(['u', 'U']);
+    if let Some(hex) = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16).ok()
+    } else {
+        token.parse().ok()
+    }
+}
+
+fn is_identifier(token: &str) -> bool {
+    matches!(token.chars().next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+}
+
+/// Returns true if the source code contains a preprocessor directive that can change the
+/// declarations, like `#define` or `#ifdef`.
+fn uses_preprocessor(source: &str) -> bool {
+    strip_comments(source, true).lines().any(|line| {
+        match line.trim_start().strip_prefix('#') {
+            Some(directive) => {
+                let name = directive.trim_start().split(|c: char| !c.is_ascii_alphanumeric())
+                                    .next().unwrap_or("");
+                !matches!(name, "" | "version" | "extension" | "pragma")
+            },
+            None => false,
+        }
+    })
+}
+
+/// Removes the comments and, unless `keep_directives` is true, the preprocessor directives.
+fn strip_comments(source: &str, keep_directives: bool) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                while matches!(chars.peek(), Some(&c) if c != '\n') {
+                    chars.next();
+                }
+            },
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                output.push(' ');
+            },
+            '#' if line_start && !keep_directives => {
+                while matches!(chars.peek(), Some(&c) if c != '\n') {
+                    chars.next();
+                }
+            },
+            _ => {
+                if c == '\n' {
+                    line_start = true;
+                } else if !c.is_whitespace() {
+                    line_start = false;
+                }
+                output.push(c);
+            },
+        }
+    }
+
+    output
+}
+
+/// Returns the declarations of the global scope, without function definitions, structs and
+/// interface blocks.
+fn global_statements(source: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0u32;
+    let mut has_block = false;
+
+    for c in source.chars() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    if current.trim_end().ends_with(')') {
+                        // function definition, which isn't followed by a semicolon
+                        current.clear();
+                    } else {
+                        has_block = true;
+                    }
+                }
+                depth += 1;
+            },
+            '}' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                if !has_block {
+                    statements.push(current.clone());
+                }
+                current.clear();
+                has_block = false;
+            },
+            _ if depth == 0 => current.push(c),
+            _ => (),
+        }
+    }
+
+    statements
+}
+
+fn tokenize(statement: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for c in statement.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            current.push(c);
+        } else {
+            if !current.is_empty() {
+                tokens.push(current.clone());
+                current.clear();
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_interfaces;
+    use crate::program::{ProgramValidationError, ShaderStage};
+
+    fn check(vertex: &str, fragment: &str) -> Result<(), ProgramValidationError> {
+        check_interfaces(&[
+            (ShaderStage::Vertex, vertex.to_owned()),
+            (ShaderStage::Fragment, fragment.to_owned()),
+        ])
+    }
+
+    #[test]
+    fn matching() {
+        let vertex = "
+            #version 330
+            layout(location = 0) in vec2 position;
+            out vec3 v_color;   // comment
+            /* out vec4 commented; */
+            layout(location = 2) flat out ivec2 v_ids[2], v_other;
+            void main() { v_color = vec3(1.0); }
+        ";
+        let fragment = "
+            #version 330
+            in vec3 v_color;
+            layout(location = 2) flat in ivec2 v_ids[2];
+            out vec4 color;
+            void main() { color = vec4(v_color, 1.0); }
+        ";
+        assert_eq!(check(vertex, fragment), Ok(()));
+    }
+
+    #[test]
+    fn missing_input() {
+        assert_eq!(check("out vec3 a;", "in vec3 b;"), Err(ProgramValidationError::MissingInput {
+            stage: ShaderStage::Fragment,
+            name: "b".to_owned(),
+        }));
+    }
+
+    #[test]
+    fn type_mismatch() {
+        assert_eq!(check("out vec3 a;", "in vec4 a;"), Err(ProgramValidationError::TypeMismatch {
+            stage: ShaderStage::Fragment,
+            name: "a".to_owned(),
+            output_type: "vec3".to_owned(),
+            input_type: "vec4".to_owned(),
+        }));
+    }
+
+    #[test]
+    fn location_mismatch() {
+        let result = check("layout(location = 1) out vec3 a;", "layout(location = 0) in vec3 a;");
+        assert_eq!(result, Err(ProgramValidationError::LocationMismatch {
+            stage: ShaderStage::Fragment,
+            name: "a".to_owned(),
+            output_location: 1,
+            input_location: 0,
+        }));
+    }
+
+    #[test]
+    fn preprocessor_skipped() {
+        let vertex = "
+            #version 330
+            #define COLOR_TYPE vec3
+            out COLOR_TYPE v_color;
+        ";
+        let fragment = "
+            #version 330
+            #ifdef USE_COLOR
+            in vec4 v_color;
+            #endif
+            in vec2 v_other; // #if 0
+        ";
+        assert_eq!(check(vertex, fragment), Ok(()));
+        assert!(check("#version 330\nout vec3 a;", "#version 330\n#extension GL_foo : enable\n\
+                       in vec3 b;").is_err());
+    }
+
+    #[test]
+    fn per_vertex_arrays() {
+        let result = check_interfaces(&[
+            (ShaderStage::Vertex, "out vec3 a;".to_owned()),
+            (ShaderStage::Geometry, "in vec3 a[]; out vec3 b;".to_owned()),
+            (ShaderStage::Fragment, "varying vec3 b;".to_owned()),
+        ]);
+        assert_eq!(result, Ok(()));
+    }
+}
@@ -15,6 +15,9 @@ pub use self::reflection::{Uniform, UniformBlock, BlockLayout, OutputPrimitives}
 pub use self::reflection::{Attribute, TransformFeedbackVarying, TransformFeedbackBuffer, TransformFeedbackMode};
 pub use self::reflection::{ShaderStage, SubroutineData, SubroutineUniform};
 
+#[cfg(feature = "glsl")]
+pub use glium_derive::glsl;
+
 mod compute;
 mod program;
 mod raw;
@@ -22,6 +25,7 @@ mod reflection;
 mod shader;
 mod uniforms_storage;
 mod binary_header;
+mod interface;
 
 /// Returns true if the backend supports geometry shaders.
 #[inline]
@@ -132,6 +136,15 @@ pub enum ProgramCreationError {
 
     /// The glium-specific binary header was not found or is corrupt.
     BinaryHeaderError,
+
+    /// The program has more sampler uniforms than the number of texture units of the backend,
+    /// and its samplers were requested to be bound to fixed texture units.
+    TooManySamplers {
+        /// Number of sampler uniforms in the program.
+        samplers: usize,
+        /// Number of texture units of the backend.
+        texture_units: usize,
+    },
 }
 
 impl fmt::Display for ProgramCreationError {
@@ -160,12 +173,16 @@ impl fmt::Display for ProgramCreationError {
                 "Point size is not supported by the backend.",
             BinaryHeaderError =>
                 "The glium-specific binary header was not found or is corrupt.",
+            TooManySamplers { .. } =>
+                "The program has more samplers than the texture units of the backend",
         };
         match *self {
             CompilationError(ref s, _) =>
                 write!(fmt, "{}: {}", desc, s),
             LinkingError(ref s) =>
                 write!(fmt, "{}: {}", desc, s),
+            TooManySamplers { samplers, texture_units } =>
+                write!(fmt, "{} ({} samplers, {} texture units)", desc, samplers, texture_units),
             _ =>
                 write!(fmt, "{}", desc),
         }
@@ -212,6 +229,70 @@ impl From<ProgramCreationError> for ProgramChooserCreationError {
     }
 }
 
+/// Error returned by `Program::validate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProgramValidationError {
+    /// An input of a stage isn't written by the previous stage.
+    MissingInput {
+        /// The stage that reads the input.
+        stage: ShaderStage,
+        /// The name of the input.
+        name: String,
+    },
+
+    /// An input of a stage doesn't have the same type as the output of the previous stage.
+    TypeMismatch {
+        /// The stage that reads the input.
+        stage: ShaderStage,
+        /// The name of the input.
+        name: String,
+        /// The type of the output of the previous stage, as written in the source code.
+        output_type: String,
+        /// The type of the input, as written in the source code.
+        input_type: String,
+    },
+
+    /// An input of a stage has the same name as an output of the previous stage, but is
+    /// declared with a different location.
+    LocationMismatch {
+        /// The stage that reads the input.
+        stage: ShaderStage,
+        /// The name of the input.
+        name: String,
+        /// The location of the output of the previous stage.
+        output_location: u32,
+        /// The location of the input.
+        input_location: u32,
+    },
+
+    /// `glValidateProgram` reported that the program can't be executed in the current state.
+    ///
+    /// Contains the log of the implementation, which describes why.
+    InvalidState(String),
+}
+
+impl fmt::Display for ProgramValidationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::ProgramValidationError::*;
+        match *self {
+            MissingInput { stage, ref name } =>
+                write!(fmt, "The input `{}` of the {:?} stage isn't written by the previous stage",
+                       name, stage),
+            TypeMismatch { stage, ref name, ref output_type, ref input_type } =>
+                write!(fmt, "The input `{}` of the {:?} stage has the type `{}`, but the previous \
+                             stage writes a `{}`", name, stage, input_type, output_type),
+            LocationMismatch { stage, ref name, output_location, input_location } =>
+                write!(fmt, "The input `{}` of the {:?} stage has the location {}, but the \
+                             previous stage writes it at location {}", name, stage,
+                       input_location, output_location),
+            InvalidState(ref log) =>
+                write!(fmt, "The program can't be executed in the current state: {}", log),
+        }
+    }
+}
+
+impl Error for ProgramValidationError {}
+
 /// Error while retrieving the binary representation of a program.
 #[derive(Copy, Clone, Debug)]
 pub enum GetBinaryError {
@@ -263,7 +344,8 @@ pub enum ProgramCreationInput<'a> {
         /// meaning that the program is responsible for outputting correct `sRGB` values.
         ///
         /// If this is false, then `GL_FRAMEBUFFER_SRGB` will be enabled when this program is used
-        /// (if it is supported).
+        /// (if it is supported). `DrawParameters::framebuffer_srgb` overrides this for a single
+        /// draw command.
         outputs_srgb: bool,
 
         /// Whether the shader uses point size.
@@ -8,18 +8,24 @@ use crate::backend::Facade;
 use crate::CapabilitiesSource;
 
 use std::fmt;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::collections::hash_map::{self, HashMap};
-use std::hash::BuildHasherDefault;
+use std::hash::{BuildHasherDefault, Hasher};
 
 use fnv::FnvHasher;
 
+use crate::ContextExt;
 use crate::GlObject;
 use crate::ProgramExt;
 use crate::Handle;
 use crate::RawUniformValue;
 
-use crate::program::{COMPILER_GLOBAL_LOCK, ProgramCreationInput, ProgramCreationError, ShaderType, Binary, SpirvProgram};
-use crate::program::GetBinaryError;
+use crate::program::{COMPILER_GLOBAL_LOCK, ProgramCreationInput, ProgramCreationError, ShaderType, Binary, SpirvProgram, SpirvEntryPoint, TransformFeedbackMode};
+use crate::program::{GetBinaryError, ProgramValidationError};
+use crate::program::interface;
 
 use crate::program::reflection::{Uniform, UniformBlock, OutputPrimitives};
 use crate::program::reflection::{Attribute, TransformFeedbackBuffer};
@@ -35,6 +41,9 @@ pub struct Program {
     raw: RawProgram,
     outputs_srgb: bool,
     uses_point_size: bool,
+    sources: Vec<(ShaderStage, String)>,
+    content_hash: u64,
+    texture_units: Vec<(String, gl::types::GLint, u16)>,
 }
 
 impl Program {
@@ -44,7 +53,9 @@ impl Program {
     {
         let input = input.into();
 
-        let (raw, outputs_srgb, uses_point_size) = match input {
+        let content_hash = hash_input(&input);
+
+        let (raw, outputs_srgb, uses_point_size, sources) = match input {
             ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
                                                tessellation_evaluation_shader, geometry_shader,
                                                fragment_shader, transform_feedback_varyings,
@@ -59,18 +70,26 @@ impl Program {
                     (fragment_shader, ShaderType::Fragment)
                 ];
 
+                let mut sources = vec![
+                    (ShaderStage::Vertex, vertex_shader.to_owned()),
+                    (ShaderStage::Fragment, fragment_shader.to_owned()),
+                ];
+
                 if let Some(gs) = geometry_shader {
                     shaders.push((gs, ShaderType::Geometry));
+                    sources.push((ShaderStage::Geometry, gs.to_owned()));
                     has_geometry_shader = true;
                 }
 
                 if let Some(ts) = tessellation_control_shader {
                     shaders.push((ts, ShaderType::TesselationControl));
+                    sources.push((ShaderStage::TessellationControl, ts.to_owned()));
                     has_tessellation_control_shader = true;
                 }
 
                 if let Some(ts) = tessellation_evaluation_shader {
                     shaders.push((ts, ShaderType::TesselationEvaluation));
+                    sources.push((ShaderStage::TessellationEvaluation, ts.to_owned()));
                     has_tessellation_evaluation_shader = true;
                 }
 
@@ -99,7 +118,7 @@ impl Program {
                 (RawProgram::from_shaders(facade, &shaders_store, has_geometry_shader,
                                                has_tessellation_control_shader, has_tessellation_evaluation_shader,
                                                transform_feedback_varyings)?,
-                 outputs_srgb, uses_point_size)
+                 outputs_srgb, uses_point_size, sources)
             },
 
             ProgramCreationInput::Binary { data, outputs_srgb, uses_point_size } => {
@@ -107,7 +126,7 @@ impl Program {
                     return Err(ProgramCreationError::PointSizeNotSupported);
                 }
 
-                (RawProgram::from_binary(facade, data)?, outputs_srgb, uses_point_size)
+                (RawProgram::from_binary(facade, data)?, outputs_srgb, uses_point_size, Vec::new())
             },
 
             ProgramCreationInput::SpirV(SpirvProgram { vertex_shader, tessellation_control_shader,
@@ -164,13 +183,16 @@ impl Program {
                 (RawProgram::from_shaders(facade, &shaders_store, has_geometry_shader,
                                                has_tessellation_control_shader, has_tessellation_evaluation_shader,
                                                transform_feedback_varyings)?,
-                 outputs_srgb, uses_point_size)
+                 outputs_srgb, uses_point_size, Vec::new())
             }
         };
         Ok(Program {
             raw,
             outputs_srgb,
             uses_point_size,
+            sources,
+            content_hash,
+            texture_units: Vec::new(),
         })
     }
 
@@ -212,6 +234,71 @@ impl Program {
         })
     }
 
+    /// Binds each sampler uniform of the program to its own texture unit, once and for all.
+    ///
+    /// By default, the texture units are picked during each draw command, depending on the
+    /// textures that are already bound. After calling this function, the samplers are assigned
+    /// the units `0`, `1`, `2`, etc. in the order of their locations, which usually is the order
+    /// in which they are declared. The textures are then bound to these units without searching
+    /// for a free unit and without updating the samplers.
+    ///
+    /// Returns an error if the program has more samplers than the texture units of the backend,
+    /// instead of panicking during a draw command.
+    ///
+    /// ```no_run
+    /// # fn example<F: glium::backend::Facade>(display: &F) {
+    /// # let vertex_source = ""; let fragment_source = "";
+    /// let program = glium::Program::from_source(display, vertex_source, fragment_source, None)
+    ///     .and_then(|program| program.with_fixed_texture_units())
+    ///     .unwrap();
+    ///
+    /// for (name, unit) in program.texture_units() {
+    ///     println!("{} is bound to the texture unit {}", name, unit);
+    /// }
+    /// # }
+    /// ```
+    pub fn with_fixed_texture_units(mut self) -> Result<Program, ProgramCreationError> {
+        let mut samplers = self.raw.uniforms()
+                               .filter(|&(_, uniform)| uniform.ty.is_sampler())
+                               .map(|(name, uniform)| (name.clone(), uniform.location))
+                               .collect::<Vec<_>>();
+        samplers.sort_by_key(|&(_, location)| location);
+
+        let context = self.raw.get_context().clone();
+        let mut ctxt = context.make_current();
+
+        let max = ctxt.capabilities.max_combined_texture_image_units as usize;
+        if samplers.len() > max {
+            return Err(ProgramCreationError::TooManySamplers {
+                samplers: samplers.len(),
+                texture_units: max,
+            });
+        }
+
+        self.raw.use_program(&mut ctxt);
+        self.texture_units = samplers.into_iter().enumerate().map(|(unit, (name, location))| {
+            self.raw.set_uniform(&mut ctxt, location,
+                                 &RawUniformValue::SignedInt(unit as gl::types::GLint));
+            (name, location, unit as u16)
+        }).collect();
+
+        Ok(self)
+    }
+
+    /// Returns the texture unit of a sampler uniform, if the program was built with
+    /// `with_fixed_texture_units`.
+    #[inline]
+    pub fn get_texture_unit(&self, name: &str) -> Option<u32> {
+        self.texture_units.iter().find(|&(n, _, _)| n == name).map(|&(_, _, unit)| unit as u32)
+    }
+
+    /// Returns the sampler uniforms and their texture units, ordered by texture unit. The list
+    /// is empty unless the program was built with `with_fixed_texture_units`.
+    #[inline]
+    pub fn texture_units(&self) -> impl Iterator<Item = (&str, u32)> + '_ {
+        self.texture_units.iter().map(|&(ref name, _, unit)| (&name[..], unit as u32))
+    }
+
     /// Returns the program's compiled binary.
     ///
     /// You can store the result in a file, then reload it later. This avoids having to compile
@@ -221,6 +308,118 @@ impl Program {
         self.raw.get_binary()
     }
 
+    /// Returns a hash of the input that was used to build the program.
+    ///
+    /// The hash covers the source code or binary of each stage, the transform feedback varyings
+    /// and the other creation parameters. It doesn't depend on the OpenGL implementation, the
+    /// platform or the version of Rust, and is the same from one execution to another, so it
+    /// can be used as the key of a cache of pipelines stored on disk. Two programs built from the same input have the same hash, even if they
+    /// belong to different contexts.
+    #[inline]
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash
+    }
+
+    /// Returns the GLSL source code of a stage of the program.
+    ///
+    /// Returns `None` if the program doesn't have this stage, or if it wasn't built from source
+    /// code, for example if it was loaded from a binary or from SPIR-V.
+    #[inline]
+    pub fn stage_source(&self, stage: ShaderStage) -> Option<&str> {
+        self.sources.iter().find(|&&(s, _)| s == stage).map(|(_, source)| &source[..])
+    }
+
+    /// Writes the source code of each stage, the reflection data and the binary of the program
+    /// in the directory `path`, which is created if necessary.
+    ///
+    /// This is a debugging helper whose output can be attached to bug reports or analyzed with
+    /// offline tools. The binary is only written if the backend can retrieve it, in a file whose
+    /// name contains the implementation-defined format.
+    ///
+    /// ```no_run
+    /// # fn example(program: glium::Program) {
+    /// program.dump_to_dir("target/shader-dump").unwrap();
+    /// # }
+    /// ```
+    pub fn dump_to_dir<P>(&self, path: P) -> io::Result<()> where P: AsRef<Path> {
+        let path = path.as_ref();
+        fs::create_dir_all(path)?;
+
+        for (stage, source) in self.sources.iter() {
+            let file_name = match *stage {
+                ShaderStage::Vertex => "vertex.glsl",
+                ShaderStage::TessellationControl => "tessellation_control.glsl",
+                ShaderStage::TessellationEvaluation => "tessellation_evaluation.glsl",
+                ShaderStage::Geometry => "geometry.glsl",
+                ShaderStage::Fragment => "fragment.glsl",
+            };
+
+            fs::write(path.join(file_name), source)?;
+        }
+
+        fs::write(path.join("reflection.txt"), self.reflection_dump())?;
+
+        if let Ok(binary) = self.get_binary() {
+            fs::write(path.join(format!("program-{:#x}.bin", binary.format)), &binary.content)?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds the content of the `reflection.txt` file written by `dump_to_dir`.
+    fn reflection_dump(&self) -> String {
+        // the entries are sorted so that the dumps of two programs can be compared
+        fn sorted<'a, K, V, I>(entries: I) -> Vec<(&'a K, &'a V)>
+                               where K: fmt::Debug + 'a, V: 'a, I: Iterator<Item = (&'a K, &'a V)>
+        {
+            let mut entries: Vec<_> = entries.collect();
+            entries.sort_by_cached_key(|&(key, _)| format!("{:?}", key));
+            entries
+        }
+
+        // writing to a `String` can't fail
+        let mut output = String::new();
+
+        writeln!(output, "uniforms:").unwrap();
+        for (name, uniform) in sorted(self.uniforms()) {
+            writeln!(output, "    {:?}: {:?}, default: {:?}", name, uniform,
+                     self.uniform_default(name)).unwrap();
+        }
+
+        writeln!(output, "uniform blocks:").unwrap();
+        for (name, block) in sorted(self.get_uniform_blocks().iter()) {
+            writeln!(output, "    {:?}: {:?}", name, block).unwrap();
+        }
+
+        writeln!(output, "shader storage blocks:").unwrap();
+        for (name, block) in sorted(self.get_shader_storage_blocks().iter()) {
+            writeln!(output, "    {:?}: {:?}", name, block).unwrap();
+        }
+
+        writeln!(output, "atomic counters:").unwrap();
+        for (name, counter) in sorted(self.get_atomic_counters().iter()) {
+            writeln!(output, "    {:?}: {:?}", name, counter).unwrap();
+        }
+
+        writeln!(output, "subroutine uniforms:").unwrap();
+        for (name, uniform) in sorted(self.get_subroutine_uniforms().iter()) {
+            writeln!(output, "    {:?}: {:?}", name, uniform).unwrap();
+        }
+
+        writeln!(output, "attributes:").unwrap();
+        for (name, attribute) in sorted(self.attributes()) {
+            writeln!(output, "    {:?}: {:?}", name, attribute).unwrap();
+        }
+
+        writeln!(output, "transform feedback buffers: {:?}",
+                 self.get_transform_feedback_buffers()).unwrap();
+        writeln!(output, "output primitives: {:?}", self.get_output_primitives()).unwrap();
+        writeln!(output, "srgb output: {}", self.outputs_srgb).unwrap();
+        writeln!(output, "uses point size: {}", self.uses_point_size).unwrap();
+
+        output
+    }
+
     /// Returns the *location* of an output fragment, if it exists.
     ///
     /// The *location* is low-level information that is used internally by glium.
@@ -237,12 +436,62 @@ impl Program {
         self.raw.get_frag_data_location(name)
     }
 
+    /// Checks that the stages of the program fit together, then whether the program can be
+    /// executed given the current OpenGL state.
+    ///
+    /// The outputs of each stage are compared with the inputs of the next one, from the source
+    /// code: every input must be written by the previous stage, with the same type and the same
+    /// location. Drivers don't always report these mismatches when linking, and the input then
+    /// silently reads garbage. Interface blocks, programs that were not built from GLSL source
+    /// code and stages that use the preprocessor beyond `#version`, `#extension` and `#pragma`
+    /// aren't checked.
+    ///
+    /// Then `glValidateProgram` is called, and its log is returned if it fails. Its result
+    /// depends on the state left by the latest draw command, for example which kind of textures
+    /// are bound to the texture units used by the samplers of the program. It is therefore most
+    /// useful right after a draw command that produced unexpected results.
+    ///
+    /// This function is slow and is meant to be used for debugging purposes.
+    #[inline]
+    pub fn validate(&self) -> Result<(), ProgramValidationError> {
+        interface::check_interfaces(&self.sources)?;
+        self.raw.validate()
+    }
+
+    /// Attaches a name to this program, so that tools like RenderDoc or apitrace show it
+    /// instead of a bare id.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`.
+    #[inline]
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        self.raw.set_label(label)
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.raw.get_uniform(name)
     }
 
+    /// Returns the value that a uniform had right after the program was linked.
+    ///
+    /// This is the value of the initializer of the uniform in the GLSL source code, or zero. It
+    /// doesn't change when the uniform is set during a draw command, so it can be used to reset
+    /// a uniform to its original value.
+    ///
+    /// ```no_run
+    /// # fn example(program: glium::Program) {
+    /// // `uniform float exposure = 1.5;`
+    /// if let Some(glium::RawUniformValue::Float(exposure)) = program.uniform_default("exposure") {
+    ///     println!("Default exposure: {}", exposure);
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn uniform_default(&self, name: &str) -> Option<RawUniformValue> {
+        self.raw.uniform_default(name)
+    }
+
     /// Returns an iterator to the list of uniforms.
     ///
     /// ## Example
@@ -291,6 +540,18 @@ impl Program {
         self.raw.transform_feedback_matches(format, stride)
     }
 
+    /// True if the transform feedback buffer at the given index matches the specified
+    /// `VertexFormat` and `stride`.
+    ///
+    /// This is useful for programs that use `TransformFeedbackMode::Separate`, where each
+    /// varying is written to its own buffer.
+    #[inline]
+    pub fn transform_feedback_buffer_matches(&self, index: usize, format: &VertexFormat,
+                                             stride: usize) -> bool
+    {
+        self.raw.transform_feedback_buffer_matches(index, format, stride)
+    }
+
     /// Returns the type of geometry that transform feedback would generate, or `None` if it
     /// depends on the vertex/index data passed when drawing.
     ///
@@ -513,4 +774,114 @@ impl ProgramExt for Program {
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
     }
+
+    #[inline]
+    fn get_fixed_texture_unit(&self, location: gl::types::GLint) -> Option<u16> {
+        self.texture_units.iter().find(|&&(_, l, _)| l == location).map(|&(_, _, unit)| unit)
+    }
+}
+
+/// Computes the FNV-1a hash of the input of a program.
+///
+/// Every value is written explicitly with a fixed width and in little endian, as the output of
+/// `Hash` implementations isn't guaranteed to be stable between Rust releases and platforms.
+fn hash_input(input: &ProgramCreationInput<'_>) -> u64 {
+    fn write_bytes(hasher: &mut FnvHasher, bytes: &[u8]) {
+        hasher.write(&(bytes.len() as u64).to_le_bytes());
+        hasher.write(bytes);
+    }
+
+    fn write_optional(hasher: &mut FnvHasher, bytes: Option<&[u8]>) {
+        match bytes {
+            Some(bytes) => {
+                hasher.write(&[1]);
+                write_bytes(hasher, bytes);
+            },
+            None => hasher.write(&[0]),
+        }
+    }
+
+    fn write_varyings(hasher: &mut FnvHasher,
+                      varyings: &Option<(Vec<String>, TransformFeedbackMode)>)
+    {
+        match *varyings {
+            Some((ref names, mode)) => {
+                hasher.write(&[1, match mode {
+                    TransformFeedbackMode::Interleaved => 0,
+                    TransformFeedbackMode::Separate => 1,
+                }]);
+                hasher.write(&(names.len() as u64).to_le_bytes());
+                for name in names {
+                    write_bytes(hasher, name.as_bytes());
+                }
+            },
+            None => hasher.write(&[0]),
+        }
+    }
+
+    fn write_entry_point(hasher: &mut FnvHasher, entry_point: Option<&SpirvEntryPoint<'_>>) {
+        write_optional(hasher, entry_point.map(|e| e.binary));
+        write_optional(hasher, entry_point.map(|e| e.entry_point.as_bytes()));
+    }
+
+    let mut hasher = FnvHasher::default();
+
+    match *input {
+        ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
+                                           tessellation_evaluation_shader, geometry_shader,
+                                           fragment_shader, ref transform_feedback_varyings,
+                                           outputs_srgb, uses_point_size } =>
+        {
+            hasher.write(&[0]);
+            write_bytes(&mut hasher, vertex_shader.as_bytes());
+            write_optional(&mut hasher, tessellation_control_shader.map(str::as_bytes));
+            write_optional(&mut hasher, tessellation_evaluation_shader.map(str::as_bytes));
+            write_optional(&mut hasher, geometry_shader.map(str::as_bytes));
+            write_bytes(&mut hasher, fragment_shader.as_bytes());
+            write_varyings(&mut hasher, transform_feedback_varyings);
+            hasher.write(&[outputs_srgb as u8, uses_point_size as u8]);
+        },
+
+        ProgramCreationInput::Binary { ref data, outputs_srgb, uses_point_size } => {
+            hasher.write(&[1]);
+            hasher.write(&data.format.to_le_bytes());
+            write_bytes(&mut hasher, &data.content);
+            hasher.write(&[outputs_srgb as u8, uses_point_size as u8]);
+        },
+
+        ProgramCreationInput::SpirV(ref program) => {
+            hasher.write(&[2]);
+            write_entry_point(&mut hasher, Some(&program.vertex_shader));
+            write_entry_point(&mut hasher, program.tessellation_control_shader.as_ref());
+            write_entry_point(&mut hasher, program.tessellation_evaluation_shader.as_ref());
+            write_entry_point(&mut hasher, program.geometry_shader.as_ref());
+            write_entry_point(&mut hasher, Some(&program.fragment_shader));
+            write_varyings(&mut hasher, &program.transform_feedback_varyings);
+            hasher.write(&[program.outputs_srgb as u8, program.uses_point_size as u8]);
+        },
+    }
+
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hash_input;
+    use crate::program::ProgramCreationInput;
+
+    #[test]
+    fn content_hash_is_fixed() {
+        let input = ProgramCreationInput::SourceCode {
+            vertex_shader: "void main() {}",
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: None,
+            geometry_shader: None,
+            fragment_shader: "void main() {}",
+            transform_feedback_varyings: None,
+            outputs_srgb: false,
+            uses_point_size: false,
+        };
+
+        assert_eq!(hash_input(&input), 0x2603_f85c_75c3_60bf);
+    }
 }
@@ -6,6 +6,7 @@ use crate::version::Api;
 
 use crate::backend::Facade;
 use crate::context::Context;
+use crate::context::SharedObject;
 use crate::ContextExt;
 use crate::UniformsExt;
 
@@ -31,7 +32,7 @@ use crate::buffer::BufferSlice;
 use crate::BufferExt;
 use crate::BufferSliceExt;
 
-use crate::program::{ProgramCreationError, Binary, GetBinaryError};
+use crate::program::{ProgramCreationError, ProgramValidationError, Binary, GetBinaryError};
 use crate::program::uniforms_storage::UniformsStorage;
 
 use crate::program::compute::ComputeCommand;
@@ -39,13 +40,14 @@ use crate::program::reflection::{Uniform, UniformBlock, OutputPrimitives};
 use crate::program::reflection::{Attribute, TransformFeedbackMode, TransformFeedbackBuffer};
 use crate::program::reflection::{SubroutineData, ShaderStage};
 use crate::program::reflection::{reflect_uniforms, reflect_attributes, reflect_uniform_blocks};
+use crate::program::reflection::reflect_uniform_defaults;
 use crate::program::reflection::{reflect_transform_feedback, reflect_geometry_output_type};
 use crate::program::reflection::{reflect_tess_eval_output_type, reflect_shader_storage_blocks};
 use crate::program::reflection::{reflect_subroutine_data};
 use crate::program::shader::Shader;
 use crate::program::binary_header::{attach_glium_header, process_glium_header};
 
-use crate::uniforms::Uniforms;
+use crate::uniforms::{Uniforms, TextureAccesses};
 
 use crate::vertex::VertexFormat;
 use crate::vertex_array_object::VertexAttributesSystem;
@@ -56,6 +58,7 @@ pub struct RawProgram {
     id: Handle,
     uniform_values: UniformsStorage,
     uniforms: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
+    uniform_defaults: HashMap<String, RawUniformValue, BuildHasherDefault<FnvHasher>>,
     uniform_blocks: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     subroutine_data: SubroutineData,
     attributes: HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>,
@@ -165,6 +168,7 @@ impl RawProgram {
         };
 
         let (uniforms, atomic_counters) = unsafe { reflect_uniforms(&mut ctxt, id) };
+        let uniform_defaults = unsafe { reflect_uniform_defaults(&mut ctxt, id, &uniforms) };
         let attributes = unsafe { reflect_attributes(&mut ctxt, id) };
         let blocks = unsafe { reflect_uniform_blocks(&mut ctxt, id) };
         let tf_buffers = unsafe { reflect_transform_feedback(&mut ctxt, id) };
@@ -187,6 +191,7 @@ impl RawProgram {
             context: facade.get_context().clone(),
             id,
             uniforms,
+            uniform_defaults,
             uniform_values: UniformsStorage::new(),
             uniform_blocks: blocks,
             subroutine_data,
@@ -246,6 +251,7 @@ impl RawProgram {
                                         has_tessellation_evaluation_shader),
             )
         };
+        let uniform_defaults = unsafe { reflect_uniform_defaults(&mut ctxt, id, &uniforms) };
 
         let output_primitives = if has_geometry_shader {
             Some(unsafe { reflect_geometry_output_type(&mut ctxt, id) })
@@ -259,6 +265,7 @@ impl RawProgram {
             context: facade.get_context().clone(),
             id,
             uniforms,
+            uniform_defaults,
             uniform_values: UniformsStorage::new(),
             uniform_blocks: blocks,
             subroutine_data,
@@ -361,12 +368,70 @@ impl RawProgram {
         location
     }
 
+    /// Checks whether the program can be executed given the current OpenGL state.
+    pub fn validate(&self) -> Result<(), ProgramValidationError> {
+        let mut ctxt = self.context.make_current();
+        let mut status: gl::types::GLint = 0;
+
+        unsafe {
+            match self.id {
+                Handle::Id(id) => {
+                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                            ctxt.version >= &Version(Api::GlEs, 2, 0));
+                    ctxt.gl.ValidateProgram(id);
+                    ctxt.gl.GetProgramiv(id, gl::VALIDATE_STATUS, &mut status);
+                },
+                Handle::Handle(id) => {
+                    assert!(ctxt.extensions.gl_arb_shader_objects);
+                    ctxt.gl.ValidateProgramARB(id);
+                    ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_VALIDATE_STATUS_ARB,
+                                                    &mut status);
+                }
+            }
+
+            if status == 0 {
+                let log = get_program_info_log(&mut ctxt, self.id);
+                return Err(ProgramValidationError::InvalidState(log));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the context corresponding to this program.
+    #[inline]
+    pub fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+
+    /// Attaches a name to this program, which OpenGL debuggers display instead of its id.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`, or if the program is an
+    /// ARB program object, as those can't be labelled.
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        match self.id {
+            Handle::Id(id) => self.context.set_object_label(gl::PROGRAM, id, label),
+            Handle::Handle(_) => Err(()),
+        }
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.uniforms.get(name)
     }
 
+    /// Returns the value that a uniform had right after the program was linked, which is the
+    /// value of its initializer in the GLSL source code, or zero if it doesn't have one.
+    ///
+    /// Returns `None` if the uniform doesn't exist, or if its value can't be queried. This is
+    /// the case of samplers and images, and of 64 bits types if the backend doesn't support
+    /// them. Booleans are returned as integers.
+    #[inline]
+    pub fn uniform_default(&self, name: &str) -> Option<RawUniformValue> {
+        self.uniform_defaults.get(name).cloned()
+    }
+
     /// Returns an iterator to the list of uniforms.
     ///
     /// ## Example
@@ -415,13 +480,24 @@ impl RawProgram {
     ///
     /// The correctness of semantic meaning(i.e. naming) between vertex attributes is the responsibility of user.
     pub fn transform_feedback_matches(&self, format: &VertexFormat, stride: usize) -> bool {
-        // TODO: doesn't support multiple buffers
-
         if self.get_transform_feedback_buffers().len() != 1 {
             return false;
         }
 
-        let buf = &self.get_transform_feedback_buffers()[0];
+        self.transform_feedback_buffer_matches(0, format, stride)
+    }
+
+    /// True if the transform feedback buffer at the given index matches the specified
+    /// `VertexFormat` and `stride`.
+    ///
+    /// Returns false if the program doesn't have a transform feedback buffer at this index.
+    pub fn transform_feedback_buffer_matches(&self, index: usize, format: &VertexFormat,
+                                             stride: usize) -> bool
+    {
+        let buf = match self.get_transform_feedback_buffers().get(index) {
+            Some(b) => b,
+            None => return false,
+        };
 
         if buf.stride != stride {
             return false;
@@ -565,7 +641,7 @@ impl RawProgram {
         let mut fences = Vec::with_capacity(0);
 
         self.use_program(&mut ctxt);
-        uniforms.bind_uniforms(&mut ctxt, self, &mut fences)?;
+        uniforms.bind_uniforms(&mut ctxt, self, &mut fences, &mut TextureAccesses::new(false))?;
         ctxt.gl.DispatchCompute(x, y, z);
 
         for fence in fences {
@@ -573,6 +649,7 @@ impl RawProgram {
         }
 
         ctxt.state.next_draw_call_id += 1;
+        ctxt.state.statistics.compute_dispatches += 1;
 
         Ok(())
     }
@@ -609,7 +686,7 @@ impl RawProgram {
         self.use_program(&mut ctxt);
 
         let mut fences = Vec::with_capacity(0);
-        uniforms.bind_uniforms(&mut ctxt, self, &mut fences)?;
+        uniforms.bind_uniforms(&mut ctxt, self, &mut fences, &mut TextureAccesses::new(false))?;
 
         ctxt.gl.DispatchComputeIndirect(offset as gl::types::GLintptr);
 
@@ -618,6 +695,7 @@ impl RawProgram {
         }
 
         ctxt.state.next_draw_call_id += 1;
+        ctxt.state.statistics.compute_dispatches += 1;
 
         Ok(())
     }
@@ -646,10 +724,17 @@ impl ProgramExt for RawProgram {
             let program_id = self.get_id();
             if ctxt.state.program != program_id {
                 match program_id {
-                    Handle::Id(id) => ctxt.gl.UseProgram(id),
-                    Handle::Handle(id) => ctxt.gl.UseProgramObjectARB(id),
+                    Handle::Id(id) => {
+                        ctxt.state.audit("glUseProgram", 0, id as u64);
+                        ctxt.gl.UseProgram(id)
+                    },
+                    Handle::Handle(id) => {
+                        ctxt.state.audit("glUseProgram", 0, id as u64);
+                        ctxt.gl.UseProgramObjectARB(id)
+                    },
                 }
                 ctxt.state.program = program_id;
+                ctxt.state.statistics.program_changes += 1;
                 self.uniform_values.flush_subroutine_uniforms();
             }
         }
@@ -711,6 +796,11 @@ impl ProgramExt for RawProgram {
     fn get_subroutine_data(&self) -> &SubroutineData {
         &self.subroutine_data
     }
+
+    #[inline]
+    fn get_fixed_texture_unit(&self, _: gl::types::GLint) -> Option<u16> {
+        None
+    }
 }
 
 impl Drop for RawProgram {
@@ -730,6 +820,7 @@ impl Drop for RawProgram {
                     if ctxt.state.program == Handle::Id(id) {
                         ctxt.gl.UseProgram(0);
                         ctxt.state.program = Handle::Id(0);
+                        ctxt.state.audit("glUseProgram", 0, 0);
                         self.uniform_values.flush_subroutine_uniforms();
                     }
 
@@ -741,6 +832,7 @@ impl Drop for RawProgram {
                     if ctxt.state.program == Handle::Handle(id) {
                         ctxt.gl.UseProgramObjectARB(0 as gl::types::GLhandleARB);
                         ctxt.state.program = Handle::Handle(0 as gl::types::GLhandleARB);
+                        ctxt.state.audit("glUseProgram", 0, 0);
                         self.uniform_values.flush_subroutine_uniforms();
                     }
 
@@ -748,6 +840,9 @@ impl Drop for RawProgram {
                 }
             }
         }
+
+        drop(ctxt);
+        self.context.forget_in_shared_contexts(SharedObject::Program(self.id));
     }
 }
 
@@ -791,7 +886,8 @@ unsafe fn check_program_link_errors(ctxt: &mut CommandContext<'_>, id: Handle)
     if link_success == 0 {
         use crate::ProgramCreationError::LinkingError;
 
-        match ctxt.gl.GetError() {
+        let error = if ctxt.capabilities.no_error { gl::NO_ERROR } else { ctxt.gl.GetError() };
+        match error {
             gl::NO_ERROR => (),
             gl::INVALID_VALUE => {
                 return Err(LinkingError("glLinkProgram triggered GL_INVALID_VALUE".to_string()));
@@ -804,42 +900,47 @@ unsafe fn check_program_link_errors(ctxt: &mut CommandContext<'_>, id: Handle)
             }
         };
 
-        let mut error_log_size: gl::types::GLint = 0;
+        return Err(LinkingError(get_program_info_log(ctxt, id)));
+    }
+
+    Ok(())
+}
+
+/// Returns the info log of a program, which contains the result of the latest link or
+/// validation operation.
+unsafe fn get_program_info_log(ctxt: &mut CommandContext<'_>, id: Handle) -> String {
+    let mut error_log_size: gl::types::GLint = 0;
 
-        match id {
-            Handle::Id(id) => {
-                assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
                     ctxt.version >= &Version(Api::GlEs, 2, 0));
-                ctxt.gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
-            },
-            Handle::Handle(id) => {
-                assert!(ctxt.extensions.gl_arb_shader_objects);
-                ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
-                                                &mut error_log_size);
-            }
+            ctxt.gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
+                                            &mut error_log_size);
         }
+    }
 
-        let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
+    let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
 
-        match id {
-            Handle::Id(id) => {
-                assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
                     ctxt.version >= &Version(Api::GlEs, 2, 0));
-                ctxt.gl.GetProgramInfoLog(id, error_log_size, &mut error_log_size,
-                                          error_log.as_mut_ptr() as *mut gl::types::GLchar);
-            },
-            Handle::Handle(id) => {
-                assert!(ctxt.extensions.gl_arb_shader_objects);
-                ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
+            ctxt.gl.GetProgramInfoLog(id, error_log_size, &mut error_log_size,
                                       error_log.as_mut_ptr() as *mut gl::types::GLchar);
-            }
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
+                                  error_log.as_mut_ptr() as *mut gl::types::GLchar);
         }
-
-        error_log.set_len(error_log_size as usize);
-
-        let msg = String::from_utf8(error_log).unwrap();
-        return Err(LinkingError(msg));
     }
 
-    Ok(())
+    error_log.set_len(error_log_size as usize);
+
+    String::from_utf8(error_log).unwrap()
 }
@@ -18,6 +18,7 @@ use crate::vertex::AttributeType;
 use crate::program;
 
 use crate::Handle;
+use crate::RawUniformValue;
 
 /// Information about a uniform (except its name).
 #[derive(Debug, Copy, Clone)]
@@ -308,7 +309,7 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
         let uniform_base = uniform.1;
 
         // Go over all the elements in the array
-        for i in 0..uniform_base.size.unwrap() {
+        for i in 0..uniform_base.size.unwrap_or(1) {
             let uniform = Uniform {
                 size: None,
                 location: uniform_base.location + (i as i32),
@@ -321,6 +322,254 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
     (uniforms_flattened, atomic_counters)
 }
 
+/// Queries the values of the uniforms right after the program has been linked. These are the
+/// values of the initializers in the source code, or zero for the uniforms that don't have one.
+///
+/// Samplers, images and the types that can't be queried on this system are skipped. Booleans
+/// are returned as integers.
+pub unsafe fn reflect_uniform_defaults(ctxt: &mut CommandContext<'_>, program: Handle,
+                                       uniforms: &HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>)
+                                       -> HashMap<String, RawUniformValue, BuildHasherDefault<FnvHasher>>
+{
+    let mut defaults = HashMap::with_hasher(Default::default());
+
+    for (name, uniform) in uniforms.iter() {
+        if let Some(value) = get_uniform_value(ctxt, program, uniform.location, uniform.ty) {
+            defaults.insert(name.clone(), value);
+        }
+    }
+
+    defaults
+}
+
+unsafe fn get_uniform_value(ctxt: &mut CommandContext<'_>, program: Handle,
+                            location: gl::types::GLint, ty: UniformType)
+                            -> Option<RawUniformValue>
+{
+    let value = match ty {
+        UniformType::Float => RawUniformValue::Float(get_floats(ctxt, program, location)?[0]),
+        UniformType::FloatVec2 => {
+            RawUniformValue::Vec2(vector(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatVec3 => {
+            RawUniformValue::Vec3(vector(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatVec4 => {
+            RawUniformValue::Vec4(vector(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat2 => {
+            RawUniformValue::Mat2(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat3 => {
+            RawUniformValue::Mat3(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat4 => {
+            RawUniformValue::Mat4(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat2x3 => {
+            RawUniformValue::Mat2x3(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat2x4 => {
+            RawUniformValue::Mat2x4(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat3x2 => {
+            RawUniformValue::Mat3x2(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat3x4 => {
+            RawUniformValue::Mat3x4(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat4x2 => {
+            RawUniformValue::Mat4x2(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::FloatMat4x3 => {
+            RawUniformValue::Mat4x3(matrix(&get_floats(ctxt, program, location)?))
+        },
+        UniformType::Double => RawUniformValue::Double(get_doubles(ctxt, program, location)?[0]),
+        UniformType::DoubleVec2 => {
+            RawUniformValue::DoubleVec2(vector(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleVec3 => {
+            RawUniformValue::DoubleVec3(vector(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleVec4 => {
+            RawUniformValue::DoubleVec4(vector(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat2 => {
+            RawUniformValue::DoubleMat2(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat3 => {
+            RawUniformValue::DoubleMat3(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat4 => {
+            RawUniformValue::DoubleMat4(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat2x3 => {
+            RawUniformValue::DoubleMat2x3(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat2x4 => {
+            RawUniformValue::DoubleMat2x4(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat3x2 => {
+            RawUniformValue::DoubleMat3x2(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat3x4 => {
+            RawUniformValue::DoubleMat3x4(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat4x2 => {
+            RawUniformValue::DoubleMat4x2(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::DoubleMat4x3 => {
+            RawUniformValue::DoubleMat4x3(matrix(&get_doubles(ctxt, program, location)?))
+        },
+        UniformType::Int | UniformType::Bool => {
+            RawUniformValue::SignedInt(get_ints(ctxt, program, location)?[0])
+        },
+        UniformType::IntVec2 | UniformType::BoolVec2 => {
+            RawUniformValue::IntVec2(vector(&get_ints(ctxt, program, location)?))
+        },
+        UniformType::IntVec3 | UniformType::BoolVec3 => {
+            RawUniformValue::IntVec3(vector(&get_ints(ctxt, program, location)?))
+        },
+        UniformType::IntVec4 | UniformType::BoolVec4 => {
+            RawUniformValue::IntVec4(vector(&get_ints(ctxt, program, location)?))
+        },
+        UniformType::UnsignedInt => {
+            RawUniformValue::UnsignedInt(get_uints(ctxt, program, location)?[0])
+        },
+        UniformType::UnsignedIntVec2 => {
+            RawUniformValue::UnsignedIntVec2(vector(&get_uints(ctxt, program, location)?))
+        },
+        UniformType::UnsignedIntVec3 => {
+            RawUniformValue::UnsignedIntVec3(vector(&get_uints(ctxt, program, location)?))
+        },
+        UniformType::UnsignedIntVec4 => {
+            RawUniformValue::UnsignedIntVec4(vector(&get_uints(ctxt, program, location)?))
+        },
+        UniformType::Int64 => RawUniformValue::Int64(get_int64s(ctxt, program, location)?[0]),
+        UniformType::Int64Vec2 => {
+            RawUniformValue::Int64Vec2(vector(&get_int64s(ctxt, program, location)?))
+        },
+        UniformType::Int64Vec3 => {
+            RawUniformValue::Int64Vec3(vector(&get_int64s(ctxt, program, location)?))
+        },
+        UniformType::Int64Vec4 => {
+            RawUniformValue::Int64Vec4(vector(&get_int64s(ctxt, program, location)?))
+        },
+        UniformType::UnsignedInt64 => {
+            RawUniformValue::UnsignedInt64(get_uint64s(ctxt, program, location)?[0])
+        },
+        UniformType::UnsignedInt64Vec2 => {
+            RawUniformValue::UnsignedInt64Vec2(vector(&get_uint64s(ctxt, program, location)?))
+        },
+        UniformType::UnsignedInt64Vec3 => {
+            RawUniformValue::UnsignedInt64Vec3(vector(&get_uint64s(ctxt, program, location)?))
+        },
+        UniformType::UnsignedInt64Vec4 => {
+            RawUniformValue::UnsignedInt64Vec4(vector(&get_uint64s(ctxt, program, location)?))
+        },
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+// the largest value that can be queried is a 4x4 matrix
+unsafe fn get_floats(ctxt: &mut CommandContext<'_>, program: Handle, location: gl::types::GLint)
+                     -> Option<[gl::types::GLfloat; 16]>
+{
+    let mut data = [0.0; 16];
+    match program {
+        Handle::Id(program) => ctxt.gl.GetUniformfv(program, location, data.as_mut_ptr()),
+        Handle::Handle(program) => ctxt.gl.GetUniformfvARB(program, location, data.as_mut_ptr()),
+    }
+    Some(data)
+}
+
+unsafe fn get_ints(ctxt: &mut CommandContext<'_>, program: Handle, location: gl::types::GLint)
+                   -> Option<[gl::types::GLint; 4]>
+{
+    let mut data = [0; 4];
+    match program {
+        Handle::Id(program) => ctxt.gl.GetUniformiv(program, location, data.as_mut_ptr()),
+        Handle::Handle(program) => ctxt.gl.GetUniformivARB(program, location, data.as_mut_ptr()),
+    }
+    Some(data)
+}
+
+unsafe fn get_uints(ctxt: &mut CommandContext<'_>, program: Handle, location: gl::types::GLint)
+                    -> Option<[gl::types::GLuint; 4]>
+{
+    let program = match program {
+        Handle::Id(program) => program,
+        Handle::Handle(_) => return None,
+    };
+
+    let mut data = [0; 4];
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+        ctxt.gl.GetUniformuiv(program, location, data.as_mut_ptr());
+    } else if ctxt.extensions.gl_ext_gpu_shader4 {
+        ctxt.gl.GetUniformuivEXT(program, location, data.as_mut_ptr());
+    } else {
+        return None;
+    }
+    Some(data)
+}
+
+unsafe fn get_doubles(ctxt: &mut CommandContext<'_>, program: Handle, location: gl::types::GLint)
+                      -> Option<[gl::types::GLdouble; 16]>
+{
+    match program {
+        Handle::Id(program) if ctxt.extensions.gl_arb_gpu_shader_fp64 => {
+            let mut data = [0.0; 16];
+            ctxt.gl.GetUniformdv(program, location, data.as_mut_ptr());
+            Some(data)
+        },
+        _ => None,
+    }
+}
+
+unsafe fn get_int64s(ctxt: &mut CommandContext<'_>, program: Handle, location: gl::types::GLint)
+                     -> Option<[gl::types::GLint64; 4]>
+{
+    match program {
+        Handle::Id(program) if ctxt.extensions.gl_arb_gpu_shader_int64 => {
+            let mut data = [0; 4];
+            ctxt.gl.GetUniformi64vARB(program, location, data.as_mut_ptr());
+            Some(data)
+        },
+        _ => None,
+    }
+}
+
+unsafe fn get_uint64s(ctxt: &mut CommandContext<'_>, program: Handle, location: gl::types::GLint)
+                      -> Option<[gl::types::GLuint64; 4]>
+{
+    match program {
+        Handle::Id(program) if ctxt.extensions.gl_arb_gpu_shader_int64 => {
+            let mut data = [0; 4];
+            ctxt.gl.GetUniformui64vARB(program, location, data.as_mut_ptr());
+            Some(data)
+        },
+        _ => None,
+    }
+}
+
+/// Takes the first `N` elements of a value returned by `glGetUniform`.
+fn vector<T, const N: usize>(data: &[T]) -> [T; N] where T: Copy + Default {
+    let mut result = [T::default(); N];
+    result.copy_from_slice(&data[.. N]);
+    result
+}
+
+/// Splits a value returned by `glGetUniform` into `C` columns of `R` rows.
+fn matrix<T, const C: usize, const R: usize>(data: &[T]) -> [[T; R]; C] where T: Copy + Default {
+    let mut result = [[T::default(); R]; C];
+    for (column, chunk) in result.iter_mut().zip(data.chunks(R)) {
+        column.copy_from_slice(chunk);
+    }
+    result
+}
+
 pub unsafe fn reflect_attributes(ctxt: &mut CommandContext<'_>, program: Handle)
                                  -> HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>
 {
@@ -975,6 +1224,14 @@ fn glenum_to_uniform_type(ty: gl::types::GLenum) -> UniformType {
         gl::UNSIGNED_INT_VEC2 => UniformType::UnsignedIntVec2,
         gl::UNSIGNED_INT_VEC3 => UniformType::UnsignedIntVec3,
         gl::UNSIGNED_INT_VEC4 => UniformType::UnsignedIntVec4,
+        gl::INT64_ARB => UniformType::Int64,
+        gl::INT64_VEC2_ARB => UniformType::Int64Vec2,
+        gl::INT64_VEC3_ARB => UniformType::Int64Vec3,
+        gl::INT64_VEC4_ARB => UniformType::Int64Vec4,
+        gl::UNSIGNED_INT64_ARB => UniformType::UnsignedInt64,
+        gl::UNSIGNED_INT64_VEC2_ARB => UniformType::UnsignedInt64Vec2,
+        gl::UNSIGNED_INT64_VEC3_ARB => UniformType::UnsignedInt64Vec3,
+        gl::UNSIGNED_INT64_VEC4_ARB => UniformType::UnsignedInt64Vec4,
         gl::BOOL => UniformType::Bool,
         gl::BOOL_VEC2 => UniformType::BoolVec2,
         gl::BOOL_VEC3 => UniformType::BoolVec3,
@@ -1014,6 +1271,7 @@ fn glenum_to_uniform_type(ty: gl::types::GLenum) -> UniformType {
         gl::SAMPLER_BUFFER => UniformType::SamplerBuffer,
         gl::SAMPLER_2D_RECT => UniformType::Sampler2dRect,
         gl::SAMPLER_2D_RECT_SHADOW => UniformType::Sampler2dRectShadow,
+        gl::SAMPLER_EXTERNAL_OES => UniformType::SamplerExternalOes,
         gl::INT_SAMPLER_1D => UniformType::ISampler1d,
         gl::INT_SAMPLER_2D => UniformType::ISampler2d,
         gl::INT_SAMPLER_3D => UniformType::ISampler3d,
@@ -1102,6 +1360,14 @@ fn glenum_to_attribute_type(value: gl::types::GLenum) -> AttributeType {
         gl::DOUBLE_VEC2 => AttributeType::F64F64,
         gl::DOUBLE_VEC3 => AttributeType::F64F64F64,
         gl::DOUBLE_VEC4 => AttributeType::F64F64F64F64,
+        gl::INT64_ARB => AttributeType::I64,
+        gl::INT64_VEC2_ARB => AttributeType::I64I64,
+        gl::INT64_VEC3_ARB => AttributeType::I64I64I64,
+        gl::INT64_VEC4_ARB => AttributeType::I64I64I64I64,
+        gl::UNSIGNED_INT64_ARB => AttributeType::U64,
+        gl::UNSIGNED_INT64_VEC2_ARB => AttributeType::U64U64,
+        gl::UNSIGNED_INT64_VEC3_ARB => AttributeType::U64U64U64,
+        gl::UNSIGNED_INT64_VEC4_ARB => AttributeType::U64U64U64U64,
         v => panic!("Unknown value returned by OpenGL attribute type: {}", v)
     }
 }
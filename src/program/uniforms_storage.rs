@@ -9,6 +9,7 @@ use fnv::FnvHasher;
 use crate::gl;
 use crate::Handle;
 use crate::context::CommandContext;
+use crate::debug::StateAudit;
 use crate::version::Version;
 use crate::version::Api;
 use crate::program::reflection::ShaderStage;
@@ -22,6 +23,17 @@ pub struct UniformsStorage {
                                          BuildHasherDefault<FnvHasher>>>,
 }
 
+/// Transposes a square matrix.
+fn transpose<const N: usize>(matrix: [[f32; N]; N]) -> [[f32; N]; N] {
+    let mut result = matrix;
+    for (i, column) in matrix.iter().enumerate() {
+        for (j, &value) in column.iter().enumerate() {
+            result[j][i] = value;
+        }
+    }
+    result
+}
+
 impl UniformsStorage {
     /// Builds a new empty storage.
     #[inline]
@@ -59,6 +71,21 @@ impl UniformsStorage {
             )
         );
 
+        // non-square matrices don't have an ARB variant
+        macro_rules! uniform_non_square(
+            ($ctxt:expr, $uniform:ident, $($params:expr),+) => (
+                unsafe {
+                    if $ctxt.version >= &Version(Api::Gl, 2, 1) ||
+                       $ctxt.version >= &Version(Api::GlEs, 3, 0)
+                    {
+                        $ctxt.gl.$uniform($($params),+)
+                    } else {
+                        panic!("Non-square matrices are not supported on this system.")
+                    }
+                }
+            )
+        );
+
         macro_rules! uniform_f64(
             ($ctxt:expr, $uniform:ident, $($params:expr),+) => (
                 unsafe {
@@ -83,6 +110,25 @@ impl UniformsStorage {
             )
         );
 
+        // the uniform values are compared with their debug representation, so that floats can
+        // be hashed
+        macro_rules! audit(
+            ($ctxt:expr) => (
+                if $ctxt.state.state_audit.is_some() {
+                    let program = match program {
+                        Handle::Id(id) => id as u64,
+                        Handle::Handle(id) => id as u64,
+                    };
+                    $ctxt.state.audit("glUniform", program << 32 | location as u32 as u64,
+                                      StateAudit::hash(format!("{:?}", value)));
+                }
+            )
+        );
+
+        // OpenGL ES 2.0 requires the `transpose` parameter of `glUniformMatrix` to be false
+        let transpose_supported = ctxt.version.0 != Api::GlEs ||
+                                  ctxt.version >= &Version(Api::GlEs, 3, 0);
+
         match (value, values.entry(location).or_insert(None)) {
             (&RawUniformValue::SignedInt(a), &mut Some(RawUniformValue::SignedInt(b))) if a == b => (),
             (&RawUniformValue::UnsignedInt(a), &mut Some(RawUniformValue::UnsignedInt(b))) if a == b => (),
@@ -90,6 +136,15 @@ impl UniformsStorage {
             (&RawUniformValue::Mat2(a), &mut Some(RawUniformValue::Mat2(b))) if a == b => (),
             (&RawUniformValue::Mat3(a), &mut Some(RawUniformValue::Mat3(b))) if a == b => (),
             (&RawUniformValue::Mat4(a), &mut Some(RawUniformValue::Mat4(b))) if a == b => (),
+            (&RawUniformValue::Mat2Transposed(a), &mut Some(RawUniformValue::Mat2Transposed(b))) if a == b => (),
+            (&RawUniformValue::Mat3Transposed(a), &mut Some(RawUniformValue::Mat3Transposed(b))) if a == b => (),
+            (&RawUniformValue::Mat4Transposed(a), &mut Some(RawUniformValue::Mat4Transposed(b))) if a == b => (),
+            (&RawUniformValue::Mat2x3(a), &mut Some(RawUniformValue::Mat2x3(b))) if a == b => (),
+            (&RawUniformValue::Mat2x4(a), &mut Some(RawUniformValue::Mat2x4(b))) if a == b => (),
+            (&RawUniformValue::Mat3x2(a), &mut Some(RawUniformValue::Mat3x2(b))) if a == b => (),
+            (&RawUniformValue::Mat3x4(a), &mut Some(RawUniformValue::Mat3x4(b))) if a == b => (),
+            (&RawUniformValue::Mat4x2(a), &mut Some(RawUniformValue::Mat4x2(b))) if a == b => (),
+            (&RawUniformValue::Mat4x3(a), &mut Some(RawUniformValue::Mat4x3(b))) if a == b => (),
             (&RawUniformValue::Vec2(a), &mut Some(RawUniformValue::Vec2(b))) if a == b => (),
             (&RawUniformValue::Vec3(a), &mut Some(RawUniformValue::Vec3(b))) if a == b => (),
             (&RawUniformValue::Vec4(a), &mut Some(RawUniformValue::Vec4(b))) if a == b => (),
@@ -103,6 +158,12 @@ impl UniformsStorage {
             (&RawUniformValue::DoubleMat2(a), &mut Some(RawUniformValue::DoubleMat2(b))) if a == b => (),
             (&RawUniformValue::DoubleMat3(a), &mut Some(RawUniformValue::DoubleMat3(b))) if a == b => (),
             (&RawUniformValue::DoubleMat4(a), &mut Some(RawUniformValue::DoubleMat4(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat2x3(a), &mut Some(RawUniformValue::DoubleMat2x3(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat2x4(a), &mut Some(RawUniformValue::DoubleMat2x4(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat3x2(a), &mut Some(RawUniformValue::DoubleMat3x2(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat3x4(a), &mut Some(RawUniformValue::DoubleMat3x4(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat4x2(a), &mut Some(RawUniformValue::DoubleMat4x2(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat4x3(a), &mut Some(RawUniformValue::DoubleMat4x3(b))) if a == b => (),
             (&RawUniformValue::DoubleVec2(a), &mut Some(RawUniformValue::DoubleVec2(b))) if a == b => (),
             (&RawUniformValue::DoubleVec3(a), &mut Some(RawUniformValue::DoubleVec3(b))) if a == b => (),
             (&RawUniformValue::DoubleVec4(a), &mut Some(RawUniformValue::DoubleVec4(b))) if a == b => (),
@@ -117,11 +178,13 @@ impl UniformsStorage {
 
             (&RawUniformValue::SignedInt(v), target) => {
                 *target = Some(RawUniformValue::SignedInt(v));
+                audit!(ctxt);
                 uniform!(ctxt, Uniform1i, Uniform1iARB, location, v);
             },
 
             (&RawUniformValue::UnsignedInt(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt(v));
+                audit!(ctxt);
 
                 // Uniform1uiARB doesn't exist
                 unsafe {
@@ -138,59 +201,151 @@ impl UniformsStorage {
 
             (&RawUniformValue::Float(v), target) => {
                 *target = Some(RawUniformValue::Float(v));
+                audit!(ctxt);
                 uniform!(ctxt, Uniform1f, Uniform1fARB, location, v);
             },
 
             (&RawUniformValue::Mat2(v), target) => {
                 *target = Some(RawUniformValue::Mat2(v));
+                audit!(ctxt);
                 uniform!(ctxt, UniformMatrix2fv, UniformMatrix2fvARB,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Mat3(v), target) => {
                 *target = Some(RawUniformValue::Mat3(v));
+                audit!(ctxt);
                 uniform!(ctxt, UniformMatrix3fv, UniformMatrix3fvARB,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Mat4(v), target) => {
                 *target = Some(RawUniformValue::Mat4(v));
+                audit!(ctxt);
                 uniform!(ctxt, UniformMatrix4fv, UniformMatrix4fvARB,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
+            (&RawUniformValue::Mat2Transposed(v), target) => {
+                *target = Some(RawUniformValue::Mat2Transposed(v));
+                audit!(ctxt);
+                if transpose_supported {
+                    uniform!(ctxt, UniformMatrix2fv, UniformMatrix2fvARB,
+                             location, 1, gl::TRUE, v.as_ptr() as *const f32);
+                } else {
+                    let v = transpose(v);
+                    uniform!(ctxt, UniformMatrix2fv, UniformMatrix2fvARB,
+                             location, 1, gl::FALSE, v.as_ptr() as *const f32);
+                }
+            },
+
+            (&RawUniformValue::Mat3Transposed(v), target) => {
+                *target = Some(RawUniformValue::Mat3Transposed(v));
+                audit!(ctxt);
+                if transpose_supported {
+                    uniform!(ctxt, UniformMatrix3fv, UniformMatrix3fvARB,
+                             location, 1, gl::TRUE, v.as_ptr() as *const f32);
+                } else {
+                    let v = transpose(v);
+                    uniform!(ctxt, UniformMatrix3fv, UniformMatrix3fvARB,
+                             location, 1, gl::FALSE, v.as_ptr() as *const f32);
+                }
+            },
+
+            (&RawUniformValue::Mat4Transposed(v), target) => {
+                *target = Some(RawUniformValue::Mat4Transposed(v));
+                audit!(ctxt);
+                if transpose_supported {
+                    uniform!(ctxt, UniformMatrix4fv, UniformMatrix4fvARB,
+                             location, 1, gl::TRUE, v.as_ptr() as *const f32);
+                } else {
+                    let v = transpose(v);
+                    uniform!(ctxt, UniformMatrix4fv, UniformMatrix4fvARB,
+                             location, 1, gl::FALSE, v.as_ptr() as *const f32);
+                }
+            },
+
+            (&RawUniformValue::Mat2x3(v), target) => {
+                *target = Some(RawUniformValue::Mat2x3(v));
+                audit!(ctxt);
+                uniform_non_square!(ctxt, UniformMatrix2x3fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat2x4(v), target) => {
+                *target = Some(RawUniformValue::Mat2x4(v));
+                audit!(ctxt);
+                uniform_non_square!(ctxt, UniformMatrix2x4fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat3x2(v), target) => {
+                *target = Some(RawUniformValue::Mat3x2(v));
+                audit!(ctxt);
+                uniform_non_square!(ctxt, UniformMatrix3x2fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat3x4(v), target) => {
+                *target = Some(RawUniformValue::Mat3x4(v));
+                audit!(ctxt);
+                uniform_non_square!(ctxt, UniformMatrix3x4fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat4x2(v), target) => {
+                *target = Some(RawUniformValue::Mat4x2(v));
+                audit!(ctxt);
+                uniform_non_square!(ctxt, UniformMatrix4x2fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat4x3(v), target) => {
+                *target = Some(RawUniformValue::Mat4x3(v));
+                audit!(ctxt);
+                uniform_non_square!(ctxt, UniformMatrix4x3fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
             (&RawUniformValue::Vec2(v), target) => {
                 *target = Some(RawUniformValue::Vec2(v));
+                audit!(ctxt);
                 uniform!(ctxt, Uniform2fv, Uniform2fvARB, location, 1, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Vec3(v), target) => {
                 *target = Some(RawUniformValue::Vec3(v));
+                audit!(ctxt);
                 uniform!(ctxt, Uniform3fv, Uniform3fvARB, location, 1, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Vec4(v), target) => {
                 *target = Some(RawUniformValue::Vec4(v));
+                audit!(ctxt);
                 uniform!(ctxt, Uniform4fv, Uniform4fvARB, location, 1, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::IntVec2(v), target) => {
                 *target = Some(RawUniformValue::IntVec2(v));
+                audit!(ctxt);
                 uniform!(ctxt, Uniform2iv, Uniform2ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::IntVec3(v), target) => {
                 *target = Some(RawUniformValue::IntVec3(v));
+                audit!(ctxt);
                 uniform!(ctxt, Uniform3iv, Uniform3ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::IntVec4(v), target) => {
                 *target = Some(RawUniformValue::IntVec4(v));
+                audit!(ctxt);
                 uniform!(ctxt, Uniform4iv, Uniform4ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::UnsignedIntVec2(v), target) => {
                 *target = Some(RawUniformValue::UnsignedIntVec2(v));
+                audit!(ctxt);
 
                 // Uniform2uivARB doesn't exist
                 unsafe {
@@ -207,6 +362,7 @@ impl UniformsStorage {
 
             (&RawUniformValue::UnsignedIntVec3(v), target) => {
                 *target = Some(RawUniformValue::UnsignedIntVec3(v));
+                audit!(ctxt);
 
                 // Uniform3uivARB doesn't exist
                 unsafe {
@@ -223,6 +379,7 @@ impl UniformsStorage {
 
             (&RawUniformValue::UnsignedIntVec4(v), target) => {
                 *target = Some(RawUniformValue::UnsignedIntVec4(v));
+                audit!(ctxt);
 
                 // Uniform4uivARB doesn't exist
                 unsafe {
@@ -238,75 +395,132 @@ impl UniformsStorage {
             },
             (&RawUniformValue::Double(v), target) => {
                 *target = Some(RawUniformValue::Double(v));
+                audit!(ctxt);
                 uniform_f64!(ctxt, Uniform1d, location, v);
             },
 
             (&RawUniformValue::DoubleMat2(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat2(v));
+                audit!(ctxt);
                 uniform_f64!(ctxt, UniformMatrix2dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleMat3(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat3(v));
+                audit!(ctxt);
                 uniform_f64!(ctxt, UniformMatrix3dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleMat4(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat4(v));
+                audit!(ctxt);
                 uniform_f64!(ctxt, UniformMatrix4dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
+            (&RawUniformValue::DoubleMat2x3(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat2x3(v));
+                audit!(ctxt);
+                uniform_f64!(ctxt, UniformMatrix2x3dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat2x4(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat2x4(v));
+                audit!(ctxt);
+                uniform_f64!(ctxt, UniformMatrix2x4dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat3x2(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat3x2(v));
+                audit!(ctxt);
+                uniform_f64!(ctxt, UniformMatrix3x2dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat3x4(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat3x4(v));
+                audit!(ctxt);
+                uniform_f64!(ctxt, UniformMatrix3x4dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat4x2(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat4x2(v));
+                audit!(ctxt);
+                uniform_f64!(ctxt, UniformMatrix4x2dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat4x3(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat4x3(v));
+                audit!(ctxt);
+                uniform_f64!(ctxt, UniformMatrix4x3dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
             (&RawUniformValue::DoubleVec2(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec2(v));
+                audit!(ctxt);
                 uniform_f64!(ctxt, Uniform2dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleVec3(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec3(v));
+                audit!(ctxt);
                 uniform_f64!(ctxt, Uniform3dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleVec4(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec4(v));
+                audit!(ctxt);
                 uniform_f64!(ctxt, Uniform4dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
             (&RawUniformValue::Int64(v), target) => {
                 *target = Some(RawUniformValue::Int64(v));
+                audit!(ctxt);
                 uniform_i64!(ctxt, Uniform1i64ARB, location, v);
             },
             (&RawUniformValue::Int64Vec2(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec2(v));
+                audit!(ctxt);
                 uniform_i64!(ctxt, Uniform2i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
 
             (&RawUniformValue::Int64Vec3(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec3(v));
+                audit!(ctxt);
                 uniform_i64!(ctxt, Uniform3i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
 
             (&RawUniformValue::Int64Vec4(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec4(v));
+                audit!(ctxt);
                 uniform_i64!(ctxt, Uniform4i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
             (&RawUniformValue::UnsignedInt64(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64(v));
+                audit!(ctxt);
                 uniform_i64!(ctxt, Uniform1ui64ARB, location, v);
             },
             (&RawUniformValue::UnsignedInt64Vec2(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec2(v));
+                audit!(ctxt);
                 uniform_i64!(ctxt, Uniform2ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
 
             (&RawUniformValue::UnsignedInt64Vec3(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec3(v));
+                audit!(ctxt);
                 uniform_i64!(ctxt, Uniform3ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
 
             (&RawUniformValue::UnsignedInt64Vec4(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec4(v));
+                audit!(ctxt);
                 uniform_i64!(ctxt, Uniform4ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
         }
@@ -335,6 +549,8 @@ impl UniformsStorage {
                 *target = Some(a);
                 match program {
                     Handle::Id(id) => unsafe {
+                        ctxt.state.audit("glUniformBlockBinding",
+                                         (id as u64) << 32 | location as u64, value as u64);
                         ctxt.gl.UniformBlockBinding(id, location, value);
                     },
                     _ => unreachable!()
@@ -366,6 +582,8 @@ impl UniformsStorage {
                 *target = Some(a);
                 match program {
                     Handle::Id(id) => unsafe {
+                        ctxt.state.audit("glShaderStorageBlockBinding",
+                                         (id as u64) << 32 | location as u64, value as u64);
                         ctxt.gl.ShaderStorageBlockBinding(id, location, value);
                     },
                     _ => unreachable!()
@@ -401,6 +619,8 @@ impl UniformsStorage {
         // TODO: don't assume that, instead use DSA if the program is not current
         assert!(ctxt.state.program == program);
         subroutine_uniforms.insert(stage, indices.to_vec());
+        ctxt.state.audit("glUniformSubroutines", stage.to_gl_enum() as u64,
+                         StateAudit::hash(indices));
         unsafe {
             ctxt.gl.UniformSubroutinesuiv(stage.to_gl_enum(), indices.len() as gl::types::GLsizei, indices.as_ptr() as *const _);
         }
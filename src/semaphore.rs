@@ -1,5 +1,9 @@
 /*!
 Contains everything related to external API semaphores.
+
+Semaphores are created by the external API, exported as a file descriptor, and imported with
+`Semaphore::new_from_fd`. OpenGL can't export its own semaphores, so a renderer that needs to
+signal Vulkan from glium should create two Vulkan semaphores, one for each direction.
 */
 // TODO: Add Windows support via EXT_external_objects_win32
 
@@ -96,14 +100,15 @@ impl Semaphore {
         facade: &F,
         fd: std::fs::File,
     ) -> Result<Self, SemaphoreCreationError> {
-        use std::os::unix::io::AsRawFd;
+        use std::os::unix::io::IntoRawFd;
 
         let ctxt = facade.get_context().make_current();
         let sem = Self::new(facade, &ctxt)?;
 
         if ctxt.extensions.gl_ext_semaphore_fd {
+            // the implementation takes ownership of the file descriptor
             ctxt.gl
-                .ImportSemaphoreFdEXT(sem.id, gl::HANDLE_TYPE_OPAQUE_FD_EXT, fd.as_raw_fd());
+                .ImportSemaphoreFdEXT(sem.id, gl::HANDLE_TYPE_OPAQUE_FD_EXT, fd.into_raw_fd());
 
             if ctxt.gl.IsSemaphoreEXT(sem.id) == gl::FALSE {
                 Err(SemaphoreCreationError::NullResult)
@@ -6,19 +6,40 @@ use crate::gl;
 use crate::backend::Facade;
 use crate::context::Context;
 use crate::ContextExt;
+use std::error::Error;
+use std::fmt;
 use std::rc::Rc;
 
 use std::thread;
+use std::time::Duration;
 
 /// Error that happens when sync functionalities are not supported.
 #[derive(Copy, Clone, Debug)]
 pub struct SyncNotSupportedError;
 
+/// Error returned by `SyncFence::wait_on_server` when the context that waits doesn't share its
+/// objects with the context of the fence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ContextNotSharedError;
+
+impl fmt::Display for ContextNotSharedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("The context doesn't share its objects with the context of the fence")
+    }
+}
+
+impl Error for ContextNotSharedError {}
+
 /// Provides a way to wait for a server-side operation to be finished.
 ///
 /// Creating a `SyncFence` injects an element in the commands queue of the backend.
 /// When this element is reached, the fence becomes signaled.
 ///
+/// Creating a fence doesn't flush the commands queue. The queue is flushed the first time you
+/// check or wait for the fence from the CPU, which guarantees that the fence is eventually
+/// signaled.
+///
 /// ## Example
 ///
 /// ```no_run
@@ -56,6 +77,61 @@ impl SyncFence {
             _ => panic!("Could not wait for the fence")
         };
     }
+
+    /// Returns true if the fence has been reached by the server, without blocking.
+    pub fn is_signaled(&self) -> bool {
+        self.wait_timeout(Duration::new(0, 0))
+    }
+
+    /// Blocks until the operation has finished on the server or until the timeout expires.
+    ///
+    /// Returns true if the fence has been reached by the server. Contrary to `wait`, the fence
+    /// is not consumed and can be waited for again.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let sync = match self.id {
+            None => return true,
+            Some(s) => s
+        };
+
+        let timeout = timeout.as_nanos().min(u64::MAX as u128) as gl::types::GLuint64;
+
+        let mut ctxt = self.context.make_current();
+        let result = unsafe { client_wait_timeout(&mut ctxt, sync, timeout) };
+
+        match result {
+            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => true,
+            gl::TIMEOUT_EXPIRED => false,
+            _ => panic!("Could not wait for the fence")
+        }
+    }
+
+    /// Makes the server wait for the fence before executing the commands that `facade` submits
+    /// afterwards.
+    ///
+    /// This function doesn't block. It is useful when the fence has been created by another
+    /// context that shares its objects with the one of `facade`, for example after uploading
+    /// resources from a loading thread. The context of the fence must have flushed its commands
+    /// queue after creating it, otherwise the server may wait forever.
+    ///
+    /// Returns an error if the context of `facade` is neither the context of the fence nor one
+    /// that shares its objects with it, as the fence doesn't exist there.
+    pub fn wait_on_server<F: Facade + ?Sized>(&self, facade: &F)
+                                              -> Result<(), ContextNotSharedError>
+    {
+        if !self.context.shares_objects_with(facade.get_context()) {
+            return Err(ContextNotSharedError);
+        }
+
+        let sync = match self.id {
+            None => return Ok(()),
+            Some(s) => s
+        };
+
+        // `glWaitSync` only affects the commands queue of the current context
+        let mut ctxt = facade.get_context().make_current();
+        unsafe { server_wait(&mut ctxt, sync) };
+        Ok(())
+    }
 }
 
 impl Drop for SyncFence {
@@ -139,6 +215,18 @@ pub unsafe fn destroy_linear_sync_fence(ctxt: &mut CommandContext<'_>, mut fence
     delete_fence(ctxt, fence);
 }
 
+/// Returns true if the fence has been reached by the server, without blocking.
+#[inline]
+pub unsafe fn is_linear_sync_fence_signaled(ctxt: &mut CommandContext<'_>,
+                                            fence: &LinearSyncFence) -> bool
+{
+    match client_wait_timeout(ctxt, fence.id.unwrap(), 0) {
+        gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => true,
+        gl::TIMEOUT_EXPIRED => false,
+        _ => panic!("Could not wait for the fence")
+    }
+}
+
 /// Calls `glClientWaitSync` and returns the result.
 ///
 /// Tries without flushing first, then with flushing.
@@ -182,6 +270,46 @@ unsafe fn client_wait(ctxt: &mut CommandContext<'_>, fence: gl::types::GLsync) -
     }
 }
 
+/// Calls `glClientWaitSync` with the given timeout in nanoseconds and returns the result.
+///
+/// The commands queue is flushed so that the fence is guaranteed to be signaled eventually.
+///
+/// # Unsafety
+///
+/// The fence object must exist.
+///
+unsafe fn client_wait_timeout(ctxt: &mut CommandContext<'_>, fence: gl::types::GLsync,
+                              timeout: gl::types::GLuint64) -> gl::types::GLenum
+{
+    if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+       ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, timeout)
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.ClientWaitSyncAPPLE(fence, gl::SYNC_FLUSH_COMMANDS_BIT_APPLE, timeout)
+    } else {
+        unreachable!();
+    }
+}
+
+/// Calls `glWaitSync`.
+///
+/// # Unsafety
+///
+/// The fence object must exist.
+///
+unsafe fn server_wait(ctxt: &mut CommandContext<'_>, fence: gl::types::GLsync) {
+    if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+       ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.WaitSync(fence, 0, gl::TIMEOUT_IGNORED);
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.WaitSyncAPPLE(fence, 0, gl::TIMEOUT_IGNORED_APPLE);
+    } else {
+        unreachable!();
+    }
+}
+
 /// Deletes a fence.
 ///
 /// # Unsafety
@@ -7,6 +7,8 @@ use crate::memory_object::MemoryObject;
 use crate::version::Version;
 use crate::context::Context;
 use crate::context::CommandContext;
+use crate::context::GarbageObject;
+use crate::context::SharedObject;
 use crate::CapabilitiesSource;
 use crate::ContextExt;
 use crate::TextureExt;
@@ -223,6 +225,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
             ctxt.gl.BindTexture(bind_point, id);
             let act = ctxt.state.active_texture as usize;
             ctxt.state.texture_units[act].texture = id;
+            ctxt.state.audit("glBindTexture", (bind_point as u64) << 32 | act as u64, id as u64);
         }
 
         if !is_multisampled {
@@ -540,6 +543,7 @@ pub unsafe fn new_from_fd<F: Facade + ?Sized>(facade: &F,
         ctxt.gl.BindTexture(bind_point, id);
         let act = ctxt.state.active_texture as usize;
         ctxt.state.texture_units[act].texture = id;
+        ctxt.state.audit("glBindTexture", (bind_point as u64) << 32 | act as u64, id as u64);
 
         let gl_tiling: crate::gl::types::GLenum = params.tiling.into();
 
@@ -796,6 +800,14 @@ impl TextureAny {
         self.levels
     }
 
+    /// Attaches a name to this texture, which OpenGL debuggers display instead of its id.
+    ///
+    /// Returns `Err` if the backend doesn't support `GL_KHR_debug`.
+    #[inline]
+    pub fn set_label(&self, label: &str) -> Result<(), ()> {
+        self.context.set_object_label(gl::TEXTURE, self.id, label)
+    }
+
     /// Returns a structure that represents the main mipmap level of the texture.
     #[inline]
     pub fn main_level(&self) -> TextureAnyMipmap<'_> {
@@ -853,6 +865,8 @@ impl TextureExt for TextureAny {
         if ctxt.state.texture_units[texture_unit as usize].texture != self.id {
             unsafe { ctxt.gl.BindTexture(bind_point, self.id) };
             ctxt.state.texture_units[texture_unit as usize].texture = self.id;
+            ctxt.state.audit("glBindTexture", (bind_point as u64) << 32 | texture_unit as u64,
+                             self.id as u64);
         }
 
         bind_point
@@ -864,6 +878,7 @@ impl TextureExt for TextureAny {
                 if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_texture_fetch {
                     unsafe { ctxt.gl.MemoryBarrier(gl::TEXTURE_FETCH_BARRIER_BIT); }
                     ctxt.state.latest_memory_barrier_texture_fetch = ctxt.state.next_draw_call_id;
+                    ctxt.state.textures_written_since_barrier.clear();
                 }
             },
             crate::TextureAccess::ImageUnit { will_write } =>{
@@ -915,17 +930,42 @@ impl Drop for TextureAny {
         // removing FBOs which contain this texture
         fbo::FramebuffersContainer::purge_texture(&mut ctxt, self.id);
 
-        // resetting the bindings
-        for tex_unit in ctxt.state.texture_units.iter_mut() {
-            if tex_unit.texture == self.id {
-                tex_unit.texture = 0;
-            }
+        if !self.owned {
+            forget_texture(&mut ctxt, self.id);
+        } else if !ctxt.garbage.defer(GarbageObject::Texture(self.id)) {
+            unsafe { delete_texture(&mut ctxt, self.id) };
         }
 
-        if self.owned {
-            unsafe { ctxt.gl.DeleteTextures(1, [ self.id ].as_ptr()); }
+        drop(ctxt);
+        self.context.forget_in_shared_contexts(SharedObject::Texture(self.id));
+    }
+}
+
+impl TextureAny {
+    /// Gives up the ownership of the texture and returns its id. The texture is removed from the
+    /// caches of its context, but isn't deleted.
+    pub(crate) fn into_id(mut self) -> gl::types::GLuint {
+        self.owned = false;
+        self.id
+    }
+}
+
+/// Deletes a texture and removes it from the state cache.
+pub(crate) unsafe fn delete_texture(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    forget_texture(ctxt, id);
+    ctxt.gl.DeleteTextures(1, [ id ].as_ptr());
+}
+
+/// Resets the texture units the texture is bound to in the state cache, and forgets that it
+/// has been rendered to since the latest texture barrier, as its name can be reused.
+pub(crate) fn forget_texture(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    for tex_unit in ctxt.state.texture_units.iter_mut() {
+        if tex_unit.texture == id {
+            tex_unit.texture = 0;
         }
     }
+
+    ctxt.state.textures_written_since_barrier.retain(|t| *t != id);
 }
 
 /// Represents a specific layer of an array texture and 3D textures.
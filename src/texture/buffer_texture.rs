@@ -410,6 +410,8 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
                 ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, id);
                 let act = ctxt.state.active_texture as usize;
                 ctxt.state.texture_units[act].texture = id;
+                ctxt.state.audit("glBindTexture", (gl::TEXTURE_BUFFER as u64) << 32 | act as u64,
+                                 id as u64);
             }
 
             // binding the buffer
@@ -548,6 +550,9 @@ impl<'a> TextureExt for BufferTextureRef<'a> {
     #[inline]
     fn bind_to_current(&self, ctxt: &mut CommandContext<'_>) -> gl::types::GLenum {
         unsafe { ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, self.texture); }
+        let act = ctxt.state.active_texture;
+        ctxt.state.audit("glBindTexture", (gl::TEXTURE_BUFFER as u64) << 32 | act as u64,
+                         self.texture as u64);
         gl::TEXTURE_BUFFER
     }
 
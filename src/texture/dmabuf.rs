@@ -0,0 +1,415 @@
+/*!
+Sharing textures with other APIs and processes through Linux DMA-BUF file descriptors.
+
+A DMA-BUF is a buffer in GPU-accessible memory that is referenced by file descriptors. Video
+decoders (VA-API, V4L2), cameras and Wayland clients hand out their images as DMA-BUFs, and
+importing them as a texture doesn't copy anything.
+
+```no_run
+# fn example(display: &glium::Display<glium::glutin::surface::WindowSurface>,
+#            buffer: glium::texture::dmabuf::DmaBuf) {
+use glium::texture::dmabuf;
+
+let texture = dmabuf::import(display, &buffer).unwrap();
+# }
+```
+
+The other way around, `export` returns DMA-BUF file descriptors that refer to the storage of a
+texture, for example to send the result of a render to a Wayland compositor or a video encoder.
+
+Both directions only work with contexts created through EGL. Importing requires
+`EGL_EXT_image_dma_buf_import` and `GL_OES_EGL_image`, and exporting requires
+`EGL_KHR_gl_texture_2D_image` and `EGL_MESA_image_dma_buf_export`.
+
+Only single-plane RGB formats can be mapped to a glium texture. To use a multi-planar YUV image,
+import each plane separately with a format of the same size (for example `R8` for the luma plane
+of NV12 and `GR88` for its chroma plane) and do the conversion in a shader.
+
+Synchronization is implicit: glium flushes the commands that write to a texture before exporting
+it, and the kernel makes the other users of the buffer wait for them.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::gl;
+use crate::texture::{Dimensions, MipmapsOption, Texture2d, UncompressedFloatFormat};
+use crate::ContextExt;
+use crate::GlObject;
+use crate::TextureExt;
+
+/// Builds a fourcc code from its four characters, like the `fourcc_code` macro of
+/// `drm_fourcc.h`.
+#[inline]
+pub const fn fourcc(code: [u8; 4]) -> u32 {
+    u32::from_le_bytes(code)
+}
+
+/// 8-bit red.
+pub const R8: u32 = fourcc(*b"R8  ");
+/// 16-bit red.
+pub const R16: u32 = fourcc(*b"R16 ");
+/// 8-bit red and green, packed in 16 bits.
+pub const GR88: u32 = fourcc(*b"GR88");
+/// 16-bit red and green, packed in 32 bits.
+pub const GR1616: u32 = fourcc(*b"GR32");
+/// 8-bit red, green and blue, with 8 bits of padding.
+pub const XRGB8888: u32 = fourcc(*b"XR24");
+/// 8-bit alpha, red, green and blue.
+pub const ARGB8888: u32 = fourcc(*b"AR24");
+/// 8-bit blue, green and red, with 8 bits of padding.
+pub const XBGR8888: u32 = fourcc(*b"XB24");
+/// 8-bit alpha, blue, green and red.
+pub const ABGR8888: u32 = fourcc(*b"AB24");
+/// 10-bit red, green and blue, with 2 bits of padding.
+pub const XRGB2101010: u32 = fourcc(*b"XR30");
+/// 2-bit alpha, 10-bit red, green and blue.
+pub const ARGB2101010: u32 = fourcc(*b"AR30");
+/// 10-bit blue, green and red, with 2 bits of padding.
+pub const XBGR2101010: u32 = fourcc(*b"XB30");
+/// 2-bit alpha, 10-bit blue, green and red.
+pub const ABGR2101010: u32 = fourcc(*b"AB30");
+/// Half-float alpha, blue, green and red.
+pub const ABGR16161616F: u32 = fourcc(*b"AB4H");
+
+/// Modifier meaning that the layout of the buffer is implied by the driver.
+const DRM_FORMAT_MOD_INVALID: u64 = 0x00ff_ffff_ffff_ffff;
+
+/// Describes an image stored in DMA-BUFs.
+#[derive(Debug)]
+pub struct DmaBuf {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// Pixel format of the image, as a DRM fourcc code.
+    pub fourcc: u32,
+    /// Tiling and compression layout of the image, as a DRM format modifier. `None` if the layout
+    /// is implied by the driver.
+    pub modifier: Option<u64>,
+    /// The planes of the image. There are between one and four of them.
+    pub planes: Vec<DmaBufPlane>,
+}
+
+/// A plane of an image stored in a DMA-BUF.
+#[derive(Debug)]
+pub struct DmaBufPlane {
+    /// File descriptor of the DMA-BUF. Several planes can refer to the same buffer.
+    pub fd: OwnedFd,
+    /// Offset in bytes of the plane from the start of the buffer.
+    pub offset: u32,
+    /// Number of bytes between the starts of two consecutive rows.
+    pub stride: u32,
+}
+
+/// Error that can happen when importing or exporting a DMA-BUF.
+#[derive(Debug)]
+pub enum DmaBufError {
+    /// The context wasn't created with EGL.
+    NotEgl,
+    /// An EGL or OpenGL extension that is required isn't supported. Contains its name.
+    ExtensionNotSupported(&'static str),
+    /// The fourcc code doesn't correspond to a format that glium can sample from.
+    UnsupportedFormat(u32),
+    /// The image has no planes, or more than four.
+    InvalidPlaneCount(usize),
+    /// An EGL function failed. Contains the EGL error code.
+    EglError(i32),
+    /// A file descriptor couldn't be duplicated.
+    IoError(io::Error),
+}
+
+impl fmt::Display for DmaBufError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::DmaBufError::*;
+        match *self {
+            NotEgl => write!(fmt, "The OpenGL context wasn't created with EGL"),
+            ExtensionNotSupported(name) => write!(fmt, "{} is not supported", name),
+            UnsupportedFormat(code) => {
+                let chars = code.to_le_bytes().map(char::from);
+                write!(fmt, "Unsupported DMA-BUF format: {}", String::from_iter(chars))
+            },
+            InvalidPlaneCount(count) => write!(fmt, "Invalid number of planes: {}", count),
+            EglError(code) => write!(fmt, "EGL error 0x{:x}", code),
+            IoError(ref err) => write!(fmt, "I/O error: {}", err),
+        }
+    }
+}
+
+impl Error for DmaBufError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            DmaBufError::IoError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DmaBufError {
+    fn from(err: io::Error) -> DmaBufError {
+        DmaBufError::IoError(err)
+    }
+}
+
+/// Imports a DMA-BUF as a texture.
+///
+/// The file descriptors aren't consumed, and can be closed once the function has returned. The
+/// texture keeps a reference to the buffer.
+pub fn import<F: Facade + ?Sized>(facade: &F, buffer: &DmaBuf)
+                                  -> Result<Texture2d, DmaBufError>
+{
+    let format = texture_format(buffer.fourcc)
+        .ok_or(DmaBufError::UnsupportedFormat(buffer.fourcc))?;
+    if buffer.planes.is_empty() || buffer.planes.len() > PLANE_ATTRIBUTES.len() {
+        return Err(DmaBufError::InvalidPlaneCount(buffer.planes.len()));
+    }
+
+    let mut ctxt = facade.get_context().make_current();
+    if !ctxt.extensions.gl_oes_egl_image {
+        return Err(DmaBufError::ExtensionNotSupported("GL_OES_EGL_image"));
+    }
+
+    let egl = unsafe { Egl::load(facade.get_context()) }?;
+    egl.require("EGL_EXT_image_dma_buf_import")?;
+    if buffer.modifier.is_some() {
+        egl.require("EGL_EXT_image_dma_buf_import_modifiers")?;
+    }
+
+    let mut attributes = vec![
+        EGL_WIDTH, buffer.width as i32,
+        EGL_HEIGHT, buffer.height as i32,
+        EGL_LINUX_DRM_FOURCC_EXT, buffer.fourcc as i32,
+    ];
+    for (plane, names) in buffer.planes.iter().zip(PLANE_ATTRIBUTES.iter()) {
+        attributes.extend_from_slice(&[
+            names[0], plane.fd.as_raw_fd(),
+            names[1], plane.offset as i32,
+            names[2], plane.stride as i32,
+        ]);
+        if let Some(modifier) = buffer.modifier {
+            attributes.extend_from_slice(&[
+                names[3], modifier as u32 as i32,
+                names[4], (modifier >> 32) as u32 as i32,
+            ]);
+        }
+    }
+    attributes.push(EGL_NONE);
+
+    let id = unsafe {
+        let image = (egl.create_image)(egl.display, ptr::null_mut(), EGL_LINUX_DMA_BUF_EXT,
+                                       ptr::null_mut(), attributes.as_ptr());
+        if image.is_null() {
+            return Err(DmaBufError::EglError((egl.get_error)()));
+        }
+
+        let mut id = 0;
+        ctxt.gl.GenTextures(1, &mut id);
+        ctxt.gl.BindTexture(gl::TEXTURE_2D, id);
+        let act = ctxt.state.active_texture as usize;
+        ctxt.state.texture_units[act].texture = id;
+        ctxt.state.audit("glBindTexture", (gl::TEXTURE_2D as u64) << 32 | act as u64, id as u64);
+
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        ctxt.gl.EGLImageTargetTexture2DOES(gl::TEXTURE_2D, image);
+
+        // the texture keeps the storage alive
+        (egl.destroy_image)(egl.display, image);
+        id
+    };
+    drop(ctxt);
+
+    let ty = Dimensions::Texture2d { width: buffer.width, height: buffer.height };
+    Ok(unsafe { Texture2d::from_id(facade, format, id, true, MipmapsOption::NoMipmap, ty) })
+}
+
+/// Exports the storage of the main level of a texture as a DMA-BUF.
+///
+/// The format and the layout are chosen by the driver, and are described in the returned value.
+/// The texture and the buffer share their memory, so that later draws to the texture are
+/// visible to the other users of the buffer.
+pub fn export(texture: &Texture2d) -> Result<DmaBuf, DmaBufError> {
+    let context = texture.get_context();
+    let ctxt = context.make_current();
+    let egl = unsafe { Egl::load(context) }?;
+    egl.require("EGL_KHR_gl_texture_2D_image")?;
+    egl.require("EGL_MESA_image_dma_buf_export")?;
+    let unsupported = DmaBufError::ExtensionNotSupported("EGL_MESA_image_dma_buf_export");
+    let (query, export) = egl.export_query.zip(egl.export).ok_or(unsupported)?;
+
+    unsafe {
+        // the other users of the buffer must see the commands that have been issued so far
+        ctxt.gl.Flush();
+
+        let attributes = [EGL_GL_TEXTURE_LEVEL_KHR, 0, EGL_NONE];
+        let image = (egl.create_image)(egl.display, egl.context, EGL_GL_TEXTURE_2D_KHR,
+                                       texture.get_id() as usize as *mut c_void,
+                                       attributes.as_ptr());
+        if image.is_null() {
+            return Err(DmaBufError::EglError((egl.get_error)()));
+        }
+
+        let result = (|| {
+            let mut fourcc = 0;
+            let mut num_planes = 0;
+            if query(egl.display, image, &mut fourcc, &mut num_planes, ptr::null_mut()) == 0 {
+                return Err(DmaBufError::EglError((egl.get_error)()));
+            }
+
+            let num_planes = num_planes.max(0) as usize;
+            let mut modifiers = vec![DRM_FORMAT_MOD_INVALID; num_planes];
+            let mut fds = vec![-1; num_planes];
+            let mut strides = vec![0; num_planes];
+            let mut offsets = vec![0; num_planes];
+            if query(egl.display, image, &mut fourcc, &mut 0, modifiers.as_mut_ptr()) == 0 ||
+               export(egl.display, image, fds.as_mut_ptr(), strides.as_mut_ptr(),
+                      offsets.as_mut_ptr()) == 0
+            {
+                return Err(DmaBufError::EglError((egl.get_error)()));
+            }
+
+            // taking ownership first, so that nothing leaks on error
+            let fds: Vec<_> = fds.into_iter()
+                                 .map(|fd| (fd >= 0).then(|| OwnedFd::from_raw_fd(fd)))
+                                 .collect();
+
+            let mut planes: Vec<DmaBufPlane> = Vec::with_capacity(num_planes);
+            for ((fd, stride), offset) in fds.into_iter().zip(strides).zip(offsets) {
+                // the driver returns -1 for planes that live in the buffer of the previous one
+                let fd = match (fd, planes.last()) {
+                    (Some(fd), _) => fd,
+                    (None, Some(previous)) => previous.fd.try_clone()?,
+                    (None, None) => return Err(DmaBufError::InvalidPlaneCount(0)),
+                };
+                planes.push(DmaBufPlane { fd, offset: offset as u32, stride: stride as u32 });
+            }
+            if planes.is_empty() {
+                return Err(DmaBufError::InvalidPlaneCount(0));
+            }
+
+            Ok(DmaBuf {
+                width: texture.width(),
+                height: texture.height(),
+                fourcc: fourcc as u32,
+                modifier: modifiers.first().copied().filter(|&m| m != DRM_FORMAT_MOD_INVALID),
+                planes,
+            })
+        })();
+
+        (egl.destroy_image)(egl.display, image);
+        result
+    }
+}
+
+/// Returns the texture format with the same channels and sizes as a fourcc format.
+fn texture_format(fourcc: u32) -> Option<UncompressedFloatFormat> {
+    Some(match fourcc {
+        R8 => UncompressedFloatFormat::U8,
+        R16 => UncompressedFloatFormat::U16,
+        GR88 => UncompressedFloatFormat::U8U8,
+        GR1616 => UncompressedFloatFormat::U16U16,
+        XRGB8888 | ARGB8888 | XBGR8888 | ABGR8888 => UncompressedFloatFormat::U8U8U8U8,
+        XRGB2101010 | ARGB2101010 | XBGR2101010 | ABGR2101010 => {
+            UncompressedFloatFormat::U10U10U10U2
+        },
+        ABGR16161616F => UncompressedFloatFormat::F16F16F16F16,
+        _ => return None,
+    })
+}
+
+type EGLDisplay = *mut c_void;
+type EGLContext = *mut c_void;
+type EGLImage = *mut c_void;
+
+const EGL_NONE: i32 = 0x3038;
+const EGL_EXTENSIONS: i32 = 0x3055;
+const EGL_HEIGHT: i32 = 0x3056;
+const EGL_WIDTH: i32 = 0x3057;
+const EGL_GL_TEXTURE_2D_KHR: u32 = 0x30B1;
+const EGL_GL_TEXTURE_LEVEL_KHR: i32 = 0x30BC;
+const EGL_LINUX_DMA_BUF_EXT: u32 = 0x3270;
+const EGL_LINUX_DRM_FOURCC_EXT: i32 = 0x3271;
+
+/// The fd, offset, pitch, modifier low and modifier high attributes of each plane.
+const PLANE_ATTRIBUTES: [[i32; 5]; 4] = [
+    [0x3272, 0x3273, 0x3274, 0x3443, 0x3444],
+    [0x3275, 0x3276, 0x3277, 0x3445, 0x3446],
+    [0x3278, 0x3279, 0x327A, 0x3447, 0x3448],
+    [0x3440, 0x3441, 0x3442, 0x3449, 0x344A],
+];
+
+/// The EGL functions used by this module, and the current display and context.
+struct Egl {
+    display: EGLDisplay,
+    context: EGLContext,
+    extensions: String,
+    get_error: unsafe extern "C" fn() -> i32,
+    create_image: unsafe extern "C" fn(EGLDisplay, EGLContext, u32, *mut c_void, *const i32)
+                                       -> EGLImage,
+    destroy_image: unsafe extern "C" fn(EGLDisplay, EGLImage) -> u32,
+    export_query: Option<unsafe extern "C" fn(EGLDisplay, EGLImage, *mut c_int, *mut c_int,
+                                              *mut u64) -> u32>,
+    export: Option<unsafe extern "C" fn(EGLDisplay, EGLImage, *mut c_int, *mut i32, *mut i32)
+                                        -> u32>,
+}
+
+impl Egl {
+    /// Loads the functions through the backend of the context, which must be current.
+    ///
+    /// The backend of GLX and WGL contexts returns null for functions whose name doesn't start
+    /// with `gl`, which is how they are told apart from EGL.
+    unsafe fn load(context: &Context) -> Result<Egl, DmaBufError> {
+        unsafe fn load<T>(context: &Context, symbol: &str) -> Option<T> {
+            let ptr = context.get_proc_address(symbol);
+            if ptr.is_null() { None } else { Some(mem::transmute_copy(&ptr)) }
+        }
+
+        let get_current_display: unsafe extern "C" fn() -> EGLDisplay =
+            load(context, "eglGetCurrentDisplay").ok_or(DmaBufError::NotEgl)?;
+        let get_current_context: unsafe extern "C" fn() -> EGLContext =
+            load(context, "eglGetCurrentContext").ok_or(DmaBufError::NotEgl)?;
+        let query_string: unsafe extern "C" fn(EGLDisplay, i32) -> *const c_char =
+            load(context, "eglQueryString").ok_or(DmaBufError::NotEgl)?;
+
+        let display = get_current_display();
+        if display.is_null() {
+            return Err(DmaBufError::NotEgl);
+        }
+
+        let extensions = query_string(display, EGL_EXTENSIONS);
+        let extensions = if extensions.is_null() {
+            String::new()
+        } else {
+            std::ffi::CStr::from_ptr(extensions).to_string_lossy().into_owned()
+        };
+
+        Ok(Egl {
+            display,
+            context: get_current_context(),
+            extensions,
+            get_error: load(context, "eglGetError").ok_or(DmaBufError::NotEgl)?,
+            create_image: load(context, "eglCreateImageKHR")
+                .ok_or(DmaBufError::ExtensionNotSupported("EGL_KHR_image_base"))?,
+            destroy_image: load(context, "eglDestroyImageKHR")
+                .ok_or(DmaBufError::ExtensionNotSupported("EGL_KHR_image_base"))?,
+            export_query: load(context, "eglExportDMABUFImageQueryMESA"),
+            export: load(context, "eglExportDMABUFImageMESA"),
+        })
+    }
+
+    /// Returns an error if the display doesn't support an extension.
+    fn require(&self, name: &'static str) -> Result<(), DmaBufError> {
+        if self.extensions.split(' ').any(|e| e == name) {
+            Ok(())
+        } else {
+            Err(DmaBufError::ExtensionNotSupported(name))
+        }
+    }
+}
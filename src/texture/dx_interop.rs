@@ -0,0 +1,317 @@
+/*!
+Sharing Direct3D 11 textures with OpenGL on Windows, through `WGL_NV_DX_interop2`.
+
+A Direct3D device is opened once with `DxDevice::open`, then each `ID3D11Texture2D` that needs
+to be drawn to or sampled from is registered with `register_texture`. This makes it possible to
+draw a glium overlay on top of the back buffer of a Direct3D application, or to use a video frame
+decoded by Media Foundation as a texture.
+
+The two APIs can't use a texture at the same time. OpenGL can only access it while it is locked,
+which is enforced by only giving access to the `Texture2d` through the guard returned by
+`DxTexture::lock`. Dropping the guard unlocks the texture and hands it back to Direct3D.
+
+```no_run
+# fn example(display: &glium::Display<glium::glutin::surface::WindowSurface>,
+#            d3d_device: *mut std::os::raw::c_void, d3d_texture: *mut std::os::raw::c_void) {
+use glium::Surface;
+use glium::texture::UncompressedFloatFormat;
+use glium::texture::dx_interop::{DxAccess, DxDevice};
+
+let device = unsafe { DxDevice::open(display, d3d_device) }.unwrap();
+let mut texture = unsafe {
+    device.register_texture(d3d_texture, UncompressedFloatFormat::U8U8U8U8, 1280, 720,
+                            DxAccess::ReadWrite)
+}.unwrap();
+
+// every frame, after Direct3D is done with the texture
+{
+    let texture = texture.lock().unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    // ... draw the overlay ...
+}
+// Direct3D can use the texture again
+# }
+```
+
+Only the formats that can be rendered to are supported, which excludes the NV12 frames of most
+video decoders. Convert them to `DXGI_FORMAT_B8G8R8A8_UNORM` with an `ID3D11VideoProcessor`
+first. Direct3D stores the rows from top to bottom, so the content of a shared texture appears
+upside down to OpenGL.
+
+*/
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::ops::Deref;
+use std::os::raw::{c_char, c_void};
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::gl;
+use crate::texture::{Dimensions, MipmapsOption, Texture2d, UncompressedFloatFormat};
+use crate::ContextExt;
+
+/// A Direct3D device opened for sharing its resources with OpenGL.
+pub struct DxDevice {
+    device: Rc<Device>,
+}
+
+/// How OpenGL uses a shared texture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DxAccess {
+    /// OpenGL only reads the texture.
+    ReadOnly,
+    /// OpenGL reads and writes the texture.
+    ReadWrite,
+    /// OpenGL overwrites the whole texture without reading it, and its previous content is
+    /// discarded when it is locked.
+    WriteDiscard,
+}
+
+/// A Direct3D texture registered with OpenGL.
+///
+/// Must be dropped before the Direct3D texture is released.
+pub struct DxTexture {
+    device: Rc<Device>,
+    handle: Handle,
+    texture: Texture2d,
+}
+
+/// A locked `DxTexture`, that OpenGL can use. Unlocks the texture when dropped.
+pub struct LockedDxTexture<'a> {
+    texture: &'a mut DxTexture,
+}
+
+/// Error that can happen when sharing a Direct3D texture.
+#[derive(Debug)]
+pub enum DxInteropError {
+    /// The context wasn't created with WGL.
+    NotWgl,
+    /// A WGL extension that is required isn't supported. Contains its name.
+    ExtensionNotSupported(&'static str),
+    /// A WGL function failed. Contains the error returned by `GetLastError`.
+    OsError(io::Error),
+}
+
+impl fmt::Display for DxInteropError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::DxInteropError::*;
+        match *self {
+            NotWgl => write!(fmt, "The OpenGL context wasn't created with WGL"),
+            ExtensionNotSupported(name) => write!(fmt, "{} is not supported", name),
+            OsError(ref err) => write!(fmt, "WGL error: {}", err),
+        }
+    }
+}
+
+impl Error for DxInteropError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            DxInteropError::OsError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DxInteropError {
+    fn from(err: io::Error) -> DxInteropError {
+        DxInteropError::OsError(err)
+    }
+}
+
+impl DxDevice {
+    /// Opens a Direct3D device for sharing.
+    ///
+    /// # Safety
+    ///
+    /// `d3d_device` must be a valid `ID3D11Device` that outlives the returned object and the
+    /// textures registered with it.
+    pub unsafe fn open<F: Facade + ?Sized>(facade: &F, d3d_device: *mut c_void)
+                                           -> Result<DxDevice, DxInteropError>
+    {
+        let context = facade.get_context();
+        let ctxt = context.make_current();
+        let wgl = Wgl::load(context)?;
+        let handle = (wgl.open_device)(d3d_device);
+        drop(ctxt);
+
+        if handle.is_null() {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        Ok(DxDevice {
+            device: Rc::new(Device { context: context.clone(), handle, wgl }),
+        })
+    }
+
+    /// Registers a Direct3D texture, so that it can be used as a `Texture2d`.
+    ///
+    /// `format`, `width` and `height` must match the description of the texture, as glium
+    /// can't query them.
+    ///
+    /// # Safety
+    ///
+    /// `resource` must be a valid `ID3D11Texture2D` of the device, that outlives the returned
+    /// object.
+    pub unsafe fn register_texture(&self, resource: *mut c_void,
+                                   format: UncompressedFloatFormat, width: u32, height: u32,
+                                   access: DxAccess) -> Result<DxTexture, DxInteropError>
+    {
+        let access = match access {
+            DxAccess::ReadOnly => WGL_ACCESS_READ_ONLY_NV,
+            DxAccess::ReadWrite => WGL_ACCESS_READ_WRITE_NV,
+            DxAccess::WriteDiscard => WGL_ACCESS_WRITE_DISCARD_NV,
+        };
+
+        let ctxt = self.device.context.make_current();
+        let mut id = 0;
+        ctxt.gl.GenTextures(1, &mut id);
+        let handle = (self.device.wgl.register_object)(self.device.handle, resource, id,
+                                                      gl::TEXTURE_2D, access);
+        if handle.is_null() {
+            let err = io::Error::last_os_error();
+            ctxt.gl.DeleteTextures(1, &id);
+            return Err(err.into());
+        }
+        drop(ctxt);
+
+        let ty = Dimensions::Texture2d { width, height };
+        let texture = Texture2d::from_id(&self.device.context, format, id, true,
+                                         MipmapsOption::NoMipmap, ty);
+
+        Ok(DxTexture { device: self.device.clone(), handle, texture })
+    }
+}
+
+impl fmt::Debug for DxDevice {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "DxDevice({:?})", self.device.handle)
+    }
+}
+
+impl DxTexture {
+    /// Locks the texture so that OpenGL can use it. Direct3D must not use it until the returned
+    /// object is dropped.
+    pub fn lock(&mut self) -> Result<LockedDxTexture<'_>, DxInteropError> {
+        let ctxt = self.device.context.make_current();
+        let result = unsafe {
+            (self.device.wgl.lock_objects)(self.device.handle, 1, &mut self.handle)
+        };
+        drop(ctxt);
+
+        if result == 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        Ok(LockedDxTexture { texture: self })
+    }
+}
+
+impl fmt::Debug for DxTexture {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "DxTexture({:?}, {:?})", self.handle, self.texture)
+    }
+}
+
+impl Drop for DxTexture {
+    fn drop(&mut self) {
+        // the OpenGL texture is deleted afterwards, when the field is dropped
+        let _ctxt = self.device.context.make_current();
+        unsafe { (self.device.wgl.unregister_object)(self.device.handle, self.handle) };
+    }
+}
+
+impl<'a> Deref for LockedDxTexture<'a> {
+    type Target = Texture2d;
+
+    #[inline]
+    fn deref(&self) -> &Texture2d {
+        &self.texture.texture
+    }
+}
+
+impl<'a> fmt::Debug for LockedDxTexture<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "LockedDxTexture({:?})", self.texture)
+    }
+}
+
+impl<'a> Drop for LockedDxTexture<'a> {
+    fn drop(&mut self) {
+        let texture = &mut *self.texture;
+        let device = &texture.device;
+        let _ctxt = device.context.make_current();
+        unsafe { (device.wgl.unlock_objects)(device.handle, 1, &mut texture.handle) };
+    }
+}
+
+type Handle = *mut c_void;
+
+const WGL_ACCESS_READ_ONLY_NV: gl::types::GLenum = 0x0000;
+const WGL_ACCESS_READ_WRITE_NV: gl::types::GLenum = 0x0001;
+const WGL_ACCESS_WRITE_DISCARD_NV: gl::types::GLenum = 0x0002;
+
+/// An opened Direct3D device, shared between the `DxDevice` and its textures.
+struct Device {
+    context: Rc<Context>,
+    handle: Handle,
+    wgl: Wgl,
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        let _ctxt = self.context.make_current();
+        unsafe { (self.wgl.close_device)(self.handle) };
+    }
+}
+
+/// The WGL functions used by this module.
+struct Wgl {
+    open_device: unsafe extern "system" fn(*mut c_void) -> Handle,
+    close_device: unsafe extern "system" fn(Handle) -> i32,
+    register_object: unsafe extern "system" fn(Handle, *mut c_void, gl::types::GLuint,
+                                               gl::types::GLenum, gl::types::GLenum) -> Handle,
+    unregister_object: unsafe extern "system" fn(Handle, Handle) -> i32,
+    lock_objects: unsafe extern "system" fn(Handle, gl::types::GLint, *mut Handle) -> i32,
+    unlock_objects: unsafe extern "system" fn(Handle, gl::types::GLint, *mut Handle) -> i32,
+}
+
+impl Wgl {
+    /// Loads the functions through the backend of the context, which must be current.
+    ///
+    /// The backend of WGL contexts falls back to the exports of `opengl32.dll`, which is where
+    /// `wglGetCurrentDC` comes from. Other backends return null for it.
+    unsafe fn load(context: &Context) -> Result<Wgl, DxInteropError> {
+        unsafe fn load<T>(context: &Context, symbol: &str) -> Option<T> {
+            let ptr = context.get_proc_address(symbol);
+            if ptr.is_null() { None } else { Some(mem::transmute_copy(&ptr)) }
+        }
+
+        let get_current_dc: unsafe extern "system" fn() -> *mut c_void =
+            load(context, "wglGetCurrentDC").ok_or(DxInteropError::NotWgl)?;
+        let get_extensions: unsafe extern "system" fn(*mut c_void) -> *const c_char =
+            load(context, "wglGetExtensionsStringARB")
+                .ok_or(DxInteropError::ExtensionNotSupported("WGL_ARB_extensions_string"))?;
+
+        let extensions = get_extensions(get_current_dc());
+        let supported = !extensions.is_null() &&
+            CStr::from_ptr(extensions).to_string_lossy().split(' ')
+                                      .any(|e| e == "WGL_NV_DX_interop2");
+        let unsupported = || DxInteropError::ExtensionNotSupported("WGL_NV_DX_interop2");
+        if !supported {
+            return Err(unsupported());
+        }
+
+        Ok(Wgl {
+            open_device: load(context, "wglDXOpenDeviceNV").ok_or_else(unsupported)?,
+            close_device: load(context, "wglDXCloseDeviceNV").ok_or_else(unsupported)?,
+            register_object: load(context, "wglDXRegisterObjectNV").ok_or_else(unsupported)?,
+            unregister_object: load(context, "wglDXUnregisterObjectNV").ok_or_else(unsupported)?,
+            lock_objects: load(context, "wglDXLockObjectsNV").ok_or_else(unsupported)?,
+            unlock_objects: load(context, "wglDXUnlockObjectsNV").ok_or_else(unsupported)?,
+        })
+    }
+}
@@ -0,0 +1,237 @@
+/*!
+External textures, whose content is owned by another API and sampled with `samplerExternalOES`.
+
+Android camera frames, `SurfaceTexture`s and hardware video decoders produce images in formats
+that OpenGL can't describe, usually multi-planar YUV with a driver-specific layout. The
+`GL_OES_EGL_image_external` extension binds them to a special texture target. Sampling converts
+them to RGB, but they can't be rendered to, and mipmaps and repeating wrap modes aren't
+available.
+
+```no_run
+# fn example(display: &glium::Display<glium::glutin::surface::WindowSurface>,
+#            image: *const std::os::raw::c_void) {
+use glium::texture::external::ExternalTexture;
+
+let texture = unsafe { ExternalTexture::from_egl_image(display, image) }.unwrap();
+
+let fragment_shader = "
+    #version 100
+    #extension GL_OES_EGL_image_external : require
+    precision mediump float;
+
+    uniform samplerExternalOES frame;
+    varying vec2 v_tex_coords;
+
+    void main() {
+        gl_FragColor = texture2D(frame, v_tex_coords);
+    }
+";
+
+let uniforms = glium::uniform! { frame: &texture };
+# }
+```
+
+*/
+use std::fmt;
+use std::os::raw::c_void;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::{CommandContext, Context, GarbageObject, SharedObject};
+use crate::fbo;
+use crate::gl;
+use crate::uniforms::{AsUniformValue, MagnifySamplerFilter, MinifySamplerFilter, Sampler};
+use crate::uniforms::{SamplerBehavior, SamplerWrapFunction, UniformValue};
+use crate::CapabilitiesSource;
+use crate::ContextExt;
+use crate::GlObject;
+use crate::TextureExt;
+
+use super::any::{delete_texture, forget_texture};
+
+/// A texture bound to `GL_TEXTURE_EXTERNAL_OES`.
+pub struct ExternalTexture {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    owned: bool,
+}
+
+/// External textures are not supported.
+#[derive(Debug, Copy, Clone)]
+pub struct ExternalTexturesNotSupportedError;
+
+impl fmt::Display for ExternalTexturesNotSupportedError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("GL_OES_EGL_image_external is not supported")
+    }
+}
+
+impl std::error::Error for ExternalTexturesNotSupportedError {}
+
+/// Returns true if external textures are supported by the implementation.
+#[inline]
+pub fn is_supported<C: CapabilitiesSource + ?Sized>(ctxt: &C) -> bool {
+    ctxt.get_extensions().gl_oes_egl_image_external
+}
+
+impl ExternalTexture {
+    /// Builds an external texture whose content is an `EGLImage`.
+    ///
+    /// The image can be destroyed afterwards, the texture keeps a reference to its content.
+    ///
+    /// # Safety
+    ///
+    /// `image` must be a valid `EGLImage` of the display of the context.
+    pub unsafe fn from_egl_image<F: Facade + ?Sized>(facade: &F, image: *const c_void)
+                                                     -> Result<ExternalTexture,
+                                                               ExternalTexturesNotSupportedError>
+    {
+        let mut ctxt = facade.get_context().make_current();
+        if !is_supported(&ctxt) {
+            return Err(ExternalTexturesNotSupportedError);
+        }
+
+        let mut id = 0;
+        ctxt.gl.GenTextures(1, &mut id);
+        let texture = ExternalTexture { context: facade.get_context().clone(), id, owned: true };
+        texture.bind_to_current(&mut ctxt);
+        ctxt.gl.EGLImageTargetTexture2DOES(gl::TEXTURE_EXTERNAL_OES, image as *const _);
+
+        Ok(texture)
+    }
+
+    /// Builds an external texture from an existing texture name, for example the one given to
+    /// an Android `SurfaceTexture`. If `owned` is true, the texture is deleted when this object
+    /// is dropped.
+    ///
+    /// # Safety
+    ///
+    /// `id` must be a texture of the context, that is bound to `GL_TEXTURE_EXTERNAL_OES` or has
+    /// never been bound. If it isn't owned, it must not be deleted before this object.
+    pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F, id: gl::types::GLuint, owned: bool)
+                                              -> ExternalTexture
+    {
+        ExternalTexture { context: facade.get_context().clone(), id, owned }
+    }
+
+    /// Builds a `Sampler` marker object that allows you to indicate how the texture should be
+    /// sampled from inside a shader. Only the filters without mipmaps and the `Clamp` wrap
+    /// function are supported, the other ones are replaced with these when drawing.
+    ///
+    /// The sampler initially uses the `Clamp` wrap function and `Linear` filters.
+    #[inline]
+    pub fn sampled(&self) -> Sampler<'_, ExternalTexture> {
+        Sampler(self, SamplerBehavior {
+            wrap_function: (SamplerWrapFunction::Clamp, SamplerWrapFunction::Clamp,
+                            SamplerWrapFunction::Clamp),
+            minify_filter: MinifySamplerFilter::Linear,
+            magnify_filter: MagnifySamplerFilter::Linear,
+            .. Default::default()
+        })
+    }
+
+    /// Returns the context this texture belongs to.
+    #[inline]
+    pub fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+/// Replaces the mipmap filters and the wrap functions that external textures don't support
+/// with the `Clamp` wrap function and the matching filter without mipmaps.
+pub(crate) fn supported_behavior(behavior: SamplerBehavior) -> SamplerBehavior {
+    let minify_filter = match behavior.minify_filter {
+        MinifySamplerFilter::Nearest | MinifySamplerFilter::NearestMipmapNearest |
+        MinifySamplerFilter::NearestMipmapLinear => MinifySamplerFilter::Nearest,
+        MinifySamplerFilter::Linear | MinifySamplerFilter::LinearMipmapNearest |
+        MinifySamplerFilter::LinearMipmapLinear => MinifySamplerFilter::Linear,
+    };
+
+    SamplerBehavior {
+        wrap_function: (SamplerWrapFunction::Clamp, SamplerWrapFunction::Clamp,
+                        SamplerWrapFunction::Clamp),
+        minify_filter,
+        .. behavior
+    }
+}
+
+impl fmt::Debug for ExternalTexture {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "External texture #{}", self.id)
+    }
+}
+
+impl GlObject for ExternalTexture {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl TextureExt for ExternalTexture {
+    #[inline]
+    fn get_texture_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+
+    #[inline]
+    fn get_bind_point(&self) -> gl::types::GLenum {
+        gl::TEXTURE_EXTERNAL_OES
+    }
+
+    fn bind_to_current(&self, ctxt: &mut CommandContext<'_>) -> gl::types::GLenum {
+        let texture_unit = ctxt.state.active_texture;
+        if ctxt.state.texture_units[texture_unit as usize].texture != self.id {
+            unsafe { ctxt.gl.BindTexture(gl::TEXTURE_EXTERNAL_OES, self.id) };
+            ctxt.state.texture_units[texture_unit as usize].texture = self.id;
+            ctxt.state.audit("glBindTexture",
+                             (gl::TEXTURE_EXTERNAL_OES as u64) << 32 | texture_unit as u64,
+                             self.id as u64);
+        }
+
+        gl::TEXTURE_EXTERNAL_OES
+    }
+
+    fn prepare_for_access(&self, _: &mut CommandContext<'_>, _: crate::TextureAccess) {
+        // external textures can't be written by shaders
+    }
+}
+
+impl Drop for ExternalTexture {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        // external textures can be attached to framebuffers on some implementations
+        fbo::FramebuffersContainer::purge_texture(&mut ctxt, self.id);
+
+        if !self.owned {
+            forget_texture(&mut ctxt, self.id);
+        } else if !ctxt.garbage.defer(GarbageObject::Texture(self.id)) {
+            unsafe { delete_texture(&mut ctxt, self.id) };
+        }
+
+        drop(ctxt);
+        self.context.forget_in_shared_contexts(SharedObject::Texture(self.id));
+    }
+}
+
+impl AsUniformValue for &ExternalTexture {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::ExternalTexture(self, None)
+    }
+}
+
+impl<'a> AsUniformValue for Sampler<'a, ExternalTexture> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::ExternalTexture(self.0, Some(self.1))
+    }
+}
@@ -124,6 +124,7 @@ pub use crate::image_format::{CompressedFormat, DepthFormat, DepthStencilFormat,
 pub use crate::image_format::{CompressedSrgbFormat, SrgbFormat};
 pub use self::any::{TextureAny, TextureAnyMipmap, TextureAnyLayer, TextureAnyLayerMipmap};
 pub use self::any::{TextureAnyImage, Dimensions};
+pub(crate) use self::any::{delete_texture, forget_texture};
 pub use self::bindless::{ResidentTexture, TextureHandle, BindlessTexturesNotSupportedError};
 pub use self::get_format::{InternalFormat, InternalFormatType, GetFormatError};
 pub use self::pixel::PixelValue;
@@ -138,6 +139,11 @@ pub use self::texture_import::TextureImportError;
 
 pub mod bindless;
 pub mod buffer_texture;
+#[cfg(target_os = "linux")]
+pub mod dmabuf;
+#[cfg(all(windows, feature = "dx_interop"))]
+pub mod dx_interop;
+pub mod external;
 pub mod pixel_buffer;
 
 mod any;
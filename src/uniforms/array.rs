@@ -0,0 +1,29 @@
+use crate::uniforms::{AsUniformValues, UniformValue};
+
+/// Array of uniforms, built from a slice.
+///
+/// Each element is bound to the uniform named `name[i]`, where `name` is the name of the array
+/// and `i` the index of the element. If the elements are structs, their fields are bound to
+/// `name[i].field`. Elements that don't exist in the program are ignored, like any other uniform.
+///
+/// The names are built every time the uniforms are bound, so for large arrays you should prefer
+/// a `UniformBuffer`.
+#[derive(Debug)]
+pub struct UniformArray<'a, T: 'a>(pub &'a [T]);
+
+impl<'a, T> Clone for UniformArray<'a, T> {
+    #[inline]
+    fn clone(&self) -> UniformArray<'a, T> {
+        *self
+    }
+}
+
+impl<'a, T> Copy for UniformArray<'a, T> {}
+
+impl<'b, T> AsUniformValues for UniformArray<'b, T> where T: AsUniformValues {
+    fn visit_uniform_values<'a>(&'a self, name: &str, output: &mut dyn FnMut(&str, UniformValue<'a>)) {
+        for (index, element) in self.0.iter().enumerate() {
+            element.visit_uniform_values(&format!("{}[{}]", name, index), output);
+        }
+    }
+}
@@ -17,15 +17,17 @@ use crate::ProgramExt;
 use crate::UniformsExt;
 use crate::RawUniformValue;
 use crate::TextureExt;
+use crate::GlObject;
 
 use crate::uniforms::Uniforms;
 use crate::uniforms::UniformValue;
 use crate::uniforms::SamplerBehavior;
 use crate::uniforms::ImageUnitBehavior;
+use crate::uniforms::{BlockBinding, BlockBindingKind};
 
 
 use crate::context::CommandContext;
-use crate::buffer::Inserter;
+use crate::buffer::{BufferAnySlice, Inserter};
 
 use crate::utils::bitsfield::Bitsfield;
 
@@ -36,7 +38,7 @@ use crate::version::Api;
 
 impl<U> UniformsExt for U where U: Uniforms {
     fn bind_uniforms<'a, P>(&'a self, mut ctxt: &mut CommandContext<'_>, program: &P,
-                            fences: &mut Vec<Inserter<'a>>)
+                            fences: &mut Vec<Inserter<'a>>, accesses: &mut TextureAccesses)
                             -> Result<(), DrawError>
                             where P: ProgramExt
     {
@@ -52,6 +54,21 @@ impl<U> UniformsExt for U where U: Uniforms {
             = HashMap::with_hasher(Default::default());
 
         let mut visiting_result = Ok(());
+
+        // Buffers with an explicit binding point are bound first, so that the binding points
+        // that are picked automatically for the named blocks don't overlap with them.
+        self.visit_block_bindings(|binding| {
+            if visiting_result.is_err() { return; }
+
+            match bind_explicit_block(&mut ctxt, &binding, &mut uniform_buffer_bind_points,
+                                      &mut shared_storage_buffer_bind_points)
+            {
+                Ok(Some(fence)) => fences.push(fence),
+                Ok(None) => (),
+                Err(e) => visiting_result = Err(e),
+            }
+        });
+
         self.visit_values(|name, value| {
             if visiting_result.is_err() { return; }
 
@@ -68,7 +85,8 @@ impl<U> UniformsExt for U where U: Uniforms {
                 }
 
                 match bind_uniform(&mut ctxt, &value, program, uniform.location,
-                                   &mut texture_bind_points, &mut image_unit_bind_points, name)
+                                   &mut texture_bind_points, &mut image_unit_bind_points,
+                                   accesses, name, None)
                 {
                     Ok(_) => (),
                     Err(e) => {
@@ -144,6 +162,59 @@ impl<U> UniformsExt for U where U: Uniforms {
     }
 }
 
+/// Keeps track of the textures that are sampled and of the textures that are written through
+/// an image unit by a command, in order to detect hazards.
+pub struct TextureAccesses {
+    enabled: bool,
+    sampled: Vec<(gl::types::GLuint, String)>,
+    written: Vec<(gl::types::GLuint, String)>,
+}
+
+impl TextureAccesses {
+    /// Builds a new tracker. Nothing is recorded if `enabled` is false.
+    #[inline]
+    pub fn new(enabled: bool) -> TextureAccesses {
+        TextureAccesses {
+            enabled,
+            sampled: Vec::new(),
+            written: Vec::new(),
+        }
+    }
+
+    #[inline]
+    fn record(&mut self, texture: gl::types::GLuint, written: bool, name: &str) {
+        if !self.enabled {
+            return;
+        }
+
+        if written {
+            self.written.push((texture, name.to_owned()));
+        } else {
+            self.sampled.push((texture, name.to_owned()));
+        }
+    }
+
+    /// Returns the ids and uniform names of the textures that are sampled.
+    #[inline]
+    pub fn get_sampled(&self) -> &[(gl::types::GLuint, String)] {
+        &self.sampled
+    }
+
+    /// Returns an error if a texture is both sampled and written through an image unit.
+    pub fn check_images(&self) -> Result<(), DrawError> {
+        for (texture, image) in self.written.iter() {
+            if let Some((_, sampler)) = self.sampled.iter().find(|(t, _)| t == texture) {
+                return Err(DrawError::ImageWrittenWhileSampled {
+                    image: image.clone(),
+                    sampler: sampler.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
 fn bind_subroutine_uniforms<P>(ctxt: &mut context::CommandContext<'_>, program: &P,
                             subroutine_bindings: &HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, BuildHasherDefault<FnvHasher>>)
                             -> Result<(), DrawError>
@@ -203,10 +274,11 @@ fn bind_uniform_block<'a, P>(ctxt: &mut context::CommandContext<'_>, value: &Uni
                 }
             }
 
+            check_uniform_buffer_offset(ctxt, buffer)?;
+
             let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
             buffer_bind_points.set_used(bind_point);
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
             let fence = buffer.add_fence();
             let block_id = block.id as gl::types::GLuint;
 
@@ -257,6 +329,44 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext<'_>, valu
     }
 }
 
+fn bind_explicit_block<'a>(ctxt: &mut context::CommandContext<'_>, binding: &BlockBinding<'a>,
+                           uniform_buffer_bind_points: &mut Bitsfield,
+                           shared_storage_buffer_bind_points: &mut Bitsfield)
+                           -> Result<Option<Inserter<'a>>, DrawError>
+{
+    let (max, bind_points) = match binding.kind {
+        BlockBindingKind::Uniform => {
+            (ctxt.capabilities.max_indexed_uniform_buffer, uniform_buffer_bind_points)
+        },
+        BlockBindingKind::ShaderStorage => {
+            (ctxt.capabilities.max_indexed_shader_storage_buffer, shared_storage_buffer_bind_points)
+        },
+    };
+
+    if binding.binding >= max.max(0) as u32 || binding.binding >= 256 {
+        return Err(DrawError::BlockBindingOutOfRange { binding: binding.binding });
+    }
+
+    if let BlockBindingKind::Uniform = binding.kind {
+        check_uniform_buffer_offset(ctxt, &binding.buffer)?;
+    }
+
+    bind_points.set_used(binding.binding as u16);
+
+    let buffer = binding.buffer;
+    let fence = buffer.add_fence();
+
+    match binding.kind {
+        BlockBindingKind::Uniform => {
+            buffer.prepare_and_bind_for_uniform(ctxt, binding.binding as gl::types::GLuint)
+        },
+        BlockBindingKind::ShaderStorage => {
+            buffer.prepare_and_bind_for_shared_storage(ctxt, binding.binding as gl::types::GLuint)
+        },
+    }
+
+    Ok(fence)
+}
 
 fn bind_atomic_counter<'a, P>(ctxt: &mut context::CommandContext<'_>, value: &UniformValue<'a>,
                               block: &program::UniformBlock,
@@ -293,7 +403,8 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
                    value: &UniformValue, program: &P, location: gl::types::GLint,
                    texture_bind_points: &mut Bitsfield,
                    image_unit_bind_points: &mut Bitsfield,
-                   name: &str)
+                   accesses: &mut TextureAccesses,
+                   name: &str, sampler_object: Option<gl::types::GLuint>)
                    -> Result<(), DrawError> where P: ProgramExt
 {
     // The spec only states that a negative location is an error.
@@ -316,6 +427,10 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
                 name: name.to_owned(),
             })
         },
+        UniformValue::TextureWithSampler(texture, sampler) => {
+            bind_uniform(ctxt, texture, program, location, texture_bind_points,
+                         image_unit_bind_points, accesses, name, Some(sampler.get_id()))
+        },
         UniformValue::Bool(val) => {
             // Booleans get passed as integers.
             program.set_uniform(ctxt, location, &RawUniformValue::SignedInt(val as i32));
@@ -345,6 +460,42 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             program.set_uniform(ctxt, location, &RawUniformValue::Mat4(val));
             Ok(())
         },
+        UniformValue::Mat2Transposed(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat2Transposed(val));
+            Ok(())
+        },
+        UniformValue::Mat3Transposed(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat3Transposed(val));
+            Ok(())
+        },
+        UniformValue::Mat4Transposed(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat4Transposed(val));
+            Ok(())
+        },
+        UniformValue::Mat2x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat2x3(val));
+            Ok(())
+        },
+        UniformValue::Mat2x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat2x4(val));
+            Ok(())
+        },
+        UniformValue::Mat3x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat3x2(val));
+            Ok(())
+        },
+        UniformValue::Mat3x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat3x4(val));
+            Ok(())
+        },
+        UniformValue::Mat4x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat4x2(val));
+            Ok(())
+        },
+        UniformValue::Mat4x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat4x3(val));
+            Ok(())
+        },
         UniformValue::Vec2(val) => {
             program.set_uniform(ctxt, location, &RawUniformValue::Vec2(val));
             Ok(())
@@ -412,6 +563,30 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat4(val));
             Ok(())
         },
+        UniformValue::DoubleMat2x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat2x3(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat2x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat2x4(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat3x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat3x2(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat3x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat3x4(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat4x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat4x2(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat4x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat4x3(val));
+            Ok(())
+        },
         UniformValue::DoubleVec2(val) => {
             program.set_uniform(ctxt, location, &RawUniformValue::DoubleVec2(val));
             Ok(())
@@ -457,269 +632,280 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             Ok(())
         },
         UniformValue::Texture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedSrgbTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::Texture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedSrgbTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::Texture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::Texture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedSrgbTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::Texture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedSrgbTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::Texture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedSrgbTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::Texture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::Cubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedSrgbCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::SrgbCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::CompressedSrgbCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::IntegralCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::UnsignedCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::DepthCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::BufferTexture(texture) => {
-            bind_texture_uniform(ctxt, &texture, None, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &texture, None, None, location, program, texture_bind_points, accesses, name)
+        },
+        UniformValue::ExternalTexture(texture, sampler) => {
+            let sampler = sampler.map(crate::texture::external::supported_behavior);
+            bind_texture_uniform(ctxt, texture, sampler, sampler_object, location, program, texture_bind_points, accesses, name)
         },
         UniformValue::Image1d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::IntegralImage1d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::UnsignedImage1d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::Image2d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::IntegralImage2d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::UnsignedImage2d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::Image3d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::IntegralImage3d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::UnsignedImage3d(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::Image1dArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::IntegralImage1dArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::UnsignedImage1dArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::Image2dArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::IntegralImage2dArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::UnsignedImage2dArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::ImageCube(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::IntegralImageCube(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::UnsignedImageCube(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::ImageCubeArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::IntegralImageCubeArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
         UniformValue::UnsignedImageCubeArray(texture, unit) => {
-            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
+            bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points, accesses, name)
         },
     }
 }
 
 fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
                               texture: &T, sampler: Option<SamplerBehavior>,
+                              sampler_object: Option<gl::types::GLuint>,
                               location: gl::types::GLint, program: &P,
-                              texture_bind_points: &mut Bitsfield)
+                              texture_bind_points: &mut Bitsfield,
+                              accesses: &mut TextureAccesses, name: &str)
                               -> Result<(), DrawError> where P: ProgramExt, T: TextureExt
 {
+    accesses.record(texture.get_texture_id(), false, name);
     texture.prepare_for_access(ctxt, crate::TextureAccess::TextureFetch);
     
-    let sampler = if let Some(sampler) = sampler {
-        Some(crate::sampler_object::get_sampler(ctxt, &sampler)?)
+    // a `SamplerObject` takes precedence over the behavior of the texture
+    let sampler = if let Some(sampler_object) = sampler_object {
+        sampler_object
+    } else if let Some(sampler) = sampler {
+        crate::sampler_object::get_sampler(ctxt, &sampler)?
     } else {
-        None
+        0
     };
 
-    let sampler = sampler.unwrap_or(0);
-
-    // finding an appropriate texture unit
-    let texture_unit =
+    // the unit is either fixed by the program or searched for
+    let fixed_texture_unit = program.get_fixed_texture_unit(location);
+    let texture_unit = if let Some(unit) = fixed_texture_unit {
+        unit
+    } else {
         ctxt.state.texture_units
             .iter().enumerate()
             .find(|&(unit, content)| {
@@ -738,14 +924,17 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
             })
             .unwrap_or_else(|| {
                 texture_bind_points.get_unused().expect("Not enough texture units available")
-            });
+            })
+    };
     assert!((texture_unit as gl::types::GLint) <
             ctxt.capabilities.max_combined_texture_image_units);
     texture_bind_points.set_used(texture_unit);
 
-    // updating the program to use the right unit
-    program.set_uniform(ctxt, location,
-                        &RawUniformValue::SignedInt(texture_unit as gl::types::GLint));
+    // updating the program to use the right unit, fixed units were set at creation
+    if fixed_texture_unit.is_none() {
+        program.set_uniform(ctxt, location,
+                            &RawUniformValue::SignedInt(texture_unit as gl::types::GLint));
+    }
 
     // updating the state of the texture unit
     if ctxt.state.texture_units.len() <= texture_unit as usize {
@@ -762,6 +951,7 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
         if ctxt.state.active_texture != texture_unit as gl::types::GLenum {
             unsafe { ctxt.gl.ActiveTexture(texture_unit as gl::types::GLenum + gl::TEXTURE0) };
             ctxt.state.active_texture = texture_unit as gl::types::GLenum;
+            ctxt.state.audit("glActiveTexture", 0, texture_unit as u64);
         }
 
         texture.bind_to_current(ctxt);
@@ -773,6 +963,7 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
 
             unsafe { ctxt.gl.BindSampler(texture_unit as gl::types::GLenum, sampler); }
             ctxt.state.texture_units[texture_unit as usize].sampler = sampler;
+            ctxt.state.audit("glBindSampler", texture_unit as u64, sampler as u64);
         }
     }
 
@@ -783,7 +974,8 @@ fn bind_image_uniform<P, T>(
     ctxt: &mut context::CommandContext,
     texture: &T, unit_behavior: Option<ImageUnitBehavior>,
     location: gl::types::GLint, program: &P,
-    image_unit_bind_points: &mut Bitsfield
+    image_unit_bind_points: &mut Bitsfield,
+    accesses: &mut TextureAccesses, name: &str
 ) -> Result<(), DrawError>
   where P: ProgramExt, T: TextureExt
 {
@@ -809,6 +1001,7 @@ fn bind_image_uniform<P, T>(
     let will_write =
         unit_behavior.access.eq(&ImageUnitAccess::Write) | unit_behavior.access.eq(&ImageUnitAccess::ReadWrite);
     
+    accesses.record(texture.get_texture_id(), will_write, name);
     texture.prepare_for_access(ctxt, crate::TextureAccess::ImageUnit { will_write });
 
     unsafe {
@@ -825,3 +1018,18 @@ fn bind_image_uniform<P, T>(
 
     Ok(())
 }
+
+/// Checks that a slice of a buffer starts at an offset that can be passed to
+/// `glBindBufferRange` with `GL_UNIFORM_BUFFER`.
+fn check_uniform_buffer_offset(ctxt: &context::CommandContext<'_>, buffer: &BufferAnySlice<'_>)
+                               -> Result<(), DrawError>
+{
+    let offset = buffer.get_offset_bytes();
+    let alignment = ctxt.capabilities.uniform_buffer_offset_alignment.max(1) as usize;
+
+    if offset % alignment != 0 {
+        return Err(DrawError::UniformBufferOffsetNotAligned { offset, alignment });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+/*!
+Alignment and size of the types that can be stored in a uniform or shader storage block.
+
+These values follow the rules of the `std140` and `std430` layouts described in the OpenGL
+specification. They are used by `#[derive(UniformBlock)]` to check at compile time that the
+fields of a struct are at the offsets that OpenGL expects.
+
+*/
+
+/// Type that can be a member of a block with the `std140` or `std430` layout.
+///
+/// The alignments and sizes are in bytes. The size of an array or a struct includes the padding
+/// at its end.
+pub trait BlockMember {
+    /// Base alignment of the type in the `std140` layout.
+    const STD140_ALIGN: usize;
+    /// Size of the type in the `std140` layout.
+    const STD140_SIZE: usize;
+    /// Base alignment of the type in the `std430` layout.
+    const STD430_ALIGN: usize;
+    /// Size of the type in the `std430` layout.
+    const STD430_SIZE: usize;
+
+    /// True for the scalar types, whose arrays of 2 to 4 elements are vectors.
+    #[doc(hidden)]
+    const __SCALAR: bool = false;
+}
+
+/// Rounds `offset` up to the next multiple of `align`.
+#[doc(hidden)]
+#[inline]
+pub const fn __align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) / align * align
+}
+
+/// Returns the largest of two values.
+#[doc(hidden)]
+#[inline]
+pub const fn __max(a: usize, b: usize) -> usize {
+    if a > b { a } else { b }
+}
+
+macro_rules! impl_block_member_scalar {
+    ($ty:ty, $size:expr) => (
+        impl BlockMember for $ty {
+            const STD140_ALIGN: usize = $size;
+            const STD140_SIZE: usize = $size;
+            const STD430_ALIGN: usize = $size;
+            const STD430_SIZE: usize = $size;
+            const __SCALAR: bool = true;
+        }
+    );
+}
+
+impl_block_member_scalar!(i32, 4);
+impl_block_member_scalar!(u32, 4);
+impl_block_member_scalar!(f32, 4);
+impl_block_member_scalar!(f64, 8);
+impl_block_member_scalar!(i64, 8);
+impl_block_member_scalar!(u64, 8);
+
+/// Returns true if `[T; len]` is a vector.
+const fn is_vector<T>(len: usize) -> bool where T: BlockMember {
+    T::__SCALAR && matches!(len, 2..=4)
+}
+
+/// Returns the base alignment of a vector of `len` elements of `size` bytes. A `vec3` is
+/// aligned like a `vec4`, but the next member can be put right after it.
+const fn vector_align(size: usize, len: usize) -> usize {
+    if len == 3 { 4 * size } else { len * size }
+}
+
+// In `std140` the stride of an array is rounded up to a multiple of 16 bytes. Matrices are
+// stored like arrays of column vectors, so they follow the same rules.
+impl<T, const N: usize> BlockMember for [T; N] where T: BlockMember {
+    const STD140_ALIGN: usize = if is_vector::<T>(N) {
+        vector_align(T::STD140_SIZE, N)
+    } else {
+        __max(T::STD140_ALIGN, 16)
+    };
+
+    const STD140_SIZE: usize = if is_vector::<T>(N) {
+        N * T::STD140_SIZE
+    } else {
+        N * __align_up(T::STD140_SIZE, Self::STD140_ALIGN)
+    };
+
+    const STD430_ALIGN: usize = if is_vector::<T>(N) {
+        vector_align(T::STD430_SIZE, N)
+    } else {
+        T::STD430_ALIGN
+    };
+
+    const STD430_SIZE: usize = if is_vector::<T>(N) {
+        N * T::STD430_SIZE
+    } else {
+        N * __align_up(T::STD430_SIZE, T::STD430_ALIGN)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockMember;
+
+    #[test]
+    fn vec3_alignment() {
+        assert_eq!(<[f32; 3] as BlockMember>::STD140_ALIGN, 16);
+        assert_eq!(<[f32; 3] as BlockMember>::STD140_SIZE, 12);
+    }
+
+    #[test]
+    fn array_stride() {
+        assert_eq!(<[f32; 8] as BlockMember>::STD140_SIZE, 8 * 16);
+        assert_eq!(<[f32; 8] as BlockMember>::STD430_SIZE, 8 * 4);
+        assert_eq!(<[[f32; 3]; 6] as BlockMember>::STD430_SIZE, 6 * 16);
+    }
+
+    #[test]
+    fn matrix_columns() {
+        assert_eq!(<[[f32; 2]; 2] as BlockMember>::STD140_SIZE, 32);
+        assert_eq!(<[[f32; 2]; 2] as BlockMember>::STD430_SIZE, 16);
+        assert_eq!(<[[f32; 3]; 3] as BlockMember>::STD430_SIZE, 48);
+    }
+
+    #[test]
+    fn non_square_matrix() {
+        assert_eq!(<[[f32; 2]; 3] as BlockMember>::STD140_SIZE, 48);
+        assert_eq!(<[[f32; 2]; 3] as BlockMember>::STD430_SIZE, 24);
+        assert_eq!(<[[f32; 4]; 2] as BlockMember>::STD430_SIZE, 32);
+    }
+
+    #[test]
+    fn long_array() {
+        assert_eq!(<[f32; 100] as BlockMember>::STD140_SIZE, 100 * 16);
+        assert_eq!(<[[f32; 4]; 100] as BlockMember>::STD430_SIZE, 100 * 16);
+    }
+}
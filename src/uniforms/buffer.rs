@@ -1,5 +1,5 @@
 use crate::buffer::{Content, Buffer, BufferAny, BufferType, BufferMode, BufferCreationError};
-use crate::buffer::{BufferSlice, BufferMutSlice};
+use crate::buffer::{BufferSlice, BufferMutSlice, BufferAnySlice};
 use crate::uniforms::{AsUniformValue, UniformBlock, UniformValue, LayoutMismatchError};
 use crate::program;
 
@@ -29,6 +29,40 @@ pub struct UniformBuffer<T: ?Sized> where T: Content {
     buffer: Buffer<T>,
 }
 
+/// A buffer associated to an explicit binding point of a program.
+///
+/// Built with `UniformBuffer::bind_to` or `UniformBuffer::bind_to_storage`, and passed to the
+/// draw command with `ExplicitBindings`.
+#[derive(Copy, Clone)]
+pub struct BlockBinding<'a> {
+    pub(crate) buffer: BufferAnySlice<'a>,
+    pub(crate) binding: u32,
+    pub(crate) kind: BlockBindingKind,
+}
+
+/// Kind of block that a `BlockBinding` is bound to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlockBindingKind {
+    /// A uniform block, corresponding to `layout(binding = N) uniform` in GLSL.
+    Uniform,
+    /// A shader storage block, corresponding to `layout(binding = N) buffer` in GLSL.
+    ShaderStorage,
+}
+
+impl<'a> BlockBinding<'a> {
+    /// Returns the binding point.
+    #[inline]
+    pub fn get_binding(&self) -> u32 {
+        self.binding
+    }
+
+    /// Returns the kind of block.
+    #[inline]
+    pub fn get_kind(&self) -> BlockBindingKind {
+        self.kind
+    }
+}
+
 /// Same as `UniformBuffer` but doesn't contain any information about the type.
 #[derive(Debug)]
 pub struct TypelessUniformBuffer {
@@ -199,6 +233,31 @@ impl<T: ?Sized> UniformBuffer<T> where T: Content {
             buffer,
         })
     }
+
+    /// Binds the buffer to the uniform block whose binding point is `binding`.
+    ///
+    /// Contrary to passing the buffer to `uniform!`, the program isn't queried for the name of
+    /// the block and the layout of the content isn't checked. The binding point must be set in
+    /// the shader with `layout(binding = N)`. This is required for programs created from SPIR-V,
+    /// where the names of the blocks may have been stripped.
+    #[inline]
+    pub fn bind_to(&self, binding: u32) -> BlockBinding<'_> {
+        BlockBinding {
+            buffer: self.buffer.as_slice_any(),
+            binding,
+            kind: BlockBindingKind::Uniform,
+        }
+    }
+
+    /// Same as `bind_to`, but binds the buffer to a shader storage block.
+    #[inline]
+    pub fn bind_to_storage(&self, binding: u32) -> BlockBinding<'_> {
+        BlockBinding {
+            buffer: self.buffer.as_slice_any(),
+            binding,
+            kind: BlockBindingKind::ShaderStorage,
+        }
+    }
 }
 
 impl<T: ?Sized> Deref for UniformBuffer<T> where T: Content {
@@ -0,0 +1,100 @@
+//! Implementations of `AsUniformValue` for the vectors and matrices of the `glam`, `cgmath` and
+//! `nalgebra` crates, each behind the feature with the name of the crate.
+//!
+//! The matrices of the three crates are column-major like in GLSL, so they are passed as is.
+use crate::uniforms::AsUniformValue;
+use crate::uniforms::UniformValue;
+
+macro_rules! impl_as_uniform_value {
+    ($feature:literal, $ty:ty, $variant:ident, |$value:ident| $conversion:expr) => (
+        #[cfg(feature = $feature)]
+        impl AsUniformValue for $ty {
+            #[inline]
+            fn as_uniform_value(&self) -> UniformValue<'_> {
+                let $value = self;
+                UniformValue::$variant($conversion)
+            }
+        }
+    );
+}
+
+impl_as_uniform_value!("glam", glam::Vec2, Vec2, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::Vec3, Vec3, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::Vec4, Vec4, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::Vec3A, Vec3, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::IVec2, IntVec2, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::IVec3, IntVec3, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::IVec4, IntVec4, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::UVec2, UnsignedIntVec2, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::UVec3, UnsignedIntVec3, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::UVec4, UnsignedIntVec4, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::BVec2, BoolVec2, |v| (*v).into());
+impl_as_uniform_value!("glam", glam::BVec3, BoolVec3, |v| (*v).into());
+impl_as_uniform_value!("glam", glam::BVec4, BoolVec4, |v| (*v).into());
+impl_as_uniform_value!("glam", glam::DVec2, DoubleVec2, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::DVec3, DoubleVec3, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::DVec4, DoubleVec4, |v| v.to_array());
+impl_as_uniform_value!("glam", glam::Mat2, Mat2, |v| v.to_cols_array_2d());
+impl_as_uniform_value!("glam", glam::Mat3, Mat3, |v| v.to_cols_array_2d());
+impl_as_uniform_value!("glam", glam::Mat4, Mat4, |v| v.to_cols_array_2d());
+impl_as_uniform_value!("glam", glam::Mat3A, Mat3, |v| v.to_cols_array_2d());
+impl_as_uniform_value!("glam", glam::DMat2, DoubleMat2, |v| v.to_cols_array_2d());
+impl_as_uniform_value!("glam", glam::DMat3, DoubleMat3, |v| v.to_cols_array_2d());
+impl_as_uniform_value!("glam", glam::DMat4, DoubleMat4, |v| v.to_cols_array_2d());
+
+impl_as_uniform_value!("cgmath", cgmath::Vector2<f32>, Vec2, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector3<f32>, Vec3, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector4<f32>, Vec4, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector2<f64>, DoubleVec2, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector3<f64>, DoubleVec3, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector4<f64>, DoubleVec4, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector2<i32>, IntVec2, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector3<i32>, IntVec3, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector4<i32>, IntVec4, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector2<u32>, UnsignedIntVec2, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector3<u32>, UnsignedIntVec3, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Vector4<u32>, UnsignedIntVec4, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Point2<f32>, Vec2, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Point3<f32>, Vec3, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Point2<f64>, DoubleVec2, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Point3<f64>, DoubleVec3, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Matrix2<f32>, Mat2, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Matrix3<f32>, Mat3, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Matrix4<f32>, Mat4, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Matrix2<f64>, DoubleMat2, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Matrix3<f64>, DoubleMat3, |v| (*v).into());
+impl_as_uniform_value!("cgmath", cgmath::Matrix4<f64>, DoubleMat4, |v| (*v).into());
+
+impl_as_uniform_value!("nalgebra", nalgebra::Vector2<f32>, Vec2, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector3<f32>, Vec3, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector4<f32>, Vec4, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector2<f64>, DoubleVec2, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector3<f64>, DoubleVec3, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector4<f64>, DoubleVec4, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector2<i32>, IntVec2, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector3<i32>, IntVec3, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector4<i32>, IntVec4, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector2<u32>, UnsignedIntVec2, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector3<u32>, UnsignedIntVec3, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector4<u32>, UnsignedIntVec4, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector2<bool>, BoolVec2, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector3<bool>, BoolVec3, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Vector4<bool>, BoolVec4, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point2<f32>, Vec2, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point3<f32>, Vec3, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point4<f32>, Vec4, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point2<f64>, DoubleVec2, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point3<f64>, DoubleVec3, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point4<f64>, DoubleVec4, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point2<i32>, IntVec2, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point3<i32>, IntVec3, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point4<i32>, IntVec4, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point2<u32>, UnsignedIntVec2, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point3<u32>, UnsignedIntVec3, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Point4<u32>, UnsignedIntVec4, |v| v.coords.into());
+impl_as_uniform_value!("nalgebra", nalgebra::Matrix2<f32>, Mat2, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Matrix3<f32>, Mat3, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Matrix4<f32>, Mat4, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Matrix2<f64>, DoubleMat2, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Matrix3<f64>, DoubleMat3, |v| (*v).into());
+impl_as_uniform_value!("nalgebra", nalgebra::Matrix4<f64>, DoubleMat4, |v| (*v).into());
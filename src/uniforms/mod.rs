@@ -36,6 +36,31 @@ let uniforms = uniform! {
 # }
 ```
 
+## Arrays and structs
+
+Arrays of uniforms, such as `uniform vec3 positions[8];`, can be set from a slice with a
+`UniformArray`. The elements of an array of structs are set field by field, which requires the
+Rust struct to implement `AsUniformValues` with the `implement_uniform_struct!` macro.
+
+```no_run
+# use glium::{uniform, implement_uniform_struct};
+# fn main() {
+#[derive(Copy, Clone)]
+struct Light {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+
+implement_uniform_struct!(Light, position, color);
+
+# let lights: Vec<Light> = Vec::new();
+// corresponds to `struct Light { vec3 position; vec3 color; }; uniform Light lights[8];`
+let uniforms = uniform! {
+    lights: glium::uniforms::UniformArray(&lights),
+};
+# }
+```
+
 ## Blocks
 
 In GLSL, you can choose to use a uniform *block*. When you use a block, you first need to
@@ -79,6 +104,13 @@ let uniforms = uniform! {
 # }
 ```
 
+Rust arrays of any length can be stored in a block. Arrays of two to four scalars correspond to
+vectors and arrays of two to four vectors correspond to matrices: for example `[[f32; 3]; 2]` is
+a `mat2x3`, which has two columns, and `[[f32; 4]; 100]` is a `vec4[100]`.
+
+If the content of a small block changes between each draw command, use a `PushUniforms` instead
+of writing to the same `UniformBuffer` over and over again.
+
 ## Subroutines
 OpenGL allows the use of subroutines, which are like function pointers. Subroutines can be used
 to change the functionality of a shader program at runtime. This method is usually a lot faster
@@ -129,25 +161,45 @@ let program = glium::Program::from_source(&display,
 # }
 ```
 */
-pub use self::buffer::UniformBuffer;
+pub use self::block_member::BlockMember;
+#[doc(hidden)]
+pub use self::block_member::{__align_up, __max};
+pub use self::array::UniformArray;
+pub use self::buffer::{UniformBuffer, BlockBinding, BlockBindingKind};
+pub use self::push::{PushUniforms, PushedUniforms};
+pub use self::push::{PushConstants, PUSH_CONSTANTS_NAME, MAX_PUSH_CONSTANTS_SIZE};
 pub use self::sampler::{SamplerWrapFunction, MagnifySamplerFilter, MinifySamplerFilter, DepthTextureComparison};
 pub use self::sampler::{Sampler, SamplerBehavior};
-pub use self::uniforms::{EmptyUniforms, UniformsStorage, DynamicUniforms};
+pub use self::sampler::{SamplerObject, TextureWithSampler, SamplersNotSupportedError};
+pub use self::uniforms::{EmptyUniforms, UniformsStorage, DynamicUniforms, ExplicitBindings};
+pub use self::uniforms::{UniformsPair, OwnedDynamicUniforms};
 pub use self::image_unit::{ImageUnitAccess, ImageUnitFormat, ImageUnitError};
 pub use self::image_unit::{ImageUnit, ImageUnitBehavior};
-pub use self::value::{UniformValue, UniformType};
+pub use self::value::{UniformValue, UniformType, Transposed};
+
+#[cfg(feature = "derive")]
+pub use glium_derive::UniformBlock;
+
+pub(crate) use self::bind::TextureAccesses;
 
 use std::error::Error;
 use std::fmt;
+use std::rc::Rc;
 
 use crate::buffer::Content as BufferContent;
 use crate::buffer::Buffer;
+use crate::buffer::BufferSlice;
 use crate::program;
 use crate::program::BlockLayout;
 
+mod array;
 mod bind;
+mod block_member;
 mod buffer;
 mod image_unit;
+#[cfg(any(feature = "cgmath", feature = "glam", feature = "nalgebra"))]
+mod interop;
+mod push;
 mod sampler;
 mod uniforms;
 mod value;
@@ -158,6 +210,20 @@ mod value;
 pub trait Uniforms {
     /// Calls the parameter once with the name and value of each uniform.
     fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, _: F);
+
+    /// Calls the parameter once with each buffer that must be bound to an explicit binding
+    /// point. The default implementation doesn't bind anything.
+    #[inline]
+    fn visit_block_bindings<'a, F: FnMut(BlockBinding<'a>)>(&'a self, _: F) {
+    }
+
+    /// Builds an object that contains the uniforms of `self` followed by the uniforms of `other`.
+    ///
+    /// See `UniformsPair`.
+    #[inline]
+    fn chain<U>(self, other: U) -> UniformsPair<Self, U> where Self: Sized, U: Uniforms {
+        UniformsPair(self, other)
+    }
 }
 
 /// Error about a block layout mismatch.
@@ -282,6 +348,31 @@ pub trait AsUniformValue {
     fn as_uniform_value(&self) -> UniformValue<'_>;
 }
 
+/// Value that is made of several uniforms, like an array or a struct.
+///
+/// This is implemented for every type that implements `AsUniformValue`, in which case the only
+/// uniform is the value itself. Use the `implement_uniform_struct!` macro to implement it
+/// for your structs.
+pub trait AsUniformValues {
+    /// Calls `output` with the name and the value of each uniform, given that the name of the
+    /// whole value is `name`.
+    fn visit_uniform_values<'a>(&'a self, name: &str, output: &mut dyn FnMut(&str, UniformValue<'a>));
+}
+
+impl<T> AsUniformValues for T where T: AsUniformValue {
+    #[inline]
+    fn visit_uniform_values<'a>(&'a self, name: &str, output: &mut dyn FnMut(&str, UniformValue<'a>)) {
+        output(name, self.as_uniform_value());
+    }
+}
+
+impl<T: ?Sized> AsUniformValue for Rc<T> where T: AsUniformValue {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        (**self).as_uniform_value()
+    }
+}
+
 // TODO: no way to bind a slice
 impl<'a, T: ?Sized> AsUniformValue for &'a Buffer<T> where T: UniformBlock + BufferContent {
     #[inline]
@@ -298,6 +389,27 @@ impl<'a, T: ?Sized> AsUniformValue for &'a Buffer<T> where T: UniformBlock + Buf
     }
 }
 
+/// A slice of a buffer can be bound to a uniform block, for example to store the data of many
+/// objects in a single buffer.
+///
+/// The offset of the slice must be a multiple of `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`, which is
+/// available as `Capabilities::uniform_buffer_offset_alignment`. The elements of an array must
+/// therefore be padded to this alignment if they are bound one by one. Otherwise drawing returns
+/// `DrawError::UniformBufferOffsetNotAligned`.
+impl<'a, T: ?Sized> AsUniformValue for BufferSlice<'a, T> where T: UniformBlock + BufferContent {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        #[inline]
+        fn f<T>(block: &program::UniformBlock) -> Result<(), LayoutMismatchError>
+                where T: ?Sized + UniformBlock + BufferContent
+        {
+            T::matches(&block.layout, 0)
+        }
+
+        UniformValue::Block(self.as_slice_any(), f::<T>)
+    }
+}
+
 /// Objects that are suitable for being inside a uniform block or a SSBO.
 pub trait UniformBlock {        // TODO: `: Copy`, but unsized structs don't impl `Copy`
     /// Checks whether the uniforms' layout matches the given block if `Self` starts at
@@ -352,86 +464,136 @@ impl<T> UniformBlock for [T] where T: UniformBlock {
     }
 }
 
-macro_rules! impl_uniform_block_array {
-    ($len:expr) => (
-        impl<T> UniformBlock for [T; $len] where T: UniformBlock {
-            fn matches(layout: &program::BlockLayout, base_offset: usize)
-                       -> Result<(), LayoutMismatchError>
-            {
-                if let &BlockLayout::Struct { ref members } = layout {
-                    if members.len() == 1 {
-                        return Self::matches(&members[0].1, base_offset);
-                    }
+impl<T, const N: usize> UniformBlock for [T; N] where T: UniformBlock {
+    fn matches(layout: &program::BlockLayout, base_offset: usize)
+               -> Result<(), LayoutMismatchError>
+    {
+        // small arrays of scalars and of vectors are GLSL vectors and matrices
+        if let Some(obtained) = array_basic_type::<T>(N) {
+            return if let BlockLayout::BasicType { ty, offset_in_buffer } = *layout {
+                if ty != obtained {
+                    Err(LayoutMismatchError::TypeMismatch {
+                        expected: ty,
+                        obtained,
+                    })
+                } else if offset_in_buffer != base_offset {
+                    Err(LayoutMismatchError::OffsetMismatch {
+                        expected: offset_in_buffer,
+                        obtained: base_offset,
+                    })
+                } else {
+                    Ok(())
                 }
 
-                if let &BlockLayout::Array { ref content, length } = layout {
-                    if let Err(err) = T::matches(content, base_offset) {
-                        return Err(LayoutMismatchError::MemberMismatch {
-                            member: "<array content>".to_owned(),
-                            err: Box::new(err),
-                        });
-                    }
-
-                    if length != $len {
-                        return Err(LayoutMismatchError::LayoutMismatch {
-                            expected: layout.clone(),
-                            obtained: Self::build_layout(base_offset),
-                        });
-                    }
+            } else {
+                Err(LayoutMismatchError::LayoutMismatch {
+                    expected: layout.clone(),
+                    obtained: Self::build_layout(base_offset),
+                })
+            };
+        }
 
-                    Ok(())
+        if let BlockLayout::Struct { members } = layout {
+            if members.len() == 1 {
+                return Self::matches(&members[0].1, base_offset);
+            }
+        }
 
-                } else {
-                    Err(LayoutMismatchError::LayoutMismatch {
-                        expected: layout.clone(),
-                        obtained: Self::build_layout(base_offset),
-                    })
-                }
+        if let BlockLayout::Array { content, length } = layout {
+            if let Err(err) = T::matches(content, base_offset) {
+                return Err(LayoutMismatchError::MemberMismatch {
+                    member: "<array content>".to_owned(),
+                    err: Box::new(err),
+                });
             }
 
-            #[inline]
-            fn build_layout(base_offset: usize) -> program::BlockLayout {
-                BlockLayout::Array {
-                    content: Box::new(T::build_layout(base_offset)),
-                    length: $len,
-                }
+            if *length != N {
+                return Err(LayoutMismatchError::LayoutMismatch {
+                    expected: layout.clone(),
+                    obtained: Self::build_layout(base_offset),
+                });
             }
+
+            Ok(())
+
+        } else {
+            Err(LayoutMismatchError::LayoutMismatch {
+                expected: layout.clone(),
+                obtained: Self::build_layout(base_offset),
+            })
+        }
+    }
+
+    #[inline]
+    fn build_layout(base_offset: usize) -> program::BlockLayout {
+        if let Some(ty) = array_basic_type::<T>(N) {
+            return BlockLayout::BasicType {
+                ty,
+                offset_in_buffer: base_offset,
+            };
+        }
+
+        BlockLayout::Array {
+            content: Box::new(T::build_layout(base_offset)),
+            length: N,
         }
-    );
+    }
 }
 
-impl_uniform_block_array!(5);
-impl_uniform_block_array!(6);
-impl_uniform_block_array!(7);
-impl_uniform_block_array!(8);
-impl_uniform_block_array!(9);
-impl_uniform_block_array!(10);
-impl_uniform_block_array!(11);
-impl_uniform_block_array!(12);
-impl_uniform_block_array!(13);
-impl_uniform_block_array!(14);
-impl_uniform_block_array!(15);
-impl_uniform_block_array!(16);
-impl_uniform_block_array!(17);
-impl_uniform_block_array!(18);
-impl_uniform_block_array!(19);
-impl_uniform_block_array!(20);
-impl_uniform_block_array!(21);
-impl_uniform_block_array!(22);
-impl_uniform_block_array!(23);
-impl_uniform_block_array!(24);
-impl_uniform_block_array!(25);
-impl_uniform_block_array!(26);
-impl_uniform_block_array!(27);
-impl_uniform_block_array!(28);
-impl_uniform_block_array!(29);
-impl_uniform_block_array!(30);
-impl_uniform_block_array!(31);
-impl_uniform_block_array!(32);
-impl_uniform_block_array!(64);
-impl_uniform_block_array!(128);
-impl_uniform_block_array!(256);
-impl_uniform_block_array!(512);
-impl_uniform_block_array!(1024);
-impl_uniform_block_array!(2048);
-impl_uniform_block_array!(4096);
+/// Returns the type of the vector or matrix that corresponds to `[T; length]`, or `None` if
+/// this is a regular array.
+///
+/// For example `[f32; 3]` is a `vec3` and `[[f32; 3]; 2]` is a `mat2x3`.
+fn array_basic_type<T>(length: usize) -> Option<UniformType> where T: UniformBlock {
+    use self::UniformType as U;
+
+    let element = match T::build_layout(0) {
+        BlockLayout::BasicType { ty, .. } => ty,
+        _ => return None,
+    };
+
+    let ty = match (element, length) {
+        (U::Float, 2) => U::FloatVec2,
+        (U::Float, 3) => U::FloatVec3,
+        (U::Float, 4) => U::FloatVec4,
+        (U::Double, 2) => U::DoubleVec2,
+        (U::Double, 3) => U::DoubleVec3,
+        (U::Double, 4) => U::DoubleVec4,
+        (U::Int, 2) => U::IntVec2,
+        (U::Int, 3) => U::IntVec3,
+        (U::Int, 4) => U::IntVec4,
+        (U::UnsignedInt, 2) => U::UnsignedIntVec2,
+        (U::UnsignedInt, 3) => U::UnsignedIntVec3,
+        (U::UnsignedInt, 4) => U::UnsignedIntVec4,
+        (U::Int64, 2) => U::Int64Vec2,
+        (U::Int64, 3) => U::Int64Vec3,
+        (U::Int64, 4) => U::Int64Vec4,
+        (U::UnsignedInt64, 2) => U::UnsignedInt64Vec2,
+        (U::UnsignedInt64, 3) => U::UnsignedInt64Vec3,
+        (U::UnsignedInt64, 4) => U::UnsignedInt64Vec4,
+        (U::Bool, 2) => U::BoolVec2,
+        (U::Bool, 3) => U::BoolVec3,
+        (U::Bool, 4) => U::BoolVec4,
+        (U::FloatVec2, 2) => U::FloatMat2,
+        (U::FloatVec3, 3) => U::FloatMat3,
+        (U::FloatVec4, 4) => U::FloatMat4,
+        (U::FloatVec3, 2) => U::FloatMat2x3,
+        (U::FloatVec4, 2) => U::FloatMat2x4,
+        (U::FloatVec2, 3) => U::FloatMat3x2,
+        (U::FloatVec4, 3) => U::FloatMat3x4,
+        (U::FloatVec2, 4) => U::FloatMat4x2,
+        (U::FloatVec3, 4) => U::FloatMat4x3,
+        (U::DoubleVec2, 2) => U::DoubleMat2,
+        (U::DoubleVec3, 3) => U::DoubleMat3,
+        (U::DoubleVec4, 4) => U::DoubleMat4,
+        (U::DoubleVec3, 2) => U::DoubleMat2x3,
+        (U::DoubleVec4, 2) => U::DoubleMat2x4,
+        (U::DoubleVec2, 3) => U::DoubleMat3x2,
+        (U::DoubleVec4, 3) => U::DoubleMat3x4,
+        (U::DoubleVec2, 4) => U::DoubleMat4x2,
+        (U::DoubleVec3, 4) => U::DoubleMat4x3,
+        _ => return None,
+    };
+
+    Some(ty)
+}
@@ -0,0 +1,242 @@
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferSlice, BufferType, BufferMode, BufferCreationError};
+use crate::field;
+use crate::program;
+use crate::uniforms::{AsUniformValue, UniformBlock, UniformValue, LayoutMismatchError};
+use crate::uniforms::Uniforms;
+use crate::uniforms::{BlockBinding, BlockBindingKind, __align_up};
+use crate::CapabilitiesSource;
+
+/// Ring of small uniform blocks that emulates push constants.
+///
+/// Updating many small per-object uniforms with `glUniform*` calls is slow with some drivers.
+/// Instead, each call to `push` writes the data in the next slot of a persistently-mapped uniform
+/// buffer, and only the range of this slot is bound when drawing.
+///
+/// ```ignore
+/// let push_uniforms = PushUniforms::<ObjectData>::new(&display).unwrap();
+///
+/// for object in objects.iter() {
+///     let data = ObjectData { model: object.model, color: object.color };
+///     target.draw(&object.vb, &object.ib, &program, &uniform! { ObjectData: push_uniforms.push(data) },
+///                 &Default::default()).unwrap();
+/// }
+/// ```
+///
+/// When all the slots have been used, the ring starts again from the first slot. If the GPU
+/// is still reading a slot when it is about to be overwritten, `push` waits until it is done.
+/// The capacity should therefore be large enough for the number of draw commands of a frame.
+///
+/// If the backend doesn't support persistent mapping, this falls back to a regular dynamic
+/// buffer.
+pub struct PushUniforms<T> where T: UniformBlock + Copy {
+    buffer: Buffer<[u8]>,
+    stride: usize,
+    capacity: usize,
+    next_slot: Cell<usize>,
+    marker: PhantomData<T>,
+}
+
+/// Slot of a `PushUniforms` that contains some data. Pass it to the draw command like a
+/// `UniformBuffer`.
+#[derive(Copy, Clone)]
+pub struct PushedUniforms<'a, T> where T: UniformBlock + Copy {
+    slice: BufferSlice<'a, T>,
+}
+
+impl<T> PushUniforms<T> where T: UniformBlock + Copy {
+    /// Builds a new ring with 256 slots.
+    #[inline]
+    pub fn new<F>(facade: &F) -> Result<PushUniforms<T>, BufferCreationError>
+                  where F: ?Sized + Facade
+    {
+        PushUniforms::with_capacity(facade, 256)
+    }
+
+    /// Builds a new ring with the given number of slots.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn with_capacity<F>(facade: &F, capacity: usize)
+                            -> Result<PushUniforms<T>, BufferCreationError>
+                            where F: ?Sized + Facade
+    {
+        assert!(capacity >= 1);
+
+        let alignment = facade.get_context().get_capabilities().uniform_buffer_offset_alignment;
+        if alignment <= 0 {
+            return Err(BufferCreationError::BufferTypeNotSupported);
+        }
+
+        // each slot starts at an offset that can be bound
+        let stride = __align_up(mem::size_of::<T>(), alignment as usize);
+
+        let buffer = Buffer::empty_array(facade, BufferType::UniformBuffer, stride * capacity,
+                                         BufferMode::Persistent)?;
+
+        Ok(PushUniforms {
+            buffer,
+            stride,
+            capacity,
+            next_slot: Cell::new(0),
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of slots of the ring.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Writes `data` in the next slot of the ring and returns this slot.
+    pub fn push(&self, data: T) -> PushedUniforms<'_, T> {
+        let slot = self.next_slot.get();
+        self.next_slot.set((slot + 1) % self.capacity);
+
+        let slice = unsafe {
+            self.buffer.slice_custom(field::_hidden_field::<T>(slot * self.stride, None))
+        };
+
+        // waits for the draw commands that are still using this slot
+        slice.write(&data);
+
+        PushedUniforms {
+            slice,
+        }
+    }
+}
+
+impl<'a, T> PushedUniforms<'a, T> where T: UniformBlock + Copy {
+    /// Binds the slot to the uniform block whose binding point is `binding`.
+    ///
+    /// See `UniformBuffer::bind_to`.
+    #[inline]
+    pub fn bind_to(&self, binding: u32) -> BlockBinding<'a> {
+        BlockBinding {
+            buffer: self.slice.as_slice_any(),
+            binding,
+            kind: BlockBindingKind::Uniform,
+        }
+    }
+}
+
+impl<'a, T> AsUniformValue for PushedUniforms<'a, T> where T: UniformBlock + Copy {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        #[inline]
+        fn f<T>(block: &program::UniformBlock) -> Result<(), LayoutMismatchError>
+                where T: UniformBlock + Copy
+        {
+            T::matches(&block.layout, 0)
+        }
+
+        UniformValue::Block(self.slice.as_slice_any(), f::<T>)
+    }
+}
+
+/// Name of the uniform array that receives the data of a `PushConstants`.
+pub const PUSH_CONSTANTS_NAME: &str = "glium_push_constants";
+
+/// Maximal size in bytes of the data of a `PushConstants`.
+pub const MAX_PUSH_CONSTANTS_SIZE: usize = 128;
+
+/// Names of the elements of the array, so that they aren't built during each draw command.
+const PUSH_CONSTANTS_ELEMENTS: [&str; MAX_PUSH_CONSTANTS_SIZE / 16] = [
+    "glium_push_constants[0]", "glium_push_constants[1]", "glium_push_constants[2]",
+    "glium_push_constants[3]", "glium_push_constants[4]", "glium_push_constants[5]",
+    "glium_push_constants[6]", "glium_push_constants[7]",
+];
+
+/// Small per-draw data that is uploaded with `glUniform*` calls, like push constants.
+///
+/// The bytes of the data are copied into the `uvec4` elements of the uniform array named
+/// `glium_push_constants`, which the program must declare with enough elements for the data.
+/// The declaration is returned by `glsl_declaration`. The elements are uploaded with
+/// `glUniform4uiv`, so that the bits are passed as they are, and the fields that aren't unsigned
+/// integers are read in the shader with `uintBitsToFloat` or `int`. This requires GLSL 1.30
+/// or GLSL ES 3.00.
+///
+/// Unlike a `PushUniforms`, this doesn't need uniform buffers and doesn't wait for the GPU, but
+/// it is limited to 128 bytes.
+///
+/// ```ignore
+/// #[derive(Copy, Clone)]
+/// #[repr(C)]
+/// struct ObjectData {
+///     model: [[f32; 4]; 4],
+///     color: [f32; 4],
+/// }
+///
+/// // in the vertex shader, with `PushConstants::<ObjectData>::glsl_declaration()`:
+/// // uniform uvec4 glium_push_constants[5];
+/// // mat4 model = mat4(uintBitsToFloat(glium_push_constants[0]),
+/// //                   uintBitsToFloat(glium_push_constants[1]),
+/// //                   uintBitsToFloat(glium_push_constants[2]),
+/// //                   uintBitsToFloat(glium_push_constants[3]));
+///
+/// for object in objects.iter() {
+///     let data = ObjectData { model: object.model, color: object.color };
+///     // `ObjectData` has no padding
+///     let data = unsafe { PushConstants::new(data) };
+///     target.draw(&object.vb, &object.ib, &program, &UniformsPair(&uniforms, data),
+///                 &Default::default()).unwrap();
+/// }
+/// ```
+///
+/// Using data larger than 128 bytes is a compilation error.
+#[derive(Copy, Clone)]
+pub struct PushConstants<T> where T: Copy {
+    elements: [[u32; 4]; MAX_PUSH_CONSTANTS_SIZE / 16],
+    marker: PhantomData<T>,
+}
+
+impl<T> PushConstants<T> where T: Copy {
+    const SIZE_VALID: () = assert!(mem::size_of::<T>() <= MAX_PUSH_CONSTANTS_SIZE,
+                                   "push constants can't be larger than 128 bytes");
+
+    /// Number of `uvec4` elements that contain the data.
+    const LEN: usize = (mem::size_of::<T>() + 15) / 16;
+
+    /// Copies the bytes of the data.
+    ///
+    /// # Safety
+    ///
+    /// `T` must not contain padding bytes or pointers, like a `#[repr(C)]` struct of `f32`s,
+    /// `i32`s, `u32`s and arrays of them whose fields are all 4-byte aligned.
+    #[inline]
+    pub unsafe fn new(data: T) -> PushConstants<T> {
+        let () = Self::SIZE_VALID;
+
+        let mut elements = [[0u32; 4]; MAX_PUSH_CONSTANTS_SIZE / 16];
+        ptr::copy_nonoverlapping(&data as *const T as *const u8,
+                                 elements.as_mut_ptr() as *mut u8, mem::size_of::<T>());
+
+        PushConstants {
+            elements,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the GLSL declaration of the uniform array that receives the data, for example
+    /// `uniform uvec4 glium_push_constants[5];` for 80 bytes.
+    pub fn glsl_declaration() -> String {
+        let () = Self::SIZE_VALID;
+        format!("uniform uvec4 {}[{}];", PUSH_CONSTANTS_NAME, Self::LEN.max(1))
+    }
+}
+
+impl<T> Uniforms for PushConstants<T> where T: Copy {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        for (name, element) in PUSH_CONSTANTS_ELEMENTS.iter().zip(&self.elements[.. Self::LEN]) {
+            output(name, UniformValue::UnsignedIntVec4(*element));
+        }
+    }
+}
@@ -1,3 +1,16 @@
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::sampler_object;
+use crate::uniforms::{AsUniformValue, UniformValue};
+use crate::version::Api;
+use crate::version::Version;
+use crate::CapabilitiesSource;
+use crate::ContextExt;
+use crate::GlObject;
 use crate::ToGlEnum;
 use crate::gl;
 
@@ -233,3 +246,141 @@ impl Default for SamplerBehavior {
         }
     }
 }
+
+/// A sampler object that is created once and can be used with any number of textures.
+///
+/// When you pass a `Sampler` to the uniforms, glium looks for a sampler object that matches its
+/// `SamplerBehavior` in a cache every time the texture is bound, and keeps it alive until the
+/// context is destroyed. A `SamplerObject` instead is bound directly, and is destroyed when it
+/// is dropped.
+///
+/// Use the `with_sampler` method of a texture to sample it with a `SamplerObject`.
+///
+/// ```no_run
+/// # use glium::uniform;
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>, diffuse: glium::Texture2d, normals: glium::Texture2d)
+/// #     where T: SurfaceTypeTrait + ResizeableSurface {
+/// use glium::uniforms::{SamplerObject, SamplerBehavior, MagnifySamplerFilter};
+///
+/// let sampler = SamplerObject::new(&display, SamplerBehavior {
+///     magnify_filter: MagnifySamplerFilter::Nearest,
+///     .. Default::default()
+/// }).unwrap();
+///
+/// let uniforms = uniform! {
+///     diffuse: diffuse.with_sampler(&sampler),
+///     normals: normals.with_sampler(&sampler),
+/// };
+/// # }
+/// ```
+pub struct SamplerObject {
+    context: Rc<Context>,
+    sampler: Option<sampler_object::SamplerObject>,
+    behavior: SamplerBehavior,
+}
+
+impl SamplerObject {
+    /// Builds a new sampler object.
+    pub fn new<F>(facade: &F, behavior: SamplerBehavior)
+                  -> Result<SamplerObject, SamplersNotSupportedError> where F: ?Sized + Facade
+    {
+        if !SamplerObject::is_supported(facade) {
+            return Err(SamplersNotSupportedError);
+        }
+
+        let context = facade.get_context().clone();
+        let sampler = {
+            let mut ctxt = context.make_current();
+            sampler_object::SamplerObject::new(&mut ctxt, &behavior)
+        };
+
+        Ok(SamplerObject {
+            context,
+            sampler: Some(sampler),
+            behavior,
+        })
+    }
+
+    /// Returns true if sampler objects are supported by the backend.
+    #[inline]
+    pub fn is_supported<C>(context: &C) -> bool where C: ?Sized + CapabilitiesSource {
+        context.get_version() >= &Version(Api::Gl, 3, 3) ||
+            context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+            context.get_extensions().gl_arb_sampler_objects
+    }
+
+    /// Returns the behavior that was passed when creating the sampler.
+    #[inline]
+    pub fn get_behavior(&self) -> &SamplerBehavior {
+        &self.behavior
+    }
+}
+
+impl fmt::Debug for SamplerObject {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "SamplerObject #{} ({:?})", self.get_id(), self.behavior)
+    }
+}
+
+impl GlObject for SamplerObject {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.sampler.as_ref().unwrap().get_id()
+    }
+}
+
+impl Drop for SamplerObject {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+        let sampler = self.sampler.take().unwrap();
+
+        // deleting a sampler unbinds it from the texture units
+        for unit in ctxt.state.texture_units.iter_mut() {
+            if unit.sampler == sampler.get_id() {
+                unit.sampler = 0;
+            }
+        }
+
+        sampler.destroy(&mut ctxt);
+    }
+}
+
+/// A texture combined with a `SamplerObject`. Built with the `with_sampler` method of textures.
+#[derive(Copy, Clone)]
+pub struct TextureWithSampler<'a> {
+    texture: UniformValue<'a>,
+    sampler: &'a SamplerObject,
+}
+
+impl<'a> TextureWithSampler<'a> {
+    #[inline]
+    pub(crate) fn new(texture: UniformValue<'a>, sampler: &'a SamplerObject) -> TextureWithSampler<'a> {
+        TextureWithSampler {
+            texture,
+            sampler,
+        }
+    }
+}
+
+impl<'a> AsUniformValue for TextureWithSampler<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::TextureWithSampler(&self.texture, self.sampler)
+    }
+}
+
+/// Error that can happen when creating a `SamplerObject`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SamplersNotSupportedError;
+
+impl fmt::Display for SamplersNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        fmt.write_str("Sampler objects are not supported by the backend")
+    }
+}
+
+impl Error for SamplersNotSupportedError {}
@@ -1,5 +1,7 @@
-use crate::uniforms::{Uniforms, UniformValue, AsUniformValue};
+use crate::uniforms::{Uniforms, UniformValue, AsUniformValue, AsUniformValues, BlockBinding};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 /// Object that can be used when you don't have any uniforms.
 #[derive(Debug, Copy, Clone)]
@@ -13,13 +15,13 @@ impl Uniforms for EmptyUniforms {
 
 /// Stores uniforms.
 #[derive(Copy, Clone)]
-pub struct UniformsStorage<'n, T, R> where T: AsUniformValue, R: Uniforms {
+pub struct UniformsStorage<'n, T, R> where T: AsUniformValues, R: Uniforms {
     name: &'n str,
     value: T,
     rest: R,
 }
 
-impl<'n, T> UniformsStorage<'n, T, EmptyUniforms> where T: AsUniformValue {
+impl<'n, T> UniformsStorage<'n, T, EmptyUniforms> where T: AsUniformValues {
     /// Builds a new storage with a value.
     #[inline]
     pub fn new(name: &'n str, value: T)
@@ -33,12 +35,12 @@ impl<'n, T> UniformsStorage<'n, T, EmptyUniforms> where T: AsUniformValue {
     }
 }
 
-impl<'n, T, R> UniformsStorage<'n, T, R> where T: AsUniformValue, R: Uniforms {
+impl<'n, T, R> UniformsStorage<'n, T, R> where T: AsUniformValues, R: Uniforms {
     /// Adds a value to the storage.
     #[inline]
     pub fn add<U>(self, name: &'n str, value: U)
                   -> UniformsStorage<'n, U, UniformsStorage<'n, T, R>>
-                  where U: AsUniformValue
+                  where U: AsUniformValues
     {
         UniformsStorage {
             name,
@@ -48,18 +50,65 @@ impl<'n, T, R> UniformsStorage<'n, T, R> where T: AsUniformValue, R: Uniforms {
     }
 }
 
-impl<'n, T, R> Uniforms for UniformsStorage<'n, T, R> where T: AsUniformValue, R: Uniforms {
+impl<'n, T, R> Uniforms for UniformsStorage<'n, T, R> where T: AsUniformValues, R: Uniforms {
     #[inline]
     fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
-        output(self.name, self.value.as_uniform_value());
+        self.value.visit_uniform_values(self.name, &mut output);
         self.rest.visit_values(output);
     }
 }
 
+/// Uniforms of two objects that are bound one after the other.
+///
+/// This allows keeping uniforms that change at different rates in different objects, and
+/// combining them at draw time without copying them. Since `Uniforms` is also implemented for
+/// references, the objects can be borrowed.
+///
+/// ```ignore
+/// let frame = uniform! { view: view, projection: projection };
+/// let material = uniform! { diffuse: &texture };
+///
+/// for object in objects.iter() {
+///     let uniforms = (&frame).chain(&material).chain(uniform! { model: object.model });
+///     target.draw(&object.vb, &object.ib, &program, &uniforms, &Default::default()).unwrap();
+/// }
+/// ```
+///
+/// If both objects contain a uniform with the same name, the value of the second one is the
+/// one that is used, as it is bound last.
+#[derive(Debug, Copy, Clone)]
+pub struct UniformsPair<A, B>(pub A, pub B);
+
+impl<A, B> Uniforms for UniformsPair<A, B> where A: Uniforms, B: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        self.0.visit_values(&mut output);
+        self.1.visit_values(output);
+    }
+
+    #[inline]
+    fn visit_block_bindings<'a, F: FnMut(BlockBinding<'a>)>(&'a self, mut output: F) {
+        self.0.visit_block_bindings(&mut output);
+        self.1.visit_block_bindings(output);
+    }
+}
+
+impl<U: ?Sized> Uniforms for &U where U: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, output: F) {
+        (**self).visit_values(output);
+    }
+
+    #[inline]
+    fn visit_block_bindings<'a, F: FnMut(BlockBinding<'a>)>(&'a self, output: F) {
+        (**self).visit_block_bindings(output);
+    }
+}
+
 /// Stores Uniforms dynamicly in a HashMap.
 #[derive(Clone)]
 pub struct DynamicUniforms<'a, 's>{
-    map: HashMap<&'s str, UniformValue<'a>>,
+    map: HashMap<Cow<'s, str>, UniformValue<'a>>,
 }
 
 impl<'a, 's> DynamicUniforms<'a, 's>{
@@ -73,7 +122,15 @@ impl<'a, 's> DynamicUniforms<'a, 's>{
     /// Add a value to the DynamicUniforms
     #[inline]
     pub fn add(&mut self, key: &'s str, value: &'a dyn AsUniformValue){
-        self.map.insert(key, value.as_uniform_value());
+        self.map.insert(Cow::Borrowed(key), value.as_uniform_value());
+    }
+
+    /// Add a value made of several uniforms, like a `UniformArray`, to the DynamicUniforms
+    pub fn add_array(&mut self, key: &'s str, value: &'a dyn AsUniformValues){
+        let map = &mut self.map;
+        value.visit_uniform_values(key, &mut |name, value| {
+            map.insert(Cow::Owned(name.to_owned()), value);
+        });
     }
 }
 
@@ -84,3 +141,103 @@ impl Uniforms for DynamicUniforms<'_, '_>{
         }
     }
 }
+
+/// Stores uniforms dynamically in a HashMap, like `DynamicUniforms`, but owns their values.
+///
+/// Since this object doesn't borrow anything, it can be returned from a function or cached,
+/// for example by a material system. Textures can be shared between several objects by
+/// wrapping them in an `Rc`. Cloning the object is cheap, as the values are reference-counted.
+///
+/// ```ignore
+/// fn build_material(texture: Rc<Texture2d>) -> OwnedDynamicUniforms {
+///     let mut uniforms = OwnedDynamicUniforms::new();
+///     uniforms.add("diffuse", texture);
+///     uniforms.add("shininess", 32.0f32);
+///     uniforms
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct OwnedDynamicUniforms {
+    map: HashMap<String, Rc<dyn AsUniformValues>>,
+}
+
+impl OwnedDynamicUniforms {
+    /// Creates a new empty object.
+    #[inline]
+    pub fn new() -> OwnedDynamicUniforms {
+        OwnedDynamicUniforms {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Adds a value, or replaces the value that has the same name.
+    ///
+    /// This accepts everything that `DynamicUniforms::add_array` accepts, as long as it doesn't
+    /// borrow anything.
+    #[inline]
+    pub fn add<S, T>(&mut self, key: S, value: T)
+                     where S: Into<String>, T: AsUniformValues + 'static
+    {
+        self.map.insert(key.into(), Rc::new(value));
+    }
+
+    /// Removes a value. Returns false if there was no value with this name.
+    #[inline]
+    pub fn remove(&mut self, key: &str) -> bool {
+        self.map.remove(key).is_some()
+    }
+}
+
+impl Uniforms for OwnedDynamicUniforms {
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        for (key, value) in self.map.iter() {
+            value.visit_uniform_values(key, &mut output);
+        }
+    }
+}
+
+/// Adds buffers bound to explicit binding points to other uniforms.
+///
+/// ```ignore
+/// let uniforms = ExplicitBindings::new(uniform! { scale: 2.0f32 })
+///     .with_binding(lights.bind_to(0))
+///     .with_binding(particles.bind_to_storage(1));
+/// ```
+#[derive(Clone)]
+pub struct ExplicitBindings<'b, U> where U: Uniforms {
+    uniforms: U,
+    bindings: Vec<BlockBinding<'b>>,
+}
+
+impl<'b, U> ExplicitBindings<'b, U> where U: Uniforms {
+    /// Builds a new object with the uniforms that are looked up by name.
+    #[inline]
+    pub fn new(uniforms: U) -> ExplicitBindings<'b, U> {
+        ExplicitBindings {
+            uniforms,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Adds a buffer bound to an explicit binding point.
+    #[inline]
+    pub fn with_binding(mut self, binding: BlockBinding<'b>) -> ExplicitBindings<'b, U> {
+        self.bindings.push(binding);
+        self
+    }
+}
+
+impl<'b, U> Uniforms for ExplicitBindings<'b, U> where U: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, output: F) {
+        self.uniforms.visit_values(output);
+    }
+
+    #[inline]
+    fn visit_block_bindings<'a, F: FnMut(BlockBinding<'a>)>(&'a self, mut output: F) {
+        self.uniforms.visit_block_bindings(&mut output);
+        for binding in self.bindings.iter() {
+            output(*binding);
+        }
+    }
+}
@@ -7,6 +7,7 @@ use crate::uniforms::AsUniformValue;
 use crate::uniforms::LayoutMismatchError;
 use crate::uniforms::UniformBlock;
 use crate::uniforms::SamplerBehavior;
+use crate::uniforms::SamplerObject;
 
 use crate::uniforms::ImageUnitBehavior;
 use crate::buffer::BufferAnySlice;
@@ -102,6 +103,7 @@ pub enum UniformType {
     Sampler1dArrayShadow,
     Sampler2dArrayShadow,
     SamplerCubeArrayShadow,
+    SamplerExternalOes,
     Image1d,
     IImage1d,
     UImage1d,
@@ -138,6 +140,33 @@ pub enum UniformType {
     AtomicCounterUint,
 }
 
+impl UniformType {
+    /// Returns true if the type is a sampler, in other words if the uniform is bound to a
+    /// texture unit.
+    pub fn is_sampler(&self) -> bool {
+        matches!(*self,
+            UniformType::Sampler1d | UniformType::ISampler1d | UniformType::USampler1d |
+            UniformType::Sampler2d | UniformType::ISampler2d | UniformType::USampler2d |
+            UniformType::Sampler3d | UniformType::ISampler3d | UniformType::USampler3d |
+            UniformType::Sampler1dArray | UniformType::ISampler1dArray |
+            UniformType::USampler1dArray | UniformType::Sampler2dArray |
+            UniformType::ISampler2dArray | UniformType::USampler2dArray |
+            UniformType::SamplerCube | UniformType::ISamplerCube | UniformType::USamplerCube |
+            UniformType::Sampler2dRect | UniformType::ISampler2dRect |
+            UniformType::USampler2dRect | UniformType::Sampler2dRectShadow |
+            UniformType::SamplerCubeArray | UniformType::ISamplerCubeArray |
+            UniformType::USamplerCubeArray | UniformType::SamplerBuffer |
+            UniformType::ISamplerBuffer | UniformType::USamplerBuffer |
+            UniformType::Sampler2dMultisample | UniformType::ISampler2dMultisample |
+            UniformType::USampler2dMultisample | UniformType::Sampler2dMultisampleArray |
+            UniformType::ISampler2dMultisampleArray | UniformType::USampler2dMultisampleArray |
+            UniformType::Sampler1dShadow | UniformType::Sampler2dShadow |
+            UniformType::SamplerCubeShadow | UniformType::Sampler1dArrayShadow |
+            UniformType::Sampler2dArrayShadow | UniformType::SamplerCubeArrayShadow |
+            UniformType::SamplerExternalOes)
+    }
+}
+
 /// Represents a value to bind to a uniform.
 #[allow(missing_docs)]
 #[derive(Copy)]
@@ -148,6 +177,8 @@ pub enum UniformValue<'a> {
     /// the buffer has finished being used.
     Block(BufferAnySlice<'a>, fn(&program::UniformBlock) -> Result<(), LayoutMismatchError>),
     Subroutine(ShaderStage, &'a str),
+    /// A texture, which is one of the other variants, that is sampled with a `SamplerObject`.
+    TextureWithSampler(&'a UniformValue<'a>, &'a SamplerObject),
     SignedInt(i32),
     UnsignedInt(u32),
     Float(f32),
@@ -157,6 +188,24 @@ pub enum UniformValue<'a> {
     Mat3([[f32; 3]; 3]),
     /// 4x4 column-major matrix.
     Mat4([[f32; 4]; 4]),
+    /// 2x3 column-major matrix, with 2 columns of 3 rows.
+    Mat2x3([[f32; 3]; 2]),
+    /// 2x4 column-major matrix, with 2 columns of 4 rows.
+    Mat2x4([[f32; 4]; 2]),
+    /// 3x2 column-major matrix, with 3 columns of 2 rows.
+    Mat3x2([[f32; 2]; 3]),
+    /// 3x4 column-major matrix, with 3 columns of 4 rows.
+    Mat3x4([[f32; 4]; 3]),
+    /// 4x2 column-major matrix, with 4 columns of 2 rows.
+    Mat4x2([[f32; 2]; 4]),
+    /// 4x3 column-major matrix, with 4 columns of 3 rows.
+    Mat4x3([[f32; 3]; 4]),
+    /// 2x2 row-major matrix. See `Transposed`.
+    Mat2Transposed([[f32; 2]; 2]),
+    /// 3x3 row-major matrix. See `Transposed`.
+    Mat3Transposed([[f32; 3]; 3]),
+    /// 4x4 row-major matrix. See `Transposed`.
+    Mat4Transposed([[f32; 4]; 4]),
     Vec2([f32; 2]),
     Vec3([f32; 3]),
     Vec4([f32; 4]),
@@ -177,6 +226,12 @@ pub enum UniformValue<'a> {
     DoubleMat2([[f64;2]; 2]),
     DoubleMat3([[f64;3]; 3]),
     DoubleMat4([[f64;4]; 4]),
+    DoubleMat2x3([[f64; 3]; 2]),
+    DoubleMat2x4([[f64; 4]; 2]),
+    DoubleMat3x2([[f64; 2]; 3]),
+    DoubleMat3x4([[f64; 4]; 3]),
+    DoubleMat4x2([[f64; 2]; 4]),
+    DoubleMat4x3([[f64; 3]; 4]),
     Int64(i64),
     Int64Vec2([i64; 2]),
     Int64Vec3([i64; 3]),
@@ -245,6 +300,7 @@ pub enum UniformValue<'a> {
     UnsignedCubemapArray(&'a texture::UnsignedCubemapArray, Option<SamplerBehavior>),
     DepthCubemapArray(&'a texture::DepthCubemapArray, Option<SamplerBehavior>),
     BufferTexture(texture::buffer_texture::BufferTextureRef<'a>),
+    ExternalTexture(&'a texture::external::ExternalTexture, Option<SamplerBehavior>),
 
     Image1d(&'a texture::Texture1d, Option<ImageUnitBehavior>),
     IntegralImage1d(&'a texture::IntegralTexture1d, Option<ImageUnitBehavior>),
@@ -280,6 +336,7 @@ impl<'a> UniformValue<'a> {
     /// Returns true if this value can be used with a uniform of the given type.
     pub fn is_usable_with(&self, ty: &UniformType) -> bool {
         match (self, *ty) {
+            (&UniformValue::TextureWithSampler(texture, _), ty) => texture.is_usable_with(&ty),
             (&UniformValue::Bool(_), UniformType::Bool) => true,
             (&UniformValue::SignedInt(_), UniformType::Int) => true,
             (&UniformValue::UnsignedInt(_), UniformType::UnsignedInt) => true,
@@ -287,6 +344,15 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::Mat2(_), UniformType::FloatMat2) => true,
             (&UniformValue::Mat3(_), UniformType::FloatMat3) => true,
             (&UniformValue::Mat4(_), UniformType::FloatMat4) => true,
+            (&UniformValue::Mat2x3(_), UniformType::FloatMat2x3) => true,
+            (&UniformValue::Mat2x4(_), UniformType::FloatMat2x4) => true,
+            (&UniformValue::Mat3x2(_), UniformType::FloatMat3x2) => true,
+            (&UniformValue::Mat3x4(_), UniformType::FloatMat3x4) => true,
+            (&UniformValue::Mat4x2(_), UniformType::FloatMat4x2) => true,
+            (&UniformValue::Mat4x3(_), UniformType::FloatMat4x3) => true,
+            (&UniformValue::Mat2Transposed(_), UniformType::FloatMat2) => true,
+            (&UniformValue::Mat3Transposed(_), UniformType::FloatMat3) => true,
+            (&UniformValue::Mat4Transposed(_), UniformType::FloatMat4) => true,
             (&UniformValue::Vec2(_), UniformType::FloatVec2) => true,
             (&UniformValue::Vec3(_), UniformType::FloatVec3) => true,
             (&UniformValue::Vec4(_), UniformType::FloatVec4) => true,
@@ -303,9 +369,23 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::DoubleMat2(_), UniformType::DoubleMat2) => true,
             (&UniformValue::DoubleMat3(_), UniformType::DoubleMat3) => true,
             (&UniformValue::DoubleMat4(_), UniformType::DoubleMat4) => true,
+            (&UniformValue::DoubleMat2x3(_), UniformType::DoubleMat2x3) => true,
+            (&UniformValue::DoubleMat2x4(_), UniformType::DoubleMat2x4) => true,
+            (&UniformValue::DoubleMat3x2(_), UniformType::DoubleMat3x2) => true,
+            (&UniformValue::DoubleMat3x4(_), UniformType::DoubleMat3x4) => true,
+            (&UniformValue::DoubleMat4x2(_), UniformType::DoubleMat4x2) => true,
+            (&UniformValue::DoubleMat4x3(_), UniformType::DoubleMat4x3) => true,
             (&UniformValue::DoubleVec2(_), UniformType::DoubleVec2) => true,
             (&UniformValue::DoubleVec3(_), UniformType::DoubleVec3) => true,
             (&UniformValue::DoubleVec4(_), UniformType::DoubleVec4) => true,
+            (&UniformValue::Int64(_), UniformType::Int64) => true,
+            (&UniformValue::Int64Vec2(_), UniformType::Int64Vec2) => true,
+            (&UniformValue::Int64Vec3(_), UniformType::Int64Vec3) => true,
+            (&UniformValue::Int64Vec4(_), UniformType::Int64Vec4) => true,
+            (&UniformValue::UnsignedInt64(_), UniformType::UnsignedInt64) => true,
+            (&UniformValue::UnsignedInt64Vec2(_), UniformType::UnsignedInt64Vec2) => true,
+            (&UniformValue::UnsignedInt64Vec3(_), UniformType::UnsignedInt64Vec3) => true,
+            (&UniformValue::UnsignedInt64Vec4(_), UniformType::UnsignedInt64Vec4) => true,
             (&UniformValue::Texture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::CompressedTexture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::SrgbTexture1d(_, _), UniformType::Sampler1d) => true,
@@ -370,6 +450,7 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::BufferTexture(tex), UniformType::USamplerBuffer) => {
                 tex.get_texture_type() == texture::buffer_texture::BufferTextureType::Unsigned
             },
+            (&UniformValue::ExternalTexture(..), UniformType::SamplerExternalOes) => true,
             (&UniformValue::Texture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
             (&UniformValue::SrgbTexture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
             (&UniformValue::IntegralTexture2dMultisample(..), UniformType::ISampler2dMultisample) => true,
@@ -446,6 +527,27 @@ macro_rules! impl_uniform_block_basic {
     );
 }
 
+/// Checks at compile time that a matrix with `C` columns and `R` rows exists in GLSL.
+struct MatrixDimensions<const C: usize, const R: usize>;
+
+impl<const C: usize, const R: usize> MatrixDimensions<C, R> {
+    const VALID: () = assert!(matches!(C, 2..=4) && matches!(R, 2..=4),
+                              "matrices must have between 2 and 4 columns and rows");
+}
+
+/// Copies a matrix into an array of the same dimensions, whose dimensions are known by the
+/// caller.
+#[inline]
+fn convert_matrix<T, const C: usize, const R: usize, const C2: usize, const R2: usize>(
+    matrix: &[[T; R]; C]) -> [[T; R2]; C2] where T: Copy + Default
+{
+    let mut result = [[T::default(); R2]; C2];
+    for (dest, column) in result.iter_mut().zip(matrix.iter()) {
+        dest.copy_from_slice(column);
+    }
+    result
+}
+
 impl AsUniformValue for i8 {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -490,8 +592,6 @@ impl AsUniformValue for [i32; 2] {
     }
 }
 
-impl_uniform_block_basic!([i32; 2], UniformType::IntVec2);
-
 impl AsUniformValue for (i32, i32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -508,8 +608,6 @@ impl AsUniformValue for [i32; 3] {
     }
 }
 
-impl_uniform_block_basic!([i32; 3], UniformType::IntVec3);
-
 impl AsUniformValue for (i32, i32, i32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -526,8 +624,6 @@ impl AsUniformValue for [i32; 4] {
     }
 }
 
-impl_uniform_block_basic!([i32; 4], UniformType::IntVec4);
-
 impl AsUniformValue for (i32, i32, i32, i32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -553,8 +649,6 @@ impl AsUniformValue for [u32; 2] {
     }
 }
 
-impl_uniform_block_basic!([u32; 2], UniformType::UnsignedIntVec2);
-
 impl AsUniformValue for (u32, u32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -571,8 +665,6 @@ impl AsUniformValue for [u32; 3] {
     }
 }
 
-impl_uniform_block_basic!([u32; 3], UniformType::UnsignedIntVec3);
-
 impl AsUniformValue for (u32, u32, u32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -589,8 +681,6 @@ impl AsUniformValue for [u32; 4] {
     }
 }
 
-impl_uniform_block_basic!([u32; 4], UniformType::UnsignedIntVec4);
-
 impl AsUniformValue for (u32, u32, u32, u32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -616,8 +706,6 @@ impl AsUniformValue for [bool; 2] {
     }
 }
 
-impl_uniform_block_basic!([bool; 2], UniformType::BoolVec2);
-
 impl AsUniformValue for (bool, bool) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -634,8 +722,6 @@ impl AsUniformValue for [bool; 3] {
     }
 }
 
-impl_uniform_block_basic!([bool; 3], UniformType::BoolVec3);
-
 impl AsUniformValue for (bool, bool, bool) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -652,8 +738,6 @@ impl AsUniformValue for [bool; 4] {
     }
 }
 
-impl_uniform_block_basic!([bool; 4], UniformType::BoolVec4);
-
 impl AsUniformValue for (bool, bool, bool, bool) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -672,33 +756,61 @@ impl AsUniformValue for f32 {
 
 impl_uniform_block_basic!(f32, UniformType::Float);
 
-impl AsUniformValue for [[f32; 2]; 2] {
+/// Column-major matrix with `C` columns of `R` rows, for example `[[f32; 3]; 2]` for a
+/// `mat2x3`.
+///
+/// Using a matrix whose dimensions aren't between 2 and 4 is a compilation error.
+impl<const C: usize, const R: usize> AsUniformValue for [[f32; R]; C] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
-        UniformValue::Mat2(*self)
+        let () = MatrixDimensions::<C, R>::VALID;
+
+        match (C, R) {
+            (2, 2) => UniformValue::Mat2(convert_matrix(self)),
+            (3, 3) => UniformValue::Mat3(convert_matrix(self)),
+            (4, 4) => UniformValue::Mat4(convert_matrix(self)),
+            (2, 3) => UniformValue::Mat2x3(convert_matrix(self)),
+            (2, 4) => UniformValue::Mat2x4(convert_matrix(self)),
+            (3, 2) => UniformValue::Mat3x2(convert_matrix(self)),
+            (3, 4) => UniformValue::Mat3x4(convert_matrix(self)),
+            (4, 2) => UniformValue::Mat4x2(convert_matrix(self)),
+            (4, 3) => UniformValue::Mat4x3(convert_matrix(self)),
+            _ => unreachable!(),
+        }
     }
 }
 
-impl_uniform_block_basic!([[f32; 2]; 2], UniformType::FloatMat2);
-
-impl AsUniformValue for [[f32; 3]; 3] {
+/// Wraps a row-major square matrix, for example a matrix of a math library that stores rows.
+///
+/// The matrix is passed to OpenGL as it is, and OpenGL transposes it when setting the uniform,
+/// which avoids transposing it on the CPU. OpenGL ES 2.0 doesn't support this, in which case
+/// glium transposes the matrix itself.
+///
+/// ```no_run
+/// # fn example(rows: [[f32; 4]; 4]) {
+/// use glium::uniforms::Transposed;
+///
+/// let uniforms = glium::uniform! { matrix: Transposed(rows) };
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transposed<T>(pub T);
+
+/// Only 2x2, 3x3 and 4x4 matrices can be transposed. Using another size is a compilation error.
+impl<const N: usize> AsUniformValue for Transposed<[[f32; N]; N]> {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
-        UniformValue::Mat3(*self)
-    }
-}
+        let () = MatrixDimensions::<N, N>::VALID;
 
-impl_uniform_block_basic!([[f32; 3]; 3], UniformType::FloatMat3);
-
-impl AsUniformValue for [[f32; 4]; 4] {
-    #[inline]
-    fn as_uniform_value(&self) -> UniformValue<'_> {
-        UniformValue::Mat4(*self)
+        match N {
+            2 => UniformValue::Mat2Transposed(convert_matrix(&self.0)),
+            3 => UniformValue::Mat3Transposed(convert_matrix(&self.0)),
+            4 => UniformValue::Mat4Transposed(convert_matrix(&self.0)),
+            _ => unreachable!(),
+        }
     }
 }
 
-impl_uniform_block_basic!([[f32; 4]; 4], UniformType::FloatMat4);
-
 impl AsUniformValue for (f32, f32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -733,8 +845,6 @@ impl AsUniformValue for [f32; 2] {
     }
 }
 
-impl_uniform_block_basic!([f32; 2], UniformType::FloatVec2);
-
 impl AsUniformValue for [f32; 3] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -742,8 +852,6 @@ impl AsUniformValue for [f32; 3] {
     }
 }
 
-impl_uniform_block_basic!([f32; 3], UniformType::FloatVec3);
-
 impl AsUniformValue for [f32; 4] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -751,9 +859,6 @@ impl AsUniformValue for [f32; 4] {
     }
 }
 
-impl_uniform_block_basic!([f32; 4], UniformType::FloatVec4);
-
-//TODO bool, i32, u32 and f64 should also be implemented as cgmath and nalgebra variants (i.e. nalgebra::Vec3<f64>).
 // Start of double type variants
 impl AsUniformValue for f64 {
     #[inline]
@@ -764,6 +869,30 @@ impl AsUniformValue for f64 {
 
 impl_uniform_block_basic!(f64, UniformType::Double);
 
+/// Column-major matrix with `C` columns of `R` rows, for example `[[f64; 3]; 2]` for a
+/// `dmat2x3`.
+///
+/// Using a matrix whose dimensions aren't between 2 and 4 is a compilation error.
+impl<const C: usize, const R: usize> AsUniformValue for [[f64; R]; C] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        let () = MatrixDimensions::<C, R>::VALID;
+
+        match (C, R) {
+            (2, 2) => UniformValue::DoubleMat2(convert_matrix(self)),
+            (3, 3) => UniformValue::DoubleMat3(convert_matrix(self)),
+            (4, 4) => UniformValue::DoubleMat4(convert_matrix(self)),
+            (2, 3) => UniformValue::DoubleMat2x3(convert_matrix(self)),
+            (2, 4) => UniformValue::DoubleMat2x4(convert_matrix(self)),
+            (3, 2) => UniformValue::DoubleMat3x2(convert_matrix(self)),
+            (3, 4) => UniformValue::DoubleMat3x4(convert_matrix(self)),
+            (4, 2) => UniformValue::DoubleMat4x2(convert_matrix(self)),
+            (4, 3) => UniformValue::DoubleMat4x3(convert_matrix(self)),
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl AsUniformValue for [f64; 2] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -771,8 +900,6 @@ impl AsUniformValue for [f64; 2] {
     }
 }
 
-impl_uniform_block_basic!([f64; 2], UniformType::DoubleVec2);
-
 impl AsUniformValue for (f64, f64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -789,8 +916,6 @@ impl AsUniformValue for [f64; 3] {
     }
 }
 
-impl_uniform_block_basic!([f64; 3], UniformType::DoubleVec3);
-
 impl AsUniformValue for (f64, f64, f64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -807,8 +932,6 @@ impl AsUniformValue for [f64; 4] {
     }
 }
 
-impl_uniform_block_basic!([f64; 4], UniformType::DoubleVec4);
-
 impl AsUniformValue for (f64, f64, f64, f64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -818,33 +941,6 @@ impl AsUniformValue for (f64, f64, f64, f64) {
 
 impl_uniform_block_basic!((f64, f64, f64, f64), UniformType::DoubleVec4);
 
-impl AsUniformValue for [[f64; 2]; 2] {
-    #[inline]
-    fn as_uniform_value(&self) -> UniformValue<'_> {
-        UniformValue::DoubleMat2(*self)
-    }
-}
-
-impl_uniform_block_basic!([[f64; 2]; 2], UniformType::DoubleMat2);
-
-impl AsUniformValue for [[f64; 3]; 3] {
-    #[inline]
-    fn as_uniform_value(&self) -> UniformValue<'_> {
-        UniformValue::DoubleMat3(*self)
-    }
-}
-
-impl_uniform_block_basic!([[f64; 3]; 3], UniformType::DoubleMat3);
-
-impl AsUniformValue for [[f64; 4]; 4] {
-    #[inline]
-    fn as_uniform_value(&self) -> UniformValue<'_> {
-        UniformValue::DoubleMat4(*self)
-    }
-}
-
-impl_uniform_block_basic!([[f64; 4]; 4], UniformType::DoubleMat4);
-
 impl AsUniformValue for i64 {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -852,7 +948,7 @@ impl AsUniformValue for i64 {
     }
 }
 
-impl_uniform_block_basic!(i64, UniformType::Int);
+impl_uniform_block_basic!(i64, UniformType::Int64);
 
 impl AsUniformValue for [i64; 2] {
     #[inline]
@@ -861,8 +957,6 @@ impl AsUniformValue for [i64; 2] {
     }
 }
 
-impl_uniform_block_basic!([i64; 2], UniformType::Int64Vec2);
-
 impl AsUniformValue for (i64, i64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -879,8 +973,6 @@ impl AsUniformValue for [i64; 3] {
     }
 }
 
-impl_uniform_block_basic!([i64; 3], UniformType::Int64Vec3);
-
 impl AsUniformValue for (i64, i64, i64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -897,8 +989,6 @@ impl AsUniformValue for [i64; 4] {
     }
 }
 
-impl_uniform_block_basic!([i64; 4], UniformType::Int64Vec4);
-
 impl AsUniformValue for (i64, i64, i64, i64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -924,8 +1014,6 @@ impl AsUniformValue for [u64; 2] {
     }
 }
 
-impl_uniform_block_basic!([u64; 2], UniformType::UnsignedInt64Vec2);
-
 impl AsUniformValue for (u64, u64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -942,8 +1030,6 @@ impl AsUniformValue for [u64; 3] {
     }
 }
 
-impl_uniform_block_basic!([u64; 3], UniformType::UnsignedInt64Vec3);
-
 impl AsUniformValue for (u64, u64, u64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -960,8 +1046,6 @@ impl AsUniformValue for [u64; 4] {
     }
 }
 
-impl_uniform_block_basic!([u64; 4], UniformType::UnsignedInt64Vec4);
-
 impl AsUniformValue for (u64, u64, u64, u64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -0,0 +1,524 @@
+/*!
+Helpers that don't wrap an OpenGL feature but make glium easier to use.
+
+# Frame budget
+
+Expensive GPU work that isn't needed immediately, like baking lightmaps with compute shaders or
+generating the mipmaps of many textures, can freeze the application if it is all submitted
+at once. If a single submission takes too long, the operating system may even consider that the
+GPU is hung and reset the driver.
+
+A `FrameBudget` holds a queue of such jobs and runs as many of them per frame as fit in a given
+amount of GPU time. The GPU time of the jobs is measured with timestamp queries.
+
+# Frame pacing
+
+Animations look smoother when they are sampled at the time the frame will be shown rather than
+at the time it is rendered, and input feels more responsive when rendering starts as late as
+possible before the vertical blank. A `FramePacer` predicts the next vertical blanks from the
+refresh rate of the monitor and the times at which the previous frames were presented.
+
+*/
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::debug::TimestampQuery;
+
+/// A job of a `FrameBudget`.
+type Job<'a> = Box<dyn FnOnce(&Rc<Context>) + 'a>;
+
+/// Queue of GPU jobs that are spread across frames so that each frame spends at most a given
+/// amount of GPU time on them.
+///
+/// The jobs should be chunks of work of similar cost, for example one dispatch over a tile of
+/// a large texture. The time a job takes on the GPU is estimated from the previous jobs, and
+/// `run_frame` stops once the estimate of the next job exceeds what is left of the budget. At
+/// least one job is run each frame, so a job that takes longer than the budget should be split
+/// into smaller ones.
+///
+/// The commands are flushed after each job, so that the driver submits them separately.
+///
+/// If the backend doesn't support timestamp queries, a single job is run per frame.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glium::uniform;
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>, bake: glium::program::ComputeShader)
+/// #     where T: SurfaceTypeTrait + ResizeableSurface {
+/// use std::time::Duration;
+/// use glium::util::FrameBudget;
+///
+/// // baking a large texture one tile at a time
+/// let mut budget = FrameBudget::new(&display, Duration::from_millis(2));
+/// for tile in 0 .. 256u32 {
+///     let bake = &bake;
+///     budget.push(move |_| bake.execute(uniform! { tile: tile }, 16, 16, 1));
+/// }
+///
+/// // once per frame
+/// budget.run_frame();
+/// # }
+/// ```
+pub struct FrameBudget<'a> {
+    context: Rc<Context>,
+    budget: Duration,
+    jobs: VecDeque<Job<'a>>,
+    // queries around the jobs whose GPU time hasn't been retrieved yet
+    pending: VecDeque<(TimestampQuery, TimestampQuery)>,
+    estimate: Option<Duration>,
+}
+
+impl<'a> FrameBudget<'a> {
+    /// Builds an empty queue that spends at most `budget` of GPU time per frame.
+    pub fn new<F>(facade: &F, budget: Duration) -> FrameBudget<'a> where F: ?Sized + Facade {
+        FrameBudget {
+            context: facade.get_context().clone(),
+            budget,
+            jobs: VecDeque::new(),
+            pending: VecDeque::new(),
+            estimate: None,
+        }
+    }
+
+    /// Adds a job at the end of the queue.
+    #[inline]
+    pub fn push<J>(&mut self, job: J) where J: FnOnce(&Rc<Context>) + 'a {
+        self.jobs.push_back(Box::new(job));
+    }
+
+    /// Runs the jobs at the front of the queue that fit in the budget. Call this once per frame.
+    ///
+    /// Returns the number of jobs that have been run.
+    pub fn run_frame(&mut self) -> usize {
+        self.update_estimate();
+
+        let mut spent = Duration::new(0, 0);
+        let mut count = 0;
+
+        loop {
+            if count >= 1 {
+                match self.estimate {
+                    Some(estimate) if spent + estimate <= self.budget => (),
+                    _ => break,
+                }
+            }
+
+            let job = match self.jobs.pop_front() {
+                Some(job) => job,
+                None => break,
+            };
+
+            let before = TimestampQuery::new(&self.context);
+            job(&self.context);
+            let after = TimestampQuery::new(&self.context);
+            self.context.flush();
+
+            if let (Some(before), Some(after)) = (before, after) {
+                self.pending.push_back((before, after));
+            }
+
+            spent += self.estimate.unwrap_or_default();
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Returns the number of jobs that haven't been run yet.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Returns true if all the jobs have been run.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Returns the GPU time spent per frame.
+    #[inline]
+    pub fn get_budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Changes the GPU time spent per frame.
+    #[inline]
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    /// Returns the estimate of the GPU time of a job, or `None` if no job has been measured yet.
+    #[inline]
+    pub fn get_estimated_job_time(&self) -> Option<Duration> {
+        self.estimate
+    }
+
+    /// Retrieves the GPU time of the jobs whose queries are available, without blocking.
+    fn update_estimate(&mut self) {
+        while let Some((before, after)) = self.pending.front() {
+            if !before.is_ready() || !after.is_ready() {
+                break;
+            }
+
+            let (before, after) = self.pending.pop_front().unwrap();
+            let sample = Duration::from_nanos(after.get().saturating_sub(before.get()));
+
+            // moving average, so that the estimate follows jobs whose cost changes slowly
+            self.estimate = Some(match self.estimate {
+                Some(estimate) => (estimate * 3 + sample) / 4,
+                None => sample,
+            });
+        }
+    }
+}
+
+impl fmt::Debug for FrameBudget<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        fmt.debug_struct("FrameBudget")
+            .field("budget", &self.budget)
+            .field("jobs", &self.jobs.len())
+            .field("estimate", &self.estimate)
+            .finish()
+    }
+}
+
+/// Predicts when the next frames will be presented.
+///
+/// Call `begin_frame` before drawing, `frame_submitted` right before `Frame::finish` and
+/// `end_frame` right after it. The time between `begin_frame` and `frame_submitted` is the cost
+/// of a frame. With vsync, swapping the buffers blocks until a vertical blank, so the time at
+/// which `finish` returns is used as an estimate of when the frame was presented.
+///
+/// This estimate is off by the time the compositor takes. Platforms that report the exact time
+/// of presentation, like the `wp_presentation` protocol of Wayland or `glXGetSyncValuesOML`,
+/// aren't exposed by glutin, but their timestamps can be passed to `frame_presented`, which
+/// takes precedence over the estimate.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>, monitor: winit::monitor::MonitorHandle)
+/// #     where T: SurfaceTypeTrait + ResizeableSurface {
+/// use glium::util::FramePacer;
+///
+/// let millihertz = monitor.refresh_rate_millihertz().unwrap_or(60_000);
+/// let mut pacer = FramePacer::from_refresh_rate_millihertz(millihertz);
+///
+/// // once per frame
+/// pacer.wait_for_deadline();
+/// let presentation = pacer.begin_frame();
+/// // ... update the animations to `presentation` ...
+/// let mut frame = display.draw();
+/// frame.clear_color(0.0, 0.0, 0.0, 1.0);
+/// pacer.frame_submitted();
+/// frame.finish().unwrap();
+/// pacer.end_frame();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    refresh_interval: Duration,
+    // latest known vertical blank
+    vblank: Instant,
+    // true if `vblank` was reported by the platform instead of estimated
+    vblank_reported: bool,
+    // start of the frame being drawn
+    frame_start: Option<Instant>,
+    frame_time: Option<Duration>,
+    slack: Duration,
+}
+
+impl FramePacer {
+    /// Builds a pacer for a monitor whose vertical blanks are separated by `refresh_interval`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `refresh_interval` is zero.
+    pub fn new(refresh_interval: Duration) -> FramePacer {
+        assert!(refresh_interval > Duration::new(0, 0), "The refresh interval can't be zero");
+
+        FramePacer {
+            refresh_interval,
+            vblank: Instant::now(),
+            vblank_reported: false,
+            frame_start: None,
+            frame_time: None,
+            slack: Duration::from_millis(1),
+        }
+    }
+
+    /// Builds a pacer for a monitor with the given refresh rate, in the unit returned by
+    /// `MonitorHandle::refresh_rate_millihertz` in winit.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `millihertz` is zero.
+    #[inline]
+    pub fn from_refresh_rate_millihertz(millihertz: u32) -> FramePacer {
+        assert!(millihertz != 0, "The refresh rate can't be zero");
+        FramePacer::new(Duration::from_nanos(1_000_000_000_000 / millihertz as u64))
+    }
+
+    /// Marks the beginning of a frame and returns the time at which it is expected to be
+    /// presented, which is the time at which the animations should be sampled.
+    #[inline]
+    pub fn begin_frame(&mut self) -> Instant {
+        self.begin_frame_at(Instant::now())
+    }
+
+    fn begin_frame_at(&mut self, now: Instant) -> Instant {
+        self.frame_start = Some(now);
+        self.predicted_presentation_after(now)
+    }
+
+    /// Marks the end of the work of a frame. Must be called right before `Frame::finish`.
+    ///
+    /// The swap isn't part of the cost of a frame: with vsync, it waits for the vertical blank,
+    /// and including this wait would push the deadline of the next frame one refresh earlier.
+    #[inline]
+    pub fn frame_submitted(&mut self) {
+        self.frame_submitted_at(Instant::now())
+    }
+
+    fn frame_submitted_at(&mut self, now: Instant) {
+        if let Some(start) = self.frame_start.take() {
+            let sample = now.saturating_duration_since(start);
+
+            // moving average, the cost of a frame usually changes slowly
+            self.frame_time = Some(match self.frame_time {
+                Some(estimate) => (estimate * 3 + sample) / 4,
+                None => sample,
+            });
+        }
+    }
+
+    /// Marks the end of a frame. Must be called right after `Frame::finish` returns.
+    ///
+    /// Unless the platform reported the presentation times with `frame_presented`, the current
+    /// time is used as the time of the latest vertical blank.
+    #[inline]
+    pub fn end_frame(&mut self) {
+        self.end_frame_at(Instant::now())
+    }
+
+    fn end_frame_at(&mut self, now: Instant) {
+        // a frame without `frame_submitted` isn't measured
+        self.frame_start = None;
+
+        if !self.vblank_reported {
+            self.vblank = now;
+        }
+    }
+
+    /// Reports the time at which a frame has been presented, as measured by the platform.
+    ///
+    /// If the platform also reports the refresh interval, passing it corrects the nominal
+    /// refresh rate. Once this has been called, `end_frame` doesn't estimate the presentation
+    /// times anymore.
+    pub fn frame_presented(&mut self, presented: Instant, refresh_interval: Option<Duration>) {
+        if let Some(interval) = refresh_interval {
+            if interval > Duration::new(0, 0) {
+                self.refresh_interval = interval;
+            }
+        }
+
+        if presented > self.vblank || !self.vblank_reported {
+            self.vblank = presented;
+        }
+        self.vblank_reported = true;
+    }
+
+    /// Returns the predicted time of the first vertical blank after now.
+    #[inline]
+    pub fn next_vblank(&self) -> Instant {
+        self.next_vblank_after(Instant::now())
+    }
+
+    /// Returns the predicted time of the first vertical blank strictly after `time`.
+    pub fn next_vblank_after(&self, time: Instant) -> Instant {
+        let interval = self.refresh_interval.as_nanos();
+
+        if time < self.vblank {
+            let periods = ((self.vblank - time).as_nanos() - 1) / interval;
+            self.vblank - Duration::from_nanos((periods * interval) as u64)
+        } else {
+            let periods = (time - self.vblank).as_nanos() / interval + 1;
+            self.vblank + Duration::from_nanos((periods * interval) as u64)
+        }
+    }
+
+    /// Returns the time at which a frame that starts being drawn at `time` is expected to be
+    /// presented, taking into account the measured duration of the previous frames.
+    pub fn predicted_presentation_after(&self, time: Instant) -> Instant {
+        let ready = time + self.frame_time.unwrap_or_default() + self.slack;
+        let vblank = self.next_vblank_after(time);
+
+        if ready <= vblank {
+            vblank
+        } else {
+            self.next_vblank_after(ready)
+        }
+    }
+
+    /// Returns the latest time at which the next frame can start being drawn and still be
+    /// presented at the next vertical blank.
+    ///
+    /// Starting the frame at this time instead of right after the previous one reduces the
+    /// latency between the input and its presentation.
+    #[inline]
+    pub fn deadline(&self) -> Instant {
+        self.deadline_at(Instant::now())
+    }
+
+    fn deadline_at(&self, now: Instant) -> Instant {
+        let presentation = self.predicted_presentation_after(now);
+        let budget = self.frame_time.unwrap_or_default() + self.slack;
+
+        match presentation.checked_sub(budget) {
+            Some(deadline) if deadline > now => deadline,
+            _ => now,
+        }
+    }
+
+    /// Sleeps until `deadline`.
+    ///
+    /// The thread may wake up a bit late, which is why the deadline keeps a safety margin that
+    /// can be adjusted with `set_slack`.
+    pub fn wait_for_deadline(&self) {
+        let deadline = self.deadline();
+        let now = Instant::now();
+
+        if deadline > now {
+            thread::sleep(deadline - now);
+        }
+    }
+
+    /// Returns the duration between two vertical blanks.
+    #[inline]
+    pub fn get_refresh_interval(&self) -> Duration {
+        self.refresh_interval
+    }
+
+    /// Returns the average time between `begin_frame` and `frame_submitted`, or `None` if no
+    /// frame has been measured yet.
+    #[inline]
+    pub fn get_estimated_frame_time(&self) -> Option<Duration> {
+        self.frame_time
+    }
+
+    /// Returns the safety margin that is added to the estimated frame time. The default is 1ms.
+    #[inline]
+    pub fn get_slack(&self) -> Duration {
+        self.slack
+    }
+
+    /// Changes the safety margin that is added to the estimated frame time.
+    #[inline]
+    pub fn set_slack(&mut self, slack: Duration) {
+        self.slack = slack;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FramePacer;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn next_vblank() {
+        let mut pacer = FramePacer::new(Duration::from_millis(10));
+        let base = Instant::now();
+        pacer.frame_presented(base, None);
+
+        assert_eq!(pacer.next_vblank_after(base), base + Duration::from_millis(10));
+        assert_eq!(pacer.next_vblank_after(base + Duration::from_millis(3)),
+                   base + Duration::from_millis(10));
+        assert_eq!(pacer.next_vblank_after(base + Duration::from_millis(25)),
+                   base + Duration::from_millis(30));
+        assert_eq!(pacer.next_vblank_after(base - Duration::from_millis(10)), base);
+        assert_eq!(pacer.next_vblank_after(base - Duration::from_millis(15)),
+                   base - Duration::from_millis(10));
+    }
+
+    #[test]
+    fn reported_interval() {
+        let mut pacer = FramePacer::from_refresh_rate_millihertz(60_000);
+        assert_eq!(pacer.get_refresh_interval(), Duration::from_nanos(16_666_666));
+
+        let base = Instant::now();
+        pacer.frame_presented(base, Some(Duration::from_millis(8)));
+        assert_eq!(pacer.get_refresh_interval(), Duration::from_millis(8));
+        assert_eq!(pacer.next_vblank_after(base + Duration::from_millis(9)),
+                   base + Duration::from_millis(16));
+
+        // an older timestamp doesn't move the phase back
+        pacer.frame_presented(base - Duration::from_millis(3), None);
+        assert_eq!(pacer.next_vblank_after(base), base + Duration::from_millis(8));
+    }
+
+    #[test]
+    fn late_frame_skips_vblank() {
+        let mut pacer = FramePacer::new(Duration::from_millis(10));
+        let base = Instant::now();
+        pacer.frame_presented(base, None);
+        pacer.set_slack(Duration::from_millis(0));
+
+        assert_eq!(pacer.predicted_presentation_after(base + Duration::from_millis(2)),
+                   base + Duration::from_millis(10));
+
+        pacer.frame_time = Some(Duration::from_millis(12));
+        assert_eq!(pacer.predicted_presentation_after(base + Duration::from_millis(2)),
+                   base + Duration::from_millis(20));
+    }
+
+    #[test]
+    fn vsync_loop() {
+        // 60Hz with 2ms of work per frame, the swap returns at the next vertical blank
+        let interval = Duration::from_nanos(16_666_667);
+        let work = Duration::from_millis(2);
+        let mut pacer = FramePacer::new(interval);
+        let base = Instant::now();
+        let vblank_after = |time: Instant| {
+            let periods = (time - base).as_nanos() / interval.as_nanos() + 1;
+            base + Duration::from_nanos((periods * interval.as_nanos()) as u64)
+        };
+
+        let mut now = base;
+        pacer.end_frame_at(now);
+        let mut presentations = Vec::new();
+        for frame in 0 .. 20 {
+            now = now.max(pacer.deadline_at(now));
+            let predicted = pacer.begin_frame_at(now);
+            now += work;
+            pacer.frame_submitted_at(now);
+            now = vblank_after(now);
+            pacer.end_frame_at(now);
+            presentations.push(now);
+
+            // the cost of the first frame is unknown
+            if frame >= 1 {
+                assert_eq!(predicted, now);
+            }
+        }
+
+        assert_eq!(pacer.get_estimated_frame_time(), Some(work));
+        // no vertical blank is missed once the frame time is known
+        for pair in presentations[1 ..].windows(2) {
+            assert_eq!(pair[1] - pair[0], interval);
+        }
+
+        // the frames start as late as possible
+        let deadline = pacer.deadline_at(now);
+        assert_eq!(vblank_after(now) - deadline, work + pacer.get_slack());
+    }
+}
@@ -446,6 +446,29 @@ pub struct VertexBufferAny {
 }
 
 impl VertexBufferAny {
+    /// Builds a vertex buffer from raw bytes, whose layout is only known at runtime, for example
+    /// because it is read from a model file.
+    ///
+    /// `elements_size` is the number of bytes between two consecutive vertices. The length of
+    /// `data` must be a multiple of it.
+    ///
+    /// # Safety
+    ///
+    /// The bindings must describe attributes that fit within `elements_size` bytes.
+    pub unsafe fn new_raw<F: Facade + ?Sized>(facade: &F, data: &[u8], bindings: VertexFormat,
+                                              elements_size: usize)
+                                              -> Result<VertexBufferAny, CreationError>
+    {
+        if bindings.iter().any(|(_, _, _, ty, _)| !ty.is_supported(facade)) {
+            return Err(CreationError::FormatNotSupported);
+        }
+
+        let mut buffer: BufferAny = Buffer::new(facade, data, BufferType::ArrayBuffer,
+                                                BufferMode::Default)?.into();
+        buffer.set_elements_size(elements_size);
+        Ok(VertexBufferAny { buffer, bindings })
+    }
+
     /// Returns the number of bytes between two consecutive elements in the buffer.
     #[inline]
     pub fn get_elements_size(&self) -> usize {
@@ -10,6 +10,8 @@ use crate::CapabilitiesSource;
 use cgmath;
 #[cfg(feature = "nalgebra")]
 use nalgebra;
+#[cfg(feature = "glam")]
+use glam;
 
 #[allow(missing_docs)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -998,441 +1000,521 @@ unsafe impl Attribute for cgmath::Matrix4<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i8> {
+unsafe impl Attribute for nalgebra::Point1<i8> {
     const TYPE: AttributeType = AttributeType::I8;
 }
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i8> {
+unsafe impl Attribute for nalgebra::Point2<i8> {
     const TYPE: AttributeType = AttributeType::I8I8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i8> {
+unsafe impl Attribute for nalgebra::Point3<i8> {
     const TYPE: AttributeType = AttributeType::I8I8I8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i8> {
+unsafe impl Attribute for nalgebra::Point4<i8> {
     const TYPE: AttributeType = AttributeType::I8I8I8I8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i8> {
+unsafe impl Attribute for nalgebra::Vector1<i8> {
     const TYPE: AttributeType = AttributeType::I8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i8> {
+unsafe impl Attribute for nalgebra::Vector2<i8> {
     const TYPE: AttributeType = AttributeType::I8I8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i8> {
+unsafe impl Attribute for nalgebra::Vector3<i8> {
     const TYPE: AttributeType = AttributeType::I8I8I8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i8> {
+unsafe impl Attribute for nalgebra::Vector4<i8> {
     const TYPE: AttributeType = AttributeType::I8I8I8I8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u8> {
+unsafe impl Attribute for nalgebra::Point1<u8> {
     const TYPE: AttributeType = AttributeType::U8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u8> {
+unsafe impl Attribute for nalgebra::Point2<u8> {
     const TYPE: AttributeType = AttributeType::U8U8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u8> {
+unsafe impl Attribute for nalgebra::Point3<u8> {
     const TYPE: AttributeType = AttributeType::U8U8U8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u8> {
+unsafe impl Attribute for nalgebra::Point4<u8> {
     const TYPE: AttributeType = AttributeType::U8U8U8U8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u8> {
+unsafe impl Attribute for nalgebra::Vector1<u8> {
     const TYPE: AttributeType = AttributeType::U8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u8> {
+unsafe impl Attribute for nalgebra::Vector2<u8> {
     const TYPE: AttributeType = AttributeType::U8U8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u8> {
+unsafe impl Attribute for nalgebra::Vector3<u8> {
     const TYPE: AttributeType = AttributeType::U8U8U8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u8> {
+unsafe impl Attribute for nalgebra::Vector4<u8> {
     const TYPE: AttributeType = AttributeType::U8U8U8U8;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i16> {
+unsafe impl Attribute for nalgebra::Point1<i16> {
     const TYPE: AttributeType = AttributeType::I16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i16> {
+unsafe impl Attribute for nalgebra::Point2<i16> {
     const TYPE: AttributeType = AttributeType::I16I16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i16> {
+unsafe impl Attribute for nalgebra::Point3<i16> {
     const TYPE: AttributeType = AttributeType::I16I16I16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i16> {
+unsafe impl Attribute for nalgebra::Point4<i16> {
     const TYPE: AttributeType = AttributeType::I16I16I16I16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i16> {
+unsafe impl Attribute for nalgebra::Vector1<i16> {
     const TYPE: AttributeType = AttributeType::I16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i16> {
+unsafe impl Attribute for nalgebra::Vector2<i16> {
     const TYPE: AttributeType = AttributeType::I16I16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i16> {
+unsafe impl Attribute for nalgebra::Vector3<i16> {
     const TYPE: AttributeType = AttributeType::I16I16I16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i16> {
+unsafe impl Attribute for nalgebra::Vector4<i16> {
     const TYPE: AttributeType = AttributeType::I16I16I16I16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u16> {
+unsafe impl Attribute for nalgebra::Point1<u16> {
     const TYPE: AttributeType = AttributeType::U16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u16> {
+unsafe impl Attribute for nalgebra::Point2<u16> {
     const TYPE: AttributeType = AttributeType::U16U16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u16> {
+unsafe impl Attribute for nalgebra::Point3<u16> {
     const TYPE: AttributeType = AttributeType::U16U16U16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u16> {
+unsafe impl Attribute for nalgebra::Point4<u16> {
     const TYPE: AttributeType = AttributeType::U16U16U16U16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u16> {
+unsafe impl Attribute for nalgebra::Vector1<u16> {
     const TYPE: AttributeType = AttributeType::U16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u16> {
+unsafe impl Attribute for nalgebra::Vector2<u16> {
     const TYPE: AttributeType = AttributeType::U16U16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u16> {
+unsafe impl Attribute for nalgebra::Vector3<u16> {
     const TYPE: AttributeType = AttributeType::U16U16U16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u16> {
+unsafe impl Attribute for nalgebra::Vector4<u16> {
     const TYPE: AttributeType = AttributeType::U16U16U16U16;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i32> {
+unsafe impl Attribute for nalgebra::Point1<i32> {
     const TYPE: AttributeType = AttributeType::I32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i32> {
+unsafe impl Attribute for nalgebra::Point2<i32> {
     const TYPE: AttributeType = AttributeType::I32I32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i32> {
+unsafe impl Attribute for nalgebra::Point3<i32> {
     const TYPE: AttributeType = AttributeType::I32I32I32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i32> {
+unsafe impl Attribute for nalgebra::Point4<i32> {
     const TYPE: AttributeType = AttributeType::I32I32I32I32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i32> {
+unsafe impl Attribute for nalgebra::Vector1<i32> {
     const TYPE: AttributeType = AttributeType::I32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i32> {
+unsafe impl Attribute for nalgebra::Vector2<i32> {
     const TYPE: AttributeType = AttributeType::I32I32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i32> {
+unsafe impl Attribute for nalgebra::Vector3<i32> {
     const TYPE: AttributeType = AttributeType::I32I32I32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i32> {
+unsafe impl Attribute for nalgebra::Vector4<i32> {
     const TYPE: AttributeType = AttributeType::I32I32I32I32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u32> {
+unsafe impl Attribute for nalgebra::Point1<u32> {
     const TYPE: AttributeType = AttributeType::U32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u32> {
+unsafe impl Attribute for nalgebra::Point2<u32> {
     const TYPE: AttributeType = AttributeType::U32U32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u32> {
+unsafe impl Attribute for nalgebra::Point3<u32> {
     const TYPE: AttributeType = AttributeType::U32U32U32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u32> {
+unsafe impl Attribute for nalgebra::Point4<u32> {
     const TYPE: AttributeType = AttributeType::U32U32U32U32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u32> {
+unsafe impl Attribute for nalgebra::Vector1<u32> {
     const TYPE: AttributeType = AttributeType::U32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u32> {
+unsafe impl Attribute for nalgebra::Vector2<u32> {
     const TYPE: AttributeType = AttributeType::U32U32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u32> {
+unsafe impl Attribute for nalgebra::Vector3<u32> {
     const TYPE: AttributeType = AttributeType::U32U32U32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u32> {
+unsafe impl Attribute for nalgebra::Vector4<u32> {
     const TYPE: AttributeType = AttributeType::U32U32U32U32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i64> {
+unsafe impl Attribute for nalgebra::Point1<i64> {
     const TYPE: AttributeType = AttributeType::I64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i64> {
+unsafe impl Attribute for nalgebra::Point2<i64> {
     const TYPE: AttributeType = AttributeType::I64I64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i64> {
+unsafe impl Attribute for nalgebra::Point3<i64> {
     const TYPE: AttributeType = AttributeType::I64I64I64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i64> {
+unsafe impl Attribute for nalgebra::Point4<i64> {
     const TYPE: AttributeType = AttributeType::I64I64I64I64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i64> {
+unsafe impl Attribute for nalgebra::Vector1<i64> {
     const TYPE: AttributeType = AttributeType::I64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i64> {
+unsafe impl Attribute for nalgebra::Vector2<i64> {
     const TYPE: AttributeType = AttributeType::I64I64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i64> {
+unsafe impl Attribute for nalgebra::Vector3<i64> {
     const TYPE: AttributeType = AttributeType::I64I64I64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i64> {
+unsafe impl Attribute for nalgebra::Vector4<i64> {
     const TYPE: AttributeType = AttributeType::I64I64I64I64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u64> {
+unsafe impl Attribute for nalgebra::Point1<u64> {
     const TYPE: AttributeType = AttributeType::U64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u64> {
+unsafe impl Attribute for nalgebra::Point2<u64> {
     const TYPE: AttributeType = AttributeType::U64U64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u64> {
+unsafe impl Attribute for nalgebra::Point3<u64> {
     const TYPE: AttributeType = AttributeType::U64U64U64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u64> {
+unsafe impl Attribute for nalgebra::Point4<u64> {
     const TYPE: AttributeType = AttributeType::U64U64U64U64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u64> {
+unsafe impl Attribute for nalgebra::Vector1<u64> {
     const TYPE: AttributeType = AttributeType::U64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u64> {
+unsafe impl Attribute for nalgebra::Vector2<u64> {
     const TYPE: AttributeType = AttributeType::U64U64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u64> {
+unsafe impl Attribute for nalgebra::Vector3<u64> {
     const TYPE: AttributeType = AttributeType::U64U64U64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u64> {
+unsafe impl Attribute for nalgebra::Vector4<u64> {
     const TYPE: AttributeType = AttributeType::U64U64U64U64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<f32> {
+unsafe impl Attribute for nalgebra::Point1<f32> {
     const TYPE: AttributeType = AttributeType::F32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<f32> {
+unsafe impl Attribute for nalgebra::Point2<f32> {
     const TYPE: AttributeType = AttributeType::F32F32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<f32> {
+unsafe impl Attribute for nalgebra::Point3<f32> {
     const TYPE: AttributeType = AttributeType::F32F32F32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<f32> {
+unsafe impl Attribute for nalgebra::Point4<f32> {
     const TYPE: AttributeType = AttributeType::F32F32F32F32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<f32> {
+unsafe impl Attribute for nalgebra::Vector1<f32> {
     const TYPE: AttributeType = AttributeType::F32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<f32> {
+unsafe impl Attribute for nalgebra::Vector2<f32> {
     const TYPE: AttributeType = AttributeType::F32F32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<f32> {
+unsafe impl Attribute for nalgebra::Vector3<f32> {
     const TYPE: AttributeType = AttributeType::F32F32F32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<f32> {
+unsafe impl Attribute for nalgebra::Vector4<f32> {
     const TYPE: AttributeType = AttributeType::F32F32F32F32;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat1<f32> {
-    const TYPE: AttributeType = AttributeType::F32;
-}
-
-#[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat2<f32> {
+unsafe impl Attribute for nalgebra::Matrix2<f32> {
     const TYPE: AttributeType = AttributeType::F32x2x2;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat3<f32> {
+unsafe impl Attribute for nalgebra::Matrix3<f32> {
     const TYPE: AttributeType = AttributeType::F32x3x3;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat4<f32> {
+unsafe impl Attribute for nalgebra::Matrix4<f32> {
     const TYPE: AttributeType = AttributeType::F32x4x4;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<f64> {
+unsafe impl Attribute for nalgebra::Point1<f64> {
     const TYPE: AttributeType = AttributeType::F64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<f64> {
+unsafe impl Attribute for nalgebra::Point2<f64> {
     const TYPE: AttributeType = AttributeType::F64F64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<f64> {
+unsafe impl Attribute for nalgebra::Point3<f64> {
     const TYPE: AttributeType = AttributeType::F64F64F64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<f64> {
+unsafe impl Attribute for nalgebra::Point4<f64> {
     const TYPE: AttributeType = AttributeType::F64F64F64F64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<f64> {
+unsafe impl Attribute for nalgebra::Vector1<f64> {
     const TYPE: AttributeType = AttributeType::F64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<f64> {
+unsafe impl Attribute for nalgebra::Vector2<f64> {
     const TYPE: AttributeType = AttributeType::F64F64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<f64> {
+unsafe impl Attribute for nalgebra::Vector3<f64> {
     const TYPE: AttributeType = AttributeType::F64F64F64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<f64> {
+unsafe impl Attribute for nalgebra::Vector4<f64> {
     const TYPE: AttributeType = AttributeType::F64F64F64F64;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat1<f64> {
-    const TYPE: AttributeType = AttributeType::F64;
+unsafe impl Attribute for nalgebra::Matrix2<f64> {
+    const TYPE: AttributeType = AttributeType::F64x2x2;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat2<f64> {
-    const TYPE: AttributeType = AttributeType::F64x2x2;
+unsafe impl Attribute for nalgebra::Matrix3<f64> {
+    const TYPE: AttributeType = AttributeType::F64x3x3;
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat3<f64> {
+unsafe impl Attribute for nalgebra::Matrix4<f64> {
+    const TYPE: AttributeType = AttributeType::F64x4x4;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec2 {
+    const TYPE: AttributeType = AttributeType::F32F32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec3 {
+    const TYPE: AttributeType = AttributeType::F32F32F32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec4 {
+    const TYPE: AttributeType = AttributeType::F32F32F32F32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::IVec2 {
+    const TYPE: AttributeType = AttributeType::I32I32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::IVec3 {
+    const TYPE: AttributeType = AttributeType::I32I32I32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::IVec4 {
+    const TYPE: AttributeType = AttributeType::I32I32I32I32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::UVec2 {
+    const TYPE: AttributeType = AttributeType::U32U32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::UVec3 {
+    const TYPE: AttributeType = AttributeType::U32U32U32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::UVec4 {
+    const TYPE: AttributeType = AttributeType::U32U32U32U32;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat2 {
+    const TYPE: AttributeType = AttributeType::F32x2x2;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat3 {
+    const TYPE: AttributeType = AttributeType::F32x3x3;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat4 {
+    const TYPE: AttributeType = AttributeType::F32x4x4;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::DVec2 {
+    const TYPE: AttributeType = AttributeType::F64F64;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::DVec3 {
+    const TYPE: AttributeType = AttributeType::F64F64F64;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::DVec4 {
+    const TYPE: AttributeType = AttributeType::F64F64F64F64;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::DMat2 {
+    const TYPE: AttributeType = AttributeType::F64x2x2;
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::DMat3 {
     const TYPE: AttributeType = AttributeType::F64x3x3;
 }
 
-#[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat4<f64> {
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::DMat4 {
     const TYPE: AttributeType = AttributeType::F64x4x4;
 }
 
@@ -1442,7 +1524,7 @@ mod tests {
     #[allow(unused_imports)]
     use std::mem;
 
-    #[cfg(feature="cgmath")]
+    #[cfg(any(feature = "cgmath", feature = "nalgebra", feature = "glam"))]
     macro_rules! test_layout_val {
         ($from_val:path, $ety:ty, $ncomps:expr, $literal:expr) => {{
             let arr: [$ety; $ncomps] = unsafe { mem::transmute($from_val($literal)) };
@@ -1450,14 +1532,6 @@ mod tests {
         }}
     }
 
-    #[cfg(feature = "nalgebra")]
-    macro_rules! test_layout_ref {
-        ($from_ref:path, $ety:ty, $ncomps:expr, $literal:expr) => {{
-            let arr: &[$ety; $ncomps] = unsafe { mem::transmute($from_ref(&$literal)) };
-            assert_eq!(*arr, $literal);
-        }}
-    }
-
     #[cfg(feature="cgmath")]
     #[test]
     fn test_cgmath_layout() {
@@ -1528,97 +1602,131 @@ mod tests {
                                                               [12.0f64, 13.0, 14.0, 15.0]]);
     }
 
+    #[cfg(feature = "glam")]
+    #[test]
+    fn test_glam_layout() {
+        use glam;
+
+        test_layout_val!(glam::Vec2::from, f32, 2, [0.0f32, 1.0]);
+        test_layout_val!(glam::Vec3::from, f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_val!(glam::Vec4::from, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+        test_layout_val!(glam::IVec2::from, i32, 2, [0i32, 1]);
+        test_layout_val!(glam::IVec3::from, i32, 3, [0i32, 1, 2]);
+        test_layout_val!(glam::IVec4::from, i32, 4, [0i32, 1, 2, 3]);
+        test_layout_val!(glam::UVec2::from, u32, 2, [0u32, 1]);
+        test_layout_val!(glam::UVec3::from, u32, 3, [0u32, 1, 2]);
+        test_layout_val!(glam::UVec4::from, u32, 4, [0u32, 1, 2, 3]);
+        test_layout_val!(glam::DVec2::from, f64, 2, [0.0f64, 1.0]);
+        test_layout_val!(glam::DVec3::from, f64, 3, [0.0f64, 1.0, 2.0]);
+        test_layout_val!(glam::DVec4::from, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
+
+        // matrices are built from a reference to the array
+        let mat2 = [[0.0f32, 1.0], [2.0, 3.0]];
+        let mat3 = [[0.0f32, 1.0, 2.0], [3.0, 4.0, 5.0], [6.0, 7.0, 8.0]];
+        let mat4 = [[0.0f32, 1.0, 2.0, 3.0], [4.0, 5.0, 6.0, 7.0],
+                    [8.0, 9.0, 10.0, 11.0], [12.0, 13.0, 14.0, 15.0]];
+        let dmat4 = [[0.0f64, 1.0, 2.0, 3.0], [4.0, 5.0, 6.0, 7.0],
+                     [8.0, 9.0, 10.0, 11.0], [12.0, 13.0, 14.0, 15.0]];
+
+        let arr: [[f32; 2]; 2] = unsafe { mem::transmute(glam::Mat2::from_cols_array_2d(&mat2)) };
+        assert_eq!(arr, mat2);
+        let arr: [[f32; 3]; 3] = unsafe { mem::transmute(glam::Mat3::from_cols_array_2d(&mat3)) };
+        assert_eq!(arr, mat3);
+        let arr: [[f32; 4]; 4] = unsafe { mem::transmute(glam::Mat4::from_cols_array_2d(&mat4)) };
+        assert_eq!(arr, mat4);
+        let arr: [[f64; 4]; 4] = unsafe { mem::transmute(glam::DMat4::from_cols_array_2d(&dmat4)) };
+        assert_eq!(arr, dmat4);
+    }
+
     #[cfg(feature = "nalgebra")]
     #[test]
     fn test_nalgebra_layout() {
         use nalgebra;
 
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u8, 1, [0u8]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u8, 2, [0u8, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u8, 3, [0u8, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u8, 4, [0u8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i8, 1, [0i8]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i8, 2, [0i8, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i8, 3, [0i8, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i8, 4, [0i8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u8, 1, [0u8]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u8, 2, [0u8, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u8, 3, [0u8, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u8, 4, [0u8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i8, 1, [0i8]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i8, 2, [0i8, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i8, 3, [0i8, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i8, 4, [0i8, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u16, 1, [0u16]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u16, 2, [0u16, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u16, 3, [0u16, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u16, 4, [0u16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i16, 1, [0i16]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i16, 2, [0i16, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i16, 3, [0i16, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i16, 4, [0i16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u16, 1, [0u16]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u16, 2, [0u16, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u16, 3, [0u16, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u16, 4, [0u16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i16, 1, [0i16]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i16, 2, [0i16, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i16, 3, [0i16, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i16, 4, [0i16, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u32, 1, [0u32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u32, 2, [0u32, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u32, 3, [0u32, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u32, 4, [0u32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i32, 1, [0i32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i32, 2, [0i32, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i32, 3, [0i32, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i32, 4, [0i32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u32, 1, [0u32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u32, 2, [0u32, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u32, 3, [0u32, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u32, 4, [0u32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i32, 1, [0i32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i32, 2, [0i32, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i32, 3, [0i32, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i32, 4, [0i32, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, f32, 1, [0.0f32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, f32, 2, [0.0f32, 1.0]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, f32, 3, [0.0f32, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, f64, 1, [0.0f64]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, f64, 2, [0.0f64, 1.0]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, f64, 3, [0.0f64, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, f32, 1, [0.0f32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, f32, 2, [0.0f32, 1.0]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, f32, 3, [0.0f32, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, f64, 1, [0.0f64]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, f64, 2, [0.0f64, 1.0]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, f64, 3, [0.0f64, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
-
-        test_layout_ref!(nalgebra::Mat1::from_array_ref, [f32; 1], 1, [[0.0f32]]);
-        test_layout_ref!(nalgebra::Mat2::from_array_ref, [f32; 2], 2, [[0.0f32, 1.0],
+        test_layout_val!(nalgebra::Vector1::from, u8, 1, [0u8]);
+        test_layout_val!(nalgebra::Vector2::from, u8, 2, [0u8, 1]);
+        test_layout_val!(nalgebra::Vector3::from, u8, 3, [0u8, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, u8, 4, [0u8, 1, 2, 3]);
+        test_layout_val!(nalgebra::Vector1::from, i8, 1, [0i8]);
+        test_layout_val!(nalgebra::Vector2::from, i8, 2, [0i8, 1]);
+        test_layout_val!(nalgebra::Vector3::from, i8, 3, [0i8, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, i8, 4, [0i8, 1, 2, 3]);
+        test_layout_val!(nalgebra::Point1::from, u8, 1, [0u8]);
+        test_layout_val!(nalgebra::Point2::from, u8, 2, [0u8, 1]);
+        test_layout_val!(nalgebra::Point3::from, u8, 3, [0u8, 1, 2]);
+        test_layout_val!(nalgebra::Point4::from, u8, 4, [0u8, 1, 2, 3]);
+        test_layout_val!(nalgebra::Point1::from, i8, 1, [0i8]);
+        test_layout_val!(nalgebra::Point2::from, i8, 2, [0i8, 1]);
+        test_layout_val!(nalgebra::Point3::from, i8, 3, [0i8, 1, 2]);
+        test_layout_val!(nalgebra::Point4::from, i8, 4, [0i8, 1, 2, 3]);
+
+        test_layout_val!(nalgebra::Vector1::from, u16, 1, [0u16]);
+        test_layout_val!(nalgebra::Vector2::from, u16, 2, [0u16, 1]);
+        test_layout_val!(nalgebra::Vector3::from, u16, 3, [0u16, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, u16, 4, [0u16, 1, 2, 3]);
+        test_layout_val!(nalgebra::Vector1::from, i16, 1, [0i16]);
+        test_layout_val!(nalgebra::Vector2::from, i16, 2, [0i16, 1]);
+        test_layout_val!(nalgebra::Vector3::from, i16, 3, [0i16, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, i16, 4, [0i16, 1, 2, 3]);
+        test_layout_val!(nalgebra::Point1::from, u16, 1, [0u16]);
+        test_layout_val!(nalgebra::Point2::from, u16, 2, [0u16, 1]);
+        test_layout_val!(nalgebra::Point3::from, u16, 3, [0u16, 1, 2]);
+        test_layout_val!(nalgebra::Point4::from, u16, 4, [0u16, 1, 2, 3]);
+        test_layout_val!(nalgebra::Point1::from, i16, 1, [0i16]);
+        test_layout_val!(nalgebra::Point2::from, i16, 2, [0i16, 1]);
+        test_layout_val!(nalgebra::Point3::from, i16, 3, [0i16, 1, 2]);
+        test_layout_val!(nalgebra::Point4::from, i16, 4, [0i16, 1, 2, 3]);
+
+        test_layout_val!(nalgebra::Vector1::from, u32, 1, [0u32]);
+        test_layout_val!(nalgebra::Vector2::from, u32, 2, [0u32, 1]);
+        test_layout_val!(nalgebra::Vector3::from, u32, 3, [0u32, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, u32, 4, [0u32, 1, 2, 3]);
+        test_layout_val!(nalgebra::Vector1::from, i32, 1, [0i32]);
+        test_layout_val!(nalgebra::Vector2::from, i32, 2, [0i32, 1]);
+        test_layout_val!(nalgebra::Vector3::from, i32, 3, [0i32, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, i32, 4, [0i32, 1, 2, 3]);
+        test_layout_val!(nalgebra::Point1::from, u32, 1, [0u32]);
+        test_layout_val!(nalgebra::Point2::from, u32, 2, [0u32, 1]);
+        test_layout_val!(nalgebra::Point3::from, u32, 3, [0u32, 1, 2]);
+        test_layout_val!(nalgebra::Point4::from, u32, 4, [0u32, 1, 2, 3]);
+        test_layout_val!(nalgebra::Point1::from, i32, 1, [0i32]);
+        test_layout_val!(nalgebra::Point2::from, i32, 2, [0i32, 1]);
+        test_layout_val!(nalgebra::Point3::from, i32, 3, [0i32, 1, 2]);
+        test_layout_val!(nalgebra::Point4::from, i32, 4, [0i32, 1, 2, 3]);
+
+        test_layout_val!(nalgebra::Vector1::from, f32, 1, [0.0f32]);
+        test_layout_val!(nalgebra::Vector2::from, f32, 2, [0.0f32, 1.0]);
+        test_layout_val!(nalgebra::Vector3::from, f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_val!(nalgebra::Vector4::from, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+        test_layout_val!(nalgebra::Vector1::from, f64, 1, [0.0f64]);
+        test_layout_val!(nalgebra::Vector2::from, f64, 2, [0.0f64, 1.0]);
+        test_layout_val!(nalgebra::Vector3::from, f64, 3, [0.0f64, 1.0, 2.0]);
+        test_layout_val!(nalgebra::Vector4::from, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
+        test_layout_val!(nalgebra::Point1::from, f32, 1, [0.0f32]);
+        test_layout_val!(nalgebra::Point2::from, f32, 2, [0.0f32, 1.0]);
+        test_layout_val!(nalgebra::Point3::from, f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_val!(nalgebra::Point4::from, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+        test_layout_val!(nalgebra::Point1::from, f64, 1, [0.0f64]);
+        test_layout_val!(nalgebra::Point2::from, f64, 2, [0.0f64, 1.0]);
+        test_layout_val!(nalgebra::Point3::from, f64, 3, [0.0f64, 1.0, 2.0]);
+        test_layout_val!(nalgebra::Point4::from, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
+
+        test_layout_val!(nalgebra::Matrix2::from, [f32; 2], 2, [[0.0f32, 1.0],
                                                                        [2.0f32, 3.0]]);
-        test_layout_ref!(nalgebra::Mat3::from_array_ref, [f32; 3], 3, [[0.0f32, 1.0, 2.0],
+        test_layout_val!(nalgebra::Matrix3::from, [f32; 3], 3, [[0.0f32, 1.0, 2.0],
                                                                        [3.0f32, 4.0, 5.0],
                                                                        [6.0f32, 7.0, 8.0]]);
-        test_layout_ref!(nalgebra::Mat4::from_array_ref, [f32; 4], 4, [[0.0f32, 1.0, 2.0, 3.0],
+        test_layout_val!(nalgebra::Matrix4::from, [f32; 4], 4, [[0.0f32, 1.0, 2.0, 3.0],
                                                                        [4.0f32, 5.0, 6.0, 7.0],
                                                                        [8.0f32, 9.0, 10.0, 11.0],
                                                                        [12.0f32, 13.0, 14.0, 15.0]]);
 
-        test_layout_ref!(nalgebra::Mat1::from_array_ref, [f64; 1], 1, [[0.0f64]]);
-        test_layout_ref!(nalgebra::Mat2::from_array_ref, [f64; 2], 2, [[0.0f64, 1.0],
+        test_layout_val!(nalgebra::Matrix2::from, [f64; 2], 2, [[0.0f64, 1.0],
                                                                        [2.0f64, 3.0]]);
-        test_layout_ref!(nalgebra::Mat3::from_array_ref, [f64; 3], 3, [[0.0f64, 1.0, 2.0],
+        test_layout_val!(nalgebra::Matrix3::from, [f64; 3], 3, [[0.0f64, 1.0, 2.0],
                                                                        [3.0f64, 4.0, 5.0],
                                                                        [6.0f64, 7.0, 8.0]]);
-        test_layout_ref!(nalgebra::Mat4::from_array_ref, [f64; 4], 4, [[0.0f64, 1.0, 2.0, 3.0],
+        test_layout_val!(nalgebra::Matrix4::from, [f64; 4], 4, [[0.0f64, 1.0, 2.0, 3.0],
                                                                        [4.0f64, 5.0, 6.0, 7.0],
                                                                        [8.0f64, 9.0, 10.0, 11.0],
                                                                        [12.0f64, 13.0, 14.0, 15.0]]);
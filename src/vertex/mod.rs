@@ -129,6 +129,9 @@ type and a program. Then you must pass the `&TransformFeedbackSession` to the dr
 The program you use when drawing must be the same as you the one you created the session
 with, or else you will get an error.
 
+Programs that write each varying to a separate buffer must use
+`TransformFeedbackSession::new_separate()` instead, which takes one buffer per varying.
+
 */
 use std::iter::Chain;
 use std::option::IntoIter;
@@ -138,6 +141,8 @@ pub use self::buffer::VertexBufferSlice;
 pub use self::buffer::CreationError as BufferCreationError;
 pub use self::format::{AttributeType, VertexFormat};
 pub use self::transform_feedback::{is_transform_feedback_supported, TransformFeedbackSession};
+pub use self::transform_feedback::{is_transform_feedback_pause_supported, TransformFeedbackOutput};
+pub use self::transform_feedback::{PauseNotSupportedError, TransformFeedbackSessionCreationError};
 
 use crate::buffer::BufferAnySlice;
 use crate::CapabilitiesSource;
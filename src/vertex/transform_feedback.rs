@@ -1,4 +1,5 @@
 use std::{ mem, fmt };
+use std::cell::Cell;
 use std::error::Error;
 
 use crate::version::Api;
@@ -10,11 +11,12 @@ use crate::GlObject;
 use crate::ContextExt;
 use crate::CapabilitiesSource;
 use crate::TransformFeedbackSessionExt;
-use crate::buffer::{Buffer, BufferAnySlice};
+use crate::buffer::{Buffer, BufferAnySlice, BufferMutSlice};
 use crate::index::PrimitiveType;
 use crate::program::OutputPrimitives;
 use crate::program::Program;
 use crate::vertex::Vertex;
+use crate::vertex::VertexFormat;
 
 use crate::gl;
 
@@ -54,6 +56,17 @@ use crate::gl;
 ///   to be pushed in the buffer after the existing data. However you must always use the same type
 ///   of primitives and the same program.
 ///
+/// - Drawing without a session ends the current session, and the next draw command that uses it
+///   will start writing at the beginning of the buffers again. To draw other objects in the
+///   middle of a session without losing its position, call `pause()` first and `resume()`
+///   afterwards.
+///
+/// - If the program was created with `TransformFeedbackMode::Separate`, each varying is written
+///   to its own buffer. Use `new_separate` to create a session with one buffer per varying.
+///
+/// - The number of primitives that have been written can be obtained by passing a
+///   `TransformFeedbackPrimitivesWrittenQuery` in the draw parameters.
+///
 /// # Example
 ///
 /// ```no_run
@@ -90,8 +103,46 @@ use crate::gl;
 /// ```
 #[derive(Debug)]
 pub struct TransformFeedbackSession<'a> {
-    buffer: BufferAnySlice<'a>,
+    buffers: Vec<BufferAnySlice<'a>>,
     program: &'a Program,
+    paused: Cell<bool>,
+}
+
+/// A buffer that receives the output of a transform feedback session, alongside the format of
+/// its content.
+///
+/// Can be built from any `&mut Buffer<[V]>` or `BufferMutSlice<[V]>` where `V` implements
+/// `Vertex`. With a slice, only this range of the buffer is written.
+pub struct TransformFeedbackOutput<'a> {
+    buffer: BufferAnySlice<'a>,
+    format: VertexFormat,
+    stride: usize,
+}
+
+impl<'a, V> From<&'a mut Buffer<[V]>> for TransformFeedbackOutput<'a>
+    where V: Vertex + Copy + Send + 'static
+{
+    #[inline]
+    fn from(buffer: &'a mut Buffer<[V]>) -> TransformFeedbackOutput<'a> {
+        TransformFeedbackOutput {
+            buffer: buffer.as_slice_any(),
+            format: <V as Vertex>::build_bindings(),
+            stride: mem::size_of::<V>(),
+        }
+    }
+}
+
+impl<'a, V> From<BufferMutSlice<'a, [V]>> for TransformFeedbackOutput<'a>
+    where V: Vertex + Copy + Send + 'static
+{
+    #[inline]
+    fn from(slice: BufferMutSlice<'a, [V]>) -> TransformFeedbackOutput<'a> {
+        TransformFeedbackOutput {
+            buffer: slice.as_slice_any(),
+            format: <V as Vertex>::build_bindings(),
+            stride: mem::size_of::<V>(),
+        }
+    }
 }
 
 /// Error that can happen when creating a `TransformFeedbackSession`.
@@ -102,23 +153,45 @@ pub enum TransformFeedbackSessionCreationError {
 
     /// The format of the output doesn't match what the program is expected to output.
     WrongVertexFormat,
+
+    /// The number of buffers doesn't match the number of buffers that the program outputs to.
+    WrongNumberOfBuffers {
+        /// Number of buffers that the program outputs to.
+        expected: usize,
+        /// Number of buffers that were passed.
+        obtained: usize,
+    },
 }
 
 impl fmt::Display for TransformFeedbackSessionCreationError {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         use self::TransformFeedbackSessionCreationError::*;
-        let desc = match *self {
+        match *self {
             NotSupported =>
-                "Transform feedback is not supported by the OpenGL implementation",
+                fmt.write_str("Transform feedback is not supported by the OpenGL implementation"),
             WrongVertexFormat =>
-                "The format of the output doesn't match what the program is expected to output",
-        };
-        fmt.write_str(desc)
+                fmt.write_str("The format of the output doesn't match what the program is expected to output"),
+            WrongNumberOfBuffers { expected, obtained } =>
+                write!(fmt, "The program outputs to {} buffers, but {} were passed", expected, obtained),
+        }
     }
 }
 
 impl Error for TransformFeedbackSessionCreationError {}
 
+/// Error that happens when pausing transform feedback is not supported.
+#[derive(Copy, Clone, Debug)]
+pub struct PauseNotSupportedError;
+
+impl fmt::Display for PauseNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("Pausing transform feedback is not supported by the OpenGL implementation")
+    }
+}
+
+impl Error for PauseNotSupportedError {}
+
 /// Returns true if transform feedback is supported by the OpenGL implementation.
 #[inline]
 pub fn is_transform_feedback_supported<F: ?Sized>(facade: &F) -> bool where F: Facade {
@@ -129,11 +202,21 @@ pub fn is_transform_feedback_supported<F: ?Sized>(facade: &F) -> bool where F: F
     context.get_extensions().gl_ext_transform_feedback
 }
 
+/// Returns true if transform feedback sessions can be paused and resumed.
+#[inline]
+pub fn is_transform_feedback_pause_supported<F>(facade: &F) -> bool where F: ?Sized + Facade {
+    let context = facade.get_context();
+
+    context.get_version() >= &Version(Api::Gl, 4, 0) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_arb_transform_feedback2
+}
+
 impl<'a> TransformFeedbackSession<'a> {
     /// Builds a new transform feedback session.
     ///
-    /// TODO: this constructor should ultimately support passing multiple buffers of different
-    ///       types
+    /// The program must output to a single buffer, which is the case of programs created with
+    /// `TransformFeedbackMode::Interleaved` or with a single varying.
     pub fn new<F: ?Sized, V>(facade: &F, program: &'a Program, buffer: &'a mut Buffer<[V]>)
                      -> Result<TransformFeedbackSession<'a>, TransformFeedbackSessionCreationError>
                      where F: Facade, V: Vertex + Copy + Send + 'static
@@ -149,45 +232,181 @@ impl<'a> TransformFeedbackSession<'a> {
         }
 
         Ok(TransformFeedbackSession {
-            buffer: buffer.as_slice_any(),
+            buffers: vec![buffer.as_slice_any()],
             program,
+            paused: Cell::new(false),
         })
     }
+
+    /// Builds a new transform feedback session that writes to multiple buffers.
+    ///
+    /// This is meant to be used with programs created with `TransformFeedbackMode::Separate`.
+    /// The buffers must be passed in the same order as the varyings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use glium::implement_vertex;
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>, program: glium::Program)
+    /// #     where T: SurfaceTypeTrait + ResizeableSurface {
+    /// # #[derive(Copy, Clone)] struct Position { position: [f32; 3] }
+    /// # implement_vertex!(Position, position);
+    /// # #[derive(Copy, Clone)] struct Velocity { velocity: [f32; 3] }
+    /// # implement_vertex!(Velocity, velocity);
+    /// let mut positions: glium::VertexBuffer<Position> =
+    ///     glium::VertexBuffer::empty(&display, 1024).unwrap();
+    /// let mut velocities: glium::VertexBuffer<Velocity> =
+    ///     glium::VertexBuffer::empty(&display, 1024).unwrap();
+    ///
+    /// let session = glium::vertex::TransformFeedbackSession::new_separate(&display, &program,
+    ///     vec![(&mut *positions).into(), (&mut *velocities).into()]).unwrap();
+    /// # }
+    /// ```
+    pub fn new_separate<F>(facade: &F, program: &'a Program,
+                           outputs: Vec<TransformFeedbackOutput<'a>>)
+                     -> Result<TransformFeedbackSession<'a>, TransformFeedbackSessionCreationError>
+                     where F: ?Sized + Facade
+    {
+        if !is_transform_feedback_supported(facade) {
+            return Err(TransformFeedbackSessionCreationError::NotSupported);
+        }
+
+        let expected = program.get_transform_feedback_buffers().len();
+        if outputs.is_empty() || outputs.len() != expected {
+            return Err(TransformFeedbackSessionCreationError::WrongNumberOfBuffers {
+                expected,
+                obtained: outputs.len(),
+            });
+        }
+
+        for (index, output) in outputs.iter().enumerate() {
+            if !program.transform_feedback_buffer_matches(index, &output.format, output.stride) {
+                return Err(TransformFeedbackSessionCreationError::WrongVertexFormat);
+            }
+        }
+
+        Ok(TransformFeedbackSession {
+            buffers: outputs.into_iter().map(|o| o.buffer).collect(),
+            program,
+            paused: Cell::new(false),
+        })
+    }
+
+    /// Pauses the session.
+    ///
+    /// While the session is paused, draw commands don't write anything to the buffers, and you
+    /// can draw with other programs or without a session without ending it. The next draw
+    /// command that uses the session after `resume` has been called continues writing after
+    /// the existing data.
+    pub fn pause(&self) -> Result<(), PauseNotSupportedError> {
+        let mut ctxt = self.buffers[0].get_context().make_current();
+        if !is_pause_supported(&ctxt) {
+            return Err(PauseNotSupportedError);
+        }
+
+        self.paused.set(true);
+
+        if self.is_current(&ctxt) && !ctxt.state.transform_feedback_paused {
+            unsafe { ctxt.gl.PauseTransformFeedback(); }
+            ctxt.state.transform_feedback_paused = true;
+        }
+
+        Ok(())
+    }
+
+    /// Resumes a session that has been paused with `pause`.
+    ///
+    /// The session is resumed by the next draw command that uses it.
+    pub fn resume(&self) -> Result<(), PauseNotSupportedError> {
+        let ctxt = self.buffers[0].get_context().make_current();
+        if !is_pause_supported(&ctxt) {
+            return Err(PauseNotSupportedError);
+        }
+
+        self.paused.set(false);
+        Ok(())
+    }
+
+    /// Returns true if the session has been paused with `pause`.
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused.get()
+    }
+
+    /// Returns true if the buffers of this session are the ones bound to the current transform
+    /// feedback operation.
+    fn is_current(&self, ctxt: &CommandContext<'_>) -> bool {
+        if ctxt.state.transform_feedback_enabled.is_none() {
+            return false;
+        }
+
+        let bindings = &ctxt.state.indexed_transform_feedback_buffer_bindings;
+        bindings.len() >= self.buffers.len() &&
+            self.buffers.iter().zip(bindings.iter()).all(|(b, s)| {
+                s.buffer == b.get_id() &&
+                s.offset == b.get_offset_bytes() as gl::types::GLintptr &&
+                s.size == b.get_size() as gl::types::GLsizeiptr
+            })
+    }
+}
+
+/// Returns true if `glPauseTransformFeedback` is available.
+#[inline]
+fn is_pause_supported(ctxt: &CommandContext<'_>) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+    ctxt.extensions.gl_arb_transform_feedback2
 }
 
 impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
     fn bind(&self, ctxt: &mut CommandContext<'_>, draw_primitives: PrimitiveType) {
         // TODO: check that the state matches what is required
-        if ctxt.state.transform_feedback_enabled.is_some() {
-            unimplemented!();
+        let primitives = match (self.program.get_output_primitives(), draw_primitives) {
+            (Some(OutputPrimitives::Points), _) => gl::POINTS,
+            (Some(OutputPrimitives::Lines), _) => gl::LINES,
+            (Some(OutputPrimitives::Triangles), _) => gl::TRIANGLES,
+            (Some(OutputPrimitives::Quads), _) => panic!(),         // TODO: return a proper error
+            (None, PrimitiveType::Points) => gl::POINTS,
+            (None, PrimitiveType::LinesList) => gl::LINES,
+            (None, PrimitiveType::LinesListAdjacency) => gl::LINES,
+            (None, PrimitiveType::LineStrip) => gl::LINES,
+            (None, PrimitiveType::LineStripAdjacency) => gl::LINES,
+            (None, PrimitiveType::LineLoop) => gl::LINES,
+            (None, PrimitiveType::TrianglesList) => gl::TRIANGLES,
+            (None, PrimitiveType::TrianglesListAdjacency) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleStrip) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleStripAdjacency) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleFan) => gl::TRIANGLES,
+            (None, PrimitiveType::Patches { .. }) => unreachable!(),
+        };
+
+        // continuing the current operation if it belongs to this session, otherwise starting
+        // a new one
+        if !self.is_current(ctxt) || ctxt.state.transform_feedback_enabled != Some(primitives) {
+            Self::unbind(ctxt);
+
+            // FIXME: use the memory barrier system
+            for (index, buffer) in self.buffers.iter().enumerate() {
+                buffer.bind_to_transform_feedback(ctxt, index as gl::types::GLuint);
+            }
+
+            unsafe {
+                ctxt.gl.BeginTransformFeedback(primitives);
+                ctxt.state.transform_feedback_enabled = Some(primitives);
+                ctxt.state.transform_feedback_paused = false;
+            }
         }
 
-        // FIXME: use the memory barrier system
-        self.buffer.bind_to_transform_feedback(ctxt, 0);
+        if self.paused.get() != ctxt.state.transform_feedback_paused {
+            unsafe {
+                if self.paused.get() {
+                    ctxt.gl.PauseTransformFeedback();
+                } else {
+                    ctxt.gl.ResumeTransformFeedback();
+                }
+            }
 
-        unsafe {
-            let primitives = match (self.program.get_output_primitives(), draw_primitives) {
-                (Some(OutputPrimitives::Points), _) => gl::POINTS,
-                (Some(OutputPrimitives::Lines), _) => gl::LINES,
-                (Some(OutputPrimitives::Triangles), _) => gl::TRIANGLES,
-                (Some(OutputPrimitives::Quads), _) => panic!(),         // TODO: return a proper error
-                (None, PrimitiveType::Points) => gl::POINTS,
-                (None, PrimitiveType::LinesList) => gl::LINES,
-                (None, PrimitiveType::LinesListAdjacency) => gl::LINES,
-                (None, PrimitiveType::LineStrip) => gl::LINES,
-                (None, PrimitiveType::LineStripAdjacency) => gl::LINES,
-                (None, PrimitiveType::LineLoop) => gl::LINES,
-                (None, PrimitiveType::TrianglesList) => gl::TRIANGLES,
-                (None, PrimitiveType::TrianglesListAdjacency) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleStrip) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleStripAdjacency) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleFan) => gl::TRIANGLES,
-                (None, PrimitiveType::Patches { .. }) => unreachable!(),
-            };
-
-            ctxt.gl.BeginTransformFeedback(primitives);
-            ctxt.state.transform_feedback_enabled = Some(primitives);
-            ctxt.state.transform_feedback_paused = false;
+            ctxt.state.transform_feedback_paused = self.paused.get();
         }
     }
 
@@ -221,6 +440,18 @@ impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
             TransformFeedbackSession::unbind(ctxt);
         }
     }
+
+    #[inline]
+    fn writes_to_buffer(&self, buffer: &BufferAnySlice<'_>) -> bool {
+        let start = buffer.get_offset_bytes();
+        let end = start + buffer.get_size();
+
+        self.buffers.iter().any(|b| {
+            let written_start = b.get_offset_bytes();
+            let written_end = written_start + b.get_size();
+            b.get_id() == buffer.get_id() && start < written_end && written_start < end
+        })
+    }
 }
 
 impl<'a> Drop for TransformFeedbackSession<'a> {
@@ -229,7 +460,9 @@ impl<'a> Drop for TransformFeedbackSession<'a> {
         // Since the session can be mem::forget'ed, the code in buffer/alloc.rs ensures that the
         // buffer isn't used by transform feedback.
         // However we end the session now anyway.
-        let mut ctxt = self.buffer.get_context().make_current();
-        Self::ensure_buffer_out_of_transform_feedback(&mut ctxt, self.buffer.get_id());
+        let mut ctxt = self.buffers[0].get_context().make_current();
+        for buffer in self.buffers.iter() {
+            Self::ensure_buffer_out_of_transform_feedback(&mut ctxt, buffer.get_id());
+        }
     }
 }
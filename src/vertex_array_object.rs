@@ -1,6 +1,6 @@
 use std::borrow::Borrow;
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::mem;
 
 use smallvec::SmallVec;
@@ -23,6 +23,10 @@ pub struct VertexAttributesSystem {
     // we maintain a list of VAOs for each vertexbuffer-indexbuffer-program association
     // the key is a (buffers-list-with-offset, program) ; the buffers list must be sorted
     vaos: RefCell<HashMap<(SmallVec<[(gl::types::GLuint, usize); 3]>, Handle), VertexArrayObject>>,
+
+    // the (program, vertex format) pairs whose attribute conversions have already been reported
+    // the vertex format is identified by the address of its `'static` slice
+    reported_conversions: RefCell<HashSet<(Handle, usize)>>,
 }
 
 /// Object allowing one to bind vertex attributes to the current context.
@@ -40,6 +44,7 @@ impl VertexAttributesSystem {
     pub fn new() -> VertexAttributesSystem {
         VertexAttributesSystem {
             vaos: RefCell::new(HashMap::with_hasher(Default::default())),
+            reported_conversions: RefCell::new(HashSet::new()),
         }
     }
 
@@ -78,6 +83,7 @@ impl VertexAttributesSystem {
     /// purge its VAOs cache.
     #[inline]
     pub fn purge_program(ctxt: &mut CommandContext<'_>, program: Handle) {
+        ctxt.vertex_array_objects.reported_conversions.borrow_mut().retain(|&(p, _)| p != program);
         VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program)
     }
 
@@ -261,7 +267,7 @@ impl VertexArrayObject {
     {
         // checking the attributes types
         for &(_, ref bindings, _, _, _) in vertex_buffers {
-            for &(ref name, _, location, ty, _) in bindings.iter() {
+            for &(ref name, _, location, ty, normalize) in bindings.iter() {
                 let attribute = match location {
                     -1 => {
                         // No location specified in Vertex Format. Check name instead
@@ -288,6 +294,15 @@ impl VertexArrayObject {
                     panic!("The program attribute `{}` does not match the vertex format. \
                             Program expected {:?}, got {:?}.", name, attribute.ty, ty);
                 }
+
+                if cfg!(debug_assertions) {
+                    if let Some(warning) = conversion_warning(name, ty, normalize, attribute.ty) {
+                        let key = (program.get_id(), bindings.as_ptr() as usize);
+                        if ctxt.vertex_array_objects.reported_conversions.borrow_mut().insert(key) {
+                            report_warning(ctxt, &warning);
+                        }
+                    }
+                }
             }
         }
 
@@ -364,6 +379,10 @@ impl VertexArrayObject {
             bind_vao(ctxt, self.id);
 
             if self.element_array_buffer_hijacked.get() {
+                ctxt.state.audit("glBindBuffer",
+                                 (gl::ELEMENT_ARRAY_BUFFER as u64) << 32 | self.id as u64,
+                                 self.element_array_buffer as u64);
+
                 // TODO: use a proper function
                 if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                     ctxt.version >= &Version(Api::GlEs, 2, 0)
@@ -422,6 +441,81 @@ impl GlObject for VertexArrayObject {
     }
 }
 
+/// How the components of an attribute are stored, or read by the shader.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum AttributeKind {
+    Integer,
+    Float,
+    Double,
+}
+
+impl AttributeKind {
+    fn of(ty: AttributeType) -> AttributeKind {
+        match vertex_binding_type_to_gl(ty).0 {
+            gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT | gl::INT |
+            gl::UNSIGNED_INT | gl::INT64_NV | gl::UNSIGNED_INT64_NV => AttributeKind::Integer,
+            gl::DOUBLE => AttributeKind::Double,
+            // half-floats, fixed-point and packed formats are read as floats
+            _ => AttributeKind::Float,
+        }
+    }
+}
+
+/// Returns a message if the vertex data of an attribute is not of the same kind as the attribute
+/// in the shader, for example floats for an `int` attribute.
+///
+/// OpenGL doesn't report these mismatches and converts the values, or reinterprets their bits,
+/// which is rarely intended. Normalized integers are meant to be read as floats and are accepted.
+fn conversion_warning(name: &str, ty: AttributeType, normalize: bool,
+                      attribute_ty: AttributeType) -> Option<String>
+{
+    let data = AttributeKind::of(ty);
+    let shader = AttributeKind::of(attribute_ty);
+
+    if data == shader || (data == AttributeKind::Integer && normalize &&
+                          shader == AttributeKind::Float)
+    {
+        return None;
+    }
+
+    Some(format!("the vertex attribute `{}` is {:?} in the program but {:?}{} in the vertex \
+                  format, its values will be converted", name, attribute_ty, ty,
+                 if normalize { " (normalized)" } else { "" }))
+}
+
+/// Sends a warning to the debug callback of the context, or to the `log` crate if the debug
+/// output isn't enabled.
+fn report_warning(ctxt: &mut CommandContext<'_>, message: &str) {
+    let length = message.len() as gl::types::GLsizei;
+    let ptr = message.as_ptr() as *const _;
+
+    if ctxt.state.enabled_debug_output != Some(true) {
+        #[cfg(feature = "log")]
+        log::warn!(target: "glium::vertex", "{}", message);
+
+    } else if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+              ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+              (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+    {
+        unsafe {
+            ctxt.gl.DebugMessageInsert(gl::DEBUG_SOURCE_THIRD_PARTY, gl::DEBUG_TYPE_OTHER, 0,
+                                       gl::DEBUG_SEVERITY_LOW, length, ptr);
+        }
+
+    } else if ctxt.extensions.gl_khr_debug {
+        unsafe {
+            ctxt.gl.DebugMessageInsertKHR(gl::DEBUG_SOURCE_THIRD_PARTY, gl::DEBUG_TYPE_OTHER, 0,
+                                          gl::DEBUG_SEVERITY_LOW, length, ptr);
+        }
+
+    } else if ctxt.extensions.gl_arb_debug_output {
+        unsafe {
+            ctxt.gl.DebugMessageInsertARB(gl::DEBUG_SOURCE_THIRD_PARTY, gl::DEBUG_TYPE_OTHER, 0,
+                                          gl::DEBUG_SEVERITY_LOW, length, ptr);
+        }
+    }
+}
+
 fn vertex_binding_type_to_gl(ty: AttributeType) -> (gl::types::GLenum, gl::types::GLint, gl::types::GLint) {
     match ty {
         AttributeType::I8 => (gl::BYTE, 1, 1),
@@ -511,6 +605,8 @@ fn vertex_binding_type_to_gl(ty: AttributeType) -> (gl::types::GLenum, gl::types
 /// Panics if the backend doesn't support vertex array objects.
 fn bind_vao(ctxt: &mut CommandContext<'_>, vao_id: gl::types::GLuint) {
     if ctxt.state.vertex_array != vao_id {
+        ctxt.state.audit("glBindVertexArray", 0, vao_id as u64);
+
         if ctxt.version >= &Version(Api::Gl, 3, 0) ||
             ctxt.version >= &Version(Api::GlEs, 3, 0) ||
             ctxt.extensions.gl_arb_vertex_array_object
@@ -536,6 +632,8 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
     // glVertexAttribPointer uses the current array buffer
     // TODO: use a proper function
     if ctxt.state.array_buffer_binding != vertex_buffer {
+        ctxt.state.audit("glBindBuffer", gl::ARRAY_BUFFER as u64, vertex_buffer as u64);
+
         if ctxt.version >= &Version(Api::Gl, 1, 5) ||
             ctxt.version >= &Version(Api::GlEs, 2, 0)
         {
@@ -626,3 +724,22 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::conversion_warning;
+    use crate::vertex::AttributeType;
+
+    #[test]
+    fn attribute_conversion_warning() {
+        assert!(conversion_warning("a", AttributeType::F32F32, false, AttributeType::F32F32).is_none());
+        assert!(conversion_warning("a", AttributeType::U8U8U8U8, true, AttributeType::F32F32F32F32).is_none());
+        assert!(conversion_warning("a", AttributeType::F16F16, false, AttributeType::F32F32).is_none());
+        assert!(conversion_warning("a", AttributeType::I32, false, AttributeType::U32).is_none());
+
+        let warning = conversion_warning("id", AttributeType::F32, false, AttributeType::I32).unwrap();
+        assert!(warning.contains("`id`") && warning.contains("I32") && warning.contains("F32"));
+        assert!(conversion_warning("a", AttributeType::U8U8U8U8, false, AttributeType::F32F32F32F32).is_some());
+        assert!(conversion_warning("a", AttributeType::F64, false, AttributeType::F32).is_some());
+    }
+}
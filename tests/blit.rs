@@ -1,7 +1,7 @@
 #[macro_use]
 extern crate glium;
 
-use glium::{Surface, BlitTarget, Rect, BlitMask};
+use glium::{Surface, BlitTarget, BlitError, Rect, BlitMask};
 use glium::framebuffer::SimpleFrameBuffer;
 use glium::uniforms::MagnifySamplerFilter;
 
@@ -93,3 +93,97 @@ fn blit_color_and_depth_buffer() {
     // todo: how to check dst_tex_depth? There is no .read() on a DepthTexture2d...
     display.assert_no_error(None);
 }
+
+#[test]
+fn blit_depth() {
+    let display = support::build_display();
+
+    let src_depth = support::build_constant_depth_texture(&display, 0.5);
+    let src = SimpleFrameBuffer::depth_only(&display, &src_depth).unwrap();
+    let dst_depth = support::build_constant_depth_texture(&display, 0.0);
+    let dst = SimpleFrameBuffer::depth_only(&display, &dst_depth).unwrap();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dst_rect = BlitTarget { left: 0, bottom: 0, width: 4, height: 4 };
+    src.blit_depth(&src_rect, &dst, &dst_rect).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_depth_linear_filter() {
+    let display = support::build_display();
+
+    let src_depth = support::build_constant_depth_texture(&display, 0.5);
+    let src = SimpleFrameBuffer::depth_only(&display, &src_depth).unwrap();
+    let dst_depth = support::build_constant_depth_texture(&display, 0.0);
+    let dst = SimpleFrameBuffer::depth_only(&display, &dst_depth).unwrap();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dst_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+
+    match src.blit_buffers(&src_rect, &dst, &dst_rect, MagnifySamplerFilter::Linear,
+                           BlitMask::depth())
+    {
+        Err(BlitError::InvalidFilter) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_depth_format_mismatch() {
+    let display = support::build_display();
+
+    let src_depth = glium::texture::DepthTexture2d::empty_with_format(&display,
+                        glium::texture::DepthFormat::I16, glium::texture::MipmapsOption::NoMipmap,
+                        2, 2).unwrap();
+    let src = SimpleFrameBuffer::depth_only(&display, &src_depth).unwrap();
+    let dst_depth = glium::texture::DepthTexture2d::empty_with_format(&display,
+                        glium::texture::DepthFormat::F32, glium::texture::MipmapsOption::NoMipmap,
+                        2, 2).unwrap();
+    let dst = SimpleFrameBuffer::depth_only(&display, &dst_depth).unwrap();
+
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    match src.blit_depth(&rect, &dst, &rect.into()) {
+        Err(BlitError::DepthFormatMismatch { source: 16, target: 32 }) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn linear_blit_area_downscale() {
+    let display = support::build_display();
+
+    let blitter = match glium::framebuffer::LinearBlitter::new(&display) {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    // a black and a white texel, which average to 50% gray in linear space
+    let source = glium::Texture2d::new(&display, vec![
+        vec![(0u8, 0u8, 0u8, 255u8), (255, 255, 255, 255)],
+    ]).unwrap();
+
+    let target = glium::Texture2d::empty(&display, 1, 1).unwrap();
+
+    let params = glium::framebuffer::LinearBlitParameters {
+        source_encoding: glium::framebuffer::ColorEncoding::Srgb,
+        target_encoding: glium::framebuffer::ColorEncoding::Srgb,
+        scaling: glium::framebuffer::BlitScaling::Area,
+    };
+
+    blitter.blit(&source, &Rect { left: 0, bottom: 0, width: 2, height: 1 },
+                 &mut target.as_surface(),
+                 &BlitTarget { left: 0, bottom: 0, width: 1, height: 1 }, &params).unwrap();
+
+    // 50% gray is 188 in sRGB, while filtering the sRGB values would give 128
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert!(data[0][0].0 >= 186 && data[0][0].0 <= 190, "{:?}", data[0][0]);
+    assert_eq!(data[0][0].3, 255);
+
+    display.assert_no_error(None);
+}
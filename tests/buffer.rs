@@ -976,3 +976,31 @@ fn copy_to_slice() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn upload_paths() {
+    let display = support::build_display();
+
+    let results = display.probe_upload_paths();
+    assert!(results.iter().any(|&(path, _)| path == glium::buffer::UploadPath::Direct));
+    assert!(results.iter().any(|&(path, _)| path == display.get_upload_path()));
+
+    for mode in [BufferMode::Default, BufferMode::Immutable] {
+        for &(path, _) in &results {
+            display.set_upload_path(path);
+
+            let buffer = glium::buffer::BufferView::<[u8]>::new(&display, &[0, 0, 0, 0],
+                                                                glium::buffer::BufferType::ArrayBuffer,
+                                                                mode).unwrap();
+            buffer.slice(1 .. 3).unwrap().write(&[5, 7]);
+
+            match buffer.read() {
+                Ok(data) => assert_eq!(data, [0, 5, 7, 0]),
+                Err(glium::buffer::ReadError::NotSupported) => (),
+                e => { e.unwrap(); },
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
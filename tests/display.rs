@@ -40,7 +40,7 @@ fn clear_color_rect() {
     texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
 
     let rect = glium::Rect { left: 512, bottom: 0, width: 512, height: 1024 };
-    texture.as_surface().clear(Some(&rect), Some((0.0, 1.0, 0.0, 1.0)), false, None, None);
+    texture.as_surface().clear(Some(&rect), &glium::ClearValues::new().color((0.0, 1.0, 0.0, 1.0)));
 
     let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
 
@@ -82,6 +82,193 @@ fn timestamp_query() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn gpu_timer() {
+    let display = support::build_display();
+
+    let mut timer = match glium::debug::GpuTimer::new(&display) {
+        Some(t) => t,
+        None => return
+    };
+
+    assert!(timer.get_results().is_empty());
+
+    {
+        let _outer = timer.scope("outer");
+        let _inner = timer.scope("inner");
+    }
+
+    display.finish();
+    assert!(timer.end_frame());
+
+    let results = timer.get_results();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].name, "outer");
+    assert_eq!(results[1].name, "inner");
+    assert!(results[0].begin <= results[1].begin);
+    assert!(results[1].end <= results[0].end);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn set_swap_interval() {
+    use glium::glutin::surface::SwapInterval;
+    use std::num::NonZeroU32;
+
+    let display = support::build_display();
+
+    // not all platforms allow changing the swap interval, but if one does it must allow
+    // switching back
+    if display.set_swap_interval(SwapInterval::DontWait).is_ok() {
+        display.set_swap_interval(SwapInterval::Wait(NonZeroU32::new(1).unwrap())).unwrap();
+    }
+
+    display.draw().finish().unwrap();
+    display.assert_no_error(None);
+}
+
+#[test]
+fn profiler() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    let mut profiler = glium::debug::Profiler::new(&display);
+
+    {
+        let _scope = profiler.scope("draws");
+        let mut surface = texture.as_surface();
+        surface.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+        surface.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+    }
+
+    let frame = profiler.end_frame();
+    assert_eq!(frame.statistics.draw_calls, 2);
+    assert_eq!(frame.statistics.compute_dispatches, 0);
+    assert!(frame.statistics.program_changes <= 1);
+    assert_eq!(frame.cpu_spans.len(), 1);
+    assert_eq!(frame.cpu_spans[0].name, "draws");
+    assert!(frame.cpu_spans[0].duration <= frame.cpu_duration);
+
+    let frame = profiler.end_frame();
+    assert_eq!(frame.statistics.draw_calls, 0);
+    assert!(frame.cpu_spans.is_empty());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(feature = "gl_trace")]
+fn dump_recent_calls() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let calls = display.dump_recent_calls();
+    assert!(calls.len() <= 4096);
+    assert!(calls.iter().any(|call| call.starts_with("glDraw")));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn state_audit() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    assert!(display.take_state_audit_report().is_none());
+    display.set_state_audit(true);
+
+    for _ in 0 .. 2 {
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                  &Default::default()).unwrap();
+    }
+
+    let report = display.take_state_audit_report().unwrap();
+    assert!(report.total_calls() >= 1);
+    assert!(report.top_offenders(usize::MAX).iter().all(|e| e.function != "glUseProgram"));
+
+    // nothing changes when drawing the same thing again
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+    let report = display.take_state_audit_report().unwrap();
+    assert!(report.entries.iter().all(|e| e.function != "glUseProgram"));
+
+    display.set_state_audit(false);
+    assert!(display.take_state_audit_report().is_none());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn deferred_deletion() {
+    let display = support::build_display();
+    display.set_deferred_deletion(2);
+
+    {
+        let _buffer = glium::IndexBuffer::new(&display, glium::index::PrimitiveType::TrianglesList,
+                                              &[0u16, 1, 2]).unwrap();
+        let _texture = support::build_renderable_texture(&display);
+    }
+    assert_eq!(display.pending_garbage(), 2);
+
+    display.finish();
+    assert_eq!(display.collect_garbage(), 2);
+    assert_eq!(display.pending_garbage(), 0);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    display.set_deferred_deletion(0);
+    drop(texture);
+    assert_eq!(display.pending_garbage(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn recreation_hooks() {
+    use glium::backend::Facade;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let lost = support::build_display();
+    let display = support::build_display();
+
+    let texture = Rc::new(Cell::new(None));
+    {
+        let texture = texture.clone();
+        lost.register_recreation_hook(move |context| {
+            texture.set(Some(support::build_unicolor_texture2d(context, 1.0, 0.0, 0.0)));
+        });
+    }
+
+    assert_eq!(display.get_context().recreate_resources(&lost), 1);
+    assert!(texture.take().is_some());
+
+    // the hooks have been moved to the new context
+    assert_eq!(lost.get_context().recreate_resources(&lost), 0);
+    assert_eq!(display.get_context().recreate_resources(&display), 1);
+    assert!(texture.take().is_some());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn reset_status() {
+    let display = support::build_display();
+    assert_eq!(display.get_reset_status(), glium::backend::ResetStatus::NoError);
+    assert!(!display.is_context_lost());
+    display.assert_no_error(None);
+}
+
 #[test]
 fn sync() {
     let display = support::build_display();
@@ -94,6 +281,81 @@ fn sync() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn sync_non_blocking() {
+    let display = support::build_display();
+
+    let fence = match glium::SyncFence::new(&display) {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    fence.wait_on_server(&display).unwrap();
+    assert!(fence.wait_timeout(std::time::Duration::from_secs(10)));
+    assert!(fence.is_signaled());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn calibrate_gpu_clock() {
+    let display = support::build_display();
+
+    let calibration = match display.calibrate_gpu_clock() {
+        Some(c) => c,
+        None => return
+    };
+
+    let query = match glium::debug::TimestampQuery::new(&display) {
+        Some(q) => q,
+        None => return
+    };
+    display.finish();
+    let after = std::time::Instant::now();
+
+    let reached_at = calibration.to_instant(query.get());
+    let margin = std::time::Duration::from_millis(100);
+    assert!(reached_at + margin >= calibration.get_cpu_time());
+    assert!(reached_at <= after + margin);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_recorder() {
+    use glium::capture::{CaptureFormat, FrameRecorder};
+
+    let display = support::build_display();
+
+    let mut frames = Vec::new();
+    let recorder = FrameRecorder::new(&display, 2, CaptureFormat::Rgba8, |frame| {
+        assert_eq!(frame.data.len(), frame.width as usize * frame.height as usize * 4);
+        frames.push((frame.index, frame.data[0 .. 4].to_vec()));
+    });
+    let mut recorder = match recorder {
+        Ok(r) => r,
+        Err(glium::capture::CaptureError::NotSupported) => return,
+        Err(e) => panic!("{}", e),
+    };
+
+    for _ in 0 .. 3 {
+        let mut target = display.draw();
+        target.clear_color(1.0, 0.0, 0.0, 1.0);
+        recorder.capture(&target).unwrap();
+        target.finish().unwrap();
+    }
+    recorder.flush();
+    drop(recorder);
+
+    assert_eq!(frames.len(), 3);
+    for (num, (index, pixel)) in frames.into_iter().enumerate() {
+        assert_eq!(index, num as u64);
+        assert_eq!(pixel, vec![255, 0, 0, 255]);
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn multiple_displays() {
     let display1 = support::build_display();
@@ -141,6 +403,37 @@ fn debug_string() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn debug_group() {
+    let display = support::build_display();
+
+    if display.push_debug_group("outer").is_ok() {
+        display.push_debug_group("inner").unwrap();
+        display.insert_debug_marker("Hello world").ok();
+        display.pop_debug_group().unwrap();
+        display.pop_debug_group().unwrap();
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn object_labels() {
+    let display = support::build_display();
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    // labels are only supported with `GL_KHR_debug`, but they must never trigger an error
+    if program.set_label("red program").is_ok() {
+        vb.set_label("fullscreen quad").unwrap();
+        texture.set_label("target").unwrap();
+        glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap()
+            .set_label("target framebuffer").unwrap();
+    }
+
+    display.assert_no_error(None);
+}
+
 
 #[test]
 fn is_context_lost() {
@@ -149,3 +442,73 @@ fn is_context_lost() {
     display.is_context_lost();
     display.assert_no_error(None);
 }
+
+#[test]
+fn window_sized_resize() {
+    let display = support::build_display();
+
+    let target = glium::framebuffer::WindowSized::new(&display, |facade, (width, height)| {
+        glium::Texture2d::empty(facade, width, height).unwrap()
+    });
+
+    display.resize((64, 32));
+    assert_eq!(target.get_dimensions(), (64, 32));
+    assert_eq!(target.borrow().dimensions(), (64, 32));
+
+    // dropped targets are no longer rebuilt
+    drop(target);
+    display.resize((32, 64));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn send_context() {
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    let display = glium::backend::glutin::SendContext::new(display).err()
+        .expect("the texture is still alive");
+    drop(texture);
+
+    let context = glium::backend::glutin::SendContext::new(display).unwrap();
+
+    std::thread::spawn(move || {
+        let display = context.into_display();
+        let texture = support::build_renderable_texture(&display);
+        texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+        display.assert_no_error(None);
+    }).join().unwrap();
+}
+
+#[test]
+fn frame_multisampling() {
+    let display = support::build_display();
+
+    if display.set_multisampling(Some(4)).is_err() {
+        return;
+    }
+    assert_eq!(display.get_multisampling(), Some(4));
+
+    let mut frame = display.draw();
+    frame.clear_color_and_depth((1.0, 0.0, 0.0, 1.0), 1.0);
+    frame.finish().unwrap();
+
+    display.set_multisampling(None).unwrap();
+    assert_eq!(display.get_multisampling(), None);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn destroy_surface() {
+    let display = support::build_display();
+    assert!(display.has_surface());
+
+    display.destroy_surface();
+    assert!(!display.has_surface());
+
+    // destroying it twice is harmless
+    display.destroy_surface();
+    assert!(!display.has_surface());
+}
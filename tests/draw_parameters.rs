@@ -31,6 +31,235 @@ fn color_mask() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn color_logic_op_xor() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        color_logic_op: Some(glium::draw_parameters::LogicOp::Xor),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::LogicOpNotSupported) => return,
+        r => r.unwrap(),
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    // drawing a second time restores the original content
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn color_logic_op_with_blending() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        color_logic_op: Some(glium::draw_parameters::LogicOp::Xor),
+        blend: glium::Blend::alpha_blending(),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::LogicOpNotSupported) => (),
+        Err(glium::DrawError::LogicOpWithBlending) => (),
+        r => panic!("{:?}", r),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multisampling_alpha_to_coverage() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        multisampling_alpha_to_coverage: true,
+        multisampling_alpha_to_one: true,
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    // alpha-to-coverage has no effect on a target that isn't multisampled
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::AlphaToOneNotSupported) => return,
+        r => r.unwrap(),
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn attachment_blend() {
+    let display = support::build_display();
+
+    if !glium::draw_parameters::is_attachment_blending_supported(&display) {
+        return;
+    }
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            out vec4 albedo;
+            out vec4 emissive;
+
+            void main() {
+                albedo = vec4(1.0, 0.0, 0.0, 1.0);
+                emissive = vec4(0.0, 0.0, 1.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(..)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let albedo = support::build_renderable_texture(&display);
+    albedo.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+    let emissive = support::build_renderable_texture(&display);
+    emissive.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                              [("albedo", &albedo), ("emissive", &emissive)].iter().cloned())
+                              .unwrap();
+
+    let additive = glium::Blend {
+        color: glium::BlendingFunction::Addition {
+            source: glium::LinearBlendingFactor::One,
+            destination: glium::LinearBlendingFactor::One,
+        },
+        alpha: glium::BlendingFunction::AlwaysReplace,
+        constant_value: (0.0, 0.0, 0.0, 0.0),
+    };
+
+    let params = glium::DrawParameters {
+        attachment_blend: vec![Default::default(), additive],
+        attachment_color_mask: vec![(true, true, true, true), (true, true, true, false)],
+        .. Default::default()
+    };
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = albedo.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = emissive.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn framebuffer_srgb() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(0.5, 0.5, 0.5, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let texture = match glium::texture::SrgbTexture2d::empty(&display, 1024, 1024) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    // the program outputs sRGB, but the values are converted anyway
+    let params = glium::DrawParameters {
+        framebuffer_srgb: Some(true),
+        .. Default::default()
+    };
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::FramebufferSrgbNotSupported) => return,
+        result => result.unwrap(),
+    }
+
+    // 50% gray is 188 in sRGB
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert!(data[0][0].0 >= 186 && data[0][0].0 <= 190, "{:?}", data[0][0]);
+
+    // the default follows the program again
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert!(data[0][0].0 >= 127 && data[0][0].0 <= 128, "{:?}", data[0][0]);
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn viewport_too_large() {
     let display = support::build_display();
@@ -230,9 +459,13 @@ fn dont_draw_primitives() {
 
     let texture = support::build_renderable_texture(&display);
     texture.as_surface().clear_color(0.0, 1.0, 0.0, 0.0);
+    let supported = glium::draw_parameters::is_rasterizer_discard_supported(&display);
     match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
-        Ok(_) => (),
-        Err(glium::DrawError::RasterizerDiscardNotSupported) => return,
+        Ok(_) => assert!(supported),
+        Err(glium::DrawError::RasterizerDiscardNotSupported) => {
+            assert!(!supported);
+            return;
+        },
         e => e.unwrap()
     }
 
@@ -259,9 +492,13 @@ fn dont_draw_primitives_then_draw() {
 
     let texture = support::build_renderable_texture(&display);
     texture.as_surface().clear_color(0.0, 1.0, 0.0, 0.0);
+    let supported = glium::draw_parameters::is_rasterizer_discard_supported(&display);
     match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
-        Ok(_) => (),
-        Err(glium::DrawError::RasterizerDiscardNotSupported) => return,
+        Ok(_) => assert!(supported),
+        Err(glium::DrawError::RasterizerDiscardNotSupported) => {
+            assert!(!supported);
+            return;
+        },
         e => e.unwrap()
     }
     texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
@@ -742,7 +979,7 @@ macro_rules! blending_test {
             ).unwrap();
 
             let texture = support::build_renderable_texture(&display);
-            texture.as_surface().clear(None, Some($source), false, None, None);
+            texture.as_surface().clear(None, &glium::ClearValues::new().color($source));
             texture.as_surface().draw(&vb, &ib, &program, &uniform!{ color: $dest },
                                       &params).unwrap();
 
@@ -772,6 +1009,108 @@ blending_test!(one_plus_one, glium::BlendingFunction::Addition {
                (0.0, 1.0, 1.0, 0.0), (1.0, 0.0, 0.0, 1.0), (255, 255, 255, 255));
 
 
+#[test]
+fn advanced_blending_multiply() {
+    let display = support::build_display();
+
+    if !glium::draw_parameters::is_advanced_blending_supported(&display) {
+        return;
+    }
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        330 => {
+            vertex: "
+                #version 330
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 330
+                #extension GL_KHR_blend_equation_advanced : require
+
+                layout(blend_support_multiply) out;
+                out vec4 color;
+
+                void main() {
+                    color = vec4(0.0, 1.0, 1.0, 1.0);
+                }
+            "
+        },
+        320 es => {
+            vertex: "
+                #version 320 es
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 320 es
+
+                layout(blend_support_multiply) out;
+                out lowp vec4 color;
+
+                void main() {
+                    color = vec4(0.0, 1.0, 1.0, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    let params = glium::DrawParameters {
+        blend: glium::Blend::advanced(glium::draw_parameters::AdvancedBlendEquation::Multiply),
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(1.0, 1.0, 0.0, 1.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn advanced_blending_mismatch() {
+    let display = support::build_display();
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        blend: glium::Blend {
+            color: glium::BlendingFunction::Advanced(
+                glium::draw_parameters::AdvancedBlendEquation::Screen
+            ),
+            alpha: glium::BlendingFunction::AlwaysReplace,
+            constant_value: (0.0, 0.0, 0.0, 0.0),
+        },
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::AdvancedBlendingMismatch) => (),
+        r => panic!("{:?}", r)
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn provoking_vertex_last() {
     let display = support::build_display();
@@ -1004,9 +1343,13 @@ fn depth_clamp_all() {
             .. Default::default()
         });
 
+    let supported = glium::draw_parameters::is_depth_clamp_supported(&display,
+                                    glium::draw_parameters::DepthClamp::Clamp);
+
     match res {
-        Ok(_) => (),
+        Ok(_) => assert!(supported),
         Err(glium::DrawError::DepthClampNotSupported) => {
+            assert!(!supported);
             display.assert_no_error(None);
             return;
         },
@@ -1115,9 +1458,13 @@ fn depth_clamp_near() {
             .. Default::default()
         });
 
+    let supported = glium::draw_parameters::is_depth_clamp_supported(&display,
+                                    glium::draw_parameters::DepthClamp::ClampNear);
+
     match res {
-        Ok(_) => (),
+        Ok(_) => assert!(supported),
         Err(glium::DrawError::DepthClampNotSupported) => {
+            assert!(!supported);
             display.assert_no_error(None);
             return;
         },
@@ -1234,9 +1581,13 @@ fn depth_clamp_far() {
             .. Default::default()
         });
 
+    let supported = glium::draw_parameters::is_depth_clamp_supported(&display,
+                                    glium::draw_parameters::DepthClamp::ClampFar);
+
     match res {
-        Ok(_) => (),
+        Ok(_) => assert!(supported),
         Err(glium::DrawError::DepthClampNotSupported) => {
+            assert!(!supported);
             display.assert_no_error(None);
             return;
         },
@@ -1408,3 +1759,393 @@ fn primitive_restart_index() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn default_tessellation_levels() {
+    let display = support::build_display();
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display, &[
+            Vertex { position: [-1.0, -1.0] },
+            Vertex { position: [-1.0,  1.0] },
+            Vertex { position: [ 1.0, -1.0] },
+            Vertex { position: [ 1.0, -1.0] },
+            Vertex { position: [-1.0,  1.0] },
+            Vertex { position: [ 1.0,  1.0] },
+        ]).unwrap()
+    };
+
+    // the program has no tessellation control shader
+    let program = glium::Program::new(&display,
+        glium::program::ProgramCreationInput::SourceCode {
+            vertex_shader: "
+                #version 400
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: Some("
+                #version 400
+
+                layout(triangles, equal_spacing) in;
+
+                void main() {
+                    gl_Position = gl_TessCoord.x * gl_in[0].gl_Position +
+                                  gl_TessCoord.y * gl_in[1].gl_Position +
+                                  gl_TessCoord.z * gl_in[2].gl_Position;
+                }
+            "),
+            geometry_shader: None,
+            fragment_shader: "
+                #version 400
+
+                out vec4 color;
+                void main() {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+            transform_feedback_varyings: None,
+            outputs_srgb: false,
+            uses_point_size: false,
+        });
+
+    let program = match program {
+        Err(_) => return,
+        Ok(p) => p
+    };
+
+    let params = glium::DrawParameters {
+        default_tessellation_levels: glium::draw_parameters::TessellationLevels {
+            outer: [4.0, 4.0, 4.0, 1.0],
+            inner: [4.0, 1.0],
+        },
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    let res = texture.as_surface().draw(&vertex_buffer,
+        &glium::index::NoIndices(PrimitiveType::Patches { vertices_per_patch: 3 }), &program,
+        &glium::uniforms::EmptyUniforms, &params);
+
+    match res {
+        Ok(_) => (),
+        Err(glium::DrawError::DefaultTessellationLevelsNotSupported) |
+        Err(glium::DrawError::TessellationNotSupported) => {
+            display.assert_no_error(None);
+            return;
+        },
+        e => e.unwrap(),
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn hazard_image_written_while_sampled() {
+    let display = support::build_display();
+    let (vb, ib, _) = support::build_fullscreen_red_pipeline(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 420
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 420
+
+            uniform sampler2D tex;
+            layout(rgba8) writeonly uniform image2D img;
+
+            out vec4 color;
+
+            void main() {
+                color = texture(tex, vec2(0.5, 0.5));
+                imageStore(img, ivec2(gl_FragCoord.xy), color);
+            }
+        ", None);
+
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    let target = support::build_renderable_texture(&display);
+
+    let image = match texture.image_unit(glium::uniforms::ImageUnitFormat::RGBA8) {
+        Ok(i) => i.set_access(glium::uniforms::ImageUnitAccess::Write),
+        Err(_) => return
+    };
+
+    let params = glium::DrawParameters {
+        check_hazards: true,
+        .. Default::default()
+    };
+
+    let uniforms = uniform!{ tex: &texture, img: image };
+    match target.as_surface().draw(&vb, &ib, &program, &uniforms, &params) {
+        Err(glium::DrawError::ImageWrittenWhileSampled { image, sampler }) => {
+            assert_eq!(image, "img");
+            assert_eq!(sampler, "tex");
+        },
+        e => panic!("{:?}", e)
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn hash_and_eq() {
+    use std::collections::HashSet;
+
+    let default = glium::DrawParameters::default();
+    let depth_test = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+    let negative_zero = glium::DrawParameters {
+        polygon_offset: glium::draw_parameters::PolygonOffset {
+            factor: -0.0,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    assert_eq!(default, glium::DrawParameters::default());
+    assert_eq!(default, negative_zero);
+    assert!(default != depth_test);
+
+    let mut set = HashSet::new();
+    set.insert(default.clone());
+    assert!(set.contains(&negative_zero));
+    assert!(!set.contains(&depth_test));
+    set.insert(depth_test);
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn polygon_offset_clamp() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        polygon_offset: glium::draw_parameters::PolygonOffset {
+            factor: 4.0,
+            units: 1.0,
+            clamp: 0.01,
+            fill: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let supported = glium::draw_parameters::is_polygon_offset_clamp_supported(&display);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Ok(_) => assert!(supported),
+        Err(glium::DrawError::PolygonOffsetNotSupported) => {
+            assert!(!supported);
+            display.assert_no_error(None);
+            return;
+        },
+        e => e.unwrap(),
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clip_planes_bitmask_out_of_bounds() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let max = glium::CapabilitiesSource::get_capabilities(&display).max_clip_distances;
+    if max >= 32 {
+        return;
+    }
+
+    let params = glium::DrawParameters {
+        clip_planes_bitmask: 1 << max,
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::ClipPlaneIndexOutOfBounds) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn viewports_without_viewport_index() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let (width, height) = (texture.get_width(), texture.get_height().unwrap());
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        viewports: vec![
+            glium::Rect { left: 0, bottom: 0, width: width / 2, height },
+            glium::Rect { left: width / 2, bottom: 0, width: width / 2, height },
+        ],
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Ok(_) => assert!(glium::draw_parameters::is_viewport_array_supported(&display)),
+        Err(glium::DrawError::TooManyViewports) => {
+            assert!(!glium::draw_parameters::is_viewport_array_supported(&display));
+            display.assert_no_error(None);
+            return;
+        },
+        e => e.unwrap(),
+    }
+
+    // primitives that don't write `gl_ViewportIndex` only go to the first viewport
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for (x, pixel) in row.iter().enumerate() {
+            if (x as u32) < width / 2 {
+                assert_eq!(pixel, &(255, 0, 0, 255));
+            } else {
+                assert_eq!(pixel, &(0, 0, 0, 0));
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn reversed_z() {
+    let display = support::build_display();
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 3],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        // a fullscreen quad at Z = 0.25 in clip space, which is stored as a depth of 0.25 with
+        // `ZeroToOne` and would be stored as 0.625 with `NegativeOneToOne`
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { position: [-1.0,  1.0, 0.25] }, Vertex { position: [1.0,  1.0, 0.25] },
+                Vertex { position: [-1.0, -1.0, 0.25] }, Vertex { position: [1.0, -1.0, 0.25] },
+            ]
+        ).unwrap()
+    };
+
+    let index_buffer = glium::index::NoIndices(PrimitiveType::TriangleStrip);
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                out vec4 color;
+
+                void main() {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    let params = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::draw_parameters::DepthTest::IfMore,
+            write: true,
+            .. Default::default()
+        },
+        clip_control_depth: glium::draw_parameters::ClipControlDepth::ZeroToOne,
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                                                           glium::texture::DepthFormat::F32,
+                                                           texture.get_width(),
+                                                           texture.get_height().unwrap()).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                               &texture,
+                                                                               &depth).unwrap();
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 0.5);
+
+    let supported = glium::draw_parameters::is_clip_control_supported(&display);
+
+    match framebuffer.draw(&vertex_buffer, &index_buffer, &program,
+                           &glium::uniforms::EmptyUniforms, &params)
+    {
+        Ok(_) => assert!(supported),
+        Err(glium::DrawError::ClipControlNotSupported) => {
+            assert!(!supported);
+            display.assert_no_error(None);
+            return;
+        },
+        e => e.unwrap(),
+    }
+
+    // the quad is behind the cleared depth, so nothing must have been drawn
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    display.assert_no_error(None);
+}
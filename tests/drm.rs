@@ -0,0 +1,34 @@
+#![cfg(all(feature = "drm", target_os = "linux"))]
+
+use glium::Surface;
+use glium::backend::drm::{DrmCreationError, DrmDisplay};
+
+#[test]
+fn missing_device() {
+    match DrmDisplay::open("/dev/dri/missing") {
+        Err(DrmCreationError::IoError(_)) => (),
+        other => panic!("{:?}", other.err()),
+    }
+}
+
+#[test]
+fn present() {
+    // requires a screen and no display server running
+    let display = match DrmDisplay::new() {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+
+    let mode = display.mode().clone();
+    assert_eq!(display.get_framebuffer_dimensions(), (mode.width, mode.height));
+    assert!(mode.refresh_rate_millihertz > 0);
+
+    // the first frame sets the mode, the next ones are page flips
+    for _ in 0 .. 3 {
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 1.0, 1.0);
+        frame.finish().unwrap();
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate glium;
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use glium::Surface;
+use glium::util::FrameBudget;
+
+mod support;
+
+#[test]
+fn frame_budget_runs_all_jobs() {
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+    let runs = Cell::new(0);
+
+    let mut budget = FrameBudget::new(&display, Duration::from_millis(4));
+    for _ in 0 .. 16 {
+        budget.push(|_| {
+            texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+            runs.set(runs.get() + 1);
+        });
+    }
+    assert_eq!(budget.len(), 16);
+
+    let mut frames = 0;
+    while !budget.is_empty() {
+        assert!(budget.run_frame() >= 1);
+        frames += 1;
+    }
+
+    assert_eq!(runs.get(), 16);
+    assert!(frames <= 16);
+    assert_eq!(budget.run_frame(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_budget_zero_budget() {
+    let display = support::build_display();
+
+    // with a budget of zero, jobs run one per frame
+    let mut budget = FrameBudget::new(&display, Duration::new(0, 0));
+    for _ in 0 .. 4 {
+        budget.push(|_| ());
+    }
+
+    for remaining in (0 .. 4).rev() {
+        assert_eq!(budget.run_frame(), 1);
+        assert_eq!(budget.len(), remaining);
+    }
+
+    display.assert_no_error(None);
+}
@@ -447,3 +447,383 @@ fn empty_framebuffer_simple_draw() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn texture_barrier_feedback_loop() {
+    let display = support::build_display();
+    if !glium::framebuffer::is_texture_barrier_supported(&display) {
+        return;
+    }
+
+    let (vertex_buffer, index_buffer, red_program) = support::build_fullscreen_red_pipeline(&display);
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                uniform sampler2D tex;
+                out vec4 color;
+
+                void main() {
+                    color = texelFetch(tex, ivec2(gl_FragCoord.xy), 0).gbra;
+                }
+            ",
+        },
+    ).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    let uniforms = uniform!{
+        tex: texture.sampled()
+                    .magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest)
+                    .minify_filter(glium::uniforms::MinifySamplerFilter::Nearest)
+    };
+
+    // the attachments are only tracked by draw commands that check for hazards
+    let checked = glium::DrawParameters {
+        check_hazards: true,
+        .. Default::default()
+    };
+    framebuffer.draw(&vertex_buffer, &index_buffer, &red_program, &uniform!{}, &checked).unwrap();
+
+    match framebuffer.draw(&vertex_buffer, &index_buffer, &program, &uniforms, &checked) {
+        Err(glium::DrawError::TextureFeedbackLoop { ref sampler }) if sampler == "tex" => (),
+        e => panic!("{:?}", e)
+    }
+
+    let params = glium::DrawParameters {
+        check_hazards: true,
+        auto_texture_barrier: true,
+        .. Default::default()
+    };
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program, &uniforms, &params).unwrap();
+
+    framebuffer.texture_barrier().unwrap();
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program, &uniforms, &checked).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multioutput_clear_attachments() {
+    let display = support::build_display();
+
+    let color1 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    let color2 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+
+    let mut framebuffer = match glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("color2", &color2)].iter().cloned())
+    {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    let values = glium::ClearValues::new()
+        .color((0.0, 0.0, 1.0, 1.0))
+        .attachment_color(1, glium::ClearColor::Float([1.0, 0.0, 0.0, 1.0]));
+    framebuffer.clear(None, &values);
+
+    let read_back1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    assert_eq!(read_back1[0][0], (0, 0, 255, 255));
+    assert_eq!(read_back1[127][127], (0, 0, 255, 255));
+
+    let read_back2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(read_back2[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back2[127][127], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sub_region() {
+    let display = support::build_display();
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let mut surface = texture.as_surface();
+    surface.clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let rect = glium::Rect { left: 256, bottom: 512, width: 512, height: 256 };
+        let mut sub_region = surface.sub_region(rect);
+        assert_eq!(sub_region.get_dimensions(), (512, 256));
+
+        sub_region.clear_color(0.0, 0.0, 1.0, 1.0);
+
+        // the viewport covers the left half of the sub-region
+        let params = glium::DrawParameters {
+            viewport: Some(glium::Rect { left: 0, bottom: 0, width: 256, height: 256 }),
+            .. Default::default()
+        };
+        sub_region.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+    assert_eq!(data[600][300], (255, 0, 0, 255));
+    assert_eq!(data[600][700], (0, 0, 255, 255));
+    assert_eq!(data[800][300], (0, 0, 0, 0));
+    assert_eq!(data[600][800], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn invalidate_attachments() {
+    let display = support::build_display();
+
+    let color = glium::Texture2d::empty_with_format(&display,
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                            glium::texture::MipmapsOption::NoMipmap,
+                                            128, 128).unwrap();
+    let depth = match glium::framebuffer::DepthRenderBuffer::new(&display,
+                                            glium::texture::DepthFormat::I24, 128, 128)
+    {
+        Err(_) => return,
+        Ok(d) => d
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                                   &color, &depth).unwrap();
+    framebuffer.clear_color_and_depth((1.0, 0.0, 0.0, 1.0), 1.0);
+
+    // the depth buffer and a part of the color attachment are discarded, and attachments that
+    // don't exist are ignored
+    framebuffer.invalidate(&[glium::Attachment::Depth, glium::Attachment::Stencil,
+                             glium::Attachment::Color(3)]);
+    framebuffer.invalidate_rect(&glium::Rect { left: 0, bottom: 0, width: 64, height: 64 },
+                                &[glium::Attachment::Color(0)]);
+
+    // the rest of the color attachment is untouched
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = color.read();
+    assert_eq!(data[100][100], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_only_target_shadow_comparison() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let depth_program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                }
+            ",
+        },
+    ).unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                varying vec2 v_tex_coords;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_tex_coords = position * 0.5 + 0.5;
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler2DShadow shadow_map;
+                uniform float reference;
+                varying vec2 v_tex_coords;
+
+                void main() {
+                    float lit = shadow2D(shadow_map, vec3(v_tex_coords, reference)).r;
+                    gl_FragColor = vec4(lit, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let shadow_map = glium::framebuffer::DepthOnlyTarget::new(&display, 64, 64).unwrap();
+    assert_eq!(shadow_map.get_dimensions(), (64, 64));
+
+    // the rectangle is at a depth of 0.5
+    let mut target = shadow_map.as_surface();
+    target.clear_depth(1.0);
+    target.draw(&vb, &ib, &depth_program, &uniform!{}, &glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    }).unwrap();
+
+    let texture = glium::Texture2d::empty(&display, 64, 64).unwrap();
+
+    for &(reference, expected) in &[(0.25f32, 255u8), (0.75, 0)] {
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{
+            shadow_map: shadow_map.sampled(),
+            reference: reference,
+        }, &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[32][32], (expected, 0, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn mipmap_level_attachments() {
+    let display = support::build_display();
+
+    let texture = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::EmptyMipmaps,
+                                               64, 64).unwrap();
+    let depth = glium::texture::DepthTexture2d::empty_with_format(&display,
+                                               glium::texture::DepthFormat::I24,
+                                               glium::texture::MipmapsOption::EmptyMipmaps,
+                                               64, 64).unwrap();
+
+    // a downsample chain
+    let mut chain = (1 .. texture.get_mipmap_levels())
+        .map(|level| glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                         texture.mipmap(level).unwrap(), depth.mipmap(level).unwrap()))
+        .collect::<Result<Vec<_>, _>>().unwrap();
+
+    for (level, framebuffer) in chain.iter_mut().enumerate() {
+        assert_eq!(framebuffer.get_dimensions(), (32 >> level, 32 >> level));
+        framebuffer.clear_color_and_depth((1.0, 0.0, 0.0, 1.0), 1.0);
+    }
+
+    // the names of the outputs don't need to outlive the framebuffers
+    let framebuffers = {
+        let name = String::from("color");
+        (1 .. 3).map(|level| glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                         vec![(&name[..], texture.mipmap(level).unwrap())]).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(framebuffers[1].get_dimensions(), (16, 16));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn array_layer_mipmap_attachment() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture2dArray::empty_with_format(&display,
+                                   glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                   glium::texture::MipmapsOption::EmptyMipmaps, 32, 32, 4)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display,
+                              texture.mipmap(2).unwrap().layer(3).unwrap()).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (8, 8));
+    framebuffer.clear_color(0.0, 1.0, 0.0, 1.0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn complete_framebuffers_pass_validation() {
+    let display = support::build_display();
+
+    let color = support::build_renderable_texture(&display);
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                            glium::texture::DepthFormat::I24, 1024, 1024).unwrap();
+    glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display, &color, &depth).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn incomplete_framebuffer_error_message() {
+    use glium::framebuffer::{AttachmentDiagnostic, AttachmentPoint, IncompletenessReason};
+
+    let error = glium::framebuffer::ValidationError::Incomplete {
+        reason: IncompletenessReason::IncompleteMultisample,
+        attachments: vec![
+            AttachmentDiagnostic {
+                point: AttachmentPoint::Color(0),
+                render_buffer: false,
+                level: 0,
+                internal_format: Some(0x8058),
+                dimensions: Some((128, 128)),
+                samples: Some(4),
+            },
+            AttachmentDiagnostic {
+                point: AttachmentPoint::Depth,
+                render_buffer: true,
+                level: 0,
+                internal_format: Some(0x81a6),
+                dimensions: Some((128, 128)),
+                samples: Some(0),
+            },
+        ],
+    };
+
+    let message = error.to_string();
+    assert!(message.contains("the same number of samples"));
+    assert!(message.contains("color attachment 0: texture level 0, format 0x8058, 128x128, 4 samples"));
+    assert!(message.contains("depth attachment: render buffer, format 0x81a6, 128x128, 0 samples"));
+}
+
+#[test]
+fn render_buffer_multisample_best_effort() {
+    let display = support::build_display();
+
+    let (buffer, samples) = glium::framebuffer::RenderBuffer::new_multisample_best_effort(&display,
+                                glium::texture::UncompressedFloatFormat::U8U8U8U8, 128, 128, 64)
+                                .unwrap();
+    assert!(samples <= 64);
+    assert_eq!(buffer.get_samples(), if samples == 0 { None } else { Some(samples) });
+
+    let (buffer, samples) = glium::framebuffer::RenderBuffer::new_multisample_best_effort(&display,
+                                glium::texture::UncompressedFloatFormat::U8U8U8U8, 128, 128, 0)
+                                .unwrap();
+    assert_eq!(samples, 0);
+    assert_eq!(buffer.get_samples(), None);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "glsl")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::program::glsl;
+
+mod support;
+
+const RED: f32 = 1.0;
+
+const VERTEX_SHADER: &str = glsl!(r#"
+    #version 110
+
+    attribute vec2 position;
+
+    void main() {
+        gl_Position = vec4(position, 0.0, 1.0);
+    }
+"#);
+
+#[test]
+fn interpolation() {
+    let source: String = glsl!("float value = ${RED};");
+    assert_eq!(source, "float value = 1;");
+
+    let source: String = glsl!("float values[${u8::MAX}];");
+    assert_eq!(source, "float values[255];");
+}
+
+#[test]
+fn glsl_program() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let fragment_shader = glsl!(r#"
+        #version 110
+
+        void main() {
+            gl_FragColor = vec4(${RED}, 0.0, 0.0, 1.0);
+        }
+    "#);
+
+    let program = glium::Program::from_source(&display, VERTEX_SHADER, &fragment_shader, None)
+                                  .unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
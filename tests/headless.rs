@@ -0,0 +1,1003 @@
+#![cfg(feature = "headless")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::backend::headless::HeadlessRenderer;
+
+mod support;
+
+#[test]
+fn clear_and_read() {
+    let renderer = match HeadlessRenderer::new(64, 32) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    assert_eq!(renderer.get_dimensions(), (64, 32));
+
+    renderer.draw().clear_color_and_depth((1.0, 0.0, 0.0, 1.0), 1.0);
+
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels.len(), 32);
+    assert!(pixels.iter().all(|row| row.iter().all(|&p| p == (255, 0, 0, 255))));
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn draw() {
+    let renderer = match HeadlessRenderer::new(1024, 1024) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&renderer);
+
+    let mut target = renderer.draw();
+    target.clear_color(0.0, 0.0, 0.0, 0.0);
+    target.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &Default::default())
+          .unwrap();
+
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels[512][512], (255, 0, 0, 255));
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn multioutput_framebuffer_blit() {
+    use glium::framebuffer::{DepthRenderBuffer, EmptyFrameBuffer, MultiOutputFrameBuffer};
+    use glium::framebuffer::SimpleFrameBuffer;
+    use glium::texture::{DepthFormat, Texture2d};
+    use glium::{BlitError, BlitMask, BlitTarget, Rect};
+    use glium::uniforms::MagnifySamplerFilter;
+
+    let renderer = match HeadlessRenderer::new(4, 4) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let color = Texture2d::empty(&renderer, 4, 4).unwrap();
+    let normal = Texture2d::empty(&renderer, 4, 4).unwrap();
+    let depth = DepthRenderBuffer::new(&renderer, DepthFormat::I24, 4, 4).unwrap();
+    let mut multi = MultiOutputFrameBuffer::with_depth_buffer(&renderer,
+                                                              [("color", &color),
+                                                               ("normal", &normal)],
+                                                              &depth).unwrap();
+    multi.clear_color_and_depth((0.0, 1.0, 0.0, 1.0), 0.5);
+
+    let other = Texture2d::empty(&renderer, 4, 4).unwrap();
+    let other_depth = DepthRenderBuffer::new(&renderer, DepthFormat::I24, 4, 4).unwrap();
+    let mut simple = SimpleFrameBuffer::with_depth_buffer(&renderer, &other, &other_depth)
+                                       .unwrap();
+    simple.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 1.0);
+
+    // the first output is copied
+    let rect = Rect { left: 0, bottom: 0, width: 4, height: 4 };
+    let target = BlitTarget { left: 0, bottom: 0, width: 4, height: 4 };
+    multi.blit_buffers(&rect, &simple, &target, MagnifySamplerFilter::Nearest,
+                       BlitMask::color_and_depth()).unwrap();
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = other.read();
+    assert_eq!(pixels[2][2], (0, 255, 0, 255));
+
+    simple.clear_color(1.0, 0.0, 0.0, 1.0);
+    simple.blit_depth(&rect, &multi, &target).unwrap();
+    simple.blit_whole_color_to(&multi, &target, MagnifySamplerFilter::Nearest);
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = color.read();
+    assert_eq!(pixels[2][2], (255, 0, 0, 255));
+
+    if EmptyFrameBuffer::is_supported(&renderer) {
+        let empty = EmptyFrameBuffer::new(&renderer, 4, 4, None, None, false).unwrap();
+        match empty.blit_buffers(&rect, &simple, &target, MagnifySamplerFilter::Nearest,
+                                 BlitMask::color()) {
+            Err(BlitError::NotSupported) => (),
+            e => panic!("{:?}", e),
+        }
+    }
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn devices() {
+    let devices = match glium::backend::headless::devices() {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+
+    for device in &devices {
+        if let Some(node) = device.drm_render_node_file() {
+            assert!(node.starts_with("/dev/dri/"));
+        }
+
+        let renderer = match HeadlessRenderer::with_device(device, 16, 16, Default::default()) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+
+        renderer.draw().clear_color(0.0, 1.0, 0.0, 1.0);
+        let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+        assert_eq!(pixels[8][8], (0, 255, 0, 255));
+        renderer.assert_no_error(None);
+    }
+}
+
+#[test]
+fn software() {
+    let renderer = match HeadlessRenderer::software(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    renderer.draw().clear_color(0.0, 0.0, 1.0, 1.0);
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels[0][0], (0, 0, 255, 255));
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn shared_objects() {
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        color: [f32; 3],
+    }
+
+    implement_vertex!(Vertex, position, color);
+
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let other = renderer.shared(16, 16).unwrap();
+
+    let program = program!(&renderer,
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+                attribute lowp vec3 color;
+                varying lowp vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 100
+
+                varying lowp vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let quad = |color| {
+        glium::VertexBuffer::new(&renderer, &[
+            Vertex { position: [-1.0, -1.0], color },
+            Vertex { position: [-1.0, 1.0], color },
+            Vertex { position: [1.0, -1.0], color },
+            Vertex { position: [1.0, 1.0], color },
+        ]).unwrap()
+    };
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleStrip);
+
+    // the buffer and the program of the first context are drawn by the second one
+    let red = quad([1.0, 0.0, 0.0]);
+    other.draw().draw(&red, &indices, &program, &glium::uniforms::EmptyUniforms,
+                      &Default::default()).unwrap();
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = other.texture().read();
+    assert_eq!(pixels[8][8], (255, 0, 0, 255));
+
+    // the second context must forget the vertex array object of the dropped buffer, in case
+    // the driver reuses its name
+    drop(red);
+    let green = quad([0.0, 1.0, 0.0]);
+    other.draw().draw(&green, &indices, &program, &glium::uniforms::EmptyUniforms,
+                      &Default::default()).unwrap();
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = other.texture().read();
+    assert_eq!(pixels[8][8], (0, 255, 0, 255));
+
+    renderer.assert_no_error(None);
+    other.assert_no_error(None);
+}
+
+#[test]
+fn sync_fence_shared_context() {
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let other = renderer.shared(16, 16).unwrap();
+
+    let fence = match glium::SyncFence::new(&renderer) {
+        Ok(f) => f,
+        Err(_) => return,
+    };
+    renderer.flush();
+
+    // a context that doesn't share its objects can't wait for the fence
+    if let Ok(unshared) = HeadlessRenderer::new(16, 16) {
+        assert_eq!(fence.wait_on_server(&unshared), Err(glium::ContextNotSharedError));
+    }
+
+    // the context that waits is the other one
+    fence.wait_on_server(&other).unwrap();
+    assert!(fence.wait_timeout(std::time::Duration::from_secs(10)));
+
+    #[cfg(feature = "gl_trace")]
+    {
+        let waits = |calls: Vec<String>| calls.iter().any(|call| call.starts_with("glWaitSync"));
+        assert!(waits(other.dump_recent_calls()));
+        assert!(!waits(renderer.dump_recent_calls()));
+    }
+
+    renderer.assert_no_error(None);
+    other.assert_no_error(None);
+}
+
+#[test]
+fn upload_thread() {
+    use glium::buffer::{BufferMode, BufferType};
+    use glium::texture::{RawImage2d, UncompressedFloatFormat};
+
+    struct NoopWaker;
+    impl std::task::Wake for NoopWaker {
+        fn wake(self: std::sync::Arc<Self>) {}
+    }
+
+    fn block_on<F: std::future::Future + Unpin>(mut future: F) -> F::Output {
+        let waker = std::task::Waker::from(std::sync::Arc::new(NoopWaker));
+        let mut cx = std::task::Context::from_waker(&waker);
+        loop {
+            if let std::task::Poll::Ready(output) = std::pin::Pin::new(&mut future).poll(&mut cx) {
+                return output;
+            }
+            std::thread::yield_now();
+        }
+    }
+
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let uploads = renderer.upload_thread().unwrap();
+
+    let image = RawImage2d::from_raw_rgba(vec![0u8, 255, 0, 255].repeat(8 * 8), (8, 8));
+    let texture = uploads.texture_2d(image, UncompressedFloatFormat::U8U8U8U8, true);
+    let buffer = uploads.buffer(vec![1u32, 2, 3, 4], BufferType::ArrayBuffer,
+                                BufferMode::Default);
+    let program = uploads.program("
+            #version 100
+
+            attribute lowp vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ".to_owned(), "
+            #version 100
+
+            void main() {
+                gl_FragColor = vec4(0.0, 0.0, 1.0, 1.0);
+            }
+        ".to_owned(), None);
+
+    let texture = block_on(texture).unwrap();
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(pixels[4][4], (0, 255, 0, 255));
+
+    let buffer = block_on(buffer).unwrap();
+    assert_eq!(buffer.read().unwrap(), vec![1, 2, 3, 4]);
+
+    let program = block_on(program).unwrap();
+    let (vb, ib, _) = support::build_fullscreen_red_pipeline(&renderer);
+    renderer.draw().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                         &Default::default()).unwrap();
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels[8][8], (0, 0, 255, 255));
+
+    // dropping a pending upload destroys the object on the worker
+    drop(uploads.buffer(vec![0u8; 16], BufferType::ArrayBuffer, BufferMode::Default));
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn pbuffer_display() {
+    use glium::CapabilitiesSource;
+    use glium::backend::glutin::pbuffer::PbufferDisplay;
+    use glium::glutin::api::egl;
+
+    let device = match egl::device::Device::query_devices().ok().and_then(|mut d| d.next()) {
+        Some(d) => d,
+        None => return,
+    };
+    let gl_display = match unsafe { egl::display::Display::with_device(&device, None) } {
+        Ok(d) => glium::glutin::display::Display::Egl(d),
+        Err(_) => return,
+    };
+    let display = match PbufferDisplay::from_display(&gl_display, 32, 16) {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+
+    assert_eq!(display.get_framebuffer_dimensions(), (32, 16));
+
+    let (red, green, blue, _) = display.get_capabilities().color_bits;
+    assert!(red >= 5 && green >= 5 && blue >= 5);
+    assert!(!display.get_capabilities().float_color);
+
+    let texture = glium::texture::Texture2d::empty(&display, 32, 16).unwrap();
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(pixels[8][16], (0, 255, 0, 255));
+
+    let mut frame = display.draw();
+    frame.clear_color(1.0, 0.0, 0.0, 1.0);
+    frame.finish().unwrap();
+
+    let damage = glium::Rect { left: 4, bottom: 4, width: 8, height: 8 };
+    let mut frame = display.draw();
+    frame.clear(Some(&damage), &glium::ClearValues::new().color((0.0, 0.0, 1.0, 1.0)));
+    frame.finish_with_damage(&[damage]).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn foreign_state() {
+    use glium::backend::ScopedForeignFrame;
+    use glium::glutin::api::egl;
+    use glium::glutin::prelude::*;
+    use std::ffi::CString;
+    use std::os::raw::c_void;
+
+    const SCISSOR_TEST: u32 = 0x0C11;
+    const COLOR_CLEAR_VALUE: u32 = 0x0C22;
+
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let device = egl::device::Device::query_devices().unwrap().next().unwrap();
+    let gl_display = unsafe { egl::display::Display::with_device(&device, None) }.unwrap();
+    let load = |name: &str| -> *const c_void {
+        gl_display.get_proc_address(&CString::new(name).unwrap())
+    };
+
+    let clear_color: extern "system" fn(f32, f32, f32, f32) =
+        unsafe { std::mem::transmute(load("glClearColor")) };
+    let get_floatv: extern "system" fn(u32, *mut f32) =
+        unsafe { std::mem::transmute(load("glGetFloatv")) };
+    let enable: extern "system" fn(u32) = unsafe { std::mem::transmute(load("glEnable")) };
+    let disable: extern "system" fn(u32) = unsafe { std::mem::transmute(load("glDisable")) };
+    let is_enabled: extern "system" fn(u32) -> u8 =
+        unsafe { std::mem::transmute(load("glIsEnabled")) };
+
+    let red = (255, 0, 0, 255);
+    renderer.draw().clear_color(1.0, 0.0, 0.0, 1.0);
+
+    // the clear color is changed behind glium's back
+    unsafe { renderer.exec_in_context(|| clear_color(0.0, 0.0, 1.0, 1.0)) };
+    renderer.invalidate_cached_state();
+    renderer.draw().clear_color(1.0, 0.0, 0.0, 1.0);
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels[8][8], red);
+
+    // the foreign state is restored once the guard is dropped
+    unsafe {
+        renderer.exec_in_context(|| {
+            clear_color(0.0, 0.0, 1.0, 1.0);
+            enable(SCISSOR_TEST);
+        })
+    };
+    {
+        let _guard = ScopedForeignFrame::new(&renderer);
+        renderer.draw().clear_color(0.0, 1.0, 0.0, 1.0);
+    }
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels[8][8], (0, 255, 0, 255));
+
+    let (clear, scissor) = unsafe {
+        renderer.exec_in_context(|| {
+            let mut clear = [0.0f32; 4];
+            get_floatv(COLOR_CLEAR_VALUE, clear.as_mut_ptr());
+            let scissor = is_enabled(SCISSOR_TEST) != 0;
+            disable(SCISSOR_TEST);
+            (clear, scissor)
+        })
+    };
+    assert_eq!(clear, [0.0, 0.0, 1.0, 1.0]);
+    assert!(scissor);
+
+    renderer.invalidate_cached_state();
+    renderer.draw().clear_color(1.0, 0.0, 0.0, 1.0);
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels[8][8], red);
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn gles2_feature_level() {
+    use glium::backend::FeatureLevel;
+    use glium::{Api, CapabilitiesSource, Version};
+
+    let renderer = match HeadlessRenderer::with_feature_level(16, 16, FeatureLevel::Gles2) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    assert_eq!(renderer.get_feature_level(), FeatureLevel::Gles2);
+    assert_eq!(*renderer.get_opengl_version(), Version(Api::GlEs, 2, 0));
+    assert!(!renderer.get_extensions().gl_ext_disjoint_timer_query);
+    assert!(glium::uniforms::UniformBuffer::new(&renderer, 0u32).is_err());
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&renderer);
+
+    let mut target = renderer.draw();
+    target.clear_color(0.0, 0.0, 0.0, 0.0);
+    target.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &Default::default())
+          .unwrap();
+
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels[8][8], (255, 0, 0, 255));
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn dmabuf_roundtrip() {
+    use glium::texture::dmabuf::{self, DmaBuf, DmaBufError};
+    use glium::texture::{MipmapsOption, Texture2d, UncompressedFloatFormat};
+
+    let renderer = match HeadlessRenderer::new(64, 32) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let empty = DmaBuf { width: 64, height: 32, fourcc: dmabuf::ABGR8888, modifier: None,
+                         planes: Vec::new() };
+    assert!(matches!(dmabuf::import(&renderer, &empty), Err(DmaBufError::InvalidPlaneCount(0))));
+    let yuv = DmaBuf { fourcc: dmabuf::fourcc(*b"NV12"), ..empty };
+    assert!(matches!(dmabuf::import(&renderer, &yuv), Err(DmaBufError::UnsupportedFormat(_))));
+
+    let texture = Texture2d::empty_with_format(&renderer, UncompressedFloatFormat::U8U8U8U8,
+                                               MipmapsOption::NoMipmap, 64, 32).unwrap();
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let buffer = match dmabuf::export(&texture) {
+        Ok(b) => b,
+        Err(DmaBufError::ExtensionNotSupported(_)) => return,
+        Err(e) => panic!("{}", e),
+    };
+    assert_eq!((buffer.width, buffer.height), (64, 32));
+    assert!(!buffer.planes.is_empty());
+
+    let imported = match dmabuf::import(&renderer, &buffer) {
+        Ok(t) => t,
+        Err(DmaBufError::ExtensionNotSupported(_)) => return,
+        Err(e) => panic!("{}", e),
+    };
+    drop(buffer);
+
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = imported.read();
+    assert!(pixels.iter().all(|row| row.iter().all(|&p| p == (0, 255, 0, 255))));
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn buffer_from_memory_object() {
+    use glium::buffer::{Buffer, BufferType};
+    use glium::memory_object::MemoryObject;
+    use std::io::Write;
+    use std::os::fd::FromRawFd;
+
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    // a memfd plays the role of memory exported by Vulkan
+    let fd = unsafe { libc::memfd_create(b"glium\0".as_ptr() as *const _, 0) };
+    assert!(fd >= 0);
+    let mut file = unsafe { std::fs::File::from_raw_fd(fd) };
+    let mut content = vec![0u8; 4096];
+    for (i, value) in content[1024..1024 + 64 * 4].chunks_mut(4).enumerate() {
+        value.copy_from_slice(&(i as u32).to_ne_bytes());
+    }
+    file.write_all(&content).unwrap();
+
+    let memory = match unsafe { MemoryObject::new_from_fd(&renderer, false, file, 4096) } {
+        Ok(m) => m,
+        Err(_) => return,
+    };
+    // the file descriptor is now owned by the implementation, which may reject memory that it
+    // didn't allocate itself
+    let buffer: Buffer<[u32]> = match unsafe {
+        Buffer::from_memory_object(&renderer, &memory, 1024, 64 * 4, BufferType::ArrayBuffer)
+    } {
+        Ok(b) => b,
+        Err(_) => return,
+    };
+
+    assert_eq!(buffer.len(), 64);
+    assert_eq!(buffer.read().unwrap(), (0..64).collect::<Vec<u32>>());
+
+    buffer.slice(0..2).unwrap().write(&[7, 8]);
+    assert_eq!(&buffer.read().unwrap()[..3], &[7, 8, 2]);
+
+    drop(buffer);
+    renderer.assert_no_error(None);
+}
+
+#[cfg(feature = "cuda")]
+#[test]
+fn cuda_access() {
+    use glium::buffer::{Buffer, BufferMode, BufferType};
+    use glium::cuda::Access;
+    use glium::texture::Texture2d;
+
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let mut buffer = Buffer::new(&renderer, &[[0.0f32; 4]; 8][..], BufferType::ArrayBuffer,
+                                 BufferMode::Dynamic).unwrap();
+    {
+        let mut mapping = buffer.map_write();
+        mapping.set(0, [1.0; 4]);
+        // the mapping is forgotten, so that the buffer is still mapped when it is lent
+        std::mem::forget(mapping);
+    }
+    let id = {
+        let access = Access::buffer(&mut buffer);
+        assert_eq!(access.target(), 0x8892);    // GL_ARRAY_BUFFER
+        access.id()
+    };
+    assert_eq!(id, glium::GlObject::get_id(&buffer));
+    assert_eq!(buffer.read().unwrap()[0], [1.0; 4]);
+
+    let mut texture = Texture2d::empty(&renderer, 4, 4).unwrap();
+    let access = Access::texture(&mut texture);
+    assert_eq!(access.target(), 0x0DE1);        // GL_TEXTURE_2D
+    drop(access);
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn external_texture() {
+    use glium::backend::FeatureLevel;
+    use glium::glutin::api::egl;
+    use glium::glutin::prelude::*;
+    use glium::texture::external::{self, ExternalTexture};
+    use glium::texture::Texture2d;
+    use std::ffi::CString;
+    use std::os::raw::c_void;
+
+    const EGL_GL_TEXTURE_2D_KHR: u32 = 0x30B1;
+    const EGL_NONE: i32 = 0x3038;
+
+    let renderer = match HeadlessRenderer::with_feature_level(16, 16, FeatureLevel::Gles2) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if !external::is_supported(&renderer) {
+        return;
+    }
+
+    let device = egl::device::Device::query_devices().unwrap().next().unwrap();
+    let gl_display = unsafe { egl::display::Display::with_device(&device, None) }.unwrap();
+    let load = |name: &str| -> *const c_void {
+        gl_display.get_proc_address(&CString::new(name).unwrap())
+    };
+    let get_current_display: extern "system" fn() -> *const c_void =
+        unsafe { std::mem::transmute(load("eglGetCurrentDisplay")) };
+    let get_current_context: extern "system" fn() -> *const c_void =
+        unsafe { std::mem::transmute(load("eglGetCurrentContext")) };
+    let create_image: extern "system" fn(*const c_void, *const c_void, u32, *const c_void,
+                                         *const i32) -> *const c_void =
+        unsafe { std::mem::transmute(load("eglCreateImageKHR")) };
+    let destroy_image: extern "system" fn(*const c_void, *const c_void) -> u32 =
+        unsafe { std::mem::transmute(load("eglDestroyImageKHR")) };
+
+    let source = Texture2d::empty(&renderer, 16, 16).unwrap();
+    source.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let id = glium::GlObject::get_id(&source) as usize;
+    let (display, image) = unsafe {
+        renderer.exec_in_context(|| {
+            let display = get_current_display();
+            let image = create_image(display, get_current_context(), EGL_GL_TEXTURE_2D_KHR,
+                                     id as *const c_void, [EGL_NONE].as_ptr());
+            (display as usize, image as usize)
+        })
+    };
+    let (display, image) = (display as *const c_void, image as *const c_void);
+    if image.is_null() {
+        return;
+    }
+
+    let texture = unsafe { ExternalTexture::from_egl_image(&renderer, image) }.unwrap();
+    unsafe { renderer.exec_in_context(|| destroy_image(display, image)) };
+
+    let (vb, ib, _) = support::build_fullscreen_red_pipeline(&renderer);
+    let program = glium::Program::from_source(&renderer,
+        "
+            #version 100
+
+            attribute vec2 position;
+            varying vec2 v_tex_coords;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+                v_tex_coords = position * 0.5 + 0.5;
+            }
+        ",
+        "
+            #version 100
+            #extension GL_OES_EGL_image_external : require
+            precision mediump float;
+
+            uniform samplerExternalOES frame;
+            varying vec2 v_tex_coords;
+
+            void main() {
+                gl_FragColor = texture2D(frame, v_tex_coords);
+            }
+        ", None).unwrap();
+
+    let mut target = renderer.draw();
+    target.clear_color(0.0, 0.0, 0.0, 0.0);
+    target.draw(&vb, &ib, &program, &uniform! { frame: &texture }, &Default::default()).unwrap();
+
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    assert_eq!(pixels[8][8], (0, 255, 0, 255));
+
+    drop(texture);
+    renderer.assert_no_error(None);
+}
+
+#[cfg(feature = "gltf")]
+#[test]
+fn gltf_model() {
+    use glium::gltf::{AlphaMode, Model};
+    use glium::uniforms::{MinifySamplerFilter, Sampler};
+
+    // a triangle covering the viewport, textured with a single orange texel
+    const DOCUMENT: &str = concat!(
+        r#"{"asset":{"version":"2.0"},"buffers":[{"byteLength":68,"uri":"data:application/"#,
+        r#"octet-stream;base64,AACAvwAAgL8AAAAAAABAQAAAgL8AAAAAAACAvwAAQEAAAAAAAAAAAAAAAAAA"#,
+        r#"AAAAAAAAAAAAAAAAAAAAAAABAAIAAAA="}],"bufferViews":[{"buffer":0,"byteOffset":0,"#,
+        r#""byteLength":36},{"buffer":0,"byteOffset":36,"byteLength":24},{"buffer":0,"#,
+        r#""byteOffset":60,"byteLength":6}],"accessors":[{"bufferView":0,"componentType":5126,"#,
+        r#""count":3,"type":"VEC3","min":[-1,-1,0],"max":[3,3,0]},{"bufferView":1,"#,
+        r#""componentType":5126,"count":3,"type":"VEC2"},{"bufferView":2,"componentType":5123,"#,
+        r#""count":3,"type":"SCALAR"}],"images":[{"uri":"data:image/png;base64,iVBORw0KGgoAAAA"#,
+        r#"NSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR4nGP438DwHwAGgAJ/EEwb4QAAAABJRU5ErkJggg=="}],"#,
+        r#""samplers":[{"magFilter":9728,"minFilter":9728,"wrapS":33071,"wrapT":33071}],"#,
+        r#""textures":[{"sampler":0,"source":0}],"materials":[{"name":"orange","#,
+        r#""pbrMetallicRoughness":{"baseColorTexture":{"index":0},"metallicFactor":0.0},"#,
+        r#""alphaMode":"MASK","alphaCutoff":0.25}],"meshes":[{"name":"triangle","primitives":"#,
+        r#"[{"attributes":{"POSITION":0,"TEXCOORD_0":1},"indices":2,"material":0}]}]}"#,
+    );
+
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    // `import_slice` only decodes the images of data URIs when a base path is given
+    let document = gltf::Gltf::from_slice(DOCUMENT.as_bytes()).unwrap().document;
+    let buffers = gltf::import_buffers(&document, None, None).unwrap();
+    let base = std::path::Path::new(".");
+    let images = gltf::import_images(&document, Some(base), &buffers).unwrap();
+    let model = Model::load(&renderer, &document, &buffers, &images).unwrap();
+
+    assert_eq!(model.meshes.len(), 1);
+    assert_eq!(model.meshes[0].name.as_deref(), Some("triangle"));
+    let primitive = &model.meshes[0].primitives[0];
+    assert_eq!(primitive.vertices.len(), 3);
+    assert_eq!(primitive.vertices.get_elements_size(), 5 * 4);
+    assert!(primitive.index_buffer.is_some());
+    assert_eq!(primitive.material, Some(0));
+
+    let material = model.material(primitive);
+    assert_eq!(material.name.as_deref(), Some("orange"));
+    assert_eq!(material.metallic_factor, 0.0);
+    assert_eq!(material.alpha_mode, AlphaMode::Mask(0.25));
+    let texture = material.base_color_texture.unwrap();
+    assert_eq!(texture.sampler.minify_filter, MinifySamplerFilter::Nearest);
+    assert_eq!((model.srgb_textures.len(), model.textures.len()), (1, 0));
+
+    let program = glium::Program::from_source(&renderer,
+        "
+            #version 110
+
+            attribute vec3 position;
+            attribute vec2 tex_coords_0;
+            varying vec2 v_tex_coords;
+
+            void main() {
+                gl_Position = vec4(position, 1.0);
+                v_tex_coords = tex_coords_0;
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D base_color;
+            varying vec2 v_tex_coords;
+
+            void main() {
+                gl_FragColor = texture2D(base_color, v_tex_coords);
+            }
+        ", None).unwrap();
+
+    let uniforms = uniform! {
+        base_color: Sampler(&model.srgb_textures[texture.index], texture.sampler),
+    };
+    let mut target = renderer.draw();
+    target.clear_color(0.0, 0.0, 0.0, 0.0);
+    target.draw(&primitive.vertices, primitive.indices(), &program, &uniforms,
+                &Default::default()).unwrap();
+
+    // the texel is decoded from sRGB when it is sampled
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+    let (r, g, b, _) = pixels[8][8];
+    assert_eq!((r, b), (255, 0));
+    assert!((50 ..= 60).contains(&g), "{}", g);
+
+    renderer.assert_no_error(None);
+}
+
+#[cfg(feature = "egui")]
+#[test]
+fn egui_painter() {
+    use std::rc::Rc;
+    use egui::epaint::{ClippedPrimitive, ColorImage, ImageDelta, Mesh, Primitive};
+    use egui::{pos2, Color32, Rect, TextureId, TextureOptions, TexturesDelta};
+    use glium::egui::Painter;
+
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let mut painter = Painter::new(&renderer).unwrap();
+    assert!(painter.max_texture_side() >= 16);
+
+    // a mesh covering the surface, which is 8x8 points, clipped to its top left quarter
+    let screen = Rect::from_min_max(pos2(0.0, 0.0), pos2(8.0, 8.0));
+    let primitive = |texture_id, uv: Rect| {
+        let mut mesh = Mesh::with_texture(texture_id);
+        mesh.add_rect_with_uv(screen, uv, Color32::WHITE);
+        ClippedPrimitive {
+            clip_rect: Rect::from_min_max(pos2(0.0, 0.0), pos2(4.0, 4.0)),
+            primitive: Primitive::Mesh(mesh),
+        }
+    };
+    let paint = |painter: &mut Painter, primitives: &[ClippedPrimitive], delta: &TexturesDelta| {
+        let mut target = renderer.draw();
+        target.clear_color(0.0, 0.0, 0.0, 0.0);
+        painter.paint_and_update_textures(&mut target, 2.0, primitives, delta).unwrap();
+        drop(target);
+        let pixels: Vec<Vec<(u8, u8, u8, u8)>> = renderer.texture().read();
+        pixels
+    };
+
+    // the left texel is red and the right one green
+    let managed = TextureId::Managed(0);
+    let image = ColorImage::new([2, 1], vec![Color32::RED, Color32::GREEN]);
+    let delta = TexturesDelta {
+        set: vec![(managed, ImageDelta::full(image, TextureOptions::NEAREST))],
+        free: vec![],
+    };
+    let left = Rect::from_min_max(pos2(0.0, 0.0), pos2(0.5, 1.0));
+    let pixels = paint(&mut painter, &[primitive(managed, left)], &delta);
+    // the rows are read from the bottom
+    assert_eq!(pixels[12][4], (255, 0, 0, 255));
+    assert_eq!(pixels[4][4], (0, 0, 0, 0));
+    assert_eq!(pixels[12][12], (0, 0, 0, 0));
+
+    // replacing the left texel only
+    let image = ColorImage::new([1, 1], vec![Color32::BLUE]);
+    let delta = TexturesDelta {
+        set: vec![(managed, ImageDelta::partial([0, 0], image, TextureOptions::NEAREST))],
+        free: vec![managed],
+    };
+    let right = Rect::from_min_max(pos2(0.5, 0.0), pos2(1.0, 1.0));
+    let pixels = paint(&mut painter, &[primitive(managed, left)], &delta);
+    assert_eq!(pixels[12][4], (0, 0, 255, 255));
+
+    // the texture has been freed after painting
+    let pixels = paint(&mut painter, &[primitive(managed, right)], &TexturesDelta::default());
+    assert_eq!(pixels[12][4], (0, 0, 0, 0));
+
+    // a 2x2 texture drawn on a single pixel samples the second mipmap level, which must follow
+    // partial updates
+    let mipmapped = TextureId::Managed(1);
+    let options = TextureOptions::NEAREST.with_mipmap_mode(Some(egui::TextureFilter::Nearest));
+    let minified = |painter: &mut Painter, delta: &TexturesDelta| {
+        let mut mesh = Mesh::with_texture(mipmapped);
+        mesh.add_rect_with_uv(Rect::from_min_max(pos2(0.0, 0.0), pos2(0.5, 0.5)),
+                              Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
+        let primitive = ClippedPrimitive { clip_rect: screen, primitive: Primitive::Mesh(mesh) };
+        paint(painter, &[primitive], delta)[15][0]
+    };
+    let image = ColorImage::new([2, 2], vec![Color32::RED; 4]);
+    let delta = TexturesDelta { set: vec![(mipmapped, ImageDelta::full(image, options))],
+                                free: vec![] };
+    assert_eq!(minified(&mut painter, &delta), (255, 0, 0, 255));
+    let image = ColorImage::new([2, 2], vec![Color32::BLUE; 4]);
+    let delta = TexturesDelta { set: vec![(mipmapped, ImageDelta::partial([0, 0], image, options))],
+                                free: vec![mipmapped] };
+    assert_eq!(minified(&mut painter, &delta), (0, 0, 255, 255));
+
+    // the texels of sRGB textures are written as they are stored
+    let data = glium::texture::RawImage2d::from_raw_rgba(vec![128u8, 128, 128, 255], (1, 1));
+    let texture = Rc::new(glium::texture::SrgbTexture2d::new(&renderer, data).unwrap());
+    let native = painter.register_native_texture(texture, TextureOptions::NEAREST);
+    let full = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
+    let pixels = paint(&mut painter, &[primitive(native, full)], &TexturesDelta::default());
+    let (r, _, _, a) = pixels[12][4];
+    assert!((126 ..= 130).contains(&r), "{}", r);
+    assert_eq!(a, 255);
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn feedback_loop_tracking_reset_by_swap() {
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if !glium::framebuffer::is_texture_barrier_supported(&renderer) {
+        return;
+    }
+
+    let (vertex_buffer, index_buffer, red_program) =
+        support::build_fullscreen_red_pipeline(&renderer);
+    let program = program!(&renderer,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                uniform sampler2D tex;
+                out vec4 color;
+
+                void main() {
+                    color = texelFetch(tex, ivec2(gl_FragCoord.xy), 0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let texture = support::build_renderable_texture(&renderer);
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&renderer, &texture).unwrap();
+    let uniforms = uniform!{ tex: &texture };
+    let checked = glium::DrawParameters {
+        check_hazards: true,
+        .. Default::default()
+    };
+
+    // draws that don't check for hazards don't track their attachments
+    framebuffer.draw(&vertex_buffer, &index_buffer, &red_program, &uniform!{},
+                     &Default::default()).unwrap();
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program, &uniforms, &checked).unwrap();
+
+    match framebuffer.draw(&vertex_buffer, &index_buffer, &program, &uniforms, &checked) {
+        Err(glium::DrawError::TextureFeedbackLoop { .. }) => (),
+        e => panic!("{:?}", e)
+    }
+
+    renderer.swap_buffers().unwrap();
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program, &uniforms, &checked).unwrap();
+
+    renderer.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_slices() {
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let renderer = match HeadlessRenderer::new(16, 16) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&renderer);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 140
+
+            uniform float scale;
+            in vec2 position;
+            out vec2 output_val;
+
+            void main() {
+                output_val = position * scale;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 140
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&renderer, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> =
+        glium::VertexBuffer::empty(&renderer, 12).unwrap();
+
+    // each session must only write to its own half of the buffer
+    for (range, scale) in [(0 .. 6, 1.0f32), (6 .. 12, 2.0)] {
+        let slice = out_buffer.slice_mut(range).unwrap();
+        let session = glium::vertex::TransformFeedbackSession::new_separate(&renderer, &program,
+                                                                            vec![slice.into()])
+                                                                            .unwrap();
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        };
+
+        renderer.draw().draw(&vb, &ib, &program, &uniform!{ scale: scale }, &params).unwrap();
+    }
+
+    let result = out_buffer.read().unwrap();
+    assert_eq!(result[0].output_val, (-1.0, 1.0));
+    assert_eq!(result[5].output_val, (1.0, -1.0));
+    assert_eq!(result[6].output_val, (-2.0, 2.0));
+    assert_eq!(result[11].output_val, (2.0, -2.0));
+
+    renderer.assert_no_error(None);
+}
@@ -412,3 +412,43 @@ fn multidraw_elements() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn multidraw_elements_from_ranges() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 1, 3, 2]).unwrap();
+
+    let multidraw = glium::index::DrawCommandsIndicesBuffer::empty(&display, 3);
+    let multidraw = match multidraw {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    // one range per triangle, the third command must draw nothing
+    multidraw.write_ranges(&[
+        glium::index::MeshRange { first_index: 0, count: 3, base_vertex: 0, instances: 0 .. 1 },
+        glium::index::MeshRange { first_index: 3, count: 3, base_vertex: 0, instances: 0 .. 1 },
+    ]);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, multidraw.with_index_buffer(&indices),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
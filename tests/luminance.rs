@@ -0,0 +1,58 @@
+#![cfg(feature = "luminance")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::luminance::{LuminanceReduction, LuminanceSettings};
+
+mod support;
+
+#[test]
+fn average_of_uniform_texture() {
+    let display = support::build_display();
+
+    let reduction = match LuminanceReduction::new(&display, LuminanceSettings::default()) {
+        Ok(r) => r,
+        Err(_) => return
+    };
+
+    let data = vec![vec![(0.5f32, 0.5f32, 0.5f32); 64]; 64];
+    let texture = glium::Texture2d::new(&display, data).unwrap();
+
+    reduction.reduce(&texture).unwrap();
+    let result = reduction.read_average();
+
+    assert!((result.log_average - (-1.0)).abs() < 0.1, "{:?}", result);
+    assert!((result.average - 0.5).abs() < 0.05, "{:?}", result);
+
+    if let Some(histogram) = reduction.get_histogram_buffer() {
+        let bins = histogram.read().unwrap();
+        assert_eq!(bins.iter().sum::<u32>(), 64 * 64);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn black_texels_clamped() {
+    let display = support::build_display();
+
+    let reduction = match LuminanceReduction::new(&display, LuminanceSettings::default()) {
+        Ok(r) => r,
+        Err(_) => return
+    };
+
+    // both implementations count black texels as `min_log_luminance`
+    let data = (0 .. 64).map(|row| {
+        let value = if row < 32 { 0.0f32 } else { 0.5 };
+        vec![(value, value, value); 64]
+    }).collect::<Vec<_>>();
+    let texture = glium::Texture2d::new(&display, data).unwrap();
+
+    reduction.reduce(&texture).unwrap();
+    let result = reduction.read_average();
+
+    assert!((result.log_average - (-5.5)).abs() < 0.1, "{:?}", result);
+
+    display.assert_no_error(None);
+}
@@ -309,3 +309,139 @@ fn query_to_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn query_to_buffer_u64() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            samples_passed_query: Some((&query).into()),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+               .unwrap();
+    }
+
+    let mut buffer = glium::buffer::BufferView::<u64>::empty(&display,
+                                                             glium::buffer::BufferType::ArrayBuffer,
+                                                             glium::buffer::BufferMode::Default)
+                                                      .unwrap();
+    if query.to_buffer_u64(buffer.as_slice()).is_err() {
+        return;
+    }
+
+    let mapping = buffer.map();
+    assert!(*mapping == 1024 * 1024); // texture dimensions
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn occlusion_probes() {
+    let display = support::build_display();
+
+    let mut probes = match glium::occlusion::OcclusionProbes::new(&display) {
+        Err(_) => return,
+        Ok(p) => p
+    };
+
+    let color = support::build_renderable_texture(&display);
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                                                           glium::texture::DepthFormat::I24,
+                                                           1024, 1024).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                                 &color, &depth)
+                                                                                 .unwrap();
+
+    // the left half of the target is occluded
+    framebuffer.clear_depth(1.0);
+    framebuffer.clear(Some(&glium::Rect { left: 0, bottom: 0, width: 512, height: 1024 }),
+                      &glium::ClearValues::new().depth(0.0));
+
+    assert!(probes.visibility().is_empty());
+
+    for _ in 0 .. 4 {
+        probes.test(&mut framebuffer, &[[-0.5, 0.0, 0.5], [0.5, 0.0, 0.5]]).unwrap();
+    }
+
+    assert_eq!(probes.visibility(), &[false, true]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn samples_passed_scope() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    query.scope(|| {
+        for _ in 0 .. 2 {
+            texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                      &Default::default()).unwrap();
+        }
+    }).unwrap();
+
+    assert_eq!(query.get(), 2 * 1024 * 1024);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn samples_passed_scope_nested() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::AnySamplesPassedQuery::new(&display, true) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+    let query2 = glium::draw_parameters::AnySamplesPassedQuery::new(&display, true).unwrap();
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    query.scope(|| {
+        match query2.scope(|| ()) {
+            Err(glium::DrawError::WrongQueryOperation) => (),
+            _ => panic!()
+        };
+
+        let params = glium::DrawParameters {
+            samples_passed_query: Some((&query2).into()),
+            .. Default::default()
+        };
+
+        match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                        &params)
+        {
+            Err(glium::DrawError::WrongQueryOperation) => (),
+            _ => panic!()
+        };
+    }).unwrap();
+
+    assert!(!query.get());
+
+    display.assert_no_error(None);
+}
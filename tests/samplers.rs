@@ -79,3 +79,157 @@ fn magnify_nearest_filtering() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn sampler_object_shared() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let behavior = glium::uniforms::SamplerBehavior {
+        magnify_filter: glium::uniforms::MagnifySamplerFilter::Nearest,
+        .. Default::default()
+    };
+
+    let sampler = match glium::uniforms::SamplerObject::new(&display, behavior) {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    assert_eq!(sampler.get_behavior(), &behavior);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.51, 0.0));
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                uniform lowp sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.51, 0.0));
+                }
+            ",
+        }).unwrap();
+
+    // the same sampler is used with two different textures
+    let texture1 = glium::texture::Texture2d::new(&display,
+                                                  vec![vec![(0u8, 0, 0), (255, 255, 255)]]).unwrap();
+    let texture2 = glium::texture::Texture2d::new(&display,
+                                                  vec![vec![(0u8, 0, 0), (255, 0, 0)]]).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: texture1.with_sampler(&sampler) },
+                             &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 255, 255, 255));
+
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: texture2.with_sampler(&sampler) },
+                             &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn fixed_texture_units() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler2D red;
+                uniform sampler2D green;
+
+                void main() {
+                    gl_FragColor = texture2D(red, vec2(0.5, 0.5)) + texture2D(green, vec2(0.5, 0.5));
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                uniform lowp sampler2D red;
+                uniform lowp sampler2D green;
+
+                void main() {
+                    gl_FragColor = texture2D(red, vec2(0.5, 0.5)) + texture2D(green, vec2(0.5, 0.5));
+                }
+            ",
+        }).unwrap().with_fixed_texture_units().unwrap();
+
+    let mut units = program.texture_units().map(|(_, unit)| unit).collect::<Vec<_>>();
+    units.sort();
+    assert_eq!(units, vec![0, 1]);
+    assert!(program.get_texture_unit("red").is_some());
+    assert!(program.get_texture_unit("green").is_some());
+
+    let red = glium::texture::Texture2d::new(&display, vec![vec![(255u8, 0, 0)]]).unwrap();
+    let green = glium::texture::Texture2d::new(&display, vec![vec![(0u8, 255, 0)]]).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+
+    // drawing twice checks that the units stay correct when the textures are already bound
+    for _ in 0 .. 2 {
+        output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        output.as_surface().draw(&vb, &ib, &program, &uniform!{ red: &red, green: &green },
+                                 &Default::default()).unwrap();
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        assert_eq!(data[0][0], (255, 255, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
@@ -47,6 +47,19 @@ fn program_creation() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn program_validation() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    program.validate().unwrap();
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn program_compilation_error() {
     let display = support::build_display();
@@ -154,6 +167,136 @@ fn get_frag_data_location() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn stage_source_and_dump() {
+    let display = support::build_display();
+
+    let vertex_source = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let fragment_source = "
+        #version 110
+
+        uniform vec4 color;
+
+        void main() {
+            gl_FragColor = color;
+        }
+    ";
+
+    let program = glium::Program::from_source(&display, vertex_source, fragment_source,
+                                               None).unwrap();
+
+    assert_eq!(program.stage_source(glium::program::ShaderStage::Vertex), Some(vertex_source));
+    assert_eq!(program.stage_source(glium::program::ShaderStage::Fragment), Some(fragment_source));
+    assert_eq!(program.stage_source(glium::program::ShaderStage::Geometry), None);
+
+    let path = std::env::temp_dir().join("glium-stage-source-and-dump");
+    program.dump_to_dir(&path).unwrap();
+
+    assert_eq!(std::fs::read_to_string(path.join("vertex.glsl")).unwrap(), vertex_source);
+    assert_eq!(std::fs::read_to_string(path.join("fragment.glsl")).unwrap(), fragment_source);
+    assert!(std::fs::read_to_string(path.join("reflection.txt")).unwrap().contains("\"color\""));
+
+    std::fs::remove_dir_all(&path).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn content_hash() {
+    let display = support::build_display();
+
+    let vertex_source = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let build = |red: &str| {
+        let fragment_source = format!("
+            #version 110
+
+            void main() {{
+                gl_FragColor = vec4({}, 0.0, 0.0, 1.0);
+            }}
+        ", red);
+
+        glium::Program::from_source(&display, vertex_source, &fragment_source, None).unwrap()
+    };
+
+    let a = build("1.0");
+    let b = build("1.0");
+    let c = build("0.5");
+
+    assert_eq!(a.content_hash(), b.content_hash());
+    assert!(a.content_hash() != c.content_hash());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_default() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 120
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 120
+
+            uniform float exposure = 1.5;
+            uniform vec3 tint = vec3(0.25, 0.5, 1.0);
+            uniform vec4 offset;
+
+            void main() {
+                gl_FragColor = vec4(tint * exposure, 1.0) + offset;
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version 1.20 may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    match program.uniform_default("exposure") {
+        Some(glium::RawUniformValue::Float(v)) => assert_eq!(v, 1.5),
+        v => panic!("{:?}", v)
+    };
+
+    match program.uniform_default("tint") {
+        Some(glium::RawUniformValue::Vec3(v)) => assert_eq!(v, [0.25, 0.5, 1.0]),
+        v => panic!("{:?}", v)
+    };
+
+    match program.uniform_default("offset") {
+        Some(glium::RawUniformValue::Vec4(v)) => assert_eq!(v, [0.0, 0.0, 0.0, 0.0]),
+        v => panic!("{:?}", v)
+    };
+
+    assert!(program.uniform_default("unexisting").is_none());
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_uniform_blocks() {
     let display = support::build_display();
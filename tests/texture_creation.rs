@@ -407,3 +407,45 @@ fn upload_from_pixel_buffer_inverted() {
 
     display.assert_no_error(None);
 }
+
+#[cfg(feature = "image")]
+#[test]
+fn async_loader() {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let display = support::build_display();
+    let loader = glium::loader::AsyncLoader::new(&display, 2);
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixture/opengl.png");
+    let mut load = std::pin::pin!(loader.load_texture(path));
+    let mut missing = std::pin::pin!(loader.load_texture("does-not-exist.png"));
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let texture = loop {
+        if let Poll::Ready(texture) = load.as_mut().poll(&mut cx) {
+            break texture.unwrap();
+        }
+        std::thread::yield_now();
+    };
+
+    let image = image::open(path).unwrap();
+    assert_eq!(texture.dimensions(), (image.width(), image.height()));
+
+    let error = loop {
+        if let Poll::Ready(result) = missing.as_mut().poll(&mut cx) {
+            break result.err().unwrap();
+        }
+        std::thread::yield_now();
+    };
+    assert!(matches!(error, glium::loader::LoadError::Decode(_)));
+
+    display.assert_no_error(None);
+}
@@ -134,3 +134,97 @@ read_texture_test!(read_unsignedtexture2darray, UnsignedTexture2dArray, (u8, u8,
 read_texture_test!(read_unsignedtexture3d, UnsignedTexture3d, (u8, u8, u8, u8),
     vec![vec![vec![(0, 1, 2, 3), (4, 5, 6, 7)], vec![(8, 9, 10, 11), (12, 13, 14, 15)]]]);*/
 // TODO: srgb textures
+
+#[test]
+fn surface_read_to_pixel_buffer_hdr() {
+    use glium::Surface;
+    use glium::texture::ClientFormat;
+
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture2d::empty_with_format(&display,
+                                                glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                                                glium::texture::MipmapsOption::NoMipmap, 16, 16)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color(4.0, 0.5, 0.25, 1.0);
+
+    let rect = glium::Rect { left: 4, bottom: 2, width: 3, height: 2 };
+
+    let buffer = glium::pixel_buffer::PixelBuffer::new_empty(&display, 6);
+    match framebuffer.read_to_pixel_buffer(&rect, &buffer, ClientFormat::F32F32F32F32) {
+        Ok(()) => (),
+        Err(glium::ReadError::OutputFormatNotSupported) => return,
+        e => e.unwrap()
+    };
+    let data: Vec<Vec<(f32, f32, f32, f32)>> = buffer.read_as_texture_2d().unwrap();
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[0].len(), 3);
+    assert_eq!(data[1][2], (4.0, 0.5, 0.25, 1.0));
+
+    // half floats, stored as raw bits
+    let buffer = glium::pixel_buffer::PixelBuffer::new_empty(&display, 6);
+    match framebuffer.read_to_pixel_buffer(&rect, &buffer, ClientFormat::F16F16F16F16) {
+        Ok(()) => (),
+        Err(glium::ReadError::OutputFormatNotSupported) => return,
+        e => e.unwrap()
+    };
+    let data: Vec<Vec<(u16, u16, u16, u16)>> = buffer.read_as_texture_2d().unwrap();
+    assert_eq!(data[0][0], (0x4400, 0x3800, 0x3400, 0x3c00));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn surface_read_to_pixel_buffer_integral() {
+    use glium::Surface;
+    use glium::texture::ClientFormat;
+
+    let display = support::build_display();
+
+    let texture = match glium::texture::UnsignedTexture2d::empty_with_format(&display,
+                                                glium::texture::UncompressedUintFormat::U32,
+                                                glium::texture::MipmapsOption::NoMipmap, 8, 8)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear(None, &glium::ClearValues::new()
+                                .attachment_color(0, glium::ClearColor::UnsignedInt([70000, 0, 0, 0])));
+
+    let sub_surface = framebuffer.sub_region(glium::Rect { left: 2, bottom: 2, width: 4, height: 4 });
+    let buffer = glium::pixel_buffer::PixelBuffer::new_empty(&display, 1);
+    sub_surface.read_to_pixel_buffer(&glium::Rect { left: 1, bottom: 1, width: 1, height: 1 },
+                                     &buffer, ClientFormat::U32).unwrap();
+
+    let data: Vec<Vec<u32>> = buffer.read_as_texture_2d().unwrap();
+    assert_eq!(data[0][0], 70000);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn surface_read_without_color_attachment() {
+    use glium::Surface;
+
+    let display = support::build_display();
+
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                                glium::texture::DepthFormat::I24, 4, 4).unwrap();
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::depth_only(&display, &depth).unwrap();
+
+    let buffer: glium::pixel_buffer::PixelBuffer<(u8, u8, u8, u8)> =
+        glium::pixel_buffer::PixelBuffer::new_empty(&display, 16);
+    match framebuffer.read_to_pixel_buffer(&glium::Rect { left: 0, bottom: 0, width: 4, height: 4 },
+                                           &buffer, glium::texture::ClientFormat::U8U8U8U8)
+    {
+        Err(glium::ReadError::NoColorAttachment) => (),
+        _ => panic!()
+    }
+}
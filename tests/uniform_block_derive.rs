@@ -0,0 +1,115 @@
+#![cfg(feature = "derive")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::uniforms::BlockMember;
+
+mod support;
+
+#[derive(Copy, Clone, glium::uniforms::UniformBlock)]
+#[repr(C)]
+struct Light {
+    position: [f32; 3],
+    intensity: f32,
+    color: [f32; 3],
+    #[uniform_block(padding)]
+    _padding: f32,
+}
+
+#[derive(Copy, Clone, glium::uniforms::UniformBlock)]
+#[repr(C)]
+#[uniform_block(std430)]
+struct Particle {
+    position: [f32; 2],
+    velocity: [f32; 2],
+    life: f32,
+    #[uniform_block(padding)]
+    _padding: f32,
+}
+
+#[derive(Copy, Clone, glium::uniforms::UniformBlock)]
+#[repr(C)]
+struct Scene {
+    key_lights: [Light; 2],
+    fill_lights: [Light; 32],
+    ambient: [f32; 4],
+}
+
+#[test]
+fn derived_layout() {
+    assert_eq!(Light::STD140_ALIGN, 16);
+    assert_eq!(Light::STD140_SIZE, 32);
+    assert_eq!(Particle::STD430_ALIGN, 8);
+    assert_eq!(Particle::STD430_SIZE, 24);
+    assert_eq!(<[Light; 5] as BlockMember>::STD140_SIZE, 5 * 32);
+    assert_eq!(Scene::STD140_SIZE, 2 * 32 + 32 * 32 + 16);
+    assert_eq!(Scene::STD430_SIZE, 2 * 32 + 32 * 32 + 16);
+}
+
+#[test]
+fn derived_block() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 330
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(std140) uniform Lights {
+                vec3 position;
+                float intensity;
+                vec3 color;
+            };
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = vec4(color * intensity, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let light = Light {
+        position: [0.0, 0.0, 0.0],
+        intensity: 0.5,
+        color: [2.0, 0.0, 2.0],
+        _padding: 0.0,
+    };
+
+    let buffer = match glium::uniforms::UniformBuffer::new(&display, light) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ Lights: &buffer },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
@@ -157,6 +157,211 @@ fn block() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn block_explicit_binding() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 420
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 420
+
+            layout(std140, binding = 2) uniform MyBlock {
+                vec4 color;
+            };
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = color;
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let buffer = match glium::uniforms::UniformBuffer::new(&display, [1.0f32, 1.0, 0.0, 1.0]) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let uniforms = glium::uniforms::ExplicitBindings::new(glium::uniforms::EmptyUniforms)
+        .with_binding(buffer.bind_to(2));
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn push_uniforms() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                vec3 color;
+            };
+
+            void main() {
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: (f32, f32, f32),
+    }
+
+    implement_uniform_block!(Data, color);
+
+    // two slots for three draws, so that the ring wraps around
+    let push_uniforms = match glium::uniforms::PushUniforms::<Data>::with_capacity(&display, 2) {
+        Err(_) => return,
+        Ok(p) => p
+    };
+
+    let colors = [(1.0f32, 0.0f32, 0.0f32), (0.0, 1.0, 0.0), (0.0, 0.0, 1.0)];
+    let textures = colors.iter().map(|&color| {
+        let texture = support::build_renderable_texture(&display);
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, &program,
+                                  &uniform!{ MyBlock: push_uniforms.push(Data { color }) },
+                                  &Default::default()).unwrap();
+        texture
+    }).collect::<Vec<_>>();
+
+    let expected = [(255, 0, 0, 255), (0, 255, 0, 255), (0, 0, 255, 255)];
+    for (texture, expected) in textures.iter().zip(expected.iter()) {
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        for row in data.iter() {
+            for pixel in row.iter() {
+                assert_eq!(pixel, expected);
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn block_buffer_slice() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                vec4 color;
+                vec4 padding[15];
+            };
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    // 256 bytes per element, which is the largest alignment allowed by the specs
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: [f32; 4],
+        padding: [[f32; 4]; 15],
+    }
+
+    implement_uniform_block!(Data, color, padding);
+
+    let data = [
+        Data { color: [1.0, 0.0, 0.0, 1.0], padding: [[0.0; 4]; 15] },
+        Data { color: [0.0, 1.0, 0.0, 1.0], padding: [[0.0; 4]; 15] },
+    ];
+
+    let buffer = match glium::uniforms::UniformBuffer::<[Data]>::empty_unsized(&display, 512) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+    buffer.write(&data[..]);
+
+    let slice = buffer.as_slice().get(1).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ MyBlock: slice },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn block_wrong_type() {
     let display = support::build_display();
@@ -98,6 +98,49 @@ fn uniforms_storage_multiple_values() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn uniforms_chain() {
+    use glium::uniforms::Uniforms;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color1;
+            uniform vec4 color2;
+
+            void main() {
+                gl_FragColor = color1 + color2;
+            }
+        ",
+        None).unwrap();
+
+    let global = uniform! { color1: [0.7, 0.0, 0.0, 0.5f32] };
+    let uniforms = (&global).chain(uniform! { color2: [0.3, 0.0, 0.0, 0.5f32] });
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn uniforms_storage_ignore_inactive_uniforms() {
     let display = support::build_display();
@@ -260,6 +303,59 @@ fn uniforms_dynamic_multiple_values() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn uniforms_dynamic_owned() {
+    use std::rc::Rc;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5)) + color;
+            }
+        ",
+        None).unwrap();
+
+    // the uniforms don't borrow anything and can be returned from a function
+    let build_uniforms = |texture: Rc<glium::texture::Texture2d>| {
+        let mut uniforms = glium::uniforms::OwnedDynamicUniforms::new();
+        uniforms.add("texture", texture);
+        uniforms.add("color", [0.0, 0.0, 0.0, 1.0f32]);
+        uniforms
+    };
+
+    let source = Rc::new(glium::texture::Texture2d::new(&display,
+                                                        vec![vec![(255u8, 0u8, 0u8, 0u8)]]).unwrap());
+    let uniforms = build_uniforms(source.clone());
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms.clone(),
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn uniforms_dynamic_ignore_inactive_uniforms() {
     let display = support::build_display();
@@ -422,6 +518,17 @@ uniform_test!(uniform_type_f32arr_floatmat4, "mat4", [[ 1.0f32,     2.4, -100000
                                                       [-2.0f32, -7.8867,     6.6666, -0.0],
                                                       [ 1.1f32,     7.7,       -6.1,  0.0],
                                                       [12.0f32, 12345.0,    0.11111,  0.0]]);
+uniform_test!(uniform_type_f32arr_floatmat2x3, "mat2x3", [[ 1.0f32,     2.4, -1000000.0],
+                                                          [-2.0f32, -7.8867,     6.6666]]);
+uniform_test!(uniform_type_f32arr_floatmat4x2, "mat4x2", [[ 1.0f32,     2.4],
+                                                          [-2.0f32, -7.8867],
+                                                          [ 1.1f32,     7.7],
+                                                          [12.0f32, 12345.0]]);
+uniform_test!(uniform_type_f32arr_floatmat4_transposed, "mat4",
+              glium::uniforms::Transposed([[ 1.0f32,     2.4, -1000000.0,  0.0],
+                                           [-2.0f32, -7.8867,     6.6666, -0.0],
+                                           [ 1.1f32,     7.7,       -6.1,  0.0],
+                                           [12.0f32, 12345.0,    0.11111,  0.0]]));
 
 // Doubles
 uniform_test!(uniform_type_f64_double, "double", 12.567f64);
@@ -439,6 +546,9 @@ uniform_test!(uniform_type_f64arr_doublemat4, "dmat4", [[ 1.0f64,     2.4, -1000
                                                         [-2.0f64, -7.8867,     6.6666, -0.0],
                                                         [ 1.1f64,     7.7,       -6.1,  0.0],
                                                         [12.0f64, 12345.0,    0.11111,  0.0]]);
+uniform_test!(uniform_type_f64arr_doublemat3x2, "dmat3x2", [[ 1.0f64,     2.4],
+                                                            [-2.0f64, -7.8867],
+                                                            [ 1.1f64,     7.7]]);
 
 // Integer
 uniform_test!(uniform_type_i8_int, "int", 5i8);
@@ -488,3 +598,327 @@ uniform_test!(uniform_type_boolarr_boolvec3, "bvec3", [true, true, true]);
 uniform_test!(uniform_type_booltup_boolvec3, "bvec3", (false, false, false));
 uniform_test!(uniform_type_boolarr_boolvec4, "bvec4", [true, false, false, true]);
 uniform_test!(uniform_type_booltup_boolvec4, "bvec4", (false, true, true, false));
+
+#[test]
+fn uniform_transposed_matrix() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform mat2 matrix;
+
+                void main() {
+                    gl_FragColor = vec4(matrix[0], matrix[1]);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                uniform lowp mat2 matrix;
+
+                void main() {
+                    gl_FragColor = vec4(matrix[0], matrix[1]);
+                }
+            ",
+        },
+    ).unwrap();
+
+    // the only non-zero element is at the first row and the second column
+    let rows = [[0.0f32, 1.0], [0.0, 0.0]];
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program,
+                              &uniform!{ matrix: glium::uniforms::Transposed(rows) },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 255, 0));
+
+    display.assert_no_error(None);
+}
+
+#[cfg(feature = "glam")]
+#[test]
+fn uniform_glam_matrix() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform mat2 matrix;
+
+                void main() {
+                    gl_FragColor = vec4(matrix[0], matrix[1]);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                uniform lowp mat2 matrix;
+
+                void main() {
+                    gl_FragColor = vec4(matrix[0], matrix[1]);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let matrix = glam::Mat2::from_cols_array_2d(&[[0.0, 1.0], [0.0, 0.0]]);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program,
+                              &uniform!{ matrix: matrix },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniforms_storage_array() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 colors[3];
+
+            void main() {
+                gl_FragColor = colors[0] + colors[1] + colors[2];
+            }
+        ",
+        None).unwrap();
+
+    let colors = [[0.5, 0.0, 0.0, 0.25f32], [0.5, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.25]];
+    let uniforms = uniform! {
+        colors: glium::uniforms::UniformArray(&colors),
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], RED_HALF_ALPHA);
+    assert_eq!(data.last().unwrap().last().unwrap(), &RED_HALF_ALPHA);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniforms_dynamic_array_of_structs() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            struct Light {
+                vec3 color;
+                float alpha;
+            };
+
+            uniform Light lights[2];
+
+            void main() {
+                gl_FragColor = vec4(lights[0].color + lights[1].color,
+                                    lights[0].alpha + lights[1].alpha);
+            }
+        ",
+        None).unwrap();
+
+    #[derive(Copy, Clone)]
+    struct Light {
+        color: [f32; 3],
+        alpha: f32,
+    }
+
+    implement_uniform_struct!(Light, color, alpha);
+
+    let lights = [
+        Light { color: [1.0, 0.0, 0.0], alpha: 0.25 },
+        Light { color: [0.0, 0.0, 0.0], alpha: 0.25 },
+    ];
+    let lights = glium::uniforms::UniformArray(&lights);
+
+    let mut uniforms = glium::uniforms::DynamicUniforms::new();
+    uniforms.add_array("lights", &lights);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], RED_HALF_ALPHA);
+    assert_eq!(data.last().unwrap().last().unwrap(), &RED_HALF_ALPHA);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_int64_value() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    // ignoring the test if `GL_ARB_gpu_shader_int64` is not supported
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 400
+            in vec2 position;
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 400
+            #extension GL_ARB_gpu_shader_int64 : require
+            uniform int64_t signed_value;
+            uniform u64vec2 unsigned_value;
+            out vec4 color;
+            void main() {
+                if (signed_value == -9000000000l && unsigned_value == u64vec2(9000000000ul, 1ul)) {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                } else {
+                    color = vec4(0.0, 1.0, 0.0, 1.0);
+                }
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let uniforms = uniform! {
+        signed_value: -9_000_000_000i64,
+        unsigned_value: [9_000_000_000u64, 1],
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn push_constants() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    assert_eq!(glium::uniforms::PushConstants::<[f32; 20]>::glsl_declaration(),
+               "uniform uvec4 glium_push_constants[5];");
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 130
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+
+            uniform uvec4 glium_push_constants[1];
+            out vec4 color;
+
+            void main() {
+                uvec4 data = glium_push_constants[0];
+                color = vec4(vec2(data.xy) / 255.0, data.z == 0x7fa00001u ? 1.0 : 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    // the third integer is a signaling NaN when read as a float
+    for &color in &[[255u32, 0, 0x7fa0_0001, 0], [0, 255, 0x7fa0_0001, 0]] {
+        let push_constants = unsafe { glium::uniforms::PushConstants::new(color) };
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, &program, &push_constants,
+                                  &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], (color[0] as u8, color[1] as u8, 255, 255));
+    }
+
+    display.assert_no_error(None);
+}
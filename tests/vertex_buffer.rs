@@ -90,3 +90,119 @@ fn transform_feedback() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn transform_feedback_separate_with_pause() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Position {
+        out_position: (f32, f32),
+    }
+
+    implement_vertex!(Position, out_position);
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Double {
+        out_double: (f32, f32),
+    }
+
+    implement_vertex!(Double, out_double);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 out_position;
+            varying vec2 out_double;
+
+            void main() {
+                out_position = position;
+                out_double = position * 2.0;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["out_position".to_string(), "out_double".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let mut positions: glium::VertexBuffer<Position> = glium::VertexBuffer::empty(&display, 12).unwrap();
+    let mut doubles: glium::VertexBuffer<Double> = glium::VertexBuffer::empty(&display, 12).unwrap();
+
+    let query = glium::draw_parameters::TransformFeedbackPrimitivesWrittenQuery::new(&display).unwrap();
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::new_separate(&display, &program,
+                                            vec![(&mut *positions).into(), (&mut *doubles).into()])
+                                            .unwrap();
+
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            transform_feedback_primitives_written_query: Some(&query),
+            .. Default::default()
+        };
+
+        let mut frame = display.draw();
+        frame.draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+
+        // nothing must be written while the session is paused
+        if session.pause().is_err() {
+            frame.finish().unwrap();
+            return;
+        }
+        frame.draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+        frame.draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+        session.resume().unwrap();
+        frame.draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+        frame.finish().unwrap();
+    }
+
+    assert_eq!(query.get(), 4);
+
+    let positions = match positions.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+    let doubles = doubles.read().unwrap();
+
+    for (num, expected) in [(-1.0, 1.0), (1.0, 1.0), (-1.0, -1.0),
+                            (-1.0, -1.0), (1.0, 1.0), (1.0, -1.0)].iter().enumerate()
+    {
+        assert_eq!(positions[num].out_position, *expected);
+        assert_eq!(positions[num + 6].out_position, *expected);
+        assert_eq!(doubles[num].out_double, (expected.0 * 2.0, expected.1 * 2.0));
+        assert_eq!(doubles[num + 6].out_double, (expected.0 * 2.0, expected.1 * 2.0));
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,446 @@
+//! Generation of the Rust interface of a program from the reflection of its shaders.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use glium::Program;
+use glium::program::{BlockLayout, UniformBlock};
+use glium::uniforms::UniformType;
+use glium::vertex::AttributeType;
+
+/// Source code of the shaders of the program.
+pub struct Sources {
+    pub vertex: String,
+    pub fragment: String,
+    pub geometry: Option<String>,
+}
+
+/// Generates the content of a Rust module that contains the shaders, the structs matching their
+/// attributes, uniforms and uniform blocks, and typed `program` and `draw` functions.
+///
+/// Returns an error if the program uses something that the generated code can't represent.
+pub fn generate(program: &Program, sources: &Sources, command: &str) -> Result<String, String> {
+    let mut output = String::new();
+
+    writeln!(output, "// Generated with `{}`.", command).unwrap();
+    writeln!(output, "// Don't edit this file, run the command again after changing the shaders.")
+        .unwrap();
+    writeln!(output).unwrap();
+
+    writeln!(output, "pub const VERTEX_SHADER: &str = {};", raw_string(&sources.vertex)).unwrap();
+    writeln!(output, "pub const FRAGMENT_SHADER: &str = {};", raw_string(&sources.fragment))
+        .unwrap();
+    match &sources.geometry {
+        Some(geometry) => writeln!(output, "pub const GEOMETRY_SHADER: Option<&str> = Some({});",
+                                   raw_string(geometry)).unwrap(),
+        None => writeln!(output, "pub const GEOMETRY_SHADER: Option<&str> = None;").unwrap(),
+    }
+    writeln!(output).unwrap();
+
+    write_vertex(&mut output, program)?;
+
+    let mut blocks = program.get_uniform_blocks().iter().collect::<Vec<_>>();
+    blocks.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, block) in &blocks {
+        write_block(&mut output, name, block)?;
+    }
+
+    let has_lifetime = write_uniforms(&mut output, program, &blocks)?;
+    let uniforms_type = if has_lifetime { "Uniforms<'_>" } else { "Uniforms" };
+
+    output.push_str(&format!("\
+/// Builds the program from the shaders of this module.
+pub fn program<F>(facade: &F) -> Result<glium::Program, glium::ProgramCreationError>
+    where F: ?Sized + glium::backend::Facade
+{{
+    glium::Program::from_source(facade, VERTEX_SHADER, FRAGMENT_SHADER, GEOMETRY_SHADER)
+}}
+
+/// Draws with a program built by `program`.
+pub fn draw<'i, S, I>(surface: &mut S, program: &glium::Program,
+                      vertices: &glium::VertexBuffer<Vertex>, indices: I,
+                      uniforms: &{}, draw_parameters: &glium::DrawParameters<'_>)
+                      -> Result<(), glium::DrawError>
+    where S: glium::Surface, I: Into<glium::index::IndicesSource<'i>>
+{{
+    surface.draw(vertices, indices, program, uniforms, draw_parameters)
+}}
+", uniforms_type));
+
+    Ok(output)
+}
+
+/// Returns a raw string literal whose content is `value`.
+fn raw_string(value: &str) -> String {
+    let mut hashes = String::from("#");
+    while value.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{}\"{}\"{}", hashes, value, hashes)
+}
+
+/// Turns a GLSL name into a Rust field name.
+fn field_name(name: &str) -> String {
+    let mut field = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect::<String>();
+    let field_trimmed = field.trim_end_matches('_').len();
+    field.truncate(field_trimmed);
+
+    const KEYWORDS: &[&str] = &[
+        "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn",
+        "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+        "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+        "where", "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final",
+        "macro", "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+    ];
+    if KEYWORDS.contains(&&field[..]) || field.starts_with(|c: char| c.is_ascii_digit()) {
+        field.push('_');
+    }
+    field
+}
+
+fn attribute_type(ty: AttributeType) -> Option<&'static str> {
+    use glium::vertex::AttributeType::*;
+
+    Some(match ty {
+        F32 => "f32",
+        F32F32 => "[f32; 2]",
+        F32F32F32 => "[f32; 3]",
+        F32F32F32F32 => "[f32; 4]",
+        F32x2x2 => "[[f32; 2]; 2]",
+        F32x2x3 => "[[f32; 3]; 2]",
+        F32x2x4 => "[[f32; 4]; 2]",
+        F32x3x2 => "[[f32; 2]; 3]",
+        F32x3x3 => "[[f32; 3]; 3]",
+        F32x3x4 => "[[f32; 4]; 3]",
+        F32x4x2 => "[[f32; 2]; 4]",
+        F32x4x3 => "[[f32; 3]; 4]",
+        F32x4x4 => "[[f32; 4]; 4]",
+        F64 => "f64",
+        F64F64 => "[f64; 2]",
+        F64F64F64 => "[f64; 3]",
+        F64F64F64F64 => "[f64; 4]",
+        I32 => "i32",
+        I32I32 => "[i32; 2]",
+        I32I32I32 => "[i32; 3]",
+        I32I32I32I32 => "[i32; 4]",
+        U32 => "u32",
+        U32U32 => "[u32; 2]",
+        U32U32U32 => "[u32; 3]",
+        U32U32U32U32 => "[u32; 4]",
+        _ => return None,
+    })
+}
+
+/// Returns the Rust type of a value of a uniform or of a block member, with its size in bytes and
+/// its number of columns.
+fn value_type(ty: UniformType) -> Option<(&'static str, usize, usize)> {
+    use glium::uniforms::UniformType::*;
+
+    Some(match ty {
+        Float => ("f32", 4, 1),
+        FloatVec2 => ("[f32; 2]", 8, 1),
+        FloatVec3 => ("[f32; 3]", 12, 1),
+        FloatVec4 => ("[f32; 4]", 16, 1),
+        Double => ("f64", 8, 1),
+        DoubleVec2 => ("[f64; 2]", 16, 1),
+        DoubleVec3 => ("[f64; 3]", 24, 1),
+        DoubleVec4 => ("[f64; 4]", 32, 1),
+        Int => ("i32", 4, 1),
+        IntVec2 => ("[i32; 2]", 8, 1),
+        IntVec3 => ("[i32; 3]", 12, 1),
+        IntVec4 => ("[i32; 4]", 16, 1),
+        UnsignedInt => ("u32", 4, 1),
+        UnsignedIntVec2 => ("[u32; 2]", 8, 1),
+        UnsignedIntVec3 => ("[u32; 3]", 12, 1),
+        UnsignedIntVec4 => ("[u32; 4]", 16, 1),
+        Int64 => ("i64", 8, 1),
+        Int64Vec2 => ("[i64; 2]", 16, 1),
+        Int64Vec3 => ("[i64; 3]", 24, 1),
+        Int64Vec4 => ("[i64; 4]", 32, 1),
+        UnsignedInt64 => ("u64", 8, 1),
+        UnsignedInt64Vec2 => ("[u64; 2]", 16, 1),
+        UnsignedInt64Vec3 => ("[u64; 3]", 24, 1),
+        UnsignedInt64Vec4 => ("[u64; 4]", 32, 1),
+        FloatMat2 => ("[[f32; 2]; 2]", 16, 2),
+        FloatMat3 => ("[[f32; 3]; 3]", 36, 3),
+        FloatMat4 => ("[[f32; 4]; 4]", 64, 4),
+        FloatMat2x3 => ("[[f32; 3]; 2]", 24, 2),
+        FloatMat2x4 => ("[[f32; 4]; 2]", 32, 2),
+        FloatMat3x2 => ("[[f32; 2]; 3]", 24, 3),
+        FloatMat3x4 => ("[[f32; 4]; 3]", 48, 3),
+        FloatMat4x2 => ("[[f32; 2]; 4]", 32, 4),
+        FloatMat4x3 => ("[[f32; 3]; 4]", 48, 4),
+        DoubleMat2 => ("[[f64; 2]; 2]", 32, 2),
+        DoubleMat3 => ("[[f64; 3]; 3]", 72, 3),
+        DoubleMat4 => ("[[f64; 4]; 4]", 128, 4),
+        DoubleMat2x3 => ("[[f64; 3]; 2]", 48, 2),
+        DoubleMat2x4 => ("[[f64; 4]; 2]", 64, 2),
+        DoubleMat3x2 => ("[[f64; 2]; 3]", 48, 3),
+        DoubleMat3x4 => ("[[f64; 4]; 3]", 96, 3),
+        DoubleMat4x2 => ("[[f64; 2]; 4]", 64, 4),
+        DoubleMat4x3 => ("[[f64; 3]; 4]", 96, 4),
+        _ => return None,
+    })
+}
+
+/// Returns the texture type that a sampler uniform accepts.
+fn sampler_texture(ty: UniformType) -> Option<&'static str> {
+    use glium::uniforms::UniformType::*;
+
+    Some(match ty {
+        Sampler1d => "Texture1d",
+        ISampler1d => "IntegralTexture1d",
+        USampler1d => "UnsignedTexture1d",
+        Sampler2d => "Texture2d",
+        ISampler2d => "IntegralTexture2d",
+        USampler2d => "UnsignedTexture2d",
+        Sampler3d => "Texture3d",
+        ISampler3d => "IntegralTexture3d",
+        USampler3d => "UnsignedTexture3d",
+        Sampler1dArray => "Texture1dArray",
+        ISampler1dArray => "IntegralTexture1dArray",
+        USampler1dArray => "UnsignedTexture1dArray",
+        Sampler2dArray => "Texture2dArray",
+        ISampler2dArray => "IntegralTexture2dArray",
+        USampler2dArray => "UnsignedTexture2dArray",
+        SamplerCube => "Cubemap",
+        ISamplerCube => "IntegralCubemap",
+        USamplerCube => "UnsignedCubemap",
+        SamplerCubeArray => "CubemapArray",
+        ISamplerCubeArray => "IntegralCubemapArray",
+        USamplerCubeArray => "UnsignedCubemapArray",
+        Sampler2dMultisample => "Texture2dMultisample",
+        ISampler2dMultisample => "IntegralTexture2dMultisample",
+        USampler2dMultisample => "UnsignedTexture2dMultisample",
+        Sampler2dMultisampleArray => "Texture2dMultisampleArray",
+        ISampler2dMultisampleArray => "IntegralTexture2dMultisampleArray",
+        USampler2dMultisampleArray => "UnsignedTexture2dMultisampleArray",
+        Sampler1dShadow => "DepthTexture1d",
+        Sampler2dShadow => "DepthTexture2d",
+        SamplerCubeShadow => "DepthCubemap",
+        Sampler1dArrayShadow => "DepthTexture1dArray",
+        Sampler2dArrayShadow => "DepthTexture2dArray",
+        SamplerCubeArrayShadow => "DepthCubemapArray",
+        _ => return None,
+    })
+}
+
+fn write_vertex(output: &mut String, program: &Program) -> Result<(), String> {
+    let mut attributes = program.attributes()
+        .filter(|(name, _)| !name.starts_with("gl_"))
+        .collect::<Vec<_>>();
+    attributes.sort_by_key(|(name, attribute)| (attribute.location, name.to_owned()));
+
+    let mut fields = Vec::new();
+    for (name, attribute) in attributes {
+        if attribute.size != 1 {
+            return Err(format!("the attribute `{}` is an array, which vertex structs can't \
+                                represent", name));
+        }
+        let ty = attribute_type(attribute.ty).ok_or_else(|| {
+            format!("the attribute `{}` has the unsupported type {:?}", name, attribute.ty)
+        })?;
+        fields.push((field_name(name), name, ty));
+    }
+
+    if fields.is_empty() {
+        return Err("the vertex shader doesn't have any input attribute".to_owned());
+    }
+
+    writeln!(output, "/// The input attributes of the vertex shader.").unwrap();
+    writeln!(output, "#[derive(Copy, Clone, Debug, Default)]").unwrap();
+    writeln!(output, "#[allow(non_snake_case)]").unwrap();
+    writeln!(output, "pub struct Vertex {{").unwrap();
+    for (field, _, ty) in &fields {
+        writeln!(output, "    pub {}: {},", field, ty).unwrap();
+    }
+    writeln!(output, "}}").unwrap();
+    writeln!(output).unwrap();
+
+    if fields.iter().any(|(field, name, _)| field != *name) {
+        return Err("the names of the attributes must be valid Rust identifiers".to_owned());
+    }
+    let names = fields.iter().map(|(field, _, _)| &field[..]).collect::<Vec<_>>();
+    writeln!(output, "glium::implement_vertex!(Vertex, {});", names.join(", ")).unwrap();
+    writeln!(output).unwrap();
+    Ok(())
+}
+
+/// Writes a `#[repr(C)]` struct whose fields are at the offsets of the members of the block,
+/// with padding fields in between.
+fn write_block(output: &mut String, name: &str, block: &UniformBlock) -> Result<(), String> {
+    let mut members = match &block.layout {
+        BlockLayout::Struct { members } => members,
+        _ => return Err(format!("the block `{}` isn't a struct", name)),
+    };
+    // the members of blocks that have an instance name are prefixed with the block name
+    if let [(member_name, BlockLayout::Struct { members: inner })] = &members[..] {
+        if member_name == name {
+            members = inner;
+        }
+    }
+
+    // (name, offset, Rust type, size, number of columns or elements)
+    let mut fields = Vec::new();
+    for (member, layout) in members {
+        let (ty, length) = match layout {
+            BlockLayout::BasicType { .. } => (layout, 1),
+            BlockLayout::Array { content, length } => (&**content, *length),
+            _ => return Err(format!("the member `{}` of the block `{}` is a struct or an \
+                                     unsized array, which isn't supported", member, name)),
+        };
+        let (ty, offset) = match ty {
+            BlockLayout::BasicType { ty, offset_in_buffer } => (*ty, *offset_in_buffer),
+            _ => return Err(format!("the member `{}` of the block `{}` is an array of \
+                                     structs or of arrays, which isn't supported",
+                                    member, name)),
+        };
+        let (rust, size, columns) = value_type(ty).ok_or_else(|| {
+            format!("the member `{}` of the block `{}` has the unsupported type {:?}",
+                    member, name, ty)
+        })?;
+        let rust = match layout {
+            BlockLayout::Array { .. } => format!("[{}; {}]", rust, length),
+            _ => rust.to_owned(),
+        };
+        fields.push((member, offset, rust, size * length, columns * length));
+    }
+    fields.sort_by_key(|f| f.1);
+
+    writeln!(output, "/// The content of the uniform block `{}`.", name).unwrap();
+    writeln!(output, "#[derive(Copy, Clone, Debug)]").unwrap();
+    writeln!(output, "#[repr(C)]").unwrap();
+    writeln!(output, "#[allow(non_snake_case, non_camel_case_types)]").unwrap();
+    writeln!(output, "pub struct {} {{", name).unwrap();
+
+    let mut position = 0;
+    let mut padding = 0;
+    for (index, (member, offset, ty, size, elements)) in fields.iter().enumerate() {
+        let end = fields.get(index + 1).map_or(block.size, |next| next.1);
+        let span = end - offset;
+
+        // the columns of matrices and the elements of arrays are tightly packed in Rust, while
+        // std140 aligns them to 16 bytes
+        let strided = *elements > 1 && (size / elements) % 16 != 0;
+        if span < *size || (strided && span != *size) {
+            return Err(format!("the layout of the member `{}` of the block `{}` can't be \
+                                represented in Rust, use vec4 elements and columns instead",
+                               member, name));
+        }
+
+        if *offset > position {
+            writeln!(output, "    pub _padding{}: [u8; {}],", padding, offset - position)
+                .unwrap();
+            padding += 1;
+        }
+        writeln!(output, "    pub {}: {},", field_name(member), ty).unwrap();
+        position = offset + size;
+    }
+    if block.size > position {
+        writeln!(output, "    pub _padding{}: [u8; {}],", padding, block.size - position).unwrap();
+    }
+    writeln!(output, "}}").unwrap();
+    writeln!(output).unwrap();
+
+    if fields.iter().any(|f| field_name(f.0) != *f.0) {
+        return Err(format!("the names of the members of the block `{}` must be valid Rust \
+                            identifiers", name));
+    }
+    let names = fields.iter().map(|f| &f.0[..]).collect::<Vec<_>>();
+    writeln!(output, "glium::implement_uniform_block!({}, {});", name, names.join(", ")).unwrap();
+    writeln!(output).unwrap();
+    Ok(())
+}
+
+/// Writes the `Uniforms` struct. Returns true if it has a lifetime parameter.
+fn write_uniforms(output: &mut String, program: &Program, blocks: &[(&String, &UniformBlock)])
+                  -> Result<bool, String>
+{
+    // the uniforms of arrays are grouped, their name is `name[index]`
+    let mut uniforms: BTreeMap<&str, (UniformType, Option<usize>)> = BTreeMap::new();
+    for (name, uniform) in program.uniforms() {
+        let array = name.strip_suffix(']')
+            .and_then(|n| n.rsplit_once('['))
+            .filter(|(base, _)| !base.contains(['[', '.']))
+            .and_then(|(base, index)| index.parse::<usize>().ok().map(|index| (base, index)));
+        match array {
+            Some((base, index)) => {
+                let entry = uniforms.entry(base).or_insert((uniform.ty, Some(0)));
+                entry.1 = entry.1.max(Some(index + 1));
+            },
+            None => {
+                uniforms.insert(name, (uniform.ty, None));
+            },
+        }
+    }
+
+    // (field, GLSL name, type, array length)
+    let mut fields = Vec::new();
+    let mut has_lifetime = false;
+    for (name, (ty, length)) in &uniforms {
+        let rust = if let Some((rust, _, _)) = value_type(*ty) {
+            rust.to_owned()
+        } else if let Some(texture) = sampler_texture(*ty) {
+            has_lifetime = true;
+            format!("glium::uniforms::Sampler<'a, glium::texture::{}>", texture)
+        } else {
+            has_lifetime = true;
+            "glium::uniforms::UniformValue<'a>".to_owned()
+        };
+        fields.push((field_name(name), name.to_string(), rust, *length));
+    }
+    for (name, _) in blocks {
+        has_lifetime = true;
+        fields.push((field_name(name), name.to_string(),
+                     format!("&'a glium::uniforms::UniformBuffer<{}>", name), None));
+    }
+
+    let lifetime = if has_lifetime { "<'a>" } else { "" };
+    writeln!(output, "/// The uniforms and uniform blocks of the program.").unwrap();
+    writeln!(output, "#[derive(Copy, Clone)]").unwrap();
+    writeln!(output, "#[allow(non_snake_case)]").unwrap();
+    writeln!(output, "pub struct Uniforms{} {{", lifetime).unwrap();
+    for (field, _, ty, length) in &fields {
+        match length {
+            Some(length) => writeln!(output, "    pub {}: [{}; {}],", field, ty, length).unwrap(),
+            None => writeln!(output, "    pub {}: {},", field, ty).unwrap(),
+        }
+    }
+    writeln!(output, "}}").unwrap();
+    writeln!(output).unwrap();
+
+    writeln!(output, "impl{} glium::uniforms::Uniforms for Uniforms{} {{", lifetime, lifetime)
+        .unwrap();
+    writeln!(output, "    #[allow(unused_variables, unused_mut)]").unwrap();
+    writeln!(output, "    fn visit_values<'b, F>(&'b self, mut f: F)").unwrap();
+    writeln!(output, "        where F: FnMut(&str, glium::uniforms::UniformValue<'b>)").unwrap();
+    writeln!(output, "    {{").unwrap();
+    writeln!(output, "        use glium::uniforms::AsUniformValue;").unwrap();
+    for (field, name, ty, length) in &fields {
+        let value = |access: &str| if ty.starts_with("glium::uniforms::UniformValue") {
+            access.to_owned()
+        } else {
+            format!("{}.as_uniform_value()", access)
+        };
+        match length {
+            Some(_) => {
+                writeln!(output, "        for (index, value) in self.{}.iter().enumerate() {{",
+                         field).unwrap();
+                writeln!(output, "            f(&format!(\"{}[{{}}]\", index), {});", name,
+                         value("*value")).unwrap();
+                writeln!(output, "        }}").unwrap();
+            },
+            None => {
+                writeln!(output, "        f({:?}, {});", name, value(&format!("self.{}", field)))
+                    .unwrap();
+            },
+        }
+    }
+    writeln!(output, "    }}").unwrap();
+    writeln!(output, "}}").unwrap();
+    writeln!(output).unwrap();
+
+    Ok(has_lifetime)
+}
@@ -0,0 +1,315 @@
+/*!
+Development tasks for glium, run with `cargo xtask <task>`.
+
+ - `capabilities [--format markdown|json]`: creates an OpenGL context and prints the versions,
+   limits and extensions that glium relies on. Attach its output to bug reports.
+ - `interface --vertex <path> --fragment <path> [--geometry <path>] [--output <path>]`: compiles
+   the shaders and generates a Rust module with the shaders, a `Vertex` struct matching the
+   attributes, structs for the uniform blocks, a `Uniforms` struct, and `program` and `draw`
+   functions that use them. Commit the generated file and run the command again when the shaders
+   change, the draw calls then fail to compile instead of failing at runtime.
+
+*/
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::process;
+
+use glium::{Api, CapabilitiesSource, Display, Profile, Version};
+use glium::backend::glutin::SimpleWindowBuilder;
+use glium::backend::glutin::glutin::surface::WindowSurface;
+use winit::event_loop::EventLoop;
+use winit::window::WindowBuilder;
+
+mod interface;
+
+const USAGE: &str = "\
+usage: cargo xtask capabilities [--format markdown|json]
+       cargo xtask interface --vertex <path> --fragment <path> [--geometry <path>] \
+[--output <path>]";
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    Json,
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+
+    match args.next().as_deref() {
+        Some("capabilities") => {
+            let format = match (args.next().as_deref(), args.next().as_deref()) {
+                (None, _) => Format::Markdown,
+                (Some("--format"), Some("markdown")) => Format::Markdown,
+                (Some("--format"), Some("json")) => Format::Json,
+                _ => fail(),
+            };
+            if args.next().is_some() {
+                fail();
+            }
+
+            let report = with_display(capabilities);
+            match format {
+                Format::Markdown => print!("{}", report.to_markdown()),
+                Format::Json => println!("{}", report.to_json()),
+            }
+        },
+        Some("interface") => {
+            let (mut vertex, mut fragment, mut geometry, mut output) = (None, None, None, None);
+            while let Some(arg) = args.next() {
+                let slot = match &arg[..] {
+                    "--vertex" => &mut vertex,
+                    "--fragment" => &mut fragment,
+                    "--geometry" => &mut geometry,
+                    "--output" => &mut output,
+                    _ => fail(),
+                };
+                if slot.is_some() {
+                    fail();
+                }
+                *slot = Some(args.next().unwrap_or_else(|| fail()));
+            }
+            let (vertex, fragment) = match (vertex, fragment) {
+                (Some(vertex), Some(fragment)) => (vertex, fragment),
+                _ => fail(),
+            };
+
+            let read = |path: &str| fs::read_to_string(path).unwrap_or_else(|err| {
+                eprintln!("failed to read `{}`: {}", path, err);
+                process::exit(1);
+            });
+            let sources = interface::Sources {
+                vertex: read(&vertex),
+                fragment: read(&fragment),
+                geometry: geometry.as_deref().map(read),
+            };
+
+            let mut command = format!("cargo xtask interface --vertex {} --fragment {}",
+                                      vertex, fragment);
+            if let Some(geometry) = &geometry {
+                write!(command, " --geometry {}", geometry).unwrap();
+            }
+
+            let generated = with_display(|display| {
+                let program = glium::Program::from_source(display, &sources.vertex,
+                                                          &sources.fragment,
+                                                          sources.geometry.as_deref())
+                    .map_err(|err| err.to_string())?;
+                interface::generate(&program, &sources, &command)
+            });
+            let generated = generated.unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                process::exit(1);
+            });
+
+            match output {
+                Some(path) => fs::write(&path, generated).unwrap_or_else(|err| {
+                    eprintln!("failed to write `{}`: {}", path, err);
+                    process::exit(1);
+                }),
+                None => print!("{}", generated),
+            }
+        },
+        _ => fail(),
+    }
+}
+
+fn fail() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+/// A value of the report.
+enum Value {
+    Bool(bool),
+    Int(i64),
+    Str(String),
+    None,
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Value {
+        Value::Bool(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Value {
+        Value::Int(value as i64)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Value {
+        Value::Str(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Value {
+        Value::Str(value.to_owned())
+    }
+}
+
+impl<T> From<Option<T>> for Value where T: Into<Value> {
+    fn from(value: Option<T>) -> Value {
+        value.map_or(Value::None, Into::into)
+    }
+}
+
+/// A list of sections, each of them containing a list of named values.
+struct Report {
+    sections: Vec<(&'static str, Vec<(String, Value)>)>,
+}
+
+impl Report {
+    fn to_markdown(&self) -> String {
+        let mut output = String::new();
+
+        for (title, entries) in &self.sections {
+            writeln!(output, "## {}\n", title).unwrap();
+            writeln!(output, "| name | value |").unwrap();
+            writeln!(output, "| --- | --- |").unwrap();
+            for (name, value) in entries {
+                let value = match value {
+                    Value::Bool(b) => if *b { "yes".to_owned() } else { "no".to_owned() },
+                    Value::Int(i) => i.to_string(),
+                    Value::Str(s) => s.replace('|', "\\|"),
+                    Value::None => "-".to_owned(),
+                };
+                writeln!(output, "| {} | {} |", name, value).unwrap();
+            }
+            writeln!(output).unwrap();
+        }
+
+        output
+    }
+
+    fn to_json(&self) -> String {
+        let mut output = String::from("{");
+
+        for (index, (title, entries)) in self.sections.iter().enumerate() {
+            if index != 0 {
+                output.push(',');
+            }
+            write!(output, "\n  {}: {{", json_string(title)).unwrap();
+            for (index, (name, value)) in entries.iter().enumerate() {
+                if index != 0 {
+                    output.push(',');
+                }
+                let value = match value {
+                    Value::Bool(b) => b.to_string(),
+                    Value::Int(i) => i.to_string(),
+                    Value::Str(s) => json_string(s),
+                    Value::None => "null".to_owned(),
+                };
+                write!(output, "\n    {}: {}", json_string(name), value).unwrap();
+            }
+            output.push_str("\n  }");
+        }
+
+        output.push_str("\n}");
+        output
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut output = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(output, "\\u{:04x}", c as u32).unwrap(),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+fn version_string(version: &Version) -> String {
+    let api = match version.0 {
+        Api::Gl => "OpenGL",
+        Api::GlEs => "OpenGL ES",
+    };
+    format!("{} {}.{}", api, version.1, version.2)
+}
+
+/// Calls `f` with a context created for a hidden window, as glutin can't create a context
+/// without a surface on all platforms.
+fn with_display<R>(f: impl FnOnce(&Display<WindowSurface>) -> R) -> R {
+    let event_loop = EventLoop::new().expect("failed to create the event loop");
+    let (_window, display) = SimpleWindowBuilder::new()
+        .set_window_builder(WindowBuilder::new().with_visible(false))
+        .build(&event_loop);
+    f(&display)
+}
+
+/// Builds the report of the capabilities of the context.
+fn capabilities(display: &Display<WindowSurface>) -> Report {
+    let caps = display.get_capabilities();
+
+    let context = vec![
+        ("version".to_owned(), version_string(display.get_opengl_version()).into()),
+        ("version string".to_owned(), display.get_opengl_version_string().into()),
+        ("glsl version".to_owned(), version_string(&display.get_supported_glsl_version()).into()),
+        ("vendor".to_owned(), display.get_opengl_vendor_string().into()),
+        ("renderer".to_owned(), display.get_opengl_renderer_string().into()),
+        ("profile".to_owned(), display.get_opengl_profile().map(|profile| match profile {
+            Profile::Core => "core",
+            Profile::Compatibility => "compatibility",
+        }).into()),
+        ("debug".to_owned(), display.is_debug().into()),
+        ("forward compatible".to_owned(), display.is_forward_compatible().into()),
+        ("robust".to_owned(), display.is_robust().into()),
+        ("context loss possible".to_owned(), display.is_context_loss_possible().into()),
+        ("release behavior".to_owned(), format!("{:?}", display.get_release_behavior()).into()),
+        ("srgb default framebuffer".to_owned(), caps.srgb.into()),
+        ("depth bits".to_owned(), caps.depth_bits.map(i32::from).into()),
+        ("stencil bits".to_owned(), caps.stencil_bits.map(i32::from).into()),
+    ];
+
+    let limits = vec![
+        ("max texture size".to_owned(), caps.max_texture_size.into()),
+        ("max texture buffer size".to_owned(), caps.max_texture_buffer_size.into()),
+        ("max combined texture image units".to_owned(),
+         caps.max_combined_texture_image_units.into()),
+        ("max texture max anisotropy".to_owned(),
+         caps.max_texture_max_anisotropy.map(|v| v.to_string()).into()),
+        ("max viewport width".to_owned(), caps.max_viewport_dims.0.into()),
+        ("max viewport height".to_owned(), caps.max_viewport_dims.1.into()),
+        ("max draw buffers".to_owned(), caps.max_draw_buffers.into()),
+        ("max color attachments".to_owned(), caps.max_color_attachments.into()),
+        ("max patch vertices".to_owned(), caps.max_patch_vertices.into()),
+        ("max uniform buffer bindings".to_owned(), caps.max_indexed_uniform_buffer.into()),
+        ("max shader storage buffer bindings".to_owned(),
+         caps.max_indexed_shader_storage_buffer.into()),
+        ("max atomic counter buffer bindings".to_owned(),
+         caps.max_indexed_atomic_counter_buffer.into()),
+        ("max transform feedback buffer bindings".to_owned(),
+         caps.max_indexed_transform_feedback_buffer.into()),
+        ("uniform buffer offset alignment".to_owned(),
+         caps.uniform_buffer_offset_alignment.into()),
+        ("max compute work group count x".to_owned(), caps.max_compute_work_group_count.0.into()),
+        ("max compute work group count y".to_owned(), caps.max_compute_work_group_count.1.into()),
+        ("max compute work group count z".to_owned(), caps.max_compute_work_group_count.2.into()),
+        ("max framebuffer width".to_owned(), caps.max_framebuffer_width.into()),
+        ("max framebuffer height".to_owned(), caps.max_framebuffer_height.into()),
+        ("max framebuffer layers".to_owned(), caps.max_framebuffer_layers.into()),
+        ("max framebuffer samples".to_owned(), caps.max_framebuffer_samples.into()),
+    ];
+
+    let extensions = display.get_extensions().iter()
+        .map(|(name, supported)| (name.to_owned(), supported.into()))
+        .collect();
+
+    Report {
+        sections: vec![
+            ("context", context),
+            ("limits", limits),
+            ("extensions", extensions),
+        ],
+    }
+}